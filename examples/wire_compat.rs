@@ -0,0 +1,76 @@
+//! Compare the connect handshake we send with a python-oracledb capture.
+//!
+//! Record the capture with python-oracledb in thin mode:
+//!
+//! ```text
+//! PYO_DEBUG_PACKETS=1 python -c "import oracledb; \
+//!     oracledb.connect(user='scott', password='tiger', dsn='db:1521/orcl')" > capture.log
+//! ```
+//!
+//! then run:
+//!
+//! ```text
+//! cargo run --example wire_compat --features wire-compat -- capture.log db 1521 orcl scott
+//! ```
+//!
+//! python-oracledb's thin mode also uses fast authentication, so its first
+//! DATA packet bundles the protocol, data types and first auth messages
+//! when the server supports it. Expect the connect string's CID section
+//! and the client host details to differ.
+
+use oracle_thin_rs::protocol::constants::TNS_PACKET_TYPE_CONNECT;
+use oracle_thin_rs::protocol::packet::Capabilities;
+use oracle_thin_rs::protocol::wire_compat::{parse_capture, Scenario};
+use oracle_thin_rs::protocol::{
+    AuthPhaseOneMessage, ConnectMessage, DataTypesMessage, ProtocolMessage,
+};
+use oracle_thin_rs::ConnectParams;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [capture, host, port, service, username] = args.as_slice() else {
+        eprintln!("usage: wire_compat <capture.log> <host> <port> <service> <username>");
+        std::process::exit(2);
+    };
+
+    let capture = parse_capture(&std::fs::read_to_string(capture)?)?;
+    let params = ConnectParams::new(host.as_str(), port.parse()?, service.as_str());
+    let connect_string = params.build_connect_string();
+    let caps = Capabilities::new();
+    let machine = hostname::get()?.to_string_lossy().into_owned();
+    let pid = std::process::id().to_string();
+    let osuser = whoami::username();
+
+    let mut scenario = Scenario::new(params.sdu);
+    scenario.message(
+        TNS_PACKET_TYPE_CONNECT,
+        &ConnectMessage {
+            connect_string: connect_string.as_bytes(),
+            sdu: params.sdu,
+            oob: false,
+        },
+    )?;
+    scenario.set_large_sdu(true);
+    scenario
+        .data_message(&ProtocolMessage::default())?
+        .data_message(&DataTypesMessage {
+            compile_caps: &caps.compile_caps,
+            runtime_caps: &caps.runtime_caps,
+        })?
+        .data_message(&AuthPhaseOneMessage {
+            username,
+            terminal: "unknown",
+            program: "oracle-thin-rs",
+            machine: &machine,
+            pid: &pid,
+            sid: &osuser,
+            privilege: 0,
+        })?;
+
+    let report = scenario.compare(&capture);
+    print!("{}", report);
+    if !report.is_identical() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Derive macros for oracle-thin-rs.
+//!
+//! Use them through the `derive` feature of `oracle-thin-rs`, which
+//! re-exports them next to the traits they implement.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, LitStr};
+
+/// Derive `FromRow`, mapping query columns to struct fields.
+///
+/// Named fields are matched to columns by name, case-insensitively, so
+/// `user_id` reads column `USER_ID`; `#[oracle(rename = "...")]` picks another
+/// column. Tuple struct fields are read by position. Each field is converted
+/// with `FromSql`; use `Option<T>` for nullable columns.
+#[proc_macro_derive(FromRow, attributes(oracle))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_row(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_from_row(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "FromRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let body = match fields {
+        Fields::Named(fields) => {
+            let values = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.as_ref().expect("named field");
+                    let column = column_name(field)?
+                        .unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+                    Ok(quote! { #ident: row.get_as_by_name(#column)? })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self { #(#values),* } }
+        }
+        Fields::Unnamed(fields) => {
+            let values = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(index, field)| {
+                    if let Some(column) = column_name(field)? {
+                        return Err(syn::Error::new_spanned(
+                            column,
+                            "tuple struct fields are read by position and cannot be renamed",
+                        ));
+                    }
+                    Ok(quote! { row.get_as(#index)? })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { Self(#(#values),*) }
+        }
+        Fields::Unit => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "FromRow cannot be derived for unit structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::oracle_thin_rs::FromRow for #name #ty_generics #where_clause {
+            fn from_row(row: &::oracle_thin_rs::Row) -> ::oracle_thin_rs::Result<Self> {
+                ::core::result::Result::Ok(#body)
+            }
+        }
+    })
+}
+
+/// The column named by `#[oracle(rename = "...")]`, if any.
+fn column_name(field: &Field) -> syn::Result<Option<LitStr>> {
+    let mut column = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("oracle")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                column = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown oracle attribute, expected `rename`"))
+            }
+        })?;
+    }
+    Ok(column)
+}
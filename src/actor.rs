@@ -0,0 +1,286 @@
+//! Connection owned by a background task.
+//!
+//! `ConnectionHandle::spawn` moves a `Connection` into a dedicated tokio task.
+//! Handles send it commands over a channel and await the replies, so a
+//! caller that stops waiting (a dropped future, a `select!` branch not
+//! taken) never interrupts a round trip: the task finishes the call and
+//! discards the reply. An idle task can also ping the server to keep the
+//! session alive.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::connection::{Connection, ExecuteResult, QueryResult};
+use crate::error::{Error, Result};
+use crate::options::QueryOptions;
+use crate::protocol::connect::ConnectParams;
+use crate::protocol::types::{ColumnMetadata, FromRow, OracleValue, Row};
+
+/// Commands queued before callers wait for room in the channel.
+const COMMAND_QUEUE_SIZE: usize = 64;
+
+/// Work run by the task on its connection.
+type Job = Box<dyn for<'c> FnOnce(&'c mut Connection) -> BoxFuture<'c, ()> + Send>;
+
+/// A command for the connection task.
+enum Command {
+    /// Run a job.
+    Run(Job),
+    /// Close the connection and stop.
+    Close(oneshot::Sender<Result<()>>),
+}
+
+/// Cheap, cloneable handle to a connection owned by a background task.
+///
+/// Commands from every handle run one at a time, in the order they were
+/// sent. A command whose caller stopped waiting before it started is
+/// skipped; one already running completes, keeping the connection in step
+/// with the server. The task ends, closing the connection, when `close` is
+/// called or every handle is dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use oracle_thin_rs::{Connection, ConnectionHandle, Cursor};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let conn = Connection::connect("localhost:1521/FREEPDB1", "user", "password").await?;
+///     let handle = ConnectionHandle::spawn(conn);
+///
+///     let result = handle.query("SELECT * FROM users").await?;
+///     println!("{} rows", result.len());
+///
+///     // Anything else runs as a closure on the task
+///     let count = handle
+///         .run(|conn| {
+///             Box::pin(async move {
+///                 let mut cursor = conn.open_cursor("SELECT * FROM orders").await?;
+///                 Ok(cursor.fetch_all().await?.len())
+///             })
+///         })
+///         .await?;
+///     println!("{} orders", count);
+///
+///     handle.close().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConnectionHandle {
+    commands: mpsc::Sender<Command>,
+}
+
+impl ConnectionHandle {
+    /// Move a connection into a background task and return a handle to it.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn(conn: Connection) -> Self {
+        Self::spawn_task(conn, None)
+    }
+
+    /// Like `spawn`, with the task pinging the server whenever the
+    /// connection has been idle for `idle` (see `Connection::ping_if_idle`),
+    /// so firewalls and the server's idle timeouts do not drop it.
+    ///
+    /// A failed ping is not reported and stops the pings; the next command
+    /// gets the error.
+    pub fn spawn_with_keepalive(conn: Connection, idle: Duration) -> Self {
+        Self::spawn_task(conn, Some(idle))
+    }
+
+    fn spawn_task(conn: Connection, keepalive: Option<Duration>) -> Self {
+        let (commands, receiver) = mpsc::channel(COMMAND_QUEUE_SIZE);
+        tokio::spawn(run_connection(conn, receiver, keepalive));
+        Self { commands }
+    }
+
+    /// Connect and spawn the connection's task (see `Connection::connect`).
+    pub async fn connect(conn_str: &str, username: &str, password: &str) -> Result<Self> {
+        Ok(Self::spawn(
+            Connection::connect(conn_str, username, password).await?,
+        ))
+    }
+
+    /// Connect with explicit parameters and spawn the connection's task
+    /// (see `Connection::connect_with_params`).
+    pub async fn connect_with_params(
+        params: &ConnectParams,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        Ok(Self::spawn(
+            Connection::connect_with_params(params, username, password).await?,
+        ))
+    }
+
+    /// Run `f` on the connection and return its result.
+    ///
+    /// The closure has the connection to itself until its future completes,
+    /// so cursors and transactions spanning several calls belong here.
+    ///
+    /// # Errors
+    /// Returns `Error::ConnectionClosed` if the task has stopped.
+    pub async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: for<'c> FnOnce(&'c mut Connection) -> BoxFuture<'c, Result<T>> + Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let job: Job = Box::new(move |conn| {
+            Box::pin(async move {
+                // The caller stopped waiting before the job started
+                if reply.is_closed() {
+                    return;
+                }
+                let _ = reply.send(f(conn).await);
+            })
+        });
+        self.commands
+            .send(Command::Run(job))
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+        response.await.map_err(|_| Error::ConnectionClosed)?
+    }
+
+    /// Close the connection and stop its task.
+    ///
+    /// Commands sent earlier run first; later ones fail with
+    /// `Error::ConnectionClosed`.
+    pub async fn close(&self) -> Result<()> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(Command::Close(reply))
+            .await
+            .map_err(|_| Error::ConnectionClosed)?;
+        response.await.map_err(|_| Error::ConnectionClosed)?
+    }
+
+    /// Check whether the connection's task has stopped.
+    pub fn is_closed(&self) -> bool {
+        self.commands.is_closed()
+    }
+
+    /// Execute a SELECT query (see `Connection::query`).
+    pub async fn query(&self, sql: &str) -> Result<QueryResult> {
+        let sql = sql.to_string();
+        self.run(move |conn| Box::pin(async move { conn.query(&sql).await }))
+            .await
+    }
+
+    /// Execute a SELECT query with explicit fetch options (see
+    /// `Connection::query_with_options`).
+    pub async fn query_with_options(
+        &self,
+        sql: &str,
+        options: &QueryOptions,
+    ) -> Result<QueryResult> {
+        let (sql, options) = (sql.to_string(), options.clone());
+        self.run(move |conn| Box::pin(async move { conn.query_with_options(&sql, &options).await }))
+            .await
+    }
+
+    /// Execute a query and return its first row (see `Connection::query_one`).
+    pub async fn query_one(&self, sql: &str) -> Result<Option<Row>> {
+        let sql = sql.to_string();
+        self.run(move |conn| Box::pin(async move { conn.query_one(&sql).await }))
+            .await
+    }
+
+    /// Execute a query and convert every row to `T` (see
+    /// `Connection::query_as`).
+    pub async fn query_as<T: FromRow + Send + 'static>(&self, sql: &str) -> Result<Vec<T>> {
+        let sql = sql.to_string();
+        self.run(move |conn| Box::pin(async move { conn.query_as(&sql).await }))
+            .await
+    }
+
+    /// Execute a statement with positional binds (see `Connection::execute`).
+    pub async fn execute(&self, sql: &str, params: &[OracleValue]) -> Result<ExecuteResult> {
+        let (sql, params) = (sql.to_string(), params.to_vec());
+        self.run(move |conn| Box::pin(async move { conn.execute(&sql, &params).await }))
+            .await
+    }
+
+    /// Execute a statement once per row of binds (see
+    /// `Connection::execute_many`).
+    pub async fn execute_many(&self, sql: &str, rows: &[Vec<OracleValue>]) -> Result<u64> {
+        let (sql, rows) = (sql.to_string(), rows.to_vec());
+        self.run(move |conn| Box::pin(async move { conn.execute_many(&sql, &rows).await }))
+            .await
+    }
+
+    /// Describe a query's columns without executing it (see
+    /// `Connection::describe`).
+    pub async fn describe(&self, sql: &str) -> Result<Vec<ColumnMetadata>> {
+        let sql = sql.to_string();
+        self.run(move |conn| Box::pin(async move { conn.describe(&sql).await }))
+            .await
+    }
+
+    /// Ping the server (see `Connection::ping`).
+    pub async fn ping(&self) -> Result<()> {
+        self.run(|conn| Box::pin(conn.ping())).await
+    }
+}
+
+/// Body of a connection's task: run commands until told to close or every
+/// handle is gone, pinging the server when idle if asked to.
+async fn run_connection(
+    mut conn: Connection,
+    mut commands: mpsc::Receiver<Command>,
+    mut keepalive: Option<Duration>,
+) {
+    loop {
+        let command = match keepalive {
+            Some(idle) => {
+                let wait = idle.saturating_sub(conn.idle_time());
+                tokio::select! {
+                    command = commands.recv() => command,
+                    _ = tokio::time::sleep(wait) => {
+                        // A lost session stays lost; stop probing it
+                        if conn.ping_if_idle(idle).await.is_err() {
+                            keepalive = None;
+                        }
+                        continue;
+                    }
+                }
+            }
+            None => commands.recv().await,
+        };
+        match command {
+            Some(Command::Run(job)) => job(&mut conn).await,
+            Some(Command::Close(reply)) => {
+                let _ = reply.send(conn.close().await);
+                return;
+            }
+            None => {
+                let _ = conn.close().await;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+        assert_send_sync::<ConnectionHandle>();
+    }
+
+    #[tokio::test]
+    async fn test_stopped_task_reports_closed() {
+        let (commands, receiver) = mpsc::channel(1);
+        drop(receiver);
+        let handle = ConnectionHandle { commands };
+        assert!(handle.is_closed());
+        assert!(matches!(handle.ping().await, Err(Error::ConnectionClosed)));
+        assert!(matches!(handle.close().await, Err(Error::ConnectionClosed)));
+    }
+}
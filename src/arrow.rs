@@ -0,0 +1,371 @@
+//! Apache Arrow record batches built from fetched rows (feature `arrow`).
+//!
+//! Column values are decoded from the bytes received for them straight into
+//! Arrow array builders, without going through `OracleValue`. Types map as
+//! in python-oracledb's data frames:
+//!
+//! | Oracle Type | Arrow Type |
+//! |-------------|------------|
+//! | VARCHAR2, CHAR, LONG, NVARCHAR2, NCHAR, ROWID, UROWID | `Utf8` |
+//! | NUMBER with scale 0 and precision 1-18 | `Int64` |
+//! | other NUMBER, BINARY_INTEGER | `Float64` |
+//! | BINARY_FLOAT | `Float32` |
+//! | BINARY_DOUBLE | `Float64` |
+//! | DATE | `Timestamp(Second)` |
+//! | INTERVAL DAY TO SECOND | `Duration(Microsecond)` |
+//! | BOOLEAN | `Boolean` |
+//! | RAW, LONG RAW | `Binary` |
+//!
+//! Other types (LOBs, JSON, VECTOR, intervals year to month) are rejected
+//! with `Error::UnsupportedType`.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, BinaryBuilder, BooleanBuilder, DurationMicrosecondBuilder, Float32Builder,
+    Float64Builder, Int64Builder, StringBuilder, TimestampSecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+
+use crate::error::{Error, Result};
+use crate::protocol::constants::*;
+use crate::protocol::decode::{
+    decode_binary_double, decode_binary_float, decode_interval_ds, decode_nchar,
+    decode_oracle_boolean, decode_oracle_date, decode_oracle_number,
+};
+use crate::protocol::types::{Cell, ColumnMetadata, OracleValue, Row};
+
+/// Arrow type a column is fetched as.
+fn arrow_type(column: &ColumnMetadata) -> Result<DataType> {
+    Ok(match column.oracle_type as u16 {
+        ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR | ORA_TYPE_NUM_LONG | ORA_TYPE_NUM_ROWID
+        | ORA_TYPE_NUM_UROWID => DataType::Utf8,
+        ORA_TYPE_NUM_NUMBER if column.is_integer() => DataType::Int64,
+        ORA_TYPE_NUM_NUMBER | ORA_TYPE_NUM_BINARY_INTEGER | ORA_TYPE_NUM_BINARY_DOUBLE => {
+            DataType::Float64
+        }
+        ORA_TYPE_NUM_BINARY_FLOAT => DataType::Float32,
+        ORA_TYPE_NUM_DATE => DataType::Timestamp(TimeUnit::Second, None),
+        ORA_TYPE_NUM_INTERVAL_DS => DataType::Duration(TimeUnit::Microsecond),
+        ORA_TYPE_NUM_BOOLEAN => DataType::Boolean,
+        ORA_TYPE_NUM_RAW | ORA_TYPE_NUM_LONG_RAW => DataType::Binary,
+        _ => {
+            return Err(Error::UnsupportedType {
+                type_num: column.oracle_type,
+            })
+        }
+    })
+}
+
+/// Build the Arrow schema of a query's columns.
+///
+/// # Errors
+/// Returns `Error::UnsupportedType` for columns with no Arrow mapping.
+pub fn arrow_schema(columns: &[ColumnMetadata]) -> Result<Schema> {
+    let fields = columns
+        .iter()
+        .map(|column| {
+            Ok(Field::new(
+                &column.name,
+                arrow_type(column)?,
+                column.nullable,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Schema::new(fields))
+}
+
+/// Builder of one column's array.
+enum ColumnBuilder {
+    Utf8 {
+        builder: StringBuilder,
+        nchar: bool,
+    },
+    Int64(Int64Builder),
+    /// NUMBER and BINARY_INTEGER, sent as Oracle numbers.
+    Number(Float64Builder),
+    Float32(Float32Builder),
+    /// BINARY_DOUBLE, sent as an IEEE double.
+    Double(Float64Builder),
+    Timestamp(TimestampSecondBuilder),
+    Duration(DurationMicrosecondBuilder),
+    Boolean(BooleanBuilder),
+    Binary(BinaryBuilder),
+}
+
+impl ColumnBuilder {
+    fn new(column: &ColumnMetadata, capacity: usize) -> Result<Self> {
+        Ok(match (arrow_type(column)?, column.oracle_type as u16) {
+            (DataType::Utf8, _) => Self::Utf8 {
+                builder: StringBuilder::with_capacity(capacity, capacity * 16),
+                nchar: column.is_nchar(),
+            },
+            (DataType::Int64, _) => Self::Int64(Int64Builder::with_capacity(capacity)),
+            (DataType::Float64, ORA_TYPE_NUM_BINARY_DOUBLE) => {
+                Self::Double(Float64Builder::with_capacity(capacity))
+            }
+            (DataType::Float64, _) => Self::Number(Float64Builder::with_capacity(capacity)),
+            (DataType::Float32, _) => Self::Float32(Float32Builder::with_capacity(capacity)),
+            (DataType::Timestamp(..), _) => {
+                Self::Timestamp(TimestampSecondBuilder::with_capacity(capacity))
+            }
+            (DataType::Duration(_), _) => {
+                Self::Duration(DurationMicrosecondBuilder::with_capacity(capacity))
+            }
+            (DataType::Boolean, _) => Self::Boolean(BooleanBuilder::with_capacity(capacity)),
+            _ => Self::Binary(BinaryBuilder::with_capacity(capacity, capacity * 16)),
+        })
+    }
+
+    /// Append a NULL.
+    fn append_null(&mut self) {
+        match self {
+            Self::Utf8 { builder, .. } => builder.append_null(),
+            Self::Int64(builder) => builder.append_null(),
+            Self::Number(builder) | Self::Double(builder) => builder.append_null(),
+            Self::Float32(builder) => builder.append_null(),
+            Self::Timestamp(builder) => builder.append_null(),
+            Self::Duration(builder) => builder.append_null(),
+            Self::Boolean(builder) => builder.append_null(),
+            Self::Binary(builder) => builder.append_null(),
+        }
+    }
+
+    /// Append a value from the bytes received for it.
+    fn append_encoded(&mut self, bytes: &[u8]) -> Result<()> {
+        match self {
+            Self::Utf8 { builder, nchar } => {
+                if *nchar {
+                    builder.append_value(decode_nchar(bytes)?);
+                } else {
+                    builder.append_value(String::from_utf8_lossy(bytes));
+                }
+            }
+            Self::Int64(builder) => builder.append_value(parse_number(bytes)?),
+            Self::Number(builder) => builder.append_value(parse_number(bytes)?),
+            Self::Double(builder) => builder.append_value(decode_binary_double(bytes)?),
+            Self::Float32(builder) => builder.append_value(decode_binary_float(bytes)?),
+            Self::Timestamp(builder) => {
+                builder.append_value(decode_oracle_date(bytes)?.and_utc().timestamp())
+            }
+            Self::Duration(builder) => builder.append_value(duration_micros(
+                decode_interval_ds(bytes)?.num_microseconds(),
+            )?),
+            Self::Boolean(builder) => builder.append_value(decode_oracle_boolean(bytes)?),
+            Self::Binary(builder) => builder.append_value(bytes),
+        }
+        Ok(())
+    }
+
+    /// Append a value decoded while the row was parsed.
+    fn append_value(&mut self, value: &OracleValue) -> Result<()> {
+        match (self, value) {
+            (builder, OracleValue::Null) => builder.append_null(),
+            (Self::Utf8 { builder, .. }, value) => builder.append_value(value.to_string()),
+            (Self::Int64(builder), OracleValue::Number(n)) => builder.append_value(parse(n)?),
+            (Self::Int64(builder), OracleValue::Integer(i)) => builder.append_value(*i),
+            (Self::Number(builder) | Self::Double(builder), OracleValue::Number(n)) => {
+                builder.append_value(parse(n)?)
+            }
+            #[cfg(feature = "rust_decimal")]
+            (Self::Int64(builder), OracleValue::Decimal(d)) => {
+                builder.append_value(parse(&d.to_string())?)
+            }
+            #[cfg(feature = "rust_decimal")]
+            (Self::Number(builder) | Self::Double(builder), OracleValue::Decimal(d)) => {
+                builder.append_value(parse(&d.to_string())?)
+            }
+            (Self::Timestamp(builder), OracleValue::Date(date)) => {
+                builder.append_value(date.and_utc().timestamp())
+            }
+            (Self::Duration(builder), OracleValue::IntervalDS(duration)) => {
+                builder.append_value(duration_micros(duration.num_microseconds())?)
+            }
+            (Self::Boolean(builder), OracleValue::Boolean(b)) => builder.append_value(*b),
+            (Self::Binary(builder), OracleValue::Raw(bytes)) => builder.append_value(bytes),
+            (_, value) => {
+                return Err(Error::protocol(format!(
+                    "Unexpected value for an Arrow column: {:?}",
+                    value
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Utf8 { builder, .. } => Arc::new(builder.finish()),
+            Self::Int64(builder) => Arc::new(builder.finish()),
+            Self::Number(builder) | Self::Double(builder) => Arc::new(builder.finish()),
+            Self::Float32(builder) => Arc::new(builder.finish()),
+            Self::Timestamp(builder) => Arc::new(builder.finish()),
+            Self::Duration(builder) => Arc::new(builder.finish()),
+            Self::Boolean(builder) => Arc::new(builder.finish()),
+            Self::Binary(builder) => Arc::new(builder.finish()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Utf8 { builder, .. } => builder.len(),
+            Self::Int64(builder) => builder.len(),
+            Self::Number(builder) | Self::Double(builder) => builder.len(),
+            Self::Float32(builder) => builder.len(),
+            Self::Timestamp(builder) => builder.len(),
+            Self::Duration(builder) => builder.len(),
+            Self::Boolean(builder) => builder.len(),
+            Self::Binary(builder) => builder.len(),
+        }
+    }
+}
+
+/// Parse an Oracle NUMBER into a Rust number.
+fn parse_number<T: std::str::FromStr>(bytes: &[u8]) -> Result<T> {
+    parse(&decode_oracle_number(bytes)?)
+}
+
+fn parse<T: std::str::FromStr>(number: &str) -> Result<T> {
+    number
+        .parse()
+        .map_err(|_| Error::protocol(format!("NUMBER {} does not fit its Arrow column", number)))
+}
+
+fn duration_micros(micros: Option<i64>) -> Result<i64> {
+    micros.ok_or_else(|| Error::protocol("INTERVAL DAY TO SECOND out of range"))
+}
+
+/// Accumulates rows into an Arrow record batch.
+pub(crate) struct BatchBuilder {
+    schema: SchemaRef,
+    columns: Vec<ColumnBuilder>,
+}
+
+impl BatchBuilder {
+    /// Create a builder for rows of `columns`, sized for `capacity` rows.
+    pub(crate) fn new(columns: &[ColumnMetadata], capacity: usize) -> Result<Self> {
+        Ok(Self {
+            schema: Arc::new(arrow_schema(columns)?),
+            columns: columns
+                .iter()
+                .map(|column| ColumnBuilder::new(column, capacity))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Append a row.
+    pub(crate) fn append(&mut self, row: &Row) -> Result<()> {
+        for (index, builder) in self.columns.iter_mut().enumerate() {
+            match row.cell(index) {
+                Some(Cell::Encoded { bytes, .. }) => builder.append_encoded(bytes)?,
+                Some(Cell::Value(value)) => builder.append_value(value)?,
+                None => builder.append_null(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of rows appended.
+    pub(crate) fn len(&self) -> usize {
+        self.columns.first().map_or(0, ColumnBuilder::len)
+    }
+
+    /// Build the record batch of the rows appended.
+    pub(crate) fn finish(mut self) -> Result<RecordBatch> {
+        let arrays = self.columns.iter_mut().map(ColumnBuilder::finish).collect();
+        RecordBatch::try_new(self.schema, arrays)
+            .map_err(|e| Error::protocol(format!("Invalid Arrow record batch: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::types::ColumnInfo;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, Int64Type, TimestampSecondType};
+    use arrow_array::Array;
+
+    fn column(name: &str, oracle_type: u16, precision: i8, scale: i8) -> ColumnMetadata {
+        let mut column = ColumnMetadata::new(name.to_string(), oracle_type as u8);
+        column.precision = precision;
+        column.scale = scale;
+        column
+    }
+
+    #[test]
+    fn test_arrow_schema() {
+        let schema = arrow_schema(&[
+            column("ID", ORA_TYPE_NUM_NUMBER, 10, 0),
+            column("PRICE", ORA_TYPE_NUM_NUMBER, 10, 2),
+            column("ANY_NUMBER", ORA_TYPE_NUM_NUMBER, 0, -127),
+            column("NAME", ORA_TYPE_NUM_VARCHAR, 0, 0),
+            column("CREATED", ORA_TYPE_NUM_DATE, 0, 0),
+        ])
+        .unwrap();
+        let types: Vec<&DataType> = schema.fields().iter().map(|f| f.data_type()).collect();
+        assert_eq!(
+            types,
+            [
+                &DataType::Int64,
+                &DataType::Float64,
+                &DataType::Float64,
+                &DataType::Utf8,
+                &DataType::Timestamp(TimeUnit::Second, None),
+            ]
+        );
+
+        assert!(matches!(
+            arrow_schema(&[column("DOC", ORA_TYPE_NUM_CLOB, 0, 0)]),
+            Err(Error::UnsupportedType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_batch_builder() {
+        let columns = vec![
+            column("ID", ORA_TYPE_NUM_NUMBER, 10, 0),
+            column("PRICE", ORA_TYPE_NUM_NUMBER, 0, -127),
+            column("NAME", ORA_TYPE_NUM_VARCHAR, 0, 0),
+            column("CREATED", ORA_TYPE_NUM_DATE, 0, 0),
+        ];
+        let info = Arc::new(ColumnInfo::from_metadata(&columns).unwrap());
+        let encoded = |ora_type: u16, bytes: &'static [u8]| {
+            Cell::encoded(ora_type, 0, bytes::Bytes::from_static(bytes))
+        };
+        let rows = [
+            Row::from_cells(
+                vec![
+                    // 42
+                    encoded(ORA_TYPE_NUM_NUMBER, &[0xC1, 0x2B]),
+                    // 1.5
+                    encoded(ORA_TYPE_NUM_NUMBER, &[0xC1, 0x02, 0x33]),
+                    encoded(ORA_TYPE_NUM_VARCHAR, b"abc"),
+                    // 2024-01-02 03:04:05
+                    encoded(ORA_TYPE_NUM_DATE, &[120, 124, 1, 2, 4, 5, 6]),
+                ],
+                info.clone(),
+            ),
+            Row::from_cells(vec![Cell::Value(OracleValue::Null); 4], info.clone()),
+        ];
+
+        let mut batch = BatchBuilder::new(&columns, rows.len()).unwrap();
+        for row in &rows {
+            batch.append(row).unwrap();
+        }
+        assert_eq!(batch.len(), 2);
+        let batch = batch.finish().unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+        let ids = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(ids.value(0), 42);
+        assert!(ids.is_null(1));
+        let prices = batch.column(1).as_primitive::<Float64Type>();
+        assert_eq!(prices.value(0), 1.5);
+        assert_eq!(batch.column(2).as_string::<i32>().value(0), "abc");
+        let created = batch.column(3).as_primitive::<TimestampSecondType>();
+        assert_eq!(created.value(0), 1704164645);
+        assert!(created.is_null(1));
+    }
+}
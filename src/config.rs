@@ -0,0 +1,561 @@
+//! Layered connection configuration.
+//!
+//! `ConnectBuilder` gathers connection settings from three layers, each
+//! overriding the one before:
+//!
+//! 1. an optional config file of `key = value` lines (`#` starts a comment),
+//! 2. environment variables (`ORA_DSN`, `ORA_USER`, `ORA_PASSWORD`,
+//!    `ORA_CONNECT_TIMEOUT`, `ORA_SDU`, `ORA_TCPS`, `ORA_EXPIRE_TIME`,
+//!    `ORA_PROXY`, `ORA_SDTZ` for the session time zone, and `TNS_ADMIN`),
+//! 3. values set in code.
+//!
+//! | Key                 | Value                                          |
+//! |---------------------|------------------------------------------------|
+//! | `dsn`               | Connect string, URL, descriptor or alias       |
+//! | `user`              | Database username                              |
+//! | `password`          | Database password                              |
+//! | `tns_admin`         | Directory holding `tnsnames.ora`               |
+//! | `connect_timeout`   | Seconds (fractions allowed)                    |
+//! | `sdu`               | SDU size in bytes                              |
+//! | `tcps`              | `true` to connect over TLS                     |
+//! | `expire_time`       | Minutes between TCP keepalive probes           |
+//! | `proxy`             | `socks5://` or `http://` proxy URL             |
+//! | `max_response_size` | Largest response accepted, in bytes            |
+//! | `time_zone`         | Session time zone                              |
+//!
+//! Values are checked when the parameters are built, and errors name the
+//! file line or variable the bad value came from.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::connection::Connection;
+use crate::error::{Error, Result};
+use crate::protocol::connect::{ConnectParams, Protocol};
+use crate::protocol::constants::{TNS_SDU_MAX, TNS_SDU_MIN};
+use crate::protocol::proxy::Proxy;
+use crate::protocol::tnsnames::TnsNames;
+use crate::url::DatabaseUrl;
+
+/// Keys accepted by `ConnectBuilder`.
+const KEYS: [&str; 11] = [
+    "dsn",
+    "user",
+    "password",
+    "tns_admin",
+    "connect_timeout",
+    "sdu",
+    "tcps",
+    "expire_time",
+    "proxy",
+    "max_response_size",
+    "time_zone",
+];
+
+/// Where a setting came from, for error messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    File { path: PathBuf, line: usize },
+    Env(String),
+    Code,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File { path, line } => write!(f, "{} line {}", path.display(), line),
+            Self::Env(name) => write!(f, "environment variable {}", name),
+            Self::Code => f.write_str("ConnectBuilder"),
+        }
+    }
+}
+
+/// A setting and where it came from.
+#[derive(Debug, Clone)]
+struct Setting {
+    value: String,
+    source: Source,
+}
+
+/// Builds `ConnectParams` from a config file, the environment and code.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use oracle_thin_rs::ConnectBuilder;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // ORA_DSN=db.example.com/orclpdb ORA_USER=app ORA_PASSWORD=...
+///     let conn = ConnectBuilder::new()
+///         .with_config_file("/etc/myapp/oracle.conf")?
+///         .with_env()
+///         .with_connect_timeout(Duration::from_secs(5))
+///         .connect()
+///         .await?;
+///     conn.close().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConnectBuilder {
+    file: HashMap<&'static str, Setting>,
+    env: HashMap<&'static str, Setting>,
+    code: HashMap<&'static str, Setting>,
+}
+
+impl ConnectBuilder {
+    /// Create a builder with no settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read settings from a config file.
+    ///
+    /// # Errors
+    /// Returns `Error::Io` if the file cannot be read, and
+    /// `Error::InvalidConnectString` for a line that is not `key = value`
+    /// or has an unknown key.
+    pub fn with_config_file(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        self.file = parse_config(&text, path)?;
+        Ok(self)
+    }
+
+    /// Read settings from the process environment.
+    ///
+    /// Only the variables listed in the module documentation are read;
+    /// other `ORA_` variables are left alone.
+    pub fn with_env(self) -> Self {
+        self.with_env_vars(std::env::vars())
+    }
+
+    /// Read settings from environment-style variables.
+    fn with_env_vars(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        for (name, value) in vars {
+            if let Some(key) = KEYS.into_iter().find(|key| env_name(key) == name) {
+                let source = Source::Env(name);
+                self.env.insert(key, Setting { value, source });
+            }
+        }
+        self
+    }
+
+    /// Set a value by key, overriding the config file and environment.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidConnectString` for an unknown key. Values are
+    /// checked by `build`.
+    pub fn set(self, key: &str, value: impl Into<String>) -> Result<Self> {
+        let key = find_key(key).ok_or_else(|| unknown_key(key, &Source::Code))?;
+        Ok(self.set_known(key, value.into()))
+    }
+
+    /// Set the connect string.
+    pub fn with_dsn(self, dsn: impl Into<String>) -> Self {
+        self.set_known("dsn", dsn.into())
+    }
+
+    /// Set the username and password.
+    pub fn with_credentials(self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.set_known("user", user.into())
+            .set_known("password", password.into())
+    }
+
+    /// Set the directory holding `tnsnames.ora`.
+    pub fn with_tns_admin(self, dir: impl AsRef<Path>) -> Self {
+        self.set_known("tns_admin", dir.as_ref().display().to_string())
+    }
+
+    /// Set the connect timeout.
+    pub fn with_connect_timeout(self, timeout: Duration) -> Self {
+        self.set_known("connect_timeout", timeout.as_secs_f64().to_string())
+    }
+
+    /// Set the SDU size requested.
+    pub fn with_sdu(self, sdu: u32) -> Self {
+        self.set_known("sdu", sdu.to_string())
+    }
+
+    fn set_known(mut self, key: &'static str, value: String) -> Self {
+        let source = Source::Code;
+        self.code.insert(key, Setting { value, source });
+        self
+    }
+
+    /// Get the effective setting for a key.
+    fn get(&self, key: &str) -> Option<&Setting> {
+        self.code
+            .get(key)
+            .or_else(|| self.env.get(key))
+            .or_else(|| self.file.get(key))
+    }
+
+    /// Build the connection parameters.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidConnectString` when no connect string is set,
+    /// a net service name cannot be resolved, or a value is invalid; the
+    /// message names where the value came from.
+    pub fn build(&self) -> Result<ConnectParams> {
+        self.resolve().map(|(params, _)| params)
+    }
+
+    /// Build the connection parameters and connect with the configured
+    /// username and password (or those of an `oracle://` connect string).
+    pub async fn connect(&self) -> Result<Connection> {
+        let (params, (user, password)) = self.resolve()?;
+        Connection::connect_with_params(&params, &user, &password).await
+    }
+
+    /// Build the parameters along with the username and password.
+    fn resolve(&self) -> Result<(ConnectParams, (String, String))> {
+        let dsn = self.get("dsn").ok_or_else(|| Error::InvalidConnectString {
+            message: "No connect string: set `dsn` in the config file, ORA_DSN, \
+                      or call ConnectBuilder::with_dsn"
+                .to_string(),
+        })?;
+        let in_source = |source: &Source, e: Error| match e {
+            Error::InvalidConnectString { message } => Error::InvalidConnectString {
+                message: format!("{} (dsn from {})", message, source),
+            },
+            e => e,
+        };
+
+        let value = dsn.value.trim();
+        let (mut params, url_credentials) = if value
+            .get(..9)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("oracle://"))
+        {
+            let url = DatabaseUrl::parse(value).map_err(|e| in_source(&dsn.source, e))?;
+            (url.params, Some((url.username, url.password)))
+        } else if value.starts_with('(') {
+            let params =
+                ConnectParams::from_descriptor(value).map_err(|e| in_source(&dsn.source, e))?;
+            (params, None)
+        } else if value.contains('/') {
+            let params = ConnectParams::parse(value).map_err(|e| in_source(&dsn.source, e))?;
+            (params, None)
+        } else {
+            (self.lookup_alias(value)?, None)
+        };
+
+        for key in [
+            "connect_timeout",
+            "sdu",
+            "tcps",
+            "expire_time",
+            "max_response_size",
+            "time_zone",
+        ] {
+            if let Some(setting) = self.get(key) {
+                apply_option(&mut params, key, &setting.value).map_err(|e| match e {
+                    Error::InvalidConnectString { message } => Error::InvalidConnectString {
+                        message: format!("{} (from {})", message, setting.source),
+                    },
+                    e => e,
+                })?;
+            }
+        }
+
+        let user = self.get("user").map(|s| s.value.clone());
+        let password = self.get("password").map(|s| s.value.clone());
+        let credentials = match (user, password, url_credentials) {
+            (Some(user), Some(password), _) => (user, password),
+            (Some(user), None, _) => (user, String::new()),
+            (None, Some(password), Some((user, _))) => (user, password),
+            (None, None, Some(credentials)) => credentials,
+            (None, Some(_), None) => {
+                return Err(Error::InvalidConnectString {
+                    message: "A password is set but no user".to_string(),
+                })
+            }
+            (None, None, None) => (String::new(), String::new()),
+        };
+        Ok((params, credentials))
+    }
+
+    /// Resolve a net service name from `tnsnames.ora` in `tns_admin`.
+    fn lookup_alias(&self, alias: &str) -> Result<ConnectParams> {
+        let dir = self
+            .get("tns_admin")
+            .ok_or_else(|| Error::InvalidConnectString {
+                message: format!(
+                    "{} is not host:port/service_name; to use it as a net service name, \
+                     set TNS_ADMIN to the directory holding tnsnames.ora",
+                    alias
+                ),
+            })?;
+        let path = Path::new(&dir.value).join("tnsnames.ora");
+        let text = std::fs::read_to_string(&path).map_err(|e| Error::InvalidConnectString {
+            message: format!(
+                "Cannot read {} (TNS_ADMIN from {}): {}",
+                path.display(),
+                dir.source,
+                e
+            ),
+        })?;
+        let names = TnsNames::parse(&text)?;
+        let descriptor = names
+            .get(alias)
+            .ok_or_else(|| Error::InvalidConnectString {
+                message: format!("net service name {} not found in {}", alias, path.display()),
+            })?;
+        ConnectParams::from_descriptor(descriptor)
+    }
+}
+
+/// Apply a connection option shared by `ConnectBuilder` and database URLs.
+///
+/// Returns false for keys that are not connection options.
+pub(crate) fn apply_option(params: &mut ConnectParams, key: &str, value: &str) -> Result<bool> {
+    let bad_value = || Error::InvalidConnectString {
+        message: format!("Invalid {}: {}", key, value),
+    };
+    match key {
+        "connect_timeout" => {
+            let seconds = value.parse::<f64>().map_err(|_| bad_value())?;
+            params.connect_timeout =
+                Duration::try_from_secs_f64(seconds).map_err(|_| bad_value())?;
+        }
+        "sdu" => {
+            let sdu = value.parse::<u32>().map_err(|_| bad_value())?;
+            params.sdu = sdu.clamp(TNS_SDU_MIN, TNS_SDU_MAX);
+        }
+        "tcps" => {
+            params.protocol = if parse_bool(value).ok_or_else(bad_value)? {
+                Protocol::Tcps
+            } else {
+                Protocol::Tcp
+            };
+        }
+        "expire_time" => {
+            let minutes = value.parse::<u64>().map_err(|_| bad_value())?;
+            params.expire_time = (minutes > 0).then(|| Duration::from_secs(minutes * 60));
+        }
+        "proxy" => params.proxy = Some(Proxy::parse(value)?),
+        "max_response_size" => {
+            params.max_response_size = value.parse().map_err(|_| bad_value())?;
+        }
+        "time_zone" => {
+            if value.is_empty() {
+                return Err(bad_value());
+            }
+            params.session_settings.time_zone = Some(value.to_string());
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    if ["true", "yes", "on", "1"]
+        .iter()
+        .any(|s| value.eq_ignore_ascii_case(s))
+    {
+        Some(true)
+    } else if ["false", "no", "off", "0"]
+        .iter()
+        .any(|s| value.eq_ignore_ascii_case(s))
+    {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Environment variable holding a key.
+fn env_name(key: &str) -> String {
+    match key {
+        "tns_admin" => "TNS_ADMIN".to_string(),
+        "time_zone" => "ORA_SDTZ".to_string(),
+        _ => format!("ORA_{}", key.to_ascii_uppercase()),
+    }
+}
+
+fn find_key(key: &str) -> Option<&'static str> {
+    KEYS.into_iter()
+        .find(|k| k.eq_ignore_ascii_case(key.trim()))
+}
+
+fn unknown_key(key: &str, source: &Source) -> Error {
+    Error::InvalidConnectString {
+        message: format!(
+            "Unknown setting {} in {}; expected one of {}",
+            key.trim(),
+            source,
+            KEYS.join(", ")
+        ),
+    }
+}
+
+/// Parse `key = value` lines.
+fn parse_config(text: &str, path: &Path) -> Result<HashMap<&'static str, Setting>> {
+    let mut settings = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let source = Source::File {
+            path: path.to_path_buf(),
+            line: index + 1,
+        };
+        let line = line.split_once('#').map_or(line, |(code, _)| code).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| Error::InvalidConnectString {
+                message: format!("Expected key = value in {}", source),
+            })?;
+        let key = find_key(key).ok_or_else(|| unknown_key(key, &source))?;
+        let value = value.trim().to_string();
+        settings.insert(key, Setting { value, source });
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn message(e: Error) -> String {
+        match e {
+            Error::InvalidConnectString { message } => message,
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let file = parse_config(
+            "# defaults\n\
+             dsn = filehost:1521/filesvc\n\
+             SDU = 16384   # bigger packets\n\
+             connect_timeout = 30\n\
+             \n\
+             user = file_user\n",
+            Path::new("app.conf"),
+        )
+        .unwrap();
+        let mut builder = ConnectBuilder::new().with_env_vars(env(&[
+            ("ORA_DSN", "envhost/envsvc"),
+            ("ORA_CONNECT_TIMEOUT", "10"),
+            ("ORA_SDTZ", "UTC"),
+            ("ORA_NLS10", "/ignored"),
+            ("PATH", "/bin"),
+        ]));
+        builder.file = file;
+        let builder = builder.with_connect_timeout(Duration::from_millis(1500));
+
+        let (params, (user, password)) = builder.resolve().unwrap();
+        assert_eq!(params.host, "envhost");
+        assert_eq!(params.service_name, "envsvc");
+        assert_eq!(params.sdu, 16384);
+        assert_eq!(params.connect_timeout, Duration::from_millis(1500));
+        assert_eq!(params.session_settings.time_zone.as_deref(), Some("UTC"));
+        assert_eq!((user.as_str(), password.as_str()), ("file_user", ""));
+    }
+
+    #[test]
+    fn test_dsn_forms() {
+        let params = ConnectBuilder::new()
+            .with_dsn("oracle://scott:tiger@db:1522/orcl?tcps=true")
+            .with_sdu(32768)
+            .resolve()
+            .unwrap();
+        assert_eq!((params.0.host.as_str(), params.0.port), ("db", 1522));
+        assert_eq!(params.0.protocol, Protocol::Tcps);
+        assert_eq!(params.0.sdu, 32768);
+        assert_eq!(params.1, ("scott".to_string(), "tiger".to_string()));
+
+        let params = ConnectBuilder::new()
+            .with_dsn(
+                "(DESCRIPTION=(ADDRESS=(HOST=db)(PORT=1523))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            )
+            .build()
+            .unwrap();
+        assert_eq!(params.port, 1523);
+
+        let dir =
+            std::env::temp_dir().join(format!("oracle-thin-rs-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("tnsnames.ora"),
+            "sales = (DESCRIPTION=(ADDRESS=(HOST=saleshost)(PORT=1521))\
+             (CONNECT_DATA=(SERVICE_NAME=sales)))",
+        )
+        .unwrap();
+        let builder = ConnectBuilder::new()
+            .with_env_vars(env(&[("TNS_ADMIN", dir.to_str().unwrap())]))
+            .with_dsn("SALES");
+        assert_eq!(builder.build().unwrap().host, "saleshost");
+        let missing = message(builder.with_dsn("hr").build().unwrap_err());
+        assert!(
+            missing.contains("net service name hr not found"),
+            "{}",
+            missing
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors_name_the_source() {
+        let e = ConnectBuilder::new().build().unwrap_err();
+        assert!(message(e).contains("ORA_DSN"));
+
+        let e = ConnectBuilder::new()
+            .with_dsn("localhost/orcl")
+            .with_env_vars(env(&[("ORA_SDU", "big")]))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            message(e),
+            "Invalid sdu: big (from environment variable ORA_SDU)"
+        );
+
+        let e = ConnectBuilder::new().with_dsn("orcl").build().unwrap_err();
+        assert!(message(e).contains("TNS_ADMIN"));
+
+        let e =
+            parse_config("dsn = a/b\nstatement_cache = 20\n", Path::new("app.conf")).unwrap_err();
+        assert!(message(e).starts_with("Unknown setting statement_cache in app.conf line 2"));
+
+        let e = parse_config("just a line\n", Path::new("app.conf")).unwrap_err();
+        assert_eq!(message(e), "Expected key = value in app.conf line 1");
+
+        assert!(ConnectBuilder::new().set("retry_count", "3").is_err());
+        let e = ConnectBuilder::new()
+            .with_dsn("localhost/orcl")
+            .set("password", "secret")
+            .unwrap()
+            .build()
+            .unwrap_err();
+        assert!(!message(e).contains("secret"));
+    }
+
+    #[test]
+    fn test_apply_option() {
+        let mut params = ConnectParams::new("localhost", 1521, "orcl");
+        assert!(apply_option(&mut params, "tcps", "yes").unwrap());
+        assert!(apply_option(&mut params, "expire_time", "0").unwrap());
+        assert!(apply_option(&mut params, "max_response_size", "1048576").unwrap());
+        assert!(apply_option(&mut params, "proxy", "http://proxy:3128").unwrap());
+        assert!(!apply_option(&mut params, "pool_max", "4").unwrap());
+        assert_eq!(params.protocol, Protocol::Tcps);
+        assert_eq!(params.expire_time, None);
+        assert_eq!(params.max_response_size, 1 << 20);
+        assert_eq!(params.proxy, Some(Proxy::http("proxy", 3128)));
+        assert!(apply_option(&mut params, "connect_timeout", "-1").is_err());
+        assert!(apply_option(&mut params, "proxy", "proxy:3128").is_err());
+        assert!(apply_option(&mut params, "time_zone", "").is_err());
+    }
+}
@@ -1,18 +1,62 @@
 //! High-level Connection API for Oracle thin client.
 
 use crate::cursor::{Cursor, RowCursor};
-use crate::error::{Error, Result};
-use crate::protocol::auth::{authenticate, phase_two, AuthCredentials, SessionData};
+use crate::error::{Error, ErrorContext, Phase, Result};
+use crate::intercept::{self, SqlInterceptor};
+use crate::options::QueryOptions;
+#[cfg(feature = "otel")]
+use crate::otel;
+use crate::pipeline::{Pipeline, PipelineOp, PipelineResult};
+use crate::protocol::auth::{
+    authenticate, phase_two, token_authenticate, AuthCredentials, AuthMethod, SessionData,
+};
+use crate::protocol::bind::{BindInfo, BindParams};
 use crate::protocol::buffer::ReadBuffer;
-use crate::protocol::connect::{connect, exchange_data_types, fast_auth, ConnectParams};
+use crate::protocol::clock::{self, TokioClock};
+use crate::protocol::connect::{
+    can_fail_over, connect, dial, enable_keepalive, exchange_data_types, fast_auth, open_socket,
+    start_transport, ConnectParams, Handshake, Protocol, MAX_REDIRECTS,
+};
 use crate::protocol::constants::*;
+use crate::protocol::control::{Control, Notification};
+use crate::protocol::features::ServerFeatures;
 use crate::protocol::message::DataMessage;
 use crate::protocol::message::Message;
-use crate::protocol::messages::{ExecuteMessage, MarkerMessage, TNS_MARKER_TYPE_RESET};
+use crate::protocol::message::Piggybacked;
+use crate::protocol::messages::{
+    BeginPipelineMessage, CloseCursorsMessage, EndPipelineMessage, EndToEndMessage, ExecuteMessage,
+    FreeTempLobsMessage, LobOpMessage, MarkerMessage, PingMessage, ReexecuteMessage,
+    TNS_MARKER_TYPE_RESET,
+};
 use crate::protocol::packet::{Capabilities, Packet, PacketStream};
-use crate::protocol::response::parse_execute_response;
-use crate::protocol::types::{ColumnMetadata, Row};
-use tokio::net::TcpStream;
+use crate::protocol::response::{
+    parse_defined_execute_response, parse_execute_response, parse_fetch_response,
+    parse_lob_op_response, parse_reexecute_response, parse_returning_execute_response, BatchError,
+    EndOfCall, ErrorInfo, ExecuteResponse, ImplicitResult, LobOpResponse, ReplayContext, Warning,
+};
+use crate::protocol::session::quote_identifier;
+use crate::protocol::statement::{Statement, StatementType};
+use crate::protocol::statement_cache::{CachedStatement, StatementCache};
+use crate::protocol::types::{ColumnMetadata, FromRow, Lob, OracleType, OracleValue, Row, RowId};
+use crate::protocol::version;
+use crate::querylog::{self, QueryLogger};
+use crate::stats::ConnectionStats;
+use bytes::BytesMut;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Bytes reserved for the packet header and LOB operation fields when
+/// sizing LOB data pieces to fit in one SDU.
+const LOB_OP_OVERHEAD: usize = 128;
+
+/// Most LOB data requested by one read. The server sends the data in length
+/// chunks across as many packets as it needs and the response is
+/// reassembled before parsing, so reads are not limited to one packet.
+const LOB_READ_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Longest sessionless transaction id accepted by the server.
+const MAX_TRANSACTION_ID_SIZE: usize = 64;
 
 /// Result of a query execution.
 #[derive(Debug)]
@@ -25,6 +69,15 @@ pub struct QueryResult {
     pub row_count: u64,
     /// Whether more rows are available (for pagination).
     pub more_rows: bool,
+    /// ROWID of the last row affected by a DML statement.
+    pub last_rowid: Option<RowId>,
+    /// Kind of statement executed.
+    pub statement_type: StatementType,
+    /// Result sets returned by PL/SQL with `DBMS_SQL.RETURN_RESULT`, in the
+    /// order they were returned, with all of their rows fetched.
+    pub implicit_results: Vec<QueryResult>,
+    /// Warnings raised by the statement.
+    pub warnings: Vec<Warning>,
 }
 
 impl QueryResult {
@@ -67,7 +120,44 @@ impl<'a> IntoIterator for &'a QueryResult {
     }
 }
 
+/// Result of executing a statement with `Connection::execute`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecuteResult {
+    /// Kind of statement executed.
+    pub statement_type: StatementType,
+    /// Number of rows affected by DML (0 for DDL and PL/SQL).
+    pub row_count: u64,
+    /// ROWID of the last row affected by a DML statement.
+    pub last_rowid: Option<RowId>,
+    /// Warnings raised by the statement, such as ORA-24344 for a PL/SQL
+    /// unit created with compilation errors.
+    pub warnings: Vec<Warning>,
+}
+
+/// Result of an array execution in batch error mode.
+#[derive(Debug, Default)]
+pub struct BatchResult {
+    /// Number of rows processed successfully.
+    pub row_count: u64,
+    /// Errors for the rows that failed, in row order.
+    pub errors: Vec<BatchError>,
+}
+
 /// An Oracle database connection.
+///
+/// # Cancellation safety
+///
+/// Every async method may be cancelled by dropping its future (with
+/// `tokio::time::timeout`, `select!`, or a dropped task). The connection
+/// remembers how far the call got and resynchronizes before the next one:
+/// the rest of a partly written request is written, a call waiting for its
+/// response is interrupted with a BREAK, and a partly read response is
+/// drained (Oracle Database 23ai). See [`Connection::cancel`] for the cases
+/// that leave the connection unusable; calls then fail with
+/// `Error::ConnectionClosed`.
+///
+/// A cursor whose fetch was cancelled fails its later fetches, since the
+/// rows of the cancelled fetch are lost.
 pub struct Connection {
     /// Packet stream for communication.
     stream: PacketStream,
@@ -77,6 +167,91 @@ pub struct Connection {
     session: SessionData,
     /// Whether auto-commit is enabled.
     autocommit: bool,
+    /// Round trips, statement counts and time spent in calls since connect
+    /// (byte counts are kept by the stream).
+    stats: ConnectionStats,
+    /// Server cursors released by the client, closed on the next round trip.
+    cursors_to_close: Vec<u32>,
+    /// Buffer responses spanning several packets are assembled in; its
+    /// allocation is reused once the responses split from it are dropped.
+    response_buf: BytesMut,
+    /// Statements whose cursors are kept open to be re-executed.
+    statement_cache: StatementCache,
+    /// Set when a round trip failed because the session was lost, or left
+    /// the stream out of step with the server.
+    broken: bool,
+    /// Set when a response could not be read in full, so the rest of it
+    /// would be taken for the reply to the next call.
+    desynchronized: bool,
+    /// Temporary LOB locators released by the client, freed on the next round trip.
+    temp_lobs_to_free: Vec<Vec<u8>>,
+    /// Longest a round trip may take (zero for no limit).
+    call_timeout: Duration,
+    /// Progress of the current round trip, left behind if it is abandoned.
+    call_state: CallState,
+    /// Session attributes changed by the client, sent on the next round trip.
+    end_to_end: EndToEndMessage,
+    /// When the last response was received.
+    last_activity: Instant,
+    /// Inband notifications not yet taken by the application.
+    notifications: Vec<Notification>,
+    /// Hook run on each statement before it is sent.
+    interceptor: Option<Arc<dyn SqlInterceptor>>,
+    /// Receiver of a log entry for each statement run.
+    query_logger: Option<Arc<dyn QueryLogger>>,
+    /// Server and user the connection's OpenTelemetry spans name.
+    #[cfg(feature = "otel")]
+    peer: otel::Peer,
+    /// Whether the last call left a transaction open.
+    transaction_in_progress: bool,
+    /// Latest logical transaction ID sent by the server.
+    ltxid: Option<Vec<u8>>,
+    /// Latest Application Continuity replay context sent by the server.
+    replay_context: Option<ReplayContext>,
+}
+
+/// Health of a connection, as classified by [`Connection::health`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionHealth {
+    /// No failure was seen: the connection can be used.
+    Healthy,
+    /// A call was abandoned before it completed. The next call (or
+    /// `cancel`) finishes or interrupts it first, so the connection can
+    /// still be used.
+    Interrupted,
+    /// The session was lost: the network failed, the server closed the
+    /// connection or shut the session down, or an abandoned call could not
+    /// be interrupted.
+    SessionLost,
+    /// A response could not be read in full, and the rest of it would be
+    /// taken for the reply to the next call.
+    Desynchronized,
+}
+
+impl ConnectionHealth {
+    /// Check whether the connection can still be used.
+    pub fn is_usable(&self) -> bool {
+        matches!(self, Self::Healthy | Self::Interrupted)
+    }
+}
+
+/// Progress of a round trip, used to recover when one is abandoned (its
+/// future dropped, or cut short by the call timeout).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CallState {
+    /// No round trip in progress.
+    Idle,
+    /// The request is being written; the rest of it stays queued, so it can
+    /// be finished and then interrupted.
+    Sending,
+    /// The request was sent and none of the response has been read, so the
+    /// call can be interrupted with a BREAK marker.
+    Waiting,
+    /// Part of a response has been read, with `remaining` more responses to
+    /// follow it (pipelines). Servers that flag the end of each response let
+    /// the rest be drained; on others it cannot be told apart from the next
+    /// response.
+    Receiving { remaining: usize },
 }
 
 impl Connection {
@@ -85,7 +260,8 @@ impl Connection {
     /// # Arguments
     ///
     /// * `conn_str` - Connection string in format "host:port/service_name"
-    /// * `username` - Database username
+    /// * `username` - Database username, or `user[proxy_user]` to
+    ///   authenticate as `user` and open the session as `proxy_user`
     /// * `password` - Database password
     ///
     /// # Example
@@ -111,111 +287,293 @@ impl Connection {
     }
 
     /// Connect with explicit connection parameters.
+    ///
+    /// With an access token (see `ConnectParams::with_access_token`) the
+    /// username and password are ignored.
+    ///
+    /// With failover addresses, each address is tried in turn (see
+    /// `ConnectParams::addresses`) until one accepts the connection.
+    /// Network failures and listener refusals move on to the next address;
+    /// other errors, such as invalid credentials, are returned at once.
     pub async fn connect_with_params(
         params: &ConnectParams,
         username: &str,
         password: &str,
     ) -> Result<Self> {
-        use tokio::net::lookup_host;
-        use tokio::time::timeout;
+        // Access tokens are bearer credentials, never sent in clear text
+        if matches!(params.auth, AuthMethod::Token { .. }) && params.protocol != Protocol::Tcps {
+            return Err(Error::AuthenticationFailed {
+                message: "access token authentication requires a TCPS connection".to_string(),
+            });
+        }
 
-        // Step 1: DNS resolution with timeout
-        let addr_str = format!("{}:{}", params.host, params.port);
-        let addrs = timeout(params.connect_timeout, lookup_host(&addr_str))
-            .await
-            .map_err(|_| Error::ConnectionTimeout {
-                host: params.host.clone(),
-                port: params.port,
-                timeout: params.connect_timeout,
-            })?
-            .map_err(|e| {
-                // Check if this is a DNS-specific error
-                if e.kind() == std::io::ErrorKind::NotFound
-                    || e.to_string().contains("could not resolve")
-                    || e.to_string().contains("Name or service not known")
-                    || e.to_string().contains("nodename nor servname provided")
-                {
-                    Error::DnsResolutionFailed {
-                        hostname: params.host.clone(),
-                        message: e.to_string(),
+        let mut last_error = None;
+        for address in params.addresses() {
+            let attempt = params.for_address(&address);
+            #[cfg(feature = "otel")]
+            let started = std::time::SystemTime::now();
+            let result = Self::connect_to_address(&attempt, username, password).await;
+            #[cfg(feature = "otel")]
+            otel::record_connect(&otel::Peer::new(&attempt, username), started, &result);
+            match result {
+                Ok(conn) => return Ok(conn),
+                Err(e) if can_fail_over(&e) => last_error = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or(Error::ConnectionClosed))
+    }
+
+    /// Connect to the single address of `params`.
+    ///
+    /// A listener or Connection Manager may redirect the connection, in
+    /// which case the connection is made again to the address it gives.
+    async fn connect_to_address(
+        params: &ConnectParams,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let mut target = params.clone();
+        let mut redirect = None;
+        let mut redirects = 0;
+        let (mut stream, mut caps) = loop {
+            let mut stream = Self::open_stream(&target).await?;
+            let mut caps = Capabilities::new();
+
+            // Step 2: perform TNS connect handshake
+            match connect(&mut stream, &target, redirect.as_ref(), &mut caps).await? {
+                Handshake::Accepted(_) => break (stream, caps),
+                Handshake::Redirected(next) => {
+                    redirects += 1;
+                    if redirects > MAX_REDIRECTS {
+                        return Err(Error::protocol(format!(
+                            "Connection redirected more than {} times",
+                            MAX_REDIRECTS
+                        )));
                     }
-                } else {
-                    Error::Io(e)
+                    target = params.for_redirect(&next);
+                    redirect = Some(next);
                 }
-            })?;
+            }
+        };
+
+        // Create credentials
+        let mut creds = AuthCredentials::new(username, password);
+        creds.privilege = params.privilege;
+        let alter_session = params.session_settings.logon_statement();
+
+        // Tokens replace the password challenge: negotiate, then send the token.
+        // Otherwise use FastAuth for Oracle 23ai+, or normal auth
+        let session = if let AuthMethod::Token { token, private_key } = &params.auth {
+            exchange_data_types(&mut stream, &mut caps).await?;
+            token_authenticate(
+                &mut stream,
+                token,
+                private_key.as_deref(),
+                &params.service_name,
+                &params.host,
+                params.port,
+                &alter_session,
+            )
+            .await?
+        } else if caps.features().supports_fast_auth() {
+            // FastAuth combines protocol, data types, and auth phase 1
+            let mut session = fast_auth(&mut stream, &mut caps, &creds).await?;
+
+            // Complete authentication with phase 2
+            phase_two(&mut stream, &creds, &caps, &mut session, &alter_session).await?;
+
+            session
+        } else {
+            // Exchange data types first
+            exchange_data_types(&mut stream, &mut caps).await?;
 
-        // Step 2: Try each resolved address with timeout
-        let mut last_error = None;
-        for addr in addrs {
-            match timeout(params.connect_timeout, TcpStream::connect(addr)).await {
-                Ok(Ok(tcp_stream)) => {
-                    // Set TCP_NODELAY for immediate packet transmission (matches Python oracledb)
-                    tcp_stream.set_nodelay(true)?;
+            // Then authenticate
+            authenticate(&mut stream, &creds, &caps, &alter_session).await?
+        };
 
-                    // Create packet stream
-                    let mut stream = PacketStream::new(tcp_stream);
+        let mut conn = Self::from_parts(stream, caps, session);
+        #[cfg(feature = "otel")]
+        {
+            conn.peer = otel::Peer::new(params, username);
+        }
+        for sql in params.session_settings.container_statements() {
+            conn.execute(&sql, &[]).await?;
+        }
+        Ok(conn)
+    }
 
-                    // Initialize capabilities
-                    let mut caps = Capabilities::new();
+    /// Open the packet stream to the single address of `params`.
+    async fn open_stream(params: &ConnectParams) -> Result<PacketStream> {
+        use tokio::net::lookup_host;
 
-                    // Perform TNS connect handshake
-                    connect(&mut stream, params, &mut caps).await?;
+        // Step 1: resolve the host and connect to the first reachable
+        // address, of the proxy if there is one
+        let hop = match &params.proxy {
+            Some(proxy) => params.for_address(&proxy.address),
+            None => params.clone(),
+        };
+        let mut tcp_stream = dial(
+            &hop,
+            &TokioClock,
+            |addr| async move { Ok(lookup_host(addr).await?.collect()) },
+            |addr| open_socket(addr, params),
+        )
+        .await?;
+
+        // Set TCP_NODELAY for immediate packet transmission (matches Python oracledb)
+        tcp_stream.set_nodelay(true)?;
+        if let Some(expire_time) = params.expire_time {
+            enable_keepalive(&tcp_stream, expire_time)?;
+        }
 
-                    // Note: Python's asyncio implementation also disables OOB (supports_oob = False)
-                    // so we don't need to send OOB break + RESET marker after ACCEPT
+        if let Some(proxy) = &params.proxy {
+            let tunnel = proxy.tunnel(&mut tcp_stream, &params.host, params.port);
+            clock::timeout(&TokioClock, params.connect_timeout, tunnel)
+                .await
+                .ok_or_else(|| Error::ConnectionTimeout {
+                    host: params.host.clone(),
+                    port: params.port,
+                    timeout: params.connect_timeout,
+                })??;
+        }
 
-                    // Create credentials
-                    let creds = AuthCredentials::new(username, password);
+        // Create packet stream, over TLS for TCPS
+        let transport = start_transport(params, &TokioClock, tcp_stream).await?;
+        let mut stream = PacketStream::new(transport);
+        stream.set_max_response_size(params.max_response_size);
+        Ok(stream)
+    }
 
-                    // Use FastAuth for Oracle 23ai+, otherwise normal auth
-                    let session = if caps.supports_fast_auth {
-                        // FastAuth combines protocol, data types, and auth phase 1
-                        let mut session = fast_auth(&mut stream, &mut caps, &creds).await?;
+    /// Create a connection over an established, authenticated session.
+    pub(crate) fn from_parts(
+        stream: PacketStream,
+        caps: Capabilities,
+        session: SessionData,
+    ) -> Self {
+        Self {
+            stream,
+            caps,
+            session,
+            autocommit: false,
+            stats: ConnectionStats::default(),
+            cursors_to_close: Vec::new(),
+            response_buf: BytesMut::new(),
+            statement_cache: StatementCache::default(),
+            broken: false,
+            desynchronized: false,
+            temp_lobs_to_free: Vec::new(),
+            call_timeout: Duration::ZERO,
+            call_state: CallState::Idle,
+            end_to_end: EndToEndMessage::default(),
+            last_activity: Instant::now(),
+            notifications: Vec::new(),
+            interceptor: None,
+            query_logger: None,
+            #[cfg(feature = "otel")]
+            peer: otel::Peer::default(),
+            transaction_in_progress: false,
+            ltxid: None,
+            replay_context: None,
+        }
+    }
 
-                        // Complete authentication with phase 2
-                        phase_two(&mut stream, &creds, &caps, &mut session).await?;
+    /// Check if the connection is alive by sending a ping.
+    ///
+    /// Any cursors queued for closing are released with the ping.
+    pub async fn ping(&mut self) -> Result<()> {
+        // The reply carries no rows, only the end-of-call status
+        let server_ttc = self.caps.server_ttc_field_version;
+        let reply = self
+            .send_message_and_read_response(&PingMessage::new(), |buf| {
+                parse_fetch_response(buf, &[], None, server_ttc)
+            })
+            .await?;
+        if reply.error_info.error_num != 0 {
+            return Err(reply.error_info.to_error());
+        }
 
-                        session
-                    } else {
-                        // Exchange data types first
-                        exchange_data_types(&mut stream, &mut caps).await?;
+        Ok(())
+    }
 
-                        // Then authenticate
-                        authenticate(&mut stream, &creds, &caps).await?
-                    };
+    /// Classify the connection's health, without a round trip.
+    ///
+    /// This combines the outcome of the calls made so far with the state of
+    /// the socket: a connection the server has closed is reported, and
+    /// marked, as lost. A connection that is not usable fails every later
+    /// call with `Error::ConnectionClosed` and should be dropped.
+    pub fn health(&mut self) -> ConnectionHealth {
+        if !self.broken && self.stream.is_closed() {
+            self.broken = true;
+        }
+        match (self.broken, self.desynchronized, self.call_state) {
+            (true, true, _) => ConnectionHealth::Desynchronized,
+            (true, false, _) => ConnectionHealth::SessionLost,
+            (false, _, CallState::Idle) => ConnectionHealth::Healthy,
+            (false, _, _) => ConnectionHealth::Interrupted,
+        }
+    }
 
-                    return Ok(Self {
-                        stream,
-                        caps,
-                        session,
-                        autocommit: false,
-                    });
-                }
-                Ok(Err(e)) => {
-                    last_error = Some(Error::Io(e));
-                    continue;
-                }
-                Err(_) => {
-                    return Err(Error::ConnectionTimeout {
-                        host: params.host.clone(),
-                        port: params.port,
-                        timeout: params.connect_timeout,
-                    });
-                }
-            }
+    /// Check whether the connection can be used, for pools and retry
+    /// layers deciding whether to reuse it.
+    ///
+    /// With `ping`, a usable connection is also pinged, which catches
+    /// sessions the server or a firewall dropped without closing the
+    /// socket, at the cost of a round trip.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     if !conn.is_healthy(true).await {
+    ///         conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn is_healthy(&mut self, ping: bool) -> bool {
+        if !self.health().is_usable() {
+            return false;
         }
+        !ping || self.ping().await.is_ok()
+    }
 
-        // If we exhausted all addresses without success, return the last error
-        Err(last_error.unwrap_or_else(|| Error::DnsResolutionFailed {
-            hostname: params.host.clone(),
-            message: "No addresses returned".to_string(),
-        }))
+    /// Get the time since the last response from the server.
+    pub fn idle_time(&self) -> Duration {
+        self.last_activity.elapsed()
     }
 
-    /// Check if the connection is alive by sending a ping.
-    pub async fn ping(&mut self) -> Result<()> {
-        // TODO: Implement ping
-        Ok(())
+    /// Ping the server if the connection has been idle for `max_idle` or
+    /// longer, returning whether a ping was sent.
+    ///
+    /// Meant for checking a connection before handing it out after a quiet
+    /// period, when a firewall may have dropped it: recently used
+    /// connections are known to be alive and are not pinged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     // ... connection sits idle ...
+    ///     conn.ping_if_idle(Duration::from_secs(60)).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn ping_if_idle(&mut self, max_idle: Duration) -> Result<bool> {
+        if self.idle_time() < max_idle {
+            return Ok(false);
+        }
+        self.ping().await?;
+        Ok(true)
     }
 
     /// Close the connection.
@@ -235,6 +593,218 @@ impl Connection {
         self.caps.sdu
     }
 
+    /// Get the number of request/response round trips made on this connection.
+    ///
+    /// Connection establishment is not counted.
+    pub fn round_trips(&self) -> u64 {
+        self.stats.round_trips
+    }
+
+    /// Get the statistics for this connection: round trips, bytes
+    /// exchanged, statement parses, executions and fetches, cursors leaked
+    /// by dropping them open, and the time spent in round trips.
+    ///
+    /// With the `tracing` feature, each round trip is also reported as a
+    /// `DEBUG` event with target `oracle_thin_rs::stats`.
+    pub fn stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            bytes_sent: self.stream.bytes_sent(),
+            bytes_received: self.stream.bytes_received(),
+            ..self.stats.clone()
+        }
+    }
+
+    /// Get the negotiated TTC field version.
+    ///
+    /// This is the version the server formats responses with (column
+    /// metadata, error info) and gates the 23.1/23.4 metadata fields.
+    pub fn ttc_field_version(&self) -> u8 {
+        self.caps.ttc_field_version
+    }
+
+    /// Get the TTC field version advertised by the server.
+    pub fn server_ttc_field_version(&self) -> u8 {
+        self.caps.server_ttc_field_version
+    }
+
+    /// Set the largest response, in bytes, accepted from the server.
+    ///
+    /// See `ConnectParams::with_max_response_size`.
+    pub fn set_max_response_size(&mut self, max_response_size: usize) {
+        self.stream.set_max_response_size(max_response_size);
+    }
+
+    /// Get the largest response, in bytes, accepted from the server.
+    pub fn max_response_size(&self) -> usize {
+        self.stream.max_response_size()
+    }
+
+    /// Set the longest time a call to the server may take.
+    ///
+    /// A call still running when the timeout expires is interrupted on the
+    /// server, and fails with `Error::CallTimeout`. A zero duration (the
+    /// default) means no limit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, Error};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     conn.set_call_timeout(Duration::from_secs(5));
+    ///     match conn.query("SELECT * FROM huge_table ORDER BY 1").await {
+    ///         Err(Error::CallTimeout { .. }) => println!("gave up; connection still usable"),
+    ///         other => println!("{:?}", other.map(|r| r.len())),
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_call_timeout(&mut self, timeout: Duration) {
+        self.call_timeout = timeout;
+    }
+
+    /// Get the call timeout (zero for no limit).
+    pub fn call_timeout(&self) -> Duration {
+        self.call_timeout
+    }
+
+    /// Set the number of statements whose cursors are kept open (20 by
+    /// default, zero to keep none).
+    ///
+    /// Running the SQL of a cached statement again re-executes its cursor
+    /// without sending the text to be parsed; `query`, `execute` and
+    /// queries run with `QueryOptions` are cached, cursors opened with
+    /// `open_cursor` are not. The least recently used statements past the
+    /// new size have their cursors closed on the next round trip.
+    pub fn set_statement_cache_size(&mut self, size: usize) {
+        for cursor_id in self.statement_cache.set_capacity(size) {
+            self.queue_cursor_close(cursor_id);
+        }
+    }
+
+    /// Get the number of statements whose cursors are kept open.
+    pub fn statement_cache_size(&self) -> usize {
+        self.statement_cache.capacity()
+    }
+
+    /// Get the number of statements in the statement cache.
+    pub fn cached_statements(&self) -> usize {
+        self.statement_cache.len()
+    }
+
+    /// Set a hook run on each statement before it is sent, or remove it
+    /// with `None`.
+    ///
+    /// The hook sees the SQL text, statement type, placeholder names and
+    /// bind values of every query, cursor, execute, `execute_many`,
+    /// `describe` and pipeline operation, and may rewrite or refuse the
+    /// statement. SQL that other methods run through these, such as the
+    /// sessionless transaction calls, passes through it too. For pooled
+    /// connections, set it in the pool's session callback.
+    pub fn set_sql_interceptor(&mut self, interceptor: Option<Arc<dyn SqlInterceptor>>) {
+        self.interceptor = interceptor;
+    }
+
+    /// Set a logger receiving an entry for each statement run, or remove
+    /// it with `None`.
+    ///
+    /// Queries, cursors, `execute`, `execute_many`, the `RETURNING` calls
+    /// and pipeline operations are logged once their response is read,
+    /// with the SQL as sent (after any `SqlInterceptor`), the time taken,
+    /// the row count and the ORA- code of a failure. Literals in the SQL
+    /// and bind values are redacted unless the logger asks otherwise; see
+    /// [`QueryLogger::redact`]. For pooled connections, set it in the
+    /// pool's session callback.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "tracing")]
+    /// # async fn example(conn: &mut oracle_thin_rs::Connection) {
+    /// use oracle_thin_rs::TracingQueryLogger;
+    /// use std::sync::Arc;
+    ///
+    /// conn.set_query_logger(Some(Arc::new(TracingQueryLogger)));
+    /// # }
+    /// ```
+    pub fn set_query_logger(&mut self, logger: Option<Arc<dyn QueryLogger>>) {
+        self.query_logger = logger;
+    }
+
+    /// Report a statement to the query logger, and as an OpenTelemetry
+    /// span with the `otel` feature, with the number of rows `rows` finds
+    /// in a successful result.
+    fn log_statement<T>(
+        &self,
+        stmt: &Statement,
+        binds: &[Vec<OracleValue>],
+        duration: Duration,
+        result: &Result<T>,
+        rows: impl FnOnce(&T) -> u64,
+    ) {
+        let outcome = result.as_ref().map(rows);
+        #[cfg(feature = "otel")]
+        otel::record_statement(&self.peer, stmt, duration, outcome);
+        querylog::log_statement(self.query_logger.as_ref(), stmt, binds, duration, outcome);
+    }
+
+    /// Report a cursor fetch of `sql` as an OpenTelemetry span.
+    #[cfg(feature = "otel")]
+    pub(crate) fn trace_fetch(
+        &self,
+        sql: &str,
+        started: std::time::SystemTime,
+        response: &Result<crate::protocol::response::FetchResponse>,
+    ) {
+        let info_error;
+        let outcome = match response {
+            Ok(response)
+                if response.error_info.error_num != 0
+                    && response.error_info.error_num != TNS_ERR_NO_DATA_FOUND =>
+            {
+                info_error = response.error_info.to_error();
+                Err(&info_error)
+            }
+            Ok(response) => Ok(response.rows.len() as u64),
+            Err(e) => Err(e),
+        };
+        otel::record_fetch(&self.peer, sql, started, outcome);
+    }
+
+    /// Parse a statement, passing it through the SQL interceptor.
+    fn prepare_statement(&self, sql: &str, binds: &[Vec<OracleValue>]) -> Result<Statement> {
+        intercept::prepare_statement(self.interceptor.as_ref(), sql, binds)
+    }
+
+    /// Cancel a call that was abandoned before it completed.
+    ///
+    /// Dropping a statement's future while it waits for the server (for
+    /// example with `tokio::time::timeout` or `select!`) leaves the statement
+    /// running on the server. `cancel` interrupts it with a BREAK marker and
+    /// reads the server's reply, so the connection can be used again. The
+    /// next call does this by itself; `cancel` does it right away. It does
+    /// nothing if no call was abandoned.
+    ///
+    /// Like python-oracledb's asyncio mode, the BREAK is sent in band unless
+    /// out-of-band breaks were enabled with `ConnectParams::with_oob_break`.
+    ///
+    /// A call abandoned while its request was being written has the rest
+    /// of the request written first. One abandoned after part of its
+    /// response was read has the rest of the response read and discarded,
+    /// if the server flags the end of each response (Oracle Database 23ai);
+    /// older servers leave the connection unusable, and an error is returned.
+    ///
+    /// The interrupted call's ORA-01013 is read here and not reported again,
+    /// and the statement can be executed again right away. A call cancelled
+    /// on the server by other means, such as `ALTER SYSTEM CANCEL SQL`,
+    /// fails with `Error::Cancelled` and leaves the connection usable.
+    pub async fn cancel(&mut self) -> Result<()> {
+        self.recover_abandoned_call().await
+    }
+
     /// Set auto-commit mode.
     pub fn set_autocommit(&mut self, autocommit: bool) {
         self.autocommit = autocommit;
@@ -245,91 +815,190 @@ impl Connection {
         self.autocommit
     }
 
-    /// Execute a SELECT query and return the results.
+    /// Check whether the session has uncommitted work, as reported by the
+    /// server at the end of the last call.
+    pub fn transaction_in_progress(&self) -> bool {
+        self.transaction_in_progress
+    }
+
+    /// Get the logical transaction ID (LTXID) of the session.
     ///
-    /// This is a simplified version that returns all prefetched rows.
-    /// For large result sets, use `query_iter()` instead (not yet implemented).
+    /// Services with Transaction Guard enabled (`COMMIT_OUTCOME`) send it
+    /// with each call that may commit. After an outage, passing it to
+    /// `DBMS_APP_CONT.GET_LTXID_OUTCOME` on a new session tells whether the
+    /// call in flight committed. `None` for other services.
+    pub fn ltxid(&self) -> Option<&[u8]> {
+        self.ltxid.as_deref()
+    }
+
+    /// Get the Application Continuity replay context last sent by the
+    /// server, for services with Application Continuity enabled.
+    pub fn replay_context(&self) -> Option<&ReplayContext> {
+        self.replay_context.as_ref()
+    }
+
+    /// Start a sessionless transaction (Oracle Database 23ai).
+    ///
+    /// A sessionless transaction is not tied to the session that started
+    /// it: `suspend_sessionless_transaction` detaches it, and any connection
+    /// can pick it up again with `resume_sessionless_transaction`, given the
+    /// returned transaction id. Stateless middle tiers can spread one
+    /// transaction over several requests without pinning a connection.
+    ///
+    /// `transaction_id` names the transaction (1 to 64 bytes); `None`
+    /// generates a random 16-byte id. `timeout` is how long a suspended
+    /// transaction waits to be resumed before the server rolls it back.
+    /// Auto-commit must stay off while the transaction is active, or its
+    /// statements commit it.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use oracle_thin_rs::Connection;
+    /// use std::time::Duration;
+    /// use oracle_thin_rs::{Connection, ToSql};
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let mut conn = Connection::connect(
-    ///         "localhost:1521/FREEPDB1",
-    ///         "read_user",
-    ///         "password"
-    ///     ).await?;
+    ///     let mut first = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let id = first
+    ///         .begin_sessionless_transaction(None, Duration::from_secs(60))
+    ///         .await?;
+    ///     first.execute("INSERT INTO items (id) VALUES (:1)", &[1i64.to_sql()]).await?;
+    ///     first.suspend_sessionless_transaction().await?;
     ///
-    ///     let rows = conn.query("SELECT 'hello' FROM DUAL").await?;
-    ///     for row in rows {
-    ///         println!("{:?}", row);
-    ///     }
+    ///     // Later, on any connection
+    ///     let mut second = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     second
+    ///         .resume_sessionless_transaction(&id, Duration::from_secs(60))
+    ///         .await?;
+    ///     second.execute("INSERT INTO items (id) VALUES (:1)", &[2i64.to_sql()]).await?;
+    ///     second.execute("COMMIT", &[]).await?;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn query(&mut self, sql: &str) -> Result<QueryResult> {
-        // Default prefetch size
-        let prefetch_rows = 100u32;
-
-        // Create execute message
-        let msg = ExecuteMessage::new_query(sql, prefetch_rows, self.caps.ttc_field_version);
-
-        // Debug: print the wire format and hex dump
-        let wire_size = crate::protocol::message::Message::wire_size(&msg);
-        // eprintln!("[DEBUG] Execute message wire size: {}", wire_size);
-        // eprintln!("[DEBUG] TTC field version: {}", self.caps.ttc_field_version);
+    pub async fn begin_sessionless_transaction(
+        &mut self,
+        transaction_id: Option<&[u8]>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>> {
+        let transaction_id = match transaction_id {
+            Some(id) => id.to_vec(),
+            None => {
+                let mut id = vec![0u8; 16];
+                rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut id);
+                id
+            }
+        };
+        self.start_sessionless_transaction(&transaction_id, timeout, "TRANSACTION_NEW")
+            .await?;
+        Ok(transaction_id)
+    }
 
-        // Serialize and dump hex
-        let mut debug_buf = Vec::with_capacity(wire_size);
-        crate::protocol::message::Message::write_to(&msg, &mut debug_buf)?;
-        // eprintln!("[DEBUG] Execute message hex ({} bytes):", debug_buf.len());
-        // for chunk in debug_buf.chunks(16) {
-        //     let hex: Vec<String> = chunk.iter().map(|b| format!("{:02X}", b)).collect();
-        //     eprintln!("  {}", hex.join(" "));
-        // }
+    /// Resume a sessionless transaction suspended on this or another
+    /// connection (see `begin_sessionless_transaction`).
+    ///
+    /// `timeout` is how long to wait for the transaction if another session
+    /// still has it.
+    pub async fn resume_sessionless_transaction(
+        &mut self,
+        transaction_id: &[u8],
+        timeout: Duration,
+    ) -> Result<()> {
+        self.start_sessionless_transaction(transaction_id, timeout, "TRANSACTION_RESUME")
+            .await
+    }
 
-        // Send execute message
-        self.stream.send_data_message(&msg).await?;
+    /// Suspend the active sessionless transaction, so another connection
+    /// can resume it.
+    pub async fn suspend_sessionless_transaction(&mut self) -> Result<()> {
+        self.execute("BEGIN DBMS_TRANSACTION.SUSPEND_TRANSACTION; END;", &[])
+            .await?;
+        Ok(())
+    }
 
-        // Read response, handling any control/marker packets
-        let response = self.read_data_response().await?;
+    /// Start or resume a sessionless transaction with `DBMS_TRANSACTION`,
+    /// `flag` naming the package constant for the operation.
+    async fn start_sessionless_transaction(
+        &mut self,
+        transaction_id: &[u8],
+        timeout: Duration,
+        flag: &str,
+    ) -> Result<()> {
+        if !(1..=MAX_TRANSACTION_ID_SIZE).contains(&transaction_id.len()) {
+            return Err(Error::type_conversion(format!(
+                "sessionless transaction id must be 1 to {} bytes, got {}",
+                MAX_TRANSACTION_ID_SIZE,
+                transaction_id.len()
+            )));
+        }
+        let sql = format!(
+            "DECLARE transaction_id RAW({}); BEGIN \
+             transaction_id := DBMS_TRANSACTION.START_TRANSACTION(:1, \
+             DBMS_TRANSACTION.TRANSACTION_TYPE_SESSIONLESS, :2, DBMS_TRANSACTION.{}); END;",
+            MAX_TRANSACTION_ID_SIZE, flag
+        );
+        let timeout = i64::try_from(timeout.as_secs()).unwrap_or(i64::MAX);
+        let params = [
+            OracleValue::Raw(transaction_id.to_vec()),
+            OracleValue::Integer(timeout),
+        ];
+        // A commit with the call would end the transaction it starts
+        let autocommit = std::mem::replace(&mut self.autocommit, false);
+        let result = self.execute(&sql, &params).await;
+        self.autocommit = autocommit;
+        result.map(drop)
+    }
 
-        let mut buf = ReadBuffer::new(response.payload);
-        let _data_flags = buf.read_u16_be()?;
+    /// Set the module reported for the session (`MODULE` in `V$SESSION`).
+    ///
+    /// Like the other end-to-end tracing attributes, the value is sent with
+    /// the next round trip rather than on its own; an empty string clears it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     conn.set_module("billing");
+    ///     conn.set_action("monthly run");
+    ///     conn.set_client_identifier("alice");
+    ///     // Sent with the query, so V$SESSION shows them while it runs
+    ///     conn.query("SELECT * FROM invoices").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_module(&mut self, module: &str) {
+        self.end_to_end.module = Some(module.to_string());
+    }
 
-        let exec_response = parse_execute_response(
-            &mut buf,
-            self.caps.ttc_field_version,
-            self.caps.server_ttc_field_version,
-        )?;
+    /// Set the action reported for the session (`ACTION` in `V$SESSION`).
+    pub fn set_action(&mut self, action: &str) {
+        self.end_to_end.action = Some(action.to_string());
+    }
 
-        // Check for Oracle errors
-        if exec_response.error_info.error_num != 0 && exec_response.error_info.error_num != 1403 {
-            return Err(Error::Oracle {
-                code: exec_response.error_info.error_num,
-                message: exec_response.error_info.message.unwrap_or_default(),
-            });
-        }
+    /// Set the client information reported for the session (`CLIENT_INFO`
+    /// in `V$SESSION`).
+    pub fn set_client_info(&mut self, client_info: &str) {
+        self.end_to_end.client_info = Some(client_info.to_string());
+    }
 
-        Ok(QueryResult {
-            columns: exec_response.columns,
-            rows: exec_response.rows,
-            row_count: exec_response.error_info.row_count,
-            more_rows: exec_response.more_rows,
-        })
+    /// Set the end user of the session (`CLIENT_IDENTIFIER` in `V$SESSION`).
+    pub fn set_client_identifier(&mut self, client_identifier: &str) {
+        self.end_to_end.client_identifier = Some(client_identifier.to_string());
     }
 
-    /// Open a row-by-row cursor for a SELECT query.
+    /// Execute a SELECT query and return the results.
     ///
-    /// The cursor takes exclusive access to the connection until closed.
+    /// This is a simplified version that returns all prefetched rows.
+    /// For large result sets, use `query_iter()` instead (not yet implemented).
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use oracle_thin_rs::{Connection, Cursor};
+    /// use oracle_thin_rs::Connection;
     ///
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -339,184 +1008,2166 @@ impl Connection {
     ///         "password"
     ///     ).await?;
     ///
-    ///     let mut cursor = conn.open_cursor("SELECT * FROM large_table").await?;
-    ///
-    ///     // Process rows one at a time
-    ///     while let Some(row) = cursor.next().await? {
+    ///     let rows = conn.query("SELECT 'hello' FROM DUAL").await?;
+    ///     for row in rows {
     ///         println!("{:?}", row);
     ///     }
-    ///
     ///     Ok(())
     /// }
     /// ```
-    pub async fn open_cursor(&mut self, sql: &str) -> Result<impl Cursor<Item = Row> + '_> {
-        self.open_row_cursor(sql, 100).await
+    pub async fn query(&mut self, sql: &str) -> Result<QueryResult> {
+        self.query_with_options(sql, &QueryOptions::default()).await
     }
 
-    /// Open a row cursor with a specific fetch size.
+    /// Execute a SELECT query with explicit fetch options.
     ///
-    /// # Arguments
+    /// Only the rows prefetched with the execute round trip are returned;
+    /// `QueryResult::more_rows` tells whether the query has more. Use a
+    /// cursor to fetch all rows.
     ///
-    /// * `sql` - SQL query to execute
-    /// * `fetch_size` - Number of rows to fetch per roundtrip
-    pub async fn open_row_cursor(
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, QueryOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let options = QueryOptions::new().with_prefetch_rows(1000);
+    ///     let rows = conn.query_with_options("SELECT * FROM orders", &options).await?;
+    ///     println!("{} rows, more: {}", rows.len(), rows.more_rows);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_with_options(
         &mut self,
         sql: &str,
-        fetch_size: u32,
-    ) -> Result<impl Cursor<Item = Row> + '_> {
-        // Create execute message
-        let msg = ExecuteMessage::new_query(sql, fetch_size, self.caps.ttc_field_version);
-
-        // Send execute message
-        self.stream.send_data_message(&msg).await?;
+        options: &QueryOptions,
+    ) -> Result<QueryResult> {
+        let stmt = self.prepare_statement(sql, &[])?;
+        let mut exec_response = self.execute_statement(&stmt, options, &[], true).await?;
+        let implicit_results = std::mem::take(&mut exec_response.implicit_results);
+        let implicit_results = self
+            .fetch_implicit_results(stmt.sql(), implicit_results, options.fetch_size)
+            .await?;
+
+        let mut result = query_result(&stmt, exec_response);
+        result.implicit_results = implicit_results;
+        Ok(result)
+    }
 
-        // Read response
-        let response = self.read_data_response().await?;
+    /// Fetch all rows of the implicit result sets returned by `sql`.
+    ///
+    /// Each result set is a server cursor; it is closed once its rows are
+    /// fetched, or queued for closing if fetching fails.
+    async fn fetch_implicit_results(
+        &mut self,
+        sql: &str,
+        results: Vec<ImplicitResult>,
+        fetch_size: u32,
+    ) -> Result<Vec<QueryResult>> {
+        let mut fetched = Vec::with_capacity(results.len());
+        let mut results = results.into_iter();
+        while let Some(result) = results.next() {
+            match self.fetch_implicit_result(sql, result, fetch_size).await {
+                Ok(result) => fetched.push(result),
+                Err(e) => {
+                    self.discard_implicit_results(results.collect());
+                    return Err(e);
+                }
+            }
+        }
+        Ok(fetched)
+    }
 
-        // Parse response
-        let mut buf = ReadBuffer::new(response.payload);
-        let _data_flags = buf.read_u16_be()?;
+    /// Queue the cursors of unwanted implicit result sets for closing.
+    fn discard_implicit_results(&mut self, results: Vec<ImplicitResult>) {
+        for result in results {
+            self.queue_cursor_close(result.cursor_id);
+        }
+    }
 
-        let exec_response = parse_execute_response(
-            &mut buf,
+    /// Fetch all rows of one implicit result set.
+    async fn fetch_implicit_result(
+        &mut self,
+        sql: &str,
+        result: ImplicitResult,
+        fetch_size: u32,
+    ) -> Result<QueryResult> {
+        let mut cursor = RowCursor::from_server_cursor(
+            self,
+            sql,
+            result.columns.clone(),
+            result.cursor_id,
+            fetch_size,
+        );
+        let rows = cursor.fetch_all().await?;
+
+        Ok(QueryResult {
+            columns: result.columns,
+            row_count: rows.len() as u64,
+            rows,
+            more_rows: false,
+            last_rowid: None,
+            statement_type: StatementType::Query,
+            implicit_results: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Execute a query and return only its first row (single-row mode).
+    ///
+    /// Prefetches a single row in the execute round trip, so a LOB-free
+    /// lookup costs exactly one round trip. Only when the execute describes
+    /// columns returned as locators (see `ColumnMetadata::requires_define`)
+    /// and holds the row back is it fetched with a second round trip.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect(
+    ///         "localhost:1521/FREEPDB1",
+    ///         "read_user",
+    ///         "password"
+    ///     ).await?;
+    ///
+    ///     if let Some(row) = conn.query_one("SELECT name FROM users WHERE id = 1").await? {
+    ///         println!("{:?}", row);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_one(&mut self, sql: &str) -> Result<Option<Row>> {
+        let exec_response = self.execute_query(sql, 1).await?;
+        let held_back = exec_response.rows.is_empty()
+            && exec_response.more_rows
+            && exec_response
+                .columns
+                .iter()
+                .any(ColumnMetadata::requires_define);
+        if !held_back {
+            return Ok(exec_response.rows.into_iter().next());
+        }
+        let cursor_id = exec_response.error_info.cursor_id as u32;
+        let mut cursor = RowCursor::new(
+            self,
+            sql,
+            exec_response.columns,
+            cursor_id,
+            Vec::new(),
+            true,
+            1,
+        );
+        // Done with, not leaked, whether or not the fetch succeeds
+        let row = cursor.next().await;
+        cursor.release();
+        row
+    }
+
+    /// Execute a query and convert every row to `T`.
+    ///
+    /// Unlike `query`, all rows are fetched, not just the first batch.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, FromRow, Result, Row};
+    ///
+    /// struct User {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl FromRow for User {
+    ///     fn from_row(row: &Row) -> Result<Self> {
+    ///         Ok(Self {
+    ///             id: row.get_as_by_name("ID")?,
+    ///             name: row.get_as_by_name("NAME")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let users: Vec<User> = conn.query_as("SELECT id, name FROM users").await?;
+    ///     println!("{} users", users.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn query_as<T: FromRow>(&mut self, sql: &str) -> Result<Vec<T>> {
+        let mut cursor = self.open_cursor(sql).await?;
+        cursor.fetch_all_as().await
+    }
+
+    /// Open a row-by-row cursor for a SELECT query.
+    ///
+    /// The cursor takes exclusive access to the connection until closed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, Cursor};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect(
+    ///         "localhost:1521/FREEPDB1",
+    ///         "read_user",
+    ///         "password"
+    ///     ).await?;
+    ///
+    ///     let mut cursor = conn.open_cursor("SELECT * FROM large_table").await?;
+    ///
+    ///     // Process rows one at a time
+    ///     while let Some(row) = cursor.next().await? {
+    ///         println!("{:?}", row);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn open_cursor(&mut self, sql: &str) -> Result<RowCursor<'_>> {
+        self.open_cursor_with_options(sql, &QueryOptions::default())
+            .await
+    }
+
+    /// Open a row cursor with a specific fetch size.
+    ///
+    /// # Arguments
+    ///
+    /// * `sql` - SQL query to execute
+    /// * `fetch_size` - Number of rows to fetch per roundtrip
+    pub async fn open_row_cursor(&mut self, sql: &str, fetch_size: u32) -> Result<RowCursor<'_>> {
+        let options = QueryOptions::new()
+            .with_prefetch_rows(fetch_size)
+            .with_fetch_size(fetch_size);
+        self.open_cursor_with_options(sql, &options).await
+    }
+
+    /// Open a row cursor with explicit fetch options.
+    ///
+    /// The first `prefetch_rows` rows come back with the execute round trip;
+    /// each later fetch requests `fetch_size` rows.
+    pub async fn open_cursor_with_options(
+        &mut self,
+        sql: &str,
+        options: &QueryOptions,
+    ) -> Result<RowCursor<'_>> {
+        let stmt = self.prepare_statement(sql, &[])?;
+        let exec_response = self.execute_statement(&stmt, options, &[], false).await?;
+        self.discard_implicit_results(exec_response.implicit_results);
+
+        let mut cursor = RowCursor::new(
+            self,
+            stmt.sql(),
+            exec_response.columns,
+            exec_response.error_info.cursor_id as u32,
+            exec_response.rows,
+            exec_response.more_rows,
+            options.fetch_size,
+        );
+        cursor.set_max_buffered_bytes(options.max_buffered_bytes);
+        cursor.set_timeout(options.timeout);
+        Ok(cursor)
+    }
+
+    /// Execute a DDL or DML statement or a PL/SQL block.
+    ///
+    /// Values bind by position, one per placeholder. The statement type
+    /// decides the execute options: DDL is always parsed, and no rows are
+    /// fetched. Queries are executed without fetching; use `query` for rows.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, StatementType, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let result = conn.execute("CREATE TABLE items (id NUMBER)", &[]).await?;
+    ///     assert_eq!(result.statement_type, StatementType::Ddl);
+    ///
+    ///     let result = conn
+    ///         .execute("INSERT INTO items (id) VALUES (:1)", &[1i64.to_sql()])
+    ///         .await?;
+    ///     println!("inserted {} row", result.row_count);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute(&mut self, sql: &str, params: &[OracleValue]) -> Result<ExecuteResult> {
+        let rows = [params.to_vec()];
+        let stmt = self.prepare_statement(sql, &rows)?;
+        let options = QueryOptions::new().with_prefetch_rows(0);
+        let response = self.execute_statement(&stmt, &options, &rows, true).await?;
+        Ok(self.execute_result(&stmt, response))
+    }
+
+    /// Build the result of `execute`, releasing the statement's cursor and
+    /// any implicit result sets.
+    fn execute_result(&mut self, stmt: &Statement, response: ExecuteResponse) -> ExecuteResult {
+        self.discard_implicit_results(response.implicit_results);
+        let info = response.error_info;
+        self.queue_cursor_close(info.cursor_id as u32);
+
+        ExecuteResult {
+            statement_type: stmt.statement_type(),
+            row_count: info.row_count,
+            last_rowid: info.rowid,
+            warnings: info.warnings,
+        }
+    }
+
+    /// Start a pipeline of statements sent together.
+    ///
+    /// See [`Pipeline`] for an example.
+    pub fn pipeline(&mut self) -> Pipeline<'_> {
+        Pipeline::new(self)
+    }
+
+    /// Describe the columns a query would return, without executing it.
+    ///
+    /// The statement is parsed and described in one round trip, so syntax
+    /// errors and missing tables or columns are reported as when executing
+    /// it. Statements other than queries have no columns; they are parsed
+    /// and an empty list is returned, except DDL, which Oracle runs when it
+    /// is parsed and so is not sent at all.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     for column in conn.describe("SELECT id, name FROM items").await? {
+    ///         println!("{} {}", column.name, column.data_type()?);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn describe(&mut self, sql: &str) -> Result<Vec<ColumnMetadata>> {
+        let stmt = self.prepare_statement(sql, &[])?;
+        self.describe_statement(&stmt).await
+    }
+
+    /// Parse a statement on the server without executing it, and return it
+    /// for inspection.
+    ///
+    /// Like `describe`, this reports syntax errors and missing objects up
+    /// front (DDL is not sent). The returned statement is the text the
+    /// connection would send, after any `SqlInterceptor`, and lists its bind
+    /// placeholders with `Statement::bind_info`, so parameters can be
+    /// checked with `Statement::check_params` before executing it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let sql = "UPDATE items SET name = :name WHERE id = :id";
+    ///     let stmt = conn.prepare(sql).await?;
+    ///     for bind in stmt.bind_info() {
+    ///         println!("{} at position {}", bind.name, bind.position);
+    ///     }
+    ///     let params = ["widget".to_sql(), 7i64.to_sql()];
+    ///     stmt.check_params(&params)?;
+    ///     conn.execute(sql, &params).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn prepare(&mut self, sql: &str) -> Result<Statement> {
+        let stmt = self.prepare_statement(sql, &[])?;
+        self.describe_statement(&stmt).await?;
+        Ok(stmt)
+    }
+
+    /// Parse and describe a statement in one round trip.
+    async fn describe_statement(&mut self, stmt: &Statement) -> Result<Vec<ColumnMetadata>> {
+        if stmt.is_ddl() {
+            return Ok(Vec::new());
+        }
+        ExecuteMessage::check_sql_length(stmt.sql())?;
+        let msg = ExecuteMessage::new_describe(stmt, self.caps.ttc_field_version);
+        self.stats.record_execute(msg.calc_options());
+
+        let (ttc, server_ttc) = (
+            self.caps.ttc_field_version,
+            self.caps.server_ttc_field_version,
+        );
+        let response = self
+            .send_message_and_read_response(&msg, |buf| {
+                parse_execute_response(buf, ttc, server_ttc)
+            })
+            .await?;
+
+        let info = &response.error_info;
+        if info.error_num != 0 {
+            let context = ErrorContext::new(Phase::Parse, stmt.sql(), info.cursor_id as u32);
+            return Err(info.to_error().with_context(context));
+        }
+        self.queue_cursor_close(info.cursor_id as u32);
+
+        Ok(response.columns)
+    }
+
+    /// Execute a DML statement or PL/SQL block once for each row of bind values.
+    ///
+    /// All rows are sent in a single round trip. Values bind by position;
+    /// every row must have one value per placeholder, and the non-NULL
+    /// values in a position must all be of the same kind. Execution stops at
+    /// the first failing row; use `execute_many_with_batch_errors` to carry
+    /// on past failures.
+    ///
+    /// Returns the number of rows affected.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let rows: Vec<_> = (1..=1000i64)
+    ///         .map(|id| vec![id.to_sql(), format!("item {}", id).to_sql()])
+    ///         .collect();
+    ///     let count = conn
+    ///         .execute_many("INSERT INTO items (id, name) VALUES (:1, :2)", &rows)
+    ///         .await?;
+    ///     println!("inserted {} rows", count);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute_many(&mut self, sql: &str, rows: &[Vec<OracleValue>]) -> Result<u64> {
+        Ok(self.execute_array(sql, rows, false).await?.row_count)
+    }
+
+    /// Execute a DML statement once for each row of bind values, collecting
+    /// per-row errors instead of stopping at the first failure.
+    ///
+    /// Rows that fail are reported in `BatchResult::errors` with their
+    /// zero-based offset; all other rows are processed. Binding works as in
+    /// `execute_many`.
+    pub async fn execute_many_with_batch_errors(
+        &mut self,
+        sql: &str,
+        rows: &[Vec<OracleValue>],
+    ) -> Result<BatchResult> {
+        self.execute_array(sql, rows, true).await
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause and return
+    /// the returned values, one row per affected row.
+    ///
+    /// `params` binds the placeholders before the clause, as in `execute`;
+    /// `returning` gives the type of each placeholder after `INTO`, in order.
+    /// The result's columns are named after those placeholders.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, OracleType, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let result = conn
+    ///         .execute_returning(
+    ///             "INSERT INTO items (name) VALUES (:1) RETURNING id INTO :id",
+    ///             &["widget".to_sql()],
+    ///             &[OracleType::Number { precision: 0, scale: -127 }],
+    ///         )
+    ///         .await?;
+    ///     let id: i64 = result.rows[0].get_as(0)?;
+    ///     println!("new item {}", id);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute_returning(
+        &mut self,
+        sql: &str,
+        params: &[OracleValue],
+        returning: &[OracleType],
+    ) -> Result<QueryResult> {
+        let (stmt, columns, response) = self
+            .execute_returning_array(sql, &[params.to_vec()], returning)
+            .await?;
+        let info = response.error_info;
+        Ok(QueryResult {
+            columns,
+            rows: response.returned.into_iter().flatten().collect(),
+            row_count: info.row_count,
+            more_rows: false,
+            last_rowid: info.rowid,
+            statement_type: stmt.statement_type(),
+            implicit_results: Vec::new(),
+            warnings: info.warnings,
+        })
+    }
+
+    /// Execute an INSERT and return the value generated for its key column,
+    /// such as an identity column or one filled from a sequence by a
+    /// trigger.
+    ///
+    /// A `RETURNING id_column INTO` clause is added to `sql`, so the key is
+    /// read in the same round trip. The statement must insert exactly one
+    /// row, and the key must be numeric.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let sql = "INSERT INTO items (name) VALUES (:1)";
+    ///     let id = conn
+    ///         .insert_returning_id(sql, &["widget".to_sql()], "id")
+    ///         .await?;
+    ///     println!("new item {}", id);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn insert_returning_id(
+        &mut self,
+        sql: &str,
+        params: &[OracleValue],
+        id_column: &str,
+    ) -> Result<i64> {
+        let sql = returning_id_sql(sql, id_column)?;
+        let id_type = OracleType::Number {
+            precision: 0,
+            scale: -127,
+        };
+        let result = self.execute_returning(&sql, params, &[id_type]).await?;
+        match result.rows.as_slice() {
+            [row] => row.get_as(0),
+            rows => Err(Error::protocol(format!(
+                "insert_returning_id expects one inserted row, got {}",
+                rows.len()
+            ))),
+        }
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause once for
+    /// each row of bind values, in a single round trip.
+    ///
+    /// Binding works as in `execute_many`, with `returning` as in
+    /// `execute_returning`. Returns, for each row of bind values, the rows
+    /// it affected with their returned values.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, OracleType, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let rows = vec![vec!["red".to_sql()], vec!["blue".to_sql()]];
+    ///     let returned = conn
+    ///         .execute_many_returning(
+    ///             "INSERT INTO items (name) VALUES (:1) RETURNING id INTO :id",
+    ///             &rows,
+    ///             &[OracleType::Number { precision: 0, scale: -127 }],
+    ///         )
+    ///         .await?;
+    ///     for (input, ids) in rows.iter().zip(&returned) {
+    ///         println!("{:?} -> {:?}", input, ids[0].get(0));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute_many_returning(
+        &mut self,
+        sql: &str,
+        rows: &[Vec<OracleValue>],
+        returning: &[OracleType],
+    ) -> Result<Vec<Vec<Row>>> {
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (_, _, response) = self.execute_returning_array(sql, rows, returning).await?;
+        Ok(response.returned)
+    }
+
+    /// Execute a DML statement with a `RETURNING ... INTO` clause for each
+    /// row of bind values, returning the statement, the returned columns
+    /// and the response.
+    async fn execute_returning_array(
+        &mut self,
+        sql: &str,
+        rows: &[Vec<OracleValue>],
+        returning: &[OracleType],
+    ) -> Result<(Statement, Vec<ColumnMetadata>, ExecuteResponse)> {
+        let stmt = self.prepare_statement(sql, rows)?;
+        ExecuteMessage::check_sql_length(stmt.sql())?;
+        if !stmt.is_returning() {
+            return Err(Error::protocol(
+                "statement has no RETURNING ... INTO clause",
+            ));
+        }
+        if stmt.num_return_binds() != returning.len() {
+            return Err(Error::protocol(format!(
+                "statement returns into {} binds, but {} types were given",
+                stmt.num_return_binds(),
+                returning.len()
+            )));
+        }
+        stmt.check_params(&rows[0])?;
+
+        let return_binds = returning
+            .iter()
+            .map(|ty| BindInfo::returning(ty, &self.caps))
+            .collect::<Result<Vec<_>>>()?;
+        let columns = stmt
+            .bind_info()
+            .iter()
+            .filter(|b| b.is_return)
+            .zip(return_binds.iter().zip(returning))
+            .map(|(b, (bind, ty))| bind.returned_column(&b.name, ty))
+            .collect::<Vec<_>>();
+        let binds = BindParams::new(rows, &self.caps)?.with_returning(&return_binds);
+        let started = Instant::now();
+        let result = self
+            .execute_returning_statement(&stmt, &binds, &columns)
+            .await;
+        self.log_statement(&stmt, rows, started.elapsed(), &result, |r| {
+            r.error_info.row_count
+        });
+        Ok((stmt, columns, result?))
+    }
+
+    /// Send a statement with a `RETURNING ... INTO` clause and check the
+    /// response.
+    async fn execute_returning_statement(
+        &mut self,
+        stmt: &Statement,
+        binds: &BindParams,
+        columns: &[ColumnMetadata],
+    ) -> Result<ExecuteResponse> {
+        let mut msg = ExecuteMessage::new_statement(stmt, 0, self.caps.ttc_field_version);
+        msg.binds = Some(binds);
+        msg.commit = self.autocommit;
+        self.stats.record_execute(msg.calc_options());
+
+        let (ttc, server_ttc) = (
+            self.caps.ttc_field_version,
+            self.caps.server_ttc_field_version,
+        );
+        let response = self
+            .send_message_and_read_response(&msg, |buf| {
+                parse_returning_execute_response(buf, ttc, server_ttc, columns)
+            })
+            .await?;
+        let info = &response.error_info;
+        self.queue_cursor_close(info.cursor_id as u32);
+        if info.error_num != 0 {
+            let context = ErrorContext::new(Phase::Execute, stmt.sql(), info.cursor_id as u32);
+            return Err(info.to_error().with_context(context));
+        }
+        Ok(response)
+    }
+
+    /// Execute a statement with array binds in a single round trip.
+    async fn execute_array(
+        &mut self,
+        sql: &str,
+        rows: &[Vec<OracleValue>],
+        batch_errors: bool,
+    ) -> Result<BatchResult> {
+        if rows.is_empty() {
+            return Ok(BatchResult::default());
+        }
+        let stmt = self.prepare_statement(sql, rows)?;
+        ExecuteMessage::check_sql_length(stmt.sql())?;
+        if stmt.is_query() {
+            return Err(Error::protocol(
+                "queries cannot be executed with multiple rows of binds",
+            ));
+        }
+        let binds = BindParams::new(rows, &self.caps)?;
+        let started = Instant::now();
+        let result = self
+            .execute_array_statement(&stmt, &binds, batch_errors)
+            .await;
+        self.log_statement(&stmt, rows, started.elapsed(), &result, |r| r.row_count);
+        result
+    }
+
+    /// Send a statement with array binds and check the response.
+    async fn execute_array_statement(
+        &mut self,
+        stmt: &Statement,
+        binds: &BindParams,
+        batch_errors: bool,
+    ) -> Result<BatchResult> {
+        let mut msg = ExecuteMessage::new_statement(stmt, 0, self.caps.ttc_field_version);
+        msg.binds = Some(binds);
+        msg.batch_errors = batch_errors;
+        msg.commit = self.autocommit;
+        self.stats.record_execute(msg.calc_options());
+
+        let (ttc, server_ttc) = (
+            self.caps.ttc_field_version,
+            self.caps.server_ttc_field_version,
+        );
+        let response = self
+            .send_message_and_read_response(&msg, |buf| {
+                parse_execute_response(buf, ttc, server_ttc)
+            })
+            .await?;
+        self.discard_implicit_results(response.implicit_results);
+        let info = response.error_info;
+        self.queue_cursor_close(info.cursor_id as u32);
+
+        // In batch error mode ORA-24381 only says that some rows failed
+        if info.error_num != 0 && !(batch_errors && info.error_num == TNS_ERR_ARRAY_DML_ERRORS) {
+            let context = ErrorContext::new(Phase::Execute, stmt.sql(), info.cursor_id as u32);
+            return Err(info.to_error().with_context(context));
+        }
+
+        Ok(BatchResult {
+            row_count: info.row_count,
+            errors: info.batch_errors,
+        })
+    }
+
+    /// Execute a query in a single round trip, prefetching up to `prefetch_rows` rows.
+    ///
+    /// The describe information is returned together with the first batch of
+    /// rows, so no separate define round trip is made. Columns whose values
+    /// would need one (see `ColumnMetadata::requires_define`) are returned as
+    /// the server sends them, and the server may hold their rows back until
+    /// the cursor is fetched from.
+    pub(crate) async fn execute_query(
+        &mut self,
+        sql: &str,
+        prefetch_rows: u32,
+    ) -> Result<ExecuteResponse> {
+        let options = QueryOptions::new().with_prefetch_rows(prefetch_rows);
+        let stmt = self.prepare_statement(sql, &[])?;
+        let mut exec_response = self.execute_statement(&stmt, &options, &[], true).await?;
+        self.discard_implicit_results(std::mem::take(&mut exec_response.implicit_results));
+        Ok(exec_response)
+    }
+
+    /// Execute a statement in a single round trip with the given fetch
+    /// options and a row of bind values (none for `&[]` or an empty row),
+    /// and report it to the query logger.
+    ///
+    /// With `cache`, the statement's cursor is kept in the statement cache,
+    /// and a cursor cached for the same SQL is re-executed.
+    async fn execute_statement(
+        &mut self,
+        stmt: &Statement,
+        options: &QueryOptions,
+        rows: &[Vec<OracleValue>],
+        cache: bool,
+    ) -> Result<ExecuteResponse> {
+        let binds = if rows.iter().all(Vec::is_empty) {
+            None
+        } else {
+            Some(BindParams::new(rows, &self.caps)?)
+        };
+        let started = Instant::now();
+        let result = if stmt.is_query() && !options.defines.is_empty() {
+            self.execute_defined(stmt, options, binds.as_ref()).await
+        } else {
+            self.execute_statement_with_retry(stmt, options, binds.as_ref(), cache)
+                .await
+        };
+        self.log_statement(stmt, rows, started.elapsed(), &result, |response| {
+            response_rows(stmt, response)
+        });
+        result
+    }
+
+    /// Execute a statement in a single round trip with the given fetch options.
+    ///
+    /// A statement failing because state it depended on was invalidated
+    /// (see `Error::is_stale_statement`) is parsed and executed once more;
+    /// the failed call did no work, and the new parse picks up the new state.
+    ///
+    /// With `cache`, a cursor cached for the statement's SQL is re-executed
+    /// (`TNS_FUNC_REEXECUTE`, or `TNS_FUNC_REEXECUTE_AND_FETCH` for
+    /// queries) if it was bound with compatible values, and the cursor is
+    /// cached once the call succeeds.
+    async fn execute_statement_with_retry(
+        &mut self,
+        stmt: &Statement,
+        options: &QueryOptions,
+        binds: Option<&BindParams>,
+        cache: bool,
+    ) -> Result<ExecuteResponse> {
+        let cache = cache && is_cacheable(stmt, options);
+        let mut cached = if cache {
+            self.take_cached_statement(stmt, options, binds)
+        } else {
+            None
+        };
+        let mut retried = false;
+        loop {
+            // Send the message and read the response, handling any control/marker packets
+            let (ttc, server_ttc) = (
+                self.caps.ttc_field_version,
+                self.caps.server_ttc_field_version,
+            );
+            let timeout = options.timeout.unwrap_or(self.call_timeout);
+            let exec_response = match &cached {
+                Some(entry) => {
+                    let mut msg =
+                        ReexecuteMessage::new(entry.cursor_id, stmt, options.prefetch_rows);
+                    msg.binds = binds;
+                    msg.commit = self.autocommit;
+                    self.stats.record_execute(msg.calc_options());
+                    let columns = &entry.columns;
+                    self.send_message_and_read_response_within(&msg, timeout, |buf| {
+                        parse_reexecute_response(buf, ttc, server_ttc, columns)
+                    })
+                    .await
+                }
+                None => {
+                    let msg = self.execute_message(stmt, options, binds)?;
+                    self.stats.record_execute(msg.calc_options());
+                    self.send_message_and_read_response_within(&msg, timeout, |buf| {
+                        parse_execute_response(buf, ttc, server_ttc)
+                    })
+                    .await
+                }
+            };
+            let exec_response = match exec_response {
+                Ok(response) => response,
+                Err(e) => {
+                    // The state of the cursor is unknown
+                    if let Some(entry) = cached {
+                        self.queue_cursor_close(entry.cursor_id);
+                    }
+                    return Err(e);
+                }
+            };
+
+            match check_execute_response(stmt.sql(), exec_response) {
+                Err(e) if e.is_stale_statement() && !retried => {
+                    // The failed cursor is not reused
+                    if let Some(context) = e.context() {
+                        self.queue_cursor_close(context.cursor_id);
+                    }
+                    if let Some(entry) = cached.take() {
+                        self.queue_cursor_close(entry.cursor_id);
+                    }
+                    retried = true;
+                }
+                Ok(response) => {
+                    if cache {
+                        self.cache_statement(stmt, options, binds, cached, &response);
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    // A cached cursor stays usable after the statement fails
+                    if let Some(entry) = cached {
+                        self.cache_entry(entry);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Execute a query whose columns are converted by the server (see
+    /// `QueryOptions::with_fetch_as`).
+    ///
+    /// The query is executed without fetching, which describes it; its
+    /// cursor is then defined with the conversions and executed again,
+    /// fetching the first rows in the defined types.
+    async fn execute_defined(
+        &mut self,
+        stmt: &Statement,
+        options: &QueryOptions,
+        binds: Option<&BindParams>,
+    ) -> Result<ExecuteResponse> {
+        let describe_options = QueryOptions {
+            prefetch_rows: 0,
+            defines: Vec::new(),
+            ..options.clone()
+        };
+        let described = self
+            .execute_statement_with_retry(stmt, &describe_options, binds, false)
+            .await?;
+        let cursor_id = described.error_info.cursor_id as u32;
+
+        let (defines, columns) = match define_columns(&described.columns, options, &self.caps) {
+            Ok(defined) => defined,
+            Err(e) => {
+                self.queue_cursor_close(cursor_id);
+                return Err(e);
+            }
+        };
+        let mut msg = ExecuteMessage::new_define(
+            stmt,
+            cursor_id,
+            &defines,
+            options.prefetch_rows,
+            self.caps.ttc_field_version,
+        );
+        msg.max_long_size = options.max_long_size;
+        self.stats.record_execute(msg.calc_options());
+
+        let (ttc, server_ttc) = (
+            self.caps.ttc_field_version,
+            self.caps.server_ttc_field_version,
+        );
+        let timeout = options.timeout.unwrap_or(self.call_timeout);
+        let response = self
+            .send_message_and_read_response_within(&msg, timeout, |buf| {
+                parse_defined_execute_response(buf, ttc, server_ttc, &columns)
+            })
+            .await?;
+
+        let info = &response.error_info;
+        if info.error_num != 0 && info.error_num != TNS_ERR_NO_DATA_FOUND {
+            self.queue_cursor_close(cursor_id);
+            let context = ErrorContext::new(Phase::Define, stmt.sql(), cursor_id);
+            return Err(info.to_error().with_context(context));
+        }
+        // The cursor is the one the query was executed with
+        let mut response = response;
+        response.error_info.cursor_id = described.error_info.cursor_id;
+        Ok(response)
+    }
+
+    /// Take the statement cached for the SQL of `stmt`, if its cursor can
+    /// be re-executed with `binds`; an incompatible cursor is closed.
+    fn take_cached_statement(
+        &mut self,
+        stmt: &Statement,
+        options: &QueryOptions,
+        binds: Option<&BindParams>,
+    ) -> Option<CachedStatement> {
+        let entry = self.statement_cache.take(stmt.sql())?;
+        let binds = binds.map(BindParams::binds).unwrap_or_default();
+        if entry.accepts(binds, options.max_long_size) {
+            Some(entry)
+        } else {
+            self.queue_cursor_close(entry.cursor_id);
+            None
+        }
+    }
+
+    /// Cache the cursor of a statement that was executed successfully,
+    /// either re-executed from `cached` or newly parsed.
+    fn cache_statement(
+        &mut self,
+        stmt: &Statement,
+        options: &QueryOptions,
+        binds: Option<&BindParams>,
+        cached: Option<CachedStatement>,
+        response: &ExecuteResponse,
+    ) {
+        let entry = cached.unwrap_or_else(|| CachedStatement {
+            sql: stmt.sql().to_string(),
+            cursor_id: response.error_info.cursor_id as u32,
+            columns: response.columns.clone(),
+            binds: binds.map(|b| b.binds().to_vec()).unwrap_or_default(),
+            max_long_size: options.max_long_size,
+        });
+        if entry.cursor_id != 0 {
+            self.cache_entry(entry);
+        }
+    }
+
+    /// Add a statement to the statement cache, closing the cursors it
+    /// pushes out.
+    fn cache_entry(&mut self, entry: CachedStatement) {
+        for cursor_id in self.statement_cache.insert(entry) {
+            self.queue_cursor_close(cursor_id);
+        }
+    }
+
+    /// Build the message executing a statement.
+    fn execute_message<'a>(
+        &self,
+        stmt: &'a Statement,
+        options: &QueryOptions,
+        binds: Option<&'a BindParams>,
+    ) -> Result<ExecuteMessage<'a>> {
+        ExecuteMessage::check_sql_length(stmt.sql())?;
+        let mut msg =
+            ExecuteMessage::new_statement(stmt, options.prefetch_rows, self.caps.ttc_field_version);
+        msg.max_long_size = options.max_long_size;
+        msg.binds = binds;
+        msg.commit = self.autocommit;
+        Ok(msg)
+    }
+
+    /// Run the operations of a pipeline, returning one result per operation.
+    ///
+    /// Servers that support pipelining (Oracle Database 23ai) get every
+    /// request in one round trip: the requests are written back to back
+    /// between a `BeginPipelineMessage` and an `EndPipelineMessage`, and
+    /// the responses to the requests and to the end message are then read
+    /// in order. Other servers run the operations one after another. Either
+    /// way, queries return their implicit result sets and leave their
+    /// cursors in the statement cache, as `query_with_options` does.
+    /// Oracle errors are returned per operation; errors that leave the
+    /// connection unusable end the whole pipeline.
+    pub(crate) async fn run_pipeline(
+        &mut self,
+        ops: &[PipelineOp],
+    ) -> Result<Vec<Result<PipelineResult>>> {
+        if !self.caps.features().supports_pipelining() {
+            let mut results = Vec::with_capacity(ops.len());
+            for op in ops {
+                let result = match op {
+                    PipelineOp::Query { sql, options } => self
+                        .query_with_options(sql, options)
+                        .await
+                        .map(PipelineResult::Query),
+                    PipelineOp::Execute { sql, params } => {
+                        self.execute(sql, params).await.map(PipelineResult::Execute)
+                    }
+                };
+                results.push(pipeline_op_result(result)?);
+            }
+            return Ok(results);
+        }
+
+        // Build every request up front; one that cannot be built fails alone
+        let prepared: Vec<Result<(Statement, Option<BindParams>)>> = ops
+            .iter()
+            .map(|op| {
+                let (stmt, binds) = match op {
+                    PipelineOp::Execute { params, .. } if !params.is_empty() => {
+                        let rows = std::slice::from_ref(params);
+                        let stmt = self.prepare_statement(op.sql(), rows)?;
+                        (stmt, Some(BindParams::new(rows, &self.caps)?))
+                    }
+                    _ => (self.prepare_statement(op.sql(), &[])?, None),
+                };
+                ExecuteMessage::check_sql_length(stmt.sql())?;
+                Ok((stmt, binds))
+            })
+            .collect();
+        let exec_options = QueryOptions::new().with_prefetch_rows(0);
+        let messages: Vec<Option<ExecuteMessage>> = ops
+            .iter()
+            .zip(&prepared)
+            .map(|(op, prepared)| {
+                let (stmt, binds) = prepared.as_ref().ok()?;
+                let options = match op {
+                    PipelineOp::Query { options, .. } => options,
+                    PipelineOp::Execute { .. } => &exec_options,
+                };
+                self.execute_message(stmt, options, binds.as_ref()).ok()
+            })
+            .collect();
+
+        self.recover_abandoned_call().await?;
+        self.stats.round_trips += 1;
+        for msg in messages.iter().flatten() {
+            self.stats.record_execute(msg.calc_options());
+        }
+        let (ttc, server_ttc) = (
+            self.caps.ttc_field_version,
+            self.caps.server_ttc_field_version,
+        );
+        let timeout = self.call_timeout;
+        let started = Instant::now();
+        let call = async {
+            self.call_state = CallState::Sending;
+            self.queue_request(&BeginPipelineMessage::new())?;
+            for msg in messages.iter().flatten() {
+                self.stream.queue_data_message(msg)?;
+            }
+            self.stream.queue_data_message(&EndPipelineMessage::new())?;
+            // Every request goes out in one write
+            self.stream.flush().await?;
+            self.call_state = CallState::Waiting;
+
+            let mut remaining = messages.iter().flatten().count() + 1;
+            let mut responses = Vec::with_capacity(messages.len());
+            for msg in &messages {
+                if msg.is_none() {
+                    responses.push(None);
+                    continue;
+                }
+                let first = self.read_data_response().await?;
+                remaining -= 1;
+                self.call_state = CallState::Receiving { remaining };
+                let response = self
+                    .read_response(first, |buf| parse_execute_response(buf, ttc, server_ttc))
+                    .await?;
+                self.call_state = CallState::Waiting;
+                responses.push(Some(response));
+            }
+            let first = self.read_data_response().await?;
+            self.call_state = CallState::Receiving { remaining: 0 };
+            let end = self
+                .read_response(first, |buf| {
+                    parse_fetch_response(buf, &[], None, server_ttc)
+                })
+                .await?;
+            self.call_state = CallState::Waiting;
+            if end.error_info.error_num != 0 {
+                return Err(end.error_info.to_error());
+            }
+            self.last_activity = Instant::now();
+            Ok(responses)
+        };
+        let responses = if timeout.is_zero() {
+            call.await
+        } else {
+            match clock::timeout(&TokioClock, timeout, call).await {
+                Some(responses) => responses,
+                None => Err(self.interrupt_timed_out_call(timeout).await),
+            }
+        };
+        self.call_state = CallState::Idle;
+        self.finish_round_trip(started);
+        let elapsed = started.elapsed();
+        let responses = responses.inspect_err(|e| self.broken |= e.is_connection_lost())?;
+        drop(messages);
+
+        let mut results = Vec::with_capacity(ops.len());
+        for ((op, prepared), response) in ops.iter().zip(prepared).zip(responses) {
+            let result = match prepared {
+                Ok((stmt, _)) => {
+                    self.pipeline_op_response(op, &stmt, response, elapsed)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            results.push(pipeline_op_result(result)?);
+        }
+        Ok(results)
+    }
+
+    /// Turn the response to one pipelined operation into its result.
+    async fn pipeline_op_response(
+        &mut self,
+        op: &PipelineOp,
+        stmt: &Statement,
+        response: Option<ExecuteResponse>,
+        elapsed: Duration,
+    ) -> Result<PipelineResult> {
+        let response =
+            response.ok_or_else(|| Error::protocol("no response for a pipeline operation"))?;
+        let response = check_execute_response(stmt.sql(), response);
+        let binds = match op {
+            PipelineOp::Execute { params, .. } => std::slice::from_ref(params),
+            PipelineOp::Query { .. } => &[],
+        };
+        self.log_statement(stmt, binds, elapsed, &response, |response| {
+            response_rows(stmt, response)
+        });
+        let mut response = response?;
+        match op {
+            PipelineOp::Query { options, .. } => {
+                if is_cacheable(stmt, options) {
+                    self.cache_statement(stmt, options, None, None, &response);
+                } else {
+                    self.queue_cursor_close(response.error_info.cursor_id as u32);
+                }
+                let implicit_results = std::mem::take(&mut response.implicit_results);
+                let implicit_results = self
+                    .fetch_implicit_results(stmt.sql(), implicit_results, options.fetch_size)
+                    .await?;
+                let mut result = query_result(stmt, response);
+                result.implicit_results = implicit_results;
+                Ok(PipelineResult::Query(result))
+            }
+            PipelineOp::Execute { .. } => {
+                Ok(PipelineResult::Execute(self.execute_result(stmt, response)))
+            }
+        }
+    }
+
+    /// Helper to read a DATA response, handling control and marker packets.
+    ///
+    /// When we receive a MARKER packet (typically BREAK/RESET from server due to an error),
+    /// we need to send a RESET marker back and wait for the server's RESET marker,
+    /// then read the actual error response.
+    async fn read_data_response(&mut self) -> Result<crate::protocol::packet::Packet> {
+        loop {
+            let packet = self.stream.read_packet().await?;
+
+            match packet.packet_type {
+                TNS_PACKET_TYPE_DATA => return Ok(packet),
+                TNS_PACKET_TYPE_MARKER => {
+                    // Server sent a MARKER packet (usually due to an error)
+                    // Send RESET marker back
+                    let msg = MarkerMessage::reset();
+                    self.stream
+                        .send_message(TNS_PACKET_TYPE_MARKER, &msg)
+                        .await?;
+
+                    // Read packets until we get a RESET marker back from server
+                    loop {
+                        let marker_packet = self.stream.read_packet().await?;
+                        if marker_packet.packet_type == TNS_PACKET_TYPE_MARKER {
+                            // Check if it's a RESET marker (payload[2] == 2)
+                            if marker_packet.payload.len() >= 3
+                                && marker_packet.payload[2] == TNS_MARKER_TYPE_RESET
+                            {
+                                break;
+                            }
+                            // Continue reading if it's not a RESET marker
+                            continue;
+                        } else if marker_packet.packet_type == TNS_PACKET_TYPE_DATA {
+                            // Got the error response
+                            return Ok(marker_packet);
+                        }
+                    }
+                    // Continue to read the actual DATA response with error info
+                    continue;
+                }
+                TNS_PACKET_TYPE_CONTROL => {
+                    self.handle_control(&packet.payload);
+                    continue;
+                }
+                _ => {
+                    return Err(Error::UnexpectedPacketType {
+                        expected: TNS_PACKET_TYPE_DATA,
+                        actual: packet.packet_type,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Act on a control packet received between DATA packets.
+    ///
+    /// The server turns OOB breaks off if it cannot receive them. Inband
+    /// notifications are kept for `take_notifications`; a session shutdown
+    /// leaves the connection unusable after the current call.
+    fn handle_control(&mut self, payload: &[u8]) {
+        match Control::parse(payload) {
+            Control::ResetOob => self.caps.supports_oob = false,
+            Control::Notification(notification) => {
+                self.broken |= notification == Notification::SessionShutdown;
+                self.notifications.push(notification);
+            }
+            Control::Ignored => {}
+        }
+    }
+
+    /// Take the inband notifications received since the last call.
+    ///
+    /// The server notifies sessions of events such as a planned shutdown
+    /// (`Notification::SessionShutdown`) or an upcoming outage
+    /// (`Notification::InbandMessage`) between the packets of a response.
+    /// Checking after calls lets an application drain work from the
+    /// connection before the server closes it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, Notification};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "password").await?;
+    ///     conn.query("SELECT * FROM users").await?;
+    ///     for notification in conn.take_notifications() {
+    ///         if notification == Notification::SessionShutdown {
+    ///             println!("server is shutting the session down, reconnecting");
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn take_notifications(&mut self) -> Vec<Notification> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    /// Get the warnings the server returned with the logon, such as
+    /// ORA-28002 (the password will expire within some days).
+    ///
+    /// The session is usable; the warnings ask for the password to be
+    /// changed before logons start failing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let conn = Connection::connect("localhost:1521/FREEPDB1", "user", "password").await?;
+    ///     for warning in conn.auth_warnings() {
+    ///         eprintln!("ORA-{:05}: {}", warning.code, warning.message);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn auth_warnings(&self) -> &[Warning] {
+        &self.session.warnings
+    }
+
+    /// Get the session parameter value.
+    pub fn session_param(&self, key: &str) -> Option<&str> {
+        self.session.params.get(key).map(|s| s.as_str())
+    }
+
+    /// Get the server version as (major, minor, update, release, patch).
+    ///
+    /// Returns `None` if the server reported no version in any known form.
+    pub fn server_version(&self) -> Option<(u8, u8, u8, u8, u8)> {
+        version::server_version(&self.session.params, self.caps.ttc_field_version)
+    }
+
+    /// Get the features of the server, such as native BOOLEAN or VECTOR
+    /// support, from the negotiated capabilities and the server version.
+    pub fn server_features(&self) -> ServerFeatures {
+        self.caps
+            .features()
+            .with_server_version(self.server_version())
+    }
+
+    /// Get the server version banner.
+    ///
+    /// This is the full banner when the server sends one (for example
+    /// "Oracle Database 19c Enterprise Edition Release 19.0.0.0.0 - Production"),
+    /// otherwise the dotted version followed by the server's status string.
+    pub fn server_banner(&self) -> Option<String> {
+        version::server_banner(&self.session.params, self.caps.ttc_field_version)
+    }
+
+    /// Get the internal packet stream (for advanced use).
+    #[allow(dead_code)]
+    pub(crate) fn _stream(&self) -> &PacketStream {
+        &self.stream
+    }
+
+    /// Get a mutable reference to the internal packet stream.
+    #[allow(dead_code)]
+    pub(crate) fn _stream_mut(&mut self) -> &mut PacketStream {
+        &mut self.stream
+    }
+
+    /// Get the capabilities.
+    #[allow(dead_code)]
+    pub(crate) fn _capabilities(&self) -> &Capabilities {
+        &self.caps
+    }
+
+    // --- Low-level packet I/O for Cursor use ---
+
+    /// Send a data message and parse the response with `parse`.
+    ///
+    /// Handles control/marker packets internally, and responses spanning
+    /// several packets (see `read_response`). `parse` is given the response
+    /// after its data flags. Cursors queued by `queue_cursor_close` are
+    /// closed with a piggyback ahead of the message. A failure that loses
+    /// the session marks the connection as broken.
+    /// This is used by RowCursor for fetch operations.
+    pub(crate) async fn send_message_and_read_response<M, T>(
+        &mut self,
+        message: &M,
+        parse: impl FnMut(&mut ReadBuffer) -> Result<T>,
+    ) -> Result<T>
+    where
+        M: DataMessage + Message,
+        T: EndOfCall,
+    {
+        let timeout = self.call_timeout;
+        self.send_message_and_read_response_within(message, timeout, parse)
+            .await
+    }
+
+    /// Send a data message and parse the response, like
+    /// `send_message_and_read_response`, with `timeout` in place of the
+    /// call timeout (zero for no limit).
+    pub(crate) async fn send_message_and_read_response_within<M, T>(
+        &mut self,
+        message: &M,
+        timeout: Duration,
+        parse: impl FnMut(&mut ReadBuffer) -> Result<T>,
+    ) -> Result<T>
+    where
+        M: DataMessage + Message,
+        T: EndOfCall,
+    {
+        self.recover_abandoned_call().await?;
+        self.stats.round_trips += 1;
+
+        let started = Instant::now();
+        let call = async {
+            let first = self.round_trip(message).await?;
+            self.call_state = CallState::Receiving { remaining: 0 };
+            self.read_response(first, parse).await
+        };
+        let response = if timeout.is_zero() {
+            call.await
+        } else {
+            match clock::timeout(&TokioClock, timeout, call).await {
+                Some(response) => response,
+                None => Err(self.interrupt_timed_out_call(timeout).await),
+            }
+        };
+        self.call_state = CallState::Idle;
+        self.finish_round_trip(started);
+
+        if let Err(e) = &response {
+            self.broken |= e.is_connection_lost();
+        }
+        response
+    }
+
+    /// Count a fetch requested by a cursor.
+    pub(crate) fn record_fetch(&mut self) {
+        self.stats.fetches += 1;
+    }
+
+    /// Add the time taken by a round trip to the statistics, and report it
+    /// to `tracing` subscribers.
+    fn finish_round_trip(&mut self, started: Instant) {
+        let elapsed = started.elapsed();
+        self.stats.wait_time += elapsed;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "oracle_thin_rs::stats",
+            round_trip = self.stats.round_trips,
+            elapsed_us = elapsed.as_micros() as u64,
+            bytes_sent = self.stream.bytes_sent(),
+            bytes_received = self.stream.bytes_received(),
+            parses = self.stats.parses,
+            executes = self.stats.executes,
+            fetches = self.stats.fetches,
+            "round trip completed"
+        );
+    }
+
+    /// Interrupt a call cut short by `timeout`, and return the timeout
+    /// error.
+    ///
+    /// The server gets as long again to acknowledge the BREAK; if it does
+    /// not, the connection is marked as broken.
+    async fn interrupt_timed_out_call(&mut self, timeout: Duration) -> Error {
+        let recovered = clock::timeout(&TokioClock, timeout, self.recover_abandoned_call()).await;
+        if !matches!(recovered, Some(Ok(()))) {
+            self.broken = true;
+        }
+        Error::CallTimeout { timeout }
+    }
+
+    /// Recover from a round trip abandoned before it completed, so the next
+    /// request starts on a clean stream.
+    ///
+    /// A connection that lost its session, or could not be recovered, stays
+    /// unusable: every later call fails with `Error::ConnectionClosed`.
+    async fn recover_abandoned_call(&mut self) -> Result<()> {
+        if self.broken {
+            return Err(Error::ConnectionClosed);
+        }
+        // Each step updates the state, so recovery cut short resumes from it
+        let result = loop {
+            match self.call_state {
+                CallState::Idle => return Ok(()),
+                CallState::Sending => {
+                    // Finish writing the request, then interrupt it
+                    if let Err(e) = self.stream.flush().await {
+                        break Err(e);
+                    }
+                    self.call_state = CallState::Waiting;
+                }
+                CallState::Waiting => {
+                    let result = self.break_call().await;
+                    self.call_state = CallState::Idle;
+                    break result;
+                }
+                CallState::Receiving { remaining } if self.caps.supports_end_of_response => {
+                    if let Err(e) = self.drain_response().await {
+                        break Err(e);
+                    }
+                    self.call_state = match remaining {
+                        0 => CallState::Idle,
+                        n => CallState::Receiving { remaining: n - 1 },
+                    };
+                }
+                CallState::Receiving { .. } => {
+                    self.desynchronized = true;
+                    break Err(Error::protocol(
+                        "connection unusable: a call was abandoned after part of its response was read",
+                    ));
+                }
+            }
+        };
+        if result.is_err() {
+            self.broken = true;
+        }
+        result
+    }
+
+    /// Read and discard the rest of a response, up to the packet flagged as
+    /// its end.
+    ///
+    /// Cursors the response opened are not known, so they stay open until
+    /// the session ends.
+    async fn drain_response(&mut self) -> Result<()> {
+        while !self.read_data_response().await?.has_end_of_response() {}
+        Ok(())
+    }
+
+    /// Interrupt the call in progress with a BREAK and read its response.
+    ///
+    /// The BREAK is sent out of band when the server accepted OOB breaks,
+    /// falling back to an in-band BREAK marker if the urgent byte cannot be
+    /// sent. The server answers with the marker exchange handled by
+    /// `read_data_response`, then ends the call with ORA-01013. A call that
+    /// completed before the BREAK arrived returns its own response instead.
+    async fn break_call(&mut self) -> Result<()> {
+        let sent_oob = self.caps.supports_oob && self.stream.send_oob_break().is_ok();
+        if !sent_oob {
+            self.stream
+                .send_message(TNS_PACKET_TYPE_MARKER, &MarkerMessage::brk())
+                .await?;
+        }
+        let (ttc, server_ttc) = (
             self.caps.ttc_field_version,
             self.caps.server_ttc_field_version,
-        )?;
+        );
+        let first = self.read_data_response().await?;
+        let response = self
+            .read_response(first, |buf| parse_execute_response(buf, ttc, server_ttc))
+            .await?;
+        // Cursors opened by the abandoned call are not needed
+        self.queue_cursor_close(response.error_info.cursor_id as u32);
+        self.discard_implicit_results(response.implicit_results);
+        Ok(())
+    }
+
+    /// Parse a response that may span several DATA packets.
+    ///
+    /// Servers that flag the last packet of a response have all of its
+    /// packets read before parsing. Older servers give no such flag, so the
+    /// response is parsed whenever no further packet has arrived yet, until
+    /// it no longer runs out before its end-of-call status. A parse that ran
+    /// out in the middle of a value (such as a long value sent in chunks
+    /// over many packets) is only retried once the rest of it can have
+    /// arrived. A response growing past the stream's maximum response size
+    /// is abandoned. The packets of a response are joined in `response_buf`.
+    async fn read_response<T: EndOfCall>(
+        &mut self,
+        first: Packet,
+        mut parse: impl FnMut(&mut ReadBuffer) -> Result<T>,
+    ) -> Result<T> {
+        let max_size = self.stream.max_response_size();
+        let mut end_of_response = first.has_end_of_response();
+        let mut payload = first.payload;
+        // Payload length the last parse needs before it can get further
+        let mut wanted = 0;
+
+        loop {
+            if end_of_response || !self.caps.supports_end_of_response {
+                let mut buf = ReadBuffer::new(payload.clone());
+                buf.skip(2)?; // data flags
+                match parse(&mut buf) {
+                    Err(Error::BufferTooSmall { needed, available }) if !end_of_response => {
+                        wanted = payload.len() + needed.saturating_sub(available);
+                    }
+                    Ok(response) => {
+                        self.record_end_of_call(response.error_info());
+                        return Ok(response);
+                    }
+                    Err(e) => {
+                        // Unless the whole response was read, the rest of it
+                        // would be taken for the reply to the next call
+                        if !end_of_response && !matches!(e, Error::Oracle { .. }) {
+                            self.desynchronized = true;
+                            self.broken = true;
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+
+            // Wait for the next packet, then take any others already received.
+            // A payload joined on an earlier pass is extended in place once
+            // the parser has let go of it; the first packet's payload, which
+            // shares the stream's read buffer, is copied. Anything left from
+            // an abandoned response is dropped first
+            self.response_buf.clear();
+            match payload.try_into_mut() {
+                Ok(data) => self.response_buf.unsplit(data),
+                Err(payload) => self.response_buf.extend_from_slice(&payload),
+            }
+            let mut next = Some(self.read_data_response().await?);
+            while let Some(packet) = next {
+                end_of_response |= packet.has_end_of_response();
+                // Every packet repeats the data flags
+                self.response_buf
+                    .extend_from_slice(packet.payload.get(2..).unwrap_or_default());
+                if self.response_buf.len() > max_size {
+                    let size = self.response_buf.len();
+                    // Such a buffer is not worth keeping for later responses
+                    self.response_buf = BytesMut::new();
+                    return Err(Error::ResponseTooLarge {
+                        size,
+                        max: max_size,
+                    });
+                }
+                next = if end_of_response {
+                    None
+                } else if self.response_buf.len() < wanted {
+                    Some(self.read_data_response().await?)
+                } else {
+                    self.try_read_data_packet()?
+                };
+            }
+            payload = self.response_buf.split().freeze();
+        }
+    }
+
+    /// Keep the session state the server reported with a call.
+    fn record_end_of_call(&mut self, info: &ErrorInfo) {
+        if let Some(call_status) = info.call_status {
+            self.transaction_in_progress = call_status & TNS_EOCS_FLAGS_TXN_IN_PROGRESS != 0;
+        }
+        if let Some(ltxid) = &info.ltxid {
+            self.ltxid = Some(ltxid.clone());
+        }
+        if let Some(replay_context) = &info.replay_context {
+            self.replay_context = Some(replay_context.clone());
+        }
+    }
+
+    /// Take a DATA packet that has already been received, without waiting.
+    fn try_read_data_packet(&mut self) -> Result<Option<Packet>> {
+        while let Some(packet) = self.stream.try_read_packet()? {
+            match packet.packet_type {
+                TNS_PACKET_TYPE_DATA => return Ok(Some(packet)),
+                TNS_PACKET_TYPE_CONTROL => self.handle_control(&packet.payload),
+                actual => {
+                    return Err(Error::UnexpectedPacketType {
+                        expected: TNS_PACKET_TYPE_DATA,
+                        actual,
+                    })
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    async fn round_trip<M>(&mut self, message: &M) -> Result<Packet>
+    where
+        M: DataMessage + Message,
+    {
+        self.call_state = CallState::Sending;
+        self.queue_request(message)?;
+        self.stream.flush().await?;
+        self.call_state = CallState::Waiting;
+        let packet = self.read_data_response().await?;
+        self.last_activity = Instant::now();
+        Ok(packet)
+    }
+
+    /// Queue a request, with any queued piggybacks ahead of it, to be
+    /// written by the next flush of the stream.
+    fn queue_request<M>(&mut self, message: &M) -> Result<()>
+    where
+        M: DataMessage + Message,
+    {
+        let mut piggybacks: Vec<Box<dyn Message + Send + Sync>> = Vec::new();
+        if !self.cursors_to_close.is_empty() {
+            let cursor_ids = std::mem::take(&mut self.cursors_to_close);
+            piggybacks.push(Box::new(CloseCursorsMessage::new(cursor_ids)));
+        }
+        if !self.temp_lobs_to_free.is_empty() {
+            let locators = std::mem::take(&mut self.temp_lobs_to_free);
+            piggybacks.push(Box::new(FreeTempLobsMessage::new(locators)));
+        }
+        if !self.end_to_end.is_empty() {
+            piggybacks.push(Box::new(std::mem::take(&mut self.end_to_end)));
+        }
+
+        if piggybacks.is_empty() {
+            self.stream.queue_data_message(message)
+        } else {
+            let msg = Piggybacked {
+                piggyback: piggybacks.as_slice(),
+                message,
+            };
+            self.stream.queue_data_message(&msg)
+        }
+    }
+
+    /// Queue a server cursor to be closed on the next round trip.
+    ///
+    /// Used when a cursor is dropped, where no I/O can be performed.
+    /// Cursors of cached statements are left open.
+    pub(crate) fn queue_cursor_close(&mut self, cursor_id: u32) {
+        if cursor_id != 0
+            && !self.cursors_to_close.contains(&cursor_id)
+            && !self.statement_cache.contains_cursor(cursor_id)
+        {
+            self.cursors_to_close.push(cursor_id);
+        }
+    }
+
+    /// Check if a round trip failed because the session was lost.
+    pub(crate) fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Record a cursor dropped while still open on the server, and queue it
+    /// to be closed on the next round trip.
+    ///
+    /// In debug builds with the `tracing` feature, this is reported as a
+    /// `WARN` event with target `oracle_thin_rs::cursor`.
+    pub(crate) fn record_leaked_cursor(&mut self, cursor_id: u32, sql: &str) {
+        self.stats.cursors_leaked += 1;
+        #[cfg(all(feature = "tracing", debug_assertions))]
+        tracing::warn!(
+            target: "oracle_thin_rs::cursor",
+            cursor_id,
+            sql,
+            pending_closes = self.cursors_to_close.len() + 1,
+            "cursor dropped without close()"
+        );
+        #[cfg(not(all(feature = "tracing", debug_assertions)))]
+        let _ = sql;
+        self.queue_cursor_close(cursor_id);
+    }
+
+    /// Get the number of cursors waiting to be closed on the next round trip.
+    pub fn pending_cursor_closes(&self) -> usize {
+        self.cursors_to_close.len()
+    }
+
+    /// Close the cursors waiting to be closed now, instead of with the next
+    /// round trip.
+    ///
+    /// Queued closes are sent with the next call anyway; this is for
+    /// long-lived connections that may sit idle while holding many of them
+    /// (each counts towards the server's `OPEN_CURSORS` limit until closed).
+    /// Does nothing if none are waiting.
+    pub async fn close_pending_cursors(&mut self) -> Result<()> {
+        if self.cursors_to_close.is_empty() {
+            return Ok(());
+        }
+        // Piggybacks need a carrier call; a ping sends the closes right away
+        self.ping().await
+    }
+
+    // --- LOB operations ---
+
+    /// Create a temporary CLOB.
+    ///
+    /// The LOB lives until it is passed to `free_temp_lob` or the session
+    /// ends.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::Connection;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect(
+    ///         "localhost:1521/FREEPDB1",
+    ///         "user",
+    ///         "password"
+    ///     ).await?;
+    ///
+    ///     let mut lob = conn.create_temp_clob().await?;
+    ///     conn.append_clob(&mut lob, "first part, ").await?;
+    ///     conn.append_clob(&mut lob, "second part").await?;
+    ///     assert_eq!(conn.lob_length(&mut lob).await?, 23);
+    ///     conn.free_temp_lob(lob);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_temp_clob(&mut self) -> Result<Lob> {
+        self.create_temp_lob(OracleType::Clob).await
+    }
+
+    /// Create a temporary BLOB.
+    ///
+    /// The LOB lives until it is passed to `free_temp_lob` or the session
+    /// ends.
+    pub async fn create_temp_blob(&mut self) -> Result<Lob> {
+        self.create_temp_lob(OracleType::Blob).await
+    }
 
-        // Check for Oracle errors
-        if exec_response.error_info.error_num != 0 && exec_response.error_info.error_num != 1403 {
-            return Err(Error::Oracle {
-                code: exec_response.error_info.error_num,
-                message: exec_response.error_info.message.unwrap_or_default(),
-            });
+    async fn create_temp_lob(&mut self, oracle_type: OracleType) -> Result<Lob> {
+        let mut lob = Lob::new_temporary(oracle_type);
+        let csfrm = match lob.oracle_type() {
+            OracleType::Blob => 0,
+            OracleType::Nclob => CS_FORM_NCHAR,
+            _ => CS_FORM_IMPLICIT,
+        };
+        let type_num = lob.oracle_type().type_num() as u16;
+        let msg = LobOpMessage::create_temp(lob.locator(), csfrm, type_num);
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(&mut lob, &reply);
+        Ok(lob)
+    }
+
+    /// Free a temporary LOB.
+    ///
+    /// No I/O is performed: the LOB is freed with the next round trip.
+    /// Persistent LOBs are ignored.
+    pub fn free_temp_lob(&mut self, lob: Lob) {
+        if lob.is_temporary() {
+            self.temp_lobs_to_free.push(lob.locator().to_vec());
         }
+    }
 
-        Ok(RowCursor::new(
-            self,
-            exec_response.columns,
-            exec_response.error_info.cursor_id as u32,
-            exec_response.rows,
-            exec_response.more_rows,
-            fetch_size,
-            self.caps.server_ttc_field_version,
-        ))
+    /// Get the number of temporary LOBs waiting to be freed on the next round trip.
+    pub fn pending_temp_lob_frees(&self) -> usize {
+        self.temp_lobs_to_free.len()
     }
 
-    /// Helper to read a DATA response, handling control and marker packets.
-    ///
-    /// When we receive a MARKER packet (typically BREAK/RESET from server due to an error),
-    /// we need to send a RESET marker back and wait for the server's RESET marker,
-    /// then read the actual error response.
-    async fn read_data_response(&mut self) -> Result<crate::protocol::packet::Packet> {
-        loop {
-            let packet = self.stream.read_packet().await?;
+    /// Get the current length of a LOB in characters (CLOB) or bytes (BLOB, BFILE).
+    pub async fn lob_length(&mut self, lob: &mut Lob) -> Result<u64> {
+        let msg = LobOpMessage::get_length(lob.locator());
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        let length = reply.amount.unwrap_or(0);
+        lob.set_size(length);
+        Ok(length)
+    }
 
-            match packet.packet_type {
-                TNS_PACKET_TYPE_DATA => return Ok(packet),
-                TNS_PACKET_TYPE_MARKER => {
-                    // Server sent a MARKER packet (usually due to an error)
-                    // Send RESET marker back
-                    let msg = MarkerMessage::reset();
-                    self.stream
-                        .send_message(TNS_PACKET_TYPE_MARKER, &msg)
-                        .await?;
+    /// Read up to `amount` bytes of a BLOB, starting at the 1-based `offset`.
+    pub async fn read_blob(&mut self, lob: &mut Lob, offset: u64, amount: u64) -> Result<Vec<u8>> {
+        self.read_lob(lob, offset, amount).await
+    }
 
-                    // Read packets until we get a RESET marker back from server
-                    loop {
-                        let marker_packet = self.stream.read_packet().await?;
-                        if marker_packet.packet_type == TNS_PACKET_TYPE_MARKER {
-                            // Check if it's a RESET marker (payload[2] == 2)
-                            if marker_packet.payload.len() >= 3
-                                && marker_packet.payload[2] == TNS_MARKER_TYPE_RESET
-                            {
-                                break;
-                            }
-                            // Continue reading if it's not a RESET marker
-                            continue;
-                        } else if marker_packet.packet_type == TNS_PACKET_TYPE_DATA {
-                            // Got the error response
-                            return Ok(marker_packet);
-                        }
-                    }
-                    // Continue to read the actual DATA response with error info
-                    continue;
-                }
-                TNS_PACKET_TYPE_CONTROL => {
-                    // Handle CONTROL packet - just continue
-                    continue;
-                }
-                _ => {
-                    return Err(Error::UnexpectedPacketType {
-                        expected: TNS_PACKET_TYPE_DATA,
-                        actual: packet.packet_type,
-                    });
-                }
+    /// Read up to `amount` characters of a CLOB, starting at the 1-based `offset`.
+    pub async fn read_clob(&mut self, lob: &mut Lob, offset: u64, amount: u64) -> Result<String> {
+        let data = self.read_lob(lob, offset, amount).await?;
+        lob.decode_text(&data)
+    }
+
+    /// Read a LOB in pieces of up to `LOB_READ_SIZE` bytes, each piece in
+    /// one round trip.
+    async fn read_lob(&mut self, lob: &mut Lob, offset: u64, amount: u64) -> Result<Vec<u8>> {
+        let budget =
+            LOB_READ_SIZE.min(self.max_response_size().saturating_sub(LOB_OP_OVERHEAD) as u64);
+        let piece = (budget / lob.max_bytes_per_char()).max(1);
+        let mut data = Vec::new();
+        let mut offset = offset;
+        let mut remaining = amount;
+        while remaining > 0 {
+            let msg = LobOpMessage::read(lob.locator(), offset, remaining.min(piece));
+            let reply = self.lob_op(&msg).await?;
+            apply_locator(lob, &reply);
+            let read = reply.amount.unwrap_or(0);
+            match reply.data {
+                Some(bytes) if read > 0 => data.extend_from_slice(&bytes),
+                _ => break,
             }
+            offset += read;
+            remaining = remaining.saturating_sub(read);
         }
+        Ok(data)
     }
 
-    /// Get the session parameter value.
-    pub fn session_param(&self, key: &str) -> Option<&str> {
-        self.session.params.get(key).map(|s| s.as_str())
+    /// Write bytes to a BLOB, starting at the 1-based `offset`.
+    ///
+    /// Data larger than a packet is written in several round trips.
+    pub async fn write_blob(&mut self, lob: &mut Lob, offset: u64, data: &[u8]) -> Result<()> {
+        let piece = self.lob_piece_size(lob).max(1);
+        let mut offset = offset;
+        for chunk in data.chunks(piece) {
+            self.write_lob_piece(lob, offset, chunk, chunk.len() as u64)
+                .await?;
+            offset += chunk.len() as u64;
+        }
+        Ok(())
     }
 
-    /// Get the server version from session data.
-    pub fn server_version(&self) -> Option<(u8, u8, u8, u8, u8)> {
-        let version_str = self.session.params.get("AUTH_VERSION_NO")?;
-        let version: u32 = version_str.parse().ok()?;
-
-        // Parse version based on TTC field version
-        if self.caps.ttc_field_version >= 11 {
-            // 18.1+ format
-            Some((
-                ((version >> 24) & 0xFF) as u8,
-                ((version >> 16) & 0xFF) as u8,
-                ((version >> 12) & 0x0F) as u8,
-                ((version >> 4) & 0xFF) as u8,
-                (version & 0x0F) as u8,
-            ))
+    /// Write text to a CLOB, starting at the 1-based character `offset`.
+    ///
+    /// Text larger than a packet is written in several round trips, split
+    /// on character boundaries.
+    pub async fn write_clob(&mut self, lob: &mut Lob, offset: u64, text: &str) -> Result<()> {
+        // UTF-16 needs at most twice the UTF-8 length
+        let budget = if lob.uses_utf16() {
+            self.lob_piece_size(lob) / 2
         } else {
-            // Legacy format
-            Some((
-                ((version >> 24) & 0xFF) as u8,
-                ((version >> 20) & 0x0F) as u8,
-                ((version >> 12) & 0x0F) as u8,
-                ((version >> 8) & 0x0F) as u8,
-                (version & 0x0F) as u8,
-            ))
+            self.lob_piece_size(lob)
+        };
+        let mut offset = offset;
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut end = budget.min(rest.len());
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            if end == 0 {
+                end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+            }
+            let (piece, tail) = rest.split_at(end);
+            let (bytes, length) = lob.encode_text(piece);
+            self.write_lob_piece(lob, offset, &bytes, length).await?;
+            offset += length;
+            rest = tail;
         }
+        Ok(())
     }
 
-    /// Get the internal packet stream (for advanced use).
-    #[allow(dead_code)]
-    pub(crate) fn _stream(&self) -> &PacketStream {
-        &self.stream
+    /// Append bytes to the end of a BLOB.
+    ///
+    /// The end is taken from `Lob::size`, which tracks writes made through
+    /// this locator.
+    pub async fn append_blob(&mut self, lob: &mut Lob, data: &[u8]) -> Result<()> {
+        self.write_blob(lob, lob.size() + 1, data).await
     }
 
-    /// Get a mutable reference to the internal packet stream.
-    #[allow(dead_code)]
-    pub(crate) fn _stream_mut(&mut self) -> &mut PacketStream {
-        &mut self.stream
+    /// Append text to the end of a CLOB.
+    ///
+    /// The end is taken from `Lob::size`, which tracks writes made through
+    /// this locator.
+    pub async fn append_clob(&mut self, lob: &mut Lob, text: &str) -> Result<()> {
+        self.write_clob(lob, lob.size() + 1, text).await
     }
 
-    /// Get the capabilities.
-    #[allow(dead_code)]
-    pub(crate) fn _capabilities(&self) -> &Capabilities {
-        &self.caps
+    /// Trim a LOB to `new_size` characters (CLOB) or bytes (BLOB).
+    pub async fn trim_lob(&mut self, lob: &mut Lob, new_size: u64) -> Result<()> {
+        let msg = LobOpMessage::trim(lob.locator(), new_size);
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        lob.set_size(new_size);
+        Ok(())
     }
 
-    // --- Low-level packet I/O for Cursor use ---
+    // --- BFILE operations ---
 
-    /// Send a data message and read the response packet.
+    /// Check if the file a BFILE locator points to exists on the server.
     ///
-    /// Handles control/marker packets internally.
-    /// This is used by RowCursor for fetch operations.
-    pub(crate) async fn send_message_and_read_response<M>(&mut self, message: &M) -> Result<Packet>
-    where
-        M: DataMessage + Message,
-    {
-        self.stream.send_data_message(message).await?;
-        self.read_data_response().await
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{Connection, Lob};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "pass").await?;
+    ///     let row = conn
+    ///         .query_one("SELECT BFILENAME('DATA_DIR', 'report.bin') FROM DUAL")
+    ///         .await?
+    ///         .unwrap();
+    ///     let mut bfile = row.get_as::<Lob>(0)?;
+    ///     if conn.bfile_exists(&mut bfile).await? {
+    ///         conn.open_bfile(&mut bfile).await?;
+    ///         let length = conn.lob_length(&mut bfile).await?;
+    ///         let data = conn.read_bfile(&mut bfile, 1, length).await?;
+    ///         conn.close_bfile(&mut bfile).await?;
+    ///         println!("read {} bytes", data.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bfile_exists(&mut self, lob: &mut Lob) -> Result<bool> {
+        check_bfile(lob)?;
+        let msg = LobOpMessage::file_exists(lob.locator());
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        Ok(reply.flag)
+    }
+
+    /// Open a BFILE for reading.
+    ///
+    /// A BFILE must be open before it is read, and should be closed with
+    /// `close_bfile` when done; the server limits the number of open files
+    /// per session (`SESSION_MAX_OPEN_FILES`).
+    pub async fn open_bfile(&mut self, lob: &mut Lob) -> Result<()> {
+        check_bfile(lob)?;
+        let msg = LobOpMessage::file_open(lob.locator());
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        Ok(())
+    }
+
+    /// Check if a BFILE is open.
+    pub async fn is_bfile_open(&mut self, lob: &mut Lob) -> Result<bool> {
+        check_bfile(lob)?;
+        let msg = LobOpMessage::file_is_open(lob.locator());
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        Ok(reply.flag)
+    }
+
+    /// Close a BFILE opened with `open_bfile`.
+    pub async fn close_bfile(&mut self, lob: &mut Lob) -> Result<()> {
+        check_bfile(lob)?;
+        let msg = LobOpMessage::file_close(lob.locator());
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        Ok(())
+    }
+
+    /// Read up to `amount` bytes of an open BFILE, starting at the 1-based `offset`.
+    pub async fn read_bfile(&mut self, lob: &mut Lob, offset: u64, amount: u64) -> Result<Vec<u8>> {
+        check_bfile(lob)?;
+        self.read_lob(lob, offset, amount).await
+    }
+
+    async fn write_lob_piece(
+        &mut self,
+        lob: &mut Lob,
+        offset: u64,
+        data: &[u8],
+        length: u64,
+    ) -> Result<()> {
+        let msg = LobOpMessage::write(lob.locator(), offset, data);
+        let reply = self.lob_op(&msg).await?;
+        apply_locator(lob, &reply);
+        lob.set_size(lob.size().max(offset - 1 + length));
+        Ok(())
+    }
+
+    /// Largest LOB data piece that keeps a LOB message within one packet.
+    fn lob_piece_size(&self, lob: &Lob) -> usize {
+        (self.caps.sdu as usize)
+            .saturating_sub(LOB_OP_OVERHEAD + lob.locator().len())
+            .min(u16::MAX as usize)
+    }
+
+    /// Send a LOB operation and parse its reply.
+    async fn lob_op(&mut self, msg: &LobOpMessage<'_>) -> Result<LobOpResponse> {
+        let server_ttc = self.caps.server_ttc_field_version;
+        let reply = self
+            .send_message_and_read_response(msg, |buf| parse_lob_op_response(buf, msg, server_ttc))
+            .await?;
+        // Reading past the end reports no data found
+        let error_num = reply.error_info.error_num;
+        if error_num != 0 && error_num != TNS_ERR_NO_DATA_FOUND {
+            return Err(reply.error_info.to_error());
+        }
+        Ok(reply)
+    }
+}
+
+/// Build a `QueryResult` from an execute response, without its implicit
+/// result sets.
+/// Get the rows a query fetched with its execution, or the rows affected
+/// by other statements.
+fn response_rows(stmt: &Statement, response: &ExecuteResponse) -> u64 {
+    if stmt.is_query() {
+        response.rows.len() as u64
+    } else {
+        response.error_info.row_count
+    }
+}
+
+fn query_result(stmt: &Statement, response: ExecuteResponse) -> QueryResult {
+    QueryResult {
+        columns: response.columns,
+        rows: response.rows,
+        row_count: response.error_info.row_count,
+        more_rows: response.more_rows,
+        last_rowid: response.error_info.rowid,
+        statement_type: stmt.statement_type(),
+        implicit_results: Vec::new(),
+        warnings: response.error_info.warnings,
+    }
+}
+
+/// Get the defines fetching `columns` with the conversions of `options`,
+/// and the columns the rows are then decoded with.
+fn define_columns(
+    columns: &[ColumnMetadata],
+    options: &QueryOptions,
+    caps: &Capabilities,
+) -> Result<(Vec<BindInfo>, Vec<ColumnMetadata>)> {
+    if let Some(&(index, _)) = options.defines.iter().find(|(i, _)| *i >= columns.len()) {
+        return Err(Error::ColumnIndexOutOfBounds {
+            index,
+            count: columns.len(),
+        });
+    }
+    let mut defines = Vec::with_capacity(columns.len());
+    let mut defined = Vec::with_capacity(columns.len());
+    for (index, column) in columns.iter().enumerate() {
+        let define = match options.defines.iter().find(|(i, _)| *i == index) {
+            Some(&(_, fetch_as)) => BindInfo::define(fetch_as.ora_type_num(), caps),
+            None => BindInfo::define_column(column)?,
+        };
+        defined.push(if define.ora_type_num == column.oracle_type as u16 {
+            column.clone()
+        } else {
+            define.defined_column(column)
+        });
+        defines.push(define);
+    }
+    Ok((defines, defined))
+}
+
+/// Check if a statement's cursor can be cached for re-execution.
+///
+/// DDL is run when parsed, so it is always sent in full. Queries executed
+/// without fetching any rows are not worth keeping open.
+fn is_cacheable(stmt: &Statement, options: &QueryOptions) -> bool {
+    !stmt.is_ddl() && (!stmt.is_query() || options.prefetch_rows > 0)
+}
+
+/// Return the Oracle error reported by an execute response, if any.
+///
+/// ORA-01403 (no data found) only ends a query's rows.
+fn check_execute_response(sql: &str, response: ExecuteResponse) -> Result<ExecuteResponse> {
+    let info = &response.error_info;
+    if info.error_num != 0 && info.error_num != TNS_ERR_NO_DATA_FOUND {
+        let context = ErrorContext::new(Phase::Execute, sql, info.cursor_id as u32);
+        return Err(info.to_error().with_context(context));
+    }
+    Ok(response)
+}
+
+/// Keep the error of a pipeline operation as its result, unless the error
+/// leaves the connection unusable and so ends the pipeline.
+fn pipeline_op_result<T>(result: Result<T>) -> Result<Result<T>> {
+    match result {
+        Err(e) if e.is_connection_lost() || matches!(e, Error::CallTimeout { .. }) => Err(e),
+        result => Ok(result),
+    }
+}
+
+/// Add a clause returning `id_column` to an INSERT statement.
+fn returning_id_sql(sql: &str, id_column: &str) -> Result<String> {
+    let stmt = Statement::parse(sql);
+    if !stmt.is_insert() || stmt.is_returning() {
+        return Err(Error::protocol(
+            "insert_returning_id expects an INSERT without a RETURNING clause",
+        ));
+    }
+    Ok(format!(
+        "{} RETURNING {} INTO :oracle_thin_rs_id",
+        sql.trim_end().trim_end_matches(';'),
+        quote_identifier(id_column)
+    ))
+}
+
+/// Reject file operations on locators that are not BFILEs.
+fn check_bfile(lob: &Lob) -> Result<()> {
+    if lob.is_bfile() {
+        Ok(())
+    } else {
+        Err(Error::type_conversion(format!(
+            "expected a BFILE locator, got {}",
+            lob.oracle_type()
+        )))
+    }
+}
+
+/// Adopt the locator returned by a LOB operation.
+fn apply_locator(lob: &mut Lob, reply: &LobOpResponse) {
+    if let Some(locator) = &reply.locator {
+        lob.set_locator(locator);
     }
 }
 
@@ -524,6 +3175,81 @@ impl Connection {
 mod tests {
     use super::*;
     use std::time::Duration;
+    use tokio::net::TcpStream;
+
+    #[test]
+    fn test_returning_id_sql() {
+        assert_eq!(
+            returning_id_sql("INSERT INTO t (name) VALUES (:1);\n", "id").unwrap(),
+            "INSERT INTO t (name) VALUES (:1) RETURNING id INTO :oracle_thin_rs_id"
+        );
+        assert_eq!(
+            returning_id_sql("insert into t values (:1)", "Order Id").unwrap(),
+            "insert into t values (:1) RETURNING \"Order Id\" INTO :oracle_thin_rs_id"
+        );
+        assert!(returning_id_sql("SELECT id FROM t", "id").is_err());
+        assert!(returning_id_sql("UPDATE t SET name = :1", "id").is_err());
+        assert!(returning_id_sql("INSERT INTO t VALUES (1) RETURNING id INTO :x", "id").is_err());
+    }
+
+    #[test]
+    fn test_check_bfile() {
+        assert!(check_bfile(&Lob::new(OracleType::Bfile, vec![], 0, 0)).is_ok());
+        assert!(check_bfile(&Lob::new(OracleType::Blob, vec![], 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_define_columns() {
+        use crate::options::FetchAs;
+
+        let caps = Capabilities::new();
+        let mut amount = ColumnMetadata::new("AMOUNT".to_string(), ORA_TYPE_NUM_NUMBER as u8);
+        amount.precision = 10;
+        amount.scale = 2;
+        let columns = [
+            amount,
+            ColumnMetadata::new("NOTES".to_string(), ORA_TYPE_NUM_CLOB as u8),
+            ColumnMetadata::new("R".to_string(), ORA_TYPE_NUM_ROWID as u8),
+        ];
+        let options = QueryOptions::new()
+            .with_fetch_as(0, FetchAs::String)
+            .with_fetch_as(1, FetchAs::Long);
+
+        let (defines, defined) = define_columns(&columns, &options, &caps).unwrap();
+        let types: Vec<u16> = defines.iter().map(|d| d.ora_type_num).collect();
+        assert_eq!(
+            types,
+            [
+                ORA_TYPE_NUM_VARCHAR,
+                ORA_TYPE_NUM_LONG,
+                ORA_TYPE_NUM_VARCHAR
+            ]
+        );
+        assert_eq!(defines[0].buffer_size, caps.max_string_size);
+        assert_eq!(defines[2].buffer_size, TNS_MAX_UROWID_LENGTH);
+        // Rows are decoded as the converted types, under the same names
+        assert_eq!(defined[0].name, "AMOUNT");
+        assert_eq!(
+            defined[0].data_type().unwrap(),
+            OracleType::Varchar2 {
+                max_size: caps.max_string_size
+            }
+        );
+        assert_eq!(defined[1].oracle_type as u16, ORA_TYPE_NUM_LONG);
+        assert_eq!(defined[2].oracle_type as u16, ORA_TYPE_NUM_VARCHAR);
+
+        // Columns past the last, and LOBs left as locators, are rejected
+        let options = QueryOptions::new().with_fetch_as(3, FetchAs::String);
+        assert!(matches!(
+            define_columns(&columns, &options, &caps),
+            Err(Error::ColumnIndexOutOfBounds { index: 3, count: 3 })
+        ));
+        let options = QueryOptions::new().with_fetch_as(0, FetchAs::String);
+        assert!(matches!(
+            define_columns(&columns, &options, &caps),
+            Err(Error::TypeConversion { .. })
+        ));
+    }
 
     #[test]
     fn test_connect_params_parse() {
@@ -566,4 +3292,749 @@ mod tests {
         let params = ConnectParams::parse("localhost:1521/ORCL").unwrap();
         assert_eq!(params.connect_timeout, Duration::from_secs(20));
     }
+    /// Encode a packet with a small-SDU header.
+    fn encode_packet(packet_type: u8, payload: &[u8]) -> Vec<u8> {
+        let mut packet = ((payload.len() + 8) as u16).to_be_bytes().to_vec();
+        packet.extend_from_slice(&[0, 0, packet_type, 0, 0, 0]);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    /// Read a small-SDU packet, returning its type and payload.
+    async fn read_raw_packet(tcp: &mut TcpStream) -> (u8, Vec<u8>) {
+        use tokio::io::AsyncReadExt;
+
+        let mut header = [0u8; 8];
+        tcp.read_exact(&mut header).await.unwrap();
+        let len = u16::from_be_bytes([header[0], header[1]]) as usize;
+        let mut payload = vec![0u8; len - 8];
+        tcp.read_exact(&mut payload).await.unwrap();
+        (header[4], payload)
+    }
+
+    /// Connect to a fake server running `serve` on its end of the socket.
+    async fn connect_to_fake_server<F, Fut>(serve: F) -> (Connection, tokio::task::JoinHandle<()>)
+    where
+        F: FnOnce(TcpStream) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap());
+        let (client, (tcp, _)) = tokio::join!(client, async { listener.accept().await.unwrap() });
+        let client = client.unwrap();
+        client.set_nodelay(true).unwrap();
+        tcp.set_nodelay(true).unwrap();
+        let server = tokio::spawn(serve(tcp));
+
+        let stream = PacketStream::new(client);
+        let conn = Connection::from_parts(stream, Capabilities::new(), SessionData::default());
+        (conn, server)
+    }
+
+    /// Connect to a fake server that answers one ping only after it is
+    /// interrupted, then answers a second ping normally.
+    async fn connect_to_slow_server() -> (Connection, tokio::task::JoinHandle<()>) {
+        use tokio::io::AsyncWriteExt;
+
+        connect_to_fake_server(|mut tcp| async move {
+            let status = [0, 0, TNS_MSG_TYPE_STATUS, 0, 0];
+
+            // The first ping is never answered
+            assert_eq!(read_raw_packet(&mut tcp).await.0, TNS_PACKET_TYPE_DATA);
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_MARKER);
+            assert_eq!(payload[2], TNS_MARKER_TYPE_BREAK);
+
+            // Acknowledge the break, then end the call
+            let brk = encode_packet(TNS_PACKET_TYPE_MARKER, &[1, 0, TNS_MARKER_TYPE_BREAK]);
+            tcp.write_all(&brk).await.unwrap();
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_MARKER);
+            assert_eq!(payload[2], TNS_MARKER_TYPE_RESET);
+            let reset = encode_packet(TNS_PACKET_TYPE_MARKER, &[1, 0, TNS_MARKER_TYPE_RESET]);
+            tcp.write_all(&reset).await.unwrap();
+            let reply = encode_packet(TNS_PACKET_TYPE_DATA, &status);
+            tcp.write_all(&reply).await.unwrap();
+
+            // The second ping is answered right away
+            assert_eq!(read_raw_packet(&mut tcp).await.0, TNS_PACKET_TYPE_DATA);
+            tcp.write_all(&reply).await.unwrap();
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_call_timeout_breaks_call() {
+        let (mut conn, server) = connect_to_slow_server().await;
+        conn.set_call_timeout(Duration::from_millis(100));
+        assert_eq!(conn.call_timeout(), Duration::from_millis(100));
+
+        let err = conn.ping().await.unwrap_err();
+        assert!(matches!(err, Error::CallTimeout { .. }));
+        assert!(!err.is_connection_lost());
+        assert!(!conn.is_broken());
+
+        conn.ping().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_query_timeout_breaks_call() {
+        let (mut conn, server) = connect_to_slow_server().await;
+        let options = QueryOptions::new().with_timeout(Duration::from_millis(100));
+
+        let err = conn
+            .query_with_options("SELECT 1 FROM DUAL", &options)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::CallTimeout { timeout } if timeout == Duration::from_millis(100)),
+            "{:?}",
+            err
+        );
+        assert!(!conn.is_broken());
+        assert_eq!(conn.call_timeout(), Duration::ZERO);
+
+        conn.ping().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sessionless_transaction_id_checked() {
+        // Rejected before anything is sent
+        let (mut conn, server) = connect_to_fake_server(|_| async {}).await;
+        for id in [&[][..], &[7; 65][..]] {
+            let err = conn
+                .begin_sessionless_transaction(Some(id), Duration::from_secs(1))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::TypeConversion { .. }), "{:?}", err);
+        }
+        assert!(conn
+            .resume_sessionless_transaction(&[], Duration::ZERO)
+            .await
+            .is_err());
+        assert_eq!(conn.round_trips(), 0);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_inband_notifications() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            let status = encode_packet(TNS_PACKET_TYPE_DATA, &[0, 0, TNS_MSG_TYPE_STATUS, 0, 0]);
+            let notification = |code: u32| {
+                let mut payload = TNS_CONTROL_TYPE_INBAND_NOTIFICATION.to_be_bytes().to_vec();
+                payload.extend_from_slice(&[0; 4]);
+                payload.extend_from_slice(&code.to_be_bytes());
+                encode_packet(TNS_PACKET_TYPE_CONTROL, &payload)
+            };
+
+            // A planned outage is announced ahead of a response
+            read_raw_packet(&mut tcp).await;
+            tcp.write_all(&notification(TNS_ERR_INBAND_MESSAGE))
+                .await
+                .unwrap();
+            tcp.write_all(&status).await.unwrap();
+
+            // Then the session is shut down
+            read_raw_packet(&mut tcp).await;
+            tcp.write_all(&notification(TNS_ERR_SESSION_SHUTDOWN))
+                .await
+                .unwrap();
+            tcp.write_all(&status).await.unwrap();
+        })
+        .await;
+
+        assert!(conn.take_notifications().is_empty());
+        conn.ping().await.unwrap();
+        assert_eq!(conn.take_notifications(), vec![Notification::InbandMessage]);
+        assert!(conn.take_notifications().is_empty());
+        assert!(!conn.is_broken());
+
+        // The call completes, but the session is gone
+        conn.ping().await.unwrap();
+        assert_eq!(
+            conn.take_notifications(),
+            vec![Notification::SessionShutdown]
+        );
+        assert!(conn.is_broken());
+        assert!(matches!(conn.ping().await, Err(Error::ConnectionClosed)));
+        server.await.unwrap();
+    }
+
+    /// Encode a value in the chunked length format, in chunks of 1000 bytes.
+    fn encode_chunked(value: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![TNS_LONG_LENGTH_INDICATOR];
+        for chunk in value.chunks(1000) {
+            encoded.push(2);
+            encoded.extend_from_slice(&(chunk.len() as u16).to_be_bytes());
+            encoded.extend_from_slice(chunk);
+        }
+        encoded.push(0); // end of chunks
+        encoded
+    }
+
+    /// Encode a row holding one long RAW value.
+    fn encode_long_raw_row(value: &[u8]) -> Vec<u8> {
+        let mut response = vec![TNS_MSG_TYPE_ROW_DATA];
+        response.extend_from_slice(&encode_chunked(value));
+        response.extend_from_slice(&[TNS_MSG_TYPE_STATUS, 0, 0]);
+        response
+    }
+
+    #[tokio::test]
+    async fn test_chunked_values_across_packets() {
+        use tokio::io::AsyncWriteExt;
+
+        // Values whose response ends a little before or after the first
+        // packet is full, moving the chunk boundaries across it
+        let max_payload = TNS_SDU_DEFAULT as usize - 8 - 2;
+        let lengths: Vec<usize> = (max_payload - 40..max_payload + 8).collect();
+        let responses: Vec<Vec<u8>> = lengths
+            .iter()
+            .map(|&len| encode_long_raw_row(&vec![0xAB; len]))
+            .collect();
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            for response in responses {
+                read_raw_packet(&mut tcp).await;
+                // Every packet starts with the data flags
+                for piece in response.chunks(max_payload) {
+                    let payload = [&[0, 0], piece].concat();
+                    tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &payload))
+                        .await
+                        .unwrap();
+                }
+            }
+        })
+        .await;
+
+        let columns = [ColumnMetadata::new(
+            "R".to_string(),
+            ORA_TYPE_NUM_LONG_RAW as u8,
+        )];
+        for len in lengths {
+            let response = conn
+                .send_message_and_read_response(&PingMessage::new(), |buf| {
+                    parse_fetch_response(buf, &columns, None, 0)
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                response.rows[0].get(0),
+                Some(&OracleValue::Raw(vec![0xAB; len])),
+                "length {}",
+                len
+            );
+        }
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_multi_megabyte_long_value() {
+        use tokio::io::AsyncWriteExt;
+
+        let text: String = "LONG value ".repeat(200_000);
+        let mut response = vec![TNS_MSG_TYPE_ROW_DATA];
+        response.extend_from_slice(&encode_chunked(text.as_bytes()));
+        response.extend_from_slice(&[TNS_MSG_TYPE_STATUS, 0, 0]);
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            let max_payload = TNS_SDU_DEFAULT as usize - 8 - 2;
+            read_raw_packet(&mut tcp).await;
+            for piece in response.chunks(max_payload) {
+                let payload = [&[0, 0], piece].concat();
+                tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &payload))
+                    .await
+                    .unwrap();
+            }
+        })
+        .await;
+
+        let columns = [ColumnMetadata::new(
+            "L".to_string(),
+            ORA_TYPE_NUM_LONG as u8,
+        )];
+        let response = conn
+            .send_message_and_read_response(&PingMessage::new(), |buf| {
+                parse_fetch_response(buf, &columns, None, 0)
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.rows[0].get(0), Some(&OracleValue::String(text)));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_large_lob_read_in_one_round_trip() {
+        use tokio::io::AsyncWriteExt;
+
+        // A 1 MB CLOB comes back in one reply spanning hundreds of packets
+        let text: String = "0123456789abcdef".repeat(64 * 1024);
+        let mut response = vec![TNS_MSG_TYPE_LOB_DATA];
+        response.extend_from_slice(&encode_chunked(text.as_bytes()));
+        response.push(TNS_MSG_TYPE_PARAMETER);
+        response.extend_from_slice(&[9, 8, 7, 6]); // updated locator
+        response.extend_from_slice(&[0x03, 0x10, 0x00, 0x00]); // SB8 amount
+        response.extend_from_slice(&[TNS_MSG_TYPE_STATUS, 0, 0]);
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            let max_payload = TNS_SDU_DEFAULT as usize - 8 - 2;
+            read_raw_packet(&mut tcp).await;
+            for piece in response.chunks(max_payload) {
+                let payload = [&[0, 0], piece].concat();
+                tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &payload))
+                    .await
+                    .unwrap();
+            }
+        })
+        .await;
+
+        let mut lob = Lob::new(OracleType::Clob, vec![0; 4], text.len() as u64, 0);
+        let read = conn
+            .read_clob(&mut lob, 1, text.len() as u64)
+            .await
+            .unwrap();
+        assert_eq!(read, text);
+        assert_eq!(lob.locator(), &[9, 8, 7, 6]);
+        assert_eq!(conn.stats().round_trips, 1);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_access_token_requires_tcps() {
+        let params = ConnectParams::new("127.0.0.1", 9, "orcl").with_access_token("s3cr3t", None);
+        assert!(!format!("{:?}", params).contains("s3cr3t"));
+
+        // Rejected before connecting
+        let result = Connection::connect_with_params(&params, "", "").await;
+        assert!(matches!(result, Err(Error::AuthenticationFailed { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_abandoned_call() {
+        let (mut conn, server) = connect_to_slow_server().await;
+        // Nothing to cancel yet
+        conn.cancel().await.unwrap();
+
+        let abandoned = tokio::time::timeout(Duration::from_millis(100), conn.ping()).await;
+        assert!(abandoned.is_err());
+        assert_eq!(conn.health(), ConnectionHealth::Interrupted);
+        conn.cancel().await.unwrap();
+        assert!(!conn.is_broken());
+        assert_eq!(conn.health(), ConnectionHealth::Healthy);
+
+        assert!(conn.is_healthy(true).await);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_health_server_closed() {
+        let (mut conn, server) = connect_to_fake_server(|tcp| async move { drop(tcp) }).await;
+        server.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(conn.health(), ConnectionHealth::SessionLost);
+        assert!(!conn.is_healthy(false).await);
+        assert!(matches!(conn.ping().await, Err(Error::ConnectionClosed)));
+    }
+
+    #[tokio::test]
+    async fn test_health_desynchronized() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            read_raw_packet(&mut tcp).await;
+            // Not a message type a ping reply can hold
+            let reply = encode_packet(TNS_PACKET_TYPE_DATA, &[0, 0, 0xEE]);
+            tcp.write_all(&reply).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        })
+        .await;
+        assert_eq!(conn.health(), ConnectionHealth::Healthy);
+
+        assert!(matches!(conn.ping().await, Err(Error::Protocol { .. })));
+        assert_eq!(conn.health(), ConnectionHealth::Desynchronized);
+        assert!(!conn.is_healthy(true).await);
+        assert!(matches!(conn.ping().await, Err(Error::ConnectionClosed)));
+        server.await.unwrap();
+    }
+
+    /// Connect to a fake server that sends the reply to a ping in two
+    /// packets, the second only once the client has given up on it.
+    async fn connect_to_split_reply_server(
+        end_of_response: bool,
+    ) -> (Connection, tokio::task::JoinHandle<()>) {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(move |mut tcp| async move {
+            let end = if end_of_response {
+                TNS_DATA_FLAGS_END_OF_RESPONSE.to_be_bytes()
+            } else {
+                [0, 0]
+            };
+            read_raw_packet(&mut tcp).await;
+            let first = encode_packet(TNS_PACKET_TYPE_DATA, &[0, 0, TNS_MSG_TYPE_STATUS]);
+            tcp.write_all(&first).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let rest = encode_packet(TNS_PACKET_TYPE_DATA, &[end[0], end[1], 0, 0]);
+            tcp.write_all(&rest).await.unwrap();
+
+            if end_of_response {
+                read_raw_packet(&mut tcp).await;
+                let reply = [end[0], end[1], TNS_MSG_TYPE_STATUS, 0, 0];
+                tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                    .await
+                    .unwrap();
+            }
+        })
+        .await;
+        conn.caps.supports_end_of_response = end_of_response;
+        (conn, server)
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_response_drained() {
+        let (mut conn, server) = connect_to_split_reply_server(true).await;
+
+        let abandoned = tokio::time::timeout(Duration::from_millis(100), conn.ping()).await;
+        assert!(abandoned.is_err());
+        // The rest of the first reply is discarded before the next ping
+        conn.ping().await.unwrap();
+        assert!(!conn.is_broken());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_response_without_end_flag() {
+        let (mut conn, server) = connect_to_split_reply_server(false).await;
+
+        let abandoned = tokio::time::timeout(Duration::from_millis(100), conn.ping()).await;
+        assert!(abandoned.is_err());
+        assert!(matches!(conn.cancel().await, Err(Error::Protocol { .. })));
+        assert!(conn.is_broken());
+        // Later calls fail fast
+        assert!(matches!(conn.ping().await, Err(Error::ConnectionClosed)));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_fetch_fails_cursor() {
+        let (mut conn, server) = connect_to_slow_server().await;
+        let columns = vec![ColumnMetadata::new("ID".to_string(), 2)];
+        let mut cursor = RowCursor::new(
+            &mut conn,
+            "SELECT 1 FROM DUAL",
+            columns,
+            1,
+            vec![],
+            true,
+            10,
+        );
+
+        let abandoned = tokio::time::timeout(Duration::from_millis(100), cursor.next()).await;
+        assert!(abandoned.is_err());
+        assert!(matches!(cursor.next().await, Err(Error::Protocol { .. })));
+        drop(cursor);
+
+        // The connection itself recovers
+        conn.cancel().await.unwrap();
+        conn.ping().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cursor_fetches_with_fetch_message() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_DATA);
+            // A bare FETCH of one row (the fetch size is at least 1) from cursor 3
+            assert_eq!(
+                &payload[2..],
+                &[TNS_MSG_TYPE_FUNCTION, TNS_FUNC_FETCH, 1, 1, 3, 1, 1]
+            );
+            let reply = [
+                0,
+                0,
+                TNS_MSG_TYPE_ROW_DATA,
+                1,
+                b'x',
+                TNS_MSG_TYPE_STATUS,
+                0,
+                0,
+            ];
+            tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                .await
+                .unwrap();
+        })
+        .await;
+        let columns = vec![ColumnMetadata::new(
+            "A".to_string(),
+            ORA_TYPE_NUM_VARCHAR as u8,
+        )];
+        let mut cursor = RowCursor::new(&mut conn, "SELECT A FROM T", columns, 3, vec![], true, 0);
+
+        // The row is decoded with the columns described by the execute call
+        let row = cursor.next().await.unwrap().unwrap();
+        assert_eq!(row.get(0).and_then(|v| v.as_str()), Some("x"));
+        assert_eq!(row.columns()[0].name, "A");
+        drop(cursor);
+        assert_eq!(conn.stats().fetches, 1);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cached_query_reexecuted() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_DATA);
+            // Cursor 3 is executed and fetched from without the SQL text
+            assert_eq!(
+                &payload[2..],
+                &[
+                    TNS_MSG_TYPE_FUNCTION,
+                    TNS_FUNC_REEXECUTE_AND_FETCH,
+                    1,
+                    1,
+                    3,
+                    1,
+                    50,
+                    1,
+                    TNS_EXEC_OPTION_EXECUTE as u8,
+                    0
+                ]
+            );
+            let reply = [
+                0,
+                0,
+                TNS_MSG_TYPE_ROW_DATA,
+                1,
+                b'x',
+                TNS_MSG_TYPE_STATUS,
+                0,
+                0,
+            ];
+            tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                .await
+                .unwrap();
+        })
+        .await;
+        let options = QueryOptions::new().with_prefetch_rows(50);
+        conn.cache_entry(CachedStatement {
+            sql: "SELECT A FROM T".to_string(),
+            cursor_id: 3,
+            columns: vec![ColumnMetadata::new(
+                "A".to_string(),
+                ORA_TYPE_NUM_VARCHAR as u8,
+            )],
+            binds: Vec::new(),
+            max_long_size: options.max_long_size,
+        });
+
+        // The row is decoded with the columns cached with the cursor
+        let result = conn
+            .query_with_options("SELECT A FROM T", &options)
+            .await
+            .unwrap();
+        assert_eq!(result.rows[0].get(0).and_then(|v| v.as_str()), Some("x"));
+        assert_eq!(result.columns[0].name, "A");
+        let stats = conn.stats();
+        assert_eq!((stats.parses, stats.executes, stats.fetches), (0, 1, 1));
+        assert_eq!(conn.cached_statements(), 1);
+        assert_eq!(conn.pending_cursor_closes(), 0);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_framing() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            // The pipeline begins in a packet of its own
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_DATA);
+            assert_eq!(&payload[..2], &TNS_DATA_FLAGS_BEGIN_PIPELINE.to_be_bytes());
+            assert_eq!(
+                &payload[2..],
+                &[
+                    TNS_MSG_TYPE_FUNCTION,
+                    TNS_FUNC_PIPELINE_BEGIN,
+                    1,
+                    0,
+                    0,
+                    TNS_PIPELINE_MODE_CONTINUE_ON_ERROR
+                ]
+            );
+            // Then each request, with no data flags
+            for _ in 0..2 {
+                let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+                assert_eq!(packet_type, TNS_PACKET_TYPE_DATA);
+                assert_eq!(
+                    &payload[..4],
+                    &[0, 0, TNS_MSG_TYPE_FUNCTION, TNS_FUNC_EXECUTE]
+                );
+            }
+            let (_, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(
+                &payload[..],
+                &[0, 0, TNS_MSG_TYPE_FUNCTION, TNS_FUNC_PIPELINE_END, 1, 0]
+            );
+
+            // One response per request, then one to the end of the pipeline
+            let mut reply = Vec::new();
+            for _ in 0..3 {
+                let status = [
+                    0x20,
+                    0,
+                    TNS_MSG_TYPE_STATUS,
+                    0,
+                    0,
+                    TNS_MSG_TYPE_END_OF_RESPONSE,
+                ];
+                reply.extend(encode_packet(TNS_PACKET_TYPE_DATA, &status));
+            }
+            tcp.write_all(&reply).await.unwrap();
+        })
+        .await;
+        conn.caps.supports_pipelining = true;
+        conn.caps.supports_end_of_response = true;
+
+        let results = conn
+            .pipeline()
+            .add_execute("DELETE FROM T", Vec::new())
+            .add_execute("UPDATE T SET A = 1", Vec::new())
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(conn.stats().round_trips, 1);
+        server.await.unwrap();
+    }
+
+    /// Run `query_one` on a cached cursor whose column has type `ora_type`,
+    /// against a server that holds the row back from the execute.
+    async fn query_one_held_back(ora_type: u16) -> (Option<Row>, u64) {
+        use tokio::io::AsyncWriteExt;
+
+        let fetched = ora_type == ORA_TYPE_NUM_CLOB;
+        let (mut conn, server) = connect_to_fake_server(move |mut tcp| async move {
+            let (_, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(payload[3], TNS_FUNC_REEXECUTE_AND_FETCH);
+            // No rows, and an end of call naming cursor 3 (fields of a
+            // server older than 20c)
+            let mut reply = vec![0, 0, TNS_MSG_TYPE_ERROR, 0, 0, 0, 0, 0, 0, 1, 3];
+            reply.extend([0; 23]);
+            tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                .await
+                .unwrap();
+            if fetched {
+                // One row, with a null CLOB
+                let (_, payload) = read_raw_packet(&mut tcp).await;
+                assert_eq!(
+                    &payload[2..],
+                    &[TNS_MSG_TYPE_FUNCTION, TNS_FUNC_FETCH, 1, 1, 3, 1, 1]
+                );
+                let reply = [0, 0, TNS_MSG_TYPE_ROW_DATA, 0, TNS_MSG_TYPE_STATUS, 0, 0];
+                tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                    .await
+                    .unwrap();
+            }
+        })
+        .await;
+        conn.cache_entry(CachedStatement {
+            sql: "SELECT A FROM T".to_string(),
+            cursor_id: 3,
+            columns: vec![ColumnMetadata::new("A".to_string(), ora_type as u8)],
+            binds: Vec::new(),
+            max_long_size: QueryOptions::default().max_long_size,
+        });
+
+        let row = conn.query_one("SELECT A FROM T").await.unwrap();
+        server.await.unwrap();
+        assert_eq!(conn.stats().cursors_leaked, 0);
+        (row, conn.stats().round_trips)
+    }
+
+    #[tokio::test]
+    async fn test_query_one_fetches_only_when_define_required() {
+        // A LOB-free query takes the execute's answer in one round trip
+        let (row, round_trips) = query_one_held_back(ORA_TYPE_NUM_VARCHAR).await;
+        assert!(row.is_none());
+        assert_eq!(round_trips, 1);
+
+        // A row held back for a LOB column is fetched
+        let (row, round_trips) = query_one_held_back(ORA_TYPE_NUM_CLOB).await;
+        assert_eq!(row.unwrap().get(0), Some(&OracleValue::Null));
+        assert_eq!(round_trips, 2);
+    }
+
+    #[tokio::test]
+    async fn test_leaked_cursors_closed_together() {
+        use tokio::io::AsyncWriteExt;
+
+        let (mut conn, server) = connect_to_fake_server(|mut tcp| async move {
+            // Every queued close rides on one ping
+            let (packet_type, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(packet_type, TNS_PACKET_TYPE_DATA);
+            assert_eq!(
+                &payload[2..],
+                &[
+                    TNS_MSG_TYPE_PIGGYBACK,
+                    TNS_FUNC_CLOSE_CURSORS,
+                    1,
+                    1,
+                    1,
+                    3,
+                    1,
+                    5,
+                    1,
+                    6,
+                    1,
+                    7,
+                    TNS_MSG_TYPE_FUNCTION,
+                    TNS_FUNC_PING,
+                    1
+                ]
+            );
+            let reply = [0, 0, TNS_MSG_TYPE_STATUS, 0, 0];
+            tcp.write_all(&encode_packet(TNS_PACKET_TYPE_DATA, &reply))
+                .await
+                .unwrap();
+        })
+        .await;
+
+        // Cursors dropped while open are leaked, and queued for closing; the
+        // counter is kept in every build, the WARN only with debug_assertions
+        for cursor_id in [5, 6] {
+            drop(RowCursor::new(
+                &mut conn,
+                "SELECT 1 FROM DUAL",
+                vec![],
+                cursor_id,
+                vec![],
+                true,
+                1,
+            ));
+        }
+        assert_eq!(conn.stats().cursors_leaked, 2);
+        assert_eq!(conn.pending_cursor_closes(), 2);
+
+        // Closing one explicitly is not a leak, and sends all the closes
+        let mut cursor =
+            RowCursor::new(&mut conn, "SELECT 1 FROM DUAL", vec![], 7, vec![], true, 1);
+        cursor.close().await.unwrap();
+        drop(cursor);
+        let stats = conn.stats();
+        assert_eq!(stats.cursors_leaked, 2);
+        assert_eq!(stats.round_trips, 1);
+        assert_eq!(conn.pending_cursor_closes(), 0);
+        server.await.unwrap();
+    }
 }
@@ -2,16 +2,19 @@
 //!
 //! The cursor module provides a trait-based interface for iterating over query
 //! results. The `Cursor` trait defines the common interface, while `RowCursor`
-//! provides a row-by-row iteration implementation.
+//! is its one implementation: every cursor the connection hands out, whether
+//! opened by a query or by the server (implicit result sets), buffers and
+//! fetches rows through the same state machine.
 
 use crate::connection::Connection;
-use crate::error::Result;
-use crate::protocol::buffer::ReadBuffer;
-use crate::protocol::messages::FetchMessage;
-use crate::protocol::response::parse_fetch_response;
-use crate::protocol::types::{ColumnMetadata, Row};
+use crate::error::{Error, ErrorContext, Phase, Result};
+use crate::protocol::message::{DataMessage, Message};
+use crate::protocol::messages::{ExecuteMessage, FetchMessage};
+use crate::protocol::response::{parse_fetch_response, FetchResponse};
+use crate::protocol::types::{ColumnMetadata, FromRow, Row};
 use futures::Stream;
 use std::future::Future;
+use std::time::Duration;
 
 /// Base trait for all cursor types.
 ///
@@ -93,10 +96,18 @@ pub trait Cursor {
 ///
 /// # Lifecycle
 ///
-/// 1. Created by `Connection::open_cursor()`
-/// 2. Iterated via `next()` or `fetch_all()`
+/// 1. Created by `Connection::open_cursor()` with the rows prefetched by the
+///    execute round trip, or for a cursor opened by the server with no rows
+/// 2. Iterated via `next()` or `fetch_all()`; each fetch appends up to
+///    `fetch_size` rows to the buffer and updates whether the server has more
 /// 3. Automatically closed when exhausted or explicitly via `close()`
 ///
+/// Dropping a cursor before it is exhausted (e.g. a stream cut short with
+/// `take()`) discards the buffered rows and queues the server cursor to be
+/// closed with the connection's next round trip. It also counts as leaked in
+/// `ConnectionStats::cursors_leaked` (and is logged in debug builds with the
+/// `tracing` feature). Call `close()` to release it immediately instead.
+///
 /// # Example
 ///
 /// ```no_run
@@ -132,6 +143,8 @@ pub struct RowCursor<'conn> {
     cursor_id: u32,
     /// Buffered rows from prefetch/fetch.
     buffer: Vec<Row>,
+    /// Last row received, whose columns the next fetch may repeat.
+    last_row: Option<Row>,
     /// Current position in buffer.
     buffer_pos: usize,
     /// Whether the server has more rows.
@@ -142,6 +155,38 @@ pub struct RowCursor<'conn> {
     rows_fetched: u64,
     /// Server TTC field version.
     server_ttc_field_version: u8,
+    /// SQL text, for error context.
+    sql: String,
+    /// Whether the next fetch must be an execute, for cursors opened by the
+    /// server that this session has not fetched from yet.
+    requires_execute: bool,
+    /// Estimated memory held by the rows in the buffer.
+    buffered_bytes: usize,
+    /// Most memory held in rows at once, including rows collected by `fetch_all`.
+    peak_buffered_bytes: usize,
+    /// Cap on the memory held in buffered rows.
+    max_buffered_bytes: Option<usize>,
+    /// Whether the cap was reached, so rows must be streamed.
+    streaming_only: bool,
+    /// Timeout for each fetch, in place of the connection's call timeout.
+    timeout: Option<Duration>,
+    /// Set while a fetch is in flight, and left set if its future is dropped.
+    fetch_abandoned: bool,
+}
+
+/// Check that a FETCH was answered for the cursor it named.
+///
+/// The server reports the cursor in the end-of-call status; another cursor
+/// there means the replies are out of step with the requests.
+fn check_fetched_cursor(cursor_id: u32, response: &FetchResponse) -> Result<()> {
+    let reported = u32::from(response.error_info.cursor_id);
+    if reported != 0 && reported != cursor_id {
+        return Err(Error::protocol(format!(
+            "fetch from cursor {} was answered for cursor {}",
+            cursor_id, reported
+        )));
+    }
+    Ok(())
 }
 
 impl<'conn> RowCursor<'conn> {
@@ -150,62 +195,178 @@ impl<'conn> RowCursor<'conn> {
     /// This is called by Connection::open_cursor().
     pub(crate) fn new(
         conn: &'conn mut Connection,
+        sql: &str,
         columns: Vec<ColumnMetadata>,
         cursor_id: u32,
         rows: Vec<Row>,
         more_rows: bool,
         fetch_size: u32,
-        server_ttc_field_version: u8,
     ) -> Self {
         let rows_fetched = rows.len() as u64;
+        let buffered_bytes = rows.iter().map(Row::memory_size).sum();
+        let server_ttc_field_version = conn.server_ttc_field_version();
         Self {
             conn,
             columns,
             cursor_id,
+            last_row: rows.last().cloned(),
             buffer: rows,
             buffer_pos: 0,
             more_rows,
             fetch_size,
             rows_fetched,
             server_ttc_field_version,
+            sql: sql.to_string(),
+            requires_execute: false,
+            buffered_bytes,
+            peak_buffered_bytes: buffered_bytes,
+            max_buffered_bytes: None,
+            streaming_only: false,
+            timeout: None,
+            fetch_abandoned: false,
         }
     }
 
-    /// Internal: Perform a fetch from the server.
-    async fn do_fetch(&mut self) -> Result<()> {
+    /// Create a cursor for a cursor opened by the server, such as an
+    /// implicit result set, with no rows fetched yet.
+    ///
+    /// `sql` is the statement that returned the cursor.
+    pub(crate) fn from_server_cursor(
+        conn: &'conn mut Connection,
+        sql: &str,
+        columns: Vec<ColumnMetadata>,
+        cursor_id: u32,
+        fetch_size: u32,
+    ) -> Self {
+        let mut cursor = Self::new(conn, sql, columns, cursor_id, Vec::new(), true, fetch_size);
+        cursor.requires_execute = true;
+        cursor
+    }
+
+    /// Internal: Perform a fetch from the server, returning the estimated
+    /// memory used by the fetched rows.
+    async fn do_fetch(&mut self) -> Result<usize> {
         // Reuse buffer capacity
         if self.buffer_pos >= self.buffer.len() {
             self.buffer.clear();
             self.buffer_pos = 0;
+            self.buffered_bytes = 0;
         }
 
-        // Create fetch message
-        let msg = FetchMessage::new(self.cursor_id, self.fetch_size);
-
-        // Send and receive via Connection
-        let response = self.conn.send_message_and_read_response(&msg).await?;
-
-        // Parse response
-        let mut buf = ReadBuffer::new(response.payload);
-        let _data_flags = buf.read_u16_be()?;
-
-        let fetch_response =
-            parse_fetch_response(&mut buf, &self.columns, self.server_ttc_field_version)?;
+        // The rows of a fetch whose future was dropped are lost; carrying on
+        // would silently skip them
+        if self.fetch_abandoned {
+            return Err(Error::protocol(
+                "cursor unusable: a fetch was abandoned before it completed",
+            ));
+        }
+        self.fetch_abandoned = true;
+        #[cfg(feature = "otel")]
+        let started = std::time::SystemTime::now();
+        let fetch_response = self.request_rows().await;
+        self.fetch_abandoned = false;
+        #[cfg(feature = "otel")]
+        self.conn.trace_fetch(&self.sql, started, &fetch_response);
+        let fetch_response = fetch_response?;
 
         // Check for errors (1403 = ORA-01403 "no data found" = normal end)
-        if fetch_response.error_info.error_num != 0 && fetch_response.error_info.error_num != 1403 {
-            return Err(crate::error::Error::Oracle {
-                code: fetch_response.error_info.error_num,
-                message: fetch_response.error_info.message.unwrap_or_default(),
-            });
+        let info = fetch_response.error_info;
+        if info.error_num != 0 && info.error_num != 1403 {
+            let context = ErrorContext::new(Phase::Fetch, &self.sql, self.cursor_id);
+            return Err(info.to_error().with_context(context));
         }
 
         // Update state
-        self.rows_fetched += fetch_response.rows.len() as u64;
+        let num_rows = fetch_response.rows.len();
+        let bytes: usize = fetch_response.rows.iter().map(Row::memory_size).sum();
+        self.rows_fetched += num_rows as u64;
+        if let Some(row) = fetch_response.rows.last() {
+            self.last_row = Some(row.clone());
+        }
         self.buffer.extend(fetch_response.rows);
         self.more_rows = fetch_response.more_rows;
+        self.buffered_bytes += bytes;
+        self.peak_buffered_bytes = self.peak_buffered_bytes.max(self.buffered_bytes);
+
+        // Past the cap, ask for only as many rows as fit in it
+        if let Some(max) = self.max_buffered_bytes {
+            if self.buffered_bytes > max && num_rows > 0 {
+                self.streaming_only = true;
+                let row_size = bytes.div_ceil(num_rows).max(1);
+                let fitting = (max / row_size).clamp(1, u32::MAX as usize) as u32;
+                self.fetch_size = self.fetch_size.min(fitting);
+            }
+        }
 
-        Ok(())
+        Ok(bytes)
+    }
+
+    /// Internal: Request the next rows from the server.
+    ///
+    /// A cursor opened by this session was parsed, executed and defined by
+    /// its execute call, so its rows are requested with a bare FETCH naming
+    /// the server cursor, and decoded with the columns described then. A
+    /// cursor opened by the server is defined by an execute call on its
+    /// first fetch, and fetched the same way after that.
+    async fn request_rows(&mut self) -> Result<FetchResponse> {
+        if self.cursor_id == 0 {
+            return Err(Error::protocol("cannot fetch from a closed cursor"));
+        }
+        // Fetching no rows would never reach the end of the result set
+        let fetch_size = self.fetch_size.max(1);
+        self.conn.record_fetch();
+        if self.requires_execute {
+            let ttc = self.conn.ttc_field_version();
+            let msg = ExecuteMessage::new_cursor_fetch(self.cursor_id, fetch_size, ttc);
+            let response = self.send_fetch(&msg).await?;
+            self.requires_execute = false;
+            Ok(response)
+        } else {
+            let msg = FetchMessage::new(self.cursor_id, fetch_size);
+            let response = self.send_fetch(&msg).await?;
+            check_fetched_cursor(self.cursor_id, &response)?;
+            Ok(response)
+        }
+    }
+
+    /// Internal: Send a message fetching rows and parse the reply.
+    async fn send_fetch<M>(&mut self, msg: &M) -> Result<FetchResponse>
+    where
+        M: DataMessage + Message,
+    {
+        let (columns, server_ttc) = (&self.columns, self.server_ttc_field_version);
+        let last_row = self.last_row.as_ref();
+        let timeout = self.timeout.unwrap_or(self.conn.call_timeout());
+        self.conn
+            .send_message_and_read_response_within(msg, timeout, |buf| {
+                parse_fetch_response(buf, columns, last_row, server_ttc)
+            })
+            .await
+    }
+
+    /// Internal: Take the next buffered row.
+    fn take_buffered(&mut self) -> Option<Row> {
+        let row = self.buffer.get(self.buffer_pos).cloned()?;
+        self.buffer_pos += 1;
+        Some(row)
+    }
+
+    /// Internal: Discard buffered rows and queue the server cursor for closing.
+    pub(crate) fn release(&mut self) {
+        self.buffer.clear();
+        self.buffer_pos = 0;
+        self.buffered_bytes = 0;
+        self.more_rows = false;
+        self.conn.queue_cursor_close(self.cursor_id);
+        self.cursor_id = 0;
+    }
+
+    /// Internal: Error for `fetch_all` past the buffered memory cap.
+    fn buffer_limit_error(&self, size: usize) -> Error {
+        Error::BufferLimitExceeded {
+            size,
+            max: self.max_buffered_bytes.unwrap_or_default(),
+        }
     }
 
     /// Get column metadata.
@@ -232,6 +393,95 @@ impl<'conn> RowCursor<'conn> {
     pub fn buffered_count(&self) -> usize {
         self.buffer.len().saturating_sub(self.buffer_pos)
     }
+
+    /// Get the estimated memory, in bytes, held by the current batch of rows.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+
+    /// Get the most memory, in bytes, the cursor has held in rows at once,
+    /// including rows collected by `fetch_all`.
+    pub fn peak_buffered_bytes(&self) -> usize {
+        self.peak_buffered_bytes
+    }
+
+    /// Get the cap on memory held in buffered rows.
+    pub fn max_buffered_bytes(&self) -> Option<usize> {
+        self.max_buffered_bytes
+    }
+
+    /// Cap the memory held in buffered rows (see
+    /// `QueryOptions::with_max_buffered_bytes`).
+    pub fn set_max_buffered_bytes(&mut self, max_buffered_bytes: Option<usize>) {
+        self.max_buffered_bytes = max_buffered_bytes;
+    }
+
+    /// Get the timeout for each fetch, if it overrides the connection's
+    /// call timeout.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Bound each fetch round trip (see `QueryOptions::with_timeout`), or
+    /// use the connection's call timeout with `None`.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Check whether the cap was reached, so the remaining rows must be
+    /// streamed with `next()` rather than collected with `fetch_all()`.
+    pub fn is_streaming_only(&self) -> bool {
+        self.streaming_only
+    }
+
+    /// Get the next row converted to `T`.
+    pub async fn next_as<T: FromRow>(&mut self) -> Result<Option<T>> {
+        match self.next().await? {
+            Some(row) => T::from_row(&row).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch all remaining rows converted to `T`.
+    pub async fn fetch_all_as<T: FromRow>(&mut self) -> Result<Vec<T>> {
+        self.fetch_all().await?.iter().map(T::from_row).collect()
+    }
+
+    /// Fetch up to `batch_size` rows (at least one) as an Arrow record
+    /// batch, or `None` once every row has been fetched.
+    ///
+    /// Values are decoded straight into Arrow arrays; see [`crate::arrow`]
+    /// for the type mapping.
+    ///
+    /// # Errors
+    /// Returns `Error::UnsupportedType` if a column has no Arrow mapping.
+    #[cfg(feature = "arrow")]
+    pub async fn fetch_arrow(
+        &mut self,
+        batch_size: usize,
+    ) -> Result<Option<arrow_array::RecordBatch>> {
+        let batch_size = batch_size.max(1);
+        let mut batch = crate::arrow::BatchBuilder::new(&self.columns, batch_size)?;
+        while batch.len() < batch_size {
+            if self.buffer_pos >= self.buffer.len() {
+                if !self.more_rows {
+                    break;
+                }
+                self.do_fetch().await?;
+                if self.buffer_pos >= self.buffer.len() {
+                    break;
+                }
+            }
+            batch.append(&self.buffer[self.buffer_pos])?;
+            self.buffer_pos += 1;
+        }
+
+        if batch.len() == 0 {
+            self.release();
+            return Ok(None);
+        }
+        batch.finish().map(Some)
+    }
 }
 
 impl<'conn> Cursor for RowCursor<'conn> {
@@ -263,55 +513,67 @@ impl<'conn> Cursor for RowCursor<'conn> {
 
     async fn close(&mut self) -> Result<()> {
         if self.cursor_id != 0 {
-            // TODO: Send close message to server (Phase 1: just mark closed)
-            self.cursor_id = 0;
-            self.more_rows = false;
+            self.release();
+            self.conn.close_pending_cursors().await?;
         }
         Ok(())
     }
 
     async fn next(&mut self) -> Result<Option<Self::Item>> {
-        // Try buffered first
-        if self.buffer_pos < self.buffer.len() {
-            let row = self.buffer[self.buffer_pos].clone();
-            self.buffer_pos += 1;
+        // Try buffered first, then fetch more from server
+        if let Some(row) = self.take_buffered() {
             return Ok(Some(row));
         }
-
-        // No more rows?
-        if !self.more_rows {
-            self.cursor_id = 0;
-            return Ok(None);
+        if self.more_rows {
+            self.do_fetch().await?;
+            if let Some(row) = self.take_buffered() {
+                return Ok(Some(row));
+            }
         }
 
-        // Fetch more from server
-        self.do_fetch().await?;
-
-        // Try buffer again after fetch
-        if self.buffer_pos < self.buffer.len() {
-            let row = self.buffer[self.buffer_pos].clone();
-            self.buffer_pos += 1;
-            Ok(Some(row))
-        } else {
-            self.cursor_id = 0;
-            Ok(None)
-        }
+        self.release();
+        Ok(None)
     }
 
     async fn fetch_all(&mut self) -> Result<Vec<Self::Item>> {
-        let mut all_rows = std::mem::take(&mut self.buffer);
+        if self.streaming_only {
+            return Err(self.buffer_limit_error(self.buffered_bytes));
+        }
+
+        let mut all_rows = self.buffer.split_off(self.buffer_pos);
+        let mut total_bytes: usize = all_rows.iter().map(Row::memory_size).sum();
+        self.buffer.clear();
         self.buffer_pos = 0;
+        self.buffered_bytes = 0;
 
         while self.more_rows {
-            self.do_fetch().await?;
+            if self.max_buffered_bytes.is_some_and(|max| total_bytes > max) {
+                // Hand the collected rows back to the buffer for streaming
+                self.streaming_only = true;
+                self.buffer = all_rows;
+                self.buffered_bytes = total_bytes;
+                return Err(self.buffer_limit_error(total_bytes));
+            }
+            total_bytes += self.do_fetch().await?;
+            self.peak_buffered_bytes = self.peak_buffered_bytes.max(total_bytes);
             all_rows.append(&mut self.buffer);
+            self.buffered_bytes = 0;
         }
 
-        self.cursor_id = 0;
+        self.release();
         Ok(all_rows)
     }
 }
 
+impl Drop for RowCursor<'_> {
+    fn drop(&mut self) {
+        if self.cursor_id != 0 {
+            self.conn.record_leaked_cursor(self.cursor_id, &self.sql);
+            self.cursor_id = 0;
+        }
+    }
+}
+
 /// Extension trait for converting Cursor to Stream.
 ///
 /// # Example
@@ -350,17 +612,31 @@ impl<C: Cursor + Unpin> CursorStreamExt for C {
     fn into_stream(self) -> impl Stream<Item = Result<Self::Item>> {
         use futures::stream;
 
-        stream::unfold(
-            Some(self),
-            |opt_cursor| async move {
-                let mut cursor = opt_cursor?;
-                match cursor.next().await {
-                    Ok(Some(item)) => Some((Ok(item), Some(cursor))),
-                    Ok(None) => None,
-                    Err(e) => Some((Err(e), Some(cursor))),
-                }
-            },
-        )
+        stream::unfold(Some(self), |opt_cursor| async move {
+            let mut cursor = opt_cursor?;
+            match cursor.next().await {
+                Ok(Some(item)) => Some((Ok(item), Some(cursor))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), Some(cursor))),
+            }
+        })
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_fetched_cursor() {
+        let mut response = FetchResponse::new();
+        assert!(check_fetched_cursor(7, &response).is_ok());
+        response.error_info.cursor_id = 7;
+        assert!(check_fetched_cursor(7, &response).is_ok());
+        response.error_info.cursor_id = 8;
+        assert!(matches!(
+            check_fetched_cursor(7, &response),
+            Err(Error::Protocol { .. })
+        ));
+    }
+}
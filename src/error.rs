@@ -1,5 +1,6 @@
 //! Error types for the Oracle thin client.
 
+use std::fmt;
 use std::io;
 use thiserror::Error;
 
@@ -54,8 +55,19 @@ pub enum Error {
     ConnectionClosed,
 
     /// Oracle database error.
-    #[error("ORA-{code:05}: {message}")]
-    Oracle { code: u32, message: String },
+    ///
+    /// Errors raised while running a statement carry the statement and the
+    /// operation that failed in `context`, the position in the statement
+    /// the error was detected at in `offset`, and the rows processed before
+    /// it in `row_count`. Use the `is_*` methods to classify the error.
+    #[error("ORA-{code:05}: {message}{}", context_suffix(.context))]
+    Oracle {
+        code: u32,
+        message: String,
+        offset: u32,
+        row_count: u64,
+        context: Option<Box<ErrorContext>>,
+    },
 
     /// Type conversion error.
     #[error("Type conversion error: {message}")]
@@ -93,11 +105,181 @@ pub enum Error {
         timeout: std::time::Duration,
     },
 
+    /// The call was cancelled on the server (`ORA-01013`), for example by
+    /// `ALTER SYSTEM CANCEL SQL` from another session.
+    ///
+    /// The statement's work is rolled back, and the connection stays usable:
+    /// the same statement can be executed again.
+    #[error("ORA-01013: user requested cancel of current operation{}", context_suffix(.context))]
+    Cancelled { context: Option<Box<ErrorContext>> },
+
+    /// A call took longer than the connection's call timeout.
+    ///
+    /// The call is interrupted on the server; the connection stays usable
+    /// unless that fails too.
+    #[error("Call timed out after {timeout:?}")]
+    CallTimeout { timeout: std::time::Duration },
+
+    /// No pooled session was released within the pool's acquire timeout.
+    #[error("Timed out after {timeout:?} waiting for a pooled connection")]
+    PoolTimeout { timeout: std::time::Duration },
+
+    /// An Oracle wallet could not be loaded.
+    #[error("Wallet error: {message}")]
+    Wallet { message: String },
+
     /// DNS resolution failed.
     #[error("Failed to resolve hostname '{hostname}': {message}")]
     DnsResolutionFailed { hostname: String, message: String },
+
+    /// SQL text longer than the server accepts.
+    #[error("SQL statement is {length} bytes, exceeding the maximum of {max} bytes")]
+    StatementTooLarge { length: usize, max: usize },
+
+    /// A response from the server grew past the configured maximum size.
+    ///
+    /// The rest of the response is left unread, so the connection cannot be
+    /// used further.
+    #[error("Response of at least {size} bytes exceeds the maximum of {max} bytes")]
+    ResponseTooLarge { size: usize, max: usize },
+
+    /// A cursor's buffered rows grew past the configured maximum.
+    ///
+    /// The rows stay buffered in the cursor, which continues in streaming
+    /// mode: fetch the rest with `next()`.
+    #[error("Buffered rows use {size} bytes, exceeding the maximum of {max} bytes")]
+    BufferLimitExceeded { size: usize, max: usize },
+
+    /// A row of bind values has a different length than the first row.
+    #[error("Row {row} has {actual} bind values, expected {expected}")]
+    BindCountMismatch {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A `SqlInterceptor` refused to send a statement.
+    #[error("Statement rejected: {message}")]
+    StatementRejected { message: String },
+}
+
+/// Longest SQL text, in characters, kept in an `ErrorContext`.
+const MAX_CONTEXT_SQL_CHARS: usize = 100;
+
+/// Operation during which a statement failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Parsing or describing the statement without executing it.
+    Parse,
+    /// Executing the statement (parsing it first if needed).
+    Execute,
+    /// Redefining the output columns of a query.
+    Define,
+    /// Fetching rows from an open cursor.
+    Fetch,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Parse => "parse",
+            Self::Execute => "execute",
+            Self::Define => "define",
+            Self::Fetch => "fetch",
+        })
+    }
+}
+
+/// The statement an error was raised for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Operation that failed.
+    pub phase: Phase,
+    /// SQL text with whitespace collapsed, truncated to 100 characters.
+    pub sql: String,
+    /// Server cursor ID (0 if none was opened).
+    pub cursor_id: u32,
+}
+
+impl ErrorContext {
+    /// Create the context for a statement.
+    pub fn new(phase: Phase, sql: &str, cursor_id: u32) -> Self {
+        let mut sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        if let Some((index, _)) = sql.char_indices().nth(MAX_CONTEXT_SQL_CHARS) {
+            sql.truncate(index);
+            sql.push_str("...");
+        }
+        Self {
+            phase,
+            sql,
+            cursor_id,
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cursor_id != 0 {
+            write!(f, "{}, cursor {}: {}", self.phase, self.cursor_id, self.sql)
+        } else {
+            write!(f, "{}: {}", self.phase, self.sql)
+        }
+    }
+}
+
+/// Split an `ORA-NNNNN: text` line into its code and text.
+fn parse_error_line(line: &str) -> Option<(u32, &str)> {
+    let (prefix, text) = line.strip_prefix("ORA-")?.split_once(':')?;
+    Some((prefix.parse().ok()?, text.trim()))
 }
 
+fn context_suffix(context: &Option<Box<ErrorContext>>) -> String {
+    context
+        .as_ref()
+        .map(|c| format!(" ({})", c))
+        .unwrap_or_default()
+}
+
+/// Oracle error codes for violated integrity constraints.
+const CONSTRAINT_VIOLATION_CODES: [u32; 6] = [
+    1,    // unique constraint violated
+    1400, // cannot insert NULL
+    1407, // cannot update to NULL
+    2290, // check constraint violated
+    2291, // parent key not found
+    2292, // child record found
+];
+
+/// Oracle error codes for calls that gave up waiting.
+const TIMEOUT_CODES: [u32; 5] = [
+    54,    // resource busy and acquire with NOWAIT specified or timeout expired
+    1013,  // user requested cancel of current operation
+    3136,  // inbound connection timed out
+    12170, // TNS:connect timeout occurred
+    30006, // resource busy; acquire with WAIT timeout expired
+];
+
+/// Oracle error codes for statements whose parsed state was invalidated.
+const STALE_STATEMENT_CODES: [u32; 4] = [
+    1003, // no statement parsed
+    4061, // existing state of package has been invalidated
+    4065, // not executed, altered or dropped stored procedure
+    4068, // existing state of packages has been discarded
+];
+
+/// Oracle error codes meaning the session is gone and must be re-established.
+const SESSION_LOST_CODES: [u32; 9] = [
+    28,    // your session has been killed
+    1012,  // not logged on
+    3113,  // end-of-file on communication channel
+    3114,  // not connected to ORACLE
+    3135,  // connection lost contact
+    12537, // TNS:connection closed
+    12570, // TNS:packet reader failure
+    12571, // TNS:packet writer failure
+    12572, // TNS:packet reader failure
+];
+
 impl Error {
     /// Create a protocol error.
     pub fn protocol(message: impl Into<String>) -> Self {
@@ -111,6 +293,211 @@ impl Error {
         Self::Oracle {
             code,
             message: message.into(),
+            offset: 0,
+            row_count: 0,
+            context: None,
+        }
+    }
+
+    /// Attach the statement context to an Oracle error.
+    ///
+    /// Other errors are returned unchanged.
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Self::Cancelled { .. } => Self::Cancelled {
+                context: Some(Box::new(context)),
+            },
+            Self::Oracle {
+                code,
+                message,
+                offset,
+                row_count,
+                ..
+            } => Self::Oracle {
+                code,
+                message,
+                offset,
+                row_count,
+                context: Some(Box::new(context)),
+            },
+            other => other,
+        }
+    }
+
+    /// Get the Oracle error code (the number of `ORA-NNNNN`), if any.
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Self::Oracle { code, .. } => Some(*code),
+            Self::Cancelled { .. } => Some(1013),
+            _ => None,
+        }
+    }
+
+    /// Get the position in the statement text at which an Oracle error was
+    /// detected, if the server reported one.
+    pub fn offset(&self) -> Option<u32> {
+        match self {
+            Self::Oracle { offset, .. } if *offset > 0 => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Show where in `sql` an Oracle error was detected: the line holding
+    /// the error position, with a caret under it on the next line.
+    ///
+    /// `sql` must be the statement the error was raised for, as it was
+    /// sent (the SQL kept in `context` has its whitespace collapsed).
+    /// Returns `None` if the server reported no position, or one past the
+    /// end of `sql`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::Error;
+    ///
+    /// let sql = "SELECT *\nFROM no_such_table";
+    /// let error = Error::Oracle {
+    ///     code: 942,
+    ///     message: "table or view does not exist".to_string(),
+    ///     offset: 14,
+    ///     row_count: 0,
+    ///     context: None,
+    /// };
+    /// assert_eq!(
+    ///     error.sql_with_caret(sql).unwrap(),
+    ///     "line 2: FROM no_such_table\n             ^"
+    /// );
+    /// ```
+    pub fn sql_with_caret(&self, sql: &str) -> Option<String> {
+        let offset = self.offset()? as usize;
+        let (start, _) = sql.char_indices().nth(offset)?;
+        let line_start = sql[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = sql[start..].find('\n').map_or(sql.len(), |i| start + i);
+        let line = sql[line_start..line_end].trim_end_matches('\r');
+        let prefix = format!("line {}: ", sql[..line_start].matches('\n').count() + 1);
+
+        // Keep tabs so the caret lines up however they are displayed
+        let padding: String = sql[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        Some(format!(
+            "{}{}\n{}{}^",
+            prefix,
+            line,
+            " ".repeat(prefix.len()),
+            padding
+        ))
+    }
+
+    /// Get the number of rows processed before an Oracle error was raised.
+    pub fn row_count(&self) -> Option<u64> {
+        match self {
+            Self::Oracle { row_count, .. } => Some(*row_count),
+            _ => None,
+        }
+    }
+
+    /// Get the chain of Oracle errors in the message, outermost first.
+    ///
+    /// PL/SQL errors carry the original error followed by the `ORA-06512`
+    /// lines of the call stack, and some errors wrap the one that caused
+    /// them; each `ORA-NNNNN: text` line is returned as a code and its
+    /// text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::Error;
+    ///
+    /// let e = Error::oracle(
+    ///     1476,
+    ///     "divisor is equal to zero\nORA-06512: at line 1",
+    /// );
+    /// assert_eq!(
+    ///     e.error_chain(),
+    ///     [(1476, "divisor is equal to zero"), (6512, "at line 1")]
+    /// );
+    /// ```
+    pub fn error_chain(&self) -> Vec<(u32, &str)> {
+        let Self::Oracle { code, message, .. } = self else {
+            return Vec::new();
+        };
+        let mut lines = message.lines().map(str::trim).filter(|l| !l.is_empty());
+        let mut chain = Vec::new();
+        // The first line may repeat the prefix for the error's own code
+        if let Some(first) = lines.next() {
+            chain.push(parse_error_line(first).unwrap_or((*code, first)));
+        }
+        chain.extend(lines.filter_map(parse_error_line));
+        chain
+    }
+
+    /// Get the Oracle documentation page for an Oracle error.
+    pub fn help_url(&self) -> Option<String> {
+        self.code()
+            .map(|code| format!("https://docs.oracle.com/error-help/db/ora-{:05}/", code))
+    }
+
+    /// Check if this error is a violated integrity constraint (unique,
+    /// check, foreign key or NOT NULL).
+    pub fn is_constraint_violation(&self) -> bool {
+        self.code()
+            .is_some_and(|code| CONSTRAINT_VIOLATION_CODES.contains(&code))
+    }
+
+    /// Check if this error is a detected deadlock (`ORA-00060`).
+    pub fn is_deadlock(&self) -> bool {
+        self.code() == Some(60)
+    }
+
+    /// Check if this error means an operation gave up waiting: a connect or
+    /// call timeout, a cancelled call, or a lock wait that expired.
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Self::ConnectionTimeout { .. }
+            | Self::CallTimeout { .. }
+            | Self::Cancelled { .. }
+            | Self::PoolTimeout { .. } => true,
+            Self::Oracle { code, .. } => TIMEOUT_CODES.contains(code),
+            _ => false,
+        }
+    }
+
+    /// Check if the failed operation can succeed when retried.
+    ///
+    /// True for lost sessions (once reconnected), deadlocks and lock waits
+    /// that timed out; the work done by the failed call was rolled back or
+    /// never happened.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Self::ResponseTooLarge { .. } => false,
+            Self::Oracle {
+                code: 54 | 60 | 30006,
+                ..
+            } => true,
+            _ => self.is_connection_lost(),
+        }
+    }
+
+    /// Check if this error means state the statement depended on was
+    /// invalidated: package state discarded because a package was recompiled
+    /// (`ORA-04068`, `ORA-04061`, `ORA-04065`), or a cursor no longer parsed
+    /// (`ORA-01003`).
+    ///
+    /// The failed call did no work, and parsing the statement again picks
+    /// up the new state; `execute` and the query methods do that once by
+    /// themselves.
+    pub fn is_stale_statement(&self) -> bool {
+        self.code()
+            .is_some_and(|code| STALE_STATEMENT_CODES.contains(&code))
+    }
+
+    /// Get the statement context of an Oracle error, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Oracle { context, .. } | Self::Cancelled { context } => context.as_deref(),
+            _ => None,
         }
     }
 
@@ -120,4 +507,183 @@ impl Error {
             message: message.into(),
         }
     }
+
+    /// Check if this error means the session is lost.
+    ///
+    /// Network failures, responses abandoned for exceeding the maximum
+    /// response size and the Oracle errors reported for killed or
+    /// disconnected sessions leave the connection unusable; a new session
+    /// must be established.
+    pub fn is_connection_lost(&self) -> bool {
+        match self {
+            Self::Io(_) | Self::ConnectionClosed | Self::ResponseTooLarge { .. } => true,
+            Self::Oracle { code, .. } => SESSION_LOST_CODES.contains(code),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_connection_lost() {
+        assert!(Error::ConnectionClosed.is_connection_lost());
+        assert!(Error::Io(io::Error::from(io::ErrorKind::BrokenPipe)).is_connection_lost());
+        assert!(Error::oracle(3113, "end-of-file on communication channel").is_connection_lost());
+        assert!(!Error::oracle(942, "table or view does not exist").is_connection_lost());
+        assert!(!Error::protocol("bad packet").is_connection_lost());
+        assert!(Error::ResponseTooLarge { size: 10, max: 5 }.is_connection_lost());
+    }
+
+    fn error_at(offset: u32) -> Error {
+        Error::Oracle {
+            code: 904,
+            message: "invalid identifier".to_string(),
+            offset,
+            row_count: 0,
+            context: None,
+        }
+    }
+
+    #[test]
+    fn test_sql_with_caret() {
+        assert_eq!(
+            error_at(7).sql_with_caret("SELECT nope FROM dual").unwrap(),
+            "line 1: SELECT nope FROM dual\n               ^"
+        );
+        // Offsets count characters, and tabs are kept in the padding
+        assert_eq!(
+            error_at(14)
+                .sql_with_caret("SELECT 'é',\r\n\tnope\r\nFROM dual")
+                .unwrap(),
+            "line 2: \tnope\n        \t^"
+        );
+        assert_eq!(error_at(0).sql_with_caret("SELECT"), None);
+        assert_eq!(error_at(6).sql_with_caret("SELECT"), None);
+        assert_eq!(Error::ConnectionClosed.sql_with_caret("SELECT"), None);
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let e = Error::oracle(1, "unique constraint (APP.PK) violated");
+        assert!(e.is_constraint_violation());
+        assert!(!e.is_deadlock() && !e.is_timeout() && !e.is_recoverable());
+        assert_eq!(e.code(), Some(1));
+        assert_eq!(
+            e.help_url().as_deref(),
+            Some("https://docs.oracle.com/error-help/db/ora-00001/")
+        );
+
+        let e = Error::oracle(60, "deadlock detected while waiting for resource");
+        assert!(e.is_deadlock() && e.is_recoverable());
+        assert!(Error::oracle(30006, "resource busy").is_timeout());
+        assert!(Error::CallTimeout {
+            timeout: std::time::Duration::from_secs(1)
+        }
+        .is_timeout());
+        assert!(Error::oracle(3113, "end-of-file").is_recoverable());
+        assert!(!Error::ResponseTooLarge { size: 10, max: 5 }.is_recoverable());
+
+        let e = Error::protocol("bad packet");
+        assert_eq!((e.code(), e.offset(), e.row_count()), (None, None, None));
+        assert!(e.help_url().is_none());
+    }
+
+    #[test]
+    fn test_is_stale_statement() {
+        let e = Error::oracle(
+            4068,
+            "existing state of packages has been discarded\n\
+             ORA-04061: existing state of package body \"APP.PKG\" has been invalidated",
+        );
+        assert!(e.is_stale_statement() && !e.is_recoverable());
+        assert!(Error::oracle(1003, "no statement parsed").is_stale_statement());
+        assert!(!Error::oracle(942, "table or view does not exist").is_stale_statement());
+        assert!(!Error::ConnectionClosed.is_stale_statement());
+    }
+
+    #[test]
+    fn test_cancelled_error() {
+        let e = Error::Cancelled { context: None };
+        assert!(e.is_timeout() && !e.is_recoverable() && !e.is_connection_lost());
+        assert_eq!(e.code(), Some(1013));
+        let e = e.with_context(ErrorContext::new(Phase::Execute, "BEGIN work; END;", 3));
+        assert_eq!(
+            e.to_string(),
+            "ORA-01013: user requested cancel of current operation \
+             (execute, cursor 3: BEGIN work; END;)"
+        );
+        assert_eq!(e.context().map(|c| c.phase), Some(Phase::Execute));
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let e = Error::oracle(
+            6502,
+            "ORA-06502: PL/SQL: numeric or value error\nORA-06512: at \"APP.PKG\", line 12\n\
+             ORA-06512: at line 1\n",
+        );
+        assert_eq!(
+            e.error_chain(),
+            [
+                (6502, "PL/SQL: numeric or value error"),
+                (6512, "at \"APP.PKG\", line 12"),
+                (6512, "at line 1"),
+            ]
+        );
+        assert_eq!(
+            Error::oracle(942, "table or view does not exist").error_chain(),
+            [(942, "table or view does not exist")]
+        );
+        assert!(Error::ConnectionClosed.error_chain().is_empty());
+    }
+
+    #[test]
+    fn test_error_context_display() {
+        let e = Error::oracle(942, "table or view does not exist");
+        assert_eq!(e.to_string(), "ORA-00942: table or view does not exist");
+        assert!(e.context().is_none());
+
+        let context = ErrorContext::new(Phase::Execute, "SELECT *\n  FROM missing_table", 0);
+        let e = e.with_context(context);
+        assert_eq!(
+            e.to_string(),
+            "ORA-00942: table or view does not exist (execute: SELECT * FROM missing_table)"
+        );
+        assert_eq!(e.context().map(|c| c.phase), Some(Phase::Execute));
+
+        let e = Error::oracle(1013, "user requested cancel of current operation")
+            .with_context(ErrorContext::new(Phase::Fetch, "SELECT 1 FROM DUAL", 7));
+        assert!(e
+            .to_string()
+            .ends_with("(fetch, cursor 7: SELECT 1 FROM DUAL)"));
+
+        // Only Oracle errors carry context
+        let e = Error::ConnectionClosed.with_context(ErrorContext::new(Phase::Fetch, "x", 1));
+        assert!(e.context().is_none());
+    }
+
+    #[test]
+    fn test_error_context_truncates_sql() {
+        let sql = format!("SELECT '{}' FROM DUAL", "é".repeat(200));
+        let context = ErrorContext::new(Phase::Parse, &sql, 0);
+        assert_eq!(context.sql.chars().count(), 103);
+        assert!(context.sql.starts_with("SELECT 'éé"));
+        assert!(context.sql.ends_with("..."));
+    }
+
+    #[test]
+    fn test_statement_too_large_display() {
+        let e = Error::StatementTooLarge {
+            length: 3_000_000_000,
+            max: 2_147_483_647,
+        };
+        assert_eq!(
+            e.to_string(),
+            "SQL statement is 3000000000 bytes, exceeding the maximum of 2147483647 bytes"
+        );
+        assert!(!e.is_connection_lost());
+    }
 }
@@ -0,0 +1,159 @@
+//! Hooks run on SQL text before it is sent.
+//!
+//! A `SqlInterceptor` set with `Connection::set_sql_interceptor` sees every
+//! statement the connection parses: queries, cursors, `execute`,
+//! `execute_many`, `describe` and pipelines. It can log the statement,
+//! refuse it, or send rewritten text in its place, such as the statement
+//! with a tag comment for a SQL firewall or tracing.
+
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::protocol::statement::{Statement, StatementType};
+use crate::protocol::types::OracleValue;
+
+/// A statement about to be sent, as seen by a `SqlInterceptor`.
+#[derive(Debug, Clone, Copy)]
+pub struct SqlCall<'a> {
+    statement: &'a Statement,
+    binds: &'a [Vec<OracleValue>],
+}
+
+impl<'a> SqlCall<'a> {
+    /// Get the SQL text.
+    pub fn sql(&self) -> &'a str {
+        self.statement.sql()
+    }
+
+    /// Get the statement type.
+    pub fn statement_type(&self) -> StatementType {
+        self.statement.statement_type()
+    }
+
+    /// Get the names of the statement's placeholders, in order.
+    pub fn bind_names(&self) -> &'a [String] {
+        self.statement.bind_names()
+    }
+
+    /// Get the rows of bind values: none for queries and statements
+    /// without binds, one for `execute`, one per row for `execute_many`.
+    pub fn binds(&self) -> &'a [Vec<OracleValue>] {
+        self.binds
+    }
+}
+
+/// Hook run on each statement before it is sent.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::{Error, Result, SqlCall, SqlInterceptor, StatementType};
+///
+/// /// Tag statements for tracing and refuse DDL.
+/// struct Guard;
+///
+/// impl SqlInterceptor for Guard {
+///     fn intercept(&self, call: &SqlCall<'_>) -> Result<Option<String>> {
+///         if call.statement_type() == StatementType::Ddl {
+///             return Err(Error::StatementRejected {
+///                 message: "DDL is not allowed".to_string(),
+///             });
+///         }
+///         Ok(Some(format!("/* app=billing */ {}", call.sql())))
+///     }
+/// }
+/// ```
+pub trait SqlInterceptor: Send + Sync {
+    /// Inspect a statement before it is sent.
+    ///
+    /// Return `Ok(None)` to send it unchanged, `Ok(Some(sql))` to send
+    /// `sql` instead, or an error (usually `Error::StatementRejected`) to
+    /// fail the call without contacting the server. Rewritten text must
+    /// keep the statement's placeholders, which the bind values fill.
+    fn intercept(&self, call: &SqlCall<'_>) -> Result<Option<String>>;
+}
+
+/// Parse a statement, passing it through the interceptor if there is one.
+pub(crate) fn prepare_statement(
+    interceptor: Option<&Arc<dyn SqlInterceptor>>,
+    sql: &str,
+    binds: &[Vec<OracleValue>],
+) -> Result<Statement> {
+    let statement = Statement::parse(sql);
+    let Some(interceptor) = interceptor else {
+        return Ok(statement);
+    };
+    let call = SqlCall {
+        statement: &statement,
+        binds,
+    };
+    Ok(match interceptor.intercept(&call)? {
+        Some(sql) => Statement::parse(&sql),
+        None => statement,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::sync::Mutex;
+
+    /// Records what it sees and appends a comment to queries.
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<(String, Vec<String>, usize)>>,
+    }
+
+    impl SqlInterceptor for Recorder {
+        fn intercept(&self, call: &SqlCall<'_>) -> Result<Option<String>> {
+            self.seen.lock().unwrap().push((
+                call.sql().to_string(),
+                call.bind_names().to_vec(),
+                call.binds().len(),
+            ));
+            match call.statement_type() {
+                StatementType::Query => Ok(Some(format!("{} -- traced", call.sql()))),
+                StatementType::Ddl => Err(Error::StatementRejected {
+                    message: "no DDL".to_string(),
+                }),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_prepare_statement() {
+        let recorder = Arc::new(Recorder::default());
+        let interceptor: Arc<dyn SqlInterceptor> = recorder.clone();
+
+        let stmt = prepare_statement(Some(&interceptor), "SELECT 1 FROM DUAL", &[]).unwrap();
+        assert_eq!(stmt.sql(), "SELECT 1 FROM DUAL -- traced");
+        assert!(stmt.is_query());
+
+        let rows = [vec![OracleValue::Integer(1)], vec![OracleValue::Integer(2)]];
+        let stmt =
+            prepare_statement(Some(&interceptor), "DELETE FROM t WHERE id = :id", &rows).unwrap();
+        assert_eq!(stmt.sql(), "DELETE FROM t WHERE id = :id");
+
+        assert!(matches!(
+            prepare_statement(Some(&interceptor), "DROP TABLE t", &[]),
+            Err(Error::StatementRejected { .. })
+        ));
+        assert_eq!(
+            *recorder.seen.lock().unwrap(),
+            [
+                ("SELECT 1 FROM DUAL".to_string(), vec![], 0),
+                (
+                    "DELETE FROM t WHERE id = :id".to_string(),
+                    vec!["ID".to_string()],
+                    2
+                ),
+                ("DROP TABLE t".to_string(), vec![], 0),
+            ]
+        );
+
+        let stmt = prepare_statement(None, "DROP TABLE t", &[]).unwrap();
+        assert!(stmt.is_ddl());
+    }
+}
@@ -22,6 +22,9 @@
 //!         println!("Connected to Oracle {}.{}.{}.{}.{}",
 //!             version.0, version.1, version.2, version.3, version.4);
 //!     }
+//!     if let Some(banner) = conn.server_banner() {
+//!         println!("{}", banner);
+//!     }
 //!
 //!     // Close connection
 //!     conn.close().await?;
@@ -30,14 +33,60 @@
 //! }
 //! ```
 
+pub mod actor;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod config;
 pub mod connection;
 pub mod cursor;
 pub mod error;
+pub mod export;
+pub mod intercept;
+pub mod options;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod pipeline;
+pub mod pool;
 pub mod protocol;
+pub mod querylog;
+pub mod reconnect;
+pub mod shared;
+pub mod stats;
+pub mod table;
+pub mod url;
 
 // Re-export main types
-pub use connection::{Connection, QueryResult};
+pub use actor::ConnectionHandle;
+pub use config::ConnectBuilder;
+pub use connection::{BatchResult, Connection, ConnectionHealth, ExecuteResult, QueryResult};
 pub use cursor::{Cursor, CursorStreamExt, RowCursor};
-pub use error::{Error, Result};
-pub use protocol::connect::ConnectParams;
-pub use protocol::types::{Column, ColumnInfo, ColumnMetadata, OracleType, OracleValue, Row};
+pub use error::{Error, ErrorContext, Phase, Result};
+pub use export::{CursorExportExt, ExportOptions};
+pub use intercept::{SqlCall, SqlInterceptor};
+pub use options::{FetchAs, QueryOptions};
+#[cfg(feature = "derive")]
+pub use oracle_thin_rs_derive::FromRow;
+pub use pipeline::{Pipeline, PipelineResult};
+pub use pool::{Pool, PoolOptions, PooledConnection, SessionCallback};
+pub use protocol::auth::{AuthMethod, Privilege};
+pub use protocol::connect::{Address, ConnectParams, Protocol};
+pub use protocol::control::Notification;
+pub use protocol::features::ServerFeatures;
+pub use protocol::proxy::{Proxy, ProxyKind};
+pub use protocol::response::{BatchError, ReplayContext, Warning};
+pub use protocol::session::SessionSettings;
+pub use protocol::statement::{BindInfo, Statement, StatementType};
+#[cfg(feature = "tls")]
+pub use protocol::tls::{TlsConfig, Wallet};
+pub use protocol::types::{
+    CharsetForm, CharsetId, Column, ColumnInfo, ColumnMetadata, FromRow, FromSql, Guid, Lob,
+    OracleType, OracleTypeNum, OracleValue, Row, RowId, Scn, ToSql, Vector, VectorData,
+};
+#[cfg(feature = "tracing")]
+pub use querylog::TracingQueryLogger;
+pub use querylog::{QueryLog, QueryLogger};
+pub use reconnect::{ReconnectEvent, ReconnectPolicy, ReconnectingConnection};
+pub use shared::SharedConnection;
+pub use stats::ConnectionStats;
+pub use table::TableOptions;
+pub use url::DatabaseUrl;
@@ -0,0 +1,208 @@
+//! Per-query fetch tuning.
+
+use std::time::Duration;
+
+use crate::protocol::constants::{
+    ORA_TYPE_NUM_BINARY_DOUBLE, ORA_TYPE_NUM_LONG, ORA_TYPE_NUM_LONG_RAW, ORA_TYPE_NUM_NUMBER,
+    ORA_TYPE_NUM_VARCHAR, TNS_MAX_LONG_LENGTH,
+};
+
+/// Options controlling how many rows and bytes a query fetches per round trip.
+///
+/// Larger values trade memory for fewer round trips.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::QueryOptions;
+///
+/// // Small first batch for a quick first row, large batches afterwards
+/// let options = QueryOptions::new()
+///     .with_prefetch_rows(10)
+///     .with_fetch_size(1000);
+/// assert_eq!(options.prefetch_rows, 10);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// Rows returned with the execute round trip.
+    pub prefetch_rows: u32,
+    /// Rows requested by each subsequent fetch round trip.
+    pub fetch_size: u32,
+    /// Largest LONG or LONG RAW value, in bytes, the server returns per column.
+    ///
+    /// LONG values arrive whole, in length chunks spread over as many
+    /// packets as they need, and are held in memory: there is no streaming
+    /// reader for them. A response, with every value in it, is limited to
+    /// the connection's maximum response size (1 GiB by default, see
+    /// `ConnectParams::with_max_response_size`) and fails with
+    /// `Error::ResponseTooLarge` beyond it; a cursor with a fetch size of 1
+    /// holds one row's values at a time.
+    pub max_long_size: u32,
+    /// Most memory, in bytes, a cursor holds in buffered rows (default:
+    /// unlimited).
+    pub max_buffered_bytes: Option<usize>,
+    /// Longest the execute and each fetch round trip may take, in place of
+    /// the connection's call timeout (default: the call timeout).
+    pub timeout: Option<Duration>,
+    /// Columns converted by the server before they are sent, by position
+    /// (0-based), as set with `with_fetch_as`.
+    pub defines: Vec<(usize, FetchAs)>,
+}
+
+/// Type the server converts a column's values to before sending them.
+///
+/// Conversions follow Oracle's implicit conversion rules, so they are the
+/// ones a `CAST` or `TO_CHAR` would make; text is formatted with the
+/// session's NLS settings (for example, `NLS_TIMESTAMP_FORMAT` for
+/// timestamps, set with `ALTER SESSION`). Values that do not convert fail
+/// the fetch with the server's error, such as ORA-01722.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchAs {
+    /// VARCHAR2 text, for example a NUMBER, DATE, TIMESTAMP or INTERVAL
+    /// formatted as the server formats it.
+    String,
+    /// NUMBER, for example from BINARY_DOUBLE or numeric text.
+    Number,
+    /// BINARY_DOUBLE.
+    BinaryDouble,
+    /// LONG: the whole text of a CLOB, sent inline instead of as a locator.
+    Long,
+    /// LONG RAW: the whole bytes of a BLOB, sent inline instead of as a
+    /// locator.
+    LongRaw,
+}
+
+impl FetchAs {
+    /// Get the Oracle type number the column is defined with.
+    pub(crate) fn ora_type_num(self) -> u16 {
+        match self {
+            Self::String => ORA_TYPE_NUM_VARCHAR,
+            Self::Number => ORA_TYPE_NUM_NUMBER,
+            Self::BinaryDouble => ORA_TYPE_NUM_BINARY_DOUBLE,
+            Self::Long => ORA_TYPE_NUM_LONG,
+            Self::LongRaw => ORA_TYPE_NUM_LONG_RAW,
+        }
+    }
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        Self {
+            prefetch_rows: 100,
+            fetch_size: 100,
+            max_long_size: TNS_MAX_LONG_LENGTH,
+            max_buffered_bytes: None,
+            timeout: None,
+            defines: Vec::new(),
+        }
+    }
+}
+
+impl QueryOptions {
+    /// Create the default options (100 rows per round trip, unlimited LONG size).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of rows returned with the execute round trip.
+    pub fn with_prefetch_rows(mut self, prefetch_rows: u32) -> Self {
+        self.prefetch_rows = prefetch_rows;
+        self
+    }
+
+    /// Set the number of rows requested by each fetch round trip.
+    pub fn with_fetch_size(mut self, fetch_size: u32) -> Self {
+        self.fetch_size = fetch_size;
+        self
+    }
+
+    /// Set the largest LONG or LONG RAW value returned per column.
+    ///
+    /// Longer values are truncated by the server.
+    pub fn with_max_long_size(mut self, max_long_size: u32) -> Self {
+        self.max_long_size = max_long_size;
+        self
+    }
+
+    /// Cap the memory a cursor holds in buffered rows.
+    ///
+    /// Once a fetch takes a cursor past the cap, later fetches request fewer
+    /// rows and `fetch_all` stops with `Error::BufferLimitExceeded`, leaving
+    /// the rows to be streamed with `next()`. Memory use stays within the cap
+    /// plus one fetch (and the rows prefetched by the execute).
+    pub fn with_max_buffered_bytes(mut self, max_buffered_bytes: usize) -> Self {
+        self.max_buffered_bytes = Some(max_buffered_bytes);
+        self
+    }
+
+    /// Bound the execute and each fetch round trip of this query.
+    ///
+    /// Overrides `Connection::set_call_timeout` for the query and its
+    /// cursor; a zero duration means no limit. A round trip that runs over
+    /// is interrupted like one cut short by the call timeout, leaving the
+    /// connection usable, and fails with `Error::CallTimeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Have the server convert the values of a column (0-based) before
+    /// sending them.
+    ///
+    /// The query is executed, then defined with the conversions and
+    /// executed again to fetch the rows, so it takes one more round trip
+    /// than a query fetching the columns as they are; the rows and column
+    /// metadata returned describe the converted values. Other columns keep
+    /// their types, except ROWIDs, fetched as text; LOB, JSON, VECTOR and
+    /// object columns cannot be fetched this way, so CLOBs and BLOBs must
+    /// be fetched as `Long` and `LongRaw` alongside converted columns. A
+    /// position past the last column fails the query with
+    /// `Error::ColumnIndexOutOfBounds`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::{FetchAs, QueryOptions};
+    ///
+    /// // Amounts as the server formats them, and a CLOB's text inline
+    /// let options = QueryOptions::new()
+    ///     .with_fetch_as(0, FetchAs::String)
+    ///     .with_fetch_as(2, FetchAs::Long);
+    /// assert_eq!(options.defines.len(), 2);
+    /// ```
+    pub fn with_fetch_as(mut self, column: usize, fetch_as: FetchAs) -> Self {
+        self.defines.retain(|&(c, _)| c != column);
+        self.defines.push((column, fetch_as));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_options_builder() {
+        let options = QueryOptions::new()
+            .with_prefetch_rows(1)
+            .with_fetch_size(500)
+            .with_max_long_size(4096)
+            .with_max_buffered_bytes(1 << 20)
+            .with_timeout(Duration::from_secs(3))
+            .with_fetch_as(1, FetchAs::Number)
+            .with_fetch_as(1, FetchAs::String);
+        assert_eq!(
+            options,
+            QueryOptions {
+                prefetch_rows: 1,
+                fetch_size: 500,
+                max_long_size: 4096,
+                max_buffered_bytes: Some(1 << 20),
+                timeout: Some(Duration::from_secs(3)),
+                defines: vec![(1, FetchAs::String)],
+            }
+        );
+        assert_eq!(QueryOptions::new().max_long_size, TNS_MAX_LONG_LENGTH);
+        assert_eq!(QueryOptions::new().timeout, None);
+    }
+}
@@ -0,0 +1,211 @@
+//! OpenTelemetry spans for connects, statements and fetches.
+//!
+//! With the `otel` feature, each connection attempt, statement and cursor
+//! fetch is reported as a client span to the global tracer provider
+//! (`opentelemetry::global`), as a child of the current context, so the
+//! traces of an instrumented application include the time spent in the
+//! database without instrumenting the calls themselves. Attributes follow
+//! the OpenTelemetry database semantic conventions: `db.system`,
+//! `db.name`, `db.user`, `db.statement`, `db.operation`, `net.peer.name`
+//! and `net.peer.port`. Like `QueryLog` entries, statements have their
+//! literals replaced with `?`, and bind values are never recorded.
+
+use std::time::{Duration, SystemTime};
+
+use opentelemetry::global;
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+use opentelemetry::KeyValue;
+
+use crate::error::{Error, Result};
+use crate::protocol::connect::ConnectParams;
+use crate::protocol::statement::{leading_keyword, redact_literals, Statement};
+
+/// Name of the tracer the spans are created with.
+pub const TRACER_NAME: &str = "oracle-thin-rs";
+
+/// The server and session a connection's spans are attributed to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Peer {
+    host: String,
+    port: u16,
+    service_name: String,
+    user: String,
+}
+
+impl Peer {
+    /// Describe a connection made with `params` as `user`.
+    pub(crate) fn new(params: &ConnectParams, user: &str) -> Self {
+        Self {
+            host: params.host.clone(),
+            port: params.port,
+            service_name: params.service_name.clone(),
+            user: user.to_string(),
+        }
+    }
+
+    /// Get the attributes shared by every span of the connection.
+    fn attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = vec![KeyValue::new("db.system", "oracle")];
+        if !self.host.is_empty() {
+            attributes.push(KeyValue::new("net.peer.name", self.host.clone()));
+            attributes.push(KeyValue::new("net.peer.port", i64::from(self.port)));
+        }
+        if !self.service_name.is_empty() {
+            attributes.push(KeyValue::new("db.name", self.service_name.clone()));
+        }
+        if !self.user.is_empty() {
+            attributes.push(KeyValue::new("db.user", self.user.clone()));
+        }
+        attributes
+    }
+}
+
+/// Report a connection attempt started at `started`.
+pub(crate) fn record_connect<T>(peer: &Peer, started: SystemTime, result: &Result<T>) {
+    record(
+        "connect".to_string(),
+        peer.attributes(),
+        started,
+        result.as_ref().err(),
+    );
+}
+
+/// Report a statement that took `duration`, ending now, with the rows it
+/// returned or affected.
+pub(crate) fn record_statement(
+    peer: &Peer,
+    statement: &Statement,
+    duration: Duration,
+    outcome: std::result::Result<u64, &Error>,
+) {
+    let operation = leading_keyword(statement.sql()).unwrap_or_default();
+    let mut attributes = peer.attributes();
+    attributes.push(KeyValue::new(
+        "db.statement",
+        redact_literals(statement.sql()),
+    ));
+    attributes.push(KeyValue::new("db.operation", operation.clone()));
+    if let Ok(rows) = outcome {
+        attributes.push(KeyValue::new("db.oracle.rows", rows as i64));
+    }
+    let started = SystemTime::now()
+        .checked_sub(duration)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let name = if operation.is_empty() {
+        "statement".to_string()
+    } else {
+        operation
+    };
+    record(name, attributes, started, outcome.err());
+}
+
+/// Report a fetch from the cursor of `sql` started at `started`.
+pub(crate) fn record_fetch(
+    peer: &Peer,
+    sql: &str,
+    started: SystemTime,
+    outcome: std::result::Result<u64, &Error>,
+) {
+    let mut attributes = peer.attributes();
+    attributes.push(KeyValue::new("db.statement", redact_literals(sql)));
+    attributes.push(KeyValue::new("db.operation", "FETCH"));
+    if let Ok(rows) = outcome {
+        attributes.push(KeyValue::new("db.oracle.rows", rows as i64));
+    }
+    record("FETCH".to_string(), attributes, started, outcome.err());
+}
+
+/// Create a client span from `started` to now, as a child of the current
+/// context, marked as failed if there is an error.
+fn record(name: String, attributes: Vec<KeyValue>, started: SystemTime, error: Option<&Error>) {
+    let tracer = global::tracer(TRACER_NAME);
+    let mut span = tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Client)
+        .with_start_time(started)
+        .with_attributes(attributes)
+        .start(&tracer);
+    if let Some(error) = error {
+        if let Some(code) = error.code() {
+            span.set_attribute(KeyValue::new(
+                "db.response.status_code",
+                format!("ORA-{:05}", code),
+            ));
+        }
+        span.set_status(Status::error(error.to_string()));
+    }
+    span.end();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{TraceContextExt, TracerProvider};
+    use opentelemetry::{Context, Value};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+
+    fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.clone())
+    }
+
+    #[test]
+    fn test_statement_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        global::set_tracer_provider(provider.clone());
+
+        let params = ConnectParams::new("db.example.com", 1522, "SALES");
+        let peer = Peer::new(&params, "app");
+        let stmt = Statement::parse("SELECT name FROM users WHERE id = 42");
+
+        // Spans are children of the application's current span
+        let parent = provider.tracer("app").start("handle request");
+        let parent_id = parent.span_context().span_id();
+        let cx = Context::current_with_span(parent);
+        {
+            let _guard = cx.clone().attach();
+            record_statement(&peer, &stmt, Duration::from_millis(5), Ok(1));
+            let error = Error::oracle(942, "ORA-00942: table or view does not exist");
+            record_fetch(&peer, stmt.sql(), SystemTime::now(), Err(&error));
+        }
+        cx.span().end();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        // Other tests may report spans at the same time
+        let child = |name: &str| {
+            spans
+                .iter()
+                .find(|s| s.name == name && s.parent_span_id == parent_id)
+                .unwrap()
+        };
+        let select = child("SELECT");
+        assert_eq!(select.span_kind, SpanKind::Client);
+        assert_eq!(attribute(select, "db.system"), Some("oracle".into()));
+        assert_eq!(
+            attribute(select, "db.statement"),
+            Some("SELECT name FROM users WHERE id = ?".into())
+        );
+        assert_eq!(
+            attribute(select, "net.peer.name"),
+            Some("db.example.com".into())
+        );
+        assert_eq!(attribute(select, "net.peer.port"), Some(1522i64.into()));
+        assert_eq!(attribute(select, "db.name"), Some("SALES".into()));
+        assert_eq!(attribute(select, "db.oracle.rows"), Some(1i64.into()));
+        assert!(
+            select.end_time.duration_since(select.start_time).unwrap() >= Duration::from_millis(5)
+        );
+
+        let fetch = child("FETCH");
+        assert!(matches!(fetch.status, Status::Error { .. }));
+        assert_eq!(
+            attribute(fetch, "db.response.status_code"),
+            Some("ORA-00942".into())
+        );
+    }
+}
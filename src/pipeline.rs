@@ -0,0 +1,142 @@
+//! Pipelines: several statements sent in one round trip.
+
+use crate::connection::{Connection, ExecuteResult, QueryResult};
+use crate::error::Result;
+use crate::options::QueryOptions;
+use crate::protocol::types::OracleValue;
+
+/// One operation of a pipeline.
+#[derive(Debug, Clone)]
+pub(crate) enum PipelineOp {
+    Query {
+        sql: String,
+        options: QueryOptions,
+    },
+    Execute {
+        sql: String,
+        params: Vec<OracleValue>,
+    },
+}
+
+impl PipelineOp {
+    pub(crate) fn sql(&self) -> &str {
+        match self {
+            PipelineOp::Query { sql, .. } | PipelineOp::Execute { sql, .. } => sql,
+        }
+    }
+}
+
+/// Result of one pipeline operation.
+#[derive(Debug)]
+pub enum PipelineResult {
+    /// Result of an operation added with [`Pipeline::add_query`].
+    Query(QueryResult),
+    /// Result of an operation added with [`Pipeline::add_execute`].
+    Execute(ExecuteResult),
+}
+
+impl PipelineResult {
+    /// The query result, if this is one.
+    pub fn into_query(self) -> Option<QueryResult> {
+        match self {
+            PipelineResult::Query(result) => Some(result),
+            PipelineResult::Execute(_) => None,
+        }
+    }
+
+    /// The execute result, if this is one.
+    pub fn into_execute(self) -> Option<ExecuteResult> {
+        match self {
+            PipelineResult::Execute(result) => Some(result),
+            PipelineResult::Query(_) => None,
+        }
+    }
+}
+
+/// Statements queued to run together, created by [`Connection::pipeline`].
+///
+/// On Oracle Database 23ai every operation is sent in a single round trip;
+/// older servers run them one after another. Each operation gets its own
+/// result, so one failing statement does not hide the others.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(conn: &mut oracle_thin_rs::Connection) -> oracle_thin_rs::Result<()> {
+/// use oracle_thin_rs::OracleValue;
+///
+/// let results = conn
+///     .pipeline()
+///     .add_execute("INSERT INTO t (id) VALUES (:1)", vec![OracleValue::Number("1".into())])
+///     .add_query("SELECT COUNT(*) FROM t")
+///     .run()
+///     .await?;
+/// for result in results {
+///     match result {
+///         Ok(result) => println!("{:?}", result),
+///         Err(e) => println!("failed: {}", e),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[must_use = "a pipeline does nothing until it is run"]
+pub struct Pipeline<'conn> {
+    conn: &'conn mut Connection,
+    ops: Vec<PipelineOp>,
+}
+
+impl<'conn> Pipeline<'conn> {
+    pub(crate) fn new(conn: &'conn mut Connection) -> Self {
+        Self {
+            conn,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Queue a query using the default fetch options.
+    pub fn add_query(self, sql: impl Into<String>) -> Self {
+        self.add_query_with_options(sql, QueryOptions::default())
+    }
+
+    /// Queue a query with explicit fetch options.
+    ///
+    /// Only the rows of the first batch (`prefetch_rows`) are returned;
+    /// `more_rows` tells whether the query had more.
+    pub fn add_query_with_options(mut self, sql: impl Into<String>, options: QueryOptions) -> Self {
+        self.ops.push(PipelineOp::Query {
+            sql: sql.into(),
+            options,
+        });
+        self
+    }
+
+    /// Queue a DML, DDL or PL/SQL statement with positional binds.
+    pub fn add_execute(mut self, sql: impl Into<String>, params: Vec<OracleValue>) -> Self {
+        self.ops.push(PipelineOp::Execute {
+            sql: sql.into(),
+            params,
+        });
+        self
+    }
+
+    /// Number of queued operations.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Whether no operation is queued.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Run every queued operation, returning one result per operation in
+    /// the order they were added.
+    ///
+    /// # Errors
+    /// Returns an error, instead of per-operation results, if the connection
+    /// is lost or the call times out.
+    pub async fn run(self) -> Result<Vec<Result<PipelineResult>>> {
+        self.conn.run_pipeline(&self.ops).await
+    }
+}
@@ -0,0 +1,679 @@
+//! Pool of sessions shared between tasks, with session tagging.
+//!
+//! `Pool` opens sessions on demand, up to a maximum, and lends them out as
+//! `PooledConnection`s that go back to the pool when dropped. Like
+//! python-oracledb's pools, a session can be returned with a tag describing
+//! the state it was left in (NLS settings, current schema, a tenant).
+//! `acquire_tagged` prefers an idle session with the requested tag; any
+//! other session is handed to the session callback first, which applies
+//! the state the tag stands for, typically with `ALTER SESSION` statements.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::connection::Connection;
+use crate::error::{Error, Result};
+use crate::protocol::connect::ConnectParams;
+
+/// Callback preparing a session for a requested tag.
+///
+/// It is given the session and the tag it was acquired with, and runs for
+/// every new session and for every idle session whose tag differs from the
+/// requested one.
+pub type SessionCallback = Arc<
+    dyn for<'c> Fn(&'c mut Connection, Option<&'c str>) -> BoxFuture<'c, Result<()>> + Send + Sync,
+>;
+
+/// Sizing and session-matching options for a `Pool`.
+#[derive(Clone)]
+pub struct PoolOptions {
+    /// Sessions opened when the pool is created.
+    pub min_size: usize,
+    /// Most sessions open at once.
+    pub max_size: usize,
+    /// Longest `acquire` waits for a session to be released (`None` waits
+    /// indefinitely).
+    pub acquire_timeout: Option<Duration>,
+    /// Idle time after which a session is pinged before it is handed out,
+    /// so a dropped session is replaced instead of failing the caller.
+    pub ping_interval: Option<Duration>,
+    /// Whether a session with another tag may be handed out when none has
+    /// the requested tag, rather than opening a new one.
+    pub match_any_tag: bool,
+    /// Callback preparing sessions for the requested tag.
+    session_callback: Option<SessionCallback>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 1,
+            max_size: 2,
+            acquire_timeout: None,
+            ping_interval: Some(Duration::from_secs(60)),
+            match_any_tag: false,
+            session_callback: None,
+        }
+    }
+}
+
+impl PoolOptions {
+    /// Create the default options (python-oracledb's defaults: one session
+    /// up front, at most two, idle sessions pinged after 60 seconds).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of sessions opened when the pool is created.
+    pub fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the most sessions open at once.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Fail `acquire` with `Error::PoolTimeout` after waiting `timeout` for
+    /// a session to be released.
+    pub fn with_acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = Some(timeout);
+        self
+    }
+
+    /// Ping sessions idle for `interval` before handing them out (`None`
+    /// never pings).
+    pub fn with_ping_interval(mut self, interval: Option<Duration>) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Hand out a session with another tag when none has the requested
+    /// one, instead of opening a new session.
+    pub fn with_match_any_tag(mut self, match_any_tag: bool) -> Self {
+        self.match_any_tag = match_any_tag;
+        self
+    }
+
+    /// Set the callback preparing sessions for the requested tag.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::PoolOptions;
+    ///
+    /// // Tags are tenant schemas
+    /// let options = PoolOptions::new().with_session_callback(|conn, tag| {
+    ///     Box::pin(async move {
+    ///         if let Some(schema) = tag {
+    ///             let sql = format!("ALTER SESSION SET CURRENT_SCHEMA = {}", schema);
+    ///             conn.execute(&sql, &[]).await?;
+    ///         }
+    ///         Ok(())
+    ///     })
+    /// });
+    /// assert!(options.session_callback().is_some());
+    /// ```
+    pub fn with_session_callback<F>(mut self, callback: F) -> Self
+    where
+        F: for<'c> Fn(&'c mut Connection, Option<&'c str>) -> BoxFuture<'c, Result<()>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.session_callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Get the session callback, if any.
+    pub fn session_callback(&self) -> Option<&SessionCallback> {
+        self.session_callback.as_ref()
+    }
+}
+
+impl fmt::Debug for PoolOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolOptions")
+            .field("min_size", &self.min_size)
+            .field("max_size", &self.max_size)
+            .field("acquire_timeout", &self.acquire_timeout)
+            .field("ping_interval", &self.ping_interval)
+            .field("match_any_tag", &self.match_any_tag)
+            .field("session_callback", &self.session_callback.is_some())
+            .finish()
+    }
+}
+
+/// A pool of sessions to one database, as one user.
+///
+/// Clones are cheap handles to the same pool. Sessions are opened when no
+/// idle one can be used and fewer than `max_size` are open; beyond that,
+/// `acquire` waits for a session to be released.
+///
+/// Sessions go back to the pool as they are: commit or roll back before
+/// dropping a `PooledConnection`.
+///
+/// # Example
+///
+/// ```no_run
+/// use oracle_thin_rs::{Pool, PoolOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let options = PoolOptions::new()
+///         .with_max_size(8)
+///         .with_session_callback(|conn, tag| {
+///             Box::pin(async move {
+///                 if tag == Some("iso-dates") {
+///                     conn.execute("ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'", &[])
+///                         .await?;
+///                 }
+///                 Ok(())
+///             })
+///         });
+///     let pool = Pool::connect("localhost:1521/FREEPDB1", "user", "password", options).await?;
+///
+///     let mut conn = pool.acquire_tagged("iso-dates").await?;
+///     let row = conn.query_one("SELECT TO_CHAR(SYSDATE) FROM DUAL").await?;
+///     println!("{:?}", row);
+///     // Returned with its tag, so the next acquire_tagged("iso-dates")
+///     // reuses it without running the callback
+///     drop(conn);
+///
+///     pool.close();
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Pool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    /// Parameters used to open sessions.
+    params: ConnectParams,
+    /// Database username.
+    username: String,
+    /// Database password.
+    password: String,
+    /// Pool options.
+    options: PoolOptions,
+    /// One permit per session that may be lent out.
+    permits: Arc<Semaphore>,
+    /// Idle sessions and session count.
+    state: Mutex<PoolState<Connection>>,
+}
+
+impl Pool {
+    /// Create a pool, opening `min_size` sessions.
+    ///
+    /// `conn_str` has the same format as for `Connection::connect`.
+    pub async fn connect(
+        conn_str: &str,
+        username: &str,
+        password: &str,
+        options: PoolOptions,
+    ) -> Result<Self> {
+        let params = ConnectParams::parse(conn_str)?;
+        Self::connect_with_params(params, username, password, options).await
+    }
+
+    /// Create a pool with explicit connection parameters, opening
+    /// `min_size` sessions.
+    pub async fn connect_with_params(
+        params: ConnectParams,
+        username: &str,
+        password: &str,
+        options: PoolOptions,
+    ) -> Result<Self> {
+        if options.max_size == 0 || options.min_size > options.max_size {
+            return Err(Error::protocol(format!(
+                "invalid pool size: min {} and max {}",
+                options.min_size, options.max_size
+            )));
+        }
+        let pool = Self {
+            inner: Arc::new(PoolInner {
+                permits: Arc::new(Semaphore::new(options.max_size)),
+                state: Mutex::new(PoolState::new()),
+                params,
+                username: username.to_string(),
+                password: password.to_string(),
+                options,
+            }),
+        };
+        for _ in 0..pool.inner.options.min_size {
+            let conn = pool.open_session().await?;
+            let mut state = pool.inner.lock();
+            state.open += 1;
+            state.idle.push_back(IdleSession {
+                session: conn,
+                tag: None,
+                fresh: true,
+            });
+        }
+        Ok(pool)
+    }
+
+    /// Acquire a session, preferring an untagged one.
+    ///
+    /// # Errors
+    /// Returns `Error::PoolTimeout` if the acquire timeout passes first, and
+    /// `Error::ConnectionClosed` once the pool is closed.
+    pub async fn acquire(&self) -> Result<PooledConnection> {
+        self.acquire_session(None).await
+    }
+
+    /// Acquire a session prepared for `tag`.
+    ///
+    /// An idle session with the tag is used as it is. Otherwise an untagged
+    /// session, a session with any tag (with `match_any_tag`), or a new
+    /// session is handed to the session callback, and gets the tag once the
+    /// callback succeeds. Without a callback, check `PooledConnection::tag`
+    /// to see whether the session has the requested tag.
+    ///
+    /// # Errors
+    /// Returns `Error::PoolTimeout` if the acquire timeout passes first, the
+    /// error of a failed session callback (the session is then closed), and
+    /// `Error::ConnectionClosed` once the pool is closed.
+    pub async fn acquire_tagged(&self, tag: &str) -> Result<PooledConnection> {
+        self.acquire_session(Some(tag)).await
+    }
+
+    /// Close the pool.
+    ///
+    /// Idle sessions are closed and waiting `acquire` calls fail with
+    /// `Error::ConnectionClosed`. Sessions still lent out are closed when
+    /// they are dropped.
+    pub fn close(&self) {
+        self.inner.permits.close();
+        let idle = {
+            let mut state = self.inner.lock();
+            state.closed = true;
+            state.open -= state.idle.len();
+            std::mem::take(&mut state.idle)
+        };
+        drop(idle);
+    }
+
+    /// Check whether the pool was closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().closed
+    }
+
+    /// Get the number of open sessions, idle or lent out.
+    pub fn open_count(&self) -> usize {
+        self.inner.lock().open
+    }
+
+    /// Get the number of idle sessions.
+    pub fn idle_count(&self) -> usize {
+        self.inner.lock().idle.len()
+    }
+
+    /// Get the pool options.
+    pub fn options(&self) -> &PoolOptions {
+        &self.inner.options
+    }
+
+    async fn acquire_session(&self, tag: Option<&str>) -> Result<PooledConnection> {
+        let permit = self.wait_for_permit().await?;
+        let options = &self.inner.options;
+        loop {
+            let checkout = {
+                let mut state = self.inner.lock();
+                if state.closed {
+                    return Err(Error::ConnectionClosed);
+                }
+                state.checkout(tag, options.match_any_tag, options.max_size)
+            };
+            let (mut conn, mut session_tag, fresh) = match checkout {
+                Checkout::Idle(idle) => {
+                    let mut conn = idle.session;
+                    // A session dropped while idle is replaced
+                    if let Some(interval) = options.ping_interval {
+                        if conn.ping_if_idle(interval).await.is_err() {
+                            self.inner.forget();
+                            continue;
+                        }
+                    }
+                    (conn, idle.tag, idle.fresh)
+                }
+                Checkout::New { evicted } => {
+                    drop(evicted);
+                    match self.open_session().await {
+                        Ok(conn) => (conn, None, true),
+                        Err(e) => {
+                            self.inner.forget();
+                            return Err(e);
+                        }
+                    }
+                }
+            };
+
+            if fresh || session_tag.as_deref() != tag {
+                if let Some(callback) = &options.session_callback {
+                    if let Err(e) = callback(&mut conn, tag).await {
+                        self.inner.forget();
+                        return Err(e);
+                    }
+                    session_tag = tag.map(str::to_string);
+                }
+            }
+            return Ok(PooledConnection {
+                conn: Some(conn),
+                tag: session_tag,
+                pool: self.inner.clone(),
+                _permit: permit,
+            });
+        }
+    }
+
+    /// Wait for a session to be free to lend out.
+    async fn wait_for_permit(&self) -> Result<OwnedSemaphorePermit> {
+        let permits = self.inner.permits.clone();
+        let permit = match self.inner.options.acquire_timeout {
+            None => permits.acquire_owned().await,
+            Some(timeout) => tokio::time::timeout(timeout, permits.acquire_owned())
+                .await
+                .map_err(|_| Error::PoolTimeout { timeout })?,
+        };
+        permit.map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Open a new session.
+    async fn open_session(&self) -> Result<Connection> {
+        let inner = &self.inner;
+        Connection::connect_with_params(&inner.params, &inner.username, &inner.password).await
+    }
+}
+
+impl PoolInner {
+    fn lock(&self) -> std::sync::MutexGuard<'_, PoolState<Connection>> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Take a session back, or close it if it is no longer usable.
+    fn release(&self, mut conn: Connection, tag: Option<String>) {
+        let usable = conn.health().is_usable();
+        let mut state = self.lock();
+        if state.closed || !usable {
+            state.open -= 1;
+            drop(state);
+            drop(conn);
+            return;
+        }
+        state.idle.push_back(IdleSession {
+            session: conn,
+            tag,
+            fresh: false,
+        });
+    }
+
+    /// Stop counting a session that was closed or never opened.
+    fn forget(&self) {
+        self.lock().open -= 1;
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.inner.lock();
+        f.debug_struct("Pool")
+            .field("open", &state.open)
+            .field("idle", &state.idle.len())
+            .field("closed", &state.closed)
+            .field("options", &self.inner.options)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A session lent out by a `Pool`, returned to it when dropped.
+///
+/// Dereferences to the `Connection`. A session that lost its connection is
+/// closed instead of being returned.
+pub struct PooledConnection {
+    /// The session; taken when it is returned or discarded.
+    conn: Option<Connection>,
+    /// Tag the session goes back to the pool with.
+    tag: Option<String>,
+    /// Pool the session belongs to.
+    pool: Arc<PoolInner>,
+    /// Held while the session is lent out.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PooledConnection {
+    /// Get the session's tag.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Set the tag the session is returned to the pool with, describing the
+    /// state it was left in.
+    pub fn set_tag(&mut self, tag: impl Into<String>) {
+        self.tag = Some(tag.into());
+    }
+
+    /// Return the session untagged, as a session in its initial state.
+    pub fn clear_tag(&mut self) {
+        self.tag = None;
+    }
+
+    /// Close the session instead of returning it to the pool, for example
+    /// after changing state no tag describes.
+    pub async fn discard(mut self) -> Result<()> {
+        let conn = self.conn.take().expect("session present until dropped");
+        self.pool.forget();
+        conn.close().await
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("session present until dropped")
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("session present until dropped")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.release(conn, self.tag.take());
+        }
+    }
+}
+
+impl fmt::Debug for PooledConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledConnection")
+            .field("tag", &self.tag)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Idle sessions of a pool and the number of sessions open.
+struct PoolState<S> {
+    /// Idle sessions, least recently returned first.
+    idle: VecDeque<IdleSession<S>>,
+    /// Sessions open, idle or lent out (or being opened).
+    open: usize,
+    /// Set once the pool is closed.
+    closed: bool,
+}
+
+/// A session waiting in the pool.
+struct IdleSession<S> {
+    session: S,
+    /// Tag the session was returned with.
+    tag: Option<String>,
+    /// Whether the session was opened up front and never lent out, so the
+    /// session callback has not seen it yet.
+    fresh: bool,
+}
+
+/// How a session is found for an acquire.
+enum Checkout<S> {
+    /// Use an idle session.
+    Idle(IdleSession<S>),
+    /// Open a new session, in place of an idle one evicted to make room.
+    New { evicted: Option<S> },
+}
+
+impl<S> PoolState<S> {
+    fn new() -> Self {
+        Self {
+            idle: VecDeque::new(),
+            open: 0,
+            closed: false,
+        }
+    }
+
+    /// Pick the session for an acquire of `tag`, counting a session to be
+    /// opened as open.
+    ///
+    /// Preference goes to an idle session with the tag, then an untagged
+    /// one, then (with `match_any_tag`) any idle session. Otherwise a new
+    /// session is opened; a full pool evicts its least recently used idle
+    /// session to make room. The caller holds a permit, so a full pool
+    /// always has an idle session.
+    fn checkout(&mut self, tag: Option<&str>, match_any_tag: bool, max_size: usize) -> Checkout<S> {
+        let position = self
+            .idle
+            .iter()
+            .rposition(|idle| idle.tag.as_deref() == tag)
+            .or_else(|| self.idle.iter().rposition(|idle| idle.tag.is_none()))
+            .or_else(|| {
+                match_any_tag
+                    .then(|| self.idle.len().checked_sub(1))
+                    .flatten()
+            });
+        if let Some(idle) = position.and_then(|i| self.idle.remove(i)) {
+            return Checkout::Idle(idle);
+        }
+        if self.open < max_size {
+            self.open += 1;
+            return Checkout::New { evicted: None };
+        }
+        Checkout::New {
+            evicted: self.idle.pop_front().map(|idle| idle.session),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(sessions: &[(u32, Option<&str>)]) -> PoolState<u32> {
+        let mut state = PoolState::new();
+        for &(session, tag) in sessions {
+            state.idle.push_back(IdleSession {
+                session,
+                tag: tag.map(str::to_string),
+                fresh: false,
+            });
+            state.open += 1;
+        }
+        state
+    }
+
+    fn idle_session(checkout: Checkout<u32>) -> Option<u32> {
+        match checkout {
+            Checkout::Idle(idle) => Some(idle.session),
+            Checkout::New { .. } => None,
+        }
+    }
+
+    #[test]
+    fn test_checkout_prefers_matching_tag() {
+        let mut state = state(&[(1, None), (2, Some("fr")), (3, Some("de")), (4, Some("fr"))]);
+        // Most recently returned match first
+        assert_eq!(idle_session(state.checkout(Some("fr"), false, 4)), Some(4));
+        assert_eq!(idle_session(state.checkout(Some("fr"), false, 4)), Some(2));
+        // Then an untagged session
+        assert_eq!(idle_session(state.checkout(Some("fr"), false, 4)), Some(1));
+        assert_eq!(state.idle.len(), 1);
+    }
+
+    #[test]
+    fn test_checkout_untagged() {
+        let mut state = state(&[(1, Some("fr")), (2, None)]);
+        assert_eq!(idle_session(state.checkout(None, false, 4)), Some(2));
+
+        // Room for another session: a tagged one is not used
+        assert!(matches!(
+            state.checkout(None, false, 4),
+            Checkout::New { evicted: None }
+        ));
+        assert_eq!(state.open, 3);
+    }
+
+    #[test]
+    fn test_checkout_match_any_tag() {
+        let mut state = state(&[(1, Some("fr")), (2, Some("de"))]);
+        assert_eq!(idle_session(state.checkout(Some("it"), true, 4)), Some(2));
+        assert_eq!(state.open, 2);
+    }
+
+    #[test]
+    fn test_checkout_full_pool_evicts() {
+        let mut state = state(&[(1, Some("fr")), (2, Some("de"))]);
+        match state.checkout(Some("it"), false, 2) {
+            Checkout::New { evicted } => assert_eq!(evicted, Some(1)),
+            Checkout::Idle(_) => panic!("expected a new session"),
+        }
+        // The new session replaces the evicted one
+        assert_eq!(state.open, 2);
+        assert_eq!(state.idle.len(), 1);
+    }
+
+    #[test]
+    fn test_options() {
+        let options = PoolOptions::new()
+            .with_min_size(0)
+            .with_max_size(4)
+            .with_acquire_timeout(Duration::from_secs(1))
+            .with_ping_interval(None)
+            .with_match_any_tag(true);
+        assert_eq!(
+            (options.min_size, options.max_size, options.acquire_timeout),
+            (0, 4, Some(Duration::from_secs(1)))
+        );
+        assert!(options.ping_interval.is_none() && options.match_any_tag);
+        assert!(options.session_callback().is_none());
+        assert!(format!("{:?}", options).contains("session_callback: false"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_sizes_rejected() {
+        let options = PoolOptions::new().with_min_size(3).with_max_size(2);
+        let err = Pool::connect("localhost:1/XE", "u", "p", options)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol { .. }));
+    }
+
+    #[test]
+    fn test_pool_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<Pool>();
+        assert_send_sync::<PooledConnection>();
+    }
+}
@@ -0,0 +1,256 @@
+//! TNS ACCEPT packets.
+//!
+//! The server answers a CONNECT with an ACCEPT carrying the parameters of
+//! the session: the protocol version and options, the session and
+//! transport data units, the network services (NSI) flags and, from
+//! protocol version 318, the accept flags. Connect data may follow, such as
+//! the reconnect data of a Connection Manager.
+//!
+//! All fields are big-endian. Offsets are from the start of the payload:
+//!
+//! | Offset | Size | Field                                          |
+//! |--------|------|------------------------------------------------|
+//! | 0      | 2    | protocol version                               |
+//! | 2      | 2    | protocol (global service) options              |
+//! | 4      | 2    | SDU, for versions before 315                   |
+//! | 6      | 2    | TDU, for versions before 315                   |
+//! | 8      | 2    | value of one, in the server's byte order       |
+//! | 10     | 2    | connect data length                            |
+//! | 12     | 2    | connect data offset, from the packet start     |
+//! | 14     | 2    | NSI flags                                      |
+//! | 16     | 8    | reserved (version 315 on)                      |
+//! | 24     | 4    | SDU (version 315 on)                           |
+//! | 28     | 4    | TDU (version 315 on)                           |
+//! | 32     | 1    | reserved (version 318 on)                      |
+//! | 33     | 4    | accept flags (version 318 on)                  |
+
+use bytes::Bytes;
+
+use crate::error::{Error, Result};
+use crate::protocol::buffer::ReadBuffer;
+use crate::protocol::constants::*;
+
+/// The parameters a server accepted a connection with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcceptInfo {
+    /// Protocol version.
+    pub protocol_version: u16,
+    /// Protocol options (`TNS_GSO_*`).
+    pub protocol_options: u16,
+    /// Session data unit: the largest packet, in bytes.
+    pub sdu: u32,
+    /// Transport data unit.
+    pub tdu: u32,
+    /// Network services flags (`TNS_NSI_*`).
+    pub nsi_flags: [u8; 2],
+    /// Accept flags (`TNS_ACCEPT_FLAG_*`); zero before version 318.
+    pub flags: u32,
+    /// Connect data sent with the ACCEPT, such as the reconnect data of a
+    /// Connection Manager; usually empty.
+    pub connect_data: Bytes,
+}
+
+impl AcceptInfo {
+    /// Parse an ACCEPT packet payload (without the packet header).
+    pub fn parse(payload: &[u8]) -> Result<Self> {
+        let truncated = |_| Error::protocol("ACCEPT packet too short");
+        let mut buf = ReadBuffer::new(Bytes::copy_from_slice(payload));
+
+        let protocol_version = buf.read_u16_be().map_err(truncated)?;
+        let protocol_options = buf.read_u16_be().map_err(truncated)?;
+        let mut sdu = u32::from(buf.read_u16_be().map_err(truncated)?);
+        let mut tdu = u32::from(buf.read_u16_be().map_err(truncated)?);
+        buf.skip(2).map_err(truncated)?; // value of one
+        let data_length = usize::from(buf.read_u16_be().map_err(truncated)?);
+        let data_offset = usize::from(buf.read_u16_be().map_err(truncated)?);
+        let nsi_flags = [
+            buf.read_u8().map_err(truncated)?,
+            buf.read_u8().map_err(truncated)?,
+        ];
+
+        if protocol_version >= TNS_VERSION_MIN_LARGE_SDU {
+            buf.skip(8).map_err(truncated)?;
+            sdu = buf.read_u32_be().map_err(truncated)?;
+            tdu = buf.read_u32_be().map_err(truncated)?;
+        }
+        let mut flags = 0;
+        if protocol_version >= TNS_VERSION_MIN_OOB_CHECK {
+            buf.skip(1).map_err(truncated)?;
+            flags = buf.read_u32_be().map_err(truncated)?;
+        }
+
+        let connect_data = if data_length == 0 {
+            Bytes::new()
+        } else {
+            let start = data_offset.saturating_sub(PACKET_HEADER_SIZE);
+            let data = payload.get(start..start + data_length).ok_or_else(|| {
+                Error::protocol(format!(
+                    "ACCEPT connect data ({} bytes at offset {}) past the end of the packet",
+                    data_length, data_offset
+                ))
+            })?;
+            Bytes::copy_from_slice(data)
+        };
+
+        Ok(Self {
+            protocol_version,
+            protocol_options,
+            sdu,
+            tdu,
+            nsi_flags,
+            flags,
+            connect_data,
+        })
+    }
+
+    /// Whether the server requires Native Network Encryption.
+    pub fn requires_native_encryption(&self) -> bool {
+        self.nsi_flags[0] & TNS_NSI_NA_REQUIRED != 0
+    }
+
+    /// Whether packets have 4-byte lengths, from protocol version 315.
+    pub fn uses_large_sdu(&self) -> bool {
+        self.protocol_version >= TNS_VERSION_MIN_LARGE_SDU
+    }
+
+    /// Get the connect data as text, if there is any.
+    pub fn connect_data_str(&self) -> Option<String> {
+        if self.connect_data.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(&self.connect_data).into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ACCEPT with the fields of a 12.1 server: protocol version 315,
+    /// 4-byte SDU and TDU, no accept flags.
+    const ACCEPT_12C: [u8; 32] = [
+        0x01, 0x3b, // version 315
+        0x04, 0x01, // options: can receive attention, don't care
+        0x20, 0x00, // SDU 8192
+        0xff, 0xff, // TDU
+        0x01, 0x00, // value of one
+        0x00, 0x00, // connect data length
+        0x00, 0x28, // connect data offset
+        0x41, 0x01, // NSI flags
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // reserved
+        0x00, 0x00, 0x20, 0x00, // SDU 8192
+        0x00, 0x00, 0xff, 0xff, // TDU
+    ];
+
+    /// ACCEPT with the fields of a 19c server: protocol version 318, asking
+    /// for the OOB check.
+    const ACCEPT_19C: [u8; 37] = [
+        0x01, 0x3e, // version 318
+        0x0c, 0x01, // options
+        0x20, 0x00, // SDU
+        0xff, 0xff, // TDU
+        0x01, 0x00, // value of one
+        0x00, 0x00, // connect data length
+        0x00, 0x2d, // connect data offset
+        0x41, 0x01, // NSI flags
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // reserved
+        0x00, 0x00, 0xff, 0xff, // SDU 65535
+        0x00, 0x0f, 0xff, 0xff, // TDU
+        0x00, // reserved
+        0x00, 0x00, 0x00, 0x01, // flags: check OOB
+    ];
+
+    /// ACCEPT with the fields of a 23ai server: protocol version 319, with
+    /// fast auth and end of response, and an SDU past the 2-byte field.
+    const ACCEPT_23AI: [u8; 37] = [
+        0x01, 0x3f, // version 319
+        0x0c, 0x01, // options
+        0xff, 0xff, // SDU (does not fit)
+        0xff, 0xff, // TDU
+        0x01, 0x00, // value of one
+        0x00, 0x00, // connect data length
+        0x00, 0x2d, // connect data offset
+        0x41, 0x01, // NSI flags
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // reserved
+        0x00, 0x20, 0x00, 0x00, // SDU 2 MB
+        0x00, 0x20, 0x00, 0x00, // TDU
+        0x00, // reserved
+        0x12, 0x00, 0x00, 0x01, // flags: fast auth, end of response, check OOB
+    ];
+
+    #[test]
+    fn test_parse_accept_12c() {
+        let info = AcceptInfo::parse(&ACCEPT_12C).unwrap();
+        assert_eq!(
+            info,
+            AcceptInfo {
+                protocol_version: 315,
+                protocol_options: TNS_GSO_CAN_RECV_ATTENTION | TNS_GSO_DONT_CARE,
+                sdu: 8192,
+                tdu: 0xffff,
+                nsi_flags: [0x41, 0x01],
+                flags: 0,
+                connect_data: Bytes::new(),
+            }
+        );
+        assert!(info.uses_large_sdu());
+        assert!(!info.requires_native_encryption());
+        assert_eq!(info.connect_data_str(), None);
+    }
+
+    #[test]
+    fn test_parse_accept_19c() {
+        let info = AcceptInfo::parse(&ACCEPT_19C).unwrap();
+        assert_eq!(info.protocol_version, 318);
+        assert_eq!(info.sdu, 65535);
+        assert_eq!(info.tdu, 0xfffff);
+        assert_eq!(info.flags, TNS_ACCEPT_FLAG_CHECK_OOB);
+    }
+
+    #[test]
+    fn test_parse_accept_23ai() {
+        let info = AcceptInfo::parse(&ACCEPT_23AI).unwrap();
+        assert_eq!(info.protocol_version, 319);
+        // The 4-byte SDU replaces the 2-byte one
+        assert_eq!(info.sdu, 2 * 1024 * 1024);
+        assert_eq!(
+            info.flags,
+            TNS_ACCEPT_FLAG_FAST_AUTH
+                | TNS_ACCEPT_FLAG_HAS_END_OF_RESPONSE
+                | TNS_ACCEPT_FLAG_CHECK_OOB
+        );
+    }
+
+    #[test]
+    fn test_parse_accept_connect_data() {
+        let data = b"(ADDRESS=(PROTOCOL=tcp)(HOST=db2)(PORT=1521))";
+        let mut payload = ACCEPT_19C.to_vec();
+        payload[10..12].copy_from_slice(&(data.len() as u16).to_be_bytes());
+        payload.extend_from_slice(data);
+        let info = AcceptInfo::parse(&payload).unwrap();
+        assert_eq!(&info.connect_data[..], data);
+        assert_eq!(
+            info.connect_data_str().as_deref(),
+            Some("(ADDRESS=(PROTOCOL=tcp)(HOST=db2)(PORT=1521))")
+        );
+
+        // Connect data past the end of the packet
+        payload.truncate(payload.len() - 1);
+        assert!(AcceptInfo::parse(&payload).is_err());
+    }
+
+    #[test]
+    fn test_parse_accept_malformed() {
+        assert!(AcceptInfo::parse(&[]).is_err());
+        assert!(AcceptInfo::parse(&ACCEPT_12C[..20]).is_err());
+        // Version 318 has the accept flags
+        assert!(AcceptInfo::parse(&ACCEPT_19C[..32]).is_err());
+
+        let mut nna = ACCEPT_19C;
+        nna[14] |= TNS_NSI_NA_REQUIRED;
+        assert!(AcceptInfo::parse(&nna)
+            .unwrap()
+            .requires_native_encryption());
+    }
+}
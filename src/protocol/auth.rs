@@ -7,10 +7,83 @@ use crate::protocol::crypto::{
     bytes_to_hex_upper, decrypt_cbc, derive_key_pbkdf2, encrypt_cbc, hex_to_bytes, md5_hash,
     random_bytes, sha1_hash, sha512_hash,
 };
-use crate::protocol::messages::{AuthPhaseOneMessage, AuthPhaseTwoMessage, MarkerMessage};
+use crate::protocol::messages::{
+    AuthPhaseOneMessage, AuthPhaseTwoMessage, AuthProof, MarkerMessage,
+};
 use crate::protocol::packet::{Capabilities, Packet, PacketStream};
+use crate::protocol::response::Warning;
 use bytes::Bytes;
 use std::collections::HashMap;
+use std::fmt;
+
+/// How a session is authenticated.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub enum AuthMethod {
+    /// Username and password (O5LOGON).
+    #[default]
+    Password,
+    /// Database access token instead of a username and password: an OCI IAM
+    /// database token, or an OAuth2 access token such as one issued by
+    /// Azure AD (Microsoft Entra ID). Requires a TCPS connection.
+    Token {
+        /// Token text.
+        token: String,
+        /// PEM or base64 PKCS#8 private key for OCI IAM proof-of-possession
+        /// tokens, used to sign the request; `None` for OAuth2 tokens.
+        private_key: Option<String>,
+    },
+}
+
+impl fmt::Debug for AuthMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Password => f.write_str("Password"),
+            Self::Token { private_key, .. } => f
+                .debug_struct("Token")
+                .field("token", &"<redacted>")
+                .field("private_key", &private_key.as_ref().map(|_| "<redacted>"))
+                .finish(),
+        }
+    }
+}
+
+/// Administrative privilege to open a session with, like `AS SYSDBA` in
+/// SQL*Plus.
+///
+/// The user must have been granted the privilege, and the database must
+/// accept privileged logons over the network (a password file).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    /// SYSDBA: full administration of the database.
+    SysDba,
+    /// SYSOPER: operational tasks without access to user data.
+    SysOper,
+    /// SYSASM: Automatic Storage Management administration.
+    SysAsm,
+    /// SYSBACKUP: backup and recovery.
+    SysBackup,
+    /// SYSDG: Data Guard administration.
+    SysDg,
+    /// SYSKM: Transparent Data Encryption keystore administration.
+    SysKm,
+    /// SYSRAC: Real Application Clusters administration.
+    SysRac,
+}
+
+impl Privilege {
+    /// The privilege's flag in the authentication mode.
+    pub(crate) fn auth_mode(self) -> u32 {
+        match self {
+            Privilege::SysDba => TNS_AUTH_MODE_SYSDBA,
+            Privilege::SysOper => TNS_AUTH_MODE_SYSOPER,
+            Privilege::SysAsm => TNS_AUTH_MODE_SYSASM,
+            Privilege::SysBackup => TNS_AUTH_MODE_SYSBKP,
+            Privilege::SysDg => TNS_AUTH_MODE_SYSDGD,
+            Privilege::SysKm => TNS_AUTH_MODE_SYSKMT,
+            Privilege::SysRac => TNS_AUTH_MODE_SYSRAC,
+        }
+    }
+}
 
 /// Authentication credentials.
 #[derive(Debug, Clone)]
@@ -19,16 +92,58 @@ pub struct AuthCredentials {
     pub username: String,
     /// Password.
     pub password: String,
+    /// User the session is opened as, with `username` and `password`
+    /// authenticating as its proxy.
+    pub proxy_user: Option<String>,
+    /// Administrative privilege the session is opened with.
+    pub privilege: Option<Privilege>,
 }
 
 impl AuthCredentials {
     /// Create new credentials.
+    ///
+    /// A username of the form `user[proxy_user]` authenticates as `user` and
+    /// opens the session as `proxy_user`, which must have been granted
+    /// `CONNECT THROUGH user`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::protocol::auth::AuthCredentials;
+    ///
+    /// let creds = AuthCredentials::new("app_owner[end_user]", "secret");
+    /// assert_eq!(creds.username, "app_owner");
+    /// assert_eq!(creds.proxy_user.as_deref(), Some("end_user"));
+    /// ```
     pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        let username = username.into();
+        let (username, proxy_user) = match username
+            .strip_suffix(']')
+            .and_then(|rest| rest.split_once('['))
+        {
+            Some((user, proxy)) if !user.is_empty() && !proxy.is_empty() => {
+                (user.to_string(), Some(proxy.to_string()))
+            }
+            _ => (username, None),
+        };
         Self {
-            username: username.into(),
+            username,
             password: password.into(),
+            proxy_user,
+            privilege: None,
         }
     }
+
+    /// Open the session with an administrative privilege.
+    pub fn with_privilege(mut self, privilege: Privilege) -> Self {
+        self.privilege = Some(privilege);
+        self
+    }
+
+    /// The privilege flags sent in the authentication mode.
+    pub(crate) fn privilege_mode(&self) -> u32 {
+        self.privilege.map_or(0, Privilege::auth_mode)
+    }
 }
 
 /// Session data from authentication.
@@ -40,13 +155,26 @@ pub struct SessionData {
     pub verifier_type: u32,
     /// Combo key for encryption.
     pub combo_key: Option<Vec<u8>>,
+    /// Warnings returned with a successful logon, such as ORA-28002
+    /// (password will expire).
+    pub warnings: Vec<Warning>,
 }
 
+/// Oracle errors the server reports with a successful logon.
+const AUTH_WARNING_CODES: [u32; 2] = [
+    28002, // the password will expire within n days
+    28011, // the account will expire soon
+];
+
 /// Perform two-phase O5LOGON authentication.
+///
+/// `alter_session` is the NUL-terminated `ALTER SESSION` statement the
+/// server runs once the session is created.
 pub async fn authenticate(
     stream: &mut PacketStream,
     creds: &AuthCredentials,
     caps: &Capabilities,
+    alter_session: &str,
 ) -> Result<SessionData> {
     // Phase 1: Send client info, receive verifier data
     // eprintln!("[DEBUG] Starting authentication phase one");
@@ -54,7 +182,7 @@ pub async fn authenticate(
 
     // Phase 2: Generate verifier and complete authentication
     // eprintln!("[DEBUG] Starting authentication phase two");
-    phase_two(stream, creds, caps, &mut session).await?;
+    phase_two(stream, creds, caps, &mut session, alter_session).await?;
 
     Ok(session)
 }
@@ -80,6 +208,7 @@ async fn phase_one(
         machine: &hostname,
         pid: &pid,
         sid: &osuser,
+        privilege: creds.privilege_mode(),
     };
 
     stream.send_data_message(&msg).await?;
@@ -104,20 +233,20 @@ async fn phase_one(
     parse_auth_response(response.payload)
 }
 
+/// Marker type from a marker packet payload.
+///
+/// Format: [data_flags (2 bytes), marker_type (1 byte)]; short payloads
+/// carry the marker type alone.
+fn marker_type(payload: &[u8]) -> u8 {
+    payload.get(2).or(payload.first()).copied().unwrap_or(0)
+}
+
 /// Handle a marker packet and retrieve the error message from server.
 async fn handle_marker_and_get_error(
     stream: &mut PacketStream,
     marker_packet: Packet,
 ) -> Result<SessionData> {
-    // Parse marker type from payload
-    // Format: [data_flags (2 bytes), marker_type (1 byte)]
-    let _marker_type = if marker_packet.payload.len() >= 3 {
-        marker_packet.payload[2]
-    } else if !marker_packet.payload.is_empty() {
-        marker_packet.payload[0]
-    } else {
-        0
-    };
+    let _marker_type = marker_type(&marker_packet.payload);
 
     // Send reset marker to recover (zero-copy)
     let msg = MarkerMessage::reset();
@@ -129,13 +258,7 @@ async fn handle_marker_and_get_error(
 
         if pkt.packet_type == TNS_PACKET_TYPE_MARKER {
             // Check if it's a reset marker
-            let pkt_marker_type = if pkt.payload.len() >= 3 {
-                pkt.payload[2]
-            } else if !pkt.payload.is_empty() {
-                pkt.payload[0]
-            } else {
-                0
-            };
+            let pkt_marker_type = marker_type(&pkt.payload);
             if pkt_marker_type == TNS_MARKER_TYPE_RESET {
                 break;
             }
@@ -195,15 +318,8 @@ const TNS_MARKER_TYPE_RESET: u8 = 2;
 async fn handle_marker_and_get_error_phase2(
     stream: &mut PacketStream,
     marker_packet: Packet,
-) -> Result<()> {
-    // Parse marker type from payload
-    let _marker_type = if marker_packet.payload.len() >= 3 {
-        marker_packet.payload[2]
-    } else if !marker_packet.payload.is_empty() {
-        marker_packet.payload[0]
-    } else {
-        0
-    };
+) -> Result<SessionData> {
+    let _marker_type = marker_type(&marker_packet.payload);
 
     // Send reset marker to recover (zero-copy)
     let msg = MarkerMessage::reset();
@@ -224,13 +340,7 @@ async fn handle_marker_and_get_error_phase2(
             }
             break;
         } else if pkt.packet_type == TNS_PACKET_TYPE_MARKER {
-            let pkt_marker_type = if pkt.payload.len() >= 3 {
-                pkt.payload[2]
-            } else if !pkt.payload.is_empty() {
-                pkt.payload[0]
-            } else {
-                0
-            };
+            let pkt_marker_type = marker_type(&pkt.payload);
             if pkt_marker_type == TNS_MARKER_TYPE_RESET {
                 continue;
             }
@@ -248,48 +358,32 @@ pub async fn phase_two(
     creds: &AuthCredentials,
     _caps: &Capabilities,
     session: &mut SessionData,
+    alter_session: &str,
 ) -> Result<()> {
     // Generate the verifier based on type
     let (session_key, speedy_key, encoded_password) = generate_verifier(creds, session)?;
 
-    // Timezone setting
-    let tz_stmt = get_timezone_statement();
-
     // Build and send phase 2 message (zero-copy)
     let msg = AuthPhaseTwoMessage {
         username: &creds.username,
-        session_key: &session_key,
-        speedy_key: speedy_key.as_deref(),
-        encoded_password: &encoded_password,
-        timezone_stmt: &tz_stmt,
+        proof: AuthProof::Password {
+            session_key: &session_key,
+            speedy_key: speedy_key.as_deref(),
+            encoded_password: &encoded_password,
+        },
+        proxy_user: creds.proxy_user.as_deref(),
+        alter_session,
+        privilege: creds.privilege_mode(),
     };
 
-    stream.send_data_message(&msg).await?;
-
-    // Read response
-    let response = stream.read_packet().await?;
-
-    // Handle marker packet (server break)
-    if response.packet_type == TNS_PACKET_TYPE_MARKER {
-        return handle_marker_and_get_error_phase2(stream, response).await;
-    }
-
-    if response.packet_type != TNS_PACKET_TYPE_DATA {
-        return Err(Error::UnexpectedPacketType {
-            expected: TNS_PACKET_TYPE_DATA,
-            actual: response.packet_type,
-        });
-    }
-
-    // Parse phase 2 response
-    let response_session = parse_auth_response(response.payload)?;
+    let response_session = send_phase_two(stream, &msg).await?;
 
     // Verify server response
     if let Some(combo_key) = &session.combo_key {
         if let Some(svr_response) = response_session.params.get("AUTH_SVR_RESPONSE") {
             let encoded = hex_to_bytes(svr_response)
                 .ok_or_else(|| Error::protocol("Invalid AUTH_SVR_RESPONSE hex"))?;
-            let decrypted = decrypt_cbc(combo_key, &encoded);
+            let decrypted = decrypt_cbc(combo_key, &encoded)?;
             if decrypted.len() < 32 || &decrypted[16..32] != b"SERVER_TO_CLIENT" {
                 return Err(Error::InvalidServerResponse);
             }
@@ -300,10 +394,106 @@ pub async fn phase_two(
     for (k, v) in response_session.params {
         session.params.insert(k, v);
     }
+    session.warnings.extend(response_session.warnings);
 
     Ok(())
 }
 
+/// Authenticate with a database access token instead of a password.
+///
+/// The token replaces the O5LOGON challenge, so only phase 2 is sent. For
+/// proof-of-possession tokens the request header (date, service name and
+/// host) is signed with the token's private key.
+pub async fn token_authenticate(
+    stream: &mut PacketStream,
+    token: &str,
+    private_key: Option<&str>,
+    service_name: &str,
+    host: &str,
+    port: u16,
+    alter_session: &str,
+) -> Result<SessionData> {
+    let signed_header = match private_key {
+        Some(key) => {
+            let header = token_request_header(chrono::Utc::now(), service_name, host, port);
+            let signature = sign_token_header(key, &header)?;
+            Some((header, signature))
+        }
+        None => None,
+    };
+
+    let msg = AuthPhaseTwoMessage {
+        username: "",
+        proof: AuthProof::Token {
+            token,
+            signed_header: signed_header
+                .as_ref()
+                .map(|(header, signature)| (header.as_str(), signature.as_str())),
+        },
+        proxy_user: None,
+        alter_session,
+        privilege: 0,
+    };
+
+    send_phase_two(stream, &msg).await
+}
+
+/// Request header signed for proof-of-possession tokens.
+fn token_request_header(
+    now: chrono::DateTime<chrono::Utc>,
+    service_name: &str,
+    host: &str,
+    port: u16,
+) -> String {
+    format!(
+        "date: {}\n(request-target): {}\nhost: {}:{}",
+        now.format("%a, %d %b %Y %H:%M:%S GMT"),
+        service_name,
+        host,
+        port
+    )
+}
+
+/// Sign the token request header, returning the base64 signature.
+#[cfg(feature = "tls")]
+fn sign_token_header(private_key: &str, header: &str) -> Result<String> {
+    crate::protocol::tls::sign_rsa_sha256(private_key, header.as_bytes())
+}
+
+/// Sign the token request header, returning the base64 signature.
+#[cfg(not(feature = "tls"))]
+fn sign_token_header(_private_key: &str, _header: &str) -> Result<String> {
+    Err(Error::AuthenticationFailed {
+        message: "signing access token requests requires the `tls` feature".to_string(),
+    })
+}
+
+/// Send a phase 2 message and read the session data it returns.
+async fn send_phase_two(
+    stream: &mut PacketStream,
+    msg: &AuthPhaseTwoMessage<'_>,
+) -> Result<SessionData> {
+    stream.send_data_message(msg).await?;
+
+    // Read response
+    let response = stream.read_packet().await?;
+
+    // Handle marker packet (server break)
+    if response.packet_type == TNS_PACKET_TYPE_MARKER {
+        return handle_marker_and_get_error_phase2(stream, response).await;
+    }
+
+    if response.packet_type != TNS_PACKET_TYPE_DATA {
+        return Err(Error::UnexpectedPacketType {
+            expected: TNS_PACKET_TYPE_DATA,
+            actual: response.packet_type,
+        });
+    }
+
+    // Parse phase 2 response
+    parse_auth_response(response.payload)
+}
+
 /// Generate the verifier for authentication.
 fn generate_verifier(
     creds: &AuthCredentials,
@@ -362,7 +552,7 @@ fn generate_12c_verifier(
         .ok_or_else(|| Error::protocol("Missing AUTH_SESSKEY"))?;
     let server_sesskey_bytes =
         hex_to_bytes(server_sesskey).ok_or_else(|| Error::protocol("Invalid AUTH_SESSKEY hex"))?;
-    let session_key_part_a = decrypt_cbc(&password_hash, &server_sesskey_bytes);
+    let session_key_part_a = decrypt_cbc(&password_hash, &server_sesskey_bytes)?;
 
     // Generate client's session key part (same length as part_a)
     let session_key_part_b = random_bytes(session_key_part_a.len());
@@ -440,7 +630,7 @@ fn generate_11g_verifier(
         .ok_or_else(|| Error::protocol("Missing AUTH_SESSKEY"))?;
     let server_sesskey_bytes =
         hex_to_bytes(server_sesskey).ok_or_else(|| Error::protocol("Invalid AUTH_SESSKEY hex"))?;
-    let session_key_part_a = decrypt_cbc(&password_hash, &server_sesskey_bytes);
+    let session_key_part_a = decrypt_cbc(&password_hash, &server_sesskey_bytes)?;
 
     // Generate client's session key part
     let session_key_part_b = random_bytes(session_key_part_a.len());
@@ -451,8 +641,12 @@ fn generate_11g_verifier(
     // Derive combo key using MD5
     let key_len = 24;
     let mut xor_result = vec![0u8; key_len];
-    for i in 16..40.min(session_key_part_a.len().min(session_key_part_b.len()) + 16) {
-        xor_result[i - 16] = session_key_part_a[i] ^ session_key_part_b[i];
+    for ((x, a), b) in xor_result
+        .iter_mut()
+        .zip(session_key_part_a.iter().skip(16))
+        .zip(session_key_part_b.iter().skip(16))
+    {
+        *x = a ^ b;
     }
 
     let part1 = md5_hash(&xor_result[..16]);
@@ -548,6 +742,17 @@ pub fn parse_auth_response(payload: Bytes) -> Result<SessionData> {
                     session.params.insert(clean_key, value);
                 }
             }
+
+            // A logon warning (ORA-28002) follows the parameters as error
+            // information; other error information carries no error here
+            if buf.remaining() > 0 && buf.read_u8()? == TNS_MSG_TYPE_ERROR {
+                if let Error::Oracle { code, message, .. } = parse_error(&mut buf)? {
+                    if AUTH_WARNING_CODES.contains(&code) {
+                        let message = message.trim_end().to_string();
+                        session.warnings.push(Warning { code, message });
+                    }
+                }
+            }
         }
         TNS_MSG_TYPE_ERROR => {
             // Read error
@@ -626,29 +831,143 @@ fn parse_error(buf: &mut ReadBuffer) -> Result<Error> {
             0
         };
 
-        return Ok(Error::Oracle { code, message });
+        return Ok(Error::oracle(code, message));
     }
 
     // Fallback: couldn't find ORA- pattern
-    Ok(Error::Oracle {
-        code: 0,
-        message: "Unknown Oracle error".to_string(),
-    })
+    Ok(Error::oracle(0, "Unknown Oracle error"))
 }
 
-/// Get the ALTER SESSION statement for timezone.
-fn get_timezone_statement() -> String {
-    // Get local timezone offset
-    let now = chrono::Local::now();
-    let offset = now.offset();
-    let hours = offset.local_minus_utc() / 3600;
-    let minutes = (offset.local_minus_utc().abs() % 3600) / 60;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::message::WriteExt;
+    use chrono::TimeZone;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    #[test]
+    fn test_credentials_proxy_user() {
+        let creds = AuthCredentials::new("app_owner[end_user]", "pw");
+        assert_eq!(creds.username, "app_owner");
+        assert_eq!(creds.proxy_user.as_deref(), Some("end_user"));
+
+        for username in ["scott", "[end_user]", "app_owner[]", "app[owner"] {
+            let creds = AuthCredentials::new(username, "pw");
+            assert_eq!(creds.username, username);
+            assert_eq!(creds.proxy_user, None);
+        }
+    }
+
+    #[test]
+    fn test_credentials_privilege() {
+        let creds = AuthCredentials::new("sys", "pw");
+        assert_eq!(creds.privilege_mode(), 0);
+        let creds = creds.with_privilege(Privilege::SysDba);
+        assert_eq!(creds.privilege_mode(), TNS_AUTH_MODE_SYSDBA);
+        assert_eq!(Privilege::SysBackup.auth_mode(), TNS_AUTH_MODE_SYSBKP);
+    }
 
-    let sign = if hours >= 0 { "+" } else { "-" };
-    format!(
-        "ALTER SESSION SET TIME_ZONE='{}{:02}:{:02}'\0",
-        sign,
-        hours.abs(),
-        minutes
-    )
+    #[test]
+    fn test_auth_response_warnings() {
+        let response = |error: &[u8]| {
+            let mut payload = Vec::new();
+            payload.write_u16_be(0);
+            payload.push(TNS_MSG_TYPE_PARAMETER);
+            payload.write_ub2(1);
+            payload.write_key_value("AUTH_SESSION_ID", "42", 0);
+            payload.push(TNS_MSG_TYPE_ERROR);
+            payload.extend_from_slice(&[0, 0, 0, 0]);
+            payload.extend_from_slice(error);
+            Bytes::from(payload)
+        };
+
+        let session = parse_auth_response(response(
+            b"ORA-28002: the password will expire within 7 days\n\0",
+        ))
+        .unwrap();
+        assert_eq!(session.params["AUTH_SESSION_ID"], "42");
+        assert_eq!(
+            session.warnings,
+            vec![Warning {
+                code: 28002,
+                message: "ORA-28002: the password will expire within 7 days".to_string(),
+            }]
+        );
+
+        // Error information without a logon warning is not one
+        let session = parse_auth_response(response(&[0, 0])).unwrap();
+        assert!(session.warnings.is_empty());
+        let session = parse_auth_response(response(b"ORA-00001: unique constraint\0")).unwrap();
+        assert!(session.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_token_request_header() {
+        let now = chrono::Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            token_request_header(
+                now,
+                "mydb_high.adb.oraclecloud.com",
+                "adb.example.com",
+                1522
+            ),
+            "date: Thu, 01 Jan 2026 00:00:00 GMT\n\
+             (request-target): mydb_high.adb.oraclecloud.com\n\
+             host: adb.example.com:1522"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_authenticate() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut header = [0u8; 8];
+            socket.read_exact(&mut header).await.unwrap();
+            let len = u16::from_be_bytes([header[0], header[1]]) as usize;
+            let mut request = vec![0u8; len - header.len()];
+            socket.read_exact(&mut request).await.unwrap();
+
+            // Session parameters: one key/value pair
+            let mut payload = Vec::new();
+            payload.write_u16_be(0);
+            payload.push(TNS_MSG_TYPE_PARAMETER);
+            payload.write_ub2(1);
+            payload.write_key_value("AUTH_SESSION_ID", "42", 0);
+            let mut packet = Vec::new();
+            packet.write_u16_be((payload.len() + 8) as u16);
+            packet.extend_from_slice(&[0, 0, TNS_PACKET_TYPE_DATA, 0, 0, 0]);
+            packet.extend_from_slice(&payload);
+            socket.write_all(&packet).await.unwrap();
+            request
+        });
+
+        let mut stream = PacketStream::new(TcpStream::connect(addr).await.unwrap());
+        let session = token_authenticate(
+            &mut stream,
+            "header.payload.sig",
+            None,
+            "orcl",
+            "db",
+            1522,
+            "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            session.params.get("AUTH_SESSION_ID").map(String::as_str),
+            Some("42")
+        );
+
+        let request = server.await.unwrap();
+        let text = String::from_utf8_lossy(&request);
+        assert_eq!(
+            request[2..4],
+            [TNS_MSG_TYPE_FUNCTION, TNS_FUNC_AUTH_PHASE_TWO]
+        );
+        assert!(text.contains("AUTH_TOKEN") && text.contains("header.payload.sig"));
+        assert!(!text.contains("AUTH_HEADER"));
+    }
 }
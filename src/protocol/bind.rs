@@ -0,0 +1,666 @@
+//! Bind values for statement execution.
+//!
+//! Binds are positional: the n-th value of each row is bound to the n-th
+//! placeholder in the statement. All rows share one description per bind
+//! position, taken from the values in that position.
+//!
+//! Binds receiving the values of a DML `RETURNING ... INTO` clause come
+//! after all others. They are described by the type of value they receive
+//! and carry no values of their own.
+
+use crate::error::{Error, Result};
+use crate::protocol::constants::*;
+#[cfg(feature = "rust_decimal")]
+use crate::protocol::encode::encode_oracle_decimal;
+use crate::protocol::encode::{encode_oracle_date, encode_oracle_integer, encode_oracle_number};
+use crate::protocol::message::{
+    bytes_with_length_wire_size, ub2_wire_size, ub4_wire_size, WriteExt,
+};
+use crate::protocol::packet::Capabilities;
+use crate::protocol::types::{ColumnMetadata, OracleType, OracleValue};
+
+/// Buffer size advertised for NUMBER binds.
+const NUMBER_BUFFER_SIZE: u32 = 22;
+/// Buffer size advertised for DATE binds.
+const DATE_BUFFER_SIZE: u32 = 7;
+/// Buffer size advertised for BOOLEAN binds.
+const BOOLEAN_BUFFER_SIZE: u32 = 4;
+
+/// Kind of value bound in a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindKind {
+    Text,
+    Raw,
+    Number,
+    Date,
+    Boolean,
+}
+
+impl BindKind {
+    /// Get the kind of a value, or `None` for NULL.
+    fn of(value: &OracleValue) -> Result<Option<Self>> {
+        let kind = match value {
+            OracleValue::Null => return Ok(None),
+            OracleValue::String(_) => Self::Text,
+            OracleValue::Raw(_) => Self::Raw,
+            OracleValue::Number(_) | OracleValue::Integer(_) => Self::Number,
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(_) => Self::Number,
+            OracleValue::Date(_) => Self::Date,
+            OracleValue::Boolean(_) => Self::Boolean,
+            other => return Err(Error::type_conversion(format!("cannot bind {:?}", other))),
+        };
+        Ok(Some(kind))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Text => "string",
+            Self::Raw => "raw",
+            Self::Number => "number",
+            Self::Date => "date",
+            Self::Boolean => "boolean",
+        }
+    }
+}
+
+/// Description of one bind position, shared by all rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindInfo {
+    /// Oracle type number.
+    pub ora_type_num: u16,
+    /// Largest value, in bytes.
+    pub buffer_size: u32,
+    /// Character set form (0 for non-character types).
+    pub csfrm: u8,
+}
+
+impl BindInfo {
+    fn new(kind: Option<BindKind>, max_len: usize, caps: &Capabilities) -> Self {
+        let (ora_type_num, long_type_num, csfrm) = match kind {
+            None | Some(BindKind::Text) => {
+                (ORA_TYPE_NUM_VARCHAR, ORA_TYPE_NUM_LONG, CS_FORM_IMPLICIT)
+            }
+            Some(BindKind::Raw) => (ORA_TYPE_NUM_RAW, ORA_TYPE_NUM_LONG_RAW, 0),
+            Some(BindKind::Number) => return Self::fixed(ORA_TYPE_NUM_NUMBER, NUMBER_BUFFER_SIZE),
+            Some(BindKind::Date) => return Self::fixed(ORA_TYPE_NUM_DATE, DATE_BUFFER_SIZE),
+            Some(BindKind::Boolean) if caps.features().supports_boolean() => {
+                return Self::fixed(ORA_TYPE_NUM_BOOLEAN, BOOLEAN_BUFFER_SIZE)
+            }
+            Some(BindKind::Boolean) => return Self::fixed(ORA_TYPE_NUM_NUMBER, NUMBER_BUFFER_SIZE),
+        };
+        let max_len = max_len.max(1);
+        if max_len > caps.max_string_size as usize {
+            Self {
+                ora_type_num: long_type_num,
+                buffer_size: TNS_MAX_LONG_LENGTH,
+                csfrm,
+            }
+        } else {
+            Self {
+                ora_type_num,
+                buffer_size: max_len as u32,
+                csfrm,
+            }
+        }
+    }
+
+    /// Describe a `RETURNING ... INTO` bind receiving values of the given
+    /// type.
+    ///
+    /// Character types are returned as VARCHAR2 (`max_size` in characters),
+    /// ROWIDs as their text and BOOLEANs as numbers before Oracle 23ai.
+    /// Other types cannot be returned.
+    pub fn returning(ty: &OracleType, caps: &Capabilities) -> Result<Self> {
+        let text = |max_chars: u32| Self {
+            ora_type_num: ORA_TYPE_NUM_VARCHAR,
+            buffer_size: max_chars.saturating_mul(4).clamp(1, caps.max_string_size),
+            csfrm: CS_FORM_IMPLICIT,
+        };
+        Ok(match ty {
+            OracleType::Varchar2 { max_size } | OracleType::Char { max_size } => text(*max_size),
+            OracleType::Rowid | OracleType::Urowid => text(TNS_MAX_STRING_SIZE / 4),
+            OracleType::Number { .. } | OracleType::BinaryInteger => {
+                Self::fixed(ORA_TYPE_NUM_NUMBER, NUMBER_BUFFER_SIZE)
+            }
+            OracleType::Date => Self::fixed(ORA_TYPE_NUM_DATE, DATE_BUFFER_SIZE),
+            OracleType::Raw { max_size } => Self {
+                ora_type_num: ORA_TYPE_NUM_RAW,
+                buffer_size: (*max_size).clamp(1, caps.max_string_size),
+                csfrm: 0,
+            },
+            OracleType::Boolean if caps.features().supports_boolean() => {
+                Self::fixed(ORA_TYPE_NUM_BOOLEAN, BOOLEAN_BUFFER_SIZE)
+            }
+            OracleType::Boolean => Self::fixed(ORA_TYPE_NUM_NUMBER, NUMBER_BUFFER_SIZE),
+            other => {
+                return Err(Error::type_conversion(format!(
+                    "cannot return {:?} values into a bind",
+                    other
+                )))
+            }
+        })
+    }
+
+    /// Describe the values received by a `RETURNING ... INTO` bind, for
+    /// decoding them.
+    pub fn returned_column(&self, name: &str, ty: &OracleType) -> ColumnMetadata {
+        let mut column = ColumnMetadata::new(name.to_string(), self.ora_type_num as u8);
+        if let OracleType::Number { precision, scale } = *ty {
+            column.precision = precision;
+            column.scale = scale;
+        }
+        column.max_size = self.buffer_size;
+        column.buffer_size = self.buffer_size;
+        column.charset_form = self.csfrm;
+        column
+    }
+
+    /// Describe a column defined to be fetched as `ora_type_num`.
+    ///
+    /// Text is fetched in the database character set, in buffers of the
+    /// largest VARCHAR2; LONG and LONG RAW values arrive whole.
+    pub fn define(ora_type_num: u16, caps: &Capabilities) -> Self {
+        match ora_type_num {
+            ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR => Self {
+                ora_type_num,
+                buffer_size: caps.max_string_size,
+                csfrm: CS_FORM_IMPLICIT,
+            },
+            ORA_TYPE_NUM_LONG => Self {
+                ora_type_num,
+                buffer_size: TNS_MAX_LONG_LENGTH,
+                csfrm: CS_FORM_IMPLICIT,
+            },
+            ORA_TYPE_NUM_LONG_RAW => Self::fixed(ora_type_num, TNS_MAX_LONG_LENGTH),
+            ORA_TYPE_NUM_NUMBER => Self::fixed(ora_type_num, NUMBER_BUFFER_SIZE),
+            ORA_TYPE_NUM_BINARY_DOUBLE => Self::fixed(ora_type_num, 8),
+            _ => Self::fixed(ora_type_num, caps.max_string_size),
+        }
+    }
+
+    /// Describe a column defined to be fetched in its own type.
+    ///
+    /// ROWIDs are fetched as their text. Columns returned as locators or
+    /// objects (see `ColumnMetadata::requires_define`) cannot be: a CLOB
+    /// or BLOB is fetched along with converted columns as LONG or LONG RAW.
+    pub fn define_column(column: &ColumnMetadata) -> Result<Self> {
+        let ora_type_num = column.oracle_type as u16;
+        if column.requires_define() || ora_type_num == ORA_TYPE_NUM_OBJECT {
+            return Err(Error::type_conversion(format!(
+                "column {} must be converted to be fetched alongside converted columns",
+                column.name
+            )));
+        }
+        Ok(match ora_type_num {
+            ORA_TYPE_NUM_ROWID | ORA_TYPE_NUM_UROWID => Self {
+                ora_type_num: ORA_TYPE_NUM_VARCHAR,
+                buffer_size: TNS_MAX_UROWID_LENGTH,
+                csfrm: CS_FORM_IMPLICIT,
+            },
+            _ => Self {
+                ora_type_num,
+                buffer_size: column.buffer_size,
+                csfrm: column.charset_form,
+            },
+        })
+    }
+
+    /// Describe the values of `column` once converted by this define, for
+    /// decoding them.
+    pub fn defined_column(&self, column: &ColumnMetadata) -> ColumnMetadata {
+        let mut defined = ColumnMetadata::new(column.name.clone(), self.ora_type_num as u8);
+        defined.nullable = column.nullable;
+        if self.ora_type_num == ORA_TYPE_NUM_NUMBER {
+            // Converted values have no declared precision
+            defined.scale = -127;
+        }
+        defined.max_size = self.buffer_size;
+        defined.buffer_size = self.buffer_size;
+        defined.charset_form = self.csfrm;
+        defined
+    }
+
+    fn fixed(ora_type_num: u16, buffer_size: u32) -> Self {
+        Self {
+            ora_type_num,
+            buffer_size,
+            csfrm: 0,
+        }
+    }
+
+    /// Whether values are sent as LONG data, after all other values of the row.
+    fn is_long(&self) -> bool {
+        self.buffer_size == TNS_MAX_LONG_LENGTH
+    }
+
+    fn charset(&self) -> u16 {
+        if self.csfrm != 0 {
+            TNS_CHARSET_UTF8
+        } else {
+            0
+        }
+    }
+
+    /// Calculate the size of the metadata, as sent for a bind or a define.
+    pub fn wire_size(&self, ttc_field_version: u8) -> usize {
+        let mut size = 4; // type, flags, precision, scale
+        size += ub4_wire_size(self.buffer_size);
+        size += ub4_wire_size(0); // max array elements
+        size += 1; // continuation flags (ub8 0)
+        size += ub4_wire_size(0); // OID
+        size += ub2_wire_size(0); // version
+        size += ub2_wire_size(self.charset());
+        size += 1; // charset form
+        size += ub4_wire_size(0); // LOB prefetch length
+        if ttc_field_version >= TNS_CCAP_FIELD_VERSION_12_2 {
+            size += ub4_wire_size(0); // oaccolid
+        }
+        size
+    }
+
+    /// Write the metadata, as sent for a bind or a define.
+    pub fn write_to(&self, buf: &mut Vec<u8>, ttc_field_version: u8) {
+        buf.write_u8(self.ora_type_num as u8);
+        buf.write_u8(TNS_BIND_USE_INDICATORS as u8);
+        buf.write_u8(0); // precision
+        buf.write_u8(0); // scale
+        buf.write_ub4(self.buffer_size);
+        buf.write_ub4(0); // max array elements
+        buf.write_ub8(0); // continuation flags
+        buf.write_ub4(0); // OID
+        buf.write_ub2(0); // version
+        buf.write_ub2(self.charset());
+        buf.write_u8(self.csfrm);
+        buf.write_ub4(0); // LOB prefetch length
+        if ttc_field_version >= TNS_CCAP_FIELD_VERSION_12_2 {
+            buf.write_ub4(0); // oaccolid
+        }
+    }
+}
+
+/// Bind values for one or more executions of a statement, encoded for the
+/// wire.
+#[derive(Debug, Clone, Default)]
+pub struct BindParams {
+    binds: Vec<BindInfo>,
+    /// Encoded values of each row; `None` is NULL.
+    rows: Vec<Vec<Option<Vec<u8>>>>,
+    /// Number of trailing `RETURNING ... INTO` binds, which have no values.
+    num_returning: usize,
+}
+
+impl BindParams {
+    /// Encode rows of bind values.
+    ///
+    /// Every row must have the same number of values. NULLs may appear in
+    /// any position; other values in a position must all be of one kind.
+    pub fn new(rows: &[Vec<OracleValue>], caps: &Capabilities) -> Result<Self> {
+        let num_binds = rows.first().map_or(0, Vec::len);
+        let mut kinds: Vec<Option<BindKind>> = vec![None; num_binds];
+        let mut encoded = Vec::with_capacity(rows.len());
+
+        for (row_index, row) in rows.iter().enumerate() {
+            if row.len() != num_binds {
+                return Err(Error::BindCountMismatch {
+                    row: row_index,
+                    expected: num_binds,
+                    actual: row.len(),
+                });
+            }
+            let mut values = Vec::with_capacity(num_binds);
+            for (position, (value, known)) in row.iter().zip(kinds.iter_mut()).enumerate() {
+                if let Some(kind) = BindKind::of(value)? {
+                    match known {
+                        None => *known = Some(kind),
+                        Some(k) if *k != kind => {
+                            return Err(Error::type_conversion(format!(
+                                "bind position {} mixes {} and {} values",
+                                position + 1,
+                                k.name(),
+                                kind.name()
+                            )))
+                        }
+                        Some(_) => {}
+                    }
+                }
+                values.push(encode_value(value, caps)?);
+            }
+            encoded.push(values);
+        }
+
+        let binds = kinds
+            .iter()
+            .enumerate()
+            .map(|(position, &kind)| {
+                let max_len = encoded
+                    .iter()
+                    .filter_map(|row: &Vec<Option<Vec<u8>>>| row.get(position)?.as_ref())
+                    .map(Vec::len)
+                    .max()
+                    .unwrap_or(0);
+                BindInfo::new(kind, max_len, caps)
+            })
+            .collect();
+
+        Ok(Self {
+            binds,
+            rows: encoded,
+            num_returning: 0,
+        })
+    }
+
+    /// Add binds receiving the values of a `RETURNING ... INTO` clause,
+    /// after the input binds.
+    pub fn with_returning(mut self, returning: &[BindInfo]) -> Self {
+        self.binds.extend_from_slice(returning);
+        self.num_returning += returning.len();
+        self
+    }
+
+    /// Whether any rows of values are sent: not when every bind is a
+    /// `RETURNING ... INTO` target.
+    fn has_values(&self) -> bool {
+        self.num_returning < self.binds.len()
+    }
+
+    /// Get the description of each bind position.
+    pub fn binds(&self) -> &[BindInfo] {
+        &self.binds
+    }
+
+    /// Get the number of bind positions.
+    pub fn num_binds(&self) -> usize {
+        self.binds.len()
+    }
+
+    /// Get the number of rows (executions).
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Calculate the size of the bind metadata and row data.
+    pub fn wire_size(&self, ttc_field_version: u8) -> usize {
+        let metadata: usize = self
+            .binds
+            .iter()
+            .map(|b| b.wire_size(ttc_field_version))
+            .sum();
+        metadata + self.values_wire_size()
+    }
+
+    /// Calculate the size of the row data alone.
+    pub fn values_wire_size(&self) -> usize {
+        if !self.has_values() {
+            return 0;
+        }
+        self.rows
+            .iter()
+            .map(|row| {
+                1 + row
+                    .iter()
+                    .map(|v| {
+                        v.as_ref()
+                            .map_or(1, |v| bytes_with_length_wire_size(v.len()))
+                    })
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Write the bind metadata followed by one row data message per row.
+    pub fn write_to(&self, buf: &mut Vec<u8>, ttc_field_version: u8) {
+        for bind in &self.binds {
+            bind.write_to(buf, ttc_field_version);
+        }
+        self.write_values_to(buf);
+    }
+
+    /// Write one row data message per row, without the bind metadata, for
+    /// a cursor already bound with it.
+    pub fn write_values_to(&self, buf: &mut Vec<u8>) {
+        if !self.has_values() {
+            return;
+        }
+        for row in &self.rows {
+            buf.write_u8(TNS_MSG_TYPE_ROW_DATA);
+            // LONG values go last
+            for long in [false, true] {
+                for (value, bind) in row.iter().zip(&self.binds) {
+                    if bind.is_long() != long {
+                        continue;
+                    }
+                    match value {
+                        Some(bytes) => buf.write_bytes_with_length(bytes),
+                        None => buf.write_u8(TNS_NULL_LENGTH_INDICATOR),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Encode a value for the wire, or `None` for NULL.
+///
+/// Empty strings and byte strings are NULL in Oracle.
+fn encode_value(value: &OracleValue, caps: &Capabilities) -> Result<Option<Vec<u8>>> {
+    let bytes = match value {
+        OracleValue::String(s) => s.as_bytes().to_vec(),
+        OracleValue::Raw(bytes) => bytes.clone(),
+        OracleValue::Number(n) => encode_oracle_number(n)?,
+        OracleValue::Integer(i) => encode_oracle_integer(*i),
+        #[cfg(feature = "rust_decimal")]
+        OracleValue::Decimal(d) => encode_oracle_decimal(d),
+        OracleValue::Date(dt) => encode_oracle_date(dt)?.to_vec(),
+        OracleValue::Boolean(b) if caps.features().supports_boolean() => {
+            if *b {
+                vec![1, 1]
+            } else {
+                vec![0]
+            }
+        }
+        OracleValue::Boolean(b) => encode_oracle_number(if *b { "1" } else { "0" })?,
+        _ => return Ok(None),
+    };
+    Ok((!bytes.is_empty()).then_some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn caps(server_ttc_field_version: u8) -> Capabilities {
+        let mut caps = Capabilities::new();
+        caps.server_ttc_field_version = server_ttc_field_version;
+        caps
+    }
+
+    #[test]
+    fn test_bind_params_types() {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 21)
+            .unwrap()
+            .and_hms_opt(12, 36, 5)
+            .unwrap();
+        let rows = vec![
+            vec![
+                OracleValue::Number("1".to_string()),
+                OracleValue::String("a".to_string()),
+                OracleValue::Null,
+                OracleValue::Date(date),
+            ],
+            vec![
+                OracleValue::Number("2".to_string()),
+                OracleValue::String("héllo".to_string()),
+                OracleValue::Raw(vec![1, 2, 3]),
+                OracleValue::Null,
+            ],
+        ];
+        let params = BindParams::new(&rows, &caps(13)).unwrap();
+        assert_eq!(params.num_rows(), 2);
+        assert_eq!(
+            params.binds(),
+            &[
+                BindInfo::fixed(ORA_TYPE_NUM_NUMBER, 22),
+                BindInfo {
+                    ora_type_num: ORA_TYPE_NUM_VARCHAR,
+                    buffer_size: 6,
+                    csfrm: CS_FORM_IMPLICIT,
+                },
+                BindInfo {
+                    ora_type_num: ORA_TYPE_NUM_RAW,
+                    buffer_size: 3,
+                    csfrm: 0,
+                },
+                BindInfo::fixed(ORA_TYPE_NUM_DATE, 7),
+            ]
+        );
+
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert_eq!(buf.len(), params.wire_size(13));
+        // Second row: number 2, "héllo", raw, NULL
+        let row = [
+            &[TNS_MSG_TYPE_ROW_DATA, 2, 0xC1, 0x03, 6][..],
+            "héllo".as_bytes(),
+            &[3, 1, 2, 3, 0],
+        ]
+        .concat();
+        assert!(buf.ends_with(&row));
+    }
+
+    #[test]
+    fn test_bind_params_nulls_and_booleans() {
+        let rows = vec![
+            vec![OracleValue::Null, OracleValue::Boolean(true)],
+            vec![
+                OracleValue::String(String::new()),
+                OracleValue::Boolean(false),
+            ],
+        ];
+        let params = BindParams::new(&rows, &caps(TNS_CCAP_FIELD_VERSION_23_1)).unwrap();
+        assert_eq!(params.binds()[0].ora_type_num, ORA_TYPE_NUM_VARCHAR);
+        assert_eq!(params.binds()[0].buffer_size, 1);
+        assert_eq!(params.binds()[1].ora_type_num, ORA_TYPE_NUM_BOOLEAN);
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert!(buf.ends_with(&[
+            TNS_MSG_TYPE_ROW_DATA,
+            0,
+            2,
+            1,
+            1,
+            TNS_MSG_TYPE_ROW_DATA,
+            0,
+            1,
+            0
+        ]));
+
+        // Older servers get 1 and 0
+        let params = BindParams::new(&rows, &caps(13)).unwrap();
+        assert_eq!(params.binds()[1].ora_type_num, ORA_TYPE_NUM_NUMBER);
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert!(buf.ends_with(&[TNS_MSG_TYPE_ROW_DATA, 0, 1, 0x80]));
+    }
+
+    #[test]
+    fn test_bind_params_long_values_last() {
+        let long = "x".repeat(5000);
+        let rows = vec![vec![
+            OracleValue::String(long.clone()),
+            OracleValue::Number("7".to_string()),
+        ]];
+        let mut caps = caps(13);
+        caps.max_string_size = TNS_MAX_STRING_SIZE;
+        let params = BindParams::new(&rows, &caps).unwrap();
+        assert_eq!(params.binds()[0].ora_type_num, ORA_TYPE_NUM_LONG);
+
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert_eq!(buf.len(), params.wire_size(13));
+        let row_start = buf
+            .iter()
+            .rposition(|&b| b == TNS_MSG_TYPE_ROW_DATA)
+            .unwrap();
+        assert_eq!(
+            &buf[row_start..row_start + 4],
+            &[TNS_MSG_TYPE_ROW_DATA, 2, 0xC1, 0x08]
+        );
+    }
+
+    #[test]
+    fn test_bind_params_returning() {
+        let caps = caps(13);
+        let returning = [
+            BindInfo::returning(
+                &OracleType::Number {
+                    precision: 0,
+                    scale: -127,
+                },
+                &caps,
+            )
+            .unwrap(),
+            BindInfo::returning(&OracleType::Varchar2 { max_size: 10 }, &caps).unwrap(),
+        ];
+        assert_eq!(returning[0], BindInfo::fixed(ORA_TYPE_NUM_NUMBER, 22));
+        assert_eq!(returning[1].buffer_size, 40);
+        assert!(BindInfo::returning(&OracleType::Clob, &caps).is_err());
+
+        // Returning binds are described after the inputs but carry no values
+        let rows = vec![vec![OracleValue::Integer(7)]];
+        let params = BindParams::new(&rows, &caps)
+            .unwrap()
+            .with_returning(&returning);
+        assert_eq!(params.num_binds(), 3);
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert_eq!(buf.len(), params.wire_size(13));
+        assert!(buf.ends_with(&[TNS_MSG_TYPE_ROW_DATA, 2, 0xC1, 0x08]));
+
+        // With only returning binds, no row data is sent
+        let params = BindParams::new(&[vec![]], &caps)
+            .unwrap()
+            .with_returning(&returning);
+        let mut buf = Vec::new();
+        params.write_to(&mut buf, 13);
+        assert_eq!(buf.len(), params.wire_size(13));
+        assert!(!buf.contains(&TNS_MSG_TYPE_ROW_DATA));
+
+        let column = returning[1].returned_column("NAME", &OracleType::Varchar2 { max_size: 10 });
+        assert_eq!(column.oracle_type as u16, ORA_TYPE_NUM_VARCHAR);
+        assert_eq!(column.charset_form, CS_FORM_IMPLICIT);
+    }
+
+    #[test]
+    fn test_bind_params_errors() {
+        let caps = caps(13);
+        let rows = vec![
+            vec![OracleValue::Number("1".to_string())],
+            vec![OracleValue::Number("1".to_string()), OracleValue::Null],
+        ];
+        assert!(matches!(
+            BindParams::new(&rows, &caps),
+            Err(Error::BindCountMismatch {
+                row: 1,
+                expected: 1,
+                actual: 2
+            })
+        ));
+
+        let rows = vec![
+            vec![OracleValue::Number("1".to_string())],
+            vec![OracleValue::String("1".to_string())],
+        ];
+        assert!(BindParams::new(&rows, &caps).is_err());
+
+        let rows = vec![vec![OracleValue::Number("not a number".to_string())]];
+        assert!(BindParams::new(&rows, &caps).is_err());
+
+        let rows = vec![vec![OracleValue::IntervalYM {
+            years: 1,
+            months: 2,
+        }]];
+        assert!(BindParams::new(&rows, &caps).is_err());
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::error::{Error, Result};
 use crate::protocol::constants::*;
+use crate::protocol::message::{
+    bytes_with_length_wire_size, ub2_wire_size, ub4_wire_size, ub8_wire_size, WriteExt,
+};
 use bytes::{BufMut, Bytes, BytesMut};
 
 /// A buffer for reading TNS protocol data.
@@ -10,6 +13,8 @@ pub struct ReadBuffer {
     pos: usize,
 }
 
+// Server data is untrusted: reads must fail with an error, never panic.
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
 impl ReadBuffer {
     /// Create a new read buffer from bytes.
     pub fn new(data: Bytes) -> Self {
@@ -33,7 +38,21 @@ impl ReadBuffer {
 
     /// Get a slice of the remaining data.
     pub fn as_slice(&self) -> &[u8] {
-        &self.data[self.pos..]
+        self.data.get(self.pos..).unwrap_or_default()
+    }
+
+    /// Take the next `N` bytes as an array.
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .data
+            .get(self.pos..)
+            .and_then(|rest| rest.first_chunk::<N>())
+            .ok_or(Error::BufferTooSmall {
+                needed: N,
+                available: self.remaining(),
+            })?;
+        self.pos += N;
+        Ok(*bytes)
     }
 
     /// Skip `n` bytes.
@@ -50,73 +69,27 @@ impl ReadBuffer {
 
     /// Read a single byte.
     pub fn read_u8(&mut self) -> Result<u8> {
-        if !self.has_remaining(1) {
-            return Err(Error::BufferTooSmall {
-                needed: 1,
-                available: self.remaining(),
-            });
-        }
-        let val = self.data[self.pos];
-        self.pos += 1;
-        Ok(val)
+        Ok(u8::from_be_bytes(self.read_array()?))
     }
 
     /// Read a big-endian u16.
     pub fn read_u16_be(&mut self) -> Result<u16> {
-        if !self.has_remaining(2) {
-            return Err(Error::BufferTooSmall {
-                needed: 2,
-                available: self.remaining(),
-            });
-        }
-        let val = u16::from_be_bytes([self.data[self.pos], self.data[self.pos + 1]]);
-        self.pos += 2;
-        Ok(val)
+        Ok(u16::from_be_bytes(self.read_array()?))
     }
 
     /// Read a little-endian u16.
     pub fn read_u16_le(&mut self) -> Result<u16> {
-        if !self.has_remaining(2) {
-            return Err(Error::BufferTooSmall {
-                needed: 2,
-                available: self.remaining(),
-            });
-        }
-        let val = u16::from_le_bytes([self.data[self.pos], self.data[self.pos + 1]]);
-        self.pos += 2;
-        Ok(val)
+        Ok(u16::from_le_bytes(self.read_array()?))
     }
 
     /// Read a big-endian u32.
     pub fn read_u32_be(&mut self) -> Result<u32> {
-        if !self.has_remaining(4) {
-            return Err(Error::BufferTooSmall {
-                needed: 4,
-                available: self.remaining(),
-            });
-        }
-        let val = u32::from_be_bytes([
-            self.data[self.pos],
-            self.data[self.pos + 1],
-            self.data[self.pos + 2],
-            self.data[self.pos + 3],
-        ]);
-        self.pos += 4;
-        Ok(val)
+        Ok(u32::from_be_bytes(self.read_array()?))
     }
 
     /// Read a big-endian u64.
     pub fn read_u64_be(&mut self) -> Result<u64> {
-        if !self.has_remaining(8) {
-            return Err(Error::BufferTooSmall {
-                needed: 8,
-                available: self.remaining(),
-            });
-        }
-        let mut bytes = [0u8; 8];
-        bytes.copy_from_slice(&self.data[self.pos..self.pos + 8]);
-        self.pos += 8;
-        Ok(u64::from_be_bytes(bytes))
+        Ok(u64::from_be_bytes(self.read_array()?))
     }
 
     /// Read raw bytes.
@@ -193,6 +166,18 @@ impl ReadBuffer {
         Ok(val)
     }
 
+    /// Read a variable-length i64 (Oracle's SB8 format).
+    /// The high bit of the length byte marks a negative value.
+    pub fn read_sb8(&mut self) -> Result<i64> {
+        let length = self.read_u8()?;
+        let mut val: u64 = 0;
+        for _ in 0..(length & 0x7F) {
+            val = (val << 8) | (self.read_u8()? as u64);
+        }
+        let val = val as i64;
+        Ok(if length & 0x80 != 0 { -val } else { val })
+    }
+
     /// Skip a variable-length u32 (Oracle's UB4 format).
     /// Reads the length byte and skips that many bytes.
     pub fn skip_ub4(&mut self) -> Result<()> {
@@ -273,6 +258,124 @@ impl ReadBuffer {
     }
 }
 
+// ============================================================================
+// Typed Wire Values
+// ============================================================================
+
+/// A value with a TNS wire encoding.
+///
+/// Each format is defined once, by the wrappers below, and `write_ub2`,
+/// `write_ub4` and friends encode through them. A field holding a `u16` or
+/// `u32` is bounded by its type; a length taken from `usize` is converted
+/// with `Ub2::try_from` or `Ub4::try_from`, which fails rather than
+/// truncating as an `as` cast would.
+pub trait WireValue {
+    /// Number of bytes the value takes on the wire.
+    fn wire_size(&self) -> usize;
+
+    /// Append the encoded value to `buf`.
+    fn encode<B: BufMut + ?Sized>(&self, buf: &mut B);
+}
+
+/// A UB2: a length byte followed by up to 2 big-endian bytes, leading zero
+/// bytes dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ub2(pub u16);
+
+/// A UB4: a length byte followed by up to 4 big-endian bytes, leading zero
+/// bytes dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ub4(pub u32);
+
+/// A UB8: a length byte followed by up to 8 big-endian bytes, leading zero
+/// bytes dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ub8(pub u64);
+
+/// Bytes with a length prefix: a length byte for short values, or
+/// `TNS_LONG_LENGTH_INDICATOR` followed by UB4-prefixed chunks of up to
+/// `TNS_CHUNK_SIZE` bytes and a zero UB4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthPrefixedBytes<'a>(pub &'a [u8]);
+
+/// Write the low `len` bytes of `val`, most significant first, after a
+/// length byte.
+fn encode_var_uint<B: BufMut + ?Sized>(buf: &mut B, val: u64, len: usize) {
+    buf.put_u8(len as u8);
+    buf.put_slice(&val.to_be_bytes()[8 - len..]);
+}
+
+impl WireValue for Ub2 {
+    fn wire_size(&self) -> usize {
+        ub2_wire_size(self.0)
+    }
+
+    fn encode<B: BufMut + ?Sized>(&self, buf: &mut B) {
+        encode_var_uint(buf, u64::from(self.0), self.wire_size() - 1);
+    }
+}
+
+impl WireValue for Ub4 {
+    fn wire_size(&self) -> usize {
+        ub4_wire_size(self.0)
+    }
+
+    fn encode<B: BufMut + ?Sized>(&self, buf: &mut B) {
+        encode_var_uint(buf, u64::from(self.0), self.wire_size() - 1);
+    }
+}
+
+impl WireValue for Ub8 {
+    fn wire_size(&self) -> usize {
+        ub8_wire_size(self.0)
+    }
+
+    fn encode<B: BufMut + ?Sized>(&self, buf: &mut B) {
+        encode_var_uint(buf, self.0, self.wire_size() - 1);
+    }
+}
+
+impl WireValue for LengthPrefixedBytes<'_> {
+    fn wire_size(&self) -> usize {
+        bytes_with_length_wire_size(self.0.len())
+    }
+
+    fn encode<B: BufMut + ?Sized>(&self, buf: &mut B) {
+        let bytes = self.0;
+        if bytes.len() < TNS_LONG_LENGTH_INDICATOR as usize {
+            buf.put_u8(bytes.len() as u8);
+            buf.put_slice(bytes);
+        } else {
+            buf.put_u8(TNS_LONG_LENGTH_INDICATOR);
+            for chunk in bytes.chunks(TNS_CHUNK_SIZE) {
+                Ub4(chunk.len() as u32).encode(buf);
+                buf.put_slice(chunk);
+            }
+            Ub4(0).encode(buf);
+        }
+    }
+}
+
+impl TryFrom<usize> for Ub2 {
+    type Error = Error;
+
+    fn try_from(val: usize) -> Result<Self> {
+        u16::try_from(val)
+            .map(Self)
+            .map_err(|_| Error::protocol(format!("{} does not fit in a UB2", val)))
+    }
+}
+
+impl TryFrom<usize> for Ub4 {
+    type Error = Error;
+
+    fn try_from(val: usize) -> Result<Self> {
+        u32::try_from(val)
+            .map(Self)
+            .map_err(|_| Error::protocol(format!("{} does not fit in a UB4", val)))
+    }
+}
+
 /// A buffer for writing TNS protocol data.
 pub struct WriteBuffer {
     data: BytesMut,
@@ -318,109 +421,72 @@ impl WriteBuffer {
 
     /// Write a single byte.
     pub fn write_u8(&mut self, val: u8) {
-        self.data.put_u8(val);
+        WriteExt::write_u8(self, val);
     }
 
     /// Write a big-endian u16.
     pub fn write_u16_be(&mut self, val: u16) {
-        self.data.put_u16(val);
+        WriteExt::write_u16_be(self, val);
     }
 
     /// Write a little-endian u16.
     pub fn write_u16_le(&mut self, val: u16) {
-        self.data.put_u16_le(val);
+        WriteExt::write_u16_le(self, val);
     }
 
     /// Write a big-endian u32.
     pub fn write_u32_be(&mut self, val: u32) {
-        self.data.put_u32(val);
+        WriteExt::write_u32_be(self, val);
     }
 
     /// Write a big-endian u64.
     pub fn write_u64_be(&mut self, val: u64) {
-        self.data.put_u64(val);
+        WriteExt::write_u64_be(self, val);
     }
 
     /// Write raw bytes.
     pub fn write_bytes(&mut self, bytes: &[u8]) {
-        self.data.extend_from_slice(bytes);
+        WriteExt::write_bytes(self, bytes);
     }
 
     /// Write a variable-length unsigned integer (Oracle's UB format).
     pub fn write_ub1(&mut self, val: u8) {
-        self.write_u8(val);
+        WriteExt::write_ub1(self, val);
+    }
+
+    /// Write a typed wire value.
+    pub fn write_value(&mut self, value: impl WireValue) {
+        WriteExt::write_value(self, value);
     }
 
     /// Write a variable-length u16 (Oracle's UB2 format).
     pub fn write_ub2(&mut self, val: u16) {
-        if val == 0 {
-            self.write_u8(0);
-        } else if val <= 0xff {
-            self.write_u8(1);
-            self.write_u8(val as u8);
-        } else {
-            self.write_u8(2);
-            self.write_u8(val as u8);
-            self.write_u8((val >> 8) as u8);
-        }
+        WriteExt::write_ub2(self, val);
     }
 
     /// Write a variable-length u32 (Oracle's UB4 format).
     pub fn write_ub4(&mut self, val: u32) {
-        if val == 0 {
-            self.write_u8(0);
-        } else {
-            let bytes = val.to_le_bytes();
-            let len = 4 - (val.leading_zeros() / 8) as usize;
-            self.write_u8(len as u8);
-            self.data.extend_from_slice(&bytes[..len]);
-        }
+        WriteExt::write_ub4(self, val);
     }
 
     /// Write a variable-length u64 (Oracle's UB8 format).
     pub fn write_ub8(&mut self, val: u64) {
-        if val == 0 {
-            self.write_u8(0);
-        } else {
-            let bytes = val.to_le_bytes();
-            let len = 8 - (val.leading_zeros() / 8) as usize;
-            self.write_u8(len as u8);
-            self.data.extend_from_slice(&bytes[..len]);
-        }
+        WriteExt::write_ub8(self, val);
     }
 
     /// Write bytes with a length prefix.
     pub fn write_bytes_with_length(&mut self, bytes: &[u8]) {
-        let len = bytes.len();
-        if len == 0 {
-            self.write_u8(0);
-        } else if len < TNS_LONG_LENGTH_INDICATOR as usize {
-            self.write_u8(len as u8);
-            self.write_bytes(bytes);
-        } else {
-            // Chunked write for long values
-            self.write_u8(TNS_LONG_LENGTH_INDICATOR);
-            let mut offset = 0;
-            while offset < len {
-                let chunk_len = std::cmp::min(len - offset, 65536);
-                self.write_ub4(chunk_len as u32);
-                self.write_bytes(&bytes[offset..offset + chunk_len]);
-                offset += chunk_len;
-            }
-            self.write_ub4(0); // End of chunks
-        }
+        WriteExt::write_bytes_with_length(self, bytes);
     }
 
     /// Write a string with a length prefix.
     pub fn write_str_with_length(&mut self, s: &str) {
-        self.write_bytes_with_length(s.as_bytes());
+        WriteExt::write_str_with_length(self, s);
     }
 
     /// Write padding zeros.
     pub fn write_zeros(&mut self, count: usize) {
-        for _ in 0..count {
-            self.write_u8(0);
-        }
+        WriteExt::write_zeros(self, count);
     }
 
     /// Set a u16 value at a specific position (big-endian).
@@ -440,8 +506,101 @@ impl WriteBuffer {
     }
 }
 
+impl WriteExt for WriteBuffer {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn write_value(&mut self, value: impl WireValue) {
+        value.encode(&mut self.data);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, val: u8) {
+        self.data.put_u8(val);
+    }
+
+    #[inline]
+    fn write_zeros(&mut self, count: usize) {
+        self.data.put_bytes(0, count);
+    }
+}
+
 impl Default for WriteBuffer {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(value: impl WireValue) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let size = value.wire_size();
+        value.encode(&mut buf);
+        assert_eq!(buf.len(), size);
+        buf
+    }
+
+    #[test]
+    fn test_wire_value_encoding() {
+        assert_eq!(encoded(Ub2(0)), [0]);
+        assert_eq!(encoded(Ub2(0x12)), [1, 0x12]);
+        assert_eq!(encoded(Ub2(0x1234)), [2, 0x12, 0x34]);
+        assert_eq!(encoded(Ub4(0x0001_86A0)), [3, 0x01, 0x86, 0xA0]);
+        assert_eq!(encoded(Ub4(u32::MAX)), [4, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(encoded(Ub8(0x0100_0000_0000)), [6, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(encoded(LengthPrefixedBytes(b"")), [0]);
+        assert_eq!(encoded(LengthPrefixedBytes(b"ab")), [2, b'a', b'b']);
+
+        let long = vec![7u8; TNS_CHUNK_SIZE + 1];
+        let buf = encoded(LengthPrefixedBytes(&long));
+        assert_eq!(buf[..4], [TNS_LONG_LENGTH_INDICATOR, 2, 0x7F, 0xFF]);
+        assert_eq!(buf[buf.len() - 4..], [1, 1, 7, 0]);
+    }
+
+    #[test]
+    fn test_wire_value_round_trip() {
+        for val in [0u32, 1, 0xFF, 0x100, 0xFFFF, 0x10000, 100_000, u32::MAX] {
+            let mut buf = ReadBuffer::new(encoded(Ub4(val)).into());
+            assert_eq!(buf.read_ub4().unwrap(), val);
+        }
+        for val in [0u16, 0xFF, 0x100, u16::MAX] {
+            let mut buf = ReadBuffer::new(encoded(Ub2(val)).into());
+            assert_eq!(buf.read_ub2().unwrap(), val);
+        }
+        let mut buf = ReadBuffer::new(encoded(Ub8(u64::MAX)).into());
+        assert_eq!(buf.read_ub8().unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_wire_value_try_from() {
+        assert_eq!(Ub2::try_from(65535).unwrap(), Ub2(u16::MAX));
+        assert!(Ub2::try_from(65536).is_err());
+        assert_eq!(Ub4::try_from(100_000).unwrap(), Ub4(100_000));
+        #[cfg(target_pointer_width = "64")]
+        assert!(Ub4::try_from(1usize << 32).is_err());
+    }
+
+    #[test]
+    fn test_write_buffer_matches_vec() {
+        let long = vec![3u8; 70_000];
+        let mut buf = WriteBuffer::new();
+        let mut vec = Vec::new();
+        for val in [0u32, 0x1234, 0x0012_3456] {
+            buf.write_ub4(val);
+            vec.write_ub4(val);
+        }
+        buf.write_ub2(0x1234);
+        vec.write_ub2(0x1234);
+        buf.write_ub8(0x0102_0304_0506);
+        vec.write_ub8(0x0102_0304_0506);
+        buf.write_bytes_with_length(&long);
+        vec.write_bytes_with_length(&long);
+        assert_eq!(buf.as_bytes(), &vec[..]);
+    }
+}
@@ -0,0 +1,95 @@
+//! Time source for timeouts.
+//!
+//! Timeout logic takes a `Clock` instead of calling the tokio timer
+//! directly, so tests can drive it with a paused or mock clock rather than
+//! real sleeps.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Future returned by `Clock::sleep`.
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A source of time for timeouts.
+pub trait Clock: Send + Sync {
+    /// Complete once `duration` has elapsed.
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// Clock backed by the tokio timer.
+///
+/// Follows tokio's paused time (`tokio::time::pause`), which auto-advances
+/// whenever the runtime is idle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Run `future` until it completes or `duration` elapses on `clock`.
+///
+/// Returns `None` on timeout. A future that completes on the same poll as
+/// the timer wins.
+pub async fn timeout<F: Future>(
+    clock: &dyn Clock,
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        output = future => Some(output),
+        () = clock.sleep(duration) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clock whose timers have always elapsed.
+    struct ElapsedClock;
+
+    impl Clock for ElapsedClock {
+        fn sleep(&self, _duration: Duration) -> Sleep {
+            Box::pin(std::future::ready(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_mock_clock() {
+        let clock = ElapsedClock;
+        let pending = std::future::pending::<()>();
+        assert_eq!(
+            timeout(&clock, Duration::from_secs(3600), pending).await,
+            None
+        );
+        // A ready future beats an elapsed timer
+        let ready = std::future::ready(7);
+        assert_eq!(timeout(&clock, Duration::ZERO, ready).await, Some(7));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeout_paused_tokio_clock() {
+        let start = tokio::time::Instant::now();
+        let pending = std::future::pending::<()>();
+        assert_eq!(
+            timeout(&TokioClock, Duration::from_secs(20), pending).await,
+            None
+        );
+        assert_eq!(start.elapsed(), Duration::from_secs(20));
+
+        let delayed = async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            "done"
+        };
+        assert_eq!(
+            timeout(&TokioClock, Duration::from_secs(20), delayed).await,
+            Some("done")
+        );
+        assert_eq!(start.elapsed(), Duration::from_secs(21));
+    }
+}
@@ -1,17 +1,76 @@
 //! TNS connection establishment (CONNECT/ACCEPT handshake).
 
 use crate::error::{Error, Result};
-use crate::protocol::auth::{AuthCredentials, SessionData};
+use crate::protocol::accept::AcceptInfo;
+use crate::protocol::auth::{AuthCredentials, AuthMethod, Privilege, SessionData};
 use crate::protocol::buffer::ReadBuffer;
+use crate::protocol::clock::{self, Clock};
 use crate::protocol::constants::*;
+use crate::protocol::control::Control;
 use crate::protocol::messages::{
     AuthPhaseOneMessage, ConnectMessage, DataTypesMessage, FastAuthMessage, MarkerMessage,
     ProtocolMessage,
 };
-use crate::protocol::packet::{Capabilities, Packet, PacketStream};
+use crate::protocol::packet::{Capabilities, Packet, PacketStream, DEFAULT_MAX_RESPONSE_SIZE};
+use crate::protocol::proxy::Proxy;
+use crate::protocol::session::SessionSettings;
+#[cfg(feature = "tls")]
+use crate::protocol::tls::TlsConfig;
+use crate::protocol::tnsnames::{find_nodes, find_value, parse_descriptor, DescriptorNode};
+use crate::protocol::transport::Transport;
 use base64::Engine;
+use rand::seq::SliceRandom;
 use rand::RngCore;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
 use std::time::Duration;
+use tokio::net::{TcpSocket, TcpStream};
+
+/// Network protocol of the listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Protocol {
+    /// Plain TCP.
+    #[default]
+    Tcp,
+    /// TLS over TCP (requires the `tls` feature).
+    Tcps,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tcp => "tcp",
+            Self::Tcps => "tcps",
+        })
+    }
+}
+
+/// Listener address (host and port).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    /// Host name or IP address.
+    pub host: String,
+    /// Port number.
+    pub port: u16,
+}
+
+impl Address {
+    /// Create a listener address.
+    pub fn new(host: impl Into<String>, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
 
 /// Connection parameters.
 #[derive(Debug, Clone)]
@@ -20,12 +79,52 @@ pub struct ConnectParams {
     pub host: String,
     /// Port number.
     pub port: u16,
+    /// Addresses tried, in order, when `host:port` cannot be reached (for
+    /// RAC nodes or a standby). With `source_route`, the hops after
+    /// `host:port` instead.
+    pub failover_addresses: Vec<Address>,
+    /// Try the addresses in random order (`LOAD_BALANCE`; default: off).
+    pub load_balance: bool,
+    /// Try the next address when one cannot be reached (`FAILOVER`;
+    /// default: on).
+    pub failover: bool,
+    /// Treat the addresses as a route through Oracle Connection Managers
+    /// (`SOURCE_ROUTE`; default: off): connect to the first, which
+    /// forwards the connection to the next.
+    pub source_route: bool,
     /// Service name.
     pub service_name: String,
-    /// SDU (Session Data Unit) size.
+    /// Network protocol (default: TCP).
+    pub protocol: Protocol,
+    /// Certificates and keys for TCPS connections.
+    #[cfg(feature = "tls")]
+    pub tls: Option<TlsConfig>,
+    /// How the session is authenticated (default: username and password).
+    pub auth: AuthMethod,
+    /// Administrative privilege the session is opened with (default: none).
+    pub privilege: Option<Privilege>,
+    /// SDU (Session Data Unit) size requested, in bytes (default: 8192).
+    /// The server may lower it.
     pub sdu: u32,
-    /// TCP connection timeout (default: 20 seconds, matching python-oracledb).
+    /// Timeout for host resolution and for each TCP connection attempt
+    /// (default: 20 seconds, matching python-oracledb).
     pub connect_timeout: Duration,
+    /// Largest response, in bytes, accepted from the server (default: 1 GiB).
+    pub max_response_size: usize,
+    /// Session settings applied right after authentication.
+    pub session_settings: SessionSettings,
+    /// Idle time after which TCP keepalive probes are sent (the equivalent
+    /// of `SQLNET.EXPIRE_TIME`; default: none).
+    pub expire_time: Option<Duration>,
+    /// Whether to interrupt calls with out-of-band breaks (default: false).
+    pub oob_break: bool,
+    /// TCP send buffer size, in bytes (`SO_SNDBUF`; default: the OS's).
+    pub send_buffer_size: Option<u32>,
+    /// TCP receive buffer size, in bytes (`SO_RCVBUF`; default: the OS's).
+    pub recv_buffer_size: Option<u32>,
+    /// SOCKS5 or HTTP proxy the connection is tunneled through (default:
+    /// none).
+    pub proxy: Option<Proxy>,
 }
 
 impl ConnectParams {
@@ -34,9 +133,25 @@ impl ConnectParams {
         Self {
             host: host.into(),
             port,
+            failover_addresses: Vec::new(),
+            load_balance: false,
+            failover: true,
+            source_route: false,
             service_name: service_name.into(),
+            protocol: Protocol::Tcp,
+            #[cfg(feature = "tls")]
+            tls: None,
+            auth: AuthMethod::Password,
+            privilege: None,
             sdu: TNS_SDU_DEFAULT,
             connect_timeout: Duration::from_secs(20), // Python default
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            session_settings: SessionSettings::default(),
+            expire_time: None,
+            oob_break: false,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            proxy: None,
         }
     }
 
@@ -44,7 +159,8 @@ impl ConnectParams {
     ///
     /// # Arguments
     ///
-    /// * `timeout` - The maximum time to wait for TCP connection establishment
+    /// * `timeout` - The maximum time to wait for host resolution, and for
+    ///   TCP connection establishment to each resolved address
     ///
     /// # Example
     ///
@@ -60,7 +176,280 @@ impl ConnectParams {
         self
     }
 
+    /// Set the largest response, in bytes, accepted from the server.
+    ///
+    /// A query returning more inline data than this in one round trip fails
+    /// with `Error::ResponseTooLarge` instead of buffering it, and the
+    /// connection can no longer be used. Fetch rows in smaller batches (see
+    /// `QueryOptions`) to stay below the limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "ORCL")
+    ///     .with_max_response_size(64 * 1024 * 1024);
+    /// assert_eq!(params.max_response_size, 64 * 1024 * 1024);
+    /// ```
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Set the SDU (Session Data Unit) size requested, like `SDU` in a
+    /// connect descriptor.
+    ///
+    /// The SDU is the largest packet exchanged with the server. A larger
+    /// SDU takes fewer packets for large fetches and LOB transfers; the
+    /// server lowers it to its own setting if that is smaller. Values are
+    /// clamped to 512 bytes..2 MiB, as in python-oracledb.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "ORCL").with_sdu(65535);
+    /// assert_eq!(params.sdu, 65535);
+    /// assert_eq!(ConnectParams::new("localhost", 1521, "ORCL").with_sdu(1 << 30).sdu, 2097152);
+    /// ```
+    pub fn with_sdu(mut self, sdu: u32) -> Self {
+        self.sdu = sdu.clamp(TNS_SDU_MIN, TNS_SDU_MAX);
+        self
+    }
+
+    /// Set the TCP send and receive buffer sizes, like `SEND_BUF_SIZE` and
+    /// `RECV_BUF_SIZE` in a connect descriptor.
+    ///
+    /// Buffers of about the bandwidth-delay product let large fetches use
+    /// the full bandwidth of high-latency links. `None` keeps the operating
+    /// system's size. The sizes are set before connecting, so the receive
+    /// buffer also sets the TCP window scale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "ORCL")
+    ///     .with_sdu(65535)
+    ///     .with_socket_buffer_sizes(None, Some(4 * 1024 * 1024));
+    /// ```
+    pub fn with_socket_buffer_sizes(mut self, send: Option<u32>, recv: Option<u32>) -> Self {
+        self.send_buffer_size = send;
+        self.recv_buffer_size = recv;
+        self
+    }
+
+    /// Tunnel the connection through a SOCKS5 or HTTP `CONNECT` proxy.
+    ///
+    /// The proxy is connected to, and asked to open a tunnel to each
+    /// address in turn; the listener's host name is resolved by the proxy.
+    /// Like `HTTPS_PROXY` and `HTTPS_PROXY_PORT` in a connect descriptor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::{ConnectParams, Proxy};
+    ///
+    /// let params = ConnectParams::new("db.internal", 1521, "FREEPDB1")
+    ///     .with_proxy(Proxy::socks5("localhost", 1080));
+    /// ```
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the session settings (NLS formats, time zone, edition, schema)
+    /// applied right after authentication.
+    ///
+    /// They are sent with the authentication request, so no round trip is
+    /// spent on them unless a container is switched to; see
+    /// `SessionSettings`.
+    pub fn with_session_settings(mut self, settings: SessionSettings) -> Self {
+        self.session_settings = settings;
+        self
+    }
+
+    /// Use an edition for editioned objects (edition-based redefinition),
+    /// keeping the other session settings.
+    pub fn with_edition(mut self, edition: impl Into<String>) -> Self {
+        self.session_settings.edition = Some(edition.into());
+        self
+    }
+
+    /// Switch to a pluggable database after logon, keeping the other
+    /// session settings (see `SessionSettings::with_container`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("cdb.example.com", 1521, "ORCLCDB")
+    ///     .with_container("SALES_PDB")
+    ///     .with_edition("RELEASE_2");
+    /// ```
+    pub fn with_container(mut self, container: impl Into<String>) -> Self {
+        self.session_settings.container = Some(container.into());
+        self
+    }
+
+    /// Send TCP keepalive probes once the connection has been idle for
+    /// `expire_time`, like `SQLNET.EXPIRE_TIME` or `EXPIRE_TIME` in a
+    /// connect descriptor.
+    ///
+    /// Probes keep firewalls and load balancers from silently dropping idle
+    /// connections, and detect peers that went away. As in python-oracledb,
+    /// probes are then sent every 6 seconds and the connection is dropped
+    /// after 10 unanswered ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    /// use std::time::Duration;
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "ORCL")
+    ///     .with_expire_time(Duration::from_secs(5 * 60));
+    /// ```
+    pub fn with_expire_time(mut self, expire_time: Duration) -> Self {
+        self.expire_time = Some(expire_time);
+        self
+    }
+
+    /// Interrupt calls with out-of-band breaks (TCP urgent data) instead of
+    /// in-band BREAK markers.
+    ///
+    /// Some servers and firewalls only act on a BREAK sent out of band, so
+    /// in-band breaks (the default, as in python-oracledb's asyncio mode)
+    /// are not seen until the call completes. Out-of-band breaks are
+    /// offered to the server when connecting and used if it accepts them;
+    /// they are never used over TCPS, and a break whose urgent byte cannot
+    /// be sent falls back to an in-band BREAK marker.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "ORCL").with_oob_break(true);
+    /// ```
+    pub fn with_oob_break(mut self, oob_break: bool) -> Self {
+        self.oob_break = oob_break;
+        self
+    }
+
+    /// Add an address to try when the previous ones cannot be reached.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("rac-node1", 1521, "ORCL")
+    ///     .with_failover_address("rac-node2", 1521)
+    ///     .with_load_balance(true);
+    /// assert_eq!(params.addresses().len(), 2);
+    /// ```
+    pub fn with_failover_address(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.failover_addresses.push(Address::new(host, port));
+        self
+    }
+
+    /// Try the addresses in random order, spreading connections over them.
+    pub fn with_load_balance(mut self, load_balance: bool) -> Self {
+        self.load_balance = load_balance;
+        self
+    }
+
+    /// Set whether the next address is tried when one cannot be reached.
+    ///
+    /// Without failover only one address is tried: the first, or a random
+    /// one with load balancing.
+    pub fn with_failover(mut self, failover: bool) -> Self {
+        self.failover = failover;
+        self
+    }
+
+    /// Route the connection through the addresses in order, as with
+    /// `SOURCE_ROUTE=yes`.
+    ///
+    /// The first address is a Connection Manager (CMAN), which is sent the
+    /// whole route and forwards the connection to the next address, until
+    /// the last reaches the database listener.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("cman.example.com", 1630, "sales")
+    ///     .with_failover_address("db.example.com", 1521)
+    ///     .with_source_route(true);
+    /// assert_eq!(params.addresses().len(), 1);
+    /// ```
+    pub fn with_source_route(mut self, source_route: bool) -> Self {
+        self.source_route = source_route;
+        self
+    }
+
+    /// Get the addresses to try, in the order they are tried.
+    ///
+    /// With load balancing the order is shuffled on every call. With
+    /// source routing only the first hop is connected to.
+    pub fn addresses(&self) -> Vec<Address> {
+        let mut addresses = vec![Address::new(self.host.clone(), self.port)];
+        if self.source_route {
+            return addresses;
+        }
+        addresses.extend(self.failover_addresses.iter().cloned());
+        if self.load_balance {
+            addresses.shuffle(&mut rand::thread_rng());
+        }
+        if !self.failover {
+            addresses.truncate(1);
+        }
+        addresses
+    }
+
+    /// Get a copy of the parameters that connects to `address` only.
+    ///
+    /// A source route is kept, as it is sent to the first hop.
+    pub fn for_address(&self, address: &Address) -> Self {
+        let failover_addresses = if self.source_route {
+            self.failover_addresses.clone()
+        } else {
+            Vec::new()
+        };
+        Self {
+            host: address.host.clone(),
+            port: address.port,
+            failover_addresses,
+            load_balance: false,
+            ..self.clone()
+        }
+    }
+
+    /// Get a copy of the parameters that connects to where a listener or
+    /// Connection Manager redirected the connection.
+    pub fn for_redirect(&self, redirect: &Redirect) -> Self {
+        Self {
+            host: redirect.address.host.clone(),
+            port: redirect.address.port,
+            protocol: redirect.protocol,
+            failover_addresses: Vec::new(),
+            load_balance: false,
+            source_route: false,
+            ..self.clone()
+        }
+    }
+
     /// Parse a connection string like "host:port/service_name".
+    ///
+    /// Several comma-separated addresses, as in
+    /// "host1:1521,host2:1522/service_name", are tried in order.
     pub fn parse(conn_str: &str) -> Result<Self> {
         // Format: host:port/service_name or host/service_name (default port 1521)
         let (addr_part, service_name) =
@@ -70,19 +459,189 @@ impl ConnectParams {
                     message: "Expected format: host:port/service_name".to_string(),
                 })?;
 
-        let (host, port) = if let Some((h, p)) = addr_part.split_once(':') {
-            let port = p.parse::<u16>().map_err(|_| Error::InvalidConnectString {
-                message: format!("Invalid port: {}", p),
-            })?;
-            (h.to_string(), port)
-        } else {
-            (addr_part.to_string(), 1521)
+        let mut addresses = addr_part.split(',').map(|addr| {
+            let addr = addr.trim();
+            if let Some((h, p)) = addr.split_once(':') {
+                let port = p.parse::<u16>().map_err(|_| Error::InvalidConnectString {
+                    message: format!("Invalid port: {}", p),
+                })?;
+                Ok(Address::new(h, port))
+            } else {
+                Ok(Address::new(addr, 1521))
+            }
+        });
+
+        let first = addresses
+            .next()
+            .unwrap_or_else(|| Ok(Address::new("", 1521)))?;
+        let mut params = Self::new(first.host, first.port, service_name);
+        params.failover_addresses = addresses.collect::<Result<_>>()?;
+        Ok(params)
+    }
+
+    /// Create connection parameters from a connect descriptor.
+    ///
+    /// Every `ADDRESS` (`HOST`, `PORT` with default 1521, and `PROTOCOL`,
+    /// `tcp` or `tcps`), `LOAD_BALANCE`, `FAILOVER`, `SOURCE_ROUTE`, the
+    /// `SERVICE_NAME`, `EXPIRE_TIME` (in minutes) and an HTTP proxy
+    /// (`HTTPS_PROXY`, with `HTTPS_PROXY_PORT`) are used; other settings
+    /// are ignored. `COMPRESSION=on` is an error, as network compression
+    /// is not supported. All
+    /// addresses must use the same protocol. A TCPS descriptor still needs a
+    /// `TlsConfig` (see `with_tls`) before connecting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    /// use oracle_thin_rs::protocol::connect::Protocol;
+    ///
+    /// let params = ConnectParams::from_descriptor(
+    ///     "(DESCRIPTION=(ADDRESS=(PROTOCOL=tcps)(HOST=db.example.com)(PORT=1522))\
+    ///      (CONNECT_DATA=(SERVICE_NAME=orcl)))",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(params.protocol, Protocol::Tcps);
+    /// assert_eq!((params.host.as_str(), params.port), ("db.example.com", 1522));
+    /// ```
+    pub fn from_descriptor(descriptor: &str) -> Result<Self> {
+        let nodes = parse_descriptor(descriptor)?;
+        let invalid = |message: String| Error::InvalidConnectString { message };
+
+        let mut addresses = Vec::new();
+        let mut protocol = None;
+        for node in find_nodes(&nodes, "ADDRESS") {
+            let (address, address_protocol) = parse_address(node.children())?;
+            if protocol.is_some_and(|p| p != address_protocol) {
+                return Err(invalid(
+                    "Connect descriptor mixes address protocols".to_string(),
+                ));
+            }
+            protocol = Some(address_protocol);
+            addresses.push(address);
+        }
+        if addresses.is_empty() {
+            return Err(invalid("Connect descriptor has no HOST".to_string()));
+        }
+        let service_name = find_value(&nodes, "SERVICE_NAME")
+            .ok_or_else(|| invalid("Connect descriptor has no SERVICE_NAME".to_string()))?;
+        let load_balance = parse_switch(&nodes, "LOAD_BALANCE")?.unwrap_or(false);
+        let failover = parse_switch(&nodes, "FAILOVER")?.unwrap_or(true);
+        let source_route = parse_switch(&nodes, "SOURCE_ROUTE")?.unwrap_or(false);
+        if parse_switch(&nodes, "COMPRESSION")? == Some(true) {
+            return Err(invalid(
+                "Network compression (COMPRESSION=on) is not supported".to_string(),
+            ));
+        }
+
+        let expire_time = match find_value(&nodes, "EXPIRE_TIME") {
+            Some(minutes) => {
+                let minutes = minutes
+                    .parse::<u64>()
+                    .map_err(|_| invalid(format!("Invalid EXPIRE_TIME: {}", minutes)))?;
+                (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+            }
+            None => None,
+        };
+
+        let size = |key: &str| {
+            find_value(&nodes, key)
+                .map(|value| {
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| invalid(format!("Invalid {}: {}", key, value)))
+                })
+                .transpose()
+        };
+        let proxy = match find_value(&nodes, "HTTPS_PROXY") {
+            Some(host) => {
+                let port = match find_value(&nodes, "HTTPS_PROXY_PORT") {
+                    Some(p) => p
+                        .parse::<u16>()
+                        .map_err(|_| invalid(format!("Invalid HTTPS_PROXY_PORT: {}", p)))?,
+                    None => 80,
+                };
+                Some(Proxy::http(host, port))
+            }
+            None => None,
         };
+        let sdu = size("SDU")?;
+        let send_buffer_size = size("SEND_BUF_SIZE")?;
+        let recv_buffer_size = size("RECV_BUF_SIZE")?;
+
+        let first = addresses.remove(0);
+        let mut params = Self::new(first.host, first.port, service_name);
+        params.failover_addresses = addresses;
+        params.load_balance = load_balance;
+        params.failover = failover;
+        params.source_route = source_route;
+        params.protocol = protocol.unwrap_or_default();
+        params.expire_time = expire_time;
+        if let Some(sdu) = sdu {
+            params = params.with_sdu(sdu);
+        }
+        params.send_buffer_size = send_buffer_size;
+        params.recv_buffer_size = recv_buffer_size;
+        params.proxy = proxy;
+        Ok(params)
+    }
+
+    /// Authenticate with a database access token instead of a password.
+    ///
+    /// `private_key` signs the request for OCI IAM proof-of-possession
+    /// tokens; pass `None` for OAuth2 tokens. The username and password
+    /// given when connecting are ignored. Tokens are only accepted over
+    /// TCPS.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ConnectParams;
+    ///
+    /// let params = ConnectParams::new("adb.example.com", 1522, "mydb_high")
+    ///     .with_access_token("eyJhbGciOi...", None);
+    /// ```
+    pub fn with_access_token(
+        mut self,
+        token: impl Into<String>,
+        private_key: Option<String>,
+    ) -> Self {
+        self.auth = AuthMethod::Token {
+            token: token.into(),
+            private_key,
+        };
+        self
+    }
+
+    /// Open the session with an administrative privilege, such as SYSDBA.
+    ///
+    /// Password authentication only; the privilege is not sent with access
+    /// tokens.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::{ConnectParams, Privilege};
+    ///
+    /// let params = ConnectParams::new("localhost", 1521, "FREEPDB1")
+    ///     .with_privilege(Privilege::SysDba);
+    /// ```
+    pub fn with_privilege(mut self, privilege: Privilege) -> Self {
+        self.privilege = Some(privilege);
+        self
+    }
 
-        Ok(Self::new(host, port, service_name))
+    /// Connect over TLS (TCPS) with the given certificates and keys.
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.protocol = Protocol::Tcps;
+        self.tls = Some(tls);
+        self
     }
 
     /// Build the connect descriptor string.
+    ///
+    /// With source routing the descriptor lists the whole route.
     pub fn build_connect_string(&self) -> String {
         // Get OS username
         let username = whoami::username();
@@ -97,41 +656,315 @@ impl ConnectParams {
         rand::thread_rng().fill_bytes(&mut connection_id_bytes);
         let connection_id = base64::engine::general_purpose::STANDARD.encode(connection_id_bytes);
 
+        let address = |host: &str, port: u16| {
+            format!(
+                "(ADDRESS=(PROTOCOL={})(HOST={})(PORT={}))",
+                self.protocol, host, port
+            )
+        };
+        let mut addresses = address(&self.host, self.port);
+        if self.source_route {
+            addresses.insert_str(0, "(SOURCE_ROUTE=yes)");
+            for hop in &self.failover_addresses {
+                addresses.push_str(&address(&hop.host, hop.port));
+            }
+        }
+
         format!(
-            "(DESCRIPTION=(ADDRESS=(PROTOCOL=tcp)(HOST={})(PORT={}))(CONNECT_DATA=(SERVICE_NAME={})(CID=(PROGRAM=oracle-thin-rs)(HOST={})(USER={}))(CONNECTION_ID={})))",
-            self.host, self.port, self.service_name, local_hostname, username, connection_id
+            "(DESCRIPTION={}(CONNECT_DATA=(SERVICE_NAME={})(CID=(PROGRAM=oracle-thin-rs)(HOST={})(USER={}))(CONNECTION_ID={})))",
+            addresses,
+            self.service_name,
+            local_hostname,
+            username,
+            connection_id
         )
     }
 }
 
+/// Where a listener or Connection Manager sent a connection with a
+/// REDIRECT packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Address to connect to.
+    pub address: Address,
+    /// Protocol of the address.
+    pub protocol: Protocol,
+    /// Connect data to send there, in place of the connect descriptor.
+    pub connect_data: String,
+}
+
+impl Redirect {
+    /// Parse REDIRECT data: the `ADDRESS` to connect to, a NUL, and the
+    /// connect data to send.
+    pub fn parse(data: &str) -> Result<Self> {
+        let invalid = || Error::protocol(format!("Invalid REDIRECT data: {}", data));
+        let (address, connect_data) = data.split_once('\0').ok_or_else(invalid)?;
+        let nodes = parse_descriptor(address)?;
+        let node = find_nodes(&nodes, "ADDRESS")
+            .into_iter()
+            .next()
+            .ok_or_else(invalid)?;
+        let (address, protocol) = parse_address(node.children())?;
+        Ok(Self {
+            address,
+            protocol,
+            connect_data: connect_data.trim_end_matches('\0').to_string(),
+        })
+    }
+}
+
+/// Most redirects followed while connecting.
+pub const MAX_REDIRECTS: usize = 8;
+
+/// How a listener answered a CONNECT packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Handshake {
+    /// The connection was accepted.
+    Accepted(AcceptInfo),
+    /// The connection must be made again elsewhere.
+    Redirected(Redirect),
+}
+
+/// Parse the pairs of an `ADDRESS` into the address and its protocol.
+fn parse_address(nodes: &[DescriptorNode]) -> Result<(Address, Protocol)> {
+    let invalid = |message: String| Error::InvalidConnectString { message };
+
+    let host = find_value(nodes, "HOST")
+        .ok_or_else(|| invalid("Connect descriptor has no HOST".to_string()))?;
+    let port = match find_value(nodes, "PORT") {
+        Some(p) => p
+            .parse::<u16>()
+            .map_err(|_| invalid(format!("Invalid port: {}", p)))?,
+        None => 1521,
+    };
+    let protocol = match find_value(nodes, "PROTOCOL") {
+        None => Protocol::Tcp,
+        Some(p) if p.eq_ignore_ascii_case("tcp") => Protocol::Tcp,
+        Some(p) if p.eq_ignore_ascii_case("tcps") => Protocol::Tcps,
+        Some(p) => return Err(invalid(format!("Unsupported protocol: {}", p))),
+    };
+    Ok((Address::new(host, port), protocol))
+}
+
+/// Parse an on/off descriptor setting (`on`, `yes`, `true` or `off`, `no`,
+/// `false`).
+fn parse_switch(nodes: &[DescriptorNode], key: &str) -> Result<Option<bool>> {
+    match find_value(nodes, key) {
+        None => Ok(None),
+        Some(v)
+            if ["on", "yes", "true"]
+                .iter()
+                .any(|s| v.eq_ignore_ascii_case(s)) =>
+        {
+            Ok(Some(true))
+        }
+        Some(v)
+            if ["off", "no", "false"]
+                .iter()
+                .any(|s| v.eq_ignore_ascii_case(s)) =>
+        {
+            Ok(Some(false))
+        }
+        Some(v) => Err(Error::InvalidConnectString {
+            message: format!("Invalid {}: {}", key, v),
+        }),
+    }
+}
+
+/// Check if a failure to connect to one address is worth trying the next.
+///
+/// Network failures and listener refusals fail over; authentication and
+/// other session errors would fail the same way on every address.
+pub fn can_fail_over(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Io(_)
+            | Error::ConnectionClosed
+            | Error::ConnectionRefused { .. }
+            | Error::ConnectionTimeout { .. }
+            | Error::DnsResolutionFailed { .. }
+    )
+}
+
+/// Resolve the host and open a connection to the first address that accepts.
+///
+/// Resolution and each connection attempt are bounded by `connect_timeout`
+/// on `clock`. An address that refuses or times out is skipped for the next
+/// one; if none connects, the last failure is returned.
+pub async fn dial<S, R, RF, C, CF>(
+    params: &ConnectParams,
+    clock: &dyn Clock,
+    resolve: R,
+    mut connect: C,
+) -> Result<S>
+where
+    R: FnOnce(String) -> RF,
+    RF: Future<Output = io::Result<Vec<SocketAddr>>>,
+    C: FnMut(SocketAddr) -> CF,
+    CF: Future<Output = io::Result<S>>,
+{
+    let timed_out = || Error::ConnectionTimeout {
+        host: params.host.clone(),
+        port: params.port,
+        timeout: params.connect_timeout,
+    };
+
+    let addr_str = format!("{}:{}", params.host, params.port);
+    let addrs = clock::timeout(clock, params.connect_timeout, resolve(addr_str))
+        .await
+        .ok_or_else(timed_out)?
+        .map_err(|e| dns_error(&params.host, e))?;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match clock::timeout(clock, params.connect_timeout, connect(addr)).await {
+            Some(Ok(stream)) => return Ok(stream),
+            Some(Err(e)) => last_error = Some(Error::Io(e)),
+            None => last_error = Some(timed_out()),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::DnsResolutionFailed {
+        hostname: params.host.clone(),
+        message: "No addresses returned".to_string(),
+    }))
+}
+
+/// Open a TCP connection to `addr` with the socket buffer sizes of `params`.
+pub async fn open_socket(addr: SocketAddr, params: &ConnectParams) -> io::Result<TcpStream> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    if let Some(size) = params.send_buffer_size {
+        socket.set_send_buffer_size(size)?;
+    }
+    if let Some(size) = params.recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    socket.connect(addr).await
+}
+
+/// Enable TCP keepalive probes after `expire_time` of idleness.
+pub fn enable_keepalive(stream: &TcpStream, expire_time: Duration) -> io::Result<()> {
+    let keepalive = socket2::TcpKeepalive::new().with_time(expire_time);
+    // Probe interval and count used by python-oracledb, where supported
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "windows",
+    ))]
+    let keepalive = keepalive
+        .with_interval(Duration::from_secs(6))
+        .with_retries(10);
+    socket2::SockRef::from(stream).set_tcp_keepalive(&keepalive)
+}
+
+/// Wrap a connected socket in the transport for the parameters' protocol.
+///
+/// For TCPS the TLS handshake is bounded by `connect_timeout` on `clock`.
+pub async fn start_transport(
+    params: &ConnectParams,
+    clock: &dyn Clock,
+    stream: TcpStream,
+) -> Result<Transport> {
+    match params.protocol {
+        Protocol::Tcp => Ok(Transport::from(stream)),
+        #[cfg(feature = "tls")]
+        Protocol::Tcps => {
+            let tls = params
+                .tls
+                .as_ref()
+                .ok_or_else(|| Error::InvalidConnectString {
+                    message: "TCPS requires a TLS configuration (see ConnectParams::with_tls)"
+                        .to_string(),
+                })?;
+            clock::timeout(
+                clock,
+                params.connect_timeout,
+                tls.connect(&params.host, stream),
+            )
+            .await
+            .ok_or_else(|| Error::ConnectionTimeout {
+                host: params.host.clone(),
+                port: params.port,
+                timeout: params.connect_timeout,
+            })?
+        }
+        #[cfg(not(feature = "tls"))]
+        Protocol::Tcps => {
+            let _ = (clock, stream);
+            Err(Error::InvalidConnectString {
+                message: "TCPS connections require the `tls` feature".to_string(),
+            })
+        }
+    }
+}
+
+/// Classify a host lookup failure.
+fn dns_error(host: &str, e: io::Error) -> Error {
+    let message = e.to_string();
+    if e.kind() == io::ErrorKind::NotFound
+        || message.contains("could not resolve")
+        || message.contains("Name or service not known")
+        || message.contains("nodename nor servname provided")
+    {
+        Error::DnsResolutionFailed {
+            hostname: host.to_string(),
+            message,
+        }
+    } else {
+        Error::Io(e)
+    }
+}
+
 /// Send a CONNECT packet and handle the response.
+///
+/// After a redirect, `redirect` is where the connection was sent: its
+/// connect data replaces the descriptor built from `params`. A listener
+/// may redirect the connection again, which the caller follows by
+/// connecting to the new address.
 pub async fn connect(
     stream: &mut PacketStream,
     params: &ConnectParams,
+    redirect: Option<&Redirect>,
     caps: &mut Capabilities,
-) -> Result<()> {
+) -> Result<Handshake> {
     // Build connect string
-    let connect_string = params.build_connect_string();
+    let connect_string = match redirect {
+        Some(redirect) => redirect.connect_data.clone(),
+        None => params.build_connect_string(),
+    };
     let connect_bytes = connect_string.as_bytes();
+    let packet_flags = if redirect.is_some() {
+        TNS_PACKET_FLAG_REDIRECT
+    } else {
+        0
+    };
 
     // Build CONNECT message (zero-copy)
+    let oob = params.oob_break && !stream.is_tls();
     let msg = ConnectMessage {
         connect_string: connect_bytes,
         sdu: params.sdu,
+        oob,
     };
 
     // Check if connect data needs to be sent separately
     let send_data_separately = !msg.connect_data_in_packet();
 
-    // Send CONNECT packet using zero-copy path
-    stream.send_message(TNS_PACKET_TYPE_CONNECT, &msg).await?;
-
-    // If connect data is too large, send it in a separate DATA packet
+    // Send CONNECT packet, followed in the same write by the connect data
+    // if it is too large for the packet
+    stream.queue_message_with_flags(TNS_PACKET_TYPE_CONNECT, packet_flags, &msg)?;
     if send_data_separately {
-        stream
-            .send_data(bytes::Bytes::copy_from_slice(connect_bytes), 0)
-            .await?;
+        stream.queue_data(connect_bytes, 0);
     }
+    stream.flush().await?;
 
     // Handle response
     loop {
@@ -139,24 +972,36 @@ pub async fn connect(
 
         match response.packet_type {
             TNS_PACKET_TYPE_ACCEPT => {
-                return handle_accept(response, stream, caps);
+                if response.packet_flags & TNS_PACKET_FLAG_TLS_RENEG != 0 {
+                    return Err(Error::protocol(
+                        "Server requested TLS renegotiation, which is not supported",
+                    ));
+                }
+                let info = handle_accept(response, stream, caps)?;
+                // Keep OOB breaks off unless they were offered
+                if !oob {
+                    caps.supports_oob = false;
+                    caps.supports_oob_check = false;
+                }
+                if caps.supports_oob_check {
+                    check_oob(stream, caps).await?;
+                }
+                return Ok(Handshake::Accepted(info));
             }
             TNS_PACKET_TYPE_REFUSE => {
-                return handle_refuse(response, params);
+                return Err(handle_refuse(response, params));
             }
             TNS_PACKET_TYPE_REDIRECT => {
-                // Handle redirect - for now, return an error
-                // TODO: Implement redirect handling
-                return Err(Error::protocol("Redirect not yet supported"));
+                let data = read_redirect_data(stream, response).await?;
+                return Ok(Handshake::Redirected(Redirect::parse(&data)?));
             }
             TNS_PACKET_TYPE_RESEND => {
-                // Resend the connect packet (rebuild message for simplicity)
-                stream.send_message(TNS_PACKET_TYPE_CONNECT, &msg).await?;
+                // Resend the connect packet
+                stream.queue_message_with_flags(TNS_PACKET_TYPE_CONNECT, packet_flags, &msg)?;
                 if send_data_separately {
-                    stream
-                        .send_data(bytes::Bytes::copy_from_slice(connect_bytes), 0)
-                        .await?;
+                    stream.queue_data(connect_bytes, 0);
                 }
+                stream.flush().await?;
             }
             _ => {
                 return Err(Error::UnexpectedPacketType {
@@ -168,59 +1013,47 @@ pub async fn connect(
     }
 }
 
-/// Handle ACCEPT packet.
-fn handle_accept(packet: Packet, stream: &mut PacketStream, caps: &mut Capabilities) -> Result<()> {
-    let mut buf = ReadBuffer::new(packet.payload);
-
-    // Read protocol version
-    let protocol_version = buf.read_u16_be()?;
-    if protocol_version < TNS_VERSION_MIN_ACCEPTED {
+/// Handle ACCEPT packet, setting up the stream and capabilities for the
+/// session the server accepted.
+fn handle_accept(
+    packet: Packet,
+    stream: &mut PacketStream,
+    caps: &mut Capabilities,
+) -> Result<AcceptInfo> {
+    let info = AcceptInfo::parse(&packet.payload)?;
+    if info.protocol_version < TNS_VERSION_MIN_ACCEPTED {
         return Err(Error::ServerVersionNotSupported {
             min_version: TNS_VERSION_MIN_ACCEPTED,
         });
     }
-
-    // Read protocol options
-    let _protocol_options = buf.read_u16_be()?;
-
-    // Skip some fields
-    buf.skip(10)?;
-
-    // Read NSI flags
-    let nsi_flags1 = buf.read_u8()?;
-    if (nsi_flags1 & TNS_NSI_NA_REQUIRED) != 0 {
+    if info.requires_native_encryption() {
         return Err(Error::protocol("Native Network Encryption not supported"));
     }
 
-    // Skip more fields
-    buf.skip(9)?;
-
-    // Read SDU
-    let sdu = buf.read_u32_be()?;
-    caps.sdu = sdu;
-    stream.set_sdu(sdu);
-
-    // Read flags2 if protocol version supports it
-    let mut flags2: u32 = 0;
-    if protocol_version >= TNS_VERSION_MIN_OOB_CHECK {
-        buf.skip(5)?;
-        flags2 = buf.read_u32_be()?;
-    }
-
-    // Adjust capabilities
-    caps.adjust_for_protocol(protocol_version, 0, flags2);
+    caps.sdu = info.sdu;
+    stream.set_sdu(info.sdu);
+    caps.adjust_for_protocol(info.protocol_version, info.protocol_options, info.flags);
 
-    // Enable large SDU (4-byte length headers) if protocol version supports it
     // Python uses 4-byte headers for protocol_version >= 315, regardless of SDU size
-    let use_large_sdu = protocol_version >= TNS_VERSION_MIN_LARGE_SDU;
-    stream.set_large_sdu(use_large_sdu);
+    stream.set_large_sdu(info.uses_large_sdu());
 
-    Ok(())
+    Ok(info)
 }
 
-/// Send a RESET marker after ACCEPT (mimics Python's OOB negotiation).
-/// Python sends an OOB break (!) + RESET marker, but we can only send the marker
-/// since tokio doesn't support MSG_OOB.
+/// Check out-of-band breaks after ACCEPT, as the server asked: send an OOB
+/// break followed by a RESET marker.
+///
+/// If the urgent byte cannot be sent, later breaks are sent in band; the
+/// server also turns them off with a RESET_OOB control packet if the check
+/// fails on its side.
+async fn check_oob(stream: &mut PacketStream, caps: &mut Capabilities) -> Result<()> {
+    if stream.send_oob_break().is_err() {
+        caps.supports_oob = false;
+    }
+    send_reset_marker(stream).await
+}
+
+/// Send a RESET marker.
 pub async fn send_reset_marker(stream: &mut PacketStream) -> Result<()> {
     // RESET marker packet: type=12 (MARKER), payload=[01, 00, 02]
     let msg = MarkerMessage::reset();
@@ -228,25 +1061,46 @@ pub async fn send_reset_marker(stream: &mut PacketStream) -> Result<()> {
     Ok(())
 }
 
-/// Handle REFUSE packet.
-fn handle_refuse(packet: Packet, params: &ConnectParams) -> Result<()> {
+/// Read the data of a REDIRECT packet: its length, then the data, which
+/// follows in DATA packets when it does not fit in the REDIRECT packet.
+async fn read_redirect_data(stream: &mut PacketStream, packet: Packet) -> Result<String> {
+    let mut buf = ReadBuffer::new(packet.payload);
+    let len = usize::from(buf.read_u16_be()?);
+    let mut data = buf.read_bytes(buf.remaining())?.to_vec();
+    while data.len() < len {
+        let packet = stream.read_packet().await?;
+        if packet.packet_type != TNS_PACKET_TYPE_DATA {
+            return Err(Error::UnexpectedPacketType {
+                expected: TNS_PACKET_TYPE_DATA,
+                actual: packet.packet_type,
+            });
+        }
+        // Skip the data flags
+        data.extend_from_slice(packet.payload.get(2..).unwrap_or_default());
+    }
+    data.truncate(len);
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+/// Get the error a REFUSE packet stands for.
+fn handle_refuse(packet: Packet, params: &ConnectParams) -> Error {
     // Try to extract error message from refuse data
     let payload = packet.payload;
     let message = String::from_utf8_lossy(&payload).to_string();
 
     // Check for specific error codes
     if message.contains("ERR=12514") {
-        return Err(Error::InvalidServiceName {
+        return Error::InvalidServiceName {
             service_name: params.service_name.clone(),
-        });
+        };
     }
     if message.contains("ERR=12505") {
-        return Err(Error::InvalidSid {
+        return Error::InvalidSid {
             sid: params.service_name.clone(),
-        });
+        };
     }
 
-    Err(Error::ConnectionRefused { message })
+    Error::ConnectionRefused { message }
 }
 
 /// Perform FastAuth protocol/data types/auth exchange for Oracle 23ai.
@@ -283,6 +1137,7 @@ pub async fn fast_auth(
             machine: &hostname,
             pid: &pid,
             sid: &osuser,
+            privilege: creds.privilege_mode(),
         },
     };
 
@@ -452,10 +1307,7 @@ pub async fn fast_auth(
                 if actual_error_num != 0 {
                     // There's a real error - read the message
                     let message = rbuf.read_str_with_length()?.unwrap_or_default();
-                    return Err(Error::Oracle {
-                        code: actual_error_num,
-                        message,
-                    });
+                    return Err(Error::oracle(actual_error_num, message));
                 }
                 // No error - continue processing
             }
@@ -478,14 +1330,10 @@ async fn read_data_packet(stream: &mut PacketStream, caps: &mut Capabilities) ->
         match response.packet_type {
             TNS_PACKET_TYPE_DATA => return Ok(response),
             TNS_PACKET_TYPE_CONTROL => {
-                // Handle control packet
-                if response.payload.len() >= 2 {
-                    let control_type =
-                        u16::from_be_bytes([response.payload[0], response.payload[1]]);
-                    if control_type == 9 {
-                        // TNS_CONTROL_TYPE_RESET_OOB
-                        caps.supports_oob = false;
-                    }
+                // Notifications before the session is established have no
+                // session to report to
+                if Control::parse(&response.payload) == Control::ResetOob {
+                    caps.supports_oob = false;
                 }
                 // Continue reading for the actual DATA packet
                 continue;
@@ -589,3 +1437,429 @@ pub async fn exchange_data_types(stream: &mut PacketStream, caps: &mut Capabilit
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::clock::TokioClock;
+    use std::cell::RefCell;
+    use tokio::time::{sleep, Instant};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn params() -> ConnectParams {
+        ConnectParams::new("db.example.com", 1521, "ORCL").with_connect_timeout(TIMEOUT)
+    }
+
+    fn addr(last: u8) -> SocketAddr {
+        SocketAddr::from(([192, 0, 2, last], 1521))
+    }
+
+    async fn resolve_two(_host: String) -> io::Result<Vec<SocketAddr>> {
+        Ok(vec![addr(1), addr(2)])
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_dns_timeout() {
+        let start = Instant::now();
+        let result: Result<()> = dial(
+            &params(),
+            &TokioClock,
+            |_| std::future::pending(),
+            |_| async { Ok(()) },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::ConnectionTimeout { port: 1521, .. })
+        ));
+        assert_eq!(start.elapsed(), TIMEOUT);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_dns_failure() {
+        let result: Result<()> = dial(
+            &params(),
+            &TokioClock,
+            |_| async { Err(io::Error::new(io::ErrorKind::NotFound, "no such host")) },
+            |_| async { Ok(()) },
+        )
+        .await;
+        assert!(matches!(result, Err(Error::DnsResolutionFailed { .. })));
+
+        let result: Result<()> = dial(
+            &params(),
+            &TokioClock,
+            |_| async { Ok(vec![]) },
+            |_| async { Ok(()) },
+        )
+        .await;
+        assert!(matches!(result, Err(Error::DnsResolutionFailed { .. })));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_tcp_timeout() {
+        let start = Instant::now();
+        let result: Result<()> = dial(&params(), &TokioClock, resolve_two, |_| {
+            std::future::pending()
+        })
+        .await;
+        // Each address gets the full timeout
+        assert!(matches!(result, Err(Error::ConnectionTimeout { .. })));
+        assert_eq!(start.elapsed(), TIMEOUT * 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_failover_after_timeout() {
+        let start = Instant::now();
+        let attempts = RefCell::new(Vec::new());
+        let connected = dial(&params(), &TokioClock, resolve_two, |a| {
+            attempts.borrow_mut().push((a, start.elapsed()));
+            async move {
+                if a == addr(1) {
+                    std::future::pending::<()>().await;
+                }
+                sleep(Duration::from_millis(10)).await;
+                Ok(a)
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(connected, addr(2));
+        assert_eq!(
+            attempts.into_inner(),
+            vec![(addr(1), Duration::ZERO), (addr(2), TIMEOUT)]
+        );
+        assert_eq!(start.elapsed(), TIMEOUT + Duration::from_millis(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_failover_after_refusal() {
+        let start = Instant::now();
+        let connected = dial(&params(), &TokioClock, resolve_two, |a| async move {
+            if a == addr(1) {
+                Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+            } else {
+                Ok(a)
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(connected, addr(2));
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // The last failure is reported when every address fails
+        let result: Result<()> = dial(&params(), &TokioClock, resolve_two, |_| async {
+            Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+        })
+        .await;
+        assert!(
+            matches!(result, Err(Error::Io(e)) if e.kind() == io::ErrorKind::ConnectionRefused)
+        );
+    }
+    #[test]
+    fn test_params_from_descriptor() {
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(ADDRESS=(HOST=db)(PROTOCOL=TCP))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!(params.protocol, Protocol::Tcp);
+        assert_eq!((params.host.as_str(), params.port), ("db", 1521));
+        assert!(params
+            .build_connect_string()
+            .contains("(PROTOCOL=tcp)(HOST=db)(PORT=1521)"));
+        assert_eq!(params.expire_time, None);
+
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(EXPIRE_TIME=2)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!(params.expire_time, Some(Duration::from_secs(120)));
+
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(SDU=65535)(SEND_BUF_SIZE=1048576)(RECV_BUF_SIZE=4194304)\
+             (ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!(params.sdu, 65535);
+        assert_eq!(params.send_buffer_size, Some(1048576));
+        assert_eq!(params.recv_buffer_size, Some(4194304));
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(SDU=100)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!(params.sdu, TNS_SDU_MIN);
+
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(ADDRESS=(PROTOCOL=tcps)(HOST=adb)(PORT=1522)\
+             (HTTPS_PROXY=proxy.example.com)(HTTPS_PROXY_PORT=3128))\
+             (CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!(params.proxy, Some(Proxy::http("proxy.example.com", 3128)));
+
+        // Compression cannot be asked for, but can be turned off
+        assert!(ConnectParams::from_descriptor(
+            "(DESCRIPTION=(COMPRESSION=on)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .is_err());
+        assert!(ConnectParams::from_descriptor(
+            "(DESCRIPTION=(COMPRESSION=off)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .is_ok());
+        assert!(ConnectParams::from_descriptor(
+            "(DESCRIPTION=(SDU=big)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .is_err());
+
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(FAILOVER=off)(LOAD_BALANCE=yes)(ADDRESS_LIST=\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=node1)(PORT=1521))\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=node2)(PORT=1522)))\
+             (CONNECT_DATA=(SERVICE_NAME=orcl)))",
+        )
+        .unwrap();
+        assert_eq!((params.host.as_str(), params.port), ("node1", 1521));
+        assert_eq!(params.failover_addresses, [Address::new("node2", 1522)]);
+        assert!(params.load_balance);
+        assert!(!params.failover);
+
+        for descriptor in [
+            "(DESCRIPTION=(EXPIRE_TIME=soon)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(FAILOVER=maybe)(ADDRESS=(HOST=db))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(ADDRESS=(HOST=a))(ADDRESS=(PROTOCOL=tcps)(HOST=b))\
+             (CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(ADDRESS=(HOST=db)(PORT=x))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(ADDRESS=(HOST=db)(PROTOCOL=ipc))(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(CONNECT_DATA=(SERVICE_NAME=orcl)))",
+            "(DESCRIPTION=(ADDRESS=(HOST=db))(CONNECT_DATA=(SID=orcl)))",
+        ] {
+            assert!(
+                matches!(
+                    ConnectParams::from_descriptor(descriptor),
+                    Err(Error::InvalidConnectString { .. })
+                ),
+                "{}",
+                descriptor
+            );
+        }
+    }
+
+    #[test]
+    fn test_params_parse_address_list() {
+        let params = ConnectParams::parse("node1:1521, node2/ORCL").unwrap();
+        assert_eq!(params.service_name, "ORCL");
+        assert_eq!(
+            params.addresses(),
+            [Address::new("node1", 1521), Address::new("node2", 1521)]
+        );
+        assert!(ConnectParams::parse("node1:1521,node2:x/ORCL").is_err());
+    }
+
+    #[test]
+    fn test_addresses_order() {
+        let params = ConnectParams::new("a", 1, "ORCL")
+            .with_failover_address("b", 2)
+            .with_failover_address("c", 3);
+        let hosts = |addresses: Vec<Address>| -> Vec<String> {
+            addresses.into_iter().map(|a| a.host).collect()
+        };
+        assert_eq!(hosts(params.addresses()), ["a", "b", "c"]);
+
+        // Load balancing shuffles the order but keeps every address
+        let params = params.with_load_balance(true);
+        let mut shuffled = hosts(params.addresses());
+        shuffled.sort();
+        assert_eq!(shuffled, ["a", "b", "c"]);
+
+        // Without failover a single address is tried
+        let params = params.with_failover(false);
+        assert_eq!(params.addresses().len(), 1);
+
+        let single = params.for_address(&Address::new("b", 2));
+        assert_eq!((single.host.as_str(), single.port), ("b", 2));
+        assert_eq!(single.addresses(), [Address::new("b", 2)]);
+    }
+
+    #[test]
+    fn test_params_source_route() {
+        let params = ConnectParams::from_descriptor(
+            "(DESCRIPTION=(SOURCE_ROUTE=yes)\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=cman)(PORT=1630))\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=db)(PORT=1521))\
+             (CONNECT_DATA=(SERVICE_NAME=sales)))",
+        )
+        .unwrap();
+        assert!(params.source_route);
+        // Only the Connection Manager is connected to, and is sent the route
+        assert_eq!(params.addresses(), [Address::new("cman", 1630)]);
+        let attempt = params.for_address(&params.addresses()[0]);
+        assert!(attempt.build_connect_string().starts_with(
+            "(DESCRIPTION=(SOURCE_ROUTE=yes)\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=cman)(PORT=1630))\
+             (ADDRESS=(PROTOCOL=tcp)(HOST=db)(PORT=1521))\
+             (CONNECT_DATA=(SERVICE_NAME=sales)"
+        ));
+
+        let params = params.with_source_route(false);
+        assert_eq!(params.addresses().len(), 2);
+        assert!(!params
+            .for_address(&Address::new("db", 1521))
+            .build_connect_string()
+            .contains("SOURCE_ROUTE"));
+    }
+
+    #[test]
+    fn test_parse_redirect() {
+        let redirect = Redirect::parse(
+            "(ADDRESS=(PROTOCOL=tcp)(HOST=10.0.0.5)(PORT=1522))\0(DESCRIPTION=(CONNECT_DATA=(SERVICE_NAME=sales)))\0",
+        )
+        .unwrap();
+        assert_eq!(redirect.address, Address::new("10.0.0.5", 1522));
+        assert_eq!(redirect.protocol, Protocol::Tcp);
+        assert_eq!(
+            redirect.connect_data,
+            "(DESCRIPTION=(CONNECT_DATA=(SERVICE_NAME=sales)))"
+        );
+
+        let params = params()
+            .with_failover_address("db2", 1521)
+            .for_redirect(&redirect);
+        assert_eq!(params.addresses(), [Address::new("10.0.0.5", 1522)]);
+
+        assert!(Redirect::parse("(ADDRESS=(HOST=db))").is_err());
+        assert!(Redirect::parse("(CONNECT_DATA=(SID=x))\0data").is_err());
+    }
+
+    /// Encode a packet with a small-SDU header.
+    fn encode_packet(packet_type: u8, payload: &[u8]) -> Vec<u8> {
+        let mut packet = ((payload.len() + 8) as u16).to_be_bytes().to_vec();
+        packet.extend_from_slice(&[0, 0, packet_type, 0, 0, 0]);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    /// Read a small-SDU packet, returning its type, flags and payload.
+    async fn read_raw_packet(tcp: &mut TcpStream) -> (u8, u8, Vec<u8>) {
+        use tokio::io::AsyncReadExt;
+
+        let mut header = [0u8; 8];
+        tcp.read_exact(&mut header).await.unwrap();
+        let len = u16::from_be_bytes([header[0], header[1]]) as usize;
+        let mut payload = vec![0u8; len - 8];
+        tcp.read_exact(&mut payload).await.unwrap();
+        (header[4], header[5], payload)
+    }
+
+    /// Open a packet stream to a fake listener running `serve`.
+    async fn stream_to_fake_listener<F, Fut>(
+        serve: F,
+    ) -> (PacketStream, tokio::task::JoinHandle<()>)
+    where
+        F: FnOnce(TcpStream) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap());
+        let (client, (tcp, _)) = tokio::join!(client, async { listener.accept().await.unwrap() });
+        let server = tokio::spawn(serve(tcp));
+        (PacketStream::new(client.unwrap()), server)
+    }
+
+    #[tokio::test]
+    async fn test_connect_redirected() {
+        use tokio::io::AsyncWriteExt;
+
+        let data = b"(ADDRESS=(PROTOCOL=tcp)(HOST=db2)(PORT=1522))\0(DESCRIPTION=(CONNECT_DATA=(SERVICE_NAME=sales)))";
+
+        // The redirect data follows the REDIRECT packet in a DATA packet
+        let (mut stream, server) = stream_to_fake_listener(|mut tcp| async move {
+            let (packet_type, flags, _) = read_raw_packet(&mut tcp).await;
+            assert_eq!((packet_type, flags), (TNS_PACKET_TYPE_CONNECT, 0));
+            let mut redirect = (data.len() as u16).to_be_bytes().to_vec();
+            redirect.extend_from_slice(&data[..10]);
+            let mut rest = vec![0, 0];
+            rest.extend_from_slice(&data[10..]);
+            let mut packets = encode_packet(TNS_PACKET_TYPE_REDIRECT, &redirect);
+            packets.extend(encode_packet(TNS_PACKET_TYPE_DATA, &rest));
+            tcp.write_all(&packets).await.unwrap();
+        })
+        .await;
+        let mut caps = Capabilities::new();
+        let handshake = connect(&mut stream, &params(), None, &mut caps)
+            .await
+            .unwrap();
+        server.await.unwrap();
+        let Handshake::Redirected(redirect) = handshake else {
+            panic!("expected a redirect, got {:?}", handshake);
+        };
+        assert_eq!(redirect.address, Address::new("db2", 1522));
+
+        // The connection made again sends the redirect's connect data
+        let connect_data = redirect.connect_data.clone();
+        let (mut stream, server) = stream_to_fake_listener(|mut tcp| async move {
+            let (packet_type, flags, payload) = read_raw_packet(&mut tcp).await;
+            assert_eq!(
+                (packet_type, flags),
+                (TNS_PACKET_TYPE_CONNECT, TNS_PACKET_FLAG_REDIRECT)
+            );
+            assert!(payload.ends_with(connect_data.as_bytes()));
+            let mut accept = vec![0x01, 0x3b, 0x04, 0x01, 0x20, 0x00, 0xff, 0xff];
+            accept.extend_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x28, 0x41, 0x01]);
+            accept.extend_from_slice(&[0; 8]);
+            accept.extend_from_slice(&8192u32.to_be_bytes());
+            accept.extend_from_slice(&0xffffu32.to_be_bytes());
+            tcp.write_all(&encode_packet(TNS_PACKET_TYPE_ACCEPT, &accept))
+                .await
+                .unwrap();
+        })
+        .await;
+        let target = params().for_redirect(&redirect);
+        let handshake = connect(&mut stream, &target, Some(&redirect), &mut caps)
+            .await
+            .unwrap();
+        server.await.unwrap();
+        assert!(
+            matches!(handshake, Handshake::Accepted(ref info) if info.protocol_version == 315),
+            "{:?}",
+            handshake
+        );
+    }
+
+    #[test]
+    fn test_can_fail_over() {
+        assert!(can_fail_over(&Error::Io(io::Error::from(
+            io::ErrorKind::ConnectionRefused
+        ))));
+        assert!(can_fail_over(&Error::ConnectionRefused {
+            message: "ORA-12514".to_string()
+        }));
+        assert!(!can_fail_over(&Error::AuthenticationFailed {
+            message: "ORA-01017".to_string()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_tcps_requires_tls_config() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let socket = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let mut params = params();
+        params.protocol = Protocol::Tcps;
+        let result = start_transport(&params, &TokioClock, socket).await;
+        assert!(matches!(result, Err(Error::InvalidConnectString { .. })));
+    }
+    #[tokio::test]
+    async fn test_open_socket_buffer_sizes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let params = params().with_socket_buffer_sizes(Some(256 * 1024), Some(512 * 1024));
+        let socket = open_socket(listener.local_addr().unwrap(), &params)
+            .await
+            .unwrap();
+        // The OS may round the sizes up (Linux doubles them)
+        let socket = socket2::SockRef::from(&socket);
+        assert!(socket.send_buffer_size().unwrap() >= 256 * 1024);
+        assert!(socket.recv_buffer_size().unwrap() >= 512 * 1024);
+    }
+}
@@ -16,6 +16,10 @@ pub const TNS_PACKET_TYPE_CONTROL: u8 = 14;
 pub const TNS_PACKET_FLAG_REDIRECT: u8 = 0x04;
 pub const TNS_PACKET_FLAG_TLS_RENEG: u8 = 0x08;
 
+// Control packet types
+pub const TNS_CONTROL_TYPE_INBAND_NOTIFICATION: u16 = 8;
+pub const TNS_CONTROL_TYPE_RESET_OOB: u16 = 9;
+
 // Data flags
 pub const TNS_DATA_FLAGS_EOF: u16 = 0x0040;
 pub const TNS_DATA_FLAGS_END_OF_REQUEST: u16 = 0x0800;
@@ -64,9 +68,22 @@ pub const TNS_FUNC_FETCH: u8 = 5;
 pub const TNS_FUNC_LOB_OP: u8 = 96;
 pub const TNS_FUNC_LOGOFF: u8 = 9;
 pub const TNS_FUNC_PING: u8 = 147;
+pub const TNS_FUNC_PIPELINE_BEGIN: u8 = 199;
+pub const TNS_FUNC_PIPELINE_END: u8 = 200;
 pub const TNS_FUNC_ROLLBACK: u8 = 15;
 pub const TNS_FUNC_REEXECUTE: u8 = 4;
 pub const TNS_FUNC_REEXECUTE_AND_FETCH: u8 = 78;
+pub const TNS_FUNC_SET_END_TO_END_ATTR: u8 = 135;
+
+// Pipeline modes
+pub const TNS_PIPELINE_MODE_CONTINUE_ON_ERROR: u8 = 1;
+pub const TNS_PIPELINE_MODE_ABORT_ON_ERROR: u8 = 2;
+
+// End-to-end attribute flags
+pub const TNS_END_TO_END_CLIENT_IDENTIFIER: u32 = 0x0001;
+pub const TNS_END_TO_END_MODULE: u32 = 0x0008;
+pub const TNS_END_TO_END_ACTION: u32 = 0x0010;
+pub const TNS_END_TO_END_CLIENT_INFO: u32 = 0x0100;
 
 // TTC authentication modes
 pub const TNS_AUTH_MODE_LOGON: u32 = 0x00000001;
@@ -146,7 +163,40 @@ pub const TNS_BIND_DIR_OUTPUT: u8 = 16;
 pub const TNS_BIND_DIR_INPUT: u8 = 32;
 pub const TNS_BIND_DIR_INPUT_OUTPUT: u8 = 48;
 
+// LOB operations
+pub const TNS_LOB_OP_GET_LENGTH: u32 = 0x0001;
+pub const TNS_LOB_OP_READ: u32 = 0x0002;
+pub const TNS_LOB_OP_TRIM: u32 = 0x0020;
+pub const TNS_LOB_OP_WRITE: u32 = 0x0040;
+pub const TNS_LOB_OP_FILE_OPEN: u32 = 0x0100;
+pub const TNS_LOB_OP_CREATE_TEMP: u32 = 0x0110;
+pub const TNS_LOB_OP_FREE_TEMP: u32 = 0x0111;
+pub const TNS_LOB_OP_FILE_CLOSE: u32 = 0x0200;
+pub const TNS_LOB_OP_FILE_ISOPEN: u32 = 0x0400;
+pub const TNS_LOB_OP_FILE_EXISTS: u32 = 0x0800;
+pub const TNS_LOB_OP_ARRAY: u32 = 0x80000;
+
+// LOB open modes
+pub const TNS_LOB_OPEN_READ_ONLY: u64 = 11;
+
+// LOB locator flags
+pub const TNS_LOB_LOC_OFFSET_FLAG_3: usize = 6;
+pub const TNS_LOB_LOC_OFFSET_FLAG_4: usize = 7;
+pub const TNS_LOB_LOC_FLAGS_VAR_LENGTH_CHARSET: u8 = 0x80;
+pub const TNS_LOB_LOC_FLAGS_TEMP: u8 = 0x01;
+pub const TNS_LOB_TEMP_LOCATOR_SIZE: usize = 40;
+
+// Character set forms
+pub const CS_FORM_IMPLICIT: u8 = 1;
+pub const CS_FORM_NCHAR: u8 = 2;
+
+// Column UDS flags
+pub const TNS_UDS_FLAGS_IS_JSON: u32 = 0x00000100;
+pub const TNS_UDS_FLAGS_IS_OSON: u32 = 0x00000800;
+
 // Error codes
+pub const TNS_ERR_ARRAY_DML_ERRORS: u32 = 24381;
+pub const TNS_ERR_CALL_CANCELLED: u32 = 1013;
 pub const TNS_ERR_INCONSISTENT_DATA_TYPES: u32 = 932;
 pub const TNS_ERR_VAR_NOT_IN_SELECT_LIST: u32 = 1007;
 pub const TNS_ERR_INBAND_MESSAGE: u32 = 12573;
@@ -154,6 +204,20 @@ pub const TNS_ERR_INVALID_SERVICE_NAME: u32 = 12514;
 pub const TNS_ERR_INVALID_SID: u32 = 12505;
 pub const TNS_ERR_NO_DATA_FOUND: u32 = 1403;
 pub const TNS_ERR_SESSION_SHUTDOWN: u32 = 12572;
+pub const TNS_ERR_COMPILATION_ERROR: u32 = 24344;
+
+// End-of-call status flags
+pub const TNS_EOCS_FLAGS_TXN_IN_PROGRESS: u32 = 0x0000_0002;
+
+// Server-side piggyback opcodes
+pub const TNS_SERVER_PIGGYBACK_SESS_RET: u8 = 4;
+pub const TNS_SERVER_PIGGYBACK_LTXID: u8 = 7;
+pub const TNS_SERVER_PIGGYBACK_AC_REPLAY_CONTEXT: u8 = 8;
+pub const TNS_SERVER_PIGGYBACK_EXT_SYNC: u8 = 9;
+pub const TNS_SERVER_PIGGYBACK_SESS_SIGNATURE: u8 = 10;
+
+// Warning flags of the end-of-call status
+pub const TNS_WARN_COMPILATION_CREATE: u8 = 0x20;
 
 // Compile time capability indices
 pub const TNS_CCAP_SQL_VERSION: usize = 0;
@@ -252,9 +316,17 @@ pub const TNS_ESCAPE_CHAR: u8 = 253;
 pub const TNS_LONG_LENGTH_INDICATOR: u8 = 254;
 pub const TNS_NULL_LENGTH_INDICATOR: u8 = 0;
 pub const TNS_MAX_LONG_LENGTH: u32 = 0x7fffffff;
+pub const TNS_CHUNK_SIZE: usize = 32767;
+pub const TNS_MAX_STRING_SIZE: u32 = 4000;
+pub const TNS_MAX_STRING_SIZE_EXTENDED: u32 = 32767;
+pub const TNS_MAX_UROWID_LENGTH: u32 = 5267;
 pub const TNS_DURATION_SESSION: u8 = 10;
+pub const TNS_DURATION_MID: u32 = 0x80000000;
+pub const TNS_DURATION_OFFSET: u8 = 60;
 pub const PACKET_HEADER_SIZE: usize = 8;
 pub const TNS_SDU_DEFAULT: u32 = 8192;
+pub const TNS_SDU_MIN: u32 = 512;
+pub const TNS_SDU_MAX: u32 = 2097152;
 
 // Oracle data type numbers
 pub const ORA_TYPE_NUM_BFILE: u16 = 114;
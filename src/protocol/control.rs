@@ -0,0 +1,148 @@
+//! TNS control packets.
+//!
+//! Control packets arrive between DATA packets, outside any response. The
+//! server sends them to turn out-of-band breaks off (RESET_OOB) and to tell
+//! the client about events affecting the session (inband notifications),
+//! such as the instance shutting down for planned maintenance.
+
+use crate::protocol::constants::*;
+
+/// An event the server reported to the session with an inband notification.
+///
+/// Notifications are collected by the connection as they arrive and taken
+/// with `Connection::take_notifications`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Notification {
+    /// The server is closing the session (ORA-12572), for example because
+    /// the instance is shutting down. The connection is unusable once the
+    /// current call completes.
+    SessionShutdown,
+    /// The server sent an inband message (ORA-12573), used to announce
+    /// planned outages so clients can move work elsewhere.
+    InbandMessage,
+    /// A notification with another error number.
+    Other(u32),
+}
+
+impl Notification {
+    /// Get the Oracle error number carried by the notification.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            Notification::SessionShutdown => TNS_ERR_SESSION_SHUTDOWN,
+            Notification::InbandMessage => TNS_ERR_INBAND_MESSAGE,
+            Notification::Other(code) => *code,
+        }
+    }
+
+    fn from_error_code(code: u32) -> Self {
+        match code {
+            TNS_ERR_SESSION_SHUTDOWN => Notification::SessionShutdown,
+            TNS_ERR_INBAND_MESSAGE => Notification::InbandMessage,
+            code => Notification::Other(code),
+        }
+    }
+}
+
+impl std::fmt::Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Notification::SessionShutdown => write!(f, "ORA-12572: session shutting down"),
+            Notification::InbandMessage => write!(f, "ORA-12573: inband message"),
+            Notification::Other(code) => write!(f, "ORA-{:05}: inband notification", code),
+        }
+    }
+}
+
+/// A control packet, parsed from its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Control {
+    /// The server cannot receive out-of-band breaks.
+    ResetOob,
+    /// An inband notification.
+    Notification(Notification),
+    /// A control packet of a type the client does not act on, or too short
+    /// to parse.
+    Ignored,
+}
+
+impl Control {
+    /// Parse a control packet payload: a 2-byte type, then for inband
+    /// notifications a 4-byte field the client skips and the 4-byte error
+    /// number, all big-endian.
+    pub(crate) fn parse(payload: &[u8]) -> Self {
+        let Some((control_type, rest)) = payload.split_first_chunk::<2>() else {
+            return Control::Ignored;
+        };
+        match u16::from_be_bytes(*control_type) {
+            TNS_CONTROL_TYPE_RESET_OOB => Control::ResetOob,
+            TNS_CONTROL_TYPE_INBAND_NOTIFICATION => match rest.get(4..8) {
+                Some(&[a, b, c, d]) => {
+                    Control::Notification(Notification::from_error_code(u32::from_be_bytes([
+                        a, b, c, d,
+                    ])))
+                }
+                _ => Control::Ignored,
+            },
+            _ => Control::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inband(code: u32) -> Vec<u8> {
+        let mut payload = TNS_CONTROL_TYPE_INBAND_NOTIFICATION.to_be_bytes().to_vec();
+        payload.extend([0, 0, 0, 0]);
+        payload.extend(code.to_be_bytes());
+        payload
+    }
+
+    #[test]
+    fn test_parse_control() {
+        assert_eq!(Control::parse(&[0x00, 0x09]), Control::ResetOob);
+        assert_eq!(
+            Control::parse(&inband(12572)),
+            Control::Notification(Notification::SessionShutdown)
+        );
+        assert_eq!(
+            Control::parse(&inband(12573)),
+            Control::Notification(Notification::InbandMessage)
+        );
+        assert_eq!(
+            Control::parse(&inband(3113)),
+            Control::Notification(Notification::Other(3113))
+        );
+    }
+
+    #[test]
+    fn test_parse_control_malformed() {
+        assert_eq!(Control::parse(&[]), Control::Ignored);
+        assert_eq!(Control::parse(&[0x00]), Control::Ignored);
+        assert_eq!(
+            Control::parse(&[0x00, 0x08, 0, 0, 0, 0, 0x31]),
+            Control::Ignored
+        );
+        assert_eq!(Control::parse(&[0x00, 0x63]), Control::Ignored);
+    }
+
+    #[test]
+    fn test_notification_error_code() {
+        for notification in [
+            Notification::SessionShutdown,
+            Notification::InbandMessage,
+            Notification::Other(1),
+        ] {
+            assert_eq!(
+                Notification::from_error_code(notification.error_code()),
+                notification
+            );
+        }
+        assert_eq!(
+            Notification::SessionShutdown.to_string(),
+            "ORA-12572: session shutting down"
+        );
+    }
+}
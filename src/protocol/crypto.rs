@@ -7,6 +7,8 @@ use pbkdf2::pbkdf2;
 use sha1::Sha1;
 use sha2::Sha512;
 
+use crate::error::{Error, Result};
+
 type Aes256CbcEnc = cbc::Encryptor<aes::Aes256>;
 type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 type Aes192CbcEnc = cbc::Encryptor<aes::Aes192>;
@@ -54,7 +56,10 @@ pub fn encrypt_cbc(key: &[u8], plaintext: &[u8], use_zero_padding: bool) -> Vec<
 
 /// Decrypt data using AES-CBC with the given key.
 /// The IV is all zeros (as used by Oracle).
-pub fn decrypt_cbc(key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+///
+/// The ciphertext comes from the server, so a length that is not a multiple
+/// of the block size is reported as a protocol error.
+pub fn decrypt_cbc(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
     let iv = [0u8; 16];
 
     match key.len() {
@@ -63,16 +68,16 @@ pub fn decrypt_cbc(key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
             let mut buf = ciphertext.to_vec();
             decryptor
                 .decrypt_padded_mut::<NoPadding>(&mut buf)
-                .expect("decryption failed")
-                .to_vec()
+                .map(|plain| plain.to_vec())
+                .map_err(|_| Error::protocol("Encrypted data is not block aligned"))
         }
         24 => {
             let decryptor = Aes192CbcDec::new(key.into(), &iv.into());
             let mut buf = ciphertext.to_vec();
             decryptor
                 .decrypt_padded_mut::<NoPadding>(&mut buf)
-                .expect("decryption failed")
-                .to_vec()
+                .map(|plain| plain.to_vec())
+                .map_err(|_| Error::protocol("Encrypted data is not block aligned"))
         }
         _ => panic!("Invalid key length: {}", key.len()),
     }
@@ -130,7 +135,7 @@ pub fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
 
     (0..hex.len())
         .step_by(2)
-        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
         .collect()
 }
 
@@ -144,7 +149,7 @@ mod tests {
         let plaintext = b"Hello, Oracle!";
 
         let encrypted = encrypt_cbc(&key, plaintext, false);
-        let decrypted = decrypt_cbc(&key, &encrypted);
+        let decrypted = decrypt_cbc(&key, &encrypted).unwrap();
 
         // Note: decrypted will have padding
         assert!(decrypted.starts_with(plaintext));
@@ -158,5 +163,15 @@ mod tests {
 
         let back = hex_to_bytes(&hex).unwrap();
         assert_eq!(back, bytes);
+
+        assert_eq!(hex_to_bytes("0G"), None);
+        assert_eq!(hex_to_bytes("éé"), None);
+    }
+
+    #[test]
+    fn test_decrypt_unaligned_ciphertext() {
+        let key = [0u8; 32];
+        assert!(decrypt_cbc(&key, &[0u8; 15]).is_err());
+        assert!(decrypt_cbc(&key[..24], &[0u8; 17]).is_err());
     }
 }
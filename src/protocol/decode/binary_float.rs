@@ -0,0 +1,73 @@
+//! Oracle BINARY_FLOAT / BINARY_DOUBLE decoders.
+//!
+//! Both are IEEE 754 big-endian values stored in an order-preserving form:
+//! - positive values have the sign bit set
+//! - negative values have all bits inverted
+
+use crate::error::{Error, Result};
+
+/// Decode an Oracle BINARY_FLOAT from 4 bytes.
+///
+/// # Errors
+/// Returns `Error::Protocol` if data is not exactly 4 bytes.
+pub fn decode_binary_float(data: &[u8]) -> Result<f32> {
+    let mut b: [u8; 4] = data.try_into().map_err(|_| {
+        Error::protocol(format!(
+            "BINARY_FLOAT value must be exactly 4 bytes, got {}",
+            data.len()
+        ))
+    })?;
+    undo_order_preserving(&mut b);
+    Ok(f32::from_be_bytes(b))
+}
+
+/// Decode an Oracle BINARY_DOUBLE from 8 bytes.
+///
+/// # Errors
+/// Returns `Error::Protocol` if data is not exactly 8 bytes.
+pub fn decode_binary_double(data: &[u8]) -> Result<f64> {
+    let mut b: [u8; 8] = data.try_into().map_err(|_| {
+        Error::protocol(format!(
+            "BINARY_DOUBLE value must be exactly 8 bytes, got {}",
+            data.len()
+        ))
+    })?;
+    undo_order_preserving(&mut b);
+    Ok(f64::from_be_bytes(b))
+}
+
+fn undo_order_preserving(b: &mut [u8]) {
+    match b.first_mut() {
+        Some(first) if *first & 0x80 != 0 => *first &= 0x7F,
+        _ => b.iter_mut().for_each(|x| *x = !*x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_binary_double() {
+        // 1.5 encoded order-preserving: sign bit flipped
+        let mut bytes = 1.5f64.to_be_bytes();
+        bytes[0] |= 0x80;
+        assert_eq!(decode_binary_double(&bytes).unwrap(), 1.5);
+
+        // -1.5: all bits inverted
+        let bytes: Vec<u8> = (-1.5f64).to_be_bytes().iter().map(|b| !b).collect();
+        assert_eq!(decode_binary_double(&bytes).unwrap(), -1.5);
+    }
+
+    #[test]
+    fn test_decode_binary_float() {
+        let mut bytes = 0.25f32.to_be_bytes();
+        bytes[0] |= 0x80;
+        assert_eq!(decode_binary_float(&bytes).unwrap(), 0.25);
+
+        let bytes: Vec<u8> = (-3.0f32).to_be_bytes().iter().map(|b| !b).collect();
+        assert_eq!(decode_binary_float(&bytes).unwrap(), -3.0);
+
+        assert!(decode_binary_float(&[0x80, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+//! Oracle BOOLEAN type decoder (23ai).
+//!
+//! BOOLEAN values are sent as a short byte string whose last byte is 1 for
+//! TRUE and 0 for FALSE.
+
+use crate::error::{Error, Result};
+
+/// Decode an Oracle BOOLEAN value.
+///
+/// # Errors
+/// Returns `Error::Protocol` if the data is empty.
+pub fn decode_oracle_boolean(data: &[u8]) -> Result<bool> {
+    data.last()
+        .map(|&b| b == 1)
+        .ok_or_else(|| Error::protocol("BOOLEAN value is empty"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_boolean() {
+        assert!(decode_oracle_boolean(&[0x01]).unwrap());
+        assert!(!decode_oracle_boolean(&[0x00]).unwrap());
+        // Multi-byte form: only the last byte is significant
+        assert!(decode_oracle_boolean(&[0x01, 0x01]).unwrap());
+        assert!(!decode_oracle_boolean(&[0x01, 0x00]).unwrap());
+        assert!(decode_oracle_boolean(&[]).is_err());
+    }
+}
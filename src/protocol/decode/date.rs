@@ -8,9 +8,12 @@
 //! - byte[4]: hour + 1 (0-23)
 //! - byte[5]: minute + 1 (0-59)
 //! - byte[6]: second + 1 (0-59)
+//!
+//! TIMESTAMP uses the same 7 bytes, followed by the nanoseconds as a
+//! big-endian 4-byte integer. The fraction is left out when it is zero.
 
 use crate::error::{Error, Result};
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 
 /// Decode an Oracle DATE from 7 bytes.
 ///
@@ -28,41 +31,38 @@ use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 /// // Returns: 2024-10-21 12:36:05
 /// ```
 pub fn decode_oracle_date(data: &[u8]) -> Result<NaiveDateTime> {
-    if data.len() != 7 {
+    let &[century, year_in_century, month, day, hour, minute, second] = data else {
         return Err(Error::protocol(format!(
             "DATE value must be exactly 7 bytes, got {}",
             data.len()
         )));
-    }
-
-    // Decode century and year
-    let century = (data[0] - 100) as i32;
-    let year_in_century = (data[1] - 100) as i32;
-    let year = century * 100 + year_in_century;
+    };
 
-    // Month and day are direct values
-    let month = data[2];
-    let day = data[3];
+    // Decode century and year (BC years come out negative)
+    let year = (century as i32 - 100) * 100 + (year_in_century as i32 - 100);
 
     // Hour, minute, second are stored as value + 1
-    let hour = data[4] - 1;
-    let minute = data[5] - 1;
-    let second = data[6] - 1;
+    let hour = hour as i32 - 1;
+    let minute = minute as i32 - 1;
+    let second = second as i32 - 1;
 
-    // Validate ranges
+    // Validate ranges (Oracle DATE spans 4712 BC to 9999 AD)
+    if !(-4712..=9999).contains(&year) {
+        return Err(Error::protocol(format!("Invalid year: {}", year)));
+    }
     if !(1..=12).contains(&month) {
         return Err(Error::protocol(format!("Invalid month: {}", month)));
     }
     if !(1..=31).contains(&day) {
         return Err(Error::protocol(format!("Invalid day: {}", day)));
     }
-    if hour > 23 {
+    if !(0..=23).contains(&hour) {
         return Err(Error::protocol(format!("Invalid hour: {}", hour)));
     }
-    if minute > 59 {
+    if !(0..=59).contains(&minute) {
         return Err(Error::protocol(format!("Invalid minute: {}", minute)));
     }
-    if second > 59 {
+    if !(0..=59).contains(&second) {
         return Err(Error::protocol(format!("Invalid second: {}", second)));
     }
 
@@ -83,10 +83,39 @@ pub fn decode_oracle_date(data: &[u8]) -> Result<NaiveDateTime> {
     Ok(NaiveDateTime::new(date, time))
 }
 
+/// Decode an Oracle TIMESTAMP from 7 or 11 bytes.
+///
+/// # Errors
+/// Returns `Error::Protocol` if data is not 7 or 11 bytes, or contains
+/// invalid values.
+pub fn decode_oracle_timestamp(data: &[u8]) -> Result<NaiveDateTime> {
+    let (date, fraction) = match data.split_at_checked(7) {
+        Some((date, fraction)) if fraction.is_empty() || fraction.len() == 4 => (date, fraction),
+        _ => {
+            return Err(Error::protocol(format!(
+                "TIMESTAMP value must be 7 or 11 bytes, got {}",
+                data.len()
+            )))
+        }
+    };
+    let value = decode_oracle_date(date)?;
+    let &[a, b, c, d] = fraction else {
+        return Ok(value);
+    };
+    // Nanoseconds past 999,999,999 are leap seconds to chrono, not Oracle
+    let nanos = u32::from_be_bytes([a, b, c, d]);
+    if nanos >= 1_000_000_000 {
+        return Err(Error::protocol(format!("Invalid nanoseconds: {}", nanos)));
+    }
+    value
+        .with_nanosecond(nanos)
+        .ok_or_else(|| Error::protocol(format!("Invalid nanoseconds: {}", nanos)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Datelike, Timelike};
+    use chrono::Datelike;
 
     #[test]
     fn test_decode_date_2024_10_21() {
@@ -168,4 +197,43 @@ mod tests {
         let data = [0x78, 0x7C, 0x01, 0x20, 0x01, 0x01, 0x01];
         assert!(decode_oracle_date(&data).is_err());
     }
+
+    #[test]
+    fn test_decode_date_out_of_range_bytes() {
+        // Zero time bytes and a century byte below 100 must not underflow
+        let data = [0x78, 0x7C, 0x01, 0x01, 0x00, 0x01, 0x01];
+        assert!(decode_oracle_date(&data).is_err());
+        let data = [0x00, 0x00, 0x01, 0x01, 0x01, 0x01, 0x01];
+        assert!(decode_oracle_date(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_timestamp() {
+        // 2024-10-21 12:36:05.123456789
+        let data = [
+            0x78, 0x7C, 0x0A, 0x15, 0x0D, 0x25, 0x06, 0x07, 0x5B, 0xCD, 0x15,
+        ];
+        let result = decode_oracle_timestamp(&data).unwrap();
+        assert_eq!(result.second(), 5);
+        assert_eq!(result.nanosecond(), 123_456_789);
+
+        // Without a fraction it is a DATE
+        assert_eq!(
+            decode_oracle_timestamp(&data[..7]).unwrap(),
+            decode_oracle_date(&data[..7]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_timestamp_invalid() {
+        let date = [0x78, 0x7C, 0x0A, 0x15, 0x0D, 0x25, 0x06];
+        for len in [0, 3, 8, 10, 12] {
+            let data: Vec<u8> = date.iter().copied().cycle().take(len).collect();
+            assert!(decode_oracle_timestamp(&data).is_err(), "{} bytes", len);
+        }
+        // A fraction of a second or more
+        let mut data = date.to_vec();
+        data.extend(1_000_000_000u32.to_be_bytes());
+        assert!(decode_oracle_timestamp(&data).is_err());
+    }
 }
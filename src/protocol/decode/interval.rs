@@ -0,0 +1,112 @@
+//! Oracle INTERVAL type decoders.
+//!
+//! INTERVAL YEAR TO MONTH is encoded as 5 bytes:
+//! - bytes[0..4]: years + 0x80000000 (big-endian)
+//! - byte[4]: months + 60
+//!
+//! INTERVAL DAY TO SECOND is encoded as 11 bytes:
+//! - bytes[0..4]: days + 0x80000000 (big-endian)
+//! - byte[4]: hours + 60
+//! - byte[5]: minutes + 60
+//! - byte[6]: seconds + 60
+//! - bytes[7..11]: nanoseconds + 0x80000000 (big-endian)
+
+use crate::error::{Error, Result};
+use crate::protocol::constants::{TNS_DURATION_MID, TNS_DURATION_OFFSET};
+use chrono::Duration;
+
+/// Decode an Oracle INTERVAL YEAR TO MONTH value.
+///
+/// Returns `(years, months)`. Both components carry the sign of the interval.
+///
+/// # Errors
+/// Returns `Error::Protocol` if data is not exactly 5 bytes.
+pub fn decode_interval_ym(data: &[u8]) -> Result<(i32, i32)> {
+    let &[y0, y1, y2, y3, months] = data else {
+        return Err(Error::protocol(format!(
+            "INTERVAL YEAR TO MONTH value must be exactly 5 bytes, got {}",
+            data.len()
+        )));
+    };
+
+    let years = read_duration_u32([y0, y1, y2, y3]);
+    let months = months as i32 - TNS_DURATION_OFFSET as i32;
+    Ok((years, months))
+}
+
+/// Decode an Oracle INTERVAL DAY TO SECOND value.
+///
+/// Returns a `chrono::Duration` with microsecond precision, matching
+/// python-oracledb's `timedelta` conversion.
+///
+/// # Errors
+/// Returns `Error::Protocol` if data is not exactly 11 bytes.
+pub fn decode_interval_ds(data: &[u8]) -> Result<Duration> {
+    let &[d0, d1, d2, d3, hours, minutes, seconds, n0, n1, n2, n3] = data else {
+        return Err(Error::protocol(format!(
+            "INTERVAL DAY TO SECOND value must be exactly 11 bytes, got {}",
+            data.len()
+        )));
+    };
+
+    let days = read_duration_u32([d0, d1, d2, d3]) as i64;
+    let hours = hours as i64 - TNS_DURATION_OFFSET as i64;
+    let minutes = minutes as i64 - TNS_DURATION_OFFSET as i64;
+    let seconds = seconds as i64 - TNS_DURATION_OFFSET as i64;
+    let nanos = read_duration_u32([n0, n1, n2, n3]) as i64;
+
+    Ok(Duration::days(days)
+        + Duration::seconds(hours * 3600 + minutes * 60 + seconds)
+        + Duration::microseconds(nanos / 1000))
+}
+
+/// Read a big-endian u32 stored with the 0x80000000 bias.
+fn read_duration_u32(bytes: [u8; 4]) -> i32 {
+    u32::from_be_bytes(bytes).wrapping_sub(TNS_DURATION_MID) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_interval_ym_positive() {
+        // +02-03: years = 2 + 0x80000000, months = 3 + 60
+        let data = [0x80, 0x00, 0x00, 0x02, 63];
+        assert_eq!(decode_interval_ym(&data).unwrap(), (2, 3));
+    }
+
+    #[test]
+    fn test_decode_interval_ym_negative() {
+        // -01-06: years = -1 + 0x80000000 = 0x7FFFFFFF, months = -6 + 60
+        let data = [0x7F, 0xFF, 0xFF, 0xFF, 54];
+        assert_eq!(decode_interval_ym(&data).unwrap(), (-1, -6));
+    }
+
+    #[test]
+    fn test_decode_interval_ds() {
+        // +1 02:03:04.5
+        let mut data = vec![0x80, 0x00, 0x00, 0x01, 62, 63, 64];
+        data.extend_from_slice(&(0x80000000u32 + 500_000_000).to_be_bytes());
+        let expected = Duration::days(1)
+            + Duration::hours(2)
+            + Duration::minutes(3)
+            + Duration::seconds(4)
+            + Duration::milliseconds(500);
+        assert_eq!(decode_interval_ds(&data).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decode_interval_ds_negative() {
+        // -0 00:00:01: all components carry the sign
+        let mut data = vec![0x80, 0x00, 0x00, 0x00, 60, 60, 59];
+        data.extend_from_slice(&0x80000000u32.to_be_bytes());
+        assert_eq!(decode_interval_ds(&data).unwrap(), Duration::seconds(-1));
+    }
+
+    #[test]
+    fn test_decode_interval_wrong_length() {
+        assert!(decode_interval_ym(&[0x80, 0x00]).is_err());
+        assert!(decode_interval_ds(&[0x80, 0x00, 0x00, 0x01]).is_err());
+    }
+}
@@ -6,15 +6,95 @@
 //!
 //! | Oracle Type | Module |
 //! |-------------|--------|
-//! | NUMBER      | `number` |
+//! | NUMBER      | `number` (`Decimal` with feature `rust_decimal`) |
+//! | BINARY_FLOAT / BINARY_DOUBLE | `binary_float` |
+//! | BOOLEAN     | `boolean` |
 //! | BINARY_INTEGER | `number` |
-//! | DATE        | `date` |
+//! | DATE, TIMESTAMP | `date` |
+//! | INTERVAL YEAR TO MONTH | `interval` |
+//! | INTERVAL DAY TO SECOND | `interval` |
+//! | UROWID      | `rowid` |
+//! | JSON (OSON) | `oson` (feature `json`) |
+//! | VECTOR      | `vector` |
+//! | NCHAR, NVARCHAR2 | `nchar` |
 //!
 //! String types (VARCHAR2, CHAR, LONG) use simple UTF-8 conversion
 //! and don't require dedicated decoders.
+//!
+//! Decoders take server data and must return an error rather than panic on
+//! malformed input; indexing and slicing are denied by lint in this module.
+
+#![cfg_attr(not(test), deny(clippy::indexing_slicing))]
 
+mod binary_float;
+mod boolean;
 mod date;
+mod interval;
+mod nchar;
 mod number;
+#[cfg(feature = "json")]
+mod oson;
+mod rowid;
+mod vector;
+
+pub use binary_float::{decode_binary_double, decode_binary_float};
+pub use boolean::decode_oracle_boolean;
+pub use date::{decode_oracle_date, decode_oracle_timestamp};
+pub use interval::{decode_interval_ds, decode_interval_ym};
+pub use nchar::decode_nchar;
+#[cfg(feature = "bigdecimal")]
+pub use number::decode_oracle_number_bigdecimal;
+#[cfg(feature = "rust_decimal")]
+pub use number::decode_oracle_number_decimal;
+pub use number::{decode_oracle_integer, decode_oracle_number};
+#[cfg(feature = "json")]
+pub use oson::decode_oson;
+pub use rowid::decode_urowid;
+pub use vector::decode_vector;
+
+use crate::error::Result;
+use crate::protocol::constants::*;
+use crate::protocol::types::OracleValue;
 
-pub use date::decode_oracle_date;
-pub use number::decode_oracle_number;
+/// Decode a column value from the bytes received for it.
+///
+/// `ora_type` is the column's Oracle type number and `charset_form` its
+/// character set form, telling national character set text apart. ROWIDs
+/// and LOB locators have their own framing and are decoded while the row is
+/// parsed; JSON and VECTOR values are passed as their image, without the LOB
+/// framing.
+pub fn decode_value(ora_type: u16, charset_form: u8, bytes: &[u8]) -> Result<OracleValue> {
+    Ok(match ora_type {
+        // NVARCHAR2, NCHAR
+        ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR | ORA_TYPE_NUM_LONG
+            if charset_form == CS_FORM_NCHAR =>
+        {
+            OracleValue::String(decode_nchar(bytes)?)
+        }
+        // VARCHAR2, CHAR, LONG
+        ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR | ORA_TYPE_NUM_LONG => {
+            OracleValue::String(String::from_utf8_lossy(bytes).into_owned())
+        }
+        // NUMBER, BINARY_INTEGER
+        ORA_TYPE_NUM_NUMBER | ORA_TYPE_NUM_BINARY_INTEGER => number::decode_number_value(bytes)?,
+        ORA_TYPE_NUM_DATE => OracleValue::Date(decode_oracle_date(bytes)?),
+        ORA_TYPE_NUM_TIMESTAMP => OracleValue::Date(decode_oracle_timestamp(bytes)?),
+        ORA_TYPE_NUM_BOOLEAN => OracleValue::Boolean(decode_oracle_boolean(bytes)?),
+        ORA_TYPE_NUM_UROWID => OracleValue::String(decode_urowid(bytes)?.to_string()),
+        // RAW, LONG RAW
+        ORA_TYPE_NUM_RAW | ORA_TYPE_NUM_LONG_RAW => OracleValue::Raw(bytes.to_vec()),
+        ORA_TYPE_NUM_INTERVAL_YM => {
+            let (years, months) = decode_interval_ym(bytes)?;
+            OracleValue::IntervalYM { years, months }
+        }
+        ORA_TYPE_NUM_INTERVAL_DS => OracleValue::IntervalDS(decode_interval_ds(bytes)?),
+        // Without the `json` feature the raw OSON image is returned
+        #[cfg(feature = "json")]
+        ORA_TYPE_NUM_JSON => OracleValue::Json(decode_oson(bytes)?),
+        #[cfg(not(feature = "json"))]
+        ORA_TYPE_NUM_JSON => OracleValue::Raw(bytes.to_vec()),
+        ORA_TYPE_NUM_VECTOR => OracleValue::Vector(decode_vector(bytes)?),
+        // For other types, return as string for now
+        _ => OracleValue::String(String::from_utf8_lossy(bytes).into_owned()),
+    })
+}
@@ -0,0 +1,46 @@
+//! National character set decoder (NCHAR, NVARCHAR2, NCLOB).
+//!
+//! Columns with charset form NCHAR are sent in AL16UTF16, the national
+//! character set: big-endian UTF-16 code units.
+
+use crate::error::{Error, Result};
+
+/// Decode a national character set value.
+///
+/// # Errors
+/// Returns `Error::Protocol` if the data has an odd length or is not valid
+/// UTF-16.
+pub fn decode_nchar(data: &[u8]) -> Result<String> {
+    let chunks = data.chunks_exact(2);
+    if !chunks.remainder().is_empty() {
+        return Err(Error::protocol(format!(
+            "NCHAR value has odd length {}",
+            data.len()
+        )));
+    }
+    let units: Vec<u16> = chunks
+        .filter_map(|pair| <[u8; 2]>::try_from(pair).ok())
+        .map(u16::from_be_bytes)
+        .collect();
+    String::from_utf16(&units)
+        .map_err(|e| Error::protocol(format!("Invalid UTF-16 in NCHAR value: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_nchar() {
+        assert_eq!(decode_nchar(&[0x00, 0x41, 0x00, 0xE9]).unwrap(), "Aé");
+        // Surrogate pair (U+1F600)
+        assert_eq!(
+            decode_nchar(&[0xD8, 0x3D, 0xDE, 0x00]).unwrap(),
+            "\u{1F600}"
+        );
+        assert_eq!(decode_nchar(&[]).unwrap(), "");
+        assert!(decode_nchar(&[0x00, 0x41, 0x00]).is_err());
+        // Unpaired high surrogate
+        assert!(decode_nchar(&[0xD8, 0x3D]).is_err());
+    }
+}
@@ -3,20 +3,56 @@
 //! Oracle NUMBER is a variable-length format where:
 //! - First byte is exponent (with sign info in high bit)
 //! - Remaining bytes are base-100 digits of mantissa
+//! - Negative numbers with fewer than 20 mantissa bytes end with byte 102
+//!
+//! Positive infinity is `FF 65` and negative infinity `00` (no mantissa).
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::protocol::types::OracleValue;
 
-/// Decode Oracle NUMBER format to string.
-///
-/// Preserves full precision by returning the number as a string.
-/// Use `.parse::<i64>()` or `.parse::<f64>()` to convert.
-pub fn decode_oracle_number(bytes: &[u8]) -> Result<String> {
-    if bytes.is_empty() {
-        return Ok("0".to_string());
+/// Encoding of positive infinity: the highest exponent and a digit byte of 101.
+const POSITIVE_INFINITY: [u8; 2] = [0xFF, 0x65];
+
+/// Terminator byte of negative numbers with fewer than 20 mantissa bytes.
+const NEGATIVE_TERMINATOR: u8 = 102;
+
+/// A NUMBER split into its sign, significant decimal digits and the
+/// position of the decimal point within them.
+enum Parts {
+    Zero,
+    Infinity {
+        negative: bool,
+    },
+    Finite {
+        negative: bool,
+        /// Decimal digits (0-9), without leading or trailing zeros.
+        digits: Vec<u8>,
+        /// Digits before the decimal point; negative or past the end for
+        /// leading or trailing zeros.
+        point: i16,
+    },
+}
+
+/// Get the base-100 digit of a mantissa byte: the byte minus 1 for
+/// positive numbers, subtracted from 101 for negative ones.
+fn base100_digit(byte: u8, is_positive: bool) -> Option<u8> {
+    if is_positive {
+        byte.checked_sub(1)
+    } else {
+        101u8.checked_sub(byte)
     }
+    .filter(|&pair| pair < 100)
+}
 
-    let exp_byte = bytes[0];
+/// Split NUMBER bytes into sign, digits and decimal point position.
+fn decode_parts(bytes: &[u8]) -> Result<Parts> {
+    let Some((&exp_byte, mantissa)) = bytes.split_first() else {
+        return Ok(Parts::Zero);
+    };
     let is_positive = (exp_byte & 0x80) != 0;
+    if bytes == POSITIVE_INFINITY {
+        return Ok(Parts::Infinity { negative: false });
+    }
 
     // Calculate exponent
     let exponent: i16 = if is_positive {
@@ -26,104 +62,108 @@ pub fn decode_oracle_number(bytes: &[u8]) -> Result<String> {
         (!exp_byte) as i16 - 193
     };
 
-    // decimal_point_index indicates where the decimal point goes
-    let mut decimal_point_index: i16 = exponent * 2 + 2;
-
-    // Handle zero and special cases
-    if bytes.len() == 1 {
-        if is_positive {
-            return Ok("0".to_string());
-        } else {
-            // -1e126 (max negative value) - rare, return special
-            return Ok("-1e126".to_string());
-        }
-    }
-
-    // Check for trailing 102 byte for negative numbers
-    let mantissa_end = if !is_positive && bytes[bytes.len() - 1] == 102 {
-        bytes.len() - 1
-    } else {
-        bytes.len()
+    // Negative numbers end with a terminator unless the mantissa is full
+    let mantissa = match mantissa.split_last() {
+        Some((&NEGATIVE_TERMINATOR, rest)) if !is_positive => rest,
+        _ => mantissa,
     };
 
-    // Process mantissa bytes to extract base-100 digits
-    let mut digits: Vec<u8> = Vec::with_capacity((mantissa_end - 1) * 2);
-
-    for (i, &byte) in bytes.iter().enumerate().take(mantissa_end).skip(1) {
-        let digit_pair = if is_positive {
-            byte.wrapping_sub(1)
+    // An empty mantissa is zero, or negative infinity
+    if mantissa.is_empty() {
+        return Ok(if is_positive {
+            Parts::Zero
         } else {
-            101u8.wrapping_sub(byte)
-        };
+            Parts::Infinity { negative: true }
+        });
+    }
 
-        // First digit of the pair
-        let d1 = digit_pair / 10;
-        // Second digit of the pair
-        let d2 = digit_pair % 10;
-
-        // Handle leading zeros - they reduce decimal point index
-        if digits.is_empty() && d1 == 0 {
-            decimal_point_index -= 1;
-            if d2 != 0 || i < mantissa_end - 1 {
-                digits.push(d2);
-            } else if d2 == 0 {
-                decimal_point_index -= 1;
-            }
-        } else if d1 == 10 {
-            // Overflow case (99+1=100) - rare
-            digits.push(1);
-            digits.push(0);
-            decimal_point_index += 1;
-        } else {
-            digits.push(d1);
-            // Only add trailing zero if not last byte
-            if d2 != 0 || i < mantissa_end - 1 {
-                digits.push(d2);
-            }
-        }
+    // Split the base-100 digits (plus 1 for positive numbers, subtracted
+    // from 101 for negative ones) into decimal digits
+    let mut digits: Vec<u8> = Vec::with_capacity(mantissa.len() * 2);
+    for &byte in mantissa {
+        let pair = base100_digit(byte, is_positive)
+            .ok_or_else(|| Error::protocol(format!("Invalid NUMBER mantissa byte: {}", byte)))?;
+        digits.push(pair / 10);
+        digits.push(pair % 10);
     }
 
+    // The decimal point goes after both digits of the first pair; leading
+    // zeros move it left
+    let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+    digits.drain(..leading_zeros);
+    let point: i16 = exponent * 2 + 2 - leading_zeros as i16;
+
     // Remove trailing zeros from digits
-    while !digits.is_empty() && digits[digits.len() - 1] == 0 {
+    while digits.last() == Some(&0) {
         digits.pop();
     }
 
     // If all digits were zeros
     if digits.is_empty() {
-        return Ok("0".to_string());
+        return Ok(Parts::Zero);
     }
+    Ok(Parts::Finite {
+        negative: !is_positive,
+        digits,
+        point,
+    })
+}
+
+/// Decode Oracle NUMBER format to string.
+///
+/// Preserves full precision by returning the number as a string.
+/// Use `.parse::<i64>()` or `.parse::<f64>()` to convert.
+///
+/// Oracle's infinities decode as "inf" and "-inf", which parse as `f64`
+/// infinities. The exponent byte covers 1e-130 to 9.99...e125, so the
+/// string can be long; it is never in scientific notation.
+///
+/// # Errors
+/// Returns `Error::Protocol` if a mantissa byte is not a base-100 digit.
+pub fn decode_oracle_number(bytes: &[u8]) -> Result<String> {
+    let (negative, digits, point) = match decode_parts(bytes)? {
+        Parts::Zero => return Ok("0".to_string()),
+        Parts::Infinity { negative } => {
+            return Ok(if negative { "-inf" } else { "inf" }.to_string())
+        }
+        Parts::Finite {
+            negative,
+            digits,
+            point,
+        } => (negative, digits, point),
+    };
 
     // Build the string
     let mut result = String::new();
 
-    if !is_positive {
+    if negative {
         result.push('-');
     }
 
     let num_digits = digits.len() as i16;
 
-    if decimal_point_index <= 0 {
+    if point <= 0 {
         // Number is less than 1: 0.00...digits
         result.push('0');
         result.push('.');
-        for _ in decimal_point_index..0 {
+        for _ in point..0 {
             result.push('0');
         }
         for d in &digits {
             result.push((b'0' + d) as char);
         }
-    } else if decimal_point_index >= num_digits {
+    } else if point >= num_digits {
         // Number is an integer: digits + trailing zeros
         for d in &digits {
             result.push((b'0' + d) as char);
         }
-        for _ in num_digits..decimal_point_index {
+        for _ in num_digits..point {
             result.push('0');
         }
     } else {
         // Number has decimal point in the middle
         for (i, d) in digits.iter().enumerate() {
-            if i as i16 == decimal_point_index {
+            if i as i16 == point {
                 result.push('.');
             }
             result.push((b'0' + d) as char);
@@ -133,6 +173,130 @@ pub fn decode_oracle_number(bytes: &[u8]) -> Result<String> {
     Ok(result)
 }
 
+/// Decode Oracle NUMBER format straight to an `i64`, without building the
+/// string form.
+///
+/// Returns `None` for values with a fractional part, values outside the
+/// `i64` range, infinities and malformed bytes; `decode_oracle_number`
+/// handles those (and reports malformed bytes).
+pub fn decode_oracle_integer(bytes: &[u8]) -> Option<i64> {
+    let Some((&exp_byte, mantissa)) = bytes.split_first() else {
+        return Some(0);
+    };
+    if bytes == POSITIVE_INFINITY {
+        return None;
+    }
+    let is_positive = (exp_byte & 0x80) != 0;
+    // Base-100 exponent of the first mantissa digit
+    let exponent = if is_positive {
+        exp_byte as i16 - 193
+    } else {
+        (!exp_byte) as i16 - 193
+    };
+    let mantissa = match mantissa.split_last() {
+        Some((&NEGATIVE_TERMINATOR, rest)) if !is_positive => rest,
+        _ => mantissa,
+    };
+    if mantissa.is_empty() {
+        // Zero, or negative infinity
+        return is_positive.then_some(0);
+    }
+
+    // Accumulate towards the sign so i64::MIN fits
+    let mut value: i64 = 0;
+    for (position, &byte) in mantissa.iter().enumerate() {
+        let pair = base100_digit(byte, is_positive)? as i64;
+        if position as i16 > exponent {
+            // A fractional digit
+            if pair != 0 {
+                return None;
+            }
+            continue;
+        }
+        value = value.checked_mul(100)?;
+        value = if is_positive {
+            value.checked_add(pair)?
+        } else {
+            value.checked_sub(pair)?
+        };
+    }
+    for _ in mantissa.len() as i16..=exponent {
+        value = value.checked_mul(100)?;
+    }
+    Some(value)
+}
+
+/// Decode Oracle NUMBER format to a `rust_decimal::Decimal`.
+///
+/// Returns `None` for infinities and for values a `Decimal` cannot hold
+/// exactly: more than 28 decimal places, or magnitudes of 2^96 and above.
+///
+/// # Errors
+/// Returns `Error::Protocol` if a mantissa byte is not a base-100 digit.
+#[cfg(feature = "rust_decimal")]
+pub fn decode_oracle_number_decimal(bytes: &[u8]) -> Result<Option<rust_decimal::Decimal>> {
+    let (negative, digits, point) = match decode_parts(bytes)? {
+        Parts::Zero => return Ok(Some(rust_decimal::Decimal::ZERO)),
+        Parts::Infinity { .. } => return Ok(None),
+        Parts::Finite {
+            negative,
+            digits,
+            point,
+        } => (negative, digits, point),
+    };
+    // value = digits * 10^(point - len)
+    let trailing_zeros = (point - digits.len() as i16).max(0) as u32;
+    let scale = (digits.len() as i16 - point).max(0) as u32;
+    let mantissa = digits
+        .iter()
+        .map(|&d| d as i128)
+        .chain(std::iter::repeat_n(0, trailing_zeros as usize))
+        .try_fold(0i128, |acc, d| acc.checked_mul(10)?.checked_add(d));
+    let Some(mantissa) = mantissa else {
+        return Ok(None);
+    };
+    let mantissa = if negative { -mantissa } else { mantissa };
+    Ok(rust_decimal::Decimal::try_from_i128_with_scale(mantissa, scale).ok())
+}
+
+/// Decode Oracle NUMBER format to a `bigdecimal::BigDecimal`.
+///
+/// Every finite NUMBER is held exactly; infinities return `None`.
+///
+/// # Errors
+/// Returns `Error::Protocol` if a mantissa byte is not a base-100 digit.
+#[cfg(feature = "bigdecimal")]
+pub fn decode_oracle_number_bigdecimal(bytes: &[u8]) -> Result<Option<bigdecimal::BigDecimal>> {
+    use bigdecimal::num_bigint::{BigInt, Sign};
+
+    match decode_parts(bytes)? {
+        Parts::Zero => Ok(Some(bigdecimal::BigDecimal::from(0))),
+        Parts::Infinity { .. } => Ok(None),
+        Parts::Finite {
+            negative,
+            digits,
+            point,
+        } => {
+            let sign = if negative { Sign::Minus } else { Sign::Plus };
+            let mantissa = BigInt::from_radix_be(sign, &digits, 10)
+                .ok_or_else(|| Error::protocol("invalid NUMBER digits"))?;
+            // value = mantissa * 10^-scale
+            let scale = digits.len() as i64 - point as i64;
+            Ok(Some(bigdecimal::BigDecimal::new(mantissa, scale)))
+        }
+    }
+}
+
+/// Decode a NUMBER column value: an exact `Decimal` where one can hold it
+/// (with the `rust_decimal` feature), otherwise the string form.
+pub(crate) fn decode_number_value(bytes: &[u8]) -> Result<OracleValue> {
+    #[cfg(feature = "rust_decimal")]
+    if let Some(decimal) = decode_oracle_number_decimal(bytes)? {
+        return Ok(OracleValue::Decimal(decimal));
+    }
+    Ok(OracleValue::Number(decode_oracle_number(bytes)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +330,222 @@ mod tests {
         // 0.5: exp_byte=0xC0 (192), exponent=-1, mantissa=0x33 (51)
         assert_eq!(decode_oracle_number(&[0xC0, 0x33]).unwrap(), "0.5");
     }
+
+    /// Values and their `DUMP(value, 16)` bytes.
+    const CORPUS: &[(&str, &[u8])] = &[
+        ("0", &[0x80]),
+        ("1", &[0xC1, 0x02]),
+        ("-1", &[0x3E, 0x64, 0x66]),
+        ("1.5", &[0xC1, 0x02, 0x33]),
+        ("-0.5", &[0x3F, 0x33, 0x66]),
+        ("123.456", &[0xC2, 0x02, 0x18, 0x2E, 0x3D]),
+        ("-123.456", &[0x3D, 0x64, 0x4E, 0x38, 0x29, 0x66]),
+        ("0.0000000001", &[0xBC, 0x02]),
+        ("2147483648", &[0xC5, 0x16, 0x30, 0x31, 0x25, 0x31]),
+        (
+            "-9223372036854775808",
+            &[
+                0x35, 0x5C, 0x4F, 0x44, 0x1D, 0x62, 0x21, 0x2F, 0x18, 0x2B, 0x5D, 0x66,
+            ],
+        ),
+        // 38 significant digits
+        (
+            "12345678901234567890123456789012345678",
+            &[
+                0xD3, 0x0D, 0x23, 0x39, 0x4F, 0x5B, 0x0D, 0x23, 0x39, 0x4F, 0x5B, 0x0D, 0x23, 0x39,
+                0x4F, 0x5B, 0x0D, 0x23, 0x39, 0x4F,
+            ],
+        ),
+        (
+            "-12345678901234567890123456789012345678",
+            &[
+                0x2C, 0x59, 0x43, 0x2D, 0x17, 0x0B, 0x59, 0x43, 0x2D, 0x17, 0x0B, 0x59, 0x43, 0x2D,
+                0x17, 0x0B, 0x59, 0x43, 0x2D, 0x17, 0x66,
+            ],
+        ),
+        (
+            "0.12345678901234567890123456789012345678",
+            &[
+                0xC0, 0x0D, 0x23, 0x39, 0x4F, 0x5B, 0x0D, 0x23, 0x39, 0x4F, 0x5B, 0x0D, 0x23, 0x39,
+                0x4F, 0x5B, 0x0D, 0x23, 0x39, 0x4F,
+            ],
+        ),
+        (
+            "-99999999999999999999999999999999999999",
+            &[
+                0x2C, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x02,
+                0x02, 0x02, 0x02, 0x02, 0x02, 0x02, 0x66,
+            ],
+        ),
+        // 20 mantissa bytes: negative numbers have no terminator
+        (
+            "-0.1234567890123456789012345678901234567891",
+            &[
+                0x3F, 0x59, 0x43, 0x2D, 0x17, 0x0B, 0x59, 0x43, 0x2D, 0x17, 0x0B, 0x59, 0x43, 0x2D,
+                0x17, 0x0B, 0x59, 0x43, 0x2D, 0x17, 0x0A,
+            ],
+        ),
+        ("inf", &[0xFF, 0x65]),
+        ("-inf", &[0x00]),
+    ];
+
+    #[test]
+    fn test_decode_number_corpus() {
+        for (expected, bytes) in CORPUS {
+            assert_eq!(
+                decode_oracle_number(bytes).unwrap(),
+                *expected,
+                "{:02X?}",
+                bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_number_exponent_extremes() {
+        // Largest and smallest magnitudes
+        let mut max = vec![0xFF];
+        max.extend([0x64; 19]);
+        assert_eq!(
+            decode_oracle_number(&max).unwrap(),
+            format!("{}{}", "9".repeat(38), "0".repeat(88))
+        );
+        assert_eq!(
+            decode_oracle_number(&[0x00, 0x5B, 0x66]).unwrap(),
+            format!("-1{}", "0".repeat(125))
+        );
+        assert_eq!(
+            decode_oracle_number(&[0x80, 0x02]).unwrap(),
+            format!("0.{}1", "0".repeat(129))
+        );
+        assert_eq!(
+            decode_oracle_number(&[0x7F, 0x64, 0x66]).unwrap(),
+            format!("-0.{}1", "0".repeat(129))
+        );
+    }
+
+    #[test]
+    fn test_decode_number_special() {
+        assert_eq!(decode_oracle_number(&[]).unwrap(), "0");
+        // Negative infinity, with a terminator
+        assert_eq!(decode_oracle_number(&[0x00, 0x66]).unwrap(), "-inf");
+        assert_eq!("-inf".parse::<f64>().unwrap(), f64::NEG_INFINITY);
+        // Zero digits never decode as "-0"
+        assert_eq!(decode_oracle_number(&[0x3E, 0x65, 0x66]).unwrap(), "0");
+        // Bytes outside the digit range
+        assert!(decode_oracle_number(&[0xC1, 0x00]).is_err());
+        assert!(decode_oracle_number(&[0xC1, 0x66]).is_err());
+        assert!(decode_oracle_number(&[0x3E, 0x01, 0x66]).is_err());
+    }
+
+    #[test]
+    fn test_decode_integer_corpus() {
+        for (expected, bytes) in CORPUS {
+            assert_eq!(
+                decode_oracle_integer(bytes),
+                expected.parse::<i64>().ok(),
+                "{}",
+                expected
+            );
+        }
+        assert_eq!(decode_oracle_integer(&[0xC2, 0x02]), Some(100));
+        assert_eq!(decode_oracle_integer(&[0xCA, 0x02]), Some(10i64.pow(18)));
+        assert_eq!(decode_oracle_integer(&[]), Some(0));
+        // i64::MAX and one past it
+        assert_eq!(
+            decode_oracle_integer(&[
+                0xCA, 0x0A, 0x17, 0x22, 0x49, 0x04, 0x45, 0x37, 0x4E, 0x3B, 0x08
+            ]),
+            Some(i64::MAX)
+        );
+        assert_eq!(
+            decode_oracle_integer(&[
+                0xCA, 0x0A, 0x17, 0x22, 0x49, 0x04, 0x45, 0x37, 0x4E, 0x3B, 0x09
+            ]),
+            None
+        );
+        // Magnitudes far beyond i64 stop early
+        let mut max = vec![0xFF];
+        max.extend([0x64; 19]);
+        assert_eq!(decode_oracle_integer(&max), None);
+        assert_eq!(decode_oracle_integer(&[0xC1, 0x00]), None);
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_decode_number_decimal_corpus() {
+        use std::str::FromStr;
+
+        for (expected, bytes) in CORPUS {
+            let decimal = decode_oracle_number_decimal(bytes).unwrap();
+            match rust_decimal::Decimal::from_str_exact(expected) {
+                Ok(exact) => assert_eq!(decimal, Some(exact), "{}", expected),
+                Err(_) => assert_eq!(decimal, None, "{}", expected),
+            }
+        }
+        assert_eq!(
+            decode_oracle_number_decimal(&[0xC2, 0x02, 0x18, 0x2E, 0x3D]).unwrap(),
+            Some(rust_decimal::Decimal::from_str("123.456").unwrap())
+        );
+        // Trailing zeros of large integers are multiplied in
+        assert_eq!(
+            decode_oracle_number_decimal(&[0xC5, 0x16, 0x30, 0x31, 0x25, 0x31])
+                .unwrap()
+                .map(|d| d.to_string()),
+            Some("2147483648".to_string())
+        );
+        assert_eq!(
+            decode_oracle_number_decimal(&[0xCA, 0x02])
+                .unwrap()
+                .map(|d| d.to_string()),
+            Some("1000000000000000000".to_string())
+        );
+        // Too many decimal places or too large
+        assert_eq!(decode_oracle_number_decimal(&[0x80, 0x02]).unwrap(), None);
+        let mut max = vec![0xFF];
+        max.extend([0x64; 19]);
+        assert_eq!(decode_oracle_number_decimal(&max).unwrap(), None);
+        assert!(decode_oracle_number_decimal(&[0xC1, 0x00]).is_err());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_decode_number_value() {
+        assert_eq!(
+            decode_number_value(&[0x3F, 0x33, 0x66]).unwrap(),
+            OracleValue::Decimal(rust_decimal::Decimal::new(-5, 1))
+        );
+        // Values a Decimal cannot hold keep their string form
+        assert_eq!(
+            decode_number_value(&[0xFF, 0x65]).unwrap(),
+            OracleValue::Number("inf".to_string())
+        );
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn test_decode_number_bigdecimal_corpus() {
+        for (expected, bytes) in CORPUS {
+            let decimal = decode_oracle_number_bigdecimal(bytes).unwrap();
+            match expected.parse::<bigdecimal::BigDecimal>() {
+                Ok(exact) => assert_eq!(decimal, Some(exact), "{}", expected),
+                Err(_) => assert_eq!(decimal, None, "{}", expected),
+            }
+        }
+        // Extremes are held exactly
+        let mut max = vec![0xFF];
+        max.extend([0x64; 19]);
+        assert_eq!(
+            decode_oracle_number_bigdecimal(&max).unwrap(),
+            Some(
+                format!("{}{}", "9".repeat(38), "0".repeat(88))
+                    .parse()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            decode_oracle_number_bigdecimal(&[0x80, 0x02]).unwrap(),
+            Some(format!("0.{}1", "0".repeat(129)).parse().unwrap())
+        );
+    }
 }
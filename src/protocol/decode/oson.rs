@@ -0,0 +1,546 @@
+//! Oracle OSON (binary JSON) decoder.
+//!
+//! Native JSON columns (21c+) are sent as OSON images. An image consists of:
+//! - header: magic bytes `FF 4A 5A`, version, primary flags (UB2)
+//! - field name dictionary: counts, segment sizes, hash ids, offsets, names
+//! - tree segment: nodes addressed by offsets relative to the segment start
+//!
+//! Scalar images (flag `IS_SCALAR`) skip the dictionary entirely.
+//!
+//! Decoding follows python-oracledb's `OsonDecoder`. Values with no JSON
+//! equivalent are mapped to strings: dates and timestamps as ISO 8601,
+//! intervals in Oracle literal form and binary values as uppercase hex.
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::{Error, Result};
+use crate::protocol::types::OracleValue;
+
+use super::{
+    decode_binary_double, decode_binary_float, decode_interval_ds, decode_interval_ym,
+    decode_oracle_date, decode_oracle_number,
+};
+
+const MAGIC: [u8; 3] = [0xFF, 0x4A, 0x5A];
+const VERSION_MAX_FNAME_255: u8 = 1;
+const VERSION_MAX_FNAME_65535: u8 = 3;
+
+// Primary header flags
+const FLAG_REL_OFFSET_MODE: u16 = 0x0001;
+const FLAG_NUM_FNAMES_UINT32: u16 = 0x0008;
+const FLAG_IS_SCALAR: u16 = 0x0010;
+const FLAG_NUM_FNAMES_UINT16: u16 = 0x0400;
+const FLAG_FNAMES_SEG_UINT32: u16 = 0x0800;
+const FLAG_TREE_SEG_UINT32: u16 = 0x1000;
+
+// Secondary header flags
+const FLAG_SEC_FNAMES_SEG_UINT16: u16 = 0x0100;
+
+// Node types
+const TYPE_NULL: u8 = 0x30;
+const TYPE_TRUE: u8 = 0x31;
+const TYPE_FALSE: u8 = 0x32;
+const TYPE_STRING_LENGTH_UINT8: u8 = 0x33;
+const TYPE_NUMBER_LENGTH_UINT8: u8 = 0x34;
+const TYPE_BINARY_DOUBLE: u8 = 0x36;
+const TYPE_STRING_LENGTH_UINT16: u8 = 0x37;
+const TYPE_STRING_LENGTH_UINT32: u8 = 0x38;
+const TYPE_TIMESTAMP: u8 = 0x39;
+const TYPE_BINARY_LENGTH_UINT16: u8 = 0x3A;
+const TYPE_BINARY_LENGTH_UINT32: u8 = 0x3B;
+const TYPE_DATE: u8 = 0x3C;
+const TYPE_INTERVAL_YM: u8 = 0x3D;
+const TYPE_INTERVAL_DS: u8 = 0x3E;
+const TYPE_TIMESTAMP_TZ: u8 = 0x7C;
+const TYPE_TIMESTAMP7: u8 = 0x7D;
+const TYPE_ID: u8 = 0x7E;
+const TYPE_BINARY_FLOAT: u8 = 0x7F;
+
+/// Maximum container nesting (same as serde_json), guarding against malformed
+/// or cyclic images.
+const MAX_DEPTH: usize = 128;
+
+/// Decode an OSON image into a JSON value.
+///
+/// # Errors
+/// Returns `Error::Protocol` if the image is malformed or uses an
+/// unsupported node type.
+pub fn decode_oson(data: &[u8]) -> Result<Value> {
+    OsonDecoder::new(data).decode()
+}
+
+/// Cursor over an OSON image.
+struct OsonDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    primary_flags: u16,
+    field_id_length: usize,
+    field_names: Vec<String>,
+    tree_seg_pos: usize,
+    depth: usize,
+}
+
+impl<'a> OsonDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            primary_flags: 0,
+            field_id_length: 1,
+            field_names: Vec::new(),
+            tree_seg_pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn decode(mut self) -> Result<Value> {
+        if self.read_bytes(3)? != MAGIC {
+            return Err(Error::protocol("invalid OSON magic bytes"));
+        }
+        let version = self.read_u8()?;
+        if version != VERSION_MAX_FNAME_255 && version != VERSION_MAX_FNAME_65535 {
+            return Err(Error::protocol(format!(
+                "unsupported OSON version: {}",
+                version
+            )));
+        }
+        self.primary_flags = self.read_u16()?;
+
+        // Scalar images only carry the tree segment size
+        if self.primary_flags & FLAG_IS_SCALAR != 0 {
+            self.skip(if self.has_flag(FLAG_TREE_SEG_UINT32) {
+                4
+            } else {
+                2
+            })?;
+            return self.decode_node();
+        }
+
+        let num_short_field_names = if self.has_flag(FLAG_NUM_FNAMES_UINT32) {
+            self.field_id_length = 4;
+            self.read_u32()? as usize
+        } else if self.has_flag(FLAG_NUM_FNAMES_UINT16) {
+            self.field_id_length = 2;
+            self.read_u16()? as usize
+        } else {
+            self.field_id_length = 1;
+            self.read_u8()? as usize
+        };
+
+        let short_fnames_seg_size = if self.has_flag(FLAG_FNAMES_SEG_UINT32) {
+            self.read_u32()? as usize
+        } else {
+            self.read_u16()? as usize
+        };
+
+        // Field names longer than 255 bytes live in a secondary segment
+        let mut num_long_field_names = 0;
+        let mut long_fnames_seg_size = 0;
+        let mut long_offsets_size = 4;
+        if version == VERSION_MAX_FNAME_65535 {
+            let secondary_flags = self.read_u16()?;
+            if secondary_flags & FLAG_SEC_FNAMES_SEG_UINT16 != 0 {
+                long_offsets_size = 2;
+            }
+            num_long_field_names = self.read_u32()? as usize;
+            long_fnames_seg_size = self.read_u32()? as usize;
+        }
+
+        let _tree_seg_size = if self.has_flag(FLAG_TREE_SEG_UINT32) {
+            self.read_u32()?
+        } else {
+            self.read_u16()? as u32
+        };
+        let _num_tiny_nodes = self.read_u16()?;
+
+        let short_offsets_size = if self.has_flag(FLAG_FNAMES_SEG_UINT32) {
+            4
+        } else {
+            2
+        };
+        self.read_field_names(
+            num_short_field_names,
+            short_fnames_seg_size,
+            1,
+            short_offsets_size,
+        )?;
+        self.read_field_names(
+            num_long_field_names,
+            long_fnames_seg_size,
+            2,
+            long_offsets_size,
+        )?;
+
+        self.tree_seg_pos = self.pos;
+        self.decode_node()
+    }
+
+    /// Read a field name dictionary: hash ids, name offsets, then names.
+    ///
+    /// Short names use 1-byte hash ids and lengths, long names 2-byte ones.
+    fn read_field_names(
+        &mut self,
+        num_fields: usize,
+        seg_size: usize,
+        width: usize,
+        offsets_size: usize,
+    ) -> Result<()> {
+        if num_fields == 0 {
+            return Ok(());
+        }
+        self.skip(num_fields * width)?; // hash ids
+        let offsets = self.read_bytes(num_fields * offsets_size)?;
+        let segment = self.read_bytes(seg_size)?;
+
+        for chunk in offsets.chunks(offsets_size) {
+            let offset = read_be(chunk) as usize;
+            let name_start = offset + width;
+            let len = segment
+                .get(offset..name_start)
+                .map(read_be)
+                .ok_or_else(|| Error::protocol("OSON field name offset out of range"))?
+                as usize;
+            let name = segment
+                .get(name_start..name_start + len)
+                .ok_or_else(|| Error::protocol("OSON field name out of range"))?;
+            self.field_names
+                .push(String::from_utf8_lossy(name).into_owned());
+        }
+        Ok(())
+    }
+
+    fn decode_node(&mut self) -> Result<Value> {
+        let node_type = self.read_u8()?;
+        if node_type & 0x80 != 0 {
+            return self.decode_container_node(node_type);
+        }
+
+        let value = match node_type {
+            TYPE_NULL => Value::Null,
+            TYPE_TRUE => Value::Bool(true),
+            TYPE_FALSE => Value::Bool(false),
+            TYPE_DATE | TYPE_TIMESTAMP7 => {
+                let bytes = self.read_bytes(7)?;
+                Value::String(format_iso(decode_oracle_date(bytes)?, 0))
+            }
+            TYPE_TIMESTAMP | TYPE_TIMESTAMP_TZ => {
+                let date = decode_oracle_date(self.read_bytes(7)?)?;
+                let nanos = read_be(self.read_bytes(4)?);
+                if node_type == TYPE_TIMESTAMP_TZ {
+                    self.skip(2)?; // time zone
+                }
+                Value::String(format_iso(date, nanos))
+            }
+            TYPE_BINARY_FLOAT => {
+                let bytes = self.read_bytes(4)?;
+                float_value(decode_binary_float(bytes)? as f64)
+            }
+            TYPE_BINARY_DOUBLE => {
+                let bytes = self.read_bytes(8)?;
+                float_value(decode_binary_double(bytes)?)
+            }
+            TYPE_INTERVAL_YM => {
+                let (years, months) = decode_interval_ym(self.read_bytes(5)?)?;
+                Value::String(OracleValue::IntervalYM { years, months }.to_string())
+            }
+            TYPE_INTERVAL_DS => {
+                let duration = decode_interval_ds(self.read_bytes(11)?)?;
+                Value::String(OracleValue::IntervalDS(duration).to_string())
+            }
+            TYPE_STRING_LENGTH_UINT8 => {
+                let len = self.read_u8()? as usize;
+                self.read_string(len)?
+            }
+            TYPE_STRING_LENGTH_UINT16 => {
+                let len = self.read_u16()? as usize;
+                self.read_string(len)?
+            }
+            TYPE_STRING_LENGTH_UINT32 => {
+                let len = self.read_u32()? as usize;
+                self.read_string(len)?
+            }
+            TYPE_NUMBER_LENGTH_UINT8 => {
+                let len = self.read_u8()? as usize;
+                self.read_number(len)?
+            }
+            TYPE_ID => {
+                let len = self.read_u8()? as usize;
+                Value::String(to_hex(self.read_bytes(len)?))
+            }
+            TYPE_BINARY_LENGTH_UINT16 => {
+                let len = self.read_u16()? as usize;
+                Value::String(to_hex(self.read_bytes(len)?))
+            }
+            TYPE_BINARY_LENGTH_UINT32 => {
+                let len = self.read_u32()? as usize;
+                Value::String(to_hex(self.read_bytes(len)?))
+            }
+            // Numbers with the length stored in the low nibble of the node type
+            _ if matches!(node_type & 0xF0, 0x20 | 0x40 | 0x50 | 0x60) => {
+                let len = (node_type & 0x0F) as usize + 1;
+                self.read_number(len)?
+            }
+            // Strings with the length stored in the node type itself
+            _ if node_type & 0xE0 == 0 => self.read_string(node_type as usize)?,
+            _ => {
+                return Err(Error::protocol(format!(
+                    "unsupported OSON node type: 0x{:02X}",
+                    node_type
+                )))
+            }
+        };
+        Ok(value)
+    }
+
+    fn decode_container_node(&mut self, node_type: u8) -> Result<Value> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::protocol("OSON image nested too deeply"));
+        }
+        let is_object = node_type & 0x40 == 0;
+        let container_offset = (self.pos - self.tree_seg_pos - 1) as u32;
+
+        let (mut num_children, is_shared) = self.read_num_children(node_type)?;
+        let mut field_ids_pos = self.pos;
+        let mut offsets_pos;
+        if is_shared {
+            // Field ids are shared with an earlier object of the same shape
+            let offset = self.read_offset(node_type)?;
+            offsets_pos = self.pos;
+            self.seek(self.tree_seg_pos + offset as usize)?;
+            let shared_type = self.read_u8()?;
+            num_children = self.read_num_children(shared_type)?.0;
+            field_ids_pos = self.pos;
+        } else if is_object {
+            offsets_pos = self.pos + self.field_id_length * num_children;
+        } else {
+            offsets_pos = self.pos;
+        }
+
+        let mut object = Map::new();
+        let mut array = Vec::with_capacity(num_children.min(self.data.len()));
+        for _ in 0..num_children {
+            let name = if is_object {
+                self.seek(field_ids_pos)?;
+                let id = read_be(self.read_bytes(self.field_id_length)?) as usize;
+                field_ids_pos = self.pos;
+                let name = id
+                    .checked_sub(1)
+                    .and_then(|i| self.field_names.get(i))
+                    .ok_or_else(|| Error::protocol(format!("invalid OSON field id: {}", id)))?;
+                Some(name.clone())
+            } else {
+                None
+            };
+
+            self.seek(offsets_pos)?;
+            let mut offset = self.read_offset(node_type)?;
+            if self.primary_flags & FLAG_REL_OFFSET_MODE != 0 {
+                offset = offset.wrapping_add(container_offset);
+            }
+            offsets_pos = self.pos;
+            self.seek(self.tree_seg_pos + offset as usize)?;
+
+            let child = self.decode_node()?;
+            match name {
+                Some(name) => {
+                    object.insert(name, child);
+                }
+                None => array.push(child),
+            }
+        }
+
+        self.depth -= 1;
+        Ok(if is_object {
+            Value::Object(object)
+        } else {
+            Value::Array(array)
+        })
+    }
+
+    /// Read the child count encoded by bits 3-4 of the node type.
+    ///
+    /// Returns `(count, is_shared)`; shared containers carry no count here.
+    fn read_num_children(&mut self, node_type: u8) -> Result<(usize, bool)> {
+        match node_type & 0x18 {
+            0x00 => Ok((self.read_u8()? as usize, false)),
+            0x08 => Ok((self.read_u16()? as usize, false)),
+            0x10 => Ok((self.read_u32()? as usize, false)),
+            _ => Ok((0, true)),
+        }
+    }
+
+    /// Read a tree segment offset (UB4 if bit 5 of the node type is set, else UB2).
+    fn read_offset(&mut self, node_type: u8) -> Result<u32> {
+        if node_type & 0x20 != 0 {
+            self.read_u32()
+        } else {
+            Ok(self.read_u16()? as u32)
+        }
+    }
+
+    fn read_string(&mut self, len: usize) -> Result<Value> {
+        let bytes = self.read_bytes(len)?;
+        Ok(Value::String(String::from_utf8_lossy(bytes).into_owned()))
+    }
+
+    fn read_number(&mut self, len: usize) -> Result<Value> {
+        let text = decode_oracle_number(self.read_bytes(len)?)?;
+        Ok(match text.parse::<Number>() {
+            Ok(n) => Value::Number(n),
+            Err(_) => Value::String(text),
+        })
+    }
+
+    fn has_flag(&self, flag: u16) -> bool {
+        self.primary_flags & flag != 0
+    }
+
+    fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.data.len() {
+            return Err(Error::protocol(format!(
+                "OSON offset {} beyond image of {} bytes",
+                pos,
+                self.data.len()
+            )));
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    fn skip(&mut self, n: usize) -> Result<()> {
+        self.read_bytes(n).map(|_| ())
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::protocol("unexpected end of OSON image"))?;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| Error::protocol("unexpected end of OSON image"))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(read_be(self.read_bytes(1)?) as u8)
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(read_be(self.read_bytes(2)?) as u16)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(read_be(self.read_bytes(4)?))
+    }
+}
+
+/// Read a big-endian unsigned integer of up to 4 bytes.
+fn read_be(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |acc, &b| (acc << 8) | b as u32)
+}
+
+/// JSON has no NaN/Infinity; those become null.
+fn float_value(f: f64) -> Value {
+    Number::from_f64(f).map_or(Value::Null, Value::Number)
+}
+
+fn format_iso(dt: chrono::NaiveDateTime, nanos: u32) -> String {
+    let base = dt.format("%Y-%m-%dT%H:%M:%S").to_string();
+    if nanos == 0 {
+        base
+    } else {
+        format!("{}.{:09}", base, nanos)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Header for a scalar image with a UB2 tree segment size.
+    fn scalar_image(node: &[u8]) -> Vec<u8> {
+        let mut data = vec![0xFF, 0x4A, 0x5A, VERSION_MAX_FNAME_255];
+        data.extend_from_slice(&FLAG_IS_SCALAR.to_be_bytes());
+        data.extend_from_slice(&(node.len() as u16).to_be_bytes());
+        data.extend_from_slice(node);
+        data
+    }
+
+    #[test]
+    fn test_decode_scalars() {
+        assert_eq!(
+            decode_oson(&scalar_image(&[TYPE_NULL])).unwrap(),
+            Value::Null
+        );
+        assert_eq!(
+            decode_oson(&scalar_image(&[TYPE_TRUE])).unwrap(),
+            json!(true)
+        );
+        assert_eq!(
+            decode_oson(&scalar_image(&[0x02, b'h', b'i'])).unwrap(),
+            json!("hi")
+        );
+        // Inline number of length 2: 0xC1 0x2B = 42
+        assert_eq!(
+            decode_oson(&scalar_image(&[0x21, 0xC1, 0x2B])).unwrap(),
+            json!(42)
+        );
+    }
+
+    #[test]
+    fn test_decode_object_and_array() {
+        // {"a": 1, "b": [true, null]}
+        let names = [b"a".as_slice(), b"b".as_slice()];
+        let mut segment = Vec::new();
+        let mut offsets = Vec::new();
+        for name in names {
+            offsets.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+            segment.push(name.len() as u8);
+            segment.extend_from_slice(name);
+        }
+
+        // Tree: object at 0, number at 8, array at 11, true at 17, null at 18
+        let tree = [
+            0x84, 0x02, 0x01, 0x02, 0x00, 0x08, 0x00, 0x0B, // object: 2 ids, 2 offsets
+            0x21, 0xC1, 0x02, // number 1
+            0xC0, 0x02, 0x00, 0x11, 0x00, 0x12, // array: 2 offsets
+            TYPE_TRUE, TYPE_NULL,
+        ];
+
+        let mut data = vec![0xFF, 0x4A, 0x5A, VERSION_MAX_FNAME_255, 0x00, 0x00];
+        data.push(names.len() as u8);
+        data.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+        data.extend_from_slice(&(tree.len() as u16).to_be_bytes());
+        data.extend_from_slice(&0u16.to_be_bytes()); // tiny nodes
+        data.extend_from_slice(&[0x00, 0x00]); // hash ids
+        data.extend_from_slice(&offsets);
+        data.extend_from_slice(&segment);
+        data.extend_from_slice(&tree);
+
+        let value = decode_oson(&data).unwrap();
+        assert_eq!(value, json!({"a": 1, "b": [true, null]}));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode_oson(&[0x00, 0x01, 0x02, 0x01]).is_err());
+        assert!(decode_oson(&[0xFF, 0x4A, 0x5A, 0x09, 0x00, 0x10]).is_err());
+        // Truncated string
+        assert!(decode_oson(&scalar_image(&[0x05, b'a'])).is_err());
+        // Array whose only child points back at itself
+        let mut data = vec![0xFF, 0x4A, 0x5A, VERSION_MAX_FNAME_255, 0x00, 0x00];
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00]);
+        data.extend_from_slice(&[0xC0, 0x01, 0x00, 0x00]);
+        assert!(decode_oson(&data).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Oracle UROWID decoder.
+//!
+//! UROWID values are sent as a byte string whose first byte identifies the
+//! kind of ROWID:
+//! - `1`: physical ROWID, followed by object id (4 bytes), file number
+//!   (2 bytes), block number (4 bytes) and row slot (2 bytes), big-endian
+//! - anything else: logical/foreign ROWID, encoded as-is in base64
+//!
+//! Physical ROWIDs of ROWID columns arrive as separate UB fields and are
+//! handled in response parsing.
+
+use crate::error::{Error, Result};
+use crate::protocol::types::RowId;
+
+/// Marker byte for a physical ROWID inside a UROWID value.
+const UROWID_PHYSICAL: u8 = 1;
+
+/// Length of a physical ROWID inside a UROWID value (marker included).
+const UROWID_PHYSICAL_LEN: usize = 13;
+
+/// Decode an Oracle UROWID value into its canonical text form.
+///
+/// # Errors
+/// Returns `Error::Protocol` if the data is empty or a physical ROWID is truncated.
+pub fn decode_urowid(data: &[u8]) -> Result<RowId> {
+    match data {
+        [] => Err(Error::protocol("UROWID value is empty")),
+        &[UROWID_PHYSICAL, o0, o1, o2, o3, f0, f1, b0, b1, b2, b3, r0, r1, ..] => {
+            Ok(RowId::from_parts(
+                u32::from_be_bytes([o0, o1, o2, o3]),
+                u16::from_be_bytes([f0, f1]),
+                u32::from_be_bytes([b0, b1, b2, b3]),
+                u16::from_be_bytes([r0, r1]),
+            ))
+        }
+        [UROWID_PHYSICAL, ..] => Err(Error::protocol(format!(
+            "physical UROWID must be at least {} bytes, got {}",
+            UROWID_PHYSICAL_LEN,
+            data.len()
+        ))),
+        [_, rest @ ..] => Ok(RowId::from_universal_bytes(rest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_urowid_physical() {
+        // object 73196, file 4, block 151, row 0
+        let mut data = vec![UROWID_PHYSICAL];
+        data.extend_from_slice(&73196u32.to_be_bytes());
+        data.extend_from_slice(&4u16.to_be_bytes());
+        data.extend_from_slice(&151u32.to_be_bytes());
+        data.extend_from_slice(&0u16.to_be_bytes());
+        assert_eq!(decode_urowid(&data).unwrap().as_str(), "AAAR3sAAEAAAACXAAA");
+    }
+
+    #[test]
+    fn test_decode_urowid_logical() {
+        let data = [0x02, 0x04, 0x03, 0x00, 0x12, 0x3C];
+        let rowid = decode_urowid(&data).unwrap();
+        assert!(rowid.is_universal());
+        assert_eq!(rowid.as_str(), "*BAMAEjw");
+    }
+
+    #[test]
+    fn test_decode_urowid_invalid() {
+        assert!(decode_urowid(&[]).is_err());
+        assert!(decode_urowid(&[UROWID_PHYSICAL, 0x00, 0x01]).is_err());
+    }
+}
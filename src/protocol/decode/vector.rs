@@ -0,0 +1,235 @@
+//! Oracle VECTOR type decoder (23ai).
+//!
+//! The vector image has a fixed header followed by the elements:
+//! - byte 0: magic byte (0xDB)
+//! - byte 1: version
+//! - bytes 2-3: flags (big-endian)
+//! - byte 4: element format (FLOAT32, FLOAT64, INT8, BINARY)
+//! - bytes 5-8: number of dimensions (big-endian)
+//! - 8 bytes of norm, when flagged
+//! - sparse vectors: element count (u16) and indices (u32 each)
+//! - elements: BINARY_FLOAT/BINARY_DOUBLE encoding, signed bytes, or packed bits
+
+use crate::error::{Error, Result};
+use crate::protocol::types::{Vector, VectorData};
+
+use super::{decode_binary_double, decode_binary_float};
+
+const VECTOR_MAGIC_BYTE: u8 = 0xDB;
+const VECTOR_VERSION_MAX: u8 = 2;
+
+const VECTOR_FLAG_NORM: u16 = 0x0002;
+const VECTOR_FLAG_SPARSE: u16 = 0x0020;
+
+const VECTOR_FORMAT_FLOAT32: u8 = 2;
+const VECTOR_FORMAT_FLOAT64: u8 = 3;
+const VECTOR_FORMAT_INT8: u8 = 4;
+const VECTOR_FORMAT_BINARY: u8 = 5;
+
+/// Decode an Oracle VECTOR image.
+///
+/// # Errors
+/// Returns `Error::Protocol` for a bad header, an unknown element format or
+/// truncated data.
+pub fn decode_vector(data: &[u8]) -> Result<Vector> {
+    let mut reader = Reader { data, pos: 0 };
+
+    if reader.read_u8()? != VECTOR_MAGIC_BYTE {
+        return Err(Error::protocol("Invalid VECTOR magic byte"));
+    }
+    let version = reader.read_u8()?;
+    if version > VECTOR_VERSION_MAX {
+        return Err(Error::protocol(format!(
+            "Unsupported VECTOR version: {}",
+            version
+        )));
+    }
+    let flags = reader.read_u16()?;
+    let format = reader.read_u8()?;
+    let num_dimensions = reader.read_u32()?;
+
+    if flags & VECTOR_FLAG_NORM != 0 {
+        reader.take(8)?;
+    }
+
+    if flags & VECTOR_FLAG_SPARSE != 0 {
+        let num_elements = reader.read_u16()? as usize;
+        let indices = (0..num_elements)
+            .map(|_| reader.read_u32())
+            .collect::<Result<Vec<_>>>()?;
+        let values = reader.read_elements(format, num_elements)?;
+        return Ok(Vector::Sparse {
+            num_dimensions,
+            indices,
+            values,
+        });
+    }
+
+    // Binary vectors pack 8 dimensions per byte
+    let num_elements = if format == VECTOR_FORMAT_BINARY {
+        num_dimensions as usize / 8
+    } else {
+        num_dimensions as usize
+    };
+    Ok(Vector::Dense(reader.read_elements(format, num_elements)?))
+}
+
+/// Bounds-checked cursor over the vector image.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| Error::protocol("Truncated VECTOR data"))?;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| Error::protocol("Truncated VECTOR data"))?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(u8::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take_array()?))
+    }
+
+    fn read_elements(&mut self, format: u8, count: usize) -> Result<VectorData> {
+        match format {
+            VECTOR_FORMAT_FLOAT32 => Ok(VectorData::Float32(
+                self.take(count.saturating_mul(4))?
+                    .chunks_exact(4)
+                    .map(decode_binary_float)
+                    .collect::<Result<_>>()?,
+            )),
+            VECTOR_FORMAT_FLOAT64 => Ok(VectorData::Float64(
+                self.take(count.saturating_mul(8))?
+                    .chunks_exact(8)
+                    .map(decode_binary_double)
+                    .collect::<Result<_>>()?,
+            )),
+            VECTOR_FORMAT_INT8 => Ok(VectorData::Int8(
+                self.take(count)?.iter().map(|&b| b as i8).collect(),
+            )),
+            VECTOR_FORMAT_BINARY => Ok(VectorData::Binary(self.take(count)?.to_vec())),
+            other => Err(Error::protocol(format!(
+                "Unsupported VECTOR format: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(flags: u16, format: u8, num_dimensions: u32) -> Vec<u8> {
+        let mut data = vec![VECTOR_MAGIC_BYTE, 0];
+        data.extend_from_slice(&flags.to_be_bytes());
+        data.push(format);
+        data.extend_from_slice(&num_dimensions.to_be_bytes());
+        data
+    }
+
+    fn encode_f32(x: f32) -> [u8; 4] {
+        let mut b = x.to_be_bytes();
+        if x >= 0.0 {
+            b[0] |= 0x80;
+        } else {
+            b.iter_mut().for_each(|x| *x = !*x);
+        }
+        b
+    }
+
+    #[test]
+    fn test_decode_float32() {
+        let mut data = header(VECTOR_FLAG_NORM, VECTOR_FORMAT_FLOAT32, 3);
+        data.extend_from_slice(&[0; 8]); // norm
+        for x in [1.5f32, -2.0, 0.0] {
+            data.extend_from_slice(&encode_f32(x));
+        }
+        let v = decode_vector(&data).unwrap();
+        assert_eq!(v, Vector::Dense(VectorData::Float32(vec![1.5, -2.0, 0.0])));
+    }
+
+    #[test]
+    fn test_decode_float64() {
+        let mut data = header(0, VECTOR_FORMAT_FLOAT64, 1);
+        let mut b = 0.25f64.to_be_bytes();
+        b[0] |= 0x80;
+        data.extend_from_slice(&b);
+        let v = decode_vector(&data).unwrap();
+        assert_eq!(v.to_f64_vec(), Some(vec![0.25]));
+    }
+
+    #[test]
+    fn test_decode_int8_and_binary() {
+        let mut data = header(0, VECTOR_FORMAT_INT8, 3);
+        data.extend_from_slice(&[0x01, 0xFF, 0x7F]);
+        let v = decode_vector(&data).unwrap();
+        assert_eq!(v, Vector::Dense(VectorData::Int8(vec![1, -1, 127])));
+
+        let mut data = header(0, VECTOR_FORMAT_BINARY, 16);
+        data.extend_from_slice(&[0xAA, 0x55]);
+        let v = decode_vector(&data).unwrap();
+        assert_eq!(v, Vector::Dense(VectorData::Binary(vec![0xAA, 0x55])));
+        assert_eq!(v.num_dimensions(), 16);
+    }
+
+    #[test]
+    fn test_decode_sparse() {
+        let mut data = header(VECTOR_FLAG_SPARSE, VECTOR_FORMAT_INT8, 10);
+        data.extend_from_slice(&2u16.to_be_bytes());
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.extend_from_slice(&[5, 0xFB]);
+        let v = decode_vector(&data).unwrap();
+        assert_eq!(
+            v,
+            Vector::Sparse {
+                num_dimensions: 10,
+                indices: vec![3, 7],
+                values: VectorData::Int8(vec![5, -5]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(decode_vector(&[]).is_err());
+        assert!(decode_vector(&[0x00, 0x00]).is_err());
+
+        // Truncated elements
+        let mut data = header(0, VECTOR_FORMAT_FLOAT32, 2);
+        data.extend_from_slice(&encode_f32(1.0));
+        assert!(decode_vector(&data).is_err());
+
+        // Unknown format
+        let data = header(0, 9, 0);
+        assert!(decode_vector(&data).is_err());
+
+        // Dimension count far beyond the data
+        let data = header(0, VECTOR_FORMAT_FLOAT64, u32::MAX);
+        assert!(decode_vector(&data).is_err());
+    }
+}
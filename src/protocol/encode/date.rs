@@ -0,0 +1,133 @@
+//! Oracle DATE and TIMESTAMP type encoders.
+//!
+//! See `decode::date` for the 7-byte layout and the TIMESTAMP fraction.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+use crate::error::{Error, Result};
+
+/// Encode a date and time as an Oracle DATE.
+///
+/// Fractional seconds are dropped, as DATE has no sub-second precision.
+///
+/// # Errors
+/// Returns `Error::TypeConversion` if the year is outside the DATE range
+/// (4712 BC to 9999 AD).
+pub fn encode_oracle_date(value: &NaiveDateTime) -> Result<[u8; 7]> {
+    let year = value.year();
+    if !(-4712..=9999).contains(&year) {
+        return Err(Error::type_conversion(format!(
+            "year {} is outside the Oracle DATE range",
+            year
+        )));
+    }
+    Ok([
+        (year.div_euclid(100) + 100) as u8,
+        (year.rem_euclid(100) + 100) as u8,
+        value.month() as u8,
+        value.day() as u8,
+        value.hour() as u8 + 1,
+        value.minute() as u8 + 1,
+        value.second() as u8 + 1,
+    ])
+}
+
+/// Encode a date and time as an Oracle TIMESTAMP.
+///
+/// Whole seconds are encoded as for DATE, followed by the nanoseconds when
+/// there is a fraction. A leap second (`chrono` nanoseconds past
+/// 999,999,999) is stored as the last nanosecond of its second.
+///
+/// # Errors
+/// Returns `Error::TypeConversion` if the year is outside the TIMESTAMP range
+/// (4712 BC to 9999 AD).
+pub fn encode_oracle_timestamp(value: &NaiveDateTime) -> Result<Vec<u8>> {
+    let mut bytes = encode_oracle_date(value)?.to_vec();
+    let nanos = value.nanosecond().min(999_999_999);
+    if nanos > 0 {
+        bytes.extend_from_slice(&nanos.to_be_bytes());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::decode::{decode_oracle_date, decode_oracle_timestamp};
+    use chrono::{Duration, NaiveDate};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn datetime(y: i32, m: u32, d: u32, h: u32, mi: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encode_date() {
+        let value = datetime(2024, 10, 21, 12, 36, 5);
+        assert_eq!(
+            encode_oracle_date(&value).unwrap(),
+            [0x78, 0x7C, 0x0A, 0x15, 0x0D, 0x25, 0x06]
+        );
+    }
+
+    #[test]
+    fn test_encode_date_round_trip() {
+        for value in [
+            datetime(1999, 6, 15, 12, 30, 45),
+            datetime(2000, 1, 1, 0, 0, 0),
+            datetime(9999, 12, 31, 23, 59, 59),
+            datetime(-4712, 1, 1, 0, 0, 0),
+        ] {
+            let bytes = encode_oracle_date(&value).unwrap();
+            assert_eq!(decode_oracle_date(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_encode_date_out_of_range() {
+        assert!(encode_oracle_date(&datetime(10000, 1, 1, 0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_encode_timestamp() {
+        let value = datetime(2024, 10, 21, 12, 36, 5);
+        assert_eq!(
+            encode_oracle_timestamp(&value).unwrap(),
+            encode_oracle_date(&value).unwrap()
+        );
+        let value = value.with_nanosecond(123_456_789).unwrap();
+        assert_eq!(
+            encode_oracle_timestamp(&value).unwrap(),
+            [0x78, 0x7C, 0x0A, 0x15, 0x0D, 0x25, 0x06, 0x07, 0x5B, 0xCD, 0x15]
+        );
+        // Leap second
+        let leap = value.with_nanosecond(1_500_000_000).unwrap();
+        assert_eq!(
+            decode_oracle_timestamp(&encode_oracle_timestamp(&leap).unwrap()).unwrap(),
+            value.with_nanosecond(999_999_999).unwrap()
+        );
+        assert!(encode_oracle_timestamp(&datetime(-4713, 12, 31, 0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_encode_round_trip_random() {
+        let first = datetime(-4712, 1, 1, 0, 0, 0);
+        let span = (datetime(9999, 12, 31, 23, 59, 59) - first).num_seconds();
+        let mut rng = StdRng::seed_from_u64(0xDA7E);
+        for _ in 0..10_000 {
+            let value = first + Duration::seconds(rng.gen_range(0..=span));
+            let bytes = encode_oracle_date(&value).unwrap();
+            assert_eq!(decode_oracle_date(&bytes).unwrap(), value);
+
+            let value = value
+                .with_nanosecond(rng.gen_range(0..1_000_000_000))
+                .unwrap();
+            let bytes = encode_oracle_timestamp(&value).unwrap();
+            assert_eq!(decode_oracle_timestamp(&bytes).unwrap(), value);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Data type encoders for bind values sent to the server.
+//!
+//! The counterparts of the decoders in `decode`. Encoders take client
+//! values and return an error for values Oracle cannot represent.
+//!
+//! | Oracle Type | Module |
+//! |-------------|--------|
+//! | NUMBER      | `number` |
+//! | DATE, TIMESTAMP | `date` |
+
+mod date;
+mod number;
+
+pub use date::{encode_oracle_date, encode_oracle_timestamp};
+#[cfg(feature = "rust_decimal")]
+pub use number::encode_oracle_decimal;
+pub use number::{encode_oracle_float, encode_oracle_integer, encode_oracle_number};
@@ -0,0 +1,335 @@
+//! Oracle NUMBER type encoder.
+//!
+//! See `decode::number` for the format: an exponent byte followed by
+//! base-100 mantissa digits, inverted for negative numbers.
+
+use crate::error::{Error, Result};
+
+/// Most significant decimal digits a NUMBER can hold.
+const MAX_DIGITS: usize = 40;
+
+/// Encode a decimal number given as text (for example "-123.45" or
+/// "1.5e-3") as an Oracle NUMBER.
+///
+/// # Errors
+/// Returns `Error::TypeConversion` if the text is not a decimal number, has
+/// more than 40 significant digits, or is outside the NUMBER range.
+pub fn encode_oracle_number(text: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::type_conversion(format!("invalid NUMBER value: {:?}", text));
+
+    let trimmed = text.trim();
+    let (is_negative, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i32>().map_err(|_| invalid())?),
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part
+            .bytes()
+            .chain(frac_part.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    // Significant digits and the position of the decimal point relative to
+    // the first of them
+    let mut digits: Vec<u8> = int_part
+        .bytes()
+        .chain(frac_part.bytes())
+        .map(|b| b - b'0')
+        .collect();
+    let leading_zeros = digits.iter().take_while(|&&d| d == 0).count();
+    digits.drain(..leading_zeros);
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    if digits.is_empty() {
+        return Ok(vec![0x80]);
+    }
+    let decimal_point_index = (int_part.len() as i32)
+        .checked_add(exponent)
+        .and_then(|i| i.checked_sub(leading_zeros as i32))
+        .ok_or_else(invalid)?;
+
+    if digits.len() > MAX_DIGITS {
+        return Err(Error::type_conversion(format!(
+            "NUMBER value {:?} has more than {} significant digits",
+            text, MAX_DIGITS
+        )));
+    }
+    if !(-129..=126).contains(&decimal_point_index) {
+        return Err(Error::type_conversion(format!(
+            "NUMBER value {:?} is out of range",
+            text
+        )));
+    }
+
+    Ok(encode_digits(is_negative, digits, decimal_point_index))
+}
+
+/// Encode an integer as an Oracle NUMBER.
+///
+/// Every `i64` fits, so unlike `encode_oracle_number` this cannot fail.
+pub fn encode_oracle_integer(value: i64) -> Vec<u8> {
+    encode_unsigned(value < 0, u128::from(value.unsigned_abs()), 0)
+}
+
+/// Encode a floating point number as an Oracle NUMBER.
+///
+/// The shortest decimal that reads back as the same `f64` is encoded, so
+/// `0.1` is stored as 0.1 rather than its binary approximation.
+///
+/// # Errors
+/// Returns `Error::TypeConversion` for NaN and infinities, and for values
+/// outside the NUMBER range (magnitudes from 1e-130 to below 1e126).
+pub fn encode_oracle_float(value: f64) -> Result<Vec<u8>> {
+    if !value.is_finite() {
+        return Err(Error::type_conversion(format!(
+            "NUMBER cannot hold {}",
+            value
+        )));
+    }
+    encode_oracle_number(&format!("{:e}", value))
+}
+
+/// Encode a `Decimal` as an Oracle NUMBER.
+///
+/// A `Decimal` has at most 29 significant digits and a scale of at most 28,
+/// so every value fits.
+#[cfg(feature = "rust_decimal")]
+pub fn encode_oracle_decimal(value: &rust_decimal::Decimal) -> Vec<u8> {
+    encode_unsigned(
+        value.is_sign_negative(),
+        value.mantissa().unsigned_abs(),
+        value.scale(),
+    )
+}
+
+/// Encode `magnitude * 10^-scale`, for magnitudes of at most 39 digits.
+fn encode_unsigned(is_negative: bool, magnitude: u128, scale: u32) -> Vec<u8> {
+    if magnitude == 0 {
+        return vec![0x80];
+    }
+    let mut digits: Vec<u8> = magnitude.to_string().bytes().map(|b| b - b'0').collect();
+    let decimal_point_index = digits.len() as i32 - scale as i32;
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    encode_digits(is_negative, digits, decimal_point_index)
+}
+
+/// Encode significant digits (no leading or trailing zeros, at most 40 of
+/// them) with the decimal point `decimal_point_index` digits after the first,
+/// in the NUMBER range.
+fn encode_digits(is_negative: bool, mut digits: Vec<u8>, mut decimal_point_index: i32) -> Vec<u8> {
+    // Align the digits to base-100 pairs
+    if decimal_point_index.rem_euclid(2) == 1 {
+        digits.insert(0, 0);
+        decimal_point_index += 1;
+    }
+    if digits.len() % 2 == 1 {
+        digits.push(0);
+    }
+
+    let exponent_byte = (decimal_point_index / 2 + 192) as u8;
+    let mut bytes = Vec::with_capacity(digits.len() / 2 + 2);
+    bytes.push(if is_negative {
+        !exponent_byte
+    } else {
+        exponent_byte
+    });
+    for pair in digits.chunks_exact(2) {
+        let value = pair[0] * 10 + pair[1];
+        bytes.push(if is_negative { 101 - value } else { value + 1 });
+    }
+    // Negative numbers shorter than the maximum length end with 102
+    if is_negative && bytes.len() <= MAX_DIGITS / 2 {
+        bytes.push(102);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::decode::{decode_oracle_integer, decode_oracle_number};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const ITERATIONS: usize = 10_000;
+
+    #[test]
+    fn test_encode_number_known_values() {
+        assert_eq!(encode_oracle_number("0").unwrap(), vec![0x80]);
+        assert_eq!(encode_oracle_number("-0.000").unwrap(), vec![0x80]);
+        assert_eq!(encode_oracle_number("1").unwrap(), vec![0xC1, 0x02]);
+        assert_eq!(encode_oracle_number("100").unwrap(), vec![0xC2, 0x02]);
+        assert_eq!(
+            encode_oracle_number("123.45").unwrap(),
+            vec![0xC2, 0x02, 0x18, 0x2E]
+        );
+        assert_eq!(encode_oracle_number("0.01").unwrap(), vec![0xC0, 0x02]);
+        assert_eq!(encode_oracle_number("-5").unwrap(), vec![0x3E, 0x60, 0x66]);
+    }
+
+    #[test]
+    fn test_encode_number_round_trip() {
+        for text in [
+            "1",
+            "-1",
+            "42",
+            "-123.45",
+            "0.5",
+            "0.001",
+            "-0.0009",
+            "9999999999999999999",
+            "12345678901234567890123456789012345678",
+            "-1234567890123456789012345678901234567890",
+        ] {
+            let bytes = encode_oracle_number(text).unwrap();
+            assert_eq!(decode_oracle_number(&bytes).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_encode_number_exponent() {
+        // Largest and smallest magnitudes
+        assert_eq!(encode_oracle_number("1e125").unwrap(), vec![0xFF, 0x0B]);
+        assert_eq!(encode_oracle_number("1e-130").unwrap(), vec![0x80, 0x02]);
+        assert_eq!(
+            encode_oracle_number("1.5e3").unwrap(),
+            encode_oracle_number("1500").unwrap()
+        );
+        assert_eq!(
+            encode_oracle_number("25E-4").unwrap(),
+            encode_oracle_number("0.0025").unwrap()
+        );
+        assert_eq!(
+            encode_oracle_number("+7").unwrap(),
+            encode_oracle_number("7").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_number_invalid() {
+        for text in ["", "-", ".", "abc", "1.2.3", "1e", "0x10", "1 000", "NaN"] {
+            assert!(encode_oracle_number(text).is_err(), "{:?}", text);
+        }
+        assert!(encode_oracle_number("1e126").is_err());
+        assert!(encode_oracle_number("1e-131").is_err());
+        assert!(encode_oracle_number(&"1".repeat(41)).is_err());
+    }
+
+    #[test]
+    fn test_encode_integer() {
+        for value in [0, 1, -1, 100, -5, 42, i64::MAX, i64::MIN, i64::MIN + 1] {
+            let bytes = encode_oracle_integer(value);
+            assert_eq!(bytes, encode_oracle_number(&value.to_string()).unwrap());
+            assert_eq!(decode_oracle_integer(&bytes), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_encode_integer_round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(0x1A7E6E2);
+        for _ in 0..ITERATIONS {
+            // Spread the magnitudes over every digit count
+            let value = rng.gen::<i64>() >> rng.gen_range(0..64);
+            let bytes = encode_oracle_integer(value);
+            assert_eq!(decode_oracle_integer(&bytes), Some(value), "{}", value);
+            assert_eq!(decode_oracle_number(&bytes).unwrap(), value.to_string());
+        }
+    }
+
+    #[test]
+    fn test_encode_float() {
+        assert_eq!(
+            encode_oracle_float(0.1).unwrap(),
+            encode_oracle_number("0.1").unwrap()
+        );
+        assert_eq!(encode_oracle_float(-0.0).unwrap(), vec![0x80]);
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e126, 1e-131] {
+            assert!(encode_oracle_float(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_encode_float_round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(0xF10A7);
+        for _ in 0..ITERATIONS {
+            let value = rng.gen_range(-1.0..1.0) * 10f64.powi(rng.gen_range(-120..120));
+            let bytes = encode_oracle_float(value).unwrap();
+            let decoded: f64 = decode_oracle_number(&bytes).unwrap().parse().unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_encode_number_round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(0x0D161);
+        for _ in 0..ITERATIONS {
+            // Up to 40 significant digits, without leading or trailing zeros
+            let len = rng.gen_range(1..=MAX_DIGITS);
+            let mut digits: Vec<u8> = (0..len).map(|_| rng.gen_range(0..10)).collect();
+            digits[0] = rng.gen_range(1..10);
+            digits[len - 1] = rng.gen_range(1..10);
+            let digits: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+            let exponent = rng.gen_range(-130..=125);
+            let sign = if rng.gen() { "-" } else { "" };
+
+            let text = format!("{}{}.{}e{}", sign, &digits[..1], &digits[1..], exponent);
+            let bytes = encode_oracle_number(&text).unwrap();
+            let decoded = decode_oracle_number(&bytes).unwrap();
+            assert_eq!(
+                encode_oracle_number(&decoded).unwrap(),
+                bytes,
+                "{} decoded as {}",
+                text,
+                decoded
+            );
+            let expected: f64 = text.parse().unwrap();
+            let actual: f64 = decoded.parse().unwrap();
+            assert!(
+                (actual - expected).abs() <= expected.abs() * 1e-15,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_encode_decimal_round_trip_random() {
+        use crate::protocol::decode::decode_oracle_number_decimal;
+        use rust_decimal::Decimal;
+
+        for value in [
+            Decimal::ZERO,
+            Decimal::MAX,
+            Decimal::MIN,
+            Decimal::new(1, 28),
+        ] {
+            let bytes = encode_oracle_decimal(&value);
+            assert_eq!(bytes, encode_oracle_number(&value.to_string()).unwrap());
+            assert_eq!(decode_oracle_number_decimal(&bytes).unwrap(), Some(value));
+        }
+
+        let mut rng = StdRng::seed_from_u64(0xDEC1);
+        for _ in 0..ITERATIONS {
+            let mantissa = rng.gen::<i128>() >> rng.gen_range(32..128);
+            let value = Decimal::from_i128_with_scale(mantissa, rng.gen_range(0..=28));
+            let bytes = encode_oracle_decimal(&value);
+            assert_eq!(
+                decode_oracle_number_decimal(&bytes).unwrap(),
+                Some(value),
+                "{}",
+                value
+            );
+        }
+    }
+}
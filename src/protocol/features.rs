@@ -0,0 +1,201 @@
+//! Features of the connected server.
+//!
+//! What a server can do is spread over the negotiated protocol version, the
+//! TTC field version it advertises, flags sent with ACCEPT and its compile
+//! and runtime capabilities. `ServerFeatures` answers the questions the
+//! driver and its users ask of them in one place.
+
+use crate::protocol::constants::*;
+use crate::protocol::packet::Capabilities;
+use crate::protocol::version::VersionTuple;
+
+/// Features supported by the connected server.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(conn: &oracle_thin_rs::Connection) {
+/// let features = conn.server_features();
+/// if features.supports_boolean() {
+///     // Bind booleans natively instead of as 0/1 numbers
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerFeatures {
+    protocol_version: u16,
+    ttc_field_version: u8,
+    server_ttc_field_version: u8,
+    server_version: Option<VersionTuple>,
+    fast_auth: bool,
+    pipelining: bool,
+    end_of_response: bool,
+    max_string_size: u32,
+}
+
+impl ServerFeatures {
+    /// Get the features from the negotiated capabilities, without a server
+    /// version.
+    pub(crate) fn from_caps(caps: &Capabilities) -> Self {
+        Self {
+            protocol_version: caps.protocol_version,
+            ttc_field_version: caps.ttc_field_version,
+            server_ttc_field_version: caps.server_ttc_field_version,
+            server_version: None,
+            fast_auth: caps.supports_fast_auth,
+            pipelining: caps.supports_pipelining,
+            end_of_response: caps.supports_end_of_response,
+            max_string_size: caps.max_string_size,
+        }
+    }
+
+    /// Set the server version reported at authentication.
+    pub(crate) fn with_server_version(mut self, version: Option<VersionTuple>) -> Self {
+        self.server_version = version;
+        self
+    }
+
+    /// Get the negotiated TNS protocol version.
+    pub fn protocol_version(&self) -> u16 {
+        self.protocol_version
+    }
+
+    /// Get the negotiated TTC field version, the lower of the client's and
+    /// the server's.
+    pub fn ttc_field_version(&self) -> u8 {
+        self.ttc_field_version
+    }
+
+    /// Get the TTC field version advertised by the server.
+    pub fn server_ttc_field_version(&self) -> u8 {
+        self.server_ttc_field_version
+    }
+
+    /// Get the server version as (major, minor, update, release, patch), if
+    /// the server reported one.
+    pub fn server_version(&self) -> Option<VersionTuple> {
+        self.server_version
+    }
+
+    /// Whether the server has a native BOOLEAN SQL type (23ai+).
+    ///
+    /// Without it, booleans are bound as the numbers 0 and 1.
+    pub fn supports_boolean(&self) -> bool {
+        self.server_ttc_field_version >= TNS_CCAP_FIELD_VERSION_23_1
+    }
+
+    /// Whether the server has the VECTOR type (23.4+).
+    pub fn supports_vector(&self) -> bool {
+        self.server_ttc_field_version >= TNS_CCAP_FIELD_VERSION_23_4
+    }
+
+    /// Whether the server has the native JSON type (21c+).
+    pub fn supports_json(&self) -> bool {
+        self.server_ttc_field_version >= TNS_CCAP_FIELD_VERSION_21_1
+    }
+
+    /// Whether the server accepted fast authentication (23ai+), which
+    /// combines the protocol and data type exchanges with the first
+    /// authentication phase.
+    pub fn supports_fast_auth(&self) -> bool {
+        self.fast_auth
+    }
+
+    /// Whether the server marks the end of each response.
+    pub fn supports_end_of_response(&self) -> bool {
+        self.end_of_response
+    }
+
+    /// Whether pipelined requests are sent in one go (23ai+).
+    ///
+    /// This needs both pipelining and end-of-response markers, to tell the
+    /// responses apart; otherwise pipelines run one operation at a time.
+    pub fn supports_pipelining(&self) -> bool {
+        self.pipelining && self.end_of_response
+    }
+
+    /// Whether VARCHAR2 and RAW values can be up to 32767 bytes
+    /// (`MAX_STRING_SIZE = EXTENDED`).
+    pub fn supports_extended_strings(&self) -> bool {
+        self.max_string_size > TNS_MAX_STRING_SIZE
+    }
+
+    /// Get the largest VARCHAR2/RAW value, in bytes, the server accepts in
+    /// SQL.
+    pub fn max_string_size(&self) -> u32 {
+        self.max_string_size
+    }
+}
+
+impl Capabilities {
+    /// Get the features these capabilities allow.
+    pub fn features(&self) -> ServerFeatures {
+        ServerFeatures::from_caps(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(server_ttc_field_version: u8) -> Capabilities {
+        let mut caps = Capabilities::new();
+        let mut server_caps = vec![0u8; TNS_CCAP_MAX];
+        server_caps[TNS_CCAP_FIELD_VERSION] = server_ttc_field_version;
+        caps.adjust_for_server_caps(&server_caps, &[]);
+        caps
+    }
+
+    #[test]
+    fn test_field_version_features() {
+        let features = caps(TNS_CCAP_FIELD_VERSION_19_1_EXT_1).features();
+        assert_eq!(
+            features.ttc_field_version(),
+            TNS_CCAP_FIELD_VERSION_19_1_EXT_1
+        );
+        assert!(!features.supports_json());
+        assert!(!features.supports_boolean());
+        assert!(!features.supports_vector());
+
+        let features = caps(TNS_CCAP_FIELD_VERSION_23_1).features();
+        assert!(features.supports_json());
+        assert!(features.supports_boolean());
+        assert!(!features.supports_vector());
+
+        let features = caps(TNS_CCAP_FIELD_VERSION_23_4).features();
+        assert!(features.supports_boolean());
+        assert!(features.supports_vector());
+    }
+
+    #[test]
+    fn test_protocol_features() {
+        let mut caps = caps(TNS_CCAP_FIELD_VERSION_23_4);
+        caps.adjust_for_protocol(
+            TNS_VERSION_MIN_END_OF_RESPONSE,
+            0,
+            TNS_ACCEPT_FLAG_FAST_AUTH,
+        );
+        let features = caps.features();
+        assert_eq!(features.protocol_version(), TNS_VERSION_MIN_END_OF_RESPONSE);
+        assert!(features.supports_fast_auth());
+        assert!(!features.supports_end_of_response());
+
+        // Pipelining is only used with end-of-response markers
+        caps.supports_pipelining = true;
+        assert!(!caps.features().supports_pipelining());
+        caps.supports_end_of_response = true;
+        assert!(caps.features().supports_pipelining());
+    }
+
+    #[test]
+    fn test_string_size_and_version() {
+        let mut caps = Capabilities::new();
+        assert!(!caps.features().supports_extended_strings());
+        caps.max_string_size = TNS_MAX_STRING_SIZE_EXTENDED;
+        let features = caps.features().with_server_version(Some((23, 5, 0, 24, 7)));
+        assert!(features.supports_extended_strings());
+        assert_eq!(features.max_string_size(), TNS_MAX_STRING_SIZE_EXTENDED);
+        assert_eq!(features.server_version(), Some((23, 5, 0, 24, 7)));
+        assert_eq!(caps.features().server_version(), None);
+    }
+}
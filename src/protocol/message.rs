@@ -5,6 +5,7 @@
 //! enabling single-allocation serialization.
 
 use crate::error::Result;
+use crate::protocol::buffer::{LengthPrefixedBytes, Ub2, Ub4, Ub8, WireValue};
 use crate::protocol::constants::*;
 
 // ============================================================================
@@ -43,6 +44,55 @@ pub trait DataMessage: Message {
     }
 }
 
+/// A DATA message preceded by a piggyback message in the same packet.
+///
+/// Piggybacks (e.g. closing cursors) get no response of their own; the
+/// server processes them before the function call that follows.
+pub struct Piggybacked<'a, P: ?Sized, M> {
+    /// Piggyback message written first.
+    pub piggyback: &'a P,
+    /// Function message that carries the piggyback.
+    pub message: &'a M,
+}
+
+impl<P: Message + ?Sized, M: DataMessage> Message for Piggybacked<'_, P, M> {
+    fn wire_size(&self) -> usize {
+        self.piggyback.wire_size() + self.message.wire_size()
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        self.piggyback.write_to(buf)?;
+        self.message.write_to(buf)
+    }
+}
+
+impl<P: Message + ?Sized, M: DataMessage> DataMessage for Piggybacked<'_, P, M> {
+    fn data_flags(&self) -> u16 {
+        self.message.data_flags()
+    }
+}
+
+/// Several piggyback messages are written one after another.
+impl<P: Message> Message for [P] {
+    fn wire_size(&self) -> usize {
+        self.iter().map(Message::wire_size).sum()
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        self.iter().try_for_each(|message| message.write_to(buf))
+    }
+}
+
+impl<P: Message + ?Sized> Message for Box<P> {
+    fn wire_size(&self) -> usize {
+        (**self).wire_size()
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        (**self).write_to(buf)
+    }
+}
+
 // ============================================================================
 // Size Calculation Helpers
 // ============================================================================
@@ -55,10 +105,10 @@ pub const fn ub1_wire_size(_val: u8) -> usize {
 
 /// Calculate wire size for a UB2 value in TNS variable-length format.
 ///
-/// Format: length byte + value bytes (little-endian)
+/// Format: length byte + value bytes (big-endian)
 /// - 0: 1 byte (0x00)
 /// - 1-255: 2 bytes (0x01, val)
-/// - 256-65535: 3 bytes (0x02, lo, hi)
+/// - 256-65535: 3 bytes (0x02, hi, lo)
 #[inline]
 pub const fn ub2_wire_size(val: u16) -> usize {
     match val {
@@ -70,7 +120,7 @@ pub const fn ub2_wire_size(val: u16) -> usize {
 
 /// Calculate wire size for a UB4 value in TNS variable-length format.
 ///
-/// Format: length byte + value bytes (little-endian)
+/// Format: length byte + value bytes (big-endian)
 /// - 0: 1 byte
 /// - 1-255: 2 bytes
 /// - 256-65535: 3 bytes
@@ -120,8 +170,13 @@ pub const fn bytes_with_length_wire_size(len: usize) -> usize {
         // Long format: 0xFE marker + chunked data
         // Each chunk: ub4(chunk_len) + data
         // Final: ub4(0) terminator
-        let num_chunks = len.div_ceil(65536);
-        1 + (num_chunks * 5) + len + 1 // FE + chunk headers + data + terminator
+        let full_chunks = len / TNS_CHUNK_SIZE;
+        let last_chunk = len % TNS_CHUNK_SIZE;
+        let mut headers = full_chunks * ub4_wire_size(TNS_CHUNK_SIZE as u32);
+        if last_chunk > 0 {
+            headers += ub4_wire_size(last_chunk as u32);
+        }
+        1 + headers + len + 1 // FE + chunk headers + data + terminator
     }
 }
 
@@ -153,158 +208,92 @@ pub fn key_value_wire_size(key: &str, value: &str, flags: u32) -> usize {
 // Write Helpers
 // ============================================================================
 
-/// Extension trait for writing TNS protocol data to Vec<u8>.
+/// Extension trait for writing TNS protocol data.
+///
+/// Implemented for `Vec<u8>` and `WriteBuffer`. Only raw bytes and typed
+/// wire values are written by each implementation; every other method
+/// encodes through those, so both write the same bytes.
 pub trait WriteExt {
-    /// Write a single byte.
-    fn write_u8(&mut self, val: u8);
-
-    /// Write a big-endian u16.
-    fn write_u16_be(&mut self, val: u16);
-
-    /// Write a little-endian u16.
-    fn write_u16_le(&mut self, val: u16);
-
-    /// Write a big-endian u32.
-    fn write_u32_be(&mut self, val: u32);
-
-    /// Write a big-endian u64.
-    fn write_u64_be(&mut self, val: u64);
-
     /// Write raw bytes.
     fn write_bytes(&mut self, bytes: &[u8]);
 
-    /// Write zeros.
-    fn write_zeros(&mut self, count: usize);
-
-    /// Write a UB1 value.
-    fn write_ub1(&mut self, val: u8);
-
-    /// Write a UB2 value in TNS variable-length format.
-    fn write_ub2(&mut self, val: u16);
+    /// Write a typed wire value (`Ub2`, `Ub4`, `Ub8`, `LengthPrefixedBytes`).
+    fn write_value(&mut self, value: impl WireValue);
 
-    /// Write a UB4 value in TNS variable-length format.
-    fn write_ub4(&mut self, val: u32);
-
-    /// Write a UB8 value in TNS variable-length format.
-    fn write_ub8(&mut self, val: u64);
-
-    /// Write bytes with length prefix.
-    fn write_bytes_with_length(&mut self, bytes: &[u8]);
-
-    /// Write string with length prefix.
-    fn write_str_with_length(&mut self, s: &str);
-
-    /// Write a key-value pair for auth messages.
-    fn write_key_value(&mut self, key: &str, value: &str, flags: u32);
-}
-
-impl WriteExt for Vec<u8> {
+    /// Write a single byte.
     #[inline]
     fn write_u8(&mut self, val: u8) {
-        self.push(val);
+        self.write_bytes(&[val]);
     }
 
+    /// Write a big-endian u16.
     #[inline]
     fn write_u16_be(&mut self, val: u16) {
-        self.extend_from_slice(&val.to_be_bytes());
+        self.write_bytes(&val.to_be_bytes());
     }
 
+    /// Write a little-endian u16.
     #[inline]
     fn write_u16_le(&mut self, val: u16) {
-        self.extend_from_slice(&val.to_le_bytes());
+        self.write_bytes(&val.to_le_bytes());
     }
 
+    /// Write a big-endian u32.
     #[inline]
     fn write_u32_be(&mut self, val: u32) {
-        self.extend_from_slice(&val.to_be_bytes());
+        self.write_bytes(&val.to_be_bytes());
     }
 
+    /// Write a big-endian u64.
     #[inline]
     fn write_u64_be(&mut self, val: u64) {
-        self.extend_from_slice(&val.to_be_bytes());
-    }
-
-    #[inline]
-    fn write_bytes(&mut self, bytes: &[u8]) {
-        self.extend_from_slice(bytes);
+        self.write_bytes(&val.to_be_bytes());
     }
 
-    #[inline]
+    /// Write zeros.
     fn write_zeros(&mut self, count: usize) {
-        self.resize(self.len() + count, 0);
+        for _ in 0..count {
+            self.write_u8(0);
+        }
     }
 
+    /// Write a UB1 value.
     #[inline]
     fn write_ub1(&mut self, val: u8) {
-        self.push(val);
+        self.write_u8(val);
     }
 
+    /// Write a UB2 value in TNS variable-length format.
+    #[inline]
     fn write_ub2(&mut self, val: u16) {
-        if val == 0 {
-            self.push(0);
-        } else if val <= 0xFF {
-            self.push(1);
-            self.push(val as u8);
-        } else {
-            // Big-endian: high byte first
-            self.push(2);
-            self.push((val >> 8) as u8);
-            self.push(val as u8);
-        }
+        self.write_value(Ub2(val));
     }
 
+    /// Write a UB4 value in TNS variable-length format.
+    #[inline]
     fn write_ub4(&mut self, val: u32) {
-        if val == 0 {
-            self.push(0);
-        } else {
-            // UB4 uses big-endian byte order with length prefix
-            let bytes = val.to_be_bytes();
-            let leading = (val.leading_zeros() / 8) as usize;
-            let len = 4 - leading;
-            self.push(len as u8);
-            self.extend_from_slice(&bytes[leading..]);
-        }
+        self.write_value(Ub4(val));
     }
 
+    /// Write a UB8 value in TNS variable-length format.
+    #[inline]
     fn write_ub8(&mut self, val: u64) {
-        if val == 0 {
-            self.push(0);
-        } else {
-            // UB8 uses big-endian byte order with length prefix
-            let bytes = val.to_be_bytes();
-            let leading = (val.leading_zeros() / 8) as usize;
-            let len = 8 - leading;
-            self.push(len as u8);
-            self.extend_from_slice(&bytes[leading..]);
-        }
+        self.write_value(Ub8(val));
     }
 
+    /// Write bytes with length prefix.
+    #[inline]
     fn write_bytes_with_length(&mut self, bytes: &[u8]) {
-        let len = bytes.len();
-        if len == 0 {
-            self.push(0);
-        } else if len < TNS_LONG_LENGTH_INDICATOR as usize {
-            self.push(len as u8);
-            self.extend_from_slice(bytes);
-        } else {
-            // Long format: chunked
-            self.push(TNS_LONG_LENGTH_INDICATOR);
-            let mut offset = 0;
-            while offset < len {
-                let chunk_len = std::cmp::min(len - offset, 65536);
-                self.write_ub4(chunk_len as u32);
-                self.extend_from_slice(&bytes[offset..offset + chunk_len]);
-                offset += chunk_len;
-            }
-            self.write_ub4(0); // End marker
-        }
+        self.write_value(LengthPrefixedBytes(bytes));
     }
 
+    /// Write string with length prefix.
     #[inline]
     fn write_str_with_length(&mut self, s: &str) {
         self.write_bytes_with_length(s.as_bytes());
     }
 
+    /// Write a key-value pair for auth messages.
     fn write_key_value(&mut self, key: &str, value: &str, flags: u32) {
         let key_bytes = key.as_bytes();
         let value_bytes = value.as_bytes();
@@ -319,6 +308,28 @@ impl WriteExt for Vec<u8> {
     }
 }
 
+impl WriteExt for Vec<u8> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    #[inline]
+    fn write_value(&mut self, value: impl WireValue) {
+        value.encode(self);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, val: u8) {
+        self.push(val);
+    }
+
+    #[inline]
+    fn write_zeros(&mut self, count: usize) {
+        self.resize(self.len() + count, 0);
+    }
+}
+
 // ============================================================================
 // Packet Header Writing
 // ============================================================================
@@ -482,7 +493,7 @@ mod tests {
         }
 
         // Test bytes_with_length
-        for len in [0, 1, 100, 252, 253] {
+        for len in [0, 1, 100, 252, 253, 254, 1000, 32767, 32768, 65536, 200_000] {
             buf.clear();
             let data = vec![0u8; len];
             buf.write_bytes_with_length(&data);
@@ -495,6 +506,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_bytes_with_length_chunks() {
+        let data = vec![7u8; TNS_CHUNK_SIZE + 10];
+        let mut buf = Vec::new();
+        buf.write_bytes_with_length(&data);
+
+        // 0xFE, ub4(32767) + chunk, ub4(10) + chunk, ub4(0)
+        assert_eq!(&buf[..4], &[TNS_LONG_LENGTH_INDICATOR, 2, 0x7F, 0xFF]);
+        let second = 4 + TNS_CHUNK_SIZE;
+        assert_eq!(&buf[second..second + 2], &[1, 10]);
+        assert_eq!(buf.last(), Some(&0));
+        assert_eq!(buf.len(), 4 + TNS_CHUNK_SIZE + 2 + 10 + 1);
+    }
+
     #[test]
     fn test_write_key_value() {
         let mut buf = Vec::new();
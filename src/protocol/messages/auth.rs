@@ -2,6 +2,7 @@
 
 use super::data_types;
 use crate::error::Result;
+use crate::protocol::buffer::Ub4;
 use crate::protocol::constants::*;
 use crate::protocol::message::{
     bytes_with_length_wire_size, key_value_wire_size, ub4_wire_size, DataMessage, Message, WriteExt,
@@ -28,6 +29,15 @@ pub struct AuthPhaseOneMessage<'a> {
     pub pid: &'a str,
     /// Session ID (OS username)
     pub sid: &'a str,
+    /// Administrative privilege flags (`TNS_AUTH_MODE_SYSDBA` and others),
+    /// or 0 for an ordinary session
+    pub privilege: u32,
+}
+
+impl AuthPhaseOneMessage<'_> {
+    fn auth_mode(&self) -> u32 {
+        TNS_AUTH_MODE_LOGON | self.privilege
+    }
 }
 
 impl Message for AuthPhaseOneMessage<'_> {
@@ -41,7 +51,7 @@ impl Message for AuthPhaseOneMessage<'_> {
         size += 1; // sequence number
         size += 1; // user presence flag
         size += ub4_wire_size(user_bytes_len as u32);
-        size += ub4_wire_size(TNS_AUTH_MODE_LOGON);
+        size += ub4_wire_size(self.auth_mode());
         size += 1; // pointer to key/value pairs
         size += ub4_wire_size(5); // num_pairs
         size += 1; // authivl pointer
@@ -70,8 +80,8 @@ impl Message for AuthPhaseOneMessage<'_> {
         buf.write_u8(1); // sequence number
 
         buf.write_u8(if has_user { 1 } else { 0 });
-        buf.write_ub4(user_bytes.len() as u32);
-        buf.write_ub4(TNS_AUTH_MODE_LOGON);
+        buf.write_value(Ub4::try_from(user_bytes.len())?);
+        buf.write_ub4(self.auth_mode());
 
         buf.write_u8(1); // pointer to key/value pairs
         buf.write_ub4(5); // num_pairs
@@ -101,30 +111,74 @@ impl DataMessage for AuthPhaseOneMessage<'_> {}
 
 /// Authentication phase 2 message.
 ///
-/// Sends encrypted password and session key to complete authentication.
+/// Sends the proof of identity (password verifier or access token) to
+/// complete authentication.
 pub struct AuthPhaseTwoMessage<'a> {
     /// Username
     pub username: &'a str,
-    /// Encrypted session key (hex string)
-    pub session_key: &'a str,
-    /// Speedy key (for 12c verifier, hex string)
-    pub speedy_key: Option<&'a str>,
-    /// Encrypted password (hex string)
-    pub encoded_password: &'a str,
-    /// Timezone ALTER SESSION statement
-    pub timezone_stmt: &'a str,
+    /// Password verifier or access token
+    pub proof: AuthProof<'a>,
+    /// User to open the session as, when `username` connects as its proxy
+    pub proxy_user: Option<&'a str>,
+    /// ALTER SESSION statement run once the session is created (time zone
+    /// and session settings)
+    pub alter_session: &'a str,
+    /// Administrative privilege flags, as sent in phase 1
+    pub privilege: u32,
+}
+
+/// Proof of identity sent in authentication phase 2.
+pub enum AuthProof<'a> {
+    /// O5LOGON password verifier, answering the phase 1 challenge.
+    Password {
+        /// Encrypted session key (hex string)
+        session_key: &'a str,
+        /// Speedy key (for 12c verifier, hex string)
+        speedy_key: Option<&'a str>,
+        /// Encrypted password (hex string)
+        encoded_password: &'a str,
+    },
+    /// Database access token (OCI IAM or OAuth2); phase 1 is skipped.
+    Token {
+        /// Token text
+        token: &'a str,
+        /// Request header and its base64 signature, for proof-of-possession
+        /// tokens
+        signed_header: Option<(&'a str, &'a str)>,
+    },
+}
+
+impl AuthPhaseTwoMessage<'_> {
+    fn auth_mode(&self) -> u32 {
+        let proof_mode = match self.proof {
+            AuthProof::Password { .. } => TNS_AUTH_MODE_WITH_PASSWORD,
+            AuthProof::Token { .. } => TNS_AUTH_MODE_IAM_TOKEN,
+        };
+        TNS_AUTH_MODE_LOGON | proof_mode | self.privilege
+    }
+
+    /// Sequence number: token authentication is the first function call.
+    fn sequence_number(&self) -> u8 {
+        match self.proof {
+            AuthProof::Password { .. } => 2,
+            AuthProof::Token { .. } => 1,
+        }
+    }
+
+    fn num_pairs(&self) -> u32 {
+        let proof_pairs = match self.proof {
+            AuthProof::Password { speedy_key, .. } => 2 + speedy_key.is_some() as u32,
+            AuthProof::Token { signed_header, .. } => 1 + 2 * signed_header.is_some() as u32,
+        };
+        // Client charset, driver name, client version and ALTER SESSION
+        4 + proof_pairs + self.proxy_user.is_some() as u32
+    }
 }
 
 impl Message for AuthPhaseTwoMessage<'_> {
     fn wire_size(&self) -> usize {
         let has_user = !self.username.is_empty();
         let user_bytes_len = self.username.len();
-        let auth_mode = TNS_AUTH_MODE_LOGON | TNS_AUTH_MODE_WITH_PASSWORD;
-
-        let mut num_pairs = 6u32;
-        if self.speedy_key.is_some() {
-            num_pairs += 1;
-        }
 
         let mut size = 0;
         size += 1; // message type
@@ -132,9 +186,9 @@ impl Message for AuthPhaseTwoMessage<'_> {
         size += 1; // sequence number
         size += 1; // user presence flag
         size += ub4_wire_size(user_bytes_len as u32);
-        size += ub4_wire_size(auth_mode);
+        size += ub4_wire_size(self.auth_mode());
         size += 1; // pointer to key/value pairs
-        size += ub4_wire_size(num_pairs);
+        size += ub4_wire_size(self.num_pairs());
         size += 1; // authivl pointer
         size += 1; // authovln pointer
 
@@ -143,15 +197,37 @@ impl Message for AuthPhaseTwoMessage<'_> {
         }
 
         // Key-value pairs
-        size += key_value_wire_size("AUTH_SESSKEY", self.session_key, 1);
-        if let Some(sk) = self.speedy_key {
-            size += key_value_wire_size("AUTH_PBKDF2_SPEEDY_KEY", sk, 0);
+        match self.proof {
+            AuthProof::Password {
+                session_key,
+                speedy_key,
+                encoded_password,
+            } => {
+                size += key_value_wire_size("AUTH_SESSKEY", session_key, 1);
+                if let Some(sk) = speedy_key {
+                    size += key_value_wire_size("AUTH_PBKDF2_SPEEDY_KEY", sk, 0);
+                }
+                size += key_value_wire_size("AUTH_PASSWORD", encoded_password, 0);
+            }
+            AuthProof::Token { token, .. } => {
+                size += key_value_wire_size("AUTH_TOKEN", token, 0);
+            }
         }
-        size += key_value_wire_size("AUTH_PASSWORD", self.encoded_password, 0);
         size += key_value_wire_size("SESSION_CLIENT_CHARSET", "873", 0);
         size += key_value_wire_size("SESSION_CLIENT_DRIVER_NAME", "oracle-thin-rs : 0.1.0", 0);
         size += key_value_wire_size("SESSION_CLIENT_VERSION", "185599488", 0);
-        size += key_value_wire_size("AUTH_ALTER_SESSION", self.timezone_stmt, 1);
+        size += key_value_wire_size("AUTH_ALTER_SESSION", self.alter_session, 1);
+        if let Some(proxy_user) = self.proxy_user {
+            size += key_value_wire_size("PROXY_CLIENT_NAME", proxy_user, 0);
+        }
+        if let AuthProof::Token {
+            signed_header: Some((header, signature)),
+            ..
+        } = self.proof
+        {
+            size += key_value_wire_size("AUTH_HEADER", header, 0);
+            size += key_value_wire_size("AUTH_SIGNATURE", signature, 0);
+        }
 
         size
     }
@@ -159,23 +235,17 @@ impl Message for AuthPhaseTwoMessage<'_> {
     fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
         let has_user = !self.username.is_empty();
         let user_bytes = self.username.as_bytes();
-        let auth_mode = TNS_AUTH_MODE_LOGON | TNS_AUTH_MODE_WITH_PASSWORD;
-
-        let mut num_pairs = 6u32;
-        if self.speedy_key.is_some() {
-            num_pairs += 1;
-        }
 
         buf.write_u8(TNS_MSG_TYPE_FUNCTION);
         buf.write_u8(TNS_FUNC_AUTH_PHASE_TWO);
-        buf.write_u8(2); // sequence number
+        buf.write_u8(self.sequence_number());
 
         buf.write_u8(if has_user { 1 } else { 0 });
-        buf.write_ub4(user_bytes.len() as u32);
-        buf.write_ub4(auth_mode);
+        buf.write_value(Ub4::try_from(user_bytes.len())?);
+        buf.write_ub4(self.auth_mode());
 
         buf.write_u8(1); // pointer to key/value pairs
-        buf.write_ub4(num_pairs);
+        buf.write_ub4(self.num_pairs());
         buf.write_u8(1); // authivl pointer
         buf.write_u8(1); // authovln pointer
 
@@ -184,15 +254,37 @@ impl Message for AuthPhaseTwoMessage<'_> {
         }
 
         // Key-value pairs
-        buf.write_key_value("AUTH_SESSKEY", self.session_key, 1);
-        if let Some(sk) = self.speedy_key {
-            buf.write_key_value("AUTH_PBKDF2_SPEEDY_KEY", sk, 0);
+        match self.proof {
+            AuthProof::Password {
+                session_key,
+                speedy_key,
+                encoded_password,
+            } => {
+                buf.write_key_value("AUTH_SESSKEY", session_key, 1);
+                if let Some(sk) = speedy_key {
+                    buf.write_key_value("AUTH_PBKDF2_SPEEDY_KEY", sk, 0);
+                }
+                buf.write_key_value("AUTH_PASSWORD", encoded_password, 0);
+            }
+            AuthProof::Token { token, .. } => {
+                buf.write_key_value("AUTH_TOKEN", token, 0);
+            }
         }
-        buf.write_key_value("AUTH_PASSWORD", self.encoded_password, 0);
         buf.write_key_value("SESSION_CLIENT_CHARSET", "873", 0);
         buf.write_key_value("SESSION_CLIENT_DRIVER_NAME", "oracle-thin-rs : 0.1.0", 0);
         buf.write_key_value("SESSION_CLIENT_VERSION", "185599488", 0);
-        buf.write_key_value("AUTH_ALTER_SESSION", self.timezone_stmt, 1);
+        buf.write_key_value("AUTH_ALTER_SESSION", self.alter_session, 1);
+        if let Some(proxy_user) = self.proxy_user {
+            buf.write_key_value("PROXY_CLIENT_NAME", proxy_user, 0);
+        }
+        if let AuthProof::Token {
+            signed_header: Some((header, signature)),
+            ..
+        } = self.proof
+        {
+            buf.write_key_value("AUTH_HEADER", header, 0);
+            buf.write_key_value("AUTH_SIGNATURE", signature, 0);
+        }
 
         Ok(())
     }
@@ -311,6 +403,7 @@ mod tests {
             machine: "localhost",
             pid: "12345",
             sid: "testuser",
+            privilege: 0,
         };
 
         let mut buf = Vec::with_capacity(msg.wire_size());
@@ -319,14 +412,40 @@ mod tests {
         assert_eq!(buf.len(), msg.wire_size());
     }
 
+    #[test]
+    fn test_auth_phase_one_privilege() {
+        let msg = AuthPhaseOneMessage {
+            username: "sys",
+            terminal: "unknown",
+            program: "oracle-thin-rs",
+            machine: "localhost",
+            pid: "12345",
+            sid: "testuser",
+            privilege: TNS_AUTH_MODE_SYSDBA,
+        };
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        let mut expected = Vec::new();
+        expected.write_ub4(3);
+        expected.write_ub4(TNS_AUTH_MODE_LOGON | TNS_AUTH_MODE_SYSDBA);
+        assert_eq!(buf[4..4 + expected.len()], expected);
+    }
+
     #[test]
     fn test_auth_phase_two_wire_size() {
         let msg = AuthPhaseTwoMessage {
             username: "test_user",
-            session_key: "ABCD1234",
-            speedy_key: Some("EFGH5678"),
-            encoded_password: "ENCRYPTED_PASSWORD_HEX",
-            timezone_stmt: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+            proof: AuthProof::Password {
+                session_key: "ABCD1234",
+                speedy_key: Some("EFGH5678"),
+                encoded_password: "ENCRYPTED_PASSWORD_HEX",
+            },
+            proxy_user: None,
+            alter_session: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+            privilege: 0,
         };
 
         let mut buf = Vec::with_capacity(msg.wire_size());
@@ -339,10 +458,14 @@ mod tests {
     fn test_auth_phase_two_no_speedy_key() {
         let msg = AuthPhaseTwoMessage {
             username: "test_user",
-            session_key: "ABCD1234",
-            speedy_key: None,
-            encoded_password: "ENCRYPTED_PASSWORD_HEX",
-            timezone_stmt: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+            proof: AuthProof::Password {
+                session_key: "ABCD1234",
+                speedy_key: None,
+                encoded_password: "ENCRYPTED_PASSWORD_HEX",
+            },
+            proxy_user: None,
+            alter_session: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+            privilege: 0,
         };
 
         let mut buf = Vec::with_capacity(msg.wire_size());
@@ -351,6 +474,74 @@ mod tests {
         assert_eq!(buf.len(), msg.wire_size());
     }
 
+    #[test]
+    fn test_auth_phase_two_proxy_user() {
+        let msg = AuthPhaseTwoMessage {
+            username: "app_owner",
+            proof: AuthProof::Password {
+                session_key: "ABCD1234",
+                speedy_key: None,
+                encoded_password: "ENCRYPTED_PASSWORD_HEX",
+            },
+            proxy_user: Some("end_user"),
+            alter_session: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+            privilege: 0,
+        };
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        // Seven pairs without a speedy key, the last naming the session user
+        let mut expected = Vec::new();
+        expected.write_ub4(TNS_AUTH_MODE_LOGON | TNS_AUTH_MODE_WITH_PASSWORD);
+        expected.write_u8(1);
+        expected.write_ub4(7);
+        let header_len = 4 + ub4_wire_size("app_owner".len() as u32);
+        assert_eq!(buf[header_len..header_len + expected.len()], expected);
+
+        let mut pair = Vec::new();
+        pair.write_key_value("PROXY_CLIENT_NAME", "end_user", 0);
+        assert!(buf.ends_with(&pair));
+    }
+
+    #[test]
+    fn test_auth_phase_two_token() {
+        for signed_header in [None, Some(("date: Thu, 01 Jan 2026 00:00:00 GMT", "c2ln"))] {
+            let msg = AuthPhaseTwoMessage {
+                username: "",
+                proof: AuthProof::Token {
+                    token: "eyJhbGciOi.eyJzdWIi.c2ln",
+                    signed_header,
+                },
+                proxy_user: None,
+                alter_session: "ALTER SESSION SET TIME_ZONE='+00:00'\0",
+                privilege: 0,
+            };
+
+            let mut buf = Vec::with_capacity(msg.wire_size());
+            msg.write_to(&mut buf).unwrap();
+            assert_eq!(buf.len(), msg.wire_size());
+
+            // Function header, then no user, the IAM token mode and the pairs
+            assert_eq!(
+                buf[..4],
+                [TNS_MSG_TYPE_FUNCTION, TNS_FUNC_AUTH_PHASE_TWO, 1, 0]
+            );
+            let mut expected = Vec::new();
+            expected.write_ub4(0);
+            expected.write_ub4(TNS_AUTH_MODE_LOGON | TNS_AUTH_MODE_IAM_TOKEN);
+            expected.write_u8(1);
+            expected.write_ub4(if signed_header.is_some() { 7 } else { 5 });
+            assert_eq!(buf[4..4 + expected.len()], expected);
+
+            let text = String::from_utf8_lossy(&buf);
+            assert!(text.contains("AUTH_TOKEN"));
+            assert!(!text.contains("AUTH_PASSWORD"));
+            assert_eq!(text.contains("AUTH_SIGNATURE"), signed_header.is_some());
+        }
+    }
+
     #[test]
     fn test_fast_auth_wire_size() {
         let compile_caps = vec![0u8; 64];
@@ -367,6 +558,7 @@ mod tests {
                 machine: "localhost",
                 pid: "12345",
                 sid: "testuser",
+                privilege: 0,
             },
         };
 
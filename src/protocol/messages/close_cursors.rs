@@ -0,0 +1,98 @@
+//! Close cursors piggyback message.
+
+use crate::error::Result;
+use crate::protocol::buffer::Ub4;
+use crate::protocol::constants::*;
+use crate::protocol::message::{ub4_wire_size, Message, WriteExt};
+
+/// Piggyback message releasing server cursors.
+///
+/// The server does not respond to piggybacks, so this message is never sent
+/// on its own: it is prepended to the next function message (see
+/// `Piggybacked`) and processed by the server before that call.
+pub struct CloseCursorsMessage {
+    /// Cursor IDs to close.
+    pub cursor_ids: Vec<u32>,
+}
+
+impl CloseCursorsMessage {
+    /// Create a new close cursors message.
+    pub fn new(cursor_ids: Vec<u32>) -> Self {
+        Self { cursor_ids }
+    }
+}
+
+impl Message for CloseCursorsMessage {
+    fn wire_size(&self) -> usize {
+        let mut size = 0;
+
+        // Piggyback header
+        size += 1; // message type (TNS_MSG_TYPE_PIGGYBACK)
+        size += 1; // function code (TNS_FUNC_CLOSE_CURSORS)
+        size += 1; // sequence number
+
+        // Cursor ID array
+        size += 1; // pointer
+        size += ub4_wire_size(self.cursor_ids.len() as u32);
+        size += self
+            .cursor_ids
+            .iter()
+            .map(|&id| ub4_wire_size(id))
+            .sum::<usize>();
+
+        size
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        // Piggyback header
+        buf.write_u8(TNS_MSG_TYPE_PIGGYBACK);
+        buf.write_u8(TNS_FUNC_CLOSE_CURSORS);
+        buf.write_u8(1); // sequence number
+
+        // Cursor ID array
+        buf.write_u8(1); // pointer
+        buf.write_value(Ub4::try_from(self.cursor_ids.len())?);
+        for &cursor_id in &self.cursor_ids {
+            buf.write_ub4(cursor_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_cursors_message_wire_size() {
+        let msg = CloseCursorsMessage::new(vec![3, 300, 70000]);
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        assert_eq!(buf.len(), msg.wire_size());
+    }
+
+    #[test]
+    fn test_close_cursors_message_content() {
+        let msg = CloseCursorsMessage::new(vec![42]);
+
+        let mut buf = Vec::new();
+        msg.write_to(&mut buf).unwrap();
+
+        assert_eq!(
+            buf,
+            vec![
+                TNS_MSG_TYPE_PIGGYBACK,
+                TNS_FUNC_CLOSE_CURSORS,
+                1,  // sequence
+                1,  // pointer
+                1,  // count (ub4 length byte)
+                1,  // count value
+                1,  // cursor id (ub4 length byte)
+                42, // cursor id value
+            ]
+        );
+    }
+}
@@ -17,6 +17,8 @@ pub struct ConnectMessage<'a> {
     pub connect_string: &'a [u8],
     /// SDU size to request
     pub sdu: u32,
+    /// Whether to offer out-of-band breaks
+    pub oob: bool,
 }
 
 impl ConnectMessage<'_> {
@@ -66,11 +68,21 @@ impl Message for ConnectMessage<'_> {
         buf.write_u16_be(TNS_VERSION_DESIRED);
         buf.write_u16_be(TNS_VERSION_MINIMUM);
 
-        // Service options - don't include OOB for async
-        buf.write_u16_be(TNS_GSO_DONT_CARE);
+        // Service options and connect flags, offering OOB breaks if wanted
+        let (service_options, connect_flags_2) = if self.oob {
+            (
+                TNS_GSO_DONT_CARE | TNS_GSO_CAN_RECV_ATTENTION,
+                TNS_CHECK_OOB,
+            )
+        } else {
+            (TNS_GSO_DONT_CARE, 0)
+        };
+        buf.write_u16_be(service_options);
 
-        buf.write_u16_be(self.sdu as u16); // SDU
-        buf.write_u16_be(self.sdu as u16); // TDU
+        // 16-bit SDU/TDU, superseded by the 32-bit fields below
+        let sdu = self.sdu.min(u16::MAX as u32) as u16;
+        buf.write_u16_be(sdu); // SDU
+        buf.write_u16_be(sdu); // TDU
         buf.write_u16_be(TNS_PROTOCOL_CHARACTERISTICS);
         buf.write_u16_be(0); // Line turnaround
         buf.write_u16_be(1); // Value of 1
@@ -90,9 +102,9 @@ impl Message for ConnectMessage<'_> {
         buf.write_u32_be(self.sdu);
         buf.write_u32_be(self.sdu);
 
-        // Connect flags (no OOB for async)
+        // Connect flags
         buf.write_u32_be(0); // connect_flags_1
-        buf.write_u32_be(0); // connect_flags_2
+        buf.write_u32_be(connect_flags_2);
 
         // Connect data (if fits)
         if self.connect_data_in_packet() {
@@ -162,6 +174,13 @@ impl MarkerMessage {
             marker_type: TNS_MARKER_TYPE_RESET,
         }
     }
+
+    /// Create a BREAK marker, interrupting the call in progress.
+    pub fn brk() -> Self {
+        Self {
+            marker_type: TNS_MARKER_TYPE_BREAK,
+        }
+    }
 }
 
 impl Message for MarkerMessage {
@@ -191,12 +210,48 @@ mod tests {
         let msg = ConnectMessage {
             connect_string: connect_str,
             sdu: 8192,
+            oob: false,
         };
 
         let mut buf = Vec::with_capacity(msg.wire_size());
         msg.write_to(&mut buf).unwrap();
 
         assert_eq!(buf.len(), msg.wire_size());
+        assert_eq!(&buf[4..6], TNS_GSO_DONT_CARE.to_be_bytes());
+        assert_eq!(&buf[62..66], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_connect_message_large_sdu() {
+        let msg = ConnectMessage {
+            connect_string: b"(DESCRIPTION=)",
+            sdu: TNS_SDU_MAX,
+            oob: false,
+        };
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        // The 16-bit fields saturate; the 32-bit ones carry the SDU
+        assert_eq!(&buf[6..10], [0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(&buf[50..54], TNS_SDU_MAX.to_be_bytes());
+        assert_eq!(&buf[54..58], TNS_SDU_MAX.to_be_bytes());
+    }
+
+    #[test]
+    fn test_connect_message_oob() {
+        let msg = ConnectMessage {
+            connect_string: b"(DESCRIPTION=)",
+            sdu: 8192,
+            oob: true,
+        };
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        let service_options = TNS_GSO_DONT_CARE | TNS_GSO_CAN_RECV_ATTENTION;
+        assert_eq!(&buf[4..6], service_options.to_be_bytes());
+        assert_eq!(&buf[62..66], TNS_CHECK_OOB.to_be_bytes());
     }
 
     #[test]
@@ -0,0 +1,157 @@
+//! End-to-end attributes piggyback message.
+
+use crate::error::Result;
+use crate::protocol::buffer::Ub4;
+use crate::protocol::constants::*;
+use crate::protocol::message::{bytes_with_length_wire_size, ub4_wire_size, Message, WriteExt};
+
+/// Piggyback message setting the session attributes shown in `V$SESSION`
+/// (MODULE, ACTION, CLIENT_INFO, CLIENT_IDENTIFIER).
+///
+/// Attributes left as `None` are not changed; an empty string clears one.
+/// Like `CloseCursorsMessage`, this is never sent on its own but prepended
+/// to the next function message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndToEndMessage {
+    /// End user of the session (`CLIENT_IDENTIFIER`).
+    pub client_identifier: Option<String>,
+    /// Module being executed (`MODULE`).
+    pub module: Option<String>,
+    /// Action within the module (`ACTION`).
+    pub action: Option<String>,
+    /// Free-form client information (`CLIENT_INFO`).
+    pub client_info: Option<String>,
+}
+
+impl EndToEndMessage {
+    /// Check if no attribute is changed.
+    pub fn is_empty(&self) -> bool {
+        self.client_identifier.is_none()
+            && self.module.is_none()
+            && self.action.is_none()
+            && self.client_info.is_none()
+    }
+
+    fn flags(&self) -> u32 {
+        [
+            (&self.client_identifier, TNS_END_TO_END_CLIENT_IDENTIFIER),
+            (&self.module, TNS_END_TO_END_MODULE),
+            (&self.action, TNS_END_TO_END_ACTION),
+            (&self.client_info, TNS_END_TO_END_CLIENT_INFO),
+        ]
+        .into_iter()
+        .filter(|(value, _)| value.is_some())
+        .fold(0, |flags, (_, flag)| flags | flag)
+    }
+
+    /// Attribute headers in wire order; `None` entries are the unused
+    /// fields between them.
+    fn headers(&self) -> [Option<&Option<String>>; 9] {
+        [
+            Some(&self.client_identifier),
+            Some(&self.module),
+            Some(&self.action),
+            None, // cideci
+            None, // cidcct
+            Some(&self.client_info),
+            None, // cidecs
+            None, // cidecsl
+            None, // dbop
+        ]
+    }
+
+    /// Values written after the headers, in wire order.
+    fn values(&self) -> impl Iterator<Item = &str> {
+        [
+            &self.client_identifier,
+            &self.module,
+            &self.action,
+            &self.client_info,
+        ]
+        .into_iter()
+        .filter_map(|value| value.as_deref())
+        .filter(|value| !value.is_empty())
+    }
+}
+
+impl Message for EndToEndMessage {
+    fn wire_size(&self) -> usize {
+        let mut size = 3; // piggyback header
+        size += 1 + 1; // cidnam, cidser pointers
+        size += ub4_wire_size(self.flags());
+
+        for header in self.headers() {
+            let len = header.and_then(Option::as_ref).map_or(0, String::len);
+            size += 1 + ub4_wire_size(len as u32);
+        }
+        size += self
+            .values()
+            .map(|value| bytes_with_length_wire_size(value.len()))
+            .sum::<usize>();
+
+        size
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        // Piggyback header
+        buf.write_u8(TNS_MSG_TYPE_PIGGYBACK);
+        buf.write_u8(TNS_FUNC_SET_END_TO_END_ATTR);
+        buf.write_u8(1); // sequence number
+
+        buf.write_u8(0); // cidnam pointer
+        buf.write_u8(0); // cidser pointer
+        buf.write_ub4(self.flags());
+
+        // Pointer and length of each attribute
+        for header in self.headers() {
+            match header.and_then(Option::as_ref) {
+                Some(value) => {
+                    buf.write_u8(1);
+                    buf.write_value(Ub4::try_from(value.len())?);
+                }
+                None => {
+                    buf.write_u8(0);
+                    buf.write_ub4(0);
+                }
+            }
+        }
+
+        for value in self.values() {
+            buf.write_bytes_with_length(value.as_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_to_end_message_content() {
+        let msg = EndToEndMessage {
+            module: Some("app".to_string()),
+            client_info: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(!msg.is_empty());
+        assert!(EndToEndMessage::default().is_empty());
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        let mut expected = vec![TNS_MSG_TYPE_PIGGYBACK, TNS_FUNC_SET_END_TO_END_ATTR, 1];
+        expected.extend_from_slice(&[0, 0]); // cidnam, cidser
+        expected.extend_from_slice(&[2, 0x01, 0x08]); // flags (module, client info)
+        expected.extend_from_slice(&[0, 0]); // client identifier
+        expected.extend_from_slice(&[1, 1, 3]); // module
+        expected.extend_from_slice(&[0, 0]); // action
+        expected.extend_from_slice(&[0, 0, 0, 0]); // cideci, cidcct
+        expected.extend_from_slice(&[1, 0]); // client info (cleared)
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // cidecs, cidecsl, dbop
+        expected.extend_from_slice(b"\x03app");
+        assert_eq!(buf, expected);
+    }
+}
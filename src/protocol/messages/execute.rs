@@ -1,10 +1,16 @@
 //! Execute message for SQL statement execution.
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::protocol::bind::{BindInfo, BindParams};
+use crate::protocol::buffer::Ub4;
 use crate::protocol::constants::*;
 use crate::protocol::message::{
     bytes_with_length_wire_size, ub4_wire_size, DataMessage, Message, WriteExt,
 };
+use crate::protocol::statement::Statement;
+
+/// Largest SQL text, in bytes, the server accepts in an execute message.
+pub const MAX_SQL_LENGTH: usize = TNS_MAX_LONG_LENGTH as usize;
 
 /// Field version constant for 12.2 extended features.
 const TNS_CCAP_FIELD_VERSION_12_2_EXT1: u8 = 9;
@@ -19,10 +25,27 @@ pub struct ExecuteMessage<'a> {
     pub cursor_id: u32,
     /// Whether this is a query (SELECT).
     pub is_query: bool,
+    /// Whether this is an anonymous PL/SQL block.
+    pub is_plsql: bool,
+    /// Whether this is a DDL statement, parsed on every execution.
+    pub is_ddl: bool,
     /// Number of rows to prefetch.
     pub prefetch_rows: u32,
+    /// Largest LONG/LONG RAW value returned per column.
+    pub max_long_size: u32,
     /// TTC field version from capabilities.
     pub ttc_field_version: u8,
+    /// Bind values, one row per execution.
+    pub binds: Option<&'a BindParams>,
+    /// Report per-row errors instead of stopping at the first failing row.
+    pub batch_errors: bool,
+    /// Commit the transaction after executing.
+    pub commit: bool,
+    /// Only parse and describe the statement, without executing it.
+    pub parse_only: bool,
+    /// Types the columns of an executed query are fetched as, one per
+    /// column; sent in place of bind values, which the cursor keeps.
+    pub defines: &'a [BindInfo],
 }
 
 impl<'a> ExecuteMessage<'a> {
@@ -32,40 +55,187 @@ impl<'a> ExecuteMessage<'a> {
             sql_bytes: sql.as_bytes(),
             cursor_id: 0,
             is_query: true,
+            is_plsql: false,
+            is_ddl: false,
             prefetch_rows,
+            max_long_size: TNS_MAX_LONG_LENGTH,
+            ttc_field_version,
+            binds: None,
+            batch_errors: false,
+            commit: false,
+            parse_only: false,
+            defines: &[],
+        }
+    }
+
+    /// Create a new execute message for a classified statement.
+    ///
+    /// Rows are only prefetched for queries.
+    pub fn new_statement(stmt: &'a Statement, prefetch_rows: u32, ttc_field_version: u8) -> Self {
+        Self {
+            sql_bytes: stmt.sql().as_bytes(),
+            cursor_id: 0,
+            is_query: stmt.is_query(),
+            is_plsql: stmt.is_plsql(),
+            is_ddl: stmt.is_ddl(),
+            prefetch_rows: if stmt.is_query() { prefetch_rows } else { 0 },
+            max_long_size: TNS_MAX_LONG_LENGTH,
             ttc_field_version,
+            binds: None,
+            batch_errors: false,
+            commit: false,
+            parse_only: false,
+            defines: &[],
         }
     }
 
+    /// Create a message that parses a statement and describes its select
+    /// list without executing it.
+    pub fn new_describe(stmt: &'a Statement, ttc_field_version: u8) -> Self {
+        Self {
+            parse_only: true,
+            ..Self::new_statement(stmt, 0, ttc_field_version)
+        }
+    }
+
+    /// Create a message defining the columns of an executed query, then
+    /// executing it again to fetch the first rows in the defined types.
+    ///
+    /// The cursor keeps the bind values it was executed with.
+    pub fn new_define(
+        stmt: &'a Statement,
+        cursor_id: u32,
+        defines: &'a [BindInfo],
+        prefetch_rows: u32,
+        ttc_field_version: u8,
+    ) -> Self {
+        Self {
+            cursor_id,
+            defines,
+            ..Self::new_statement(stmt, prefetch_rows, ttc_field_version)
+        }
+    }
+
+    /// Create a message fetching the first rows of a cursor opened by the
+    /// server, such as an implicit result set.
+    ///
+    /// The cursor has no SQL of its own, so it is fetched without being
+    /// parsed or executed.
+    pub fn new_cursor_fetch(cursor_id: u32, fetch_rows: u32, ttc_field_version: u8) -> Self {
+        Self {
+            sql_bytes: &[],
+            cursor_id,
+            is_query: true,
+            is_plsql: false,
+            is_ddl: false,
+            prefetch_rows: fetch_rows,
+            max_long_size: TNS_MAX_LONG_LENGTH,
+            ttc_field_version,
+            binds: None,
+            batch_errors: false,
+            commit: false,
+            parse_only: false,
+            defines: &[],
+        }
+    }
+
+    /// Check that SQL text fits in an execute message.
+    ///
+    /// Text longer than one packet is split across packets and written in
+    /// length-prefixed chunks; only the server's overall limit applies.
+    pub fn check_sql_length(sql: &str) -> Result<()> {
+        if sql.len() > MAX_SQL_LENGTH {
+            return Err(Error::StatementTooLarge {
+                length: sql.len(),
+                max: MAX_SQL_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
     /// Calculate the options flags for this execution.
-    fn calc_options(&self) -> u32 {
+    pub(crate) fn calc_options(&self) -> u32 {
         let mut options: u32 = 0;
 
-        // For new cursor, always parse
-        if self.cursor_id == 0 {
+        // Parse new cursors, and DDL on every execution since DDL runs
+        // when it is parsed
+        if self.cursor_id == 0 || self.is_ddl {
             options |= TNS_EXEC_OPTION_PARSE;
         }
 
-        // For queries, add execute and fetch
-        if self.is_query {
-            options |= TNS_EXEC_OPTION_EXECUTE;
-            if self.prefetch_rows > 0 {
+        if self.parse_only {
+            // Describe queries; other statements are only parsed
+            if self.is_query {
+                options |= TNS_EXEC_OPTION_DESCRIBE;
+            }
+        } else {
+            if !self.sql_bytes.is_empty() {
+                options |= TNS_EXEC_OPTION_EXECUTE;
+            }
+
+            // For queries, also fetch the first rows
+            if self.is_query && self.prefetch_rows > 0 {
                 options |= TNS_EXEC_OPTION_FETCH;
             }
         }
 
-        // Not PL/SQL
-        options |= TNS_EXEC_OPTION_NOT_PLSQL;
+        if !self.defines.is_empty() {
+            options |= TNS_EXEC_OPTION_DEFINE;
+        } else if self.num_binds() > 0 {
+            options |= TNS_EXEC_OPTION_BIND;
+        }
+
+        // SQL with PL/SQL declarations (WITH FUNCTION) is still not a PL/SQL block
+        if !self.is_plsql {
+            options |= TNS_EXEC_OPTION_NOT_PLSQL;
+        } else if self.num_binds() > 0 {
+            options |= TNS_EXEC_OPTION_PLSQL_BIND;
+        }
+
+        if self.batch_errors {
+            options |= TNS_EXEC_OPTION_BATCH_ERRORS;
+        }
+        if self.commit {
+            options |= TNS_EXEC_OPTION_COMMIT;
+        }
 
         options
     }
 
+    /// Number of bind positions sent: none when defining.
+    fn num_binds(&self) -> u32 {
+        if !self.defines.is_empty() {
+            return 0;
+        }
+        self.binds.map_or(0, |b| b.num_binds() as u32)
+    }
+
+    /// Number of executions (al8i4[1]); queries are executed by the fetch.
+    ///
+    /// Statements with binds are executed once per row of values.
+    fn execution_count(&self) -> u32 {
+        if self.is_query {
+            0
+        } else {
+            self.binds.map_or(1, |b| b.num_rows().max(1) as u32)
+        }
+    }
+
+    /// Rows to prefetch for queries, executions for other statements.
+    fn num_iters(&self) -> u32 {
+        if self.is_query {
+            self.prefetch_rows
+        } else {
+            self.execution_count()
+        }
+    }
+
     /// Calculate exec_flags (al8i4[9]).
     fn calc_exec_flags(&self) -> u32 {
         let mut exec_flags: u32 = 0;
 
-        // For queries with SQL, set implicit resultset flag
-        if self.is_query && !self.sql_bytes.is_empty() {
+        // Statements with SQL may return implicit result sets
+        if !self.sql_bytes.is_empty() {
             exec_flags |= TNS_EXEC_FLAGS_IMPLICIT_RESULTSET;
         }
 
@@ -103,12 +273,12 @@ impl Message for ExecuteMessage<'_> {
 
         // Prefetch settings
         size += ub4_wire_size(0); // prefetch buffer size
-        size += ub4_wire_size(self.prefetch_rows); // prefetch rows
-        size += ub4_wire_size(TNS_MAX_LONG_LENGTH); // max long size
+        size += ub4_wire_size(self.num_iters()); // prefetch rows / executions
+        size += ub4_wire_size(self.max_long_size); // max long size
 
-        // Bind pointers (no binds in this implementation)
+        // Bind pointers
         size += 1; // binds pointer
-        size += ub4_wire_size(0); // num binds
+        size += ub4_wire_size(self.num_binds()); // num binds
 
         // More pointers
         size += 1; // al8app
@@ -117,9 +287,9 @@ impl Message for ExecuteMessage<'_> {
         size += 1; // al8kv
         size += 1; // al8kvl
 
-        // Define pointers (no defines for initial query)
+        // Define pointers
         size += 1; // al8doac pointer
-        size += ub4_wire_size(0); // num defines
+        size += ub4_wire_size(self.defines.len() as u32); // num defines
 
         // Registration and more pointers
         size += ub4_wire_size(0); // registration id
@@ -159,8 +329,8 @@ impl Message for ExecuteMessage<'_> {
         // al8i4 array (13 elements)
         // [0] parse flag
         size += ub4_wire_size(if is_new_cursor { 1 } else { 0 });
-        // [1] execution count (0 for new query)
-        size += ub4_wire_size(0);
+        // [1] execution count (0 for queries)
+        size += ub4_wire_size(self.execution_count());
         // [2-4] zeros
         size += ub4_wire_size(0);
         size += ub4_wire_size(0);
@@ -181,6 +351,17 @@ impl Message for ExecuteMessage<'_> {
         // [12] zero
         size += ub4_wire_size(0);
 
+        // Define metadata, or bind metadata and values
+        if !self.defines.is_empty() {
+            size += self
+                .defines
+                .iter()
+                .map(|d| d.wire_size(self.ttc_field_version))
+                .sum::<usize>();
+        } else if let Some(binds) = self.binds {
+            size += binds.wire_size(self.ttc_field_version);
+        }
+
         size
     }
 
@@ -201,7 +382,7 @@ impl Message for ExecuteMessage<'_> {
         // SQL pointer and length
         if is_new_cursor {
             buf.write_u8(1); // has SQL
-            buf.write_ub4(sql_len as u32);
+            buf.write_value(Ub4::try_from(sql_len)?);
         } else {
             buf.write_u8(0);
             buf.write_ub4(0);
@@ -217,12 +398,13 @@ impl Message for ExecuteMessage<'_> {
 
         // Prefetch settings
         buf.write_ub4(0); // prefetch buffer size
-        buf.write_ub4(self.prefetch_rows); // prefetch rows
-        buf.write_ub4(TNS_MAX_LONG_LENGTH); // max long size
+        buf.write_ub4(self.num_iters()); // prefetch rows / executions
+        buf.write_ub4(self.max_long_size); // max long size
 
-        // Bind pointers (no binds)
-        buf.write_u8(0); // binds pointer
-        buf.write_ub4(0); // num binds
+        // Bind pointers
+        let num_binds = self.num_binds();
+        buf.write_u8(if num_binds > 0 { 1 } else { 0 }); // binds pointer
+        buf.write_ub4(num_binds); // num binds
 
         // More pointers
         buf.write_u8(0); // al8app
@@ -232,8 +414,9 @@ impl Message for ExecuteMessage<'_> {
         buf.write_u8(0); // al8kvl
 
         // Define pointers
-        buf.write_u8(0); // al8doac pointer
-        buf.write_ub4(0); // num defines
+        let num_defines = self.defines.len() as u32;
+        buf.write_u8(if num_defines > 0 { 1 } else { 0 }); // al8doac pointer
+        buf.write_ub4(num_defines); // num defines
 
         // Registration and more pointers
         buf.write_ub4(0); // registration id
@@ -273,8 +456,8 @@ impl Message for ExecuteMessage<'_> {
         // al8i4 array
         // [0] parse flag
         buf.write_ub4(if is_new_cursor { 1 } else { 0 });
-        // [1] execution count (0 for new query)
-        buf.write_ub4(0);
+        // [1] execution count (0 for queries)
+        buf.write_ub4(self.execution_count());
         // [2-4] zeros
         buf.write_ub4(0);
         buf.write_ub4(0);
@@ -295,12 +478,152 @@ impl Message for ExecuteMessage<'_> {
         // [12] zero
         buf.write_ub4(0);
 
+        // Define metadata, or bind metadata and values
+        if !self.defines.is_empty() {
+            for define in self.defines {
+                define.write_to(buf, self.ttc_field_version);
+            }
+        } else if let Some(binds) = self.binds {
+            binds.write_to(buf, self.ttc_field_version);
+        }
+
         Ok(())
     }
 }
 
 impl DataMessage for ExecuteMessage<'_> {}
 
+/// Message executing an open cursor again.
+///
+/// A cursor left open by an earlier execution of the same statement is
+/// parsed, bound and, for queries, defined already, so the message only
+/// names the cursor and carries the bind values. Queries fetch their first
+/// rows with the execution (`TNS_FUNC_REEXECUTE_AND_FETCH`); other
+/// statements use `TNS_FUNC_REEXECUTE`.
+pub struct ReexecuteMessage<'a> {
+    /// Cursor ID of the open cursor.
+    pub cursor_id: u32,
+    /// Whether this is a query (SELECT).
+    pub is_query: bool,
+    /// Number of rows to prefetch for queries.
+    pub prefetch_rows: u32,
+    /// Bind values, one row per execution, in the types the cursor was
+    /// bound with.
+    pub binds: Option<&'a BindParams>,
+    /// Commit the transaction after executing.
+    pub commit: bool,
+}
+
+impl<'a> ReexecuteMessage<'a> {
+    /// Create a message executing the open cursor of a statement.
+    pub fn new(cursor_id: u32, stmt: &Statement, prefetch_rows: u32) -> Self {
+        Self {
+            cursor_id,
+            is_query: stmt.is_query(),
+            prefetch_rows,
+            binds: None,
+            commit: false,
+        }
+    }
+
+    /// Get the function code: queries fetch with the execution.
+    fn function_code(&self) -> u8 {
+        if self.fetches() {
+            TNS_FUNC_REEXECUTE_AND_FETCH
+        } else {
+            TNS_FUNC_REEXECUTE
+        }
+    }
+
+    /// Whether rows are fetched with the execution.
+    fn fetches(&self) -> bool {
+        self.is_query && self.prefetch_rows > 0
+    }
+
+    /// Rows to prefetch for queries, executions for other statements.
+    fn num_iters(&self) -> u32 {
+        if self.fetches() {
+            self.prefetch_rows
+        } else {
+            self.binds.map_or(1, |b| b.num_rows().max(1) as u32)
+        }
+    }
+
+    /// Calculate the first execute flags word (execute, for fetching calls).
+    fn exec_flags_1(&self) -> u32 {
+        if self.fetches() {
+            TNS_EXEC_OPTION_EXECUTE
+        } else {
+            0
+        }
+    }
+
+    /// Calculate the second execute flags word (commit, for other calls).
+    fn exec_flags_2(&self) -> u32 {
+        if self.commit && !self.fetches() {
+            TNS_EXEC_OPTION_COMMIT_REEXECUTE
+        } else {
+            0
+        }
+    }
+
+    /// Get the options of the equivalent execute message, for statistics:
+    /// an execution, and a fetch for queries, without a parse.
+    pub(crate) fn calc_options(&self) -> u32 {
+        if self.fetches() {
+            TNS_EXEC_OPTION_EXECUTE | TNS_EXEC_OPTION_FETCH
+        } else {
+            TNS_EXEC_OPTION_EXECUTE
+        }
+    }
+}
+
+impl Message for ReexecuteMessage<'_> {
+    fn wire_size(&self) -> usize {
+        let mut size = 0;
+
+        // Function header
+        size += 1; // message type (TNS_MSG_TYPE_FUNCTION)
+        size += 1; // function code
+        size += 1; // sequence number
+
+        // Cursor, iterations and flags
+        size += ub4_wire_size(self.cursor_id);
+        size += ub4_wire_size(self.num_iters());
+        size += ub4_wire_size(self.exec_flags_1());
+        size += ub4_wire_size(self.exec_flags_2());
+
+        // Bind values, without metadata
+        if let Some(binds) = self.binds {
+            size += binds.values_wire_size();
+        }
+
+        size
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        // Function header
+        buf.write_u8(TNS_MSG_TYPE_FUNCTION);
+        buf.write_u8(self.function_code());
+        buf.write_u8(1); // sequence number
+
+        // Cursor, iterations and flags
+        buf.write_ub4(self.cursor_id);
+        buf.write_ub4(self.num_iters());
+        buf.write_ub4(self.exec_flags_1());
+        buf.write_ub4(self.exec_flags_2());
+
+        // Bind values, without metadata
+        if let Some(binds) = self.binds {
+            binds.write_values_to(buf);
+        }
+
+        Ok(())
+    }
+}
+
+impl DataMessage for ReexecuteMessage<'_> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,6 +638,54 @@ mod tests {
         assert_eq!(buf.len(), msg.wire_size());
     }
 
+    #[test]
+    fn test_execute_message_large_sql() {
+        // Generated IN-list well past the 64k single-chunk size
+        let list: Vec<String> = (0..20_000).map(|i| i.to_string()).collect();
+        let sql = format!("SELECT 1 FROM DUAL WHERE 1 IN ({})", list.join(","));
+        assert!(sql.len() > 65536);
+        ExecuteMessage::check_sql_length(&sql).unwrap();
+
+        let msg = ExecuteMessage::new_query(&sql, 100, 12);
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        // The SQL is written in chunks that each fit the chunk size
+        let start = buf
+            .windows(64)
+            .position(|w| w == &sql.as_bytes()[..64])
+            .unwrap();
+        assert_eq!(
+            &buf[start - 4..start],
+            &[TNS_LONG_LENGTH_INDICATOR, 2, 0x7F, 0xFF]
+        );
+    }
+
+    #[test]
+    fn test_execute_message_large_prefetch() {
+        // Row counts are UB4 fields, so prefetches past 65535 are not truncated
+        let msg = ExecuteMessage::new_query("SELECT 1 FROM DUAL", 100_000, 12);
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        assert!(buf.windows(4).any(|w| w == [3, 0x01, 0x86, 0xA0]));
+    }
+
+    #[test]
+    fn test_execute_message_max_long_size() {
+        let mut msg = ExecuteMessage::new_query("SELECT LONG_COL FROM T", 10, 12);
+        let mut default_buf = Vec::new();
+        msg.write_to(&mut default_buf).unwrap();
+
+        msg.max_long_size = 100;
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        // TNS_MAX_LONG_LENGTH takes 4 bytes as UB4, 100 takes 1
+        assert_eq!(default_buf.len() - buf.len(), 3);
+    }
+
     #[test]
     fn test_execute_message_options() {
         let msg = ExecuteMessage::new_query("SELECT 1 FROM DUAL", 100, 12);
@@ -326,4 +697,232 @@ mod tests {
         assert!(options & TNS_EXEC_OPTION_FETCH != 0);
         assert!(options & TNS_EXEC_OPTION_NOT_PLSQL != 0);
     }
+
+    #[test]
+    fn test_execute_message_cursor_fetch() {
+        let msg = ExecuteMessage::new_cursor_fetch(42, 50, 12);
+        let options = msg.calc_options();
+        assert_eq!(options, TNS_EXEC_OPTION_FETCH | TNS_EXEC_OPTION_NOT_PLSQL);
+        assert_eq!(msg.calc_exec_flags(), 0);
+        assert_eq!(msg.num_iters(), 50);
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        // Statements with SQL ask for implicit results
+        let stmt = Statement::parse("BEGIN NULL; END;");
+        let msg = ExecuteMessage::new_statement(&stmt, 0, 12);
+        assert_eq!(msg.calc_exec_flags(), TNS_EXEC_FLAGS_IMPLICIT_RESULTSET);
+    }
+
+    #[test]
+    fn test_execute_message_binds() {
+        use crate::protocol::packet::Capabilities;
+        use crate::protocol::types::OracleValue;
+
+        let rows: Vec<Vec<OracleValue>> = (1..=3)
+            .map(|i| {
+                vec![
+                    OracleValue::Number(i.to_string()),
+                    OracleValue::String(format!("name {}", i)),
+                ]
+            })
+            .collect();
+        let params = BindParams::new(&rows, &Capabilities::new()).unwrap();
+        let stmt = Statement::parse("INSERT INTO t (id, name) VALUES (:1, :2)");
+        let mut msg = ExecuteMessage::new_statement(&stmt, 100, 12);
+        msg.binds = Some(&params);
+        msg.batch_errors = true;
+
+        let options = msg.calc_options();
+        assert!(options & TNS_EXEC_OPTION_BIND != 0);
+        assert!(options & TNS_EXEC_OPTION_BATCH_ERRORS != 0);
+        assert!(options & TNS_EXEC_OPTION_PLSQL_BIND == 0);
+        assert!(options & TNS_EXEC_OPTION_COMMIT == 0);
+        assert_eq!(msg.execution_count(), 3);
+        assert_eq!(msg.num_iters(), 3);
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        // One row data message per execution, at the end of the message
+        let tail: Vec<u8> = [&[TNS_MSG_TYPE_ROW_DATA, 2, 0xC1, 0x04, 6][..], b"name 3"].concat();
+        assert!(buf.ends_with(&tail));
+
+        msg.commit = true;
+        assert!(msg.calc_options() & TNS_EXEC_OPTION_COMMIT != 0);
+    }
+
+    #[test]
+    fn test_execute_message_statement_options() {
+        let stmt = Statement::parse("BEGIN NULL; END;");
+        let msg = ExecuteMessage::new_statement(&stmt, 100, 12);
+        let options = msg.calc_options();
+        assert!(options & TNS_EXEC_OPTION_EXECUTE != 0);
+        assert!(options & TNS_EXEC_OPTION_FETCH == 0);
+        assert!(options & TNS_EXEC_OPTION_NOT_PLSQL == 0);
+        assert_eq!(msg.execution_count(), 1);
+
+        let stmt = Statement::parse(
+            "WITH FUNCTION f RETURN NUMBER IS BEGIN RETURN 1; END; SELECT f FROM DUAL",
+        );
+        let msg = ExecuteMessage::new_statement(&stmt, 100, 12);
+        let options = msg.calc_options();
+        assert!(options & TNS_EXEC_OPTION_FETCH != 0);
+        assert!(options & TNS_EXEC_OPTION_NOT_PLSQL != 0);
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+    }
+
+    #[test]
+    fn test_execute_message_ddl_and_dml_options() {
+        let stmt = Statement::parse("CREATE TABLE t (x NUMBER)");
+        let mut msg = ExecuteMessage::new_statement(&stmt, 100, 12);
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_EXECUTE | TNS_EXEC_OPTION_NOT_PLSQL
+        );
+        // DDL is parsed again even on an open cursor
+        msg.cursor_id = 7;
+        assert!(msg.calc_options() & TNS_EXEC_OPTION_PARSE != 0);
+
+        let stmt = Statement::parse("DELETE FROM t");
+        let mut msg = ExecuteMessage::new_statement(&stmt, 100, 12);
+        assert_eq!(msg.prefetch_rows, 0);
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_EXECUTE | TNS_EXEC_OPTION_NOT_PLSQL
+        );
+        msg.cursor_id = 7;
+        assert!(msg.calc_options() & TNS_EXEC_OPTION_PARSE == 0);
+    }
+
+    #[test]
+    fn test_reexecute_message() {
+        use crate::protocol::packet::Capabilities;
+        use crate::protocol::types::OracleValue;
+
+        let stmt = Statement::parse("SELECT * FROM t");
+        let msg = ReexecuteMessage::new(7, &stmt, 100);
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        assert_eq!(
+            buf,
+            [
+                TNS_MSG_TYPE_FUNCTION,
+                TNS_FUNC_REEXECUTE_AND_FETCH,
+                1,
+                1,
+                7,
+                1,
+                100,
+                1,
+                TNS_EXEC_OPTION_EXECUTE as u8,
+                0
+            ]
+        );
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_EXECUTE | TNS_EXEC_OPTION_FETCH
+        );
+
+        // DML is executed once per row of values, which follow without
+        // bind metadata
+        let rows = vec![vec![OracleValue::Integer(1)], vec![OracleValue::Integer(2)]];
+        let params = BindParams::new(&rows, &Capabilities::new()).unwrap();
+        let stmt = Statement::parse("DELETE FROM t WHERE id = :1");
+        let mut msg = ReexecuteMessage::new(7, &stmt, 100);
+        msg.binds = Some(&params);
+        msg.commit = true;
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        assert_eq!(
+            buf,
+            [
+                TNS_MSG_TYPE_FUNCTION,
+                TNS_FUNC_REEXECUTE,
+                1,
+                1,
+                7,
+                1,
+                2,
+                0,
+                1,
+                TNS_EXEC_OPTION_COMMIT_REEXECUTE as u8,
+                TNS_MSG_TYPE_ROW_DATA,
+                2,
+                0xC1,
+                0x02,
+                TNS_MSG_TYPE_ROW_DATA,
+                2,
+                0xC1,
+                0x03
+            ]
+        );
+        assert_eq!(msg.calc_options(), TNS_EXEC_OPTION_EXECUTE);
+    }
+
+    #[test]
+    fn test_execute_message_defines() {
+        use crate::protocol::packet::Capabilities;
+
+        let caps = Capabilities::new();
+        let defines = [
+            BindInfo::define(ORA_TYPE_NUM_VARCHAR, &caps),
+            BindInfo::define(ORA_TYPE_NUM_LONG, &caps),
+        ];
+        let stmt = Statement::parse("SELECT amount, notes FROM t WHERE id = :1");
+        let rows = vec![vec![crate::protocol::types::OracleValue::Integer(1)]];
+        let params = BindParams::new(&rows, &caps).unwrap();
+        let mut msg = ExecuteMessage::new_define(&stmt, 9, &defines, 50, 12);
+        msg.binds = Some(&params);
+
+        // The cursor is not parsed or bound again
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_EXECUTE
+                | TNS_EXEC_OPTION_FETCH
+                | TNS_EXEC_OPTION_DEFINE
+                | TNS_EXEC_OPTION_NOT_PLSQL
+        );
+        assert_eq!(msg.num_iters(), 50);
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        // The define metadata ends the message, with no row data
+        let mut metadata = Vec::new();
+        for define in &defines {
+            define.write_to(&mut metadata, 12);
+        }
+        assert!(buf.ends_with(&metadata));
+        assert!(!buf
+            .windows(stmt.sql().len())
+            .any(|w| w == stmt.sql().as_bytes()));
+    }
+
+    #[test]
+    fn test_execute_message_describe_options() {
+        let stmt = Statement::parse("SELECT * FROM t");
+        let msg = ExecuteMessage::new_describe(&stmt, 12);
+        assert_eq!(msg.num_iters(), 0);
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_DESCRIBE | TNS_EXEC_OPTION_NOT_PLSQL
+        );
+        let mut buf = Vec::new();
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+
+        let stmt = Statement::parse("UPDATE t SET x = 1");
+        let msg = ExecuteMessage::new_describe(&stmt, 12);
+        assert_eq!(
+            msg.calc_options(),
+            TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_NOT_PLSQL
+        );
+    }
 }
@@ -0,0 +1,373 @@
+//! LOB operation messages (create temporary, read, write, trim, length,
+//! BFILE access).
+
+use crate::error::Result;
+use crate::protocol::buffer::Ub4;
+use crate::protocol::constants::*;
+use crate::protocol::message::{
+    bytes_with_length_wire_size, ub4_wire_size, ub8_wire_size, DataMessage, Message, WriteExt,
+};
+
+/// A single LOB operation on a locator (TNS_FUNC_LOB_OP).
+///
+/// The server returns the (possibly updated) locator, followed by the
+/// amount and flag fields the operation asked for; see
+/// `parse_lob_op_response`.
+pub struct LobOpMessage<'a> {
+    /// Operation code (`TNS_LOB_OP_*`).
+    pub operation: u32,
+    /// Locator the operation applies to.
+    pub locator: &'a [u8],
+    /// Offset (1-based) or, for CREATE_TEMP, the charset form.
+    pub source_offset: u64,
+    /// Destination offset or, for CREATE_TEMP, the LOB type number.
+    pub dest_offset: u64,
+    /// Amount sent with and returned by the operation.
+    pub amount: Option<u64>,
+    /// Data to write.
+    pub data: Option<&'a [u8]>,
+}
+
+impl<'a> LobOpMessage<'a> {
+    fn new(operation: u32, locator: &'a [u8]) -> Self {
+        Self {
+            operation,
+            locator,
+            source_offset: 0,
+            dest_offset: 0,
+            amount: None,
+            data: None,
+        }
+    }
+
+    /// Create a session-duration temporary LOB into an empty locator.
+    pub fn create_temp(locator: &'a [u8], csfrm: u8, ora_type_num: u16) -> Self {
+        Self {
+            source_offset: csfrm as u64,
+            dest_offset: ora_type_num as u64,
+            amount: Some(TNS_DURATION_SESSION as u64),
+            ..Self::new(TNS_LOB_OP_CREATE_TEMP, locator)
+        }
+    }
+
+    /// Get the LOB length.
+    pub fn get_length(locator: &'a [u8]) -> Self {
+        Self {
+            amount: Some(0),
+            ..Self::new(TNS_LOB_OP_GET_LENGTH, locator)
+        }
+    }
+
+    /// Read up to `amount` characters or bytes starting at `offset`.
+    pub fn read(locator: &'a [u8], offset: u64, amount: u64) -> Self {
+        Self {
+            source_offset: offset,
+            amount: Some(amount),
+            ..Self::new(TNS_LOB_OP_READ, locator)
+        }
+    }
+
+    /// Write encoded data starting at `offset`.
+    pub fn write(locator: &'a [u8], offset: u64, data: &'a [u8]) -> Self {
+        Self {
+            source_offset: offset,
+            data: Some(data),
+            ..Self::new(TNS_LOB_OP_WRITE, locator)
+        }
+    }
+
+    /// Trim the LOB to `new_size` characters or bytes.
+    pub fn trim(locator: &'a [u8], new_size: u64) -> Self {
+        Self {
+            amount: Some(new_size),
+            ..Self::new(TNS_LOB_OP_TRIM, locator)
+        }
+    }
+
+    /// Open a BFILE for reading.
+    pub fn file_open(locator: &'a [u8]) -> Self {
+        Self {
+            amount: Some(TNS_LOB_OPEN_READ_ONLY),
+            ..Self::new(TNS_LOB_OP_FILE_OPEN, locator)
+        }
+    }
+
+    /// Close a BFILE opened with `file_open`.
+    pub fn file_close(locator: &'a [u8]) -> Self {
+        Self::new(TNS_LOB_OP_FILE_CLOSE, locator)
+    }
+
+    /// Check if a BFILE is open.
+    pub fn file_is_open(locator: &'a [u8]) -> Self {
+        Self::new(TNS_LOB_OP_FILE_ISOPEN, locator)
+    }
+
+    /// Check if the file a BFILE points to exists on the server.
+    pub fn file_exists(locator: &'a [u8]) -> Self {
+        Self::new(TNS_LOB_OP_FILE_EXISTS, locator)
+    }
+
+    /// Whether the server returns a flag after the amount.
+    pub fn returns_flag(&self) -> bool {
+        matches!(
+            self.operation,
+            TNS_LOB_OP_CREATE_TEMP | TNS_LOB_OP_FILE_ISOPEN | TNS_LOB_OP_FILE_EXISTS
+        )
+    }
+
+    /// Charset sent with CREATE_TEMP.
+    fn charset(&self) -> Option<u32> {
+        if self.operation != TNS_LOB_OP_CREATE_TEMP {
+            return None;
+        }
+        Some(if self.source_offset == CS_FORM_NCHAR as u64 {
+            TNS_CHARSET_UTF16 as u32
+        } else {
+            TNS_CHARSET_UTF8 as u32
+        })
+    }
+}
+
+impl Message for LobOpMessage<'_> {
+    fn wire_size(&self) -> usize {
+        let mut size = 0;
+
+        // Function header
+        size += 1; // message type (TNS_MSG_TYPE_FUNCTION)
+        size += 1; // function code (TNS_FUNC_LOB_OP)
+        size += 1; // sequence number
+
+        size += 1 + ub4_wire_size(self.locator.len() as u32); // source pointer, length
+        size += 1 + 1; // dest pointer, length
+        size += 1 + 1; // short source/dest offsets
+        size += 3; // charset, short amount, NULL LOB pointers
+        size += ub4_wire_size(self.operation);
+        size += 2; // SCN array pointer, length
+        size += ub8_wire_size(self.source_offset);
+        size += ub8_wire_size(self.dest_offset);
+        size += 1; // amount pointer
+        size += 6; // array LOB fields
+
+        size += self.locator.len();
+        if let Some(charset) = self.charset() {
+            size += ub4_wire_size(charset);
+        }
+        if let Some(data) = self.data {
+            size += 1 + bytes_with_length_wire_size(data.len());
+        }
+        if let Some(amount) = self.amount {
+            size += ub8_wire_size(amount);
+        }
+
+        size
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        // Function header
+        buf.write_u8(TNS_MSG_TYPE_FUNCTION);
+        buf.write_u8(TNS_FUNC_LOB_OP);
+        buf.write_u8(1); // sequence number
+
+        buf.write_u8(1); // source pointer
+        buf.write_value(Ub4::try_from(self.locator.len())?);
+        buf.write_u8(0); // dest pointer
+        buf.write_ub4(0); // dest length
+        buf.write_ub4(0); // short source offset
+        buf.write_ub4(0); // short dest offset
+        buf.write_u8(self.charset().is_some() as u8); // charset pointer
+        buf.write_u8(0); // short amount pointer
+        buf.write_u8(self.returns_flag() as u8); // NULL LOB pointer
+        buf.write_ub4(self.operation);
+        buf.write_u8(0); // SCN array pointer
+        buf.write_u8(0); // SCN array length
+        buf.write_ub8(self.source_offset);
+        buf.write_ub8(self.dest_offset);
+        buf.write_u8(self.amount.is_some() as u8); // amount pointer
+        for _ in 0..3 {
+            buf.write_u16_be(0); // array LOB (not used)
+        }
+
+        buf.write_bytes(self.locator);
+        if let Some(charset) = self.charset() {
+            buf.write_ub4(charset);
+        }
+        if let Some(data) = self.data {
+            buf.write_u8(TNS_MSG_TYPE_LOB_DATA);
+            buf.write_bytes_with_length(data);
+        }
+        if let Some(amount) = self.amount {
+            buf.write_ub8(amount);
+        }
+
+        Ok(())
+    }
+}
+
+impl DataMessage for LobOpMessage<'_> {}
+
+/// Piggyback message freeing temporary LOBs.
+///
+/// Like `CloseCursorsMessage`, this is never sent on its own but prepended
+/// to the next function message.
+pub struct FreeTempLobsMessage {
+    /// Locators of the temporary LOBs to free.
+    pub locators: Vec<Vec<u8>>,
+}
+
+impl FreeTempLobsMessage {
+    /// Create a new free temporary LOBs message.
+    pub fn new(locators: Vec<Vec<u8>>) -> Self {
+        Self { locators }
+    }
+
+    fn total_size(&self) -> u32 {
+        self.locators.iter().map(|l| l.len() as u32).sum()
+    }
+}
+
+impl Message for FreeTempLobsMessage {
+    fn wire_size(&self) -> usize {
+        let total = ub4_wire_size(self.total_size());
+
+        // Piggyback header
+        let mut size = 3;
+
+        size += 1 + total; // temp LOB data pointer, total size
+        size += 1 + 1 + 1 + 1; // dest and source locators
+        size += 3; // source offset, dest offset, charset
+        size += ub4_wire_size(TNS_LOB_OP_FREE_TEMP | TNS_LOB_OP_ARRAY);
+        size += 1 + 1 + 1 + 1 + 1; // SCN fields
+        size += 1 + 1 + 1 + 1 + 1 + total; // array LOB fields
+        size += self.locators.iter().map(Vec::len).sum::<usize>();
+
+        size
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        let total = self.total_size();
+
+        // Piggyback header
+        buf.write_u8(TNS_MSG_TYPE_PIGGYBACK);
+        buf.write_u8(TNS_FUNC_LOB_OP);
+        buf.write_u8(1); // sequence number
+
+        buf.write_u8(1); // temp LOB data pointer
+        buf.write_ub4(total);
+        buf.write_u8(0); // dest locator
+        buf.write_ub4(0);
+        buf.write_ub4(0); // source locator
+        buf.write_ub4(0);
+        buf.write_u8(0); // source offset
+        buf.write_u8(0); // dest offset
+        buf.write_u8(0); // charset
+        buf.write_ub4(TNS_LOB_OP_FREE_TEMP | TNS_LOB_OP_ARRAY);
+        buf.write_u8(0); // SCN
+        buf.write_ub4(0); // losbscn
+        buf.write_ub8(0); // lobscnl
+        buf.write_ub8(0);
+        buf.write_u8(0);
+
+        // Array LOB fields
+        buf.write_u8(0);
+        buf.write_ub4(0);
+        buf.write_u8(0);
+        buf.write_ub4(0);
+        buf.write_u8(1);
+        buf.write_ub4(total);
+        for locator in &self.locators {
+            buf.write_bytes(locator);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCATOR: [u8; 40] = [7; 40];
+
+    fn encode<M: Message>(msg: &M) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+        assert_eq!(buf.len(), msg.wire_size());
+        buf
+    }
+
+    #[test]
+    fn test_lob_op_message_wire_size() {
+        let big = vec![0xAB; 70000];
+        for msg in [
+            LobOpMessage::create_temp(&LOCATOR, CS_FORM_IMPLICIT, ORA_TYPE_NUM_CLOB),
+            LobOpMessage::create_temp(&LOCATOR, CS_FORM_NCHAR, ORA_TYPE_NUM_CLOB),
+            LobOpMessage::get_length(&LOCATOR),
+            LobOpMessage::read(&LOCATOR, 1, 8000),
+            LobOpMessage::write(&LOCATOR, 300, b"hello"),
+            LobOpMessage::write(&LOCATOR, 1, &big),
+            LobOpMessage::trim(&LOCATOR, 0),
+            LobOpMessage::file_open(&LOCATOR),
+            LobOpMessage::file_close(&LOCATOR),
+            LobOpMessage::file_is_open(&LOCATOR),
+            LobOpMessage::file_exists(&LOCATOR),
+        ] {
+            encode(&msg);
+        }
+    }
+
+    #[test]
+    fn test_lob_op_write_content() {
+        let buf = encode(&LobOpMessage::write(&LOCATOR, 1, b"hi"));
+
+        assert_eq!(&buf[..3], &[TNS_MSG_TYPE_FUNCTION, TNS_FUNC_LOB_OP, 1]);
+        // source pointer and locator length
+        assert_eq!(&buf[3..6], &[1, 1, 40]);
+        // operation
+        assert_eq!(&buf[13..15], &[1, TNS_LOB_OP_WRITE as u8]);
+        // trailing locator, then the LOB data
+        assert_eq!(&buf[buf.len() - 44..buf.len() - 4], &LOCATOR);
+        assert_eq!(
+            &buf[buf.len() - 4..],
+            &[TNS_MSG_TYPE_LOB_DATA, 2, b'h', b'i']
+        );
+    }
+
+    #[test]
+    fn test_lob_op_create_temp_content() {
+        let buf = encode(&LobOpMessage::create_temp(
+            &LOCATOR,
+            CS_FORM_IMPLICIT,
+            ORA_TYPE_NUM_BLOB,
+        ));
+
+        // charset and NULL LOB pointers set
+        assert_eq!(&buf[10..13], &[1, 0, 1]);
+        // locator, UTF-8 charset, session duration
+        let tail = &buf[buf.len() - 5..];
+        assert_eq!(tail, &[2, 0x03, 0x69, 1, TNS_DURATION_SESSION]);
+    }
+
+    #[test]
+    fn test_lob_op_file_content() {
+        let buf = encode(&LobOpMessage::file_exists(&LOCATOR));
+        // no charset, NULL LOB pointer set for the returned flag
+        assert_eq!(&buf[10..13], &[0, 0, 1]);
+        assert_eq!(&buf[13..16], &[2, 0x08, 0x00]);
+        assert!(buf.ends_with(&LOCATOR));
+
+        // open sends the read-only mode as the amount
+        let buf = encode(&LobOpMessage::file_open(&LOCATOR));
+        assert_eq!(&buf[10..13], &[0, 0, 0]);
+        assert_eq!(&buf[buf.len() - 2..], &[1, TNS_LOB_OPEN_READ_ONLY as u8]);
+    }
+
+    #[test]
+    fn test_free_temp_lobs_message() {
+        let msg = FreeTempLobsMessage::new(vec![LOCATOR.to_vec(), LOCATOR.to_vec()]);
+        let buf = encode(&msg);
+
+        assert_eq!(&buf[..3], &[TNS_MSG_TYPE_PIGGYBACK, TNS_FUNC_LOB_OP, 1]);
+        assert_eq!(&buf[3..6], &[1, 1, 80]);
+        assert!(buf.ends_with(&[7; 80]));
+    }
+}
@@ -3,15 +3,25 @@
 //! Each message implements the `Message` trait for zero-copy serialization.
 
 pub mod auth;
+pub mod close_cursors;
 pub mod connect;
 pub mod data_types;
+pub mod end_to_end;
 pub mod execute;
 pub mod fetch;
+pub mod lob_op;
+pub mod ping;
+pub mod pipeline;
 
-pub use auth::{AuthPhaseOneMessage, AuthPhaseTwoMessage, FastAuthMessage};
+pub use auth::{AuthPhaseOneMessage, AuthPhaseTwoMessage, AuthProof, FastAuthMessage};
+pub use close_cursors::CloseCursorsMessage;
 pub use connect::{
     ConnectMessage, MarkerMessage, ProtocolMessage, TNS_MARKER_TYPE_BREAK, TNS_MARKER_TYPE_RESET,
 };
 pub use data_types::DataTypesMessage;
-pub use execute::ExecuteMessage;
+pub use end_to_end::EndToEndMessage;
+pub use execute::{ExecuteMessage, ReexecuteMessage};
 pub use fetch::FetchMessage;
+pub use lob_op::{FreeTempLobsMessage, LobOpMessage};
+pub use ping::PingMessage;
+pub use pipeline::{BeginPipelineMessage, EndPipelineMessage};
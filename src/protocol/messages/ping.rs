@@ -0,0 +1,49 @@
+//! Ping message for checking connection liveness.
+
+use crate::error::Result;
+use crate::protocol::constants::*;
+use crate::protocol::message::{DataMessage, Message, WriteExt};
+
+/// Ping message.
+///
+/// A function call with no arguments; the server replies with an
+/// end-of-call status only.
+#[derive(Default)]
+pub struct PingMessage;
+
+impl PingMessage {
+    /// Create a new ping message.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Message for PingMessage {
+    fn wire_size(&self) -> usize {
+        3 // message type, function code, sequence number
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.write_u8(TNS_MSG_TYPE_FUNCTION);
+        buf.write_u8(TNS_FUNC_PING);
+        buf.write_u8(1); // sequence number
+        Ok(())
+    }
+}
+
+impl DataMessage for PingMessage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_message_content() {
+        let msg = PingMessage::new();
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        assert_eq!(buf, vec![TNS_MSG_TYPE_FUNCTION, TNS_FUNC_PING, 1]);
+    }
+}
@@ -0,0 +1,127 @@
+//! Messages bracketing a pipeline of requests.
+
+use crate::error::Result;
+use crate::protocol::constants::*;
+use crate::protocol::message::{ub2_wire_size, ub4_wire_size, DataMessage, Message, WriteExt};
+
+/// Message beginning a pipeline.
+///
+/// Sent in the DATA packet flagged `TNS_DATA_FLAGS_BEGIN_PIPELINE`, ahead
+/// of the pipelined requests. The server does not respond to it; the
+/// responses to the requests follow one another, then the response to the
+/// `EndPipelineMessage`.
+pub struct BeginPipelineMessage {
+    /// How the server handles a request that fails (`TNS_PIPELINE_MODE_*`).
+    pub mode: u8,
+}
+
+impl BeginPipelineMessage {
+    /// Create a message beginning a pipeline that runs every request,
+    /// whether or not the ones before it fail.
+    pub fn new() -> Self {
+        Self {
+            mode: TNS_PIPELINE_MODE_CONTINUE_ON_ERROR,
+        }
+    }
+}
+
+impl Default for BeginPipelineMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Message for BeginPipelineMessage {
+    fn wire_size(&self) -> usize {
+        3 // message type, function code, sequence number
+            + ub2_wire_size(0) // error set ID
+            + 1 // error set mode
+            + 1 // pipeline mode
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.write_u8(TNS_MSG_TYPE_FUNCTION);
+        buf.write_u8(TNS_FUNC_PIPELINE_BEGIN);
+        buf.write_u8(1); // sequence number
+        buf.write_ub2(0); // error set ID
+        buf.write_u8(0); // error set mode
+        buf.write_u8(self.mode);
+        Ok(())
+    }
+}
+
+impl DataMessage for BeginPipelineMessage {
+    fn data_flags(&self) -> u16 {
+        TNS_DATA_FLAGS_BEGIN_PIPELINE
+    }
+}
+
+/// Message ending a pipeline; the server replies with an end-of-call
+/// status once every request before it has been answered.
+#[derive(Default)]
+pub struct EndPipelineMessage;
+
+impl EndPipelineMessage {
+    /// Create a message ending a pipeline.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Message for EndPipelineMessage {
+    fn wire_size(&self) -> usize {
+        3 + ub4_wire_size(0) // header, pipeline ID
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) -> Result<()> {
+        buf.write_u8(TNS_MSG_TYPE_FUNCTION);
+        buf.write_u8(TNS_FUNC_PIPELINE_END);
+        buf.write_u8(1); // sequence number
+        buf.write_ub4(0); // pipeline ID (unused)
+        Ok(())
+    }
+}
+
+impl DataMessage for EndPipelineMessage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_begin_pipeline_message_content() {
+        let msg = BeginPipelineMessage::new();
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        assert_eq!(buf.len(), msg.wire_size());
+        assert_eq!(
+            buf,
+            vec![
+                TNS_MSG_TYPE_FUNCTION,
+                TNS_FUNC_PIPELINE_BEGIN,
+                1, // sequence
+                0, // error set ID
+                0, // error set mode
+                TNS_PIPELINE_MODE_CONTINUE_ON_ERROR,
+            ]
+        );
+        assert_eq!(msg.data_flags(), TNS_DATA_FLAGS_BEGIN_PIPELINE);
+    }
+
+    #[test]
+    fn test_end_pipeline_message_content() {
+        let msg = EndPipelineMessage::new();
+
+        let mut buf = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut buf).unwrap();
+
+        assert_eq!(buf.len(), msg.wire_size());
+        assert_eq!(
+            buf,
+            vec![TNS_MSG_TYPE_FUNCTION, TNS_FUNC_PIPELINE_END, 1, 0]
+        );
+        assert_eq!(msg.data_flags(), 0);
+    }
+}
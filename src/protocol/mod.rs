@@ -1,22 +1,46 @@
 //! TNS protocol implementation for Oracle thin client.
 
+pub mod accept;
 pub mod auth;
+pub mod bind;
 pub mod buffer;
+pub mod clock;
 pub mod connect;
 pub mod constants;
+pub mod control;
 pub mod crypto;
 pub mod decode;
+pub mod encode;
+pub mod features;
 pub mod message;
 pub mod messages;
 pub mod packet;
+pub mod proxy;
+#[cfg(feature = "replay")]
+pub mod replay;
 pub mod response;
+pub mod session;
+pub mod statement;
+pub mod statement_cache;
+#[cfg(feature = "tls")]
+pub mod tls;
+pub mod tnsnames;
+pub mod transport;
 pub mod types;
+pub mod version;
+#[cfg(feature = "wire-compat")]
+pub mod wire_compat;
 
-pub use buffer::{ReadBuffer, WriteBuffer};
+pub use buffer::{LengthPrefixedBytes, ReadBuffer, Ub2, Ub4, Ub8, WireValue, WriteBuffer};
+pub use features::ServerFeatures;
 pub use message::{DataMessage, Message, WriteExt};
 pub use messages::{
     AuthPhaseOneMessage, AuthPhaseTwoMessage, ConnectMessage, DataTypesMessage, ExecuteMessage,
     FastAuthMessage, MarkerMessage, ProtocolMessage,
 };
 pub use packet::Packet;
-pub use types::{Column, ColumnInfo, ColumnMetadata, OracleType, OracleValue, Row};
+pub use statement::{BindInfo, Statement, StatementType};
+pub use types::{
+    Column, ColumnInfo, ColumnMetadata, FromSql, Guid, Lob, OracleType, OracleValue, Row, RowId,
+    Scn, ToSql, Vector, VectorData,
+};
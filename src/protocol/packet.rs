@@ -4,13 +4,19 @@ use crate::error::{Error, Result};
 use crate::protocol::buffer::WriteBuffer;
 use crate::protocol::constants::*;
 use crate::protocol::message::{write_packet_header, DataMessage, Message};
+use crate::protocol::transport::Transport;
 use bytes::{Bytes, BytesMut};
+use futures::FutureExt;
+use std::io;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
 /// TNS packet header size.
 pub const HEADER_SIZE: usize = 8;
 
+/// Default limit on the size of a single response, in bytes (1 GiB).
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 1 << 30;
+
 /// A TNS packet.
 #[derive(Debug, Clone)]
 pub struct Packet {
@@ -78,25 +84,172 @@ impl Packet {
     }
 }
 
-/// TNS packet reader/writer for a TCP stream.
+/// Split the next complete packet off the front of `buf`.
+///
+/// Returns `Ok(None)` if `buf` does not yet hold a complete packet, and a
+/// protocol error if the header declares a length shorter than the header
+/// itself.
+#[cfg_attr(not(test), deny(clippy::indexing_slicing))]
+pub fn decode_packet(buf: &mut BytesMut, use_large_sdu: bool) -> Result<Option<Packet>> {
+    let &[l0, l1, l2, l3, packet_type, packet_flags, ..] = buf.as_ref() else {
+        return Ok(None);
+    };
+    if buf.len() < HEADER_SIZE {
+        return Ok(None);
+    }
+
+    let packet_len = if use_large_sdu {
+        u32::from_be_bytes([l0, l1, l2, l3]) as usize
+    } else {
+        u16::from_be_bytes([l0, l1]) as usize
+    };
+    if packet_len < HEADER_SIZE {
+        return Err(Error::protocol(format!(
+            "Invalid packet length: {}",
+            packet_len
+        )));
+    }
+    if buf.len() < packet_len {
+        return Ok(None);
+    }
+
+    let mut packet_data = buf.split_to(packet_len);
+    let payload = packet_data.split_off(HEADER_SIZE).freeze();
+    Ok(Some(Packet {
+        packet_type,
+        packet_flags,
+        payload,
+    }))
+}
+
+/// Reject a packet whose header declares more than `max_size` bytes.
+///
+/// Checked before the packet is buffered, so a corrupt or hostile length
+/// cannot make the reader accumulate unbounded data.
+pub fn check_packet_length(buf: &[u8], use_large_sdu: bool, max_size: usize) -> Result<()> {
+    let &[l0, l1, l2, l3, ..] = buf else {
+        return Ok(());
+    };
+    let packet_len = if use_large_sdu {
+        u32::from_be_bytes([l0, l1, l2, l3]) as usize
+    } else {
+        u16::from_be_bytes([l0, l1]) as usize
+    };
+    if packet_len > max_size {
+        return Err(Error::ResponseTooLarge {
+            size: packet_len,
+            max: max_size,
+        });
+    }
+    Ok(())
+}
+
+/// Frame message content as consecutive DATA packets of at most `sdu` bytes.
+///
+/// Every packet repeats the data flags ahead of its share of the content.
+pub fn frame_data_packets(
+    content: &[u8],
+    data_flags: u16,
+    sdu: usize,
+    use_large_sdu: bool,
+) -> Vec<u8> {
+    let piece = sdu.saturating_sub(HEADER_SIZE + 2).max(1);
+    let num_packets = content.len().div_ceil(piece);
+    let mut buf = Vec::with_capacity(content.len() + num_packets * (HEADER_SIZE + 2));
+
+    for chunk in content.chunks(piece) {
+        let total_size = HEADER_SIZE + 2 + chunk.len();
+        write_packet_header(&mut buf, TNS_PACKET_TYPE_DATA, 0, total_size, use_large_sdu);
+        buf.extend_from_slice(&data_flags.to_be_bytes());
+        buf.extend_from_slice(chunk);
+    }
+    buf
+}
+
+/// Serialize a message as one packet of the given type and flags, header
+/// included.
+pub fn encode_message<M: Message>(
+    packet_type: u8,
+    packet_flags: u8,
+    msg: &M,
+    use_large_sdu: bool,
+) -> Result<Vec<u8>> {
+    let total_size = HEADER_SIZE + msg.wire_size();
+    let mut buf = Vec::with_capacity(total_size);
+    write_packet_header(
+        &mut buf,
+        packet_type,
+        packet_flags,
+        total_size,
+        use_large_sdu,
+    );
+    msg.write_to(&mut buf)?;
+    Ok(buf)
+}
+
+/// Serialize a DATA message as the packets it is sent in.
+///
+/// Messages larger than the SDU are split across several DATA packets.
+pub fn encode_data_message<M: DataMessage>(
+    msg: &M,
+    sdu: usize,
+    use_large_sdu: bool,
+) -> Result<Vec<u8>> {
+    let total_size = HEADER_SIZE + msg.data_wire_size();
+    if total_size > sdu {
+        let mut content = Vec::with_capacity(msg.wire_size());
+        msg.write_to(&mut content)?;
+        return Ok(frame_data_packets(
+            &content,
+            msg.data_flags(),
+            sdu,
+            use_large_sdu,
+        ));
+    }
+
+    let mut buf = Vec::with_capacity(total_size);
+    write_packet_header(&mut buf, TNS_PACKET_TYPE_DATA, 0, total_size, use_large_sdu);
+    buf.extend_from_slice(&msg.data_flags().to_be_bytes());
+    msg.write_to(&mut buf)?;
+    Ok(buf)
+}
+
+/// TNS packet reader/writer for a TCP or TLS stream.
 pub struct PacketStream {
-    stream: TcpStream,
+    stream: Transport,
     /// Whether to use 4-byte length (large SDU) or 2-byte length.
     use_large_sdu: bool,
     /// Maximum packet size (SDU).
     sdu: u32,
-    /// Partial buffer for incomplete packets.
+    /// Data read but not yet returned as packets. Socket reads go straight
+    /// into it, and packets are split off it without copying; its space is
+    /// reused once the packets handed out are dropped.
     partial_buf: BytesMut,
+    /// Packets queued to be written together by the next `flush`.
+    write_buf: Vec<u8>,
+    /// Bytes of `write_buf` already written by a `flush` cut short.
+    written: usize,
+    /// Largest packet or response, in bytes, accepted from the server.
+    max_response_size: usize,
+    /// Bytes written to the stream.
+    bytes_sent: u64,
+    /// Bytes read from the stream.
+    bytes_received: u64,
 }
 
 impl PacketStream {
     /// Create a new packet stream.
-    pub fn new(stream: TcpStream) -> Self {
+    pub fn new(stream: impl Into<Transport>) -> Self {
         Self {
-            stream,
+            stream: stream.into(),
             use_large_sdu: false,
             sdu: TNS_SDU_DEFAULT,
             partial_buf: BytesMut::new(),
+            write_buf: Vec::new(),
+            written: 0,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            bytes_sent: 0,
+            bytes_received: 0,
         }
     }
 
@@ -110,60 +263,103 @@ impl PacketStream {
         self.sdu = sdu;
     }
 
+    /// Set the largest packet or response, in bytes, accepted from the server.
+    pub fn set_max_response_size(&mut self, max_response_size: usize) {
+        self.max_response_size = max_response_size;
+    }
+
+    /// Get the largest packet or response, in bytes, accepted from the server.
+    pub fn max_response_size(&self) -> usize {
+        self.max_response_size
+    }
+
+    /// Get the number of bytes written to the stream.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Get the number of bytes read from the stream.
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+
     /// Get the underlying TCP stream.
     pub fn stream(&self) -> &TcpStream {
-        &self.stream
+        self.stream.tcp()
     }
 
-    /// Get a mutable reference to the underlying TCP stream.
-    pub fn stream_mut(&mut self) -> &mut TcpStream {
+    /// Get a mutable reference to the transport packets are exchanged over.
+    pub fn stream_mut(&mut self) -> &mut Transport {
         &mut self.stream
     }
 
+    /// Check whether the connection is encrypted with TLS.
+    pub fn is_tls(&self) -> bool {
+        self.stream.is_tls()
+    }
+
+    /// Send an out-of-band break (TCP urgent data).
+    pub fn send_oob_break(&self) -> Result<()> {
+        Ok(self.stream.send_oob_break()?)
+    }
+
+    /// Check whether the server closed the connection, without reading
+    /// from it.
+    pub fn is_closed(&self) -> bool {
+        self.stream.is_closed()
+    }
+
     /// Read a packet from the stream.
+    ///
+    /// Cancellation safe: data read before the future is dropped stays
+    /// buffered for the next read.
     pub async fn read_packet(&mut self) -> Result<Packet> {
-        // Read until we have at least the header
-        while self.partial_buf.len() < HEADER_SIZE {
-            let mut buf = [0u8; 4096];
-            let n = self.stream.read(&mut buf).await?;
+        loop {
+            if let Some(packet) = self.decode_buffered()? {
+                return Ok(packet);
+            }
+            self.reserve_read_space();
+            let n = self.stream.read_buf(&mut self.partial_buf).await?;
             if n == 0 {
                 return Err(Error::ConnectionClosed);
             }
-            self.partial_buf.extend_from_slice(&buf[..n]);
+            self.bytes_received += n as u64;
         }
+    }
 
-        // Parse packet length
-        let packet_len = if self.use_large_sdu {
-            u32::from_be_bytes([
-                self.partial_buf[0],
-                self.partial_buf[1],
-                self.partial_buf[2],
-                self.partial_buf[3],
-            ]) as usize
-        } else {
-            u16::from_be_bytes([self.partial_buf[0], self.partial_buf[1]]) as usize
-        };
-        // Read until we have the full packet
-        while self.partial_buf.len() < packet_len {
-            let mut buf = [0u8; 4096];
-            let n = self.stream.read(&mut buf).await?;
-            if n == 0 {
-                return Err(Error::ConnectionClosed);
+    /// Read a packet only if one has already arrived, without waiting.
+    ///
+    /// A closed connection is not reported here, since the packets already
+    /// read may complete the response; the next `read_packet` reports it.
+    pub fn try_read_packet(&mut self) -> Result<Option<Packet>> {
+        loop {
+            if let Some(packet) = self.decode_buffered()? {
+                return Ok(Some(packet));
+            }
+            self.reserve_read_space();
+            // Reads are cancel safe, so an unfinished read loses no data
+            match self.stream.read_buf(&mut self.partial_buf).now_or_never() {
+                None | Some(Ok(0)) => return Ok(None),
+                Some(Ok(n)) => self.bytes_received += n as u64,
+                Some(Err(e)) => return Err(e.into()),
             }
-            self.partial_buf.extend_from_slice(&buf[..n]);
         }
+    }
 
-        // Extract the packet
-        let packet_data = self.partial_buf.split_to(packet_len);
-        let packet_type = packet_data[4];
-        let packet_flags = packet_data[5];
-        let payload = Bytes::copy_from_slice(&packet_data[HEADER_SIZE..]);
+    /// Make room to read up to a full packet of the negotiated SDU.
+    fn reserve_read_space(&mut self) {
+        self.partial_buf
+            .reserve(self.sdu as usize + PACKET_HEADER_SIZE);
+    }
 
-        Ok(Packet {
-            packet_type,
-            packet_flags,
-            payload,
-        })
+    /// Split the next complete packet off the buffered data.
+    fn decode_buffered(&mut self) -> Result<Option<Packet>> {
+        check_packet_length(
+            &self.partial_buf,
+            self.use_large_sdu,
+            self.max_response_size,
+        )?;
+        decode_packet(&mut self.partial_buf, self.use_large_sdu)
     }
 
     /// Write a packet to the stream.
@@ -172,71 +368,95 @@ impl PacketStream {
         // if bytes.len() > 64 {
         // }
         // eprintln!("[DEBUG] Sending  packet type {} with size {}", packet.packet_type, bytes.len());
-        self.stream.write_all(&bytes).await?;
-        self.stream.flush().await?;
-        Ok(())
+        self.queue_bytes(bytes.into());
+        self.flush().await
     }
 
     /// Send a DATA packet (legacy - use send_data_message for new code).
     pub async fn send_data(&mut self, data: Bytes, data_flags: u16) -> Result<()> {
-        let mut payload = WriteBuffer::with_capacity(data.len() + 2);
-        payload.write_u16_be(data_flags);
-        payload.write_bytes(&data);
-        let packet = Packet::new(TNS_PACKET_TYPE_DATA, payload.freeze());
-        self.write_packet(&packet).await
+        self.queue_data(&data, data_flags);
+        self.flush().await
     }
 
     /// Send a message as a specific packet type (zero-copy).
     ///
     /// Uses the Message trait to calculate size and serialize in a single allocation.
     pub async fn send_message<M: Message>(&mut self, packet_type: u8, msg: &M) -> Result<()> {
-        let payload_size = msg.wire_size();
-        let total_size = HEADER_SIZE + payload_size;
-
-        let mut buf = Vec::with_capacity(total_size);
-
-        // Write packet header
-        write_packet_header(&mut buf, packet_type, 0, total_size, self.use_large_sdu);
-
-        // Write message content
-        msg.write_to(&mut buf)?;
-
-        self.stream.write_all(&buf).await?;
-        self.stream.flush().await?;
-        Ok(())
+        self.queue_message(packet_type, msg)?;
+        self.flush().await
     }
 
     /// Send a DATA message (zero-copy).
     ///
     /// Uses the DataMessage trait to include data_flags and serialize in a single allocation.
+    /// Messages larger than the SDU are split across several DATA packets.
     pub async fn send_data_message<M: DataMessage>(&mut self, msg: &M) -> Result<()> {
-        let payload_size = msg.data_wire_size();
-        let total_size = HEADER_SIZE + payload_size;
+        // eprintln!("[DEBUG] Sending DATA message with size {}", buf.len());
+        self.queue_data_message(msg)?;
+        self.flush().await
+    }
 
-        let mut buf = Vec::with_capacity(total_size);
+    /// Queue a DATA packet, written by the next `flush`.
+    pub fn queue_data(&mut self, data: &[u8], data_flags: u16) {
+        let mut payload = WriteBuffer::with_capacity(data.len() + 2);
+        payload.write_u16_be(data_flags);
+        payload.write_bytes(data);
+        let packet = Packet::new(TNS_PACKET_TYPE_DATA, payload.freeze());
+        self.queue_bytes(packet.to_bytes(self.use_large_sdu).into());
+    }
 
-        // Write packet header
-        write_packet_header(
-            &mut buf,
-            TNS_PACKET_TYPE_DATA,
-            0,
-            total_size,
-            self.use_large_sdu,
-        );
+    /// Queue a message as a specific packet type, written by the next
+    /// `flush`.
+    pub fn queue_message<M: Message>(&mut self, packet_type: u8, msg: &M) -> Result<()> {
+        self.queue_message_with_flags(packet_type, 0, msg)
+    }
 
-        // Write data flags
-        buf.extend_from_slice(&msg.data_flags().to_be_bytes());
+    /// Queue a message as a packet with the given type and flags, written
+    /// by the next `flush`.
+    pub fn queue_message_with_flags<M: Message>(
+        &mut self,
+        packet_type: u8,
+        packet_flags: u8,
+        msg: &M,
+    ) -> Result<()> {
+        let buf = encode_message(packet_type, packet_flags, msg, self.use_large_sdu)?;
+        self.queue_bytes(buf);
+        Ok(())
+    }
 
-        // Write message content
-        msg.write_to(&mut buf)?;
-        // eprintln!("[DEBUG] Sending DATA message with size {}", buf.len());
-        self.stream.write_all(&buf).await?;
-        self.stream.flush().await?;
+    /// Queue a DATA message, written by the next `flush`.
+    ///
+    /// Messages sent back to back without waiting for a reply are queued
+    /// and flushed once, so they share a write instead of taking one each.
+    pub fn queue_data_message<M: DataMessage>(&mut self, msg: &M) -> Result<()> {
+        let buf = encode_data_message(msg, self.sdu as usize, self.use_large_sdu)?;
+        self.queue_bytes(buf);
         Ok(())
     }
 
-    /// Flush the stream.
+    fn queue_bytes(&mut self, bytes: Vec<u8>) {
+        if self.write_buf.is_empty() {
+            self.write_buf = bytes;
+        } else {
+            self.write_buf.extend_from_slice(&bytes);
+        }
+    }
+
+    /// Write the queued packets and flush the stream.
+    ///
+    /// Cancellation safe: if the future is dropped, the bytes not written
+    /// yet stay queued and the next `flush` writes them.
     pub async fn flush(&mut self) -> Result<()> {
+        while self.written < self.write_buf.len() {
+            let n = self.stream.write(&self.write_buf[self.written..]).await?;
+            if n == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+            self.written += n;
+            self.bytes_sent += n as u64;
+        }
+        self.write_buf.clear();
+        self.written = 0;
         self.stream.flush().await?;
         Ok(())
     }
@@ -249,12 +469,16 @@ pub struct Capabilities {
     pub protocol_version: u16,
     /// SDU size.
     pub sdu: u32,
-    /// Whether OOB (out-of-band) is supported.
+    /// Whether OOB (out-of-band) breaks are used.
     pub supports_oob: bool,
+    /// Whether the server asked for OOB breaks to be checked after ACCEPT.
+    pub supports_oob_check: bool,
     /// Whether end-of-response is supported.
     pub supports_end_of_response: bool,
     /// Whether fast auth is supported (Oracle 23ai+).
     pub supports_fast_auth: bool,
+    /// Whether the server accepts pipelined requests (Oracle 23ai+).
+    pub supports_pipelining: bool,
     /// TTC field version (for parsing - may differ from server's version for FastAuth).
     pub ttc_field_version: u8,
     /// Server's actual TTC field version (determines what fields server sends).
@@ -263,6 +487,8 @@ pub struct Capabilities {
     pub compile_caps: Vec<u8>,
     /// Runtime capabilities.
     pub runtime_caps: Vec<u8>,
+    /// Largest VARCHAR2/RAW value, in bytes, the server accepts in SQL.
+    pub max_string_size: u32,
 }
 
 impl Capabilities {
@@ -323,27 +549,32 @@ impl Capabilities {
         Self {
             protocol_version: 0,
             sdu: TNS_SDU_DEFAULT,
-            // For async implementations (tokio), OOB is not supported
-            // Python's asyncio implementation also disables OOB
+            // Enabled after ACCEPT when the client asked for OOB breaks
+            // (`ConnectParams::with_oob_break`) and the server accepts them
             supports_oob: false,
+            supports_oob_check: false,
             supports_end_of_response: false,
             supports_fast_auth: false,
+            supports_pipelining: false,
             // Initialize to match compile_caps so adjust_for_server_caps works correctly
             ttc_field_version,
             // Will be set when we receive server caps
             server_ttc_field_version: 0,
             compile_caps,
             runtime_caps,
+            max_string_size: TNS_MAX_STRING_SIZE,
         }
     }
 
     /// Adjust capabilities based on protocol negotiation.
-    pub fn adjust_for_protocol(&mut self, version: u16, _options: u16, flags2: u32) {
+    pub fn adjust_for_protocol(&mut self, version: u16, options: u16, flags2: u32) {
         self.protocol_version = version;
 
-        // Note: For async implementations (tokio), OOB remains disabled
-        // We don't enable it from flags2 because we didn't advertise it in CONNECT
-        // and we don't want to follow through with the OOB handshake
+        // OOB breaks, if the server can receive them
+        self.supports_oob = (options & TNS_GSO_CAN_RECV_ATTENTION) != 0;
+        self.supports_oob_check = self.supports_oob
+            && version >= TNS_VERSION_MIN_OOB_CHECK
+            && (flags2 & TNS_ACCEPT_FLAG_CHECK_OOB) != 0;
 
         if version >= TNS_VERSION_MIN_END_OF_RESPONSE {
             self.supports_end_of_response = (flags2 & TNS_ACCEPT_FLAG_HAS_END_OF_RESPONSE) != 0;
@@ -377,10 +608,17 @@ impl Capabilities {
             }
         }
 
+        self.supports_pipelining = server_compile_caps
+            .get(TNS_CCAP_TTC5)
+            .is_some_and(|&ttc5| ttc5 & TNS_CCAP_PIPELINING_SUPPORT != 0);
+
         // Check for 32K string support from runtime caps
-        if !server_runtime_caps.is_empty() && server_runtime_caps.len() > TNS_RCAP_TTC {
-            // The max_string_size would be 32767 if TNS_RCAP_TTC_32K is set, else 4000
-            // We don't store max_string_size currently, but we could add it
+        if let Some(&ttc) = server_runtime_caps.get(TNS_RCAP_TTC) {
+            self.max_string_size = if ttc & TNS_RCAP_TTC_32K != 0 {
+                TNS_MAX_STRING_SIZE_EXTENDED
+            } else {
+                TNS_MAX_STRING_SIZE
+            };
         }
     }
 }
@@ -390,3 +628,209 @@ impl Default for Capabilities {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_string_size_from_runtime_caps() {
+        let mut caps = Capabilities::new();
+        assert_eq!(caps.max_string_size, TNS_MAX_STRING_SIZE);
+
+        let mut runtime_caps = vec![0u8; TNS_RCAP_MAX];
+        runtime_caps[TNS_RCAP_TTC] = TNS_RCAP_TTC_32K;
+        caps.adjust_for_server_caps(&[], &runtime_caps);
+        assert_eq!(caps.max_string_size, TNS_MAX_STRING_SIZE_EXTENDED);
+
+        caps.adjust_for_server_caps(&[], &[0u8; TNS_RCAP_MAX]);
+        assert_eq!(caps.max_string_size, TNS_MAX_STRING_SIZE);
+    }
+
+    #[test]
+    fn test_oob_from_accept() {
+        let mut caps = Capabilities::new();
+        caps.adjust_for_protocol(319, TNS_GSO_DONT_CARE, TNS_ACCEPT_FLAG_CHECK_OOB);
+        assert!(!caps.supports_oob);
+        assert!(!caps.supports_oob_check);
+
+        let options = TNS_GSO_DONT_CARE | TNS_GSO_CAN_RECV_ATTENTION;
+        caps.adjust_for_protocol(319, options, 0);
+        assert!(caps.supports_oob);
+        assert!(!caps.supports_oob_check);
+
+        caps.adjust_for_protocol(319, options, TNS_ACCEPT_FLAG_CHECK_OOB);
+        assert!(caps.supports_oob_check);
+        caps.adjust_for_protocol(317, options, TNS_ACCEPT_FLAG_CHECK_OOB);
+        assert!(!caps.supports_oob_check);
+    }
+
+    #[test]
+    fn test_pipelining_from_compile_caps() {
+        let mut caps = Capabilities::new();
+        assert!(!caps.supports_pipelining);
+
+        let mut compile_caps = vec![0u8; TNS_CCAP_MAX];
+        compile_caps[TNS_CCAP_TTC5] = TNS_CCAP_PIPELINING_SUPPORT;
+        caps.adjust_for_server_caps(&compile_caps, &[]);
+        assert!(caps.supports_pipelining);
+
+        caps.adjust_for_server_caps(&compile_caps[..TNS_CCAP_TTC5], &[]);
+        assert!(!caps.supports_pipelining);
+    }
+
+    #[test]
+    fn test_check_packet_length() {
+        let header = [0x00, 0x00, 0x01, 0x00, TNS_PACKET_TYPE_DATA, 0, 0, 0];
+        // 256 bytes with a 4-byte length, 0 with a 2-byte one
+        assert!(matches!(
+            check_packet_length(&header, true, 255),
+            Err(Error::ResponseTooLarge {
+                size: 256,
+                max: 255
+            })
+        ));
+        assert!(check_packet_length(&header, true, 256).is_ok());
+        assert!(check_packet_length(&header, false, 255).is_ok());
+        // Not enough of the header yet
+        assert!(check_packet_length(&header[..3], true, 0).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_read_packet_rejects_oversized_packet() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap());
+        let (client, (mut server, _)) =
+            tokio::join!(client, async { listener.accept().await.unwrap() });
+        let mut stream = PacketStream::new(client.unwrap());
+        stream.set_max_response_size(64);
+
+        // Nothing has arrived yet
+        assert!(stream.try_read_packet().unwrap().is_none());
+
+        let small = Packet::new(TNS_PACKET_TYPE_DATA, Bytes::from_static(&[0, 0, 1]));
+        server.write_all(&small.to_bytes(false)).await.unwrap();
+        let packet = stream.read_packet().await.unwrap();
+        assert_eq!(&packet.payload[..], &[0, 0, 1]);
+
+        // Only the header of the large packet is needed to reject it
+        let large = Packet::new(TNS_PACKET_TYPE_DATA, Bytes::from(vec![0u8; 100]));
+        server
+            .write_all(&large.to_bytes(false)[..HEADER_SIZE])
+            .await
+            .unwrap();
+        assert!(matches!(
+            stream.read_packet().await,
+            Err(Error::ResponseTooLarge { size: 108, max: 64 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_packet_zero_copy() {
+        let first = Packet::new(TNS_PACKET_TYPE_DATA, Bytes::from_static(&[0, 0, 1, 2]));
+        let second = Packet::new(TNS_PACKET_TYPE_MARKER, Bytes::from_static(&[1, 0, 2]));
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&first.to_bytes(true));
+        buf.extend_from_slice(&second.to_bytes(true));
+        let start = buf.as_ptr();
+
+        // Payloads are slices of the read buffer
+        let packet = decode_packet(&mut buf, true).unwrap().unwrap();
+        assert_eq!(&packet.payload[..], &[0, 0, 1, 2]);
+        assert_eq!(packet.payload.as_ptr(), start.wrapping_add(HEADER_SIZE));
+        let packet = decode_packet(&mut buf, true).unwrap().unwrap();
+        assert_eq!(packet.packet_type, TNS_PACKET_TYPE_MARKER);
+        assert_eq!(
+            packet.payload.as_ptr(),
+            start.wrapping_add(2 * HEADER_SIZE + 4)
+        );
+        assert!(decode_packet(&mut buf, true).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_queued_packets_written_on_flush() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap());
+        let (client, (server, _)) =
+            tokio::join!(client, async { listener.accept().await.unwrap() });
+        let mut client = PacketStream::new(client.unwrap());
+        let mut server = PacketStream::new(server);
+
+        client.queue_data(&[1], 0);
+        client.queue_data(&[2], TNS_DATA_FLAGS_END_OF_REQUEST);
+        tokio::task::yield_now().await;
+        assert!(server.try_read_packet().unwrap().is_none());
+
+        client.flush().await.unwrap();
+        let first = server.read_packet().await.unwrap();
+        assert_eq!(&first.payload[..], &[0, 0, 1]);
+        let second = server.read_packet().await.unwrap();
+        assert_eq!(&second.payload[..], &[0x08, 0, 2]);
+
+        // Two packets of an 8-byte header and 3 bytes each
+        assert_eq!(client.bytes_sent(), 22);
+        assert_eq!(server.bytes_received(), 22);
+        assert_eq!((client.bytes_received(), server.bytes_sent()), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_flush_resumes_after_cancel() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap());
+        let (client, (server, _)) =
+            tokio::join!(client, async { listener.accept().await.unwrap() });
+        let mut client = PacketStream::new(client.unwrap());
+        let mut server = PacketStream::new(server);
+
+        // More than the socket buffers hold while the peer is not reading
+        let content: Vec<u8> = (0..16 * 1024 * 1024).map(|i| i as u8).collect();
+        for chunk in content.chunks(8000) {
+            client.queue_data(chunk, 0);
+        }
+        let cut_short =
+            tokio::time::timeout(std::time::Duration::from_millis(50), client.flush()).await;
+        assert!(cut_short.is_err());
+
+        let reader = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while received.len() < 16 * 1024 * 1024 {
+                let packet = server.read_packet().await.unwrap();
+                received.extend_from_slice(&packet.payload[2..]);
+            }
+            received
+        });
+        client.flush().await.unwrap();
+        assert_eq!(reader.await.unwrap(), content);
+    }
+
+    #[test]
+    fn test_frame_data_packets() {
+        let content: Vec<u8> = (0..25u8).collect();
+        let mut buf = BytesMut::from(&frame_data_packets(&content, 0, 20, true)[..]);
+
+        // 10 bytes of content per 20-byte packet
+        let mut received = Vec::new();
+        let mut packets = 0;
+        while let Some(packet) = decode_packet(&mut buf, true).unwrap() {
+            assert_eq!(packet.packet_type, TNS_PACKET_TYPE_DATA);
+            assert!(packet.total_size() <= 20);
+            assert_eq!(&packet.payload[..2], &[0, 0]);
+            received.extend_from_slice(&packet.payload[2..]);
+            packets += 1;
+        }
+        assert_eq!(packets, 3);
+        assert_eq!(received, content);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_frame_data_packets_small_sdu_header() {
+        let content = vec![1u8; 100];
+        let buf = frame_data_packets(&content, TNS_DATA_FLAGS_END_OF_RESPONSE, 60, false);
+        // 2-byte length, checksum, type, flags, header checksum, data flags
+        assert_eq!(&buf[..2], &60u16.to_be_bytes());
+        assert_eq!(buf[4], TNS_PACKET_TYPE_DATA);
+        assert_eq!(&buf[8..10], &TNS_DATA_FLAGS_END_OF_RESPONSE.to_be_bytes());
+        assert_eq!(buf.len(), 100 + 2 * (HEADER_SIZE + 2));
+    }
+}
@@ -0,0 +1,423 @@
+//! Record TNS packets from a real server and replay them in tests.
+//!
+//! A `Recorder` is a TCP proxy between a client and a server that notes
+//! every packet passing through it. The capture can be saved with
+//! `format_capture`, in the format python-oracledb prints with
+//! `PYO_DEBUG_PACKETS=1`, so its captures can be replayed too.
+//!
+//! A `MockServer` plays the server's side of a capture back: it reads each
+//! packet the client sent in the capture from its client, checking only its
+//! packet type, and answers with the packets the server sent. Requests
+//! carry session keys and sequence numbers that change from run to run, so
+//! their contents are not compared.
+//!
+//! Logging on cannot be replayed, since the client's session key is random
+//! and the server's reply is checked against it. Replay the part of a
+//! capture after the logon (`after_login`) to a connection from
+//! `MockServer::connect` instead.
+//!
+//! Only plain TCP connections can be recorded, and out-of-band breaks are
+//! not captured.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use oracle_thin_rs::protocol::packet::Capabilities;
+//! use oracle_thin_rs::protocol::replay::{after_login, MockServer, Recorder};
+//! use oracle_thin_rs::protocol::wire_compat::{format_capture, parse_capture};
+//! use oracle_thin_rs::Connection;
+//!
+//! # async fn example() -> oracle_thin_rs::Result<()> {
+//! // Record a session against a real server
+//! let recorder = Recorder::start("db.example.com:1521").await?;
+//! let conn_str = format!("{}/FREEPDB1", recorder.addr());
+//! let mut conn = Connection::connect(&conn_str, "scott", "tiger").await?;
+//! conn.ping().await?;
+//! drop(conn);
+//! let capture = recorder.finish().await?;
+//! std::fs::write("ping.log", format_capture(&capture))?;
+//!
+//! // Replay it in a test, without the database
+//! let capture = parse_capture(&std::fs::read_to_string("ping.log")?)?;
+//! let server = MockServer::start(after_login(&capture).to_vec()).await?;
+//! let mut caps = Capabilities::new();
+//! caps.protocol_version = 319;
+//! let mut conn = server.connect(caps).await?;
+//! conn.ping().await?;
+//! drop(conn);
+//! server.finish().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use bytes::BytesMut;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{lookup_host, TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+use crate::connection::Connection;
+use crate::error::{Error, Result};
+use crate::protocol::auth::SessionData;
+use crate::protocol::constants::*;
+use crate::protocol::packet::{Capabilities, PacketStream, HEADER_SIZE};
+use crate::protocol::wire_compat::{packet_length, CapturedPacket, Direction};
+
+/// A proxy recording the packets of one connection.
+pub struct Recorder {
+    addr: SocketAddr,
+    task: JoinHandle<Result<Vec<CapturedPacket>>>,
+}
+
+impl Recorder {
+    /// Listen on a local port, and forward the first connection made to it
+    /// to `upstream`.
+    pub async fn start(upstream: impl ToSocketAddrs) -> Result<Self> {
+        let upstream: Vec<SocketAddr> = lookup_host(upstream).await?.collect();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            let (client, _) = listener.accept().await?;
+            let server = TcpStream::connect(upstream.as_slice()).await?;
+            client.set_nodelay(true)?;
+            server.set_nodelay(true)?;
+
+            let packets = Mutex::new(Vec::new());
+            let large_sdu = AtomicBool::new(false);
+            let (client_read, client_write) = client.into_split();
+            let (server_read, server_write) = server.into_split();
+            let (sent, received) = tokio::join!(
+                forward(
+                    client_read,
+                    server_write,
+                    Direction::Sent,
+                    &packets,
+                    &large_sdu
+                ),
+                forward(
+                    server_read,
+                    client_write,
+                    Direction::Received,
+                    &packets,
+                    &large_sdu
+                ),
+            );
+            sent?;
+            received?;
+            Ok(packets.into_inner().unwrap())
+        });
+        Ok(Self { addr, task })
+    }
+
+    /// Get the local address clients connect to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Wait for both sides to close the connection, and get the packets
+    /// exchanged, in the order they were forwarded.
+    pub async fn finish(self) -> Result<Vec<CapturedPacket>> {
+        finish(self.task).await
+    }
+}
+
+/// Forward whole packets from one side to the other, noting each one.
+async fn forward(
+    mut from: OwnedReadHalf,
+    mut to: OwnedWriteHalf,
+    direction: Direction,
+    packets: &Mutex<Vec<CapturedPacket>>,
+    large_sdu: &AtomicBool,
+) -> Result<()> {
+    let mut buf = BytesMut::new();
+    while from.read_buf(&mut buf).await? > 0 {
+        while let Some(packet) = split_packet(&mut buf, large_sdu.load(Ordering::SeqCst))? {
+            // Both sides switch to 4-byte lengths after the ACCEPT
+            if direction == Direction::Received && accepts_large_sdu(&packet) {
+                large_sdu.store(true, Ordering::SeqCst);
+            }
+            packets.lock().unwrap().push(CapturedPacket {
+                direction,
+                bytes: packet.clone(),
+            });
+            to.write_all(&packet).await?;
+        }
+    }
+    to.write_all(&buf).await?;
+    let _ = to.shutdown().await;
+    Ok(())
+}
+
+/// A server replaying a capture to one client.
+pub struct MockServer {
+    addr: SocketAddr,
+    task: JoinHandle<Result<()>>,
+}
+
+impl MockServer {
+    /// Listen on a local port, and replay the server's side of `capture` to
+    /// the first connection made to it.
+    pub async fn start(capture: Vec<CapturedPacket>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await?;
+            tcp.set_nodelay(true)?;
+            serve(tcp, &capture).await
+        });
+        Ok(Self { addr, task })
+    }
+
+    /// Get the local address to connect to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Open a connection to the server as if it were logged on, with the
+    /// capabilities the recorded session negotiated.
+    ///
+    /// Set at least `protocol_version`, which decides the packet header
+    /// format, and `server_ttc_field_version`, which decides the fields of
+    /// the responses.
+    pub async fn connect(&self, caps: Capabilities) -> Result<Connection> {
+        let tcp = TcpStream::connect(self.addr).await?;
+        tcp.set_nodelay(true)?;
+        let mut stream = PacketStream::new(tcp);
+        stream.set_large_sdu(caps.protocol_version >= TNS_VERSION_MIN_LARGE_SDU);
+        stream.set_sdu(caps.sdu);
+        Ok(Connection::from_parts(stream, caps, SessionData::default()))
+    }
+
+    /// Wait for the whole capture to be replayed, and report any packet the
+    /// client sent that did not match it.
+    pub async fn finish(self) -> Result<()> {
+        finish(self.task).await
+    }
+}
+
+/// Replay the server's side of a capture.
+async fn serve(mut tcp: TcpStream, capture: &[CapturedPacket]) -> Result<()> {
+    let mut buf = BytesMut::new();
+    for (index, expected) in capture.iter().enumerate() {
+        if expected.direction == Direction::Received {
+            tcp.write_all(&expected.bytes).await?;
+            continue;
+        }
+
+        let large_sdu = packet_length(&expected.bytes, true) == Some(expected.bytes.len());
+        let packet = loop {
+            if let Some(packet) = split_packet(&mut buf, large_sdu)? {
+                break packet;
+            }
+            if tcp.read_buf(&mut buf).await? == 0 {
+                return Err(Error::protocol(format!(
+                    "client closed the connection before packet {} of the capture",
+                    index + 1
+                )));
+            }
+        };
+        if packet.get(4) != expected.bytes.get(4) {
+            return Err(Error::protocol(format!(
+                "packet {} of the capture: client sent packet type {:?}, expected {:?}",
+                index + 1,
+                packet.get(4),
+                expected.bytes.get(4)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Get the part of a capture after the logon completed: the packets after
+/// the server's reply to the last authentication request.
+///
+/// Captures without a logon are returned whole.
+pub fn after_login(capture: &[CapturedPacket]) -> &[CapturedPacket] {
+    let Some(auth) = capture.iter().rposition(is_auth_request) else {
+        return capture;
+    };
+    let end = capture[auth + 1..]
+        .iter()
+        .position(|p| p.direction == Direction::Sent)
+        .map_or(capture.len(), |i| auth + 1 + i);
+    &capture[end..]
+}
+
+/// Whether a packet is the client's second authentication request.
+fn is_auth_request(packet: &CapturedPacket) -> bool {
+    let bytes = &packet.bytes;
+    packet.direction == Direction::Sent
+        && bytes.get(4) == Some(&TNS_PACKET_TYPE_DATA)
+        && bytes.get(HEADER_SIZE + 2) == Some(&TNS_MSG_TYPE_FUNCTION)
+        && bytes.get(HEADER_SIZE + 3) == Some(&TNS_FUNC_AUTH_PHASE_TWO)
+}
+
+/// Whether a packet is an ACCEPT switching to 4-byte packet lengths.
+fn accepts_large_sdu(packet: &[u8]) -> bool {
+    packet.get(4) == Some(&TNS_PACKET_TYPE_ACCEPT)
+        && packet
+            .get(HEADER_SIZE..HEADER_SIZE + 2)
+            .is_some_and(|v| u16::from_be_bytes([v[0], v[1]]) >= TNS_VERSION_MIN_LARGE_SDU)
+}
+
+/// Split the first whole packet off the buffer, if it has arrived.
+fn split_packet(buf: &mut BytesMut, large_sdu: bool) -> Result<Option<Vec<u8>>> {
+    let Some(len) = packet_length(buf, large_sdu) else {
+        return Ok(None);
+    };
+    if len < HEADER_SIZE {
+        return Err(Error::protocol(format!("invalid packet length {}", len)));
+    }
+    if buf.len() < len {
+        return Ok(None);
+    }
+    Ok(Some(buf.split_to(len).to_vec()))
+}
+
+/// Wait for a recorder or server task.
+async fn finish<T>(task: JoinHandle<Result<T>>) -> Result<T> {
+    task.await
+        .map_err(|e| Error::protocol(format!("replay task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::wire_compat::{format_capture, parse_capture};
+
+    /// Build a packet with a 2- or 4-byte length.
+    fn packet(packet_type: u8, payload: &[u8], large_sdu: bool) -> Vec<u8> {
+        let len = payload.len() + HEADER_SIZE;
+        let mut packet = if large_sdu {
+            (len as u32).to_be_bytes().to_vec()
+        } else {
+            let mut header = (len as u16).to_be_bytes().to_vec();
+            header.extend_from_slice(&[0, 0]);
+            header
+        };
+        packet.extend_from_slice(&[packet_type, 0, 0, 0]);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    fn exchange() -> Vec<Vec<u8>> {
+        let accept = TNS_VERSION_MIN_END_OF_RESPONSE.to_be_bytes();
+        vec![
+            packet(TNS_PACKET_TYPE_CONNECT, b"(DESCRIPTION=)", false),
+            packet(TNS_PACKET_TYPE_ACCEPT, &accept, false),
+            packet(TNS_PACKET_TYPE_DATA, &[0, 0, TNS_MSG_TYPE_FUNCTION], true),
+            packet(TNS_PACKET_TYPE_DATA, &[0, 0, TNS_MSG_TYPE_STATUS], true),
+        ]
+    }
+
+    /// Send the client's packets of `exchange` and check the replies.
+    async fn run_client(addr: SocketAddr) {
+        let packets = exchange();
+        let mut tcp = TcpStream::connect(addr).await.unwrap();
+        for pair in packets.chunks(2) {
+            tcp.write_all(&pair[0]).await.unwrap();
+            let mut reply = vec![0u8; pair[1].len()];
+            tcp.read_exact(&mut reply).await.unwrap();
+            assert_eq!(reply, pair[1]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut tcp, _) = listener.accept().await.unwrap();
+            for pair in exchange().chunks(2) {
+                let mut request = vec![0u8; pair[0].len()];
+                tcp.read_exact(&mut request).await.unwrap();
+                assert_eq!(request, pair[0]);
+                tcp.write_all(&pair[1]).await.unwrap();
+            }
+        });
+
+        let recorder = Recorder::start(upstream).await.unwrap();
+        run_client(recorder.addr()).await;
+        let capture = recorder.finish().await.unwrap();
+        server.await.unwrap();
+
+        let directions: Vec<Direction> = capture.iter().map(|p| p.direction).collect();
+        assert_eq!(
+            directions,
+            [
+                Direction::Sent,
+                Direction::Received,
+                Direction::Sent,
+                Direction::Received
+            ]
+        );
+        let bytes: Vec<Vec<u8>> = capture.iter().map(|p| p.bytes.clone()).collect();
+        assert_eq!(bytes, exchange());
+        let capture = parse_capture(&format_capture(&capture)).unwrap();
+
+        let server = MockServer::start(capture).await.unwrap();
+        run_client(server.addr()).await;
+        server.finish().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_mismatch() {
+        let capture = vec![CapturedPacket {
+            direction: Direction::Sent,
+            bytes: packet(TNS_PACKET_TYPE_DATA, &[0, 0], false),
+        }];
+        let server = MockServer::start(capture.clone()).await.unwrap();
+        let mut tcp = TcpStream::connect(server.addr()).await.unwrap();
+        let marker = packet(
+            TNS_PACKET_TYPE_MARKER,
+            &[1, 0, TNS_MARKER_TYPE_BREAK],
+            false,
+        );
+        tcp.write_all(&marker).await.unwrap();
+        assert!(matches!(server.finish().await, Err(Error::Protocol { .. })));
+
+        // A client leaving early is reported too
+        let server = MockServer::start(capture).await.unwrap();
+        drop(TcpStream::connect(server.addr()).await.unwrap());
+        assert!(server.finish().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_after_login() {
+        let sent = |payload: &[u8]| CapturedPacket {
+            direction: Direction::Sent,
+            bytes: packet(TNS_PACKET_TYPE_DATA, payload, true),
+        };
+        let received = |payload: &[u8]| CapturedPacket {
+            direction: Direction::Received,
+            bytes: packet(TNS_PACKET_TYPE_DATA, payload, true),
+        };
+        let function = |code| [0, 0, TNS_MSG_TYPE_FUNCTION, code, 1];
+        let status = [0, 0, TNS_MSG_TYPE_STATUS, 0, 0];
+        let capture = vec![
+            sent(&function(TNS_FUNC_AUTH_PHASE_ONE)),
+            received(&status),
+            sent(&function(TNS_FUNC_AUTH_PHASE_TWO)),
+            received(&[0, 0]),
+            received(&status),
+            sent(&function(TNS_FUNC_PING)),
+            received(&status),
+        ];
+        assert_eq!(after_login(&capture), &capture[5..]);
+        assert_eq!(after_login(&capture[5..]), &capture[5..]);
+
+        let server = MockServer::start(after_login(&capture).to_vec())
+            .await
+            .unwrap();
+        let mut caps = Capabilities::new();
+        caps.protocol_version = TNS_VERSION_MIN_END_OF_RESPONSE;
+        let mut conn = server.connect(caps).await.unwrap();
+        conn.ping().await.unwrap();
+        drop(conn);
+        server.finish().await.unwrap();
+    }
+}
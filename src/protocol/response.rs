@@ -1,12 +1,18 @@
 //! Response parsing for query execution.
+//!
+//! All reads go through `ReadBuffer`, so malformed responses surface as
+//! errors rather than panics.
+
+#![cfg_attr(not(test), deny(clippy::indexing_slicing))]
 
 use std::sync::Arc;
 
 use crate::error::{Error, Result};
 use crate::protocol::buffer::ReadBuffer;
 use crate::protocol::constants::*;
-use crate::protocol::decode::{decode_oracle_date, decode_oracle_number};
-use crate::protocol::types::{ColumnInfo, ColumnMetadata, OracleValue, Row};
+use crate::protocol::decode::decode_oracle_integer;
+use crate::protocol::messages::LobOpMessage;
+use crate::protocol::types::{Cell, ColumnInfo, ColumnMetadata, Lob, OracleValue, Row, RowId};
 
 /// Information extracted from error/end-of-call response.
 #[derive(Debug, Default)]
@@ -17,8 +23,100 @@ pub struct ErrorInfo {
     pub cursor_id: u16,
     /// Row count for queries/DML.
     pub row_count: u64,
+    /// Position in the statement text at which an error was detected.
+    pub error_position: u16,
+    /// ROWID of the last row affected by a DML statement.
+    pub rowid: Option<RowId>,
     /// Error message (if any).
     pub message: Option<String>,
+    /// Per-row errors of an array execution in batch error mode.
+    pub batch_errors: Vec<BatchError>,
+    /// Warnings raised by the call, which still succeeded.
+    pub warnings: Vec<Warning>,
+    /// End-of-call status flags, if the call's status was received.
+    pub call_status: Option<u32>,
+    /// Logical transaction ID sent by the server (Transaction Guard).
+    pub ltxid: Option<Vec<u8>>,
+    /// Application Continuity replay context sent by the server.
+    pub replay_context: Option<ReplayContext>,
+}
+
+impl ErrorInfo {
+    /// Build the Oracle error reported by this end-of-call status.
+    ///
+    /// ORA-01013 (call cancelled) becomes `Error::Cancelled`.
+    pub fn to_error(&self) -> Error {
+        if self.error_num == TNS_ERR_CALL_CANCELLED {
+            return Error::Cancelled { context: None };
+        }
+        Error::Oracle {
+            code: self.error_num,
+            message: self.message.clone().unwrap_or_default(),
+            offset: self.error_position as u32,
+            row_count: self.row_count,
+            context: None,
+        }
+    }
+}
+
+/// Application Continuity replay context, sent by services with
+/// Application Continuity enabled.
+///
+/// The context is opaque; it describes the calls the server can replay
+/// after an outage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayContext {
+    /// Replay flags.
+    pub flags: u32,
+    /// Error code that disabled replay (0 if replay is enabled).
+    pub error_code: u32,
+    /// Context bytes.
+    pub context: Vec<u8>,
+}
+
+/// A response that ends with the end-of-call status.
+pub trait EndOfCall {
+    /// Get the end-of-call status.
+    fn error_info(&self) -> &ErrorInfo;
+}
+
+impl EndOfCall for ExecuteResponse {
+    fn error_info(&self) -> &ErrorInfo {
+        &self.error_info
+    }
+}
+
+impl EndOfCall for FetchResponse {
+    fn error_info(&self) -> &ErrorInfo {
+        &self.error_info
+    }
+}
+
+impl EndOfCall for LobOpResponse {
+    fn error_info(&self) -> &ErrorInfo {
+        &self.error_info
+    }
+}
+
+/// An error for one row of an array execution in batch error mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    /// Zero-based index of the failing row.
+    pub offset: u32,
+    /// Oracle error code.
+    pub code: u32,
+    /// Error message.
+    pub message: String,
+}
+
+/// A warning raised by a call that succeeded, such as creating a PL/SQL
+/// unit with compilation errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Oracle error code.
+    pub code: u32,
+    /// Warning message.
+    pub message: String,
 }
 
 /// Result from parsing an execute response.
@@ -32,6 +130,11 @@ pub struct ExecuteResponse {
     pub error_info: ErrorInfo,
     /// Whether there are more rows to fetch.
     pub more_rows: bool,
+    /// Result sets returned by PL/SQL with `DBMS_SQL.RETURN_RESULT`.
+    pub implicit_results: Vec<ImplicitResult>,
+    /// Values of a DML `RETURNING ... INTO` clause: for each execution, one
+    /// row per affected row.
+    pub returned: Vec<Vec<Row>>,
 }
 
 impl ExecuteResponse {
@@ -42,6 +145,8 @@ impl ExecuteResponse {
             rows: Vec::new(),
             error_info: ErrorInfo::default(),
             more_rows: false,
+            implicit_results: Vec::new(),
+            returned: Vec::new(),
         }
     }
 }
@@ -52,6 +157,16 @@ impl Default for ExecuteResponse {
     }
 }
 
+/// An implicit result set: a cursor opened by the server, with no rows
+/// fetched yet.
+#[derive(Debug, Clone)]
+pub struct ImplicitResult {
+    /// Column metadata.
+    pub columns: Vec<ColumnMetadata>,
+    /// Server cursor ID.
+    pub cursor_id: u32,
+}
+
 /// Parse the response from an execute message.
 ///
 /// Reads from the buffer after the data flags (2 bytes already consumed).
@@ -64,11 +179,93 @@ pub fn parse_execute_response(
     buf: &mut ReadBuffer,
     ttc_field_version: u8,
     server_ttc_field_version: u8,
+) -> Result<ExecuteResponse> {
+    parse_returning_execute_response(buf, ttc_field_version, server_ttc_field_version, &[])
+}
+
+/// Parse the response from an execute message for a DML statement with a
+/// `RETURNING ... INTO` clause.
+///
+/// `returning` describes the values bound after `INTO`, in order; the
+/// server sends them in one row data message per execution.
+pub fn parse_returning_execute_response(
+    buf: &mut ReadBuffer,
+    ttc_field_version: u8,
+    server_ttc_field_version: u8,
+    returning: &[ColumnMetadata],
+) -> Result<ExecuteResponse> {
+    parse_execute_response_with(
+        buf,
+        ttc_field_version,
+        server_ttc_field_version,
+        &[],
+        false,
+        returning,
+    )
+}
+
+/// Parse the response from a message executing an open cursor again.
+///
+/// The query is not described again: its rows are decoded with `columns`,
+/// from the execution that opened the cursor, unless the server describes
+/// it anew because its tables changed.
+pub fn parse_reexecute_response(
+    buf: &mut ReadBuffer,
+    ttc_field_version: u8,
+    server_ttc_field_version: u8,
+    columns: &[ColumnMetadata],
+) -> Result<ExecuteResponse> {
+    parse_execute_response_with(
+        buf,
+        ttc_field_version,
+        server_ttc_field_version,
+        columns,
+        false,
+        &[],
+    )
+}
+
+/// Parse the response from a message defining the columns of a query.
+///
+/// Rows arrive in the defined types and are decoded with `columns`; a
+/// describe of the query sent with them, in its own types, is skipped.
+pub fn parse_defined_execute_response(
+    buf: &mut ReadBuffer,
+    ttc_field_version: u8,
+    server_ttc_field_version: u8,
+    columns: &[ColumnMetadata],
+) -> Result<ExecuteResponse> {
+    parse_execute_response_with(
+        buf,
+        ttc_field_version,
+        server_ttc_field_version,
+        columns,
+        true,
+        &[],
+    )
+}
+
+/// Parse an execute response, decoding rows with `columns` until the
+/// server describes the query, or throughout with `defined`.
+fn parse_execute_response_with(
+    buf: &mut ReadBuffer,
+    ttc_field_version: u8,
+    server_ttc_field_version: u8,
+    columns: &[ColumnMetadata],
+    defined: bool,
+    returning: &[ColumnMetadata],
 ) -> Result<ExecuteResponse> {
     let mut response = ExecuteResponse::new();
     let mut end_of_response = false;
-    let mut num_columns: usize = 0;
+    let mut end_of_call = false;
+    let mut num_columns = columns.len();
     let mut column_info: Option<Arc<ColumnInfo>> = None;
+    if !columns.is_empty() {
+        response.columns = columns.to_vec();
+        column_info = Some(Arc::new(ColumnInfo::from_metadata(columns)?));
+    }
+    let mut bit_vector = None;
+    let returning_info = Arc::new(ColumnInfo::from_metadata(returning)?);
 
     while buf.remaining() > 0 && !end_of_response {
         let msg_type = buf.read_u8()?;
@@ -78,46 +275,72 @@ pub fn parse_execute_response(
             TNS_MSG_TYPE_DESCRIBE_INFO => {
                 // Skip raw bytes before describe info (server sends additional data)
                 buf.skip_raw_bytes_chunked()?;
-                parse_describe_info(buf, &mut response, ttc_field_version)?;
-                num_columns = response.columns.len();
-                // Create shared column info for all rows
-                column_info = Some(Arc::new(ColumnInfo::from_metadata(&response.columns)?));
+                let described = parse_describe_info(buf, ttc_field_version)?;
+                if !defined {
+                    response.columns = described;
+                    num_columns = response.columns.len();
+                    // Create shared column info for all rows
+                    column_info = Some(Arc::new(ColumnInfo::from_metadata(&response.columns)?));
+                }
             }
             TNS_MSG_TYPE_ROW_HEADER => {
                 parse_row_header(buf)?;
             }
+            TNS_MSG_TYPE_ROW_DATA if column_info.is_none() && !returning.is_empty() => {
+                let rows = parse_returned_data(buf, returning, returning_info.clone())?;
+                response.returned.push(rows);
+            }
             TNS_MSG_TYPE_ROW_DATA => {
                 // column_info should be set after DESCRIBE_INFO
                 let info = column_info
                     .clone()
                     .ok_or_else(|| Error::protocol("Row data received before column metadata"))?;
-                parse_row_data(buf, &response.columns, info, &mut response.rows)?;
+                let bits = bit_vector.take();
+                parse_row_data(
+                    buf,
+                    &response.columns,
+                    info,
+                    &mut response.rows,
+                    bits.as_deref(),
+                    None,
+                )?;
             }
             TNS_MSG_TYPE_ERROR => {
                 // Use server's field version to determine error info format
                 parse_error_info(buf, &mut response.error_info, server_ttc_field_version)?;
+                end_of_call = true;
                 eprintln!("[DEBUG] error_info: error_num={}, cursor_id={}, row_count={}",
                     response.error_info.error_num, response.error_info.cursor_id, response.error_info.row_count);
             }
             TNS_MSG_TYPE_END_OF_RESPONSE => {
                 end_of_response = true;
+                end_of_call = true;
             }
             TNS_MSG_TYPE_PARAMETER => {
                 // Process return parameters (from Python's _process_return_parameters)
                 parse_return_parameters(buf)?;
             }
             TNS_MSG_TYPE_BIT_VECTOR => {
-                // Bit vector for duplicate column detection (for performance optimization)
-                // We skip it as we're not implementing duplicate detection yet
-                parse_bit_vector(buf, num_columns)?;
+                // Marks the columns of the next row that repeat the previous row
+                bit_vector = Some(parse_bit_vector(buf, num_columns)?);
             }
             TNS_MSG_TYPE_STATUS => {
                 // Simple status message (alternative to ERROR in some flows)
-                parse_status_info(buf)?;
+                parse_status_info(buf, &mut response.error_info)?;
+                end_of_call = true;
             }
             TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK => {
                 // Server-sent state updates (session changes, transaction IDs, etc.)
-                parse_server_side_piggyback(buf)?;
+                parse_server_side_piggyback(buf, &mut response.error_info)?;
+            }
+            TNS_MSG_TYPE_IMPLICIT_RESULTSET => {
+                response.implicit_results = parse_implicit_results(buf, ttc_field_version)?;
+            }
+            TNS_MSG_TYPE_WARNING => {
+                response
+                    .error_info
+                    .warnings
+                    .extend(parse_warning_info(buf)?);
             }
             _ => {
                 return Err(Error::protocol(format!(
@@ -127,6 +350,9 @@ pub fn parse_execute_response(
             }
         }
     }
+    if !end_of_call {
+        return Err(incomplete_response());
+    }
 
     // Determine if there are more rows based on error info
     // Error 1403 (ORA-01403: no data found) means no more rows
@@ -168,14 +394,22 @@ impl Default for FetchResponse {
 /// Parse the response from a fetch message.
 ///
 /// Unlike execute response, fetch response doesn't include DESCRIBE_INFO
-/// since column metadata was already received in the execute response.
+/// since column metadata was already received in the execute response; a
+/// server describing the query again (its tables changed since the cursor
+/// was opened) fails the fetch rather than decoding rows with the old
+/// columns.
+/// `last_row` is the last row fetched before, which the first row of this
+/// response may repeat columns from.
 pub fn parse_fetch_response(
     buf: &mut ReadBuffer,
     columns: &[ColumnMetadata],
+    last_row: Option<&Row>,
     server_ttc_field_version: u8,
 ) -> Result<FetchResponse> {
     let mut response = FetchResponse::new();
     let mut end_of_response = false;
+    let mut end_of_call = false;
+    let mut bit_vector = None;
     let num_columns = columns.len();
     // Create shared column info for all rows
     let column_info = Arc::new(ColumnInfo::from_metadata(columns)?);
@@ -188,25 +422,47 @@ pub fn parse_fetch_response(
                 parse_row_header(buf)?;
             }
             TNS_MSG_TYPE_ROW_DATA => {
-                parse_row_data(buf, columns, column_info.clone(), &mut response.rows)?;
+                let bits = bit_vector.take();
+                parse_row_data(
+                    buf,
+                    columns,
+                    column_info.clone(),
+                    &mut response.rows,
+                    bits.as_deref(),
+                    last_row,
+                )?;
             }
             TNS_MSG_TYPE_ERROR => {
                 parse_error_info(buf, &mut response.error_info, server_ttc_field_version)?;
+                end_of_call = true;
             }
             TNS_MSG_TYPE_END_OF_RESPONSE => {
                 end_of_response = true;
+                end_of_call = true;
             }
             TNS_MSG_TYPE_PARAMETER => {
                 parse_return_parameters(buf)?;
             }
             TNS_MSG_TYPE_BIT_VECTOR => {
-                parse_bit_vector(buf, num_columns)?;
+                bit_vector = Some(parse_bit_vector(buf, num_columns)?);
             }
             TNS_MSG_TYPE_STATUS => {
-                parse_status_info(buf)?;
+                parse_status_info(buf, &mut response.error_info)?;
+                end_of_call = true;
             }
             TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK => {
-                parse_server_side_piggyback(buf)?;
+                parse_server_side_piggyback(buf, &mut response.error_info)?;
+            }
+            TNS_MSG_TYPE_WARNING => {
+                response
+                    .error_info
+                    .warnings
+                    .extend(parse_warning_info(buf)?);
+            }
+            TNS_MSG_TYPE_DESCRIBE_INFO => {
+                return Err(Error::protocol(
+                    "query columns changed since the cursor was opened; execute the query again",
+                ));
             }
             _ => {
                 return Err(Error::protocol(format!(
@@ -216,6 +472,9 @@ pub fn parse_fetch_response(
             }
         }
     }
+    if !end_of_call {
+        return Err(incomplete_response());
+    }
 
     // Determine if there are more rows based on error info
     // Error 1403 means no more rows
@@ -227,11 +486,7 @@ pub fn parse_fetch_response(
 }
 
 /// Parse describe info (column metadata).
-fn parse_describe_info(
-    buf: &mut ReadBuffer,
-    response: &mut ExecuteResponse,
-    ttc_field_version: u8,
-) -> Result<()> {
+fn parse_describe_info(buf: &mut ReadBuffer, ttc_field_version: u8) -> Result<Vec<ColumnMetadata>> {
     let _max_row_size = buf.read_ub4()?;
     let num_columns = buf.read_ub4()?;
 
@@ -239,9 +494,9 @@ fn parse_describe_info(
         let _ = buf.read_u8()?; // skip flags byte
     }
 
+    let mut columns = Vec::new();
     for _ in 0..num_columns {
-        let metadata = parse_column_metadata(buf, ttc_field_version)?;
-        response.columns.push(metadata);
+        columns.push(parse_column_metadata(buf, ttc_field_version)?);
     }
 
     // Skip remaining describe info fields
@@ -258,7 +513,27 @@ fn parse_describe_info(
         buf.skip_raw_bytes_chunked()?;
     }
 
-    Ok(())
+    Ok(columns)
+}
+
+/// Parse implicit result sets (TNS_MSG_TYPE_IMPLICIT_RESULTSET).
+///
+/// Each result set is described like a query, followed by the ID of the
+/// cursor its rows are fetched from.
+fn parse_implicit_results(
+    buf: &mut ReadBuffer,
+    ttc_field_version: u8,
+) -> Result<Vec<ImplicitResult>> {
+    let num_results = buf.read_ub4()?;
+    let mut results = Vec::new();
+    for _ in 0..num_results {
+        let num_bytes = buf.read_u8()?;
+        buf.skip(num_bytes as usize)?;
+        let columns = parse_describe_info(buf, ttc_field_version)?;
+        let cursor_id = buf.read_ub2()? as u32;
+        results.push(ImplicitResult { columns, cursor_id });
+    }
+    Ok(results)
 }
 
 /// Parse single column metadata.
@@ -277,7 +552,7 @@ fn parse_column_metadata(buf: &mut ReadBuffer, ttc_field_version: u8) -> Result<
 
     let _ = buf.read_ub2()?; // version
     let _ = buf.read_ub2()?; // charset id
-    let _ = buf.read_u8()?; // charset form
+    let charset_form = buf.read_u8()?;
     let max_size = buf.read_ub4()?;
 
     if ttc_field_version >= TNS_CCAP_FIELD_VERSION_12_2 {
@@ -294,7 +569,7 @@ fn parse_column_metadata(buf: &mut ReadBuffer, ttc_field_version: u8) -> Result<
     let _schema = read_column_string(buf)?; // schema
     let _type_name = read_column_string(buf)?; // type name
     let _col_pos = buf.read_ub2()?; // column position
-    let _uds_flags = buf.read_ub4()?; // uds flags
+    let uds_flags = buf.read_ub4()?; // uds flags
 
     // 23.1+ fields - domain schema/name
     // Note: Python's read_str_with_length has ub4 prefix, so use read_column_string here too
@@ -334,6 +609,9 @@ fn parse_column_metadata(buf: &mut ReadBuffer, ttc_field_version: u8) -> Result<
         max_size,
         buffer_size,
         nullable,
+        charset_form,
+        is_json: uds_flags & TNS_UDS_FLAGS_IS_JSON != 0,
+        is_oson: uds_flags & TNS_UDS_FLAGS_IS_OSON != 0,
     })
 }
 
@@ -362,55 +640,268 @@ fn parse_row_header(buf: &mut ReadBuffer) -> Result<()> {
 }
 
 /// Parse row data.
+///
+/// With a bit vector, only the columns whose bit is set are sent; the others
+/// repeat the previous row, which is the last parsed row or, for the first
+/// row of a response, `last_row`.
 fn parse_row_data(
     buf: &mut ReadBuffer,
     columns: &[ColumnMetadata],
     column_info: Arc<ColumnInfo>,
     rows: &mut Vec<Row>,
+    bit_vector: Option<&[u8]>,
+    last_row: Option<&Row>,
 ) -> Result<()> {
-    let mut values = Vec::with_capacity(columns.len());
-
-    for col in columns {
-        let value = parse_column_value(buf, col)?;
-        values.push(value);
+    let previous = rows.last().or(last_row);
+    let mut cells = Vec::with_capacity(columns.len());
+
+    for (index, col) in columns.iter().enumerate() {
+        if is_duplicate_column(bit_vector, index) {
+            let cell = previous.and_then(|row| row.cell(index)).ok_or_else(|| {
+                Error::protocol(format!(
+                    "Column {} repeats the previous row, but there is none",
+                    index
+                ))
+            })?;
+            cells.push(cell.clone());
+        } else {
+            cells.push(parse_column_cell(buf, col)?);
+        }
     }
 
-    rows.push(Row::new(values, column_info));
+    rows.push(Row::from_cells(cells, column_info));
     Ok(())
 }
 
+/// Parse the values returned into the `RETURNING ... INTO` binds by one
+/// execution.
+///
+/// Each bind carries a count followed by that many values, each followed by
+/// its untruncated length (0 unless the value did not fit the bind).
+/// Values are regrouped into one row per affected row.
+fn parse_returned_data(
+    buf: &mut ReadBuffer,
+    returning: &[ColumnMetadata],
+    column_info: Arc<ColumnInfo>,
+) -> Result<Vec<Row>> {
+    let mut columns: Vec<Vec<Cell>> = Vec::with_capacity(returning.len());
+    for col in returning {
+        let num_rows = buf.read_ub4()? as usize;
+        let mut cells = Vec::with_capacity(num_rows.min(buf.remaining()));
+        for _ in 0..num_rows {
+            cells.push(parse_column_cell(buf, col)?);
+            let actual_len = buf.read_sb8()?;
+            if actual_len > 0 {
+                return Err(Error::protocol(format!(
+                    "value returned into {} was truncated: {} bytes do not fit in {}",
+                    col.name, actual_len, col.buffer_size
+                )));
+            }
+        }
+        columns.push(cells);
+    }
+
+    let num_rows = columns.first().map_or(0, Vec::len);
+    if columns.iter().any(|cells| cells.len() != num_rows) {
+        return Err(Error::protocol(
+            "RETURNING binds returned different numbers of rows",
+        ));
+    }
+    let mut columns: Vec<_> = columns.into_iter().map(Vec::into_iter).collect();
+    Ok((0..num_rows)
+        .map(|_| {
+            let cells = columns.iter_mut().filter_map(Iterator::next).collect();
+            Row::from_cells(cells, column_info.clone())
+        })
+        .collect())
+}
+
+/// Check if a bit vector marks a column as a repeat of the previous row
+/// (bit cleared).
+fn is_duplicate_column(bit_vector: Option<&[u8]>, index: usize) -> bool {
+    bit_vector
+        .and_then(|bits| bits.get(index / 8))
+        .is_some_and(|byte| byte & (1 << (index % 8)) == 0)
+}
+
 /// Parse a single column value.
-fn parse_column_value(buf: &mut ReadBuffer, col: &ColumnMetadata) -> Result<OracleValue> {
-    // Read length-prefixed data
+///
+/// Scalar values keep the bytes received for them, decoded on first access.
+fn parse_column_cell(buf: &mut ReadBuffer, col: &ColumnMetadata) -> Result<Cell> {
+    let ora_type = col.oracle_type as u16;
+
+    // ROWID columns carry the decoded ROWID parts instead of a byte string
+    if ora_type == ORA_TYPE_NUM_ROWID {
+        // Both a zero length and 0xFF denote NULL here
+        let num_bytes = buf.read_u8()?;
+        if num_bytes == TNS_NULL_LENGTH_INDICATOR || num_bytes == 0xFF {
+            return Ok(Cell::Value(OracleValue::Null));
+        }
+        return Ok(Cell::Value(match parse_rowid(buf)? {
+            Some(rowid) => OracleValue::String(rowid.to_string()),
+            None => OracleValue::Null,
+        }));
+    }
+
+    // CLOB, BLOB and BFILE columns carry a locator; the content is read separately
+    if matches!(
+        ora_type,
+        ORA_TYPE_NUM_CLOB | ORA_TYPE_NUM_BLOB | ORA_TYPE_NUM_BFILE
+    ) {
+        return Ok(Cell::Value(parse_lob_value(buf, col)?));
+    }
+
+    // JSON and VECTOR columns carry their image with LOB framing
+    let data = if matches!(ora_type, ORA_TYPE_NUM_JSON | ORA_TYPE_NUM_VECTOR) {
+        read_lob_framed_image(buf)?
+    } else {
+        buf.read_bytes_with_length()?
+    };
+
+    Ok(match data {
+        None => Cell::Value(OracleValue::Null),
+        // Integer columns skip the string form; values that do not fit
+        // fall back to it
+        Some(bytes) => match col.is_integer().then(|| decode_oracle_integer(&bytes)) {
+            Some(Some(value)) => Cell::Value(OracleValue::Integer(value)),
+            _ => Cell::encoded(ora_type, col.charset_form, bytes),
+        },
+    })
+}
+
+/// Read a value image sent with LOB framing (JSON, VECTOR).
+///
+/// Layout: UB4 length, UB8 size, UB4 chunk size, image, LOB locator.
+fn read_lob_framed_image(buf: &mut ReadBuffer) -> Result<Option<bytes::Bytes>> {
+    let num_bytes = buf.read_ub4()?;
+    if num_bytes == 0 {
+        return Ok(None);
+    }
+    let _size = buf.read_ub8()?;
+    let _chunk_size = buf.read_ub4()?;
     let data = buf.read_bytes_with_length()?;
+    let _locator = buf.read_bytes_with_length()?;
+    Ok(data)
+}
 
-    match data {
-        None => Ok(OracleValue::Null),
-        Some(bytes) => {
-            match col.oracle_type as u16 {
-                // VARCHAR2, CHAR, LONG
-                ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR | ORA_TYPE_NUM_LONG => {
-                    let s = String::from_utf8_lossy(&bytes).to_string();
-                    Ok(OracleValue::String(s))
-                }
-                // NUMBER, BINARY_INTEGER
-                ORA_TYPE_NUM_NUMBER | ORA_TYPE_NUM_BINARY_INTEGER => {
-                    let num_str = decode_oracle_number(&bytes)?;
-                    Ok(OracleValue::Number(num_str))
+/// Parse a CLOB/BLOB column value into a locator.
+///
+/// Layout: UB4 length, UB8 size, UB4 chunk size, LOB locator.
+fn parse_lob_value(buf: &mut ReadBuffer, col: &ColumnMetadata) -> Result<OracleValue> {
+    let num_bytes = buf.read_ub4()?;
+    if num_bytes == 0 {
+        return Ok(OracleValue::Null);
+    }
+    // BFILE locators are not preceded by a size and chunk size
+    let (size, chunk_size) = if col.oracle_type as u16 == ORA_TYPE_NUM_BFILE {
+        (0, 0)
+    } else {
+        (buf.read_ub8()?, buf.read_ub4()?)
+    };
+    let locator = buf
+        .read_bytes_with_length()?
+        .ok_or_else(|| Error::protocol("Missing LOB locator"))?;
+    let oracle_type = col.data_type()?;
+    Ok(OracleValue::Lob(Lob::new(
+        oracle_type,
+        locator.to_vec(),
+        size,
+        chunk_size,
+    )))
+}
+
+/// Result from parsing a LOB operation response.
+#[derive(Debug, Default)]
+pub struct LobOpResponse {
+    /// Data returned by a READ operation.
+    pub data: Option<bytes::Bytes>,
+    /// Locator returned by the server (replaces the one sent).
+    pub locator: Option<bytes::Bytes>,
+    /// Amount returned by the server (length, bytes read or written).
+    pub amount: Option<u64>,
+    /// Flag returned by CREATE_TEMP, FILE_EXISTS and FILE_ISOPEN.
+    pub flag: bool,
+    /// Error/status information.
+    pub error_info: ErrorInfo,
+}
+
+/// Parse the response to a LOB operation.
+///
+/// The return parameters echo the fields the message asked for, so the
+/// message is needed to parse them.
+pub fn parse_lob_op_response(
+    buf: &mut ReadBuffer,
+    msg: &LobOpMessage,
+    server_ttc_field_version: u8,
+) -> Result<LobOpResponse> {
+    let mut response = LobOpResponse::default();
+    let mut end_of_response = false;
+    let mut end_of_call = false;
+
+    while buf.remaining() > 0 && !end_of_response {
+        let msg_type = buf.read_u8()?;
+
+        match msg_type {
+            TNS_MSG_TYPE_LOB_DATA => {
+                response.data = Some(buf.read_bytes_with_length()?.unwrap_or_default());
+            }
+            TNS_MSG_TYPE_PARAMETER => {
+                response.locator = Some(buf.read_bytes(msg.locator.len())?);
+                if msg.operation == TNS_LOB_OP_CREATE_TEMP {
+                    let _charset = buf.read_ub2()?;
                 }
-                // DATE
-                ORA_TYPE_NUM_DATE => {
-                    let dt = decode_oracle_date(&bytes)?;
-                    Ok(OracleValue::Date(dt))
+                if msg.amount.is_some() {
+                    response.amount = Some(buf.read_sb8()?.max(0) as u64);
                 }
-                // For other types, return as string for now
-                _ => {
-                    let s = String::from_utf8_lossy(&bytes).to_string();
-                    Ok(OracleValue::String(s))
+                if msg.returns_flag() {
+                    response.flag = buf.read_u8()? > 0;
                 }
             }
+            TNS_MSG_TYPE_ERROR => {
+                parse_error_info(buf, &mut response.error_info, server_ttc_field_version)?;
+                end_of_call = true;
+            }
+            TNS_MSG_TYPE_END_OF_RESPONSE => {
+                end_of_response = true;
+                end_of_call = true;
+            }
+            TNS_MSG_TYPE_STATUS => {
+                parse_status_info(buf, &mut response.error_info)?;
+                end_of_call = true;
+            }
+            TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK => {
+                parse_server_side_piggyback(buf, &mut response.error_info)?;
+            }
+            TNS_MSG_TYPE_WARNING => {
+                response
+                    .error_info
+                    .warnings
+                    .extend(parse_warning_info(buf)?);
+            }
+            _ => {
+                return Err(Error::protocol(format!(
+                    "Unexpected message type in LOB response: {}",
+                    msg_type
+                )));
+            }
         }
     }
+    if !end_of_call {
+        return Err(incomplete_response());
+    }
+
+    Ok(response)
+}
+
+/// Error for a response that ran out before its end-of-call status.
+///
+/// Long responses span several packets, so this tells the caller to wait
+/// for more of the response.
+fn incomplete_response() -> Error {
+    Error::BufferTooSmall {
+        needed: 1,
+        available: 0,
+    }
 }
 
 /// Parse error info from response.
@@ -419,23 +910,23 @@ fn parse_error_info(
     info: &mut ErrorInfo,
     ttc_field_version: u8,
 ) -> Result<()> {
-    let _call_status = buf.read_ub4()?; // end of call status
+    info.call_status = Some(buf.read_ub4()?); // end of call status
     let _ = buf.read_ub2()?; // end to end seq#
     let _ = buf.read_ub4()?; // current row number
     let _error_num_hint = buf.read_ub2()?; // error number hint (not the real error!)
     let _ = buf.read_ub2()?; // array elem error
     let _ = buf.read_ub2()?; // array elem error
     info.cursor_id = buf.read_ub2()?;
-    let _ = buf.read_ub2()?; // error position (sb2, but read as ub2)
+    info.error_position = buf.read_ub2()?; // sb2, but read as ub2
     let _ = buf.read_u8()?; // sql type
     let _ = buf.read_u8()?; // fatal?
     let _ = buf.read_u8()?; // flags
     let _ = buf.read_u8()?; // user cursor options
     let _ = buf.read_u8()?; // UPI parameter
-    let _ = buf.read_u8()?; // warning flags
+    let warning_flags = buf.read_u8()?;
 
-    // rowid (18 bytes typically)
-    let _ = parse_rowid(buf)?;
+    // rowid of the last row affected by DML
+    info.rowid = parse_rowid(buf)?;
 
     let _ = buf.read_ub4()?; // OS error
     let _ = buf.read_u8()?; // statement number
@@ -451,13 +942,14 @@ fn parse_error_info(
 
     // batch error codes
     let num_errors = buf.read_ub2()?;
+    let mut codes = Vec::new();
     if num_errors > 0 {
         let first_byte = buf.read_u8()?;
         for _ in 0..num_errors {
             if first_byte == TNS_LONG_LENGTH_INDICATOR {
                 let _ = buf.read_ub4()?;
             }
-            let _ = buf.read_ub2()?;
+            codes.push(buf.read_ub2()? as u32);
         }
         if first_byte == TNS_LONG_LENGTH_INDICATOR {
             buf.skip(1)?;
@@ -466,13 +958,14 @@ fn parse_error_info(
 
     // batch error offsets
     let num_offsets = buf.read_ub4()?;
+    let mut offsets = Vec::new();
     if num_offsets > 0 {
         let first_byte = buf.read_u8()?;
         for _ in 0..num_offsets {
             if first_byte == TNS_LONG_LENGTH_INDICATOR {
                 let _ = buf.read_ub4()?;
             }
-            let _ = buf.read_ub4()?;
+            offsets.push(buf.read_ub4()?);
         }
         if first_byte == TNS_LONG_LENGTH_INDICATOR {
             buf.skip(1)?;
@@ -480,16 +973,29 @@ fn parse_error_info(
     }
 
     // batch error messages
-    let temp = buf.read_ub2()?;
-    if temp > 0 {
+    let num_messages = buf.read_ub2()?;
+    let mut messages = Vec::new();
+    if num_messages > 0 {
         buf.skip(1)?; // packed size
-        for _ in 0..temp {
+        for _ in 0..num_messages {
             let _ = buf.read_ub2()?; // chunk length
-            let _ = buf.read_str_with_length()?;
+            let message = buf.read_str_with_length()?.unwrap_or_default();
+            messages.push(message.trim_end().to_string());
             buf.skip(2)?; // end marker
         }
     }
 
+    let mut offsets = offsets.into_iter();
+    let mut messages = messages.into_iter();
+    info.batch_errors = codes
+        .into_iter()
+        .map(|code| BatchError {
+            offset: offsets.next().unwrap_or_default(),
+            code,
+            message: messages.next().unwrap_or_default(),
+        })
+        .collect();
+
     // Extended error info
     info.error_num = buf.read_ub4()?;
     info.row_count = buf.read_ub8()?;
@@ -505,11 +1011,34 @@ fn parse_error_info(
     // Note: Even for ORA-01403 "no data found", the server still sends the message
     if info.error_num != 0 {
         info.message = buf.read_str_with_length()?;
+    } else if warning_flags & TNS_WARN_COMPILATION_CREATE != 0 {
+        info.warnings.push(Warning {
+            code: TNS_ERR_COMPILATION_ERROR,
+            message: "success with compilation error".to_string(),
+        });
     }
 
     Ok(())
 }
 
+/// Parse a warning (TNS_MSG_TYPE_WARNING).
+///
+/// Based on Python's _process_warning_info. A warning with code 0 or no
+/// message carries nothing to report.
+fn parse_warning_info(buf: &mut ReadBuffer) -> Result<Option<Warning>> {
+    let code = buf.read_ub2()? as u32;
+    let num_bytes = buf.read_ub2()?;
+    let _ = buf.read_ub2()?; // flags
+    if code == 0 || num_bytes == 0 {
+        return Ok(None);
+    }
+    let message = buf.read_str_with_length()?.unwrap_or_default();
+    Ok(Some(Warning {
+        code,
+        message: message.trim_end().to_string(),
+    }))
+}
+
 /// Parse return parameters (TNS_MSG_TYPE_PARAMETER).
 /// Based on Python's _process_return_parameters in MessageWithData.
 fn parse_return_parameters(buf: &mut ReadBuffer) -> Result<()> {
@@ -556,23 +1085,20 @@ fn parse_return_parameters(buf: &mut ReadBuffer) -> Result<()> {
 /// The bit vector is used for duplicate column detection - it indicates which
 /// columns in a row have the same value as the previous row. We skip this
 /// optimization and just read all column data.
-fn parse_bit_vector(buf: &mut ReadBuffer, num_columns: usize) -> Result<()> {
-    // num_columns_sent - how many columns are actually sent in this batch
+///
+/// Returns the vector, with one bit per column, set for the columns sent in
+/// the next row.
+fn parse_bit_vector(buf: &mut ReadBuffer, num_columns: usize) -> Result<Vec<u8>> {
+    // num_columns_sent - how many columns are actually sent in the next row
     let _num_columns_sent = buf.read_ub2()?;
 
-    // Calculate bytes needed for bit vector: 1 bit per column
+    // 1 bit per column
     let num_bytes = num_columns.div_ceil(8);
-
-    // Skip the bit vector data
-    if num_bytes > 0 {
-        buf.skip(num_bytes)?;
-    }
-
-    Ok(())
+    Ok(buf.read_bytes(num_bytes)?.to_vec())
 }
 
 /// Parse a ROWID value.
-fn parse_rowid(buf: &mut ReadBuffer) -> Result<Option<String>> {
+fn parse_rowid(buf: &mut ReadBuffer) -> Result<Option<RowId>> {
     // ROWID is variable length, read the parts
     let rba = buf.read_ub4()?;
     let partition_id = buf.read_ub2()?;
@@ -583,10 +1109,11 @@ fn parse_rowid(buf: &mut ReadBuffer) -> Result<Option<String>> {
     if rba == 0 && partition_id == 0 && block_num == 0 && slot_num == 0 {
         Ok(None)
     } else {
-        // Encode as base64-like ROWID string (simplified)
-        Ok(Some(format!(
-            "{:08X}{:04X}{:08X}{:04X}",
-            rba, partition_id, block_num, slot_num
+        Ok(Some(RowId::from_parts(
+            rba,
+            partition_id,
+            block_num,
+            slot_num,
         )))
     }
 }
@@ -609,8 +1136,8 @@ fn read_column_string(buf: &mut ReadBuffer) -> Result<String> {
 ///
 /// This is a simple status message that can be sent instead of the full
 /// ERROR structure in some flows. Based on Python's _process_status_info.
-fn parse_status_info(buf: &mut ReadBuffer) -> Result<()> {
-    let _call_status = buf.read_ub4()?;
+fn parse_status_info(buf: &mut ReadBuffer, info: &mut ErrorInfo) -> Result<()> {
+    info.call_status = Some(buf.read_ub4()?);
     let _end_to_end_seq = buf.read_ub2()?;
     Ok(())
 }
@@ -618,37 +1145,44 @@ fn parse_status_info(buf: &mut ReadBuffer) -> Result<()> {
 /// Parse server-side piggyback (TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK).
 ///
 /// The server can send additional state updates (session changes, transaction IDs, etc.)
-/// embedded in the response. For now we just skip the content.
+/// embedded in the response. The logical transaction ID and the replay
+/// context are kept in `info`; the rest is skipped.
 /// Based on Python's _process_server_side_piggyback.
-fn parse_server_side_piggyback(buf: &mut ReadBuffer) -> Result<()> {
+fn parse_server_side_piggyback(buf: &mut ReadBuffer, info: &mut ErrorInfo) -> Result<()> {
     let opcode = buf.read_u8()?;
 
     // Different opcodes have different payloads
-    // Python handles: LTXID (7), SESS_RET (4), AC_REPLAY_CONTEXT (8), EXT_SYNC (9), SESS_SIGNATURE (10)
     match opcode {
-        4 => {
-            // TNS_SERVER_PIGGYBACK_SESS_RET - DRCP session return
+        TNS_SERVER_PIGGYBACK_SESS_RET => {
+            // DRCP session return
             let _sess_state = buf.read_ub4()?;
             let _sess_state_serial = buf.read_ub2()?;
         }
-        7 => {
-            // TNS_SERVER_PIGGYBACK_LTXID - Logical transaction ID
+        TNS_SERVER_PIGGYBACK_LTXID => {
+            // Logical transaction ID
             let num_bytes = buf.read_ub4()?;
             if num_bytes > 0 {
-                buf.skip_raw_bytes_chunked()?;
+                let ltxid = buf.read_bytes_with_length()?.unwrap_or_default();
+                info.ltxid = Some(ltxid.to_vec());
             }
         }
-        8 => {
-            // TNS_SERVER_PIGGYBACK_AC_REPLAY_CONTEXT
-            let _flags = buf.read_ub4()?;
-            let _error_code = buf.read_ub4()?;
+        TNS_SERVER_PIGGYBACK_AC_REPLAY_CONTEXT => {
+            let flags = buf.read_ub4()?;
+            let error_code = buf.read_ub4()?;
             let num_bytes = buf.read_ub4()?;
-            if num_bytes > 0 {
-                buf.skip_raw_bytes_chunked()?;
-            }
+            let context = if num_bytes > 0 {
+                buf.read_bytes_with_length()?.unwrap_or_default().to_vec()
+            } else {
+                Vec::new()
+            };
+            info.replay_context = Some(ReplayContext {
+                flags,
+                error_code,
+                context,
+            });
         }
-        9 => {
-            // TNS_SERVER_PIGGYBACK_EXT_SYNC - Extended sync (keyword/value pairs)
+        TNS_SERVER_PIGGYBACK_EXT_SYNC => {
+            // Extended sync (keyword/value pairs)
             let num_pairs = buf.read_ub2()?;
             for _ in 0..num_pairs {
                 let key_len = buf.read_ub2()?;
@@ -661,8 +1195,7 @@ fn parse_server_side_piggyback(buf: &mut ReadBuffer) -> Result<()> {
                 }
             }
         }
-        10 => {
-            // TNS_SERVER_PIGGYBACK_SESS_SIGNATURE
+        TNS_SERVER_PIGGYBACK_SESS_SIGNATURE => {
             let num_bytes = buf.read_ub4()?;
             if num_bytes > 0 {
                 buf.skip_raw_bytes_chunked()?;
@@ -680,6 +1213,12 @@ fn parse_server_side_piggyback(buf: &mut ReadBuffer) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::decode::decode_oracle_number;
+    use crate::protocol::types::OracleType;
+
+    fn parse_column_value(buf: &mut ReadBuffer, col: &ColumnMetadata) -> Result<OracleValue> {
+        parse_column_cell(buf, col)?.into_value()
+    }
 
     #[test]
     fn test_decode_number_zero() {
@@ -737,4 +1276,580 @@ mod tests {
         // result = "0." + "5" = "0.5"
         assert_eq!(decode_oracle_number(&[0xC0, 0x33]).unwrap(), "0.5");
     }
+
+    #[test]
+    fn test_parse_error_info_batch_errors() {
+        let msg1 = b"ORA-00001: unique constraint violated  ";
+        let msg2 = b"ORA-12899: value too large";
+        let mut wire = vec![
+            0, 0, 0, 0, 0, 0, // call status .. array elem errors
+            1, 5, // cursor id
+            1, 7, 0, 0, 0, 0, 0, 0, // error position, flags
+            0, 0, 0, 0, 0, // rowid
+            0, 0, 0, 0, 0, 0, // OS error .. success iters, oerrdd
+            1, 2, 2, // two error codes
+            1, 1, 2, 0x32, 0x63, // 1 and 12899
+            1, 2, 2, // two offsets
+            1, 1, 1, 3, // rows 1 and 3
+            1, 2, 2, // two messages
+        ];
+        for msg in [&msg1[..], &msg2[..]] {
+            wire.extend_from_slice(&[1, msg.len() as u8, msg.len() as u8]);
+            wire.extend_from_slice(msg);
+            wire.extend_from_slice(&[0, 0]);
+        }
+        wire.extend_from_slice(&[2, 0x5F, 0x3D]); // ORA-24381
+        wire.extend_from_slice(&[1, 2]); // row count
+        let text = b"ORA-24381: error(s) in array DML";
+        wire.push(text.len() as u8);
+        wire.extend_from_slice(text);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let mut info = ErrorInfo::default();
+        parse_error_info(&mut buf, &mut info, 12).unwrap();
+        assert_eq!(buf.remaining(), 0);
+        assert_eq!(info.error_num, TNS_ERR_ARRAY_DML_ERRORS);
+        assert_eq!(info.row_count, 2);
+        assert_eq!(info.cursor_id, 5);
+        assert_eq!(info.error_position, 7);
+        let e = info.to_error();
+        assert_eq!(
+            (e.code(), e.offset(), e.row_count()),
+            (Some(24381), Some(7), Some(2))
+        );
+        assert_eq!(
+            info.batch_errors,
+            vec![
+                BatchError {
+                    offset: 1,
+                    code: 1,
+                    message: "ORA-00001: unique constraint violated".to_string(),
+                },
+                BatchError {
+                    offset: 3,
+                    code: 12899,
+                    message: "ORA-12899: value too large".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_info_to_error_cancelled() {
+        let info = ErrorInfo {
+            error_num: 942,
+            message: Some("ORA-00942: table or view does not exist".to_string()),
+            ..ErrorInfo::default()
+        };
+        assert!(matches!(info.to_error(), Error::Oracle { code: 942, .. }));
+
+        let info = ErrorInfo {
+            error_num: TNS_ERR_CALL_CANCELLED,
+            ..ErrorInfo::default()
+        };
+        assert!(matches!(
+            info.to_error(),
+            Error::Cancelled { context: None }
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_info_compilation_warning() {
+        let mut wire = vec![0; 6]; // call status .. array elem errors
+        wire.push(0); // cursor id
+        wire.extend_from_slice(&[0, 0, 0, 0, 0, 0, TNS_WARN_COMPILATION_CREATE]); // position, flags
+        wire.extend_from_slice(&[0; 5]); // rowid
+        wire.extend_from_slice(&[0; 6]); // OS error .. success iters, oerrdd
+        wire.extend_from_slice(&[0; 3]); // no batch errors
+        wire.extend_from_slice(&[0, 0]); // error number, row count
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let mut info = ErrorInfo::default();
+        parse_error_info(&mut buf, &mut info, 12).unwrap();
+        assert_eq!(buf.remaining(), 0);
+        assert_eq!(info.error_num, 0);
+        assert_eq!(
+            info.warnings,
+            [Warning {
+                code: TNS_ERR_COMPILATION_ERROR,
+                message: "success with compilation error".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_fetch_response_warning() {
+        let text = b"ORA-28002: the password will expire within 7 days ";
+        let mut wire = vec![TNS_MSG_TYPE_WARNING, 2, 0x6D, 0x62, 1, text.len() as u8, 0];
+        wire.push(text.len() as u8);
+        wire.extend_from_slice(text);
+        // A warning without a message is ignored
+        wire.extend_from_slice(&[TNS_MSG_TYPE_WARNING, 0, 0, 0]);
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_fetch_response(&mut buf, &[], None, 12).unwrap();
+        assert_eq!(
+            response.error_info.warnings,
+            [Warning {
+                code: 28002,
+                message: "ORA-28002: the password will expire within 7 days".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_fetch_response_piggyback_ltxid() {
+        let mut wire = vec![
+            TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK,
+            TNS_SERVER_PIGGYBACK_LTXID,
+        ];
+        wire.extend_from_slice(&[1, 4, 4, 0xAA, 0xBB, 0xCC, 0xDD]);
+        wire.extend_from_slice(&[
+            TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK,
+            TNS_SERVER_PIGGYBACK_AC_REPLAY_CONTEXT,
+        ]);
+        wire.extend_from_slice(&[1, 3, 1, 5, 1, 2, 2, 0x01, 0x02]);
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_fetch_response(&mut buf, &[], None, 12).unwrap();
+        let info = response.error_info();
+        assert_eq!(info.ltxid.as_deref(), Some(&[0xAA, 0xBB, 0xCC, 0xDD][..]));
+        assert_eq!(
+            info.replay_context,
+            Some(ReplayContext {
+                flags: 3,
+                error_code: 5,
+                context: vec![0x01, 0x02],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_returning_execute_response() {
+        let mut id = ColumnMetadata::new("ID".to_string(), ORA_TYPE_NUM_NUMBER as u8);
+        id.precision = 10;
+        let name = ColumnMetadata::new("NAME".to_string(), ORA_TYPE_NUM_VARCHAR as u8);
+        let returning = [id, name];
+
+        // Two executions: the first updated two rows, the second none
+        let mut wire = vec![TNS_MSG_TYPE_ROW_DATA];
+        wire.extend_from_slice(&[1, 2, 2, 0xC1, 0x02, 0, 2, 0xC1, 0x03, 0]);
+        wire.extend_from_slice(&[1, 2, 1, b'a', 0, 0, 0]);
+        wire.extend_from_slice(&[TNS_MSG_TYPE_ROW_DATA, 0, 0]);
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_returning_execute_response(&mut buf, 12, 12, &returning).unwrap();
+        assert_eq!(response.returned.len(), 2);
+        let rows = &response.returned[0];
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get(0), Some(&OracleValue::Integer(1)));
+        assert_eq!(rows[0].get(1), Some(&OracleValue::String("a".to_string())));
+        assert_eq!(rows[1].get(0), Some(&OracleValue::Integer(2)));
+        assert_eq!(rows[1].get(1), Some(&OracleValue::Null));
+        assert!(response.returned[1].is_empty());
+
+        // A value that did not fit its bind reports its full length
+        let mut wire = vec![TNS_MSG_TYPE_ROW_DATA];
+        wire.extend_from_slice(&[1, 1, 2, 0xC1, 0x02, 0, 1, 1, 1, b'a', 1, 9]);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let err = parse_returning_execute_response(&mut buf, 12, 12, &returning).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_fetch_response_chunked_value_prefixes() {
+        use crate::protocol::buffer::{LengthPrefixedBytes, WireValue};
+
+        // A VARCHAR2(32767) value and a LONG spanning several chunks, as
+        // when their packets have not all arrived yet
+        let columns = [
+            ColumnMetadata::new("V".to_string(), ORA_TYPE_NUM_VARCHAR as u8),
+            ColumnMetadata::new("L".to_string(), ORA_TYPE_NUM_LONG as u8),
+        ];
+        let text = "v".repeat(32767);
+        let long = "l".repeat(2 * TNS_CHUNK_SIZE + 10);
+        let mut wire = vec![TNS_MSG_TYPE_ROW_DATA];
+        LengthPrefixedBytes(text.as_bytes()).encode(&mut wire);
+        LengthPrefixedBytes(long.as_bytes()).encode(&mut wire);
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        // Every cut, around the chunk headers in particular, asks for more
+        // data rather than failing or decoding a shorter value
+        let header = 6;
+        let cuts = (0..wire.len()).filter(|&cut| {
+            cut < 2 * header
+                || (cut + header) % TNS_CHUNK_SIZE < 2 * header
+                || cut % 997 == 0
+                || cut > wire.len() - header
+        });
+        for cut in cuts {
+            let mut buf = ReadBuffer::new(bytes::Bytes::copy_from_slice(&wire[..cut]));
+            match parse_fetch_response(&mut buf, &columns, None, 12) {
+                Err(Error::BufferTooSmall { .. }) => {}
+                other => panic!("cut at {}: {:?}", cut, other.map(|r| r.rows.len())),
+            }
+        }
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_fetch_response(&mut buf, &columns, None, 12).unwrap();
+        let row = &response.rows[0];
+        assert_eq!(row.get(0).and_then(|v| v.as_str()), Some(text.as_str()));
+        assert_eq!(row.get(1).and_then(|v| v.as_str()), Some(long.as_str()));
+    }
+
+    #[test]
+    fn test_parse_fetch_response_rejects_describe_info() {
+        let columns = [ColumnMetadata::new(
+            "A".to_string(),
+            ORA_TYPE_NUM_VARCHAR as u8,
+        )];
+        let wire = vec![TNS_MSG_TYPE_DESCRIBE_INFO, 0, 1, 1];
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let err = parse_fetch_response(&mut buf, &columns, None, 12).unwrap_err();
+        assert!(err.to_string().contains("columns changed"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_fetch_response_duplicate_columns() {
+        let columns = [
+            ColumnMetadata::new("A".to_string(), ORA_TYPE_NUM_VARCHAR as u8),
+            ColumnMetadata::new("B".to_string(), ORA_TYPE_NUM_VARCHAR as u8),
+        ];
+        let text = |row: &Row, i: usize| row.get(i).and_then(|v| v.as_str().map(str::to_string));
+
+        let mut wire = vec![TNS_MSG_TYPE_ROW_DATA, 1, b'x', 1, b'1'];
+        // Only column B is sent for the second row
+        wire.extend_from_slice(&[TNS_MSG_TYPE_BIT_VECTOR, 1, 1, 0b10]);
+        wire.extend_from_slice(&[TNS_MSG_TYPE_ROW_DATA, 1, b'2']);
+        // The bit vector applies to one row only
+        wire.extend_from_slice(&[TNS_MSG_TYPE_ROW_DATA, 1, b'y', 1, b'3']);
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_fetch_response(&mut buf, &columns, None, 12).unwrap();
+        let rows: Vec<_> = response
+            .rows
+            .iter()
+            .map(|r| (text(r, 0), text(r, 1)))
+            .collect();
+        let expected = [("x", "1"), ("x", "2"), ("y", "3")]
+            .map(|(a, b)| (Some(a.to_string()), Some(b.to_string())));
+        assert_eq!(rows, expected);
+
+        // The first row of a fetch repeats the last row of the previous one
+        let wire = vec![
+            TNS_MSG_TYPE_BIT_VECTOR,
+            1,
+            1,
+            0b01,
+            TNS_MSG_TYPE_ROW_DATA,
+            1,
+            b'z',
+            TNS_MSG_TYPE_END_OF_RESPONSE,
+        ];
+        let last_row = response.rows.last();
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire.clone()));
+        let response = parse_fetch_response(&mut buf, &columns, last_row, 12).unwrap();
+        assert_eq!(text(&response.rows[0], 0).as_deref(), Some("z"));
+        assert_eq!(text(&response.rows[0], 1).as_deref(), Some("3"));
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        assert!(parse_fetch_response(&mut buf, &columns, None, 12).is_err());
+    }
+
+    #[test]
+    fn test_parse_column_value_rowid() {
+        // length byte, then rba/partition/skip/block/slot as UB fields
+        let wire = vec![
+            0x0D, // length
+            0x03, 0x01, 0x1D, 0xEC, // rba = 73196
+            0x01, 0x04, // partition (file) = 4
+            0x00, // skipped
+            0x01, 0x97, // block = 151
+            0x00, // slot = 0
+        ];
+        let col = ColumnMetadata::new("ROWID".to_string(), ORA_TYPE_NUM_ROWID as u8);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        assert_eq!(value, OracleValue::String("AAAR3sAAEAAAACXAAA".to_string()));
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(vec![0x00]));
+        assert!(parse_column_value(&mut buf, &col).unwrap().is_null());
+    }
+
+    #[test]
+    fn test_parse_column_value_json() {
+        // Scalar OSON image for `true`
+        let image = [0xFF, 0x4A, 0x5A, 0x01, 0x00, 0x10, 0x00, 0x01, 0x31];
+        let mut wire = vec![0x01, 0x01]; // UB4 length
+        wire.extend_from_slice(&[0x01, image.len() as u8]); // UB8 size
+        wire.extend_from_slice(&[0x00]); // UB4 chunk size
+        wire.push(image.len() as u8);
+        wire.extend_from_slice(&image);
+        wire.extend_from_slice(&[0x02, 0xAA, 0xBB]); // locator
+
+        let col = ColumnMetadata::new("J".to_string(), ORA_TYPE_NUM_JSON as u8);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        #[cfg(feature = "json")]
+        assert_eq!(value, OracleValue::Json(serde_json::Value::Bool(true)));
+        #[cfg(not(feature = "json"))]
+        assert_eq!(value, OracleValue::Raw(image.to_vec()));
+        assert_eq!(buf.remaining(), 0);
+    }
+
+    #[test]
+    fn test_parse_column_value_vector() {
+        // INT8 vector [1, -1]
+        let image = [
+            0xDB, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02, 0x01, 0xFF,
+        ];
+        let mut wire = vec![0x01, 0x01]; // UB4 length
+        wire.extend_from_slice(&[0x01, image.len() as u8]); // UB8 size
+        wire.extend_from_slice(&[0x00]); // UB4 chunk size
+        wire.push(image.len() as u8);
+        wire.extend_from_slice(&image);
+        wire.extend_from_slice(&[0x02, 0xAA, 0xBB]); // locator
+        wire.push(0x00); // NULL vector
+
+        let col = ColumnMetadata::new("V".to_string(), ORA_TYPE_NUM_VECTOR as u8);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        assert_eq!(value.to_f32_vec(), Some(vec![1.0, -1.0]));
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Null
+        );
+        assert_eq!(buf.remaining(), 0);
+    }
+
+    #[test]
+    fn test_parse_column_value_lob() {
+        let mut wire = vec![0x01, 0x70]; // UB4 length
+        wire.extend_from_slice(&[0x01, 0x05]); // UB8 size
+        wire.extend_from_slice(&[0x02, 0x1F, 0xC4]); // UB4 chunk size
+        wire.extend_from_slice(&[0x03, 0xAA, 0xBB, 0xCC]); // locator
+        wire.push(0x00); // NULL LOB
+
+        let col = ColumnMetadata::new("L".to_string(), ORA_TYPE_NUM_BLOB as u8);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        let lob = value.as_lob().unwrap();
+        assert!(lob.is_blob());
+        assert_eq!(lob.size(), 5);
+        assert_eq!(lob.chunk_size(), 8132);
+        assert_eq!(lob.locator(), &[0xAA, 0xBB, 0xCC]);
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Null
+        );
+        assert_eq!(buf.remaining(), 0);
+    }
+
+    #[test]
+    fn test_parse_column_value_bfile() {
+        let wire = vec![0x01, 0x70, 0x03, 0xAA, 0xBB, 0xCC]; // length, locator
+        let col = ColumnMetadata::new("F".to_string(), ORA_TYPE_NUM_BFILE as u8);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        let lob = value.as_lob().unwrap();
+        assert!(lob.is_bfile());
+        assert_eq!(lob.size(), 0);
+        assert_eq!(lob.locator(), &[0xAA, 0xBB, 0xCC]);
+        assert_eq!(buf.remaining(), 0);
+    }
+
+    #[test]
+    fn test_parse_lob_op_response_file_exists() {
+        let locator = [0u8; 4];
+        let msg = LobOpMessage::file_exists(&locator);
+
+        let mut wire = vec![TNS_MSG_TYPE_PARAMETER];
+        wire.extend_from_slice(&[9, 8, 7, 6]); // updated locator
+        wire.push(1); // flag
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_lob_op_response(&mut buf, &msg, 0).unwrap();
+        assert!(response.flag);
+        assert_eq!(response.amount, None);
+        assert_eq!(response.locator.as_deref(), Some(&[9, 8, 7, 6][..]));
+    }
+
+    #[test]
+    fn test_parse_implicit_results() {
+        // Describe info for one NUMBER column "N" (pre-12.2 field version)
+        let mut describe = vec![0x00, 0x01, 0x01, 0x00]; // max row size, 1 column, flags
+        describe.extend_from_slice(&[ORA_TYPE_NUM_NUMBER as u8, 0, 0, 0]); // type, flags, precision, scale
+        describe.extend_from_slice(&[0x01, 0x16, 0x00, 0x00]); // buffer size, max elements, cont flags
+        describe.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // OID, version, charset, form
+        describe.extend_from_slice(&[0x01, 0x16, 0x01, 0x00]); // max size, nullable, v7 length
+        describe.extend_from_slice(&[0x01, 0x01, 0x01, b'N']); // name
+        describe.extend_from_slice(&[0x00, 0x00, 0x01, 0x01, 0x00]); // schema, type name, position, UDS flags
+        describe.extend_from_slice(&[0x00; 6]); // trailing describe fields
+
+        let mut wire = vec![0x01, 0x02]; // two result sets
+        wire.extend_from_slice(&[0x02, 0xAA, 0xBB]); // opaque bytes
+        wire.extend_from_slice(&describe);
+        wire.extend_from_slice(&[0x01, 0x2A]); // cursor 42
+        wire.extend_from_slice(&[0x00, 0x00, 0x00]); // no bytes, no columns
+        wire.extend_from_slice(&[0x00; 6]);
+        wire.extend_from_slice(&[0x01, 0x2B]); // cursor 43
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let results = parse_implicit_results(&mut buf, 7).unwrap();
+        assert_eq!(buf.remaining(), 0);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].cursor_id, 42);
+        assert_eq!(results[0].columns.len(), 1);
+        assert_eq!(results[0].columns[0].name, "N");
+        assert_eq!(results[0].columns[0].max_size, 22);
+        assert!(results[0].columns[0].nullable);
+        assert_eq!(results[1].cursor_id, 43);
+        assert!(results[1].columns.is_empty());
+    }
+
+    #[test]
+    fn test_parse_lob_op_response_incomplete() {
+        let locator = [0u8; 4];
+        let msg = LobOpMessage::read(&locator, 1, 100);
+
+        // Cut off at a packet boundary, before the end-of-call status
+        let wire = vec![TNS_MSG_TYPE_LOB_DATA, 0x03, b'a', b'b', b'c'];
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire.clone()));
+        assert!(matches!(
+            parse_lob_op_response(&mut buf, &msg, 0),
+            Err(Error::BufferTooSmall { .. })
+        ));
+
+        // Cut off inside a message
+        let mut buf = ReadBuffer::new(bytes::Bytes::copy_from_slice(&wire[..3]));
+        assert!(matches!(
+            parse_lob_op_response(&mut buf, &msg, 0),
+            Err(Error::BufferTooSmall { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_lob_op_response_read() {
+        let locator = [0u8; 4];
+        let msg = LobOpMessage::read(&locator, 1, 100);
+
+        let mut wire = vec![TNS_MSG_TYPE_LOB_DATA, 0x03, b'a', b'b', b'c'];
+        wire.push(TNS_MSG_TYPE_PARAMETER);
+        wire.extend_from_slice(&[9, 8, 7, 6]); // updated locator
+        wire.extend_from_slice(&[0x01, 0x03]); // SB8 amount
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_lob_op_response(&mut buf, &msg, 0).unwrap();
+        assert_eq!(response.data.as_deref(), Some(&b"abc"[..]));
+        assert_eq!(response.locator.as_deref(), Some(&[9, 8, 7, 6][..]));
+        assert_eq!(response.amount, Some(3));
+        assert!(!response.flag);
+    }
+
+    #[test]
+    fn test_parse_lob_op_response_create_temp() {
+        let locator = [0u8; 2];
+        let msg = LobOpMessage::create_temp(&locator, CS_FORM_IMPLICIT, ORA_TYPE_NUM_CLOB);
+
+        let mut wire = vec![TNS_MSG_TYPE_PARAMETER, 0x11, 0x22];
+        wire.extend_from_slice(&[0x02, 0x03, 0x69]); // charset
+        wire.extend_from_slice(&[0x01, 0x0A]); // duration
+        wire.push(0x01); // flag
+        wire.push(TNS_MSG_TYPE_END_OF_RESPONSE);
+
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let response = parse_lob_op_response(&mut buf, &msg, 0).unwrap();
+        assert_eq!(response.locator.as_deref(), Some(&[0x11, 0x22][..]));
+        assert!(response.flag);
+        assert_eq!(buf.remaining(), 0);
+    }
+
+    #[test]
+    fn test_parse_column_value_boolean() {
+        let col = ColumnMetadata::new("B".to_string(), ORA_TYPE_NUM_BOOLEAN as u8);
+        let wire = vec![0x01, 0x01, 0x01, 0x00, 0x00];
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Boolean(true)
+        );
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Boolean(false)
+        );
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Null
+        );
+    }
+
+    #[test]
+    fn test_parse_column_value_integer() {
+        let mut col = ColumnMetadata::new("ID".to_string(), ORA_TYPE_NUM_NUMBER as u8);
+        col.precision = 10;
+        // 2147483648, then -1
+        let wire = vec![
+            0x06, 0xC5, 0x16, 0x30, 0x31, 0x25, 0x31, 0x03, 0x3E, 0x64, 0x66,
+        ];
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire.clone()));
+        assert!(matches!(
+            parse_column_cell(&mut buf, &col).unwrap(),
+            Cell::Value(OracleValue::Integer(2147483648))
+        ));
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap(),
+            OracleValue::Integer(-1)
+        );
+
+        // Other NUMBER columns keep the string form
+        col.scale = 2;
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        assert_eq!(
+            parse_column_value(&mut buf, &col).unwrap().to_i64(),
+            Some(2147483648)
+        );
+        assert!(matches!(
+            parse_column_cell(&mut buf, &col).unwrap(),
+            Cell::Encoded { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_column_value_raw() {
+        // Binary content that is not valid UTF-8 must survive unchanged
+        let data: Vec<u8> = vec![0x00, 0xFF, 0xFE, 0x80, 0x7F];
+        for ty in [ORA_TYPE_NUM_RAW, ORA_TYPE_NUM_LONG_RAW] {
+            let col = ColumnMetadata::new("R".to_string(), ty as u8);
+            let mut wire = vec![data.len() as u8];
+            wire.extend_from_slice(&data);
+            let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+            let value = parse_column_value(&mut buf, &col).unwrap();
+            assert_eq!(value, OracleValue::Raw(data.clone()));
+        }
+    }
+
+    #[test]
+    fn test_parse_column_value_nchar() {
+        // NVARCHAR2 values are sent as UTF-16BE
+        let mut col = ColumnMetadata::new("N".to_string(), ORA_TYPE_NUM_VARCHAR as u8);
+        col.charset_form = CS_FORM_NCHAR;
+        let mut buf = ReadBuffer::new(bytes::Bytes::from_static(&[
+            0x06, 0x00, 0x41, 0x00, 0xE9, 0x04, 0x2F,
+        ]));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        assert_eq!(value, OracleValue::String("A\u{e9}\u{42f}".to_string()));
+
+        // NCLOB locators are read as national character set LOBs
+        let mut col = ColumnMetadata::new("L".to_string(), ORA_TYPE_NUM_CLOB as u8);
+        col.charset_form = CS_FORM_NCHAR;
+        let mut wire = vec![0x01, 0x01, 0x01, 0x01, 0x01, 0x04];
+        wire.extend_from_slice(&[0x04, 0xAA, 0xBB, 0xCC, 0xDD]);
+        let mut buf = ReadBuffer::new(bytes::Bytes::from(wire));
+        let value = parse_column_value(&mut buf, &col).unwrap();
+        assert_eq!(value.as_lob().unwrap().oracle_type(), &OracleType::Nclob);
+    }
 }
@@ -0,0 +1,239 @@
+//! Session settings applied at connect time.
+//!
+//! The settings are sent as the `ALTER SESSION` statement that
+//! authentication runs once the session is created, so applying them costs
+//! no round trip of its own. Switching to another container is the
+//! exception: it is a statement of its own, run after logon.
+
+use std::fmt::Write;
+
+/// Session settings applied right after authentication.
+///
+/// Settings left unset keep the database defaults, except the time zone,
+/// which defaults to the client's local UTC offset as in python-oracledb.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::{ConnectParams, SessionSettings};
+///
+/// let settings = SessionSettings::new()
+///     .with_nls_date_format("YYYY-MM-DD HH24:MI:SS")
+///     .with_time_zone("UTC")
+///     .with_current_schema("APP_OWNER");
+/// let params = ConnectParams::new("localhost", 1521, "FREEPDB1").with_session_settings(settings);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSettings {
+    /// Session time zone (`TIME_ZONE`), e.g. `+02:00` or `Europe/Paris`.
+    pub time_zone: Option<String>,
+    /// Default date format (`NLS_DATE_FORMAT`).
+    pub nls_date_format: Option<String>,
+    /// Decimal and group separators (`NLS_NUMERIC_CHARACTERS`), e.g. `.,`.
+    pub nls_numeric_characters: Option<String>,
+    /// Edition used for editioned objects (`EDITION`).
+    pub edition: Option<String>,
+    /// Default schema for unqualified names (`CURRENT_SCHEMA`).
+    pub current_schema: Option<String>,
+    /// Pluggable database switched to after logon (`CONTAINER`).
+    pub container: Option<String>,
+}
+
+impl SessionSettings {
+    /// Create settings that keep the database defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the session time zone.
+    pub fn with_time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Set the default date format.
+    pub fn with_nls_date_format(mut self, format: impl Into<String>) -> Self {
+        self.nls_date_format = Some(format.into());
+        self
+    }
+
+    /// Set the decimal and group separators.
+    pub fn with_nls_numeric_characters(mut self, characters: impl Into<String>) -> Self {
+        self.nls_numeric_characters = Some(characters.into());
+        self
+    }
+
+    /// Set the edition.
+    pub fn with_edition(mut self, edition: impl Into<String>) -> Self {
+        self.edition = Some(edition.into());
+        self
+    }
+
+    /// Set the default schema.
+    pub fn with_current_schema(mut self, schema: impl Into<String>) -> Self {
+        self.current_schema = Some(schema.into());
+        self
+    }
+
+    /// Switch to a pluggable database after logon, for common users
+    /// connecting through the container database's service.
+    ///
+    /// Switching takes two extra round trips: one for `ALTER SESSION SET
+    /// CONTAINER`, and one to apply the other settings inside the new
+    /// container, where the edition and schema are looked up.
+    pub fn with_container(mut self, container: impl Into<String>) -> Self {
+        self.container = Some(container.into());
+        self
+    }
+
+    /// Build the NUL-terminated `ALTER SESSION` statement sent with
+    /// authentication.
+    ///
+    /// With a container, only the time zone is set at logon; the rest
+    /// follows the switch (see `container_statements`).
+    pub(crate) fn logon_statement(&self) -> String {
+        match self.container {
+            Some(_) => SessionSettings {
+                time_zone: self.time_zone.clone(),
+                ..Self::default()
+            }
+            .alter_session_statement(),
+            None => self.alter_session_statement(),
+        }
+    }
+
+    /// Statements run after logon to switch to the container and apply
+    /// the settings there.
+    pub(crate) fn container_statements(&self) -> Vec<String> {
+        let Some(container) = &self.container else {
+            return Vec::new();
+        };
+        let mut settings = self.alter_session_statement();
+        settings.pop();
+        vec![
+            format!(
+                "ALTER SESSION SET CONTAINER={}",
+                quote_identifier(container)
+            ),
+            settings,
+        ]
+    }
+
+    /// Build the NUL-terminated `ALTER SESSION` statement for every setting
+    /// but the container.
+    fn alter_session_statement(&self) -> String {
+        let time_zone = self
+            .time_zone
+            .clone()
+            .unwrap_or_else(|| local_utc_offset(chrono::Local::now().offset()));
+
+        let mut stmt = format!("ALTER SESSION SET TIME_ZONE={}", quote_literal(&time_zone));
+        if let Some(format) = &self.nls_date_format {
+            let _ = write!(stmt, " NLS_DATE_FORMAT={}", quote_literal(format));
+        }
+        if let Some(characters) = &self.nls_numeric_characters {
+            let _ = write!(
+                stmt,
+                " NLS_NUMERIC_CHARACTERS={}",
+                quote_literal(characters)
+            );
+        }
+        if let Some(edition) = &self.edition {
+            let _ = write!(stmt, " EDITION={}", quote_identifier(edition));
+        }
+        if let Some(schema) = &self.current_schema {
+            let _ = write!(stmt, " CURRENT_SCHEMA={}", quote_identifier(schema));
+        }
+        stmt.push('\0');
+        stmt
+    }
+}
+
+/// Format a UTC offset as `+HH:MM`.
+fn local_utc_offset(offset: &chrono::FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds >= 0 { '+' } else { '-' };
+    let seconds = seconds.abs();
+    format!("{}{:02}:{:02}", sign, seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// Quote a string literal, doubling embedded quotes.
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Write an identifier as is when it is a plain name, which Oracle folds to
+/// upper case, and quoted otherwise.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '#'));
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alter_session_statement() {
+        let settings = SessionSettings::new()
+            .with_time_zone("Europe/Paris")
+            .with_nls_date_format("DD-MON-YYYY")
+            .with_nls_numeric_characters(",.")
+            .with_edition("release_2")
+            .with_current_schema("Mixed Case");
+        assert_eq!(
+            settings.alter_session_statement(),
+            "ALTER SESSION SET TIME_ZONE='Europe/Paris' NLS_DATE_FORMAT='DD-MON-YYYY' \
+             NLS_NUMERIC_CHARACTERS=',.' EDITION=release_2 CURRENT_SCHEMA=\"Mixed Case\"\0"
+        );
+
+        let settings = SessionSettings::new().with_nls_date_format("YYYY' 'MM");
+        assert!(settings
+            .alter_session_statement()
+            .ends_with(" NLS_DATE_FORMAT='YYYY'' ''MM'\0"));
+    }
+
+    #[test]
+    fn test_container_statements() {
+        let settings = SessionSettings::new()
+            .with_time_zone("UTC")
+            .with_edition("release_2");
+        assert_eq!(
+            settings.logon_statement(),
+            "ALTER SESSION SET TIME_ZONE='UTC' EDITION=release_2\0"
+        );
+        assert!(settings.container_statements().is_empty());
+
+        let settings = settings.with_container("sales_pdb");
+        assert_eq!(
+            settings.logon_statement(),
+            "ALTER SESSION SET TIME_ZONE='UTC'\0"
+        );
+        assert_eq!(
+            settings.container_statements(),
+            [
+                "ALTER SESSION SET CONTAINER=sales_pdb",
+                "ALTER SESSION SET TIME_ZONE='UTC' EDITION=release_2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_time_zone() {
+        let stmt = SessionSettings::new().alter_session_statement();
+        assert!(stmt.starts_with("ALTER SESSION SET TIME_ZONE='"));
+        assert!(stmt.ends_with("'\0"));
+
+        let offset = chrono::FixedOffset::west_opt(3 * 3600 + 30 * 60).unwrap();
+        assert_eq!(local_utc_offset(&offset), "-03:30");
+        let offset = chrono::FixedOffset::east_opt(5 * 3600 + 45 * 60).unwrap();
+        assert_eq!(local_utc_offset(&offset), "+05:45");
+    }
+}
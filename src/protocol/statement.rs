@@ -0,0 +1,659 @@
+//! SQL statement classification.
+//!
+//! Determines the statement type from the leading keyword and scans the SQL
+//! text for bind variable placeholders, skipping comments, string literals and
+//! quoted identifiers. The result drives the execute options sent to the server.
+
+use crate::error::{Error, Result};
+
+/// Kind of SQL statement, as determined from its leading keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementType {
+    /// SELECT or WITH (including WITH FUNCTION/PROCEDURE declarations).
+    Query,
+    /// INSERT, UPDATE, DELETE or MERGE.
+    Dml,
+    /// Anonymous PL/SQL block or CALL.
+    PlSql,
+    /// CREATE, ALTER, DROP, TRUNCATE and other DDL.
+    Ddl,
+    /// Anything else (e.g. COMMIT, SET TRANSACTION).
+    Other,
+}
+
+/// A bind placeholder in a statement.
+///
+/// Oracle does not describe the types of bind placeholders, only of the
+/// columns a query returns; the value bound at a placeholder decides its
+/// type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindInfo {
+    /// Placeholder name, uppercased unless quoted (`1` for `:1`).
+    pub name: String,
+    /// Position (1-based) of the value bound to this placeholder.
+    pub position: usize,
+    /// Number of times the placeholder appears in the SQL text.
+    pub occurrences: usize,
+    /// Whether this is a DML `RETURNING ... INTO` target, filled in by the
+    /// server rather than by a parameter.
+    pub is_return: bool,
+}
+
+/// A classified SQL statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    sql: String,
+    statement_type: StatementType,
+    is_insert: bool,
+    has_plsql_declarations: bool,
+    bind_names: Vec<String>,
+    bind_info: Vec<BindInfo>,
+}
+
+impl Statement {
+    /// Classify a SQL statement.
+    ///
+    /// Bind names are collected in order of first appearance. Unquoted names
+    /// are uppercased; quoted names (`:"name"`) are kept as written. DDL is
+    /// never scanned for binds, since `:new`/`:old` in trigger bodies are not
+    /// placeholders.
+    pub fn parse(sql: &str) -> Self {
+        let tokens = tokenize(sql);
+
+        let mut keywords = tokens.iter().filter_map(|t| match t {
+            Token::Word(w) => Some(w.as_str()),
+            Token::Bind(_) => None,
+        });
+        let first = keywords.next().unwrap_or("");
+        let statement_type = match first {
+            "SELECT" | "WITH" => StatementType::Query,
+            "INSERT" | "UPDATE" | "DELETE" | "MERGE" => StatementType::Dml,
+            "DECLARE" | "BEGIN" | "CALL" => StatementType::PlSql,
+            "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "GRANT" | "REVOKE" | "ANALYZE" | "AUDIT"
+            | "NOAUDIT" | "COMMENT" | "RENAME" | "PURGE" | "FLASHBACK" => StatementType::Ddl,
+            _ => StatementType::Other,
+        };
+
+        let is_insert = first == "INSERT";
+
+        // WITH FUNCTION/PROCEDURE introduces PL/SQL declarations (12c+)
+        let has_plsql_declarations =
+            first == "WITH" && matches!(keywords.next(), Some("FUNCTION" | "PROCEDURE"));
+
+        let mut bind_info: Vec<BindInfo> = Vec::new();
+        if statement_type != StatementType::Ddl {
+            // DML binds after RETURNING ... INTO receive values
+            let mut returning = false;
+            let mut is_return = false;
+            for token in tokens {
+                match token {
+                    Token::Word(w) if statement_type == StatementType::Dml => {
+                        if w == "RETURNING" || w == "RETURN" {
+                            returning = true;
+                        } else if returning && w == "INTO" {
+                            is_return = true;
+                        }
+                    }
+                    Token::Word(_) => {}
+                    Token::Bind(name) => match bind_info.iter_mut().find(|b| b.name == name) {
+                        Some(bind) => bind.occurrences += 1,
+                        None => bind_info.push(BindInfo {
+                            name,
+                            position: bind_info.len() + 1,
+                            occurrences: 1,
+                            is_return,
+                        }),
+                    },
+                }
+            }
+        }
+
+        Self {
+            sql: sql.to_string(),
+            statement_type,
+            is_insert,
+            has_plsql_declarations,
+            bind_names: bind_info.iter().map(|b| b.name.clone()).collect(),
+            bind_info,
+        }
+    }
+
+    /// Get the SQL text.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Get the statement type.
+    pub fn statement_type(&self) -> StatementType {
+        self.statement_type
+    }
+
+    /// Check if this statement returns rows.
+    pub fn is_query(&self) -> bool {
+        self.statement_type == StatementType::Query
+    }
+
+    /// Check if this is an anonymous PL/SQL block or CALL.
+    ///
+    /// Queries with `WITH FUNCTION` declarations are not PL/SQL blocks.
+    pub fn is_plsql(&self) -> bool {
+        self.statement_type == StatementType::PlSql
+    }
+
+    /// Check if this is a DML statement.
+    pub fn is_dml(&self) -> bool {
+        self.statement_type == StatementType::Dml
+    }
+
+    /// Check if this is an INSERT statement.
+    pub fn is_insert(&self) -> bool {
+        self.is_insert
+    }
+
+    /// Check if this is a DDL statement.
+    pub fn is_ddl(&self) -> bool {
+        self.statement_type == StatementType::Ddl
+    }
+
+    /// Check if this query declares PL/SQL functions or procedures in its WITH clause.
+    pub fn has_plsql_declarations(&self) -> bool {
+        self.has_plsql_declarations
+    }
+
+    /// Get the bind variable names in order of first appearance.
+    pub fn bind_names(&self) -> &[String] {
+        &self.bind_names
+    }
+
+    /// Get the bind placeholders in order of first appearance, with the
+    /// position of the value bound to each.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::Statement;
+    ///
+    /// let stmt = Statement::parse("SELECT * FROM t WHERE a = :a OR b = :a AND c = :c");
+    /// let binds = stmt.bind_info();
+    /// assert_eq!(binds[0].name, "A");
+    /// assert_eq!(binds[0].occurrences, 2);
+    /// assert_eq!(binds[1].position, 2);
+    /// ```
+    pub fn bind_info(&self) -> &[BindInfo] {
+        &self.bind_info
+    }
+
+    /// Check if this is DML with a `RETURNING ... INTO` clause.
+    pub fn is_returning(&self) -> bool {
+        self.bind_info.iter().any(|b| b.is_return)
+    }
+
+    /// Get the number of `RETURNING ... INTO` binds, which follow all other
+    /// binds.
+    pub fn num_return_binds(&self) -> usize {
+        self.bind_info.iter().filter(|b| b.is_return).count()
+    }
+
+    /// Check that `params` has one value for each bind placeholder other
+    /// than `RETURNING ... INTO` targets.
+    ///
+    /// Returns `Error::BindCountMismatch` otherwise, so a wrong number of
+    /// parameters can be reported before anything is sent.
+    pub fn check_params<T>(&self, params: &[T]) -> Result<()> {
+        let expected = self.bind_info.len() - self.num_return_binds();
+        if params.len() != expected {
+            return Err(Error::BindCountMismatch {
+                row: 0,
+                expected,
+                actual: params.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Significant token in SQL text.
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Unquoted word, uppercased.
+    Word(String),
+    /// Bind placeholder name (without the colon).
+    Bind(String),
+}
+
+/// Split SQL text into words and bind placeholders.
+///
+/// Comments, string literals (including q-quoted literals), quoted
+/// identifiers and punctuation are skipped.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c == '-' && next == Some('-') {
+            // Single-line comment
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            // Multi-line comment
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '\'' {
+            i = skip_string_literal(&chars, i);
+        } else if c == '"' {
+            i = skip_until(&chars, i + 1, '"');
+        } else if c == ':' {
+            i += 1;
+            match chars.get(i) {
+                Some('"') => {
+                    let start = i + 1;
+                    let len = chars[start..]
+                        .iter()
+                        .position(|&ch| ch == '"')
+                        .unwrap_or(chars.len() - start);
+                    tokens.push(Token::Bind(chars[start..start + len].iter().collect()));
+                    i = (start + len + 1).min(chars.len());
+                }
+                Some(&ch) if ch.is_alphanumeric() || ch == '_' => {
+                    let start = i;
+                    while i < chars.len() && is_identifier_char(chars[i]) {
+                        i += 1;
+                    }
+                    let name: String = chars[start..i].iter().collect();
+                    tokens.push(Token::Bind(name.to_uppercase()));
+                }
+                // `:=` and other uses of a bare colon
+                _ => {}
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && is_identifier_char(chars[i]) {
+                i += 1;
+            }
+            // q'[...]' and nq'[...]' alternative quoting
+            if chars.get(i) == Some(&'\'') {
+                let prefix: String = chars[start..i].iter().collect::<String>().to_uppercase();
+                if prefix == "Q" || prefix == "NQ" {
+                    i = skip_q_literal(&chars, i);
+                    continue;
+                }
+                if prefix == "N" {
+                    continue;
+                }
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(Token::Word(word.to_uppercase()));
+        } else {
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Get the first keyword of SQL text, uppercased, such as `SELECT` or
+/// `BEGIN`.
+#[cfg(feature = "otel")]
+pub(crate) fn leading_keyword(sql: &str) -> Option<String> {
+    tokenize(sql).into_iter().find_map(|token| match token {
+        Token::Word(word) => Some(word),
+        Token::Bind(_) => None,
+    })
+}
+
+/// Replace the string and numeric literals of SQL text with `?`.
+///
+/// Comments, identifiers, quoted identifiers and bind placeholders are
+/// kept, so the text still shows what the statement does without the
+/// values written into it.
+pub(crate) fn redact_literals(sql: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = String::with_capacity(sql.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+
+        if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+        } else if c == '\'' {
+            i = skip_string_literal(&chars, i);
+            out.push('?');
+            continue;
+        } else if c == '"' {
+            i = skip_until(&chars, i + 1, '"');
+        } else if c == ':' {
+            i += 1;
+            if chars.get(i) == Some(&'"') {
+                i = skip_until(&chars, i + 1, '"');
+            } else {
+                while i < chars.len() && is_identifier_char(chars[i]) {
+                    i += 1;
+                }
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && is_identifier_char(chars[i]) {
+                i += 1;
+            }
+            if chars.get(i) == Some(&'\'') {
+                let prefix: String = chars[start..i].iter().collect::<String>().to_uppercase();
+                let end = match prefix.as_str() {
+                    "Q" | "NQ" => Some(skip_q_literal(&chars, i)),
+                    "N" => Some(skip_string_literal(&chars, i)),
+                    _ => None,
+                };
+                if let Some(end) = end {
+                    i = end;
+                    out.push('?');
+                    continue;
+                }
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.'
+                && next.is_some_and(|n| n.is_ascii_digit())
+                && (i == 0 || chars[i - 1] != '.'))
+        {
+            i = skip_number(&chars, i);
+            out.push('?');
+            continue;
+        } else {
+            i += 1;
+        }
+        out.extend(&chars[start..i]);
+    }
+
+    out
+}
+
+/// Skip a numeric literal such as `42`, `.5`, `1.5e-3` or `2.0f`.
+///
+/// A `..` range (`FOR i IN 1..10`) ends the number before the dots.
+fn skip_number(chars: &[char], start: usize) -> usize {
+    let digits = |mut i: usize| {
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+    let mut i = digits(start);
+    if chars.get(i) == Some(&'.') && chars.get(i + 1) != Some(&'.') {
+        i = digits(i + 1);
+    }
+    if matches!(chars.get(i), Some('e' | 'E')) {
+        let exponent = match chars.get(i + 1) {
+            Some('+' | '-') => i + 2,
+            _ => i + 1,
+        };
+        if chars.get(exponent).is_some_and(char::is_ascii_digit) {
+            i = digits(exponent);
+        }
+    }
+    if matches!(chars.get(i), Some('f' | 'F' | 'd' | 'D'))
+        && !chars.get(i + 1).is_some_and(|&c| is_identifier_char(c))
+    {
+        i += 1;
+    }
+    i
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '#'
+}
+
+/// Return the index just past the next `end` at or after `from`.
+fn skip_until(chars: &[char], from: usize, end: char) -> usize {
+    chars[from.min(chars.len())..]
+        .iter()
+        .position(|&c| c == end)
+        .map_or(chars.len(), |p| from + p + 1)
+}
+
+/// Skip a '...' literal starting at `start`, honouring '' escapes.
+fn skip_string_literal(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\'' {
+            if chars.get(i + 1) == Some(&'\'') {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Skip a q'<delim>...<delim>' literal; `start` points at the opening quote.
+fn skip_q_literal(chars: &[char], start: usize) -> usize {
+    let Some(&open) = chars.get(start + 1) else {
+        return chars.len();
+    };
+    let close = match open {
+        '[' => ']',
+        '{' => '}',
+        '(' => ')',
+        '<' => '>',
+        other => other,
+    };
+    let mut i = start + 2;
+    while i + 1 < chars.len() {
+        if chars[i] == close && chars[i + 1] == '\'' {
+            return i + 2;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_types() {
+        let cases = [
+            ("SELECT * FROM DUAL", StatementType::Query),
+            ("  (select 1 from dual)", StatementType::Query),
+            (
+                "with t as (select 1 x from dual) select * from t",
+                StatementType::Query,
+            ),
+            ("INSERT INTO t VALUES (1)", StatementType::Dml),
+            ("merge into t using s on (1=1)", StatementType::Dml),
+            ("BEGIN NULL; END;", StatementType::PlSql),
+            ("declare x number; begin null; end;", StatementType::PlSql),
+            ("CALL my_proc()", StatementType::PlSql),
+            ("CREATE TABLE t (x NUMBER)", StatementType::Ddl),
+            ("COMMIT", StatementType::Other),
+            ("", StatementType::Other),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(Statement::parse(sql).statement_type(), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_leading_comments() {
+        let stmt = Statement::parse("-- header\n/* hint */ SELECT 1 FROM DUAL");
+        assert!(stmt.is_query());
+
+        let stmt = Statement::parse("/* BEGIN */ UPDATE t SET x = 1");
+        assert!(stmt.is_dml());
+        assert!(!stmt.is_insert());
+
+        let stmt = Statement::parse("/* INSERT */ insert into t values (1)");
+        assert!(stmt.is_insert());
+    }
+
+    #[test]
+    fn test_with_plsql_declarations() {
+        let sql = "WITH FUNCTION double_it(n NUMBER) RETURN NUMBER IS
+                     v NUMBER;
+                   BEGIN
+                     v := n * 2;
+                     RETURN v;
+                   END;
+                   SELECT double_it(:val) FROM DUAL";
+        let stmt = Statement::parse(sql);
+        assert!(stmt.is_query());
+        assert!(!stmt.is_plsql());
+        assert!(stmt.has_plsql_declarations());
+        // `:=` assignment inside the declaration is not a bind
+        assert_eq!(stmt.bind_names(), ["VAL"]);
+
+        let stmt = Statement::parse("with procedure p is begin null; end; select 1 from dual");
+        assert!(stmt.has_plsql_declarations());
+
+        let stmt = Statement::parse(
+            "WITH function_results AS (SELECT 1 FROM DUAL) SELECT * FROM function_results",
+        );
+        assert!(stmt.is_query());
+        assert!(!stmt.has_plsql_declarations());
+    }
+
+    #[test]
+    fn test_boolean_binds_in_sql() {
+        // 23ai allows BOOLEAN expressions and binds directly in SQL
+        let stmt = Statement::parse("SELECT :flag, NOT :flag FROM DUAL WHERE :flag IS TRUE");
+        assert!(stmt.is_query());
+        assert_eq!(stmt.bind_names(), ["FLAG"]);
+
+        let stmt = Statement::parse("INSERT INTO t (id, active) VALUES (:1, :is_active)");
+        assert!(stmt.is_dml());
+        assert_eq!(stmt.bind_names(), ["1", "IS_ACTIVE"]);
+    }
+
+    #[test]
+    fn test_binds_skip_literals_and_comments() {
+        let sql = "SELECT ':not_bind', q'[:nor_this]', \"COL:X\" -- :comment
+                   /* :block */ FROM t WHERE a = :a AND b = :\"MixedCase\"";
+        let stmt = Statement::parse(sql);
+        assert_eq!(stmt.bind_names(), ["A", "MixedCase"]);
+    }
+
+    #[test]
+    #[cfg(feature = "otel")]
+    fn test_leading_keyword() {
+        assert_eq!(
+            leading_keyword("/* tag */ select 1 from dual").as_deref(),
+            Some("SELECT")
+        );
+        assert_eq!(leading_keyword("  -- nothing"), None);
+    }
+
+    #[test]
+    fn test_redact_literals() {
+        let cases = [
+            (
+                "SELECT * FROM users WHERE name = 'O''Brien' AND age > 42",
+                "SELECT * FROM users WHERE name = ? AND age > ?",
+            ),
+            (
+                "INSERT INTO t (a, b, c) VALUES (:1, q'[it's]', N'x') -- 'kept'",
+                "INSERT INTO t (a, b, c) VALUES (:1, ?, ?) -- 'kept'",
+            ),
+            (
+                "SELECT col2, \"Col 3\", 1.5e-3, .5, 2f FROM t2 WHERE x = :\"Bind1\"",
+                "SELECT col2, \"Col 3\", ?, ?, ? FROM t2 WHERE x = :\"Bind1\"",
+            ),
+            (
+                "BEGIN FOR i IN 1..10 LOOP NULL; END LOOP; END;",
+                "BEGIN FOR i IN ?..? LOOP NULL; END LOOP; END;",
+            ),
+            (
+                "SELECT /*+ FULL(t) */ 1 FROM t",
+                "SELECT /*+ FULL(t) */ ? FROM t",
+            ),
+        ];
+        for (sql, redacted) in cases {
+            assert_eq!(redact_literals(sql), redacted, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_bind_info() {
+        let stmt = Statement::parse("UPDATE t SET a = :a, b = :\"b\" WHERE a <> :A AND c = :1");
+        assert_eq!(
+            stmt.bind_info(),
+            [
+                BindInfo {
+                    name: "A".to_string(),
+                    position: 1,
+                    occurrences: 2,
+                    is_return: false,
+                },
+                BindInfo {
+                    name: "b".to_string(),
+                    position: 2,
+                    occurrences: 1,
+                    is_return: false,
+                },
+                BindInfo {
+                    name: "1".to_string(),
+                    position: 3,
+                    occurrences: 1,
+                    is_return: false,
+                },
+            ]
+        );
+        assert!(!stmt.is_returning());
+        assert!(stmt.check_params(&[1, 2, 3]).is_ok());
+        assert!(matches!(
+            stmt.check_params(&[1, 2]),
+            Err(Error::BindCountMismatch {
+                row: 0,
+                expected: 3,
+                actual: 2
+            })
+        ));
+        assert!(Statement::parse("SELECT 1 FROM DUAL")
+            .check_params::<()>(&[])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_returning_binds() {
+        let stmt = Statement::parse(
+            "INSERT INTO t (name) VALUES (:name) RETURNING id, created INTO :id, :created",
+        );
+        assert!(stmt.is_returning());
+        assert_eq!(stmt.num_return_binds(), 2);
+        let returns: Vec<bool> = stmt.bind_info().iter().map(|b| b.is_return).collect();
+        assert_eq!(returns, [false, true, true]);
+        assert!(stmt.check_params(&["x"]).is_ok());
+
+        let stmt = Statement::parse("DELETE FROM t WHERE id < :id RETURN id INTO :ids");
+        assert_eq!(stmt.num_return_binds(), 1);
+
+        // INTO alone, or outside DML, does not make a returning clause
+        assert!(!Statement::parse("INSERT INTO t VALUES (:a)").is_returning());
+        assert!(!Statement::parse("BEGIN SELECT 1 INTO :x FROM DUAL; END;").is_returning());
+    }
+
+    #[test]
+    fn test_ddl_has_no_binds() {
+        let sql = "CREATE TRIGGER trg BEFORE INSERT ON t FOR EACH ROW BEGIN :new.id := 1; END;";
+        let stmt = Statement::parse(sql);
+        assert!(stmt.is_ddl());
+        assert!(stmt.bind_names().is_empty());
+    }
+}
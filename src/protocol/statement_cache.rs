@@ -0,0 +1,181 @@
+//! Cache of statements left open on the server.
+//!
+//! A statement executed through the cache keeps its server cursor open
+//! once the call is over. Running the same SQL again then re-executes that
+//! cursor (`TNS_FUNC_REEXECUTE` or `TNS_FUNC_REEXECUTE_AND_FETCH`) instead
+//! of sending the text to be parsed, which makes the request smaller and
+//! spares the server the parse. Like python-oracledb's statement cache, the
+//! least recently used statement is closed when the cache is full.
+
+use std::collections::VecDeque;
+
+use crate::protocol::bind::BindInfo;
+use crate::protocol::types::ColumnMetadata;
+
+/// Number of statements cached by default, as in python-oracledb.
+pub const DEFAULT_STATEMENT_CACHE_SIZE: usize = 20;
+
+/// A statement whose server cursor is open.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedStatement {
+    /// SQL text the cursor was parsed from.
+    pub sql: String,
+    /// Server cursor.
+    pub cursor_id: u32,
+    /// Columns of a query, which re-executions are not described with.
+    pub columns: Vec<ColumnMetadata>,
+    /// Bind positions the cursor was bound with.
+    pub binds: Vec<BindInfo>,
+    /// Largest LONG value requested when the cursor was executed.
+    pub max_long_size: u32,
+}
+
+impl CachedStatement {
+    /// Whether the cursor can be executed again with `binds` and
+    /// `max_long_size`, without binding or parsing it anew.
+    ///
+    /// Values must bind with the same types as before, and fit in the
+    /// buffers the cursor was bound with.
+    pub fn accepts(&self, binds: &[BindInfo], max_long_size: u32) -> bool {
+        self.max_long_size == max_long_size
+            && self.binds.len() == binds.len()
+            && self.binds.iter().zip(binds).all(|(bound, bind)| {
+                bound.ora_type_num == bind.ora_type_num
+                    && bound.csfrm == bind.csfrm
+                    && bound.buffer_size >= bind.buffer_size
+            })
+    }
+}
+
+/// Least recently used cache of open statements, keyed by SQL text.
+#[derive(Debug)]
+pub(crate) struct StatementCache {
+    capacity: usize,
+    /// Least recently used first.
+    entries: VecDeque<CachedStatement>,
+}
+
+impl StatementCache {
+    /// Create an empty cache holding up to `capacity` statements.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Get the number of statements the cache holds at most.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the capacity, returning the cursors of the statements that
+    /// no longer fit, to be closed.
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<u32> {
+        self.capacity = capacity;
+        let excess = self.entries.len().saturating_sub(capacity);
+        self.entries
+            .drain(..excess)
+            .map(|entry| entry.cursor_id)
+            .collect()
+    }
+
+    /// Get the number of cached statements.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether a server cursor belongs to a cached statement.
+    pub fn contains_cursor(&self, cursor_id: u32) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.cursor_id == cursor_id)
+    }
+
+    /// Remove the statement cached for `sql`, for the duration of a call.
+    pub fn take(&mut self, sql: &str) -> Option<CachedStatement> {
+        let index = self.entries.iter().position(|entry| entry.sql == sql)?;
+        self.entries.remove(index)
+    }
+
+    /// Cache a statement as the most recently used, returning the cursors
+    /// it pushed out, to be closed.
+    ///
+    /// A statement with the same SQL is replaced. With a capacity of zero
+    /// nothing is cached, and the statement's own cursor is returned.
+    pub fn insert(&mut self, statement: CachedStatement) -> Vec<u32> {
+        let mut evicted: Vec<u32> = self
+            .take(&statement.sql)
+            .map(|old| old.cursor_id)
+            .filter(|&cursor_id| cursor_id != statement.cursor_id)
+            .into_iter()
+            .collect();
+        self.entries.push_back(statement);
+        evicted.extend(self.set_capacity(self.capacity));
+        evicted
+    }
+}
+
+impl Default for StatementCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATEMENT_CACHE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::{ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_VARCHAR};
+
+    fn statement(sql: &str, cursor_id: u32) -> CachedStatement {
+        CachedStatement {
+            sql: sql.to_string(),
+            cursor_id,
+            columns: Vec::new(),
+            binds: Vec::new(),
+            max_long_size: 0,
+        }
+    }
+
+    #[test]
+    fn test_least_recently_used_evicted() {
+        let mut cache = StatementCache::new(2);
+        assert!(cache.insert(statement("A", 1)).is_empty());
+        assert!(cache.insert(statement("B", 2)).is_empty());
+
+        // Using A makes B the least recently used
+        let a = cache.take("A").unwrap();
+        assert!(!cache.contains_cursor(1));
+        assert!(cache.insert(a).is_empty());
+        assert_eq!(cache.insert(statement("C", 3)), [2]);
+        assert!(cache.take("B").is_none());
+
+        // A new cursor for cached SQL replaces the old one
+        assert_eq!(cache.insert(statement("A", 4)), [1]);
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(cache.set_capacity(0), [3, 4]);
+        assert_eq!(cache.insert(statement("D", 5)), [5]);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_accepts_binds() {
+        let bind = |ora_type_num, buffer_size| BindInfo {
+            ora_type_num,
+            buffer_size,
+            csfrm: 0,
+        };
+        let mut cached = statement("A", 1);
+        cached.binds = vec![bind(ORA_TYPE_NUM_VARCHAR, 40)];
+
+        assert!(cached.accepts(&[bind(ORA_TYPE_NUM_VARCHAR, 10)], 0));
+        assert!(cached.accepts(&[bind(ORA_TYPE_NUM_VARCHAR, 40)], 0));
+        // Longer values, other types, other bind counts or LONG sizes need
+        // the cursor to be bound again
+        assert!(!cached.accepts(&[bind(ORA_TYPE_NUM_VARCHAR, 41)], 0));
+        assert!(!cached.accepts(&[bind(ORA_TYPE_NUM_NUMBER, 22)], 0));
+        assert!(!cached.accepts(&[], 0));
+        assert!(!cached.accepts(&[bind(ORA_TYPE_NUM_VARCHAR, 10)], 100));
+    }
+}
@@ -0,0 +1,393 @@
+//! Connect descriptors and tnsnames.ora files.
+//!
+//! A connect descriptor is a tree of parenthesized `KEY=value` pairs:
+//!
+//! ```text
+//! (DESCRIPTION=(ADDRESS=(PROTOCOL=tcps)(HOST=db.example.com)(PORT=1522))
+//!   (CONNECT_DATA=(SERVICE_NAME=orcl)))
+//! ```
+//!
+//! A tnsnames.ora file maps net service names (aliases) to descriptors.
+//! Keys and aliases are case-insensitive; values keep their case.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+
+/// A `(KEY=value)` pair of a connect descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptorNode {
+    /// Key, in upper case.
+    pub key: String,
+    /// Value: text, or nested pairs.
+    pub value: DescriptorValue,
+}
+
+/// Value of a connect descriptor pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DescriptorValue {
+    /// Plain value, with any surrounding quotes removed.
+    Text(String),
+    /// Nested pairs.
+    List(Vec<DescriptorNode>),
+}
+
+impl DescriptorNode {
+    /// Get the text value, if this is not a nested pair.
+    pub fn text(&self) -> Option<&str> {
+        match &self.value {
+            DescriptorValue::Text(text) => Some(text),
+            DescriptorValue::List(_) => None,
+        }
+    }
+
+    /// Get the nested pairs (empty for a text value).
+    pub fn children(&self) -> &[DescriptorNode] {
+        match &self.value {
+            DescriptorValue::Text(_) => &[],
+            DescriptorValue::List(children) => children,
+        }
+    }
+}
+
+/// Parse a connect descriptor into its top-level pairs.
+pub fn parse_descriptor(text: &str) -> Result<Vec<DescriptorNode>> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+    };
+    let nodes = parser.nodes()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected text after descriptor"));
+    }
+    if nodes.is_empty() {
+        return Err(invalid("empty connect descriptor"));
+    }
+    Ok(nodes)
+}
+
+/// Find the first pair with `key` (case-insensitive), searching depth first.
+pub fn find_node<'a>(nodes: &'a [DescriptorNode], key: &str) -> Option<&'a DescriptorNode> {
+    nodes.iter().find_map(|node| {
+        if node.key.eq_ignore_ascii_case(key) {
+            Some(node)
+        } else {
+            find_node(node.children(), key)
+        }
+    })
+}
+
+/// Find every pair with `key` (case-insensitive), in depth-first order.
+///
+/// Pairs nested inside a match are not searched.
+pub fn find_nodes<'a>(nodes: &'a [DescriptorNode], key: &str) -> Vec<&'a DescriptorNode> {
+    let mut found = Vec::new();
+    for node in nodes {
+        if node.key.eq_ignore_ascii_case(key) {
+            found.push(node);
+        } else {
+            found.extend(find_nodes(node.children(), key));
+        }
+    }
+    found
+}
+
+/// Find the text value of the first pair with `key` (case-insensitive).
+pub fn find_value<'a>(nodes: &'a [DescriptorNode], key: &str) -> Option<&'a str> {
+    find_node(nodes, key).and_then(DescriptorNode::text)
+}
+
+/// Net service names read from a tnsnames.ora file.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::protocol::tnsnames::TnsNames;
+///
+/// let names = TnsNames::parse(
+///     "# production\n\
+///      orcl, orcl_alias = (DESCRIPTION=(ADDRESS=(PROTOCOL=tcp)(HOST=db)(PORT=1521))\n\
+///        (CONNECT_DATA=(SERVICE_NAME=orcl)))",
+/// )
+/// .unwrap();
+/// assert!(names.get("ORCL_ALIAS").unwrap().contains("SERVICE_NAME=orcl"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TnsNames {
+    /// Descriptor text by upper-case alias.
+    entries: HashMap<String, String>,
+}
+
+impl TnsNames {
+    /// Parse the contents of a tnsnames.ora file.
+    ///
+    /// Each entry is `alias[, alias...] = descriptor`; `#` starts a comment.
+    /// Entries that are not parenthesized descriptors (such as `IFILE`
+    /// includes or Easy Connect strings) keep their text as is.
+    pub fn parse(text: &str) -> Result<Self> {
+        let stripped: String = text
+            .lines()
+            .map(|line| line.split_once('#').map_or(line, |(code, _)| code))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let chars: Vec<char> = stripped.chars().collect();
+        let mut entries = HashMap::new();
+        let mut pos = 0;
+
+        loop {
+            while chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+                pos += 1;
+            }
+            if pos >= chars.len() {
+                break;
+            }
+
+            let start = pos;
+            while chars.get(pos).is_some_and(|&c| c != '=') {
+                pos += 1;
+            }
+            if pos >= chars.len() {
+                return Err(invalid(format!(
+                    "expected '=' after net service name in tnsnames.ora: {}",
+                    chars[start..].iter().collect::<String>().trim()
+                )));
+            }
+            let names: String = chars[start..pos].iter().collect();
+            pos += 1;
+            while chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+                pos += 1;
+            }
+
+            let value_start = pos;
+            if chars.get(pos) == Some(&'(') {
+                let mut depth = 0usize;
+                while let Some(&c) = chars.get(pos) {
+                    pos += 1;
+                    match c {
+                        '(' => depth += 1,
+                        ')' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if depth != 0 {
+                    return Err(invalid(format!(
+                        "unbalanced parentheses in tnsnames.ora entry {}",
+                        names.trim()
+                    )));
+                }
+            } else {
+                while chars.get(pos).is_some_and(|&c| c != '\n') {
+                    pos += 1;
+                }
+            }
+            let value: String = chars[value_start..pos].iter().collect();
+
+            for name in names.split(',').map(str::trim) {
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return Err(invalid(format!(
+                        "invalid net service name in tnsnames.ora: {}",
+                        names.trim()
+                    )));
+                }
+                entries.insert(name.to_ascii_uppercase(), value.trim().to_string());
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Get the descriptor for a net service name (case-insensitive).
+    pub fn get(&self, alias: &str) -> Option<&str> {
+        self.entries
+            .get(&alias.to_ascii_uppercase())
+            .map(String::as_str)
+    }
+
+    /// Get all net service names, in upper case and sorted.
+    pub fn aliases(&self) -> Vec<&str> {
+        let mut aliases: Vec<&str> = self.entries.keys().map(String::as_str).collect();
+        aliases.sort_unstable();
+        aliases
+    }
+}
+
+/// Recursive descent parser for connect descriptors.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    /// Parse consecutive `(KEY=value)` pairs up to a `)` or the end.
+    fn nodes(&mut self) -> Result<Vec<DescriptorNode>> {
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('(') {
+                return Ok(nodes);
+            }
+            self.pos += 1;
+            nodes.push(self.node()?);
+        }
+    }
+
+    /// Parse the rest of a pair after its opening parenthesis.
+    fn node(&mut self) -> Result<DescriptorNode> {
+        let key = self.take_while(|c| c != '=' && c != '(' && c != ')');
+        let key = key.trim().to_ascii_uppercase();
+        if key.is_empty() || self.peek() != Some('=') {
+            return Err(self.error("expected KEY="));
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+
+        let value = if self.peek() == Some('(') {
+            DescriptorValue::List(self.nodes()?)
+        } else if self.peek() == Some('"') {
+            self.pos += 1;
+            let text = self.take_while(|c| c != '"');
+            if self.peek() != Some('"') {
+                return Err(self.error("unterminated quoted value"));
+            }
+            self.pos += 1;
+            DescriptorValue::Text(text)
+        } else {
+            DescriptorValue::Text(self.take_while(|c| c != ')' && c != '(').trim().to_string())
+        };
+
+        self.skip_whitespace();
+        if self.peek() != Some(')') {
+            return Err(self.error(format!("expected ')' to close {}", key)));
+        }
+        self.pos += 1;
+        Ok(DescriptorNode { key, value })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> Error {
+        invalid(format!(
+            "{} at offset {} of connect descriptor",
+            message, self.pos
+        ))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidConnectString {
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TNSNAMES: &str = include_str!("../../tests/fixtures/wallet/tnsnames.ora");
+
+    #[test]
+    fn test_parse_descriptor() {
+        let nodes = parse_descriptor(
+            "(DESCRIPTION = (ADDRESS=(PROTOCOL=TCPS)(HOST = db.example.com)(PORT=1522))\
+             (CONNECT_DATA=(SERVICE_NAME=orcl))\
+             (SECURITY=(SSL_SERVER_CERT_DN=\"CN=db, O=Example (Test)\")))",
+        )
+        .unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(find_value(&nodes, "host"), Some("db.example.com"));
+        assert_eq!(find_value(&nodes, "PORT"), Some("1522"));
+        assert_eq!(find_value(&nodes, "service_name"), Some("orcl"));
+        assert_eq!(
+            find_value(&nodes, "SSL_SERVER_CERT_DN"),
+            Some("CN=db, O=Example (Test)")
+        );
+        assert_eq!(find_node(&nodes, "ADDRESS").unwrap().children().len(), 3);
+        assert_eq!(find_value(&nodes, "SID"), None);
+
+        let nodes = parse_descriptor(
+            "(DESCRIPTION=(ADDRESS_LIST=(ADDRESS=(HOST=a))(ADDRESS=(HOST=b)))\
+             (ADDRESS=(HOST=c)))",
+        )
+        .unwrap();
+        let hosts: Vec<_> = find_nodes(&nodes, "address")
+            .iter()
+            .filter_map(|a| find_value(a.children(), "HOST"))
+            .collect();
+        assert_eq!(hosts, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_parse_descriptor_errors() {
+        for text in [
+            "",
+            "(DESCRIPTION=(ADDRESS=(HOST=db)",
+            "(DESCRIPTION=(HOST=db)))",
+            "(=db)",
+            "(HOST=\"db)",
+            "HOST=db",
+        ] {
+            assert!(
+                matches!(
+                    parse_descriptor(text),
+                    Err(Error::InvalidConnectString { .. })
+                ),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_tnsnames() {
+        let names = TnsNames::parse(TNSNAMES).unwrap();
+        assert_eq!(
+            names.aliases(),
+            ["TESTDB_ALIAS", "TESTDB_HIGH", "TESTDB_LOW"]
+        );
+        assert_eq!(names.get("testdb_alias"), names.get("TESTDB_LOW"));
+
+        let nodes = parse_descriptor(names.get("TestDB_High").unwrap()).unwrap();
+        assert_eq!(find_value(&nodes, "protocol"), Some("tcps"));
+        assert_eq!(
+            find_value(&nodes, "service_name"),
+            Some("testdb_high.example.com")
+        );
+        assert_eq!(names.get("missing"), None);
+
+        let names = TnsNames::parse("ezconnect = db.example.com:1521/orcl\n").unwrap();
+        assert_eq!(names.get("EZCONNECT"), Some("db.example.com:1521/orcl"));
+    }
+
+    #[test]
+    fn test_parse_tnsnames_errors() {
+        assert!(TnsNames::parse("orcl (DESCRIPTION=)").is_err());
+        assert!(TnsNames::parse("orcl = (DESCRIPTION=(ADDRESS=").is_err());
+        assert!(TnsNames::parse("bad name = (DESCRIPTION=(HOST=db))").is_err());
+        assert!(TnsNames::parse("# only a comment\n")
+            .unwrap()
+            .aliases()
+            .is_empty());
+    }
+}
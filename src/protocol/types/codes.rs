@@ -0,0 +1,291 @@
+//! Oracle type numbers and character set IDs.
+//!
+//! These are the codes Oracle uses on the wire and in its data dictionary
+//! (for example the type numbers returned by `DBMS_SQL.DESCRIBE_COLUMNS`).
+//! Matching on the enums avoids copying magic numbers; the raw constants
+//! remain in `protocol::constants` for the protocol code.
+//!
+//! The enums are `#[non_exhaustive]`, as later server releases add codes.
+
+use std::fmt;
+
+use crate::protocol::constants::{
+    CS_FORM_IMPLICIT, CS_FORM_NCHAR, ORA_TYPE_NUM_BFILE, ORA_TYPE_NUM_BINARY_DOUBLE,
+    ORA_TYPE_NUM_BINARY_FLOAT, ORA_TYPE_NUM_BINARY_INTEGER, ORA_TYPE_NUM_BLOB,
+    ORA_TYPE_NUM_BOOLEAN, ORA_TYPE_NUM_CHAR, ORA_TYPE_NUM_CLOB, ORA_TYPE_NUM_CURSOR,
+    ORA_TYPE_NUM_DATE, ORA_TYPE_NUM_INTERVAL_DS, ORA_TYPE_NUM_INTERVAL_YM, ORA_TYPE_NUM_JSON,
+    ORA_TYPE_NUM_LONG, ORA_TYPE_NUM_LONG_RAW, ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_OBJECT,
+    ORA_TYPE_NUM_RAW, ORA_TYPE_NUM_ROWID, ORA_TYPE_NUM_TIMESTAMP, ORA_TYPE_NUM_TIMESTAMP_LTZ,
+    ORA_TYPE_NUM_TIMESTAMP_TZ, ORA_TYPE_NUM_UROWID, ORA_TYPE_NUM_VARCHAR, ORA_TYPE_NUM_VECTOR,
+    TNS_CHARSET_UTF16, TNS_CHARSET_UTF8,
+};
+
+/// Oracle data type number, as sent in column metadata.
+///
+/// Unlike `OracleType`, this carries no size or precision, and covers
+/// types the client cannot decode yet. National character types share the
+/// number of their database character set counterpart (NVARCHAR2 is
+/// `Varchar`, NCLOB is `Clob`) and are told apart by `CharsetForm`.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::OracleTypeNum;
+///
+/// let type_num = OracleTypeNum::from_code(112);
+/// assert_eq!(type_num, Some(OracleTypeNum::Clob));
+/// assert_eq!(OracleTypeNum::Clob.code(), 112);
+/// assert_eq!(OracleTypeNum::Clob.to_string(), "CLOB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+pub enum OracleTypeNum {
+    /// VARCHAR2 and NVARCHAR2.
+    Varchar = ORA_TYPE_NUM_VARCHAR,
+    /// NUMBER and FLOAT.
+    Number = ORA_TYPE_NUM_NUMBER,
+    /// PL/SQL BINARY_INTEGER and PLS_INTEGER.
+    BinaryInteger = ORA_TYPE_NUM_BINARY_INTEGER,
+    /// LONG.
+    Long = ORA_TYPE_NUM_LONG,
+    /// ROWID.
+    Rowid = ORA_TYPE_NUM_ROWID,
+    /// DATE.
+    Date = ORA_TYPE_NUM_DATE,
+    /// RAW.
+    Raw = ORA_TYPE_NUM_RAW,
+    /// LONG RAW.
+    LongRaw = ORA_TYPE_NUM_LONG_RAW,
+    /// CHAR and NCHAR.
+    Char = ORA_TYPE_NUM_CHAR,
+    /// BINARY_FLOAT.
+    BinaryFloat = ORA_TYPE_NUM_BINARY_FLOAT,
+    /// BINARY_DOUBLE.
+    BinaryDouble = ORA_TYPE_NUM_BINARY_DOUBLE,
+    /// REF CURSOR.
+    Cursor = ORA_TYPE_NUM_CURSOR,
+    /// Object and collection types.
+    Object = ORA_TYPE_NUM_OBJECT,
+    /// CLOB and NCLOB.
+    Clob = ORA_TYPE_NUM_CLOB,
+    /// BLOB.
+    Blob = ORA_TYPE_NUM_BLOB,
+    /// BFILE.
+    Bfile = ORA_TYPE_NUM_BFILE,
+    /// Native JSON (21c).
+    Json = ORA_TYPE_NUM_JSON,
+    /// VECTOR (23ai).
+    Vector = ORA_TYPE_NUM_VECTOR,
+    /// TIMESTAMP.
+    Timestamp = ORA_TYPE_NUM_TIMESTAMP,
+    /// TIMESTAMP WITH TIME ZONE.
+    TimestampTz = ORA_TYPE_NUM_TIMESTAMP_TZ,
+    /// INTERVAL YEAR TO MONTH.
+    IntervalYM = ORA_TYPE_NUM_INTERVAL_YM,
+    /// INTERVAL DAY TO SECOND.
+    IntervalDS = ORA_TYPE_NUM_INTERVAL_DS,
+    /// UROWID.
+    Urowid = ORA_TYPE_NUM_UROWID,
+    /// TIMESTAMP WITH LOCAL TIME ZONE.
+    TimestampLtz = ORA_TYPE_NUM_TIMESTAMP_LTZ,
+    /// BOOLEAN (23ai).
+    Boolean = ORA_TYPE_NUM_BOOLEAN,
+}
+
+impl OracleTypeNum {
+    /// Every known type number.
+    const ALL: [OracleTypeNum; 25] = [
+        Self::Varchar,
+        Self::Number,
+        Self::BinaryInteger,
+        Self::Long,
+        Self::Rowid,
+        Self::Date,
+        Self::Raw,
+        Self::LongRaw,
+        Self::Char,
+        Self::BinaryFloat,
+        Self::BinaryDouble,
+        Self::Cursor,
+        Self::Object,
+        Self::Clob,
+        Self::Blob,
+        Self::Bfile,
+        Self::Json,
+        Self::Vector,
+        Self::Timestamp,
+        Self::TimestampTz,
+        Self::IntervalYM,
+        Self::IntervalDS,
+        Self::Urowid,
+        Self::TimestampLtz,
+        Self::Boolean,
+    ];
+
+    /// Look up a type number, returning `None` for unknown codes.
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    /// Get the numeric code.
+    pub const fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// Get the SQL name of the type.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Varchar => "VARCHAR2",
+            Self::Number => "NUMBER",
+            Self::BinaryInteger => "BINARY_INTEGER",
+            Self::Long => "LONG",
+            Self::Rowid => "ROWID",
+            Self::Date => "DATE",
+            Self::Raw => "RAW",
+            Self::LongRaw => "LONG RAW",
+            Self::Char => "CHAR",
+            Self::BinaryFloat => "BINARY_FLOAT",
+            Self::BinaryDouble => "BINARY_DOUBLE",
+            Self::Cursor => "REF CURSOR",
+            Self::Object => "OBJECT",
+            Self::Clob => "CLOB",
+            Self::Blob => "BLOB",
+            Self::Bfile => "BFILE",
+            Self::Json => "JSON",
+            Self::Vector => "VECTOR",
+            Self::Timestamp => "TIMESTAMP",
+            Self::TimestampTz => "TIMESTAMP WITH TIME ZONE",
+            Self::IntervalYM => "INTERVAL YEAR TO MONTH",
+            Self::IntervalDS => "INTERVAL DAY TO SECOND",
+            Self::Urowid => "UROWID",
+            Self::TimestampLtz => "TIMESTAMP WITH LOCAL TIME ZONE",
+            Self::Boolean => "BOOLEAN",
+        }
+    }
+}
+
+impl From<OracleTypeNum> for u16 {
+    fn from(type_num: OracleTypeNum) -> Self {
+        type_num.code()
+    }
+}
+
+impl fmt::Display for OracleTypeNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Oracle character set ID, as used for the database and national
+/// character sets.
+///
+/// Only the character sets this client sends or receives text in are
+/// listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+pub enum CharsetId {
+    /// AL32UTF8, the database character set used for all text.
+    Al32Utf8 = TNS_CHARSET_UTF8,
+    /// AL16UTF16, the national character set.
+    Al16Utf16 = TNS_CHARSET_UTF16,
+}
+
+impl CharsetId {
+    /// Look up a character set ID, returning `None` for unknown IDs.
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            TNS_CHARSET_UTF8 => Some(Self::Al32Utf8),
+            TNS_CHARSET_UTF16 => Some(Self::Al16Utf16),
+            _ => None,
+        }
+    }
+
+    /// Get the numeric ID.
+    pub const fn code(self) -> u16 {
+        self as u16
+    }
+
+    /// Get the Oracle name of the character set.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Al32Utf8 => "AL32UTF8",
+            Self::Al16Utf16 => "AL16UTF16",
+        }
+    }
+}
+
+impl From<CharsetId> for u16 {
+    fn from(charset: CharsetId) -> Self {
+        charset.code()
+    }
+}
+
+impl fmt::Display for CharsetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Character set form of a text value: whether it uses the database or the
+/// national character set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum CharsetForm {
+    /// Database character set (VARCHAR2, CHAR, CLOB).
+    Implicit = CS_FORM_IMPLICIT,
+    /// National character set (NVARCHAR2, NCHAR, NCLOB).
+    Nchar = CS_FORM_NCHAR,
+}
+
+impl CharsetForm {
+    /// Look up a character set form, returning `None` for unknown codes.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            CS_FORM_IMPLICIT => Some(Self::Implicit),
+            CS_FORM_NCHAR => Some(Self::Nchar),
+            _ => None,
+        }
+    }
+
+    /// Get the numeric code.
+    pub const fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_num_codes() {
+        for type_num in OracleTypeNum::ALL {
+            assert_eq!(OracleTypeNum::from_code(type_num.code()), Some(type_num));
+        }
+        assert_eq!(
+            OracleTypeNum::from_code(ORA_TYPE_NUM_VARCHAR),
+            Some(OracleTypeNum::Varchar)
+        );
+        assert_eq!(u16::from(OracleTypeNum::Boolean), 252);
+        assert_eq!(OracleTypeNum::from_code(0), None);
+        assert_eq!(
+            OracleTypeNum::IntervalDS.to_string(),
+            "INTERVAL DAY TO SECOND"
+        );
+    }
+
+    #[test]
+    fn test_charset_codes() {
+        assert_eq!(CharsetId::from_code(873), Some(CharsetId::Al32Utf8));
+        assert_eq!(CharsetId::Al16Utf16.code(), 2000);
+        assert_eq!(CharsetId::from_code(1), None);
+        assert_eq!(CharsetId::Al32Utf8.to_string(), "AL32UTF8");
+
+        assert_eq!(CharsetForm::from_code(2), Some(CharsetForm::Nchar));
+        assert_eq!(CharsetForm::Implicit.code(), CS_FORM_IMPLICIT);
+        assert_eq!(CharsetForm::from_code(0), None);
+    }
+}
@@ -3,8 +3,13 @@
 //! These types provide a clean interface for accessing column information
 //! from query results, derived from the internal ColumnMetadata.
 
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
 use crate::error::Result;
 
+use super::codes::OracleTypeNum;
 use super::metadata::ColumnMetadata;
 use super::oracle_type::OracleType;
 
@@ -19,6 +24,12 @@ pub struct Column {
     pub data_type: OracleType,
     /// Raw Oracle type number.
     pub oracle_type_num: u8,
+    /// Display size in characters (see `ColumnMetadata::display_size`).
+    pub display_size: Option<u32>,
+    /// Whether the column has an `IS JSON` check constraint.
+    pub is_json: bool,
+    /// Whether the column is stored as OSON.
+    pub is_oson: bool,
 }
 
 impl Column {
@@ -29,28 +40,45 @@ impl Column {
         Ok(Self {
             name: meta.name.clone(),
             nullable: meta.nullable,
-            data_type: OracleType::from_raw(
-                meta.oracle_type,
-                meta.precision,
-                meta.scale,
-                meta.max_size,
-            )?,
+            data_type: meta.data_type()?,
             oracle_type_num: meta.oracle_type,
+            display_size: meta.display_size(),
+            is_json: meta.is_json,
+            is_oson: meta.is_oson,
         })
     }
+
+    /// Get the Oracle type number, if it is a known one.
+    pub fn type_num(&self) -> Option<OracleTypeNum> {
+        OracleTypeNum::from_code(self.oracle_type_num as u16)
+    }
 }
 
 /// Shared column information for all rows in a result set.
-#[derive(Debug, Clone)]
+///
+/// Name lookups go through an index built when the column info is created,
+/// so they take constant time however wide the result set is. The columns
+/// cannot be changed afterwards, which would leave the index stale, and
+/// clones share the index.
+#[derive(Clone)]
 pub struct ColumnInfo {
     /// Column definitions.
-    pub columns: Vec<Column>,
+    columns: Vec<Column>,
+    /// Position of the first column with each upper-cased name.
+    index: Arc<HashMap<String, usize>>,
 }
 
 impl ColumnInfo {
     /// Create new column info from columns.
     pub fn new(columns: Vec<Column>) -> Self {
-        Self { columns }
+        let mut index = HashMap::with_capacity(columns.len());
+        for (position, column) in columns.iter().enumerate() {
+            index.entry(column.name.to_uppercase()).or_insert(position);
+        }
+        Self {
+            columns,
+            index: Arc::new(index),
+        }
     }
 
     /// Create column info from internal metadata.
@@ -58,7 +86,12 @@ impl ColumnInfo {
     /// Returns error if any column has an unsupported Oracle type.
     pub fn from_metadata(metadata: &[ColumnMetadata]) -> Result<Self> {
         let columns: Result<Vec<Column>> = metadata.iter().map(Column::from_metadata).collect();
-        Ok(Self { columns: columns? })
+        Ok(Self::new(columns?))
+    }
+
+    /// Get the column definitions.
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
     }
 
     /// Get column names.
@@ -82,17 +115,29 @@ impl ColumnInfo {
     }
 
     /// Find column index by name (case-insensitive).
+    ///
+    /// With duplicate names, the first such column is found.
     pub fn find_by_name(&self, name: &str) -> Option<usize> {
-        let name_upper = name.to_uppercase();
-        self.columns
-            .iter()
-            .position(|c| c.name.to_uppercase() == name_upper)
+        // Upper-case names, the usual case, are found without allocating
+        self.index
+            .get(name)
+            .or_else(|| self.index.get(&name.to_uppercase()))
+            .copied()
+    }
+}
+
+impl fmt::Debug for ColumnInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColumnInfo")
+            .field("columns", &self.columns)
+            .finish_non_exhaustive()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::constants::CS_FORM_NCHAR;
 
     fn make_test_metadata() -> Vec<ColumnMetadata> {
         vec![
@@ -104,6 +149,9 @@ mod tests {
                 max_size: 22,
                 buffer_size: 22,
                 nullable: false,
+                charset_form: 0,
+                is_json: false,
+                is_oson: false,
             },
             ColumnMetadata {
                 name: "NAME".to_string(),
@@ -113,6 +161,9 @@ mod tests {
                 max_size: 100,
                 buffer_size: 100,
                 nullable: true,
+                charset_form: 1,
+                is_json: false,
+                is_oson: false,
             },
         ]
     }
@@ -125,6 +176,9 @@ mod tests {
         assert_eq!(col.name, "ID");
         assert!(!col.nullable);
         assert_eq!(col.oracle_type_num, 2);
+        assert_eq!(col.type_num(), Some(OracleTypeNum::Number));
+        assert_eq!(col.display_size, Some(11));
+        assert!(!col.is_json);
 
         if let OracleType::Number { precision, scale } = col.data_type {
             assert_eq!(precision, 10);
@@ -134,6 +188,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_column_from_nchar_metadata() {
+        let mut meta = make_test_metadata().remove(1);
+        meta.charset_form = CS_FORM_NCHAR;
+        let col = Column::from_metadata(&meta).unwrap();
+        assert_eq!(col.data_type, OracleType::NVarchar2 { max_size: 100 });
+        assert_eq!(col.type_num(), Some(OracleTypeNum::Varchar));
+    }
+
     #[test]
     fn test_column_info_from_metadata() {
         let metadata = make_test_metadata();
@@ -144,4 +207,27 @@ mod tests {
         assert_eq!(info.find_by_name("name"), Some(1));
         assert_eq!(info.find_by_name("UNKNOWN"), None);
     }
+
+    #[test]
+    fn test_find_by_name_wide_and_duplicate() {
+        let mut metadata = make_test_metadata();
+        for i in 0..1000 {
+            let mut column = metadata[1].clone();
+            column.name = format!("Col_{}", i);
+            metadata.push(column);
+        }
+        metadata.push(metadata[0].clone());
+        let info = ColumnInfo::from_metadata(&metadata).unwrap();
+
+        assert_eq!(info.find_by_name("COL_999"), Some(1001));
+        assert_eq!(info.find_by_name("col_0"), Some(2));
+        assert_eq!(info.find_by_name("Col_500"), Some(502));
+        // The first of two columns with the same name wins
+        assert_eq!(info.find_by_name("id"), Some(0));
+
+        // Clones share the index
+        let clone = info.clone();
+        assert!(Arc::ptr_eq(&clone.index, &info.index));
+        assert_eq!(clone.columns()[1001].name, "Col_999");
+    }
 }
@@ -0,0 +1,390 @@
+//! Conversions between Rust types and `OracleValue`.
+//!
+//! `FromSql` extracts a typed Rust value from a column value, `ToSql`
+//! produces the `OracleValue` representation of a Rust value.
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::error::{Error, Result};
+
+use super::lob::Lob;
+use super::value::OracleValue;
+use super::vector::Vector;
+
+/// Conversion from an Oracle column value.
+pub trait FromSql: Sized {
+    /// Convert from an Oracle value.
+    ///
+    /// Returns `Error::TypeConversion` if the value has an incompatible type.
+    fn from_sql(value: &OracleValue) -> Result<Self>;
+}
+
+/// Conversion into an Oracle value.
+pub trait ToSql {
+    /// Convert to an Oracle value.
+    fn to_sql(&self) -> OracleValue;
+}
+
+/// Build a type conversion error for `value` converted to `target`.
+pub(crate) fn conversion_error(value: &OracleValue, target: &str) -> Error {
+    Error::type_conversion(format!("cannot convert {:?} to {}", value, target))
+}
+
+impl FromSql for OracleValue {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        Ok(value.clone())
+    }
+}
+
+impl FromSql for String {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::Null => Err(conversion_error(value, "String")),
+            other => Ok(other.to_string()),
+        }
+    }
+}
+
+impl FromSql for i64 {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value.to_i64().ok_or_else(|| conversion_error(value, "i64"))
+    }
+}
+
+impl FromSql for f64 {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value.to_f64().ok_or_else(|| conversion_error(value, "f64"))
+    }
+}
+
+/// Exact conversion from `Decimal` and from `Number` strings with at most
+/// 28 decimal places.
+#[cfg(feature = "rust_decimal")]
+impl FromSql for rust_decimal::Decimal {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::Decimal(d) => Ok(*d),
+            OracleValue::Integer(i) => Ok((*i).into()),
+            OracleValue::Number(s) => rust_decimal::Decimal::from_str_exact(s)
+                .map_err(|_| conversion_error(value, "Decimal")),
+            other => Err(conversion_error(other, "Decimal")),
+        }
+    }
+}
+
+/// Exact conversion from finite numbers.
+#[cfg(feature = "bigdecimal")]
+impl FromSql for bigdecimal::BigDecimal {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        let text = match value {
+            OracleValue::Number(s) => s.clone(),
+            OracleValue::Integer(i) => return Ok((*i).into()),
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(d) => d.to_string(),
+            other => return Err(conversion_error(other, "BigDecimal")),
+        };
+        text.parse()
+            .map_err(|_| conversion_error(value, "BigDecimal"))
+    }
+}
+
+impl FromSql for bool {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_bool()
+            .ok_or_else(|| conversion_error(value, "bool"))
+    }
+}
+
+impl FromSql for NaiveDateTime {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_date()
+            .ok_or_else(|| conversion_error(value, "NaiveDateTime"))
+    }
+}
+
+/// The date part of a DATE, dropping the time of day.
+impl FromSql for NaiveDate {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_date()
+            .map(|dt| dt.date())
+            .ok_or_else(|| conversion_error(value, "NaiveDate"))
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_bytes()
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| conversion_error(value, "Vec<u8>"))
+    }
+}
+
+impl FromSql for Vector {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_vector()
+            .cloned()
+            .ok_or_else(|| conversion_error(value, "Vector"))
+    }
+}
+
+impl FromSql for Lob {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .as_lob()
+            .cloned()
+            .ok_or_else(|| conversion_error(value, "Lob"))
+    }
+}
+
+impl FromSql for Vec<f32> {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .to_f32_vec()
+            .ok_or_else(|| conversion_error(value, "Vec<f32>"))
+    }
+}
+
+impl FromSql for Vec<f64> {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        value
+            .to_f64_vec()
+            .ok_or_else(|| conversion_error(value, "Vec<f64>"))
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::Null => Ok(None),
+            other => T::from_sql(other).map(Some),
+        }
+    }
+}
+
+impl ToSql for OracleValue {
+    fn to_sql(&self) -> OracleValue {
+        self.clone()
+    }
+}
+
+impl ToSql for str {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::String(self.to_string())
+    }
+}
+
+impl ToSql for String {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::String(self.clone())
+    }
+}
+
+impl ToSql for i64 {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Integer(*self)
+    }
+}
+
+impl ToSql for f64 {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Number(self.to_string())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl ToSql for rust_decimal::Decimal {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Decimal(*self)
+    }
+}
+
+/// Bound as NUMBER, which holds at most 40 significant digits.
+#[cfg(feature = "bigdecimal")]
+impl ToSql for bigdecimal::BigDecimal {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Number(self.to_plain_string())
+    }
+}
+
+impl ToSql for NaiveDateTime {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Date(*self)
+    }
+}
+
+/// Bound as a DATE at midnight.
+impl ToSql for NaiveDate {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Date(self.and_time(chrono::NaiveTime::MIN))
+    }
+}
+
+impl ToSql for [u8] {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Raw(self.to_vec())
+    }
+}
+
+impl ToSql for Vec<u8> {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Raw(self.clone())
+    }
+}
+
+impl ToSql for bool {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Boolean(*self)
+    }
+}
+
+impl ToSql for Vector {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Vector(self.clone())
+    }
+}
+
+impl ToSql for Lob {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Lob(self.clone())
+    }
+}
+
+impl<T: ToSql> ToSql for Option<T> {
+    fn to_sql(&self) -> OracleValue {
+        match self {
+            Some(v) => v.to_sql(),
+            None => OracleValue::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_sql_primitives() {
+        let num = OracleValue::Number("42".to_string());
+        assert_eq!(i64::from_sql(&num).unwrap(), 42);
+        assert_eq!(f64::from_sql(&num).unwrap(), 42.0);
+        assert_eq!(String::from_sql(&num).unwrap(), "42");
+
+        let s = OracleValue::String("abc".to_string());
+        assert!(i64::from_sql(&s).is_err());
+    }
+
+    #[test]
+    fn test_from_sql_option() {
+        assert_eq!(Option::<i64>::from_sql(&OracleValue::Null).unwrap(), None);
+        assert_eq!(
+            Option::<i64>::from_sql(&OracleValue::Number("7".to_string())).unwrap(),
+            Some(7)
+        );
+        assert!(i64::from_sql(&OracleValue::Null).is_err());
+    }
+
+    #[test]
+    fn test_to_sql_roundtrip() {
+        assert_eq!(i64::from_sql(&123i64.to_sql()).unwrap(), 123);
+        assert_eq!(
+            String::from_sql(&"hello".to_sql()).unwrap(),
+            "hello".to_string()
+        );
+        assert_eq!(None::<i64>.to_sql(), OracleValue::Null);
+    }
+
+    #[test]
+    fn test_date_roundtrip() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(NaiveDate::from_sql(&date.to_sql()).unwrap(), date);
+        let noon = date.and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(NaiveDate::from_sql(&noon.to_sql()).unwrap(), date);
+        assert!(NaiveDate::from_sql(&OracleValue::String("2024".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        assert_eq!(Vec::<u8>::from_sql(&data.to_sql()).unwrap(), data);
+        assert!(Vec::<u8>::from_sql(&OracleValue::String("AB".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_bool_roundtrip() {
+        assert!(bool::from_sql(&true.to_sql()).unwrap());
+        assert!(!bool::from_sql(&false.to_sql()).unwrap());
+        assert_eq!(Option::<bool>::from_sql(&OracleValue::Null).unwrap(), None);
+        assert!(bool::from_sql(&OracleValue::String("TRUE".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_lob_roundtrip() {
+        use super::super::oracle_type::OracleType;
+
+        let lob = Lob::new(OracleType::Clob, vec![0; 40], 5, 8132);
+        assert_eq!(Lob::from_sql(&lob.to_sql()).unwrap(), lob);
+        assert!(Lob::from_sql(&OracleValue::String("x".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_vector_conversions() {
+        use super::super::vector::VectorData;
+
+        let v = Vector::Dense(VectorData::Float32(vec![0.5, 1.5]));
+        let value = v.to_sql();
+        assert_eq!(Vector::from_sql(&value).unwrap(), v);
+        assert_eq!(Vec::<f32>::from_sql(&value).unwrap(), vec![0.5, 1.5]);
+        assert_eq!(Vec::<f64>::from_sql(&value).unwrap(), vec![0.5, 1.5]);
+
+        let binary = Vector::Dense(VectorData::Binary(vec![0xFF])).to_sql();
+        assert!(Vec::<f32>::from_sql(&binary).is_err());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn test_decimal_from_sql() {
+        use rust_decimal::Decimal;
+
+        let exact = Decimal::new(12345, 2);
+        assert_eq!(
+            Decimal::from_sql(&OracleValue::Decimal(exact)).unwrap(),
+            exact
+        );
+        assert_eq!(
+            Decimal::from_sql(&OracleValue::Number("123.45".to_string())).unwrap(),
+            exact
+        );
+        assert!(Decimal::from_sql(&OracleValue::Number("inf".to_string())).is_err());
+        assert!(Decimal::from_sql(&OracleValue::String("1".to_string())).is_err());
+        assert_eq!(OracleValue::Decimal(exact).to_f64(), Some(123.45));
+        assert_eq!(OracleValue::Decimal(exact).to_i64(), None);
+        assert_eq!(
+            OracleValue::Decimal(Decimal::new(-42, 0)).to_i64(),
+            Some(-42)
+        );
+        assert_eq!(OracleValue::Decimal(exact).to_string(), "123.45");
+        assert_eq!(exact.to_sql(), OracleValue::Decimal(exact));
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn test_bigdecimal_from_sql() {
+        use bigdecimal::BigDecimal;
+
+        let digits = "1234567890123456789012345678901234567.8";
+        assert_eq!(
+            BigDecimal::from_sql(&OracleValue::Number(digits.to_string())).unwrap(),
+            digits.parse::<BigDecimal>().unwrap()
+        );
+        assert!(BigDecimal::from_sql(&OracleValue::Number("-inf".to_string())).is_err());
+        assert!(BigDecimal::from_sql(&OracleValue::Null).is_err());
+
+        let value: BigDecimal = "-1e-20".parse().unwrap();
+        assert_eq!(BigDecimal::from_sql(&value.to_sql()).unwrap(), value);
+    }
+}
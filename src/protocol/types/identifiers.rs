@@ -0,0 +1,328 @@
+//! Typed identifiers: system change numbers, ROWIDs and GUIDs.
+//!
+//! These newtypes keep database identifiers from being passed around as bare
+//! strings or integers, and validate their format on construction.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+use super::convert::{conversion_error, FromSql, ToSql};
+use super::value::OracleValue;
+
+/// Base64 alphabet used by Oracle for ROWID encoding.
+const ROWID_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Length of an extended (physical) ROWID string.
+const EXTENDED_ROWID_LEN: usize = 18;
+
+/// System Change Number (SCN).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scn(pub u64);
+
+impl Scn {
+    /// Get the raw SCN value.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Scn {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for Scn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Scn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse::<u64>()
+            .map(Scn)
+            .map_err(|_| Error::type_conversion(format!("invalid SCN: {:?}", s)))
+    }
+}
+
+impl FromSql for Scn {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::Number(s) | OracleValue::String(s) => s.parse(),
+            OracleValue::Integer(i) => u64::try_from(*i)
+                .map(Scn)
+                .map_err(|_| Error::type_conversion(format!("invalid SCN: {}", i))),
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(d) => d.to_string().parse(),
+            other => Err(conversion_error(other, "Scn")),
+        }
+    }
+}
+
+impl ToSql for Scn {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Number(self.0.to_string())
+    }
+}
+
+/// Oracle ROWID (physical extended ROWID or universal ROWID).
+///
+/// Extended ROWIDs are 18 characters of Oracle's base64 alphabet, encoding
+/// the data object number, relative file number, block number and row slot.
+/// Universal ROWIDs (UROWID) start with `*` and are kept as opaque strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RowId(String);
+
+impl RowId {
+    /// Build an extended ROWID from its components.
+    pub fn from_parts(object_id: u32, file_num: u16, block_num: u32, row_num: u16) -> Self {
+        let mut s = String::with_capacity(EXTENDED_ROWID_LEN);
+        encode_base64_digits(&mut s, object_id as u64, 6);
+        encode_base64_digits(&mut s, file_num as u64, 3);
+        encode_base64_digits(&mut s, block_num as u64, 6);
+        encode_base64_digits(&mut s, row_num as u64, 3);
+        Self(s)
+    }
+
+    /// Build a universal ROWID from the bytes of a logical (or foreign) UROWID.
+    ///
+    /// The bytes are base64 encoded without padding and prefixed with `*`,
+    /// matching the text form Oracle returns for `ROWIDTOCHAR`.
+    pub fn from_universal_bytes(bytes: &[u8]) -> Self {
+        let mut s = String::with_capacity(1 + bytes.len().div_ceil(3) * 4);
+        s.push('*');
+        for chunk in bytes.chunks(3) {
+            let b0 = chunk[0] as u64;
+            let b1 = chunk.get(1).copied().unwrap_or(0) as u64;
+            let b2 = chunk.get(2).copied().unwrap_or(0) as u64;
+            let group = (b0 << 16) | (b1 << 8) | b2;
+            // 1 byte -> 2 digits, 2 bytes -> 3 digits, 3 bytes -> 4 digits
+            let num_digits = chunk.len() as u32 + 1;
+            encode_base64_digits(&mut s, group >> (6 * (4 - num_digits)), num_digits);
+        }
+        Self(s)
+    }
+
+    /// Get the ROWID string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check if this is a universal ROWID (UROWID).
+    pub fn is_universal(&self) -> bool {
+        self.0.starts_with('*')
+    }
+
+    /// Decode an extended ROWID into (object_id, file_num, block_num, row_num).
+    ///
+    /// Returns `None` for universal ROWIDs, and for parts too large for
+    /// their field (the base64 digits hold a few more bits than the fields).
+    pub fn parts(&self) -> Option<(u32, u16, u32, u16)> {
+        if self.0.len() != EXTENDED_ROWID_LEN {
+            return None;
+        }
+        let bytes = self.0.as_bytes();
+        Some((
+            u32::try_from(decode_base64_digits(&bytes[0..6])?).ok()?,
+            u16::try_from(decode_base64_digits(&bytes[6..9])?).ok()?,
+            u32::try_from(decode_base64_digits(&bytes[9..15])?).ok()?,
+            u16::try_from(decode_base64_digits(&bytes[15..18])?).ok()?,
+        ))
+    }
+}
+
+impl fmt::Display for RowId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for RowId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let valid = if let Some(rest) = s.strip_prefix('*') {
+            !rest.is_empty() && rest.bytes().all(|b| ROWID_ALPHABET.contains(&b))
+        } else {
+            s.len() == EXTENDED_ROWID_LEN && s.bytes().all(|b| ROWID_ALPHABET.contains(&b))
+        };
+        if !valid {
+            return Err(Error::type_conversion(format!("invalid ROWID: {:?}", s)));
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl FromSql for RowId {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::String(s) => s.parse(),
+            other => Err(conversion_error(other, "RowId")),
+        }
+    }
+}
+
+impl ToSql for RowId {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::String(self.0.clone())
+    }
+}
+
+/// Globally unique identifier, as returned by `SYS_GUID()` (RAW(16)).
+///
+/// Converts from either the raw 16 bytes or their `RAWTOHEX` form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guid(pub [u8; 16]);
+
+impl Guid {
+    /// Get the raw GUID bytes.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Guid {
+    /// Formats as 32 uppercase hex digits, matching `RAWTOHEX(SYS_GUID())`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Guid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::type_conversion(format!("invalid GUID: {:?}", s));
+        // from_str_radix alone would accept a sign, as in "+0"
+        if s.len() != 32 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl FromSql for Guid {
+    fn from_sql(value: &OracleValue) -> Result<Self> {
+        match value {
+            OracleValue::String(s) => s.parse(),
+            OracleValue::Raw(bytes) => <[u8; 16]>::try_from(bytes.as_slice())
+                .map(Guid)
+                .map_err(|_| conversion_error(value, "Guid")),
+            other => Err(conversion_error(other, "Guid")),
+        }
+    }
+}
+
+impl ToSql for Guid {
+    fn to_sql(&self) -> OracleValue {
+        OracleValue::Raw(self.0.to_vec())
+    }
+}
+
+/// Append `num_digits` base64 digits of `value` (most significant first).
+fn encode_base64_digits(out: &mut String, value: u64, num_digits: u32) {
+    for i in (0..num_digits).rev() {
+        let digit = (value >> (6 * i)) & 0x3F;
+        out.push(ROWID_ALPHABET[digit as usize] as char);
+    }
+}
+
+/// Decode base64 digits (most significant first).
+fn decode_base64_digits(digits: &[u8]) -> Option<u64> {
+    digits.iter().try_fold(0u64, |acc, &b| {
+        let digit = ROWID_ALPHABET.iter().position(|&c| c == b)?;
+        Some((acc << 6) | digit as u64)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scn_parse_and_format() {
+        let scn: Scn = "12345678901".parse().unwrap();
+        assert_eq!(scn, Scn(12345678901));
+        assert_eq!(scn.to_string(), "12345678901");
+        assert!("abc".parse::<Scn>().is_err());
+
+        let value = OracleValue::Number("987".to_string());
+        assert_eq!(Scn::from_sql(&value).unwrap(), Scn(987));
+        assert_eq!(Scn(987).to_sql(), value);
+    }
+
+    #[test]
+    fn test_rowid_roundtrip_parts() {
+        let rowid = RowId::from_parts(73_000, 12, 131, 5);
+        assert_eq!(rowid.as_str().len(), 18);
+        assert_eq!(rowid.parts(), Some((73_000, 12, 131, 5)));
+
+        let parsed: RowId = rowid.as_str().parse().unwrap();
+        assert_eq!(parsed, rowid);
+    }
+
+    #[test]
+    fn test_rowid_known_value() {
+        // AAAR3sAAEAAAACXAAA: object 73196, file 4, block 151, row 0
+        let rowid: RowId = "AAAR3sAAEAAAACXAAA".parse().unwrap();
+        assert_eq!(rowid.parts(), Some((73196, 4, 151, 0)));
+        assert!(!rowid.is_universal());
+    }
+
+    #[test]
+    fn test_rowid_universal_bytes() {
+        let rowid = RowId::from_universal_bytes(&[0x04, 0x03, 0x00, 0x12, 0x3C]);
+        assert_eq!(rowid.as_str(), "*BAMAEjw");
+        assert!(rowid.is_universal());
+        assert_eq!(rowid.as_str().parse::<RowId>().unwrap(), rowid);
+    }
+
+    #[test]
+    fn test_rowid_invalid() {
+        assert!("short".parse::<RowId>().is_err());
+        assert!("AAAR3sAAEAAAACXAA!".parse::<RowId>().is_err());
+        assert!("*".parse::<RowId>().is_err());
+
+        let urowid: RowId = "*BAMAEjwCwQL+".parse().unwrap();
+        assert!(urowid.is_universal());
+        assert_eq!(urowid.parts(), None);
+
+        // Digits beyond the width of the object and file numbers
+        let rowid: RowId = "/AAAAAAAEAAAACXAAA".parse().unwrap();
+        assert_eq!(rowid.parts(), None);
+        let rowid: RowId = "AAAR3s/AAAAAACXAAA".parse().unwrap();
+        assert_eq!(rowid.parts(), None);
+    }
+
+    #[test]
+    fn test_guid_parse_and_format() {
+        let guid: Guid = "0123456789ABCDEF0123456789abcdef".parse().unwrap();
+        assert_eq!(guid.0[0], 0x01);
+        assert_eq!(guid.0[15], 0xEF);
+        assert_eq!(guid.to_string(), "0123456789ABCDEF0123456789ABCDEF");
+        assert!("0123".parse::<Guid>().is_err());
+        assert!("zz23456789ABCDEF0123456789ABCDEF".parse::<Guid>().is_err());
+        assert!("+0+0+0+0+0+0+0+0+0+0+0+0+0+0+0+0".parse::<Guid>().is_err());
+    }
+
+    #[test]
+    fn test_guid_from_raw() {
+        let guid = Guid([0xAB; 16]);
+        assert_eq!(Guid::from_sql(&guid.to_sql()).unwrap(), guid);
+        assert!(Guid::from_sql(&OracleValue::Raw(vec![0xAB; 4])).is_err());
+    }
+}
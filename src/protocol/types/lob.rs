@@ -0,0 +1,213 @@
+//! LOB locators for CLOB, BLOB and BFILE values.
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::protocol::constants::{
+    TNS_LOB_LOC_FLAGS_TEMP, TNS_LOB_LOC_FLAGS_VAR_LENGTH_CHARSET, TNS_LOB_LOC_OFFSET_FLAG_3,
+    TNS_LOB_LOC_OFFSET_FLAG_4, TNS_LOB_TEMP_LOCATOR_SIZE,
+};
+
+use super::oracle_type::OracleType;
+
+/// A LOB locator, as fetched from a CLOB/BLOB/BFILE column or created as a
+/// temporary LOB.
+///
+/// The locator references the LOB on the server; its content is read and
+/// written through the LOB methods on `Connection`. The server may hand back
+/// an updated locator on every operation, so those methods take the LOB
+/// mutably.
+///
+/// Sizes and offsets are in characters for CLOBs and bytes for BLOBs and
+/// BFILEs. The server does not send a size or chunk size for BFILEs.
+/// Offsets are 1-based, as in `DBMS_LOB`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lob {
+    /// LOB type (CLOB, NCLOB, BLOB or BFILE).
+    oracle_type: OracleType,
+    /// Opaque locator bytes.
+    locator: Vec<u8>,
+    /// Size as last reported by the server or tracked across writes.
+    size: u64,
+    /// Server chunk size (0 if unknown).
+    chunk_size: u32,
+}
+
+impl Lob {
+    /// Create a LOB from a locator received from the server.
+    pub(crate) fn new(
+        oracle_type: OracleType,
+        locator: Vec<u8>,
+        size: u64,
+        chunk_size: u32,
+    ) -> Self {
+        Self {
+            oracle_type,
+            locator,
+            size,
+            chunk_size,
+        }
+    }
+
+    /// Create an empty locator for a temporary LOB, filled in by the server.
+    pub(crate) fn new_temporary(oracle_type: OracleType) -> Self {
+        Self::new(oracle_type, vec![0; TNS_LOB_TEMP_LOCATOR_SIZE], 0, 0)
+    }
+
+    /// Get the LOB type.
+    pub fn oracle_type(&self) -> &OracleType {
+        &self.oracle_type
+    }
+
+    /// Check if this is a BLOB.
+    pub fn is_blob(&self) -> bool {
+        self.oracle_type == OracleType::Blob
+    }
+
+    /// Check if this is a BFILE.
+    pub fn is_bfile(&self) -> bool {
+        self.oracle_type == OracleType::Bfile
+    }
+
+    /// Get the size in characters (CLOB) or bytes (BLOB).
+    ///
+    /// This is the size reported when the locator was fetched, updated by
+    /// writes and trims made through this locator. Use
+    /// `Connection::lob_length` for the current server-side length.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get the server chunk size (0 if unknown).
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    /// Get the raw locator bytes.
+    pub fn locator(&self) -> &[u8] {
+        &self.locator
+    }
+
+    /// Check if this is a temporary LOB.
+    pub fn is_temporary(&self) -> bool {
+        self.locator
+            .get(TNS_LOB_LOC_OFFSET_FLAG_4)
+            .is_some_and(|flags| flags & TNS_LOB_LOC_FLAGS_TEMP != 0)
+    }
+
+    /// Replace the locator with the one returned by the server.
+    pub(crate) fn set_locator(&mut self, locator: &[u8]) {
+        self.locator.clear();
+        self.locator.extend_from_slice(locator);
+    }
+
+    pub(crate) fn set_size(&mut self, size: u64) {
+        self.size = size;
+    }
+
+    /// Whether character data is stored as UTF-16 (NCLOB, or a CLOB in a
+    /// variable-width database character set).
+    pub(crate) fn uses_utf16(&self) -> bool {
+        self.oracle_type == OracleType::Nclob
+            || self
+                .locator
+                .get(TNS_LOB_LOC_OFFSET_FLAG_3)
+                .is_some_and(|flags| flags & TNS_LOB_LOC_FLAGS_VAR_LENGTH_CHARSET != 0)
+    }
+
+    /// Encode text for a CLOB write, returning the bytes and the length in
+    /// LOB characters.
+    pub(crate) fn encode_text(&self, text: &str) -> (Vec<u8>, u64) {
+        if self.uses_utf16() {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            let bytes = units.iter().flat_map(|u| u.to_be_bytes()).collect();
+            (bytes, units.len() as u64)
+        } else {
+            (text.as_bytes().to_vec(), text.chars().count() as u64)
+        }
+    }
+
+    /// Decode CLOB data read from the server.
+    pub(crate) fn decode_text(&self, data: &[u8]) -> Result<String> {
+        if self.uses_utf16() {
+            let units: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16(&units)
+                .map_err(|e| Error::type_conversion(format!("invalid UTF-16 in CLOB: {}", e)))
+        } else {
+            String::from_utf8(data.to_vec())
+                .map_err(|e| Error::type_conversion(format!("invalid UTF-8 in CLOB: {}", e)))
+        }
+    }
+
+    /// Bytes per LOB character on the wire (for sizing reads).
+    pub(crate) fn max_bytes_per_char(&self) -> u64 {
+        if self.is_blob() || self.is_bfile() {
+            1
+        } else if self.uses_utf16() {
+            2
+        } else {
+            4
+        }
+    }
+}
+
+impl fmt::Display for Lob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} ({})>", self.oracle_type, self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clob_with_flags(flag3: u8, flag4: u8) -> Lob {
+        let mut locator = vec![0u8; TNS_LOB_TEMP_LOCATOR_SIZE];
+        locator[TNS_LOB_LOC_OFFSET_FLAG_3] = flag3;
+        locator[TNS_LOB_LOC_OFFSET_FLAG_4] = flag4;
+        Lob::new(OracleType::Clob, locator, 0, 0)
+    }
+
+    #[test]
+    fn test_lob_flags() {
+        let lob = clob_with_flags(0, TNS_LOB_LOC_FLAGS_TEMP);
+        assert!(lob.is_temporary());
+        assert!(!lob.is_blob());
+        assert!(!clob_with_flags(0, 0).is_temporary());
+        assert!(!Lob::new(OracleType::Blob, vec![], 0, 0).is_temporary());
+
+        let bfile = Lob::new(OracleType::Bfile, vec![0; 60], 0, 0);
+        assert!(bfile.is_bfile());
+        assert!(!bfile.is_blob());
+        assert_eq!(bfile.max_bytes_per_char(), 1);
+    }
+
+    #[test]
+    fn test_clob_text_utf16() {
+        let lob = clob_with_flags(TNS_LOB_LOC_FLAGS_VAR_LENGTH_CHARSET, 0);
+        let (bytes, len) = lob.encode_text("a😀");
+        // Surrogate pairs count as two characters
+        assert_eq!(len, 3);
+        assert_eq!(bytes, vec![0x00, 0x61, 0xD8, 0x3D, 0xDE, 0x00]);
+        assert_eq!(lob.decode_text(&bytes).unwrap(), "a😀");
+    }
+
+    #[test]
+    fn test_clob_text_utf8() {
+        let lob = clob_with_flags(0, 0);
+        let (bytes, len) = lob.encode_text("héllo");
+        assert_eq!(len, 5);
+        assert_eq!(bytes, "héllo".as_bytes());
+        assert_eq!(lob.decode_text(&bytes).unwrap(), "héllo");
+        assert!(lob.decode_text(&[0xFF]).is_err());
+    }
+
+    #[test]
+    fn test_lob_display() {
+        let lob = Lob::new(OracleType::Blob, vec![1, 2], 42, 8132);
+        assert_eq!(lob.to_string(), "<BLOB (42)>");
+    }
+}
@@ -3,6 +3,17 @@
 //! This struct preserves the raw Oracle wire format data.
 //! For user-facing API, use `Column` which provides a cleaner interface.
 
+use crate::error::Result;
+use crate::protocol::constants::{
+    CS_FORM_NCHAR, ORA_TYPE_NUM_BFILE, ORA_TYPE_NUM_BINARY_DOUBLE, ORA_TYPE_NUM_BINARY_FLOAT,
+    ORA_TYPE_NUM_BINARY_INTEGER, ORA_TYPE_NUM_BLOB, ORA_TYPE_NUM_CHAR, ORA_TYPE_NUM_CLOB,
+    ORA_TYPE_NUM_DATE, ORA_TYPE_NUM_JSON, ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_RAW,
+    ORA_TYPE_NUM_TIMESTAMP, ORA_TYPE_NUM_TIMESTAMP_LTZ, ORA_TYPE_NUM_TIMESTAMP_TZ,
+    ORA_TYPE_NUM_VARCHAR, ORA_TYPE_NUM_VECTOR,
+};
+
+use super::oracle_type::OracleType;
+
 /// Internal column metadata from wire format.
 ///
 /// Use `Column` for user-facing API.
@@ -22,6 +33,13 @@ pub struct ColumnMetadata {
     pub buffer_size: u32,
     /// Whether NULL values are allowed.
     pub nullable: bool,
+    /// Character set form (`CS_FORM_NCHAR` for NCHAR, NVARCHAR2 and NCLOB;
+    /// 0 for non-character columns).
+    pub charset_form: u8,
+    /// Whether the column has an `IS JSON` check constraint.
+    pub is_json: bool,
+    /// Whether the column is stored as OSON (`IS JSON FORMAT OSON`).
+    pub is_oson: bool,
 }
 
 impl ColumnMetadata {
@@ -35,6 +53,118 @@ impl ColumnMetadata {
             max_size: 0,
             buffer_size: 0,
             nullable: true,
+            charset_form: 0,
+            is_json: false,
+            is_oson: false,
+        }
+    }
+
+    /// Whether the column uses the national character set.
+    pub fn is_nchar(&self) -> bool {
+        self.charset_form == CS_FORM_NCHAR
+    }
+
+    /// Get the column data type, telling national character set columns
+    /// apart.
+    ///
+    /// Returns `Err(Error::UnsupportedType)` for unsupported types.
+    pub fn data_type(&self) -> Result<OracleType> {
+        let data_type =
+            OracleType::from_raw(self.oracle_type, self.precision, self.scale, self.max_size)?;
+        Ok(if self.is_nchar() {
+            data_type.to_national()
+        } else {
+            data_type
+        })
+    }
+
+    /// Display size in characters, as reported by python-oracledb.
+    ///
+    /// Character and RAW columns use their declared size; numbers use
+    /// precision (plus sign and decimal point), falling back to 127 when
+    /// unconstrained; dates and timestamps use 23. Other types have no
+    /// meaningful display size.
+    pub fn display_size(&self) -> Option<u32> {
+        match self.oracle_type as u16 {
+            ORA_TYPE_NUM_VARCHAR | ORA_TYPE_NUM_CHAR | ORA_TYPE_NUM_RAW if self.max_size > 0 => {
+                Some(self.max_size)
+            }
+            ORA_TYPE_NUM_NUMBER | ORA_TYPE_NUM_BINARY_INTEGER => {
+                if self.precision > 0 {
+                    let mut size = self.precision as u32 + 1;
+                    if self.scale > 0 {
+                        size += self.scale as u32 + 1;
+                    }
+                    Some(size)
+                } else {
+                    Some(127)
+                }
+            }
+            ORA_TYPE_NUM_BINARY_FLOAT | ORA_TYPE_NUM_BINARY_DOUBLE => Some(127),
+            ORA_TYPE_NUM_DATE
+            | ORA_TYPE_NUM_TIMESTAMP
+            | ORA_TYPE_NUM_TIMESTAMP_TZ
+            | ORA_TYPE_NUM_TIMESTAMP_LTZ => Some(23),
+            _ => None,
+        }
+    }
+
+    /// Whether the column is a NUMBER with scale 0 and a precision whose
+    /// values all fit in an `i64` (at most 18 digits).
+    pub fn is_integer(&self) -> bool {
+        self.oracle_type as u16 == ORA_TYPE_NUM_NUMBER
+            && self.scale == 0
+            && (1..=18).contains(&self.precision)
+    }
+
+    /// Whether fetching this column needs a DEFINE round trip.
+    ///
+    /// LOB-like columns (CLOB, BLOB, BFILE, JSON, VECTOR) are returned as
+    /// locators unless the client redefines them; all other types are
+    /// returned inline with the execute response.
+    pub fn requires_define(&self) -> bool {
+        matches!(
+            self.oracle_type as u16,
+            ORA_TYPE_NUM_CLOB
+                | ORA_TYPE_NUM_BLOB
+                | ORA_TYPE_NUM_BFILE
+                | ORA_TYPE_NUM_JSON
+                | ORA_TYPE_NUM_VECTOR
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_define() {
+        for ty in [ORA_TYPE_NUM_VARCHAR, ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_DATE] {
+            assert!(!ColumnMetadata::new("C".to_string(), ty as u8).requires_define());
         }
+        for ty in [ORA_TYPE_NUM_CLOB, ORA_TYPE_NUM_BLOB, ORA_TYPE_NUM_JSON] {
+            assert!(ColumnMetadata::new("C".to_string(), ty as u8).requires_define());
+        }
+    }
+
+    #[test]
+    fn test_display_size() {
+        let mut col = ColumnMetadata::new("C".to_string(), ORA_TYPE_NUM_VARCHAR as u8);
+        col.max_size = 40;
+        assert_eq!(col.display_size(), Some(40));
+
+        let mut col = ColumnMetadata::new("N".to_string(), ORA_TYPE_NUM_NUMBER as u8);
+        assert_eq!(col.display_size(), Some(127));
+        col.precision = 10;
+        assert_eq!(col.display_size(), Some(11));
+        col.scale = 2;
+        assert_eq!(col.display_size(), Some(14));
+
+        let col = ColumnMetadata::new("D".to_string(), ORA_TYPE_NUM_DATE as u8);
+        assert_eq!(col.display_size(), Some(23));
+
+        let col = ColumnMetadata::new("L".to_string(), ORA_TYPE_NUM_CLOB as u8);
+        assert_eq!(col.display_size(), None);
     }
 }
@@ -1,13 +1,24 @@
 //! Oracle data types for query results.
 
+mod codes;
 mod column;
+mod convert;
+mod identifiers;
+mod lob;
 mod metadata;
 mod oracle_type;
 mod row;
 mod value;
+mod vector;
 
+pub use codes::{CharsetForm, CharsetId, OracleTypeNum};
 pub use column::{Column, ColumnInfo};
+pub use convert::{FromSql, ToSql};
+pub use identifiers::{Guid, RowId, Scn};
+pub use lob::Lob;
 pub use metadata::ColumnMetadata;
 pub use oracle_type::OracleType;
-pub use row::Row;
+pub(crate) use row::Cell;
+pub use row::{FromRow, Row};
 pub use value::OracleValue;
+pub use vector::{Vector, VectorData};
@@ -7,16 +7,27 @@
 
 use crate::error::{Error, Result};
 use crate::protocol::constants::{
-    ORA_TYPE_NUM_BINARY_INTEGER, ORA_TYPE_NUM_BLOB, ORA_TYPE_NUM_CHAR, ORA_TYPE_NUM_CLOB,
-    ORA_TYPE_NUM_DATE, ORA_TYPE_NUM_LONG, ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_VARCHAR,
+    ORA_TYPE_NUM_BFILE, ORA_TYPE_NUM_BINARY_INTEGER, ORA_TYPE_NUM_BLOB, ORA_TYPE_NUM_BOOLEAN,
+    ORA_TYPE_NUM_CHAR, ORA_TYPE_NUM_CLOB, ORA_TYPE_NUM_DATE, ORA_TYPE_NUM_INTERVAL_DS,
+    ORA_TYPE_NUM_INTERVAL_YM, ORA_TYPE_NUM_JSON, ORA_TYPE_NUM_LONG, ORA_TYPE_NUM_LONG_RAW,
+    ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_RAW, ORA_TYPE_NUM_ROWID, ORA_TYPE_NUM_UROWID,
+    ORA_TYPE_NUM_VARCHAR, ORA_TYPE_NUM_VECTOR,
 };
 
+/// Scale reported for floating-point numbers: FLOAT columns, and NUMBER
+/// expressions without a fixed scale such as ROWNUM and ORA_ROWSCN.
+const FLOAT_SCALE: i8 = -127;
+
 /// Oracle data type with type-specific attributes.
 #[derive(Debug, Clone, PartialEq)]
 pub enum OracleType {
     /// VARCHAR2(max_length) - variable-length string.
     Varchar2 { max_size: u32 },
     /// NUMBER(precision, scale) - numeric type.
+    ///
+    /// A scale of -127 marks a floating-point number: FLOAT(precision) with
+    /// a binary precision, or an unconstrained NUMBER when the precision is
+    /// 0 (as for the ROWNUM and ORA_ROWSCN pseudo-columns).
     Number { precision: i8, scale: i8 },
     /// BINARY_INTEGER - integer type.
     BinaryInteger,
@@ -24,14 +35,38 @@ pub enum OracleType {
     Long,
     /// CHAR(size) - fixed-length string.
     Char { max_size: u32 },
+    /// NVARCHAR2(max_length) - variable-length national character set string.
+    NVarchar2 { max_size: u32 },
+    /// NCHAR(size) - fixed-length national character set string.
+    NChar { max_size: u32 },
     /// DATE - date/time (no timezone).
     Date,
+    /// RAW(size) - variable-length binary data.
+    Raw { max_size: u32 },
+    /// LONG RAW - legacy large binary type.
+    LongRaw,
+    /// ROWID - physical row address.
+    Rowid,
+    /// UROWID - universal (physical or logical) row address.
+    Urowid,
     /// CLOB - Character Large Object.
     Clob,
     /// NCLOB - National Character Large Object.
     Nclob,
     /// BLOB - Binary Large Object.
     Blob,
+    /// BFILE - read-only binary file stored outside the database.
+    Bfile,
+    /// JSON - native binary JSON (OSON).
+    Json,
+    /// VECTOR - dense or sparse vector (23ai).
+    Vector,
+    /// BOOLEAN - native boolean (23ai).
+    Boolean,
+    /// INTERVAL YEAR TO MONTH.
+    IntervalYM,
+    /// INTERVAL DAY TO SECOND.
+    IntervalDS,
 }
 
 impl OracleType {
@@ -46,8 +81,18 @@ impl OracleType {
             ORA_TYPE_NUM_LONG => Ok(OracleType::Long),
             ORA_TYPE_NUM_CHAR => Ok(OracleType::Char { max_size }),
             ORA_TYPE_NUM_DATE => Ok(OracleType::Date),
+            ORA_TYPE_NUM_RAW => Ok(OracleType::Raw { max_size }),
+            ORA_TYPE_NUM_LONG_RAW => Ok(OracleType::LongRaw),
+            ORA_TYPE_NUM_ROWID => Ok(OracleType::Rowid),
+            ORA_TYPE_NUM_UROWID => Ok(OracleType::Urowid),
             ORA_TYPE_NUM_CLOB => Ok(OracleType::Clob),
             ORA_TYPE_NUM_BLOB => Ok(OracleType::Blob),
+            ORA_TYPE_NUM_BFILE => Ok(OracleType::Bfile),
+            ORA_TYPE_NUM_JSON => Ok(OracleType::Json),
+            ORA_TYPE_NUM_VECTOR => Ok(OracleType::Vector),
+            ORA_TYPE_NUM_BOOLEAN => Ok(OracleType::Boolean),
+            ORA_TYPE_NUM_INTERVAL_YM => Ok(OracleType::IntervalYM),
+            ORA_TYPE_NUM_INTERVAL_DS => Ok(OracleType::IntervalDS),
             _ => Err(Error::UnsupportedType {
                 type_num: oracle_type,
             }),
@@ -57,17 +102,48 @@ impl OracleType {
     /// Get the Oracle type number.
     pub fn type_num(&self) -> u8 {
         match self {
-            OracleType::Varchar2 { .. } => ORA_TYPE_NUM_VARCHAR as u8,
+            OracleType::Varchar2 { .. } | OracleType::NVarchar2 { .. } => {
+                ORA_TYPE_NUM_VARCHAR as u8
+            }
             OracleType::Number { .. } => ORA_TYPE_NUM_NUMBER as u8,
             OracleType::BinaryInteger => ORA_TYPE_NUM_BINARY_INTEGER as u8,
             OracleType::Long => ORA_TYPE_NUM_LONG as u8,
-            OracleType::Char { .. } => ORA_TYPE_NUM_CHAR as u8,
+            OracleType::Char { .. } | OracleType::NChar { .. } => ORA_TYPE_NUM_CHAR as u8,
             OracleType::Date => ORA_TYPE_NUM_DATE as u8,
+            OracleType::Raw { .. } => ORA_TYPE_NUM_RAW as u8,
+            OracleType::LongRaw => ORA_TYPE_NUM_LONG_RAW as u8,
+            OracleType::Rowid => ORA_TYPE_NUM_ROWID as u8,
+            OracleType::Urowid => ORA_TYPE_NUM_UROWID as u8,
             OracleType::Clob | OracleType::Nclob => ORA_TYPE_NUM_CLOB as u8,
             OracleType::Blob => ORA_TYPE_NUM_BLOB as u8,
+            OracleType::Bfile => ORA_TYPE_NUM_BFILE as u8,
+            OracleType::Json => ORA_TYPE_NUM_JSON as u8,
+            OracleType::Vector => ORA_TYPE_NUM_VECTOR as u8,
+            OracleType::Boolean => ORA_TYPE_NUM_BOOLEAN as u8,
+            OracleType::IntervalYM => ORA_TYPE_NUM_INTERVAL_YM as u8,
+            OracleType::IntervalDS => ORA_TYPE_NUM_INTERVAL_DS as u8,
+        }
+    }
+
+    /// Get the national character set counterpart of a character type
+    /// (NVARCHAR2, NCHAR, NCLOB); other types are returned unchanged.
+    pub fn to_national(self) -> Self {
+        match self {
+            OracleType::Varchar2 { max_size } => OracleType::NVarchar2 { max_size },
+            OracleType::Char { max_size } => OracleType::NChar { max_size },
+            OracleType::Clob => OracleType::Nclob,
+            other => other,
         }
     }
 
+    /// Whether the type uses the national character set.
+    pub fn is_national(&self) -> bool {
+        matches!(
+            self,
+            OracleType::NVarchar2 { .. } | OracleType::NChar { .. } | OracleType::Nclob
+        )
+    }
+
     /// Get precision (for Number types, 0 otherwise).
     pub fn precision(&self) -> i8 {
         match self {
@@ -84,11 +160,14 @@ impl OracleType {
         }
     }
 
-    /// Get max_size (for sized types like Varchar2/Char, 0 otherwise).
+    /// Get max_size (for sized types like Varchar2/Char/Raw, 0 otherwise).
     pub fn max_size(&self) -> u32 {
         match self {
             OracleType::Varchar2 { max_size } => *max_size,
             OracleType::Char { max_size } => *max_size,
+            OracleType::NVarchar2 { max_size } => *max_size,
+            OracleType::NChar { max_size } => *max_size,
+            OracleType::Raw { max_size } => *max_size,
             _ => 0,
         }
     }
@@ -99,8 +178,10 @@ impl std::fmt::Display for OracleType {
         match self {
             OracleType::Varchar2 { max_size } => write!(f, "VARCHAR2({})", max_size),
             OracleType::Number { precision, scale } => {
-                if *precision == 0 && *scale == 0 {
+                if *precision == 0 && (*scale == 0 || *scale == FLOAT_SCALE) {
                     write!(f, "NUMBER")
+                } else if *scale == FLOAT_SCALE {
+                    write!(f, "FLOAT({})", precision)
                 } else if *scale == 0 {
                     write!(f, "NUMBER({})", precision)
                 } else {
@@ -110,10 +191,22 @@ impl std::fmt::Display for OracleType {
             OracleType::BinaryInteger => write!(f, "BINARY_INTEGER"),
             OracleType::Long => write!(f, "LONG"),
             OracleType::Char { max_size } => write!(f, "CHAR({})", max_size),
+            OracleType::NVarchar2 { max_size } => write!(f, "NVARCHAR2({})", max_size),
+            OracleType::NChar { max_size } => write!(f, "NCHAR({})", max_size),
             OracleType::Date => write!(f, "DATE"),
+            OracleType::Raw { max_size } => write!(f, "RAW({})", max_size),
+            OracleType::LongRaw => write!(f, "LONG RAW"),
+            OracleType::Rowid => write!(f, "ROWID"),
+            OracleType::Urowid => write!(f, "UROWID"),
             OracleType::Clob => write!(f, "CLOB"),
             OracleType::Nclob => write!(f, "NCLOB"),
             OracleType::Blob => write!(f, "BLOB"),
+            OracleType::Bfile => write!(f, "BFILE"),
+            OracleType::Json => write!(f, "JSON"),
+            OracleType::Vector => write!(f, "VECTOR"),
+            OracleType::Boolean => write!(f, "BOOLEAN"),
+            OracleType::IntervalYM => write!(f, "INTERVAL YEAR TO MONTH"),
+            OracleType::IntervalDS => write!(f, "INTERVAL DAY TO SECOND"),
         }
     }
 }
@@ -140,6 +233,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_raw_binary() {
+        let t = OracleType::from_raw(ORA_TYPE_NUM_RAW as u8, 0, 0, 16);
+        assert_eq!(t.unwrap(), OracleType::Raw { max_size: 16 });
+        let t = OracleType::from_raw(ORA_TYPE_NUM_LONG_RAW as u8, 0, 0, 0);
+        assert_eq!(t.unwrap(), OracleType::LongRaw);
+        assert_eq!(format!("{}", OracleType::Raw { max_size: 16 }), "RAW(16)");
+    }
+
+    #[test]
+    fn test_from_raw_bfile() {
+        let t = OracleType::from_raw(ORA_TYPE_NUM_BFILE as u8, 0, 0, 0);
+        assert_eq!(t.unwrap(), OracleType::Bfile);
+        assert_eq!(OracleType::Bfile.type_num(), 114);
+        assert_eq!(OracleType::Bfile.to_string(), "BFILE");
+    }
+
+    #[test]
+    fn test_from_raw_23ai() {
+        let t = OracleType::from_raw(ORA_TYPE_NUM_BOOLEAN as u8, 0, 0, 0);
+        assert_eq!(t.unwrap(), OracleType::Boolean);
+        let t = OracleType::from_raw(ORA_TYPE_NUM_VECTOR as u8, 0, 0, 0);
+        assert_eq!(t.unwrap(), OracleType::Vector);
+        assert_eq!(OracleType::Boolean.type_num(), 252);
+    }
+
+    #[test]
+    fn test_national_types() {
+        let t = OracleType::Varchar2 { max_size: 20 }.to_national();
+        assert_eq!(t, OracleType::NVarchar2 { max_size: 20 });
+        assert_eq!(t.type_num(), ORA_TYPE_NUM_VARCHAR as u8);
+        assert_eq!(t.to_string(), "NVARCHAR2(20)");
+        assert_eq!(
+            OracleType::Char { max_size: 5 }.to_national().to_string(),
+            "NCHAR(5)"
+        );
+        assert_eq!(OracleType::Clob.to_national(), OracleType::Nclob);
+        assert!(OracleType::Nclob.is_national());
+        assert!(!OracleType::Clob.is_national());
+        assert_eq!(OracleType::Date.to_national(), OracleType::Date);
+    }
+
     #[test]
     fn test_from_raw_unsupported() {
         let t = OracleType::from_raw(255, 0, 0, 0);
@@ -193,4 +328,20 @@ mod tests {
             "NUMBER"
         );
     }
+
+    #[test]
+    fn test_display_float_scale() {
+        // ROWNUM and ORA_ROWSCN describe as precision 0, scale -127
+        let rownum = OracleType::from_raw(ORA_TYPE_NUM_NUMBER as u8, 0, -127, 22).unwrap();
+        assert_eq!(rownum.to_string(), "NUMBER");
+        let float = OracleType::Number {
+            precision: 126,
+            scale: -127,
+        };
+        assert_eq!(float.to_string(), "FLOAT(126)");
+
+        let rowid = OracleType::from_raw(ORA_TYPE_NUM_ROWID as u8, 0, 0, 0).unwrap();
+        assert_eq!(rowid, OracleType::Rowid);
+        assert_eq!(rowid.to_string(), "ROWID");
+    }
 }
@@ -1,15 +1,146 @@
 //! Row type for query results.
+//!
+//! Scalar column values are kept as the bytes received for them, sliced from
+//! the response without copying, and decoded on first access. Reading a few
+//! columns of a wide row only pays for those columns.
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+
+use bytes::Bytes;
+
+use crate::error::{Error, Result};
+use crate::protocol::decode::decode_value;
 
 use super::column::{Column, ColumnInfo};
+use super::convert::FromSql;
 use super::value::OracleValue;
 
+/// A column value of a row.
+#[derive(Debug, Clone)]
+pub(crate) enum Cell {
+    /// Value decoded while the row was parsed (NULLs, ROWIDs, LOB locators).
+    Value(OracleValue),
+    /// Bytes received for the value, decoded on first access.
+    Encoded {
+        /// Oracle type number of the column.
+        ora_type: u16,
+        /// Character set form of the column.
+        charset_form: u8,
+        /// Value bytes.
+        bytes: Bytes,
+        /// Decoded value, or the decode error message.
+        decoded: OnceLock<std::result::Result<OracleValue, String>>,
+    },
+}
+
+impl Cell {
+    /// Create a cell decoded on first access.
+    pub(crate) fn encoded(ora_type: u16, charset_form: u8, bytes: Bytes) -> Self {
+        Self::Encoded {
+            ora_type,
+            charset_form,
+            bytes,
+            decoded: OnceLock::new(),
+        }
+    }
+
+    /// Get the value, decoding it on first access.
+    fn value(&self) -> Result<&OracleValue> {
+        match self {
+            Self::Value(value) => Ok(value),
+            Self::Encoded {
+                ora_type,
+                charset_form,
+                bytes,
+                decoded,
+            } => decoded
+                .get_or_init(|| {
+                    decode_value(*ora_type, *charset_form, bytes).map_err(error_message)
+                })
+                .as_ref()
+                .map_err(|message| Error::protocol(message.clone())),
+        }
+    }
+
+    /// Take the value, decoding it if it was not accessed yet.
+    pub(crate) fn into_value(self) -> Result<OracleValue> {
+        match self {
+            Self::Value(value) => Ok(value),
+            Self::Encoded {
+                ora_type,
+                charset_form,
+                bytes,
+                mut decoded,
+            } => match decoded.take() {
+                Some(result) => result.map_err(Error::protocol),
+                None => decode_value(ora_type, charset_form, &bytes),
+            },
+        }
+    }
+
+    /// Estimate the memory used by the cell, in bytes.
+    fn memory_size(&self) -> usize {
+        match self {
+            Self::Value(value) => value.memory_size(),
+            Self::Encoded { bytes, decoded, .. } => {
+                let value = match decoded.get() {
+                    Some(Ok(value)) => value.memory_size(),
+                    _ => 0,
+                };
+                std::mem::size_of::<Self>() + bytes.len() + value
+            }
+        }
+    }
+}
+
+/// Message of a decode error, kept so the error can be reported again.
+fn error_message(error: Error) -> String {
+    match error {
+        Error::Protocol { message } => message,
+        error => error.to_string(),
+    }
+}
+
+/// Conversion from a row of query results, for `query_as`.
+///
+/// With the `derive` feature, `#[derive(FromRow)]` implements it for structs
+/// whose fields are named after the columns.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::{FromRow, Result, Row};
+///
+/// struct User {
+///     id: i64,
+///     name: Option<String>,
+/// }
+///
+/// impl FromRow for User {
+///     fn from_row(row: &Row) -> Result<Self> {
+///         Ok(Self {
+///             id: row.get_as_by_name("ID")?,
+///             name: row.get_as_by_name("NAME")?,
+///         })
+///     }
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Convert from a row.
+    fn from_row(row: &Row) -> Result<Self>;
+}
+
+impl FromRow for Row {
+    fn from_row(row: &Row) -> Result<Self> {
+        Ok(row.clone())
+    }
+}
+
 /// A row of query results.
 #[derive(Debug, Clone)]
 pub struct Row {
-    /// Column values.
-    values: Vec<OracleValue>,
+    /// Column values, decoded on first access.
+    cells: Vec<Cell>,
     /// Shared column information (reference counted).
     column_info: Arc<ColumnInfo>,
 }
@@ -17,42 +148,97 @@ pub struct Row {
 impl Row {
     /// Create a new row with values and shared column info.
     pub fn new(values: Vec<OracleValue>, column_info: Arc<ColumnInfo>) -> Self {
-        Self {
-            values,
-            column_info,
-        }
+        Self::from_cells(values.into_iter().map(Cell::Value).collect(), column_info)
+    }
+
+    /// Create a row from parsed cells.
+    pub(crate) fn from_cells(cells: Vec<Cell>, column_info: Arc<ColumnInfo>) -> Self {
+        Self { cells, column_info }
+    }
+
+    /// Get the cell of a column (0-based).
+    pub(crate) fn cell(&self, index: usize) -> Option<&Cell> {
+        self.cells.get(index)
     }
 
     /// Get value by column index (0-based).
+    ///
+    /// Returns `None` if the index is out of range or the value cannot be
+    /// decoded; `try_get` tells the two apart.
     pub fn get(&self, index: usize) -> Option<&OracleValue> {
-        self.values.get(index)
+        self.cells.get(index)?.value().ok()
+    }
+
+    /// Get value by column index (0-based), reporting values that cannot be
+    /// decoded.
+    pub fn try_get(&self, index: usize) -> Result<&OracleValue> {
+        self.cells
+            .get(index)
+            .ok_or(Error::ColumnIndexOutOfBounds {
+                index,
+                count: self.cells.len(),
+            })?
+            .value()
     }
 
     /// Get value by column name (case-insensitive).
     pub fn get_by_name(&self, name: &str) -> Option<&OracleValue> {
         self.column_info
             .find_by_name(name)
-            .and_then(|idx| self.values.get(idx))
+            .and_then(|idx| self.get(idx))
+    }
+
+    /// Get value by column index, converted to `T`.
+    ///
+    /// Returns `Error::NullValue` if the value is NULL and `T` is not an `Option`.
+    pub fn get_as<T: FromSql>(&self, index: usize) -> Result<T> {
+        let value = self.try_get(index)?;
+        T::from_sql(value).map_err(|e| {
+            if value.is_null() {
+                Error::NullValue {
+                    column: self
+                        .column_info
+                        .get(index)
+                        .map(|c| c.name.clone())
+                        .unwrap_or_default(),
+                }
+            } else {
+                e
+            }
+        })
+    }
+
+    /// Get value by column name (case-insensitive), converted to `T`.
+    ///
+    /// Returns `Error::ColumnNotFound` if no column has that name.
+    pub fn get_as_by_name<T: FromSql>(&self, name: &str) -> Result<T> {
+        let index = self
+            .column_info
+            .find_by_name(name)
+            .ok_or_else(|| Error::ColumnNotFound {
+                name: name.to_string(),
+            })?;
+        self.get_as(index)
     }
 
     /// Get the number of columns.
     pub fn len(&self) -> usize {
-        self.values.len()
+        self.cells.len()
     }
 
     /// Check if the row is empty.
     pub fn is_empty(&self) -> bool {
-        self.values.is_empty()
+        self.cells.is_empty()
     }
 
-    /// Get all values.
-    pub fn values(&self) -> &[OracleValue] {
-        &self.values
+    /// Get all values, decoding any not accessed yet.
+    pub fn values(&self) -> Result<Vec<&OracleValue>> {
+        self.iter().collect()
     }
 
     /// Get column information.
     pub fn columns(&self) -> &[Column] {
-        &self.column_info.columns
+        self.column_info.columns()
     }
 
     /// Get column names.
@@ -60,27 +246,21 @@ impl Row {
         self.column_info.column_names()
     }
 
-    /// Iterate over values.
-    pub fn iter(&self) -> impl Iterator<Item = &OracleValue> {
-        self.values.iter()
+    /// Iterate over values, decoding each on access.
+    pub fn iter(&self) -> impl Iterator<Item = Result<&OracleValue>> {
+        self.cells.iter().map(Cell::value)
     }
-}
-
-impl IntoIterator for Row {
-    type Item = OracleValue;
-    type IntoIter = std::vec::IntoIter<OracleValue>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.values.into_iter()
+    /// Take the values, decoding any not accessed yet.
+    pub fn into_values(self) -> Result<Vec<OracleValue>> {
+        self.cells.into_iter().map(Cell::into_value).collect()
     }
-}
-
-impl<'a> IntoIterator for &'a Row {
-    type Item = &'a OracleValue;
-    type IntoIter = std::slice::Iter<'a, OracleValue>;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.values.iter()
+    /// Estimate the memory used by the row, in bytes. The column information
+    /// shared with other rows is not counted, nor is the rest of the response
+    /// buffer that undecoded values are sliced from.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.cells.iter().map(Cell::memory_size).sum::<usize>()
     }
 }
 
@@ -96,6 +276,9 @@ mod tests {
                 nullable: true,
                 data_type: OracleType::Varchar2 { max_size: 100 },
                 oracle_type_num: 1,
+                display_size: Some(100),
+                is_json: false,
+                is_oson: false,
             },
             Column {
                 name: "VALUE".to_string(),
@@ -105,6 +288,9 @@ mod tests {
                     scale: 0,
                 },
                 oracle_type_num: 2,
+                display_size: Some(11),
+                is_json: false,
+                is_oson: false,
             },
         ]))
     }
@@ -145,4 +331,70 @@ mod tests {
         assert_eq!(columns[0].name, "NAME");
         assert_eq!(columns[1].name, "VALUE");
     }
+
+    #[test]
+    fn test_row_get_as() {
+        let column_info = make_test_column_info();
+        let row = Row::new(
+            vec![OracleValue::Null, OracleValue::Number("42".to_string())],
+            column_info,
+        );
+
+        assert_eq!(row.get_as::<i64>(1).unwrap(), 42);
+        assert_eq!(row.get_as::<Option<String>>(0).unwrap(), None);
+        assert!(matches!(
+            row.get_as::<String>(0),
+            Err(Error::NullValue { column }) if column == "NAME"
+        ));
+        assert!(matches!(
+            row.get_as::<i64>(5),
+            Err(Error::ColumnIndexOutOfBounds { index: 5, count: 2 })
+        ));
+
+        assert_eq!(row.get_as_by_name::<i64>("value").unwrap(), 42);
+        assert!(matches!(
+            row.get_as_by_name::<i64>("missing"),
+            Err(Error::ColumnNotFound { name }) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_row_lazy_decode() {
+        use crate::protocol::constants::{
+            ORA_TYPE_NUM_DATE, ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_VARCHAR,
+        };
+
+        let payload = Bytes::from_static(b"test\xc1\x2b\xff");
+        let row = Row::from_cells(
+            vec![
+                Cell::encoded(ORA_TYPE_NUM_VARCHAR, 0, payload.slice(0..4)),
+                Cell::encoded(ORA_TYPE_NUM_NUMBER, 0, payload.slice(4..6)),
+                Cell::encoded(ORA_TYPE_NUM_DATE, 0, payload.slice(6..7)),
+            ],
+            make_test_column_info(),
+        );
+
+        // Nothing is decoded until accessed
+        assert!(row.cells.iter().all(|cell| matches!(
+            cell,
+            Cell::Encoded { decoded, .. } if decoded.get().is_none()
+        )));
+        assert_eq!(row.get_as::<i64>(1).unwrap(), 42);
+        assert!(matches!(&row.cells[0], Cell::Encoded { decoded, .. } if decoded.get().is_none()));
+        assert_eq!(row.get(0), Some(&OracleValue::String("test".to_string())));
+
+        // Values that cannot be decoded are reported by try_get
+        assert_eq!(row.get(2), None);
+        assert!(matches!(row.try_get(2), Err(Error::Protocol { .. })));
+        assert!(row.values().is_err());
+        assert!(row.clone().into_values().is_err());
+
+        let values = Row::from_cells(row.cells[..2].to_vec(), make_test_column_info())
+            .into_values()
+            .unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0], OracleValue::String("test".to_string()));
+        // A Number, or a Decimal with the rust_decimal feature
+        assert_eq!(values[1].to_i64(), Some(42));
+    }
 }
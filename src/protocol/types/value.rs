@@ -1,10 +1,18 @@
 //! Oracle value types for query results.
 
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use std::fmt;
 
+use super::lob::Lob;
+use super::vector::{Vector, VectorData};
+
 /// Oracle value enum representing a single column value.
+///
+/// Non-exhaustive: some variants exist only with a crate feature enabled
+/// (`Decimal` with `rust_decimal`, `Json` with `json`), and any crate in the
+/// build may enable them, so matches need a wildcard arm.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum OracleValue {
     /// NULL value.
     Null,
@@ -13,8 +21,32 @@ pub enum OracleValue {
     /// Number value as string (preserves precision).
     /// Can be converted to i64/f64 as needed.
     Number(String),
+    /// Integer value, from NUMBER columns with scale 0 and at most 18
+    /// digits of precision (`Number` for other NUMBER columns).
+    Integer(i64),
+    /// Exact number value. With the `rust_decimal` feature, NUMBER columns
+    /// decode to this variant, falling back to `Number` for values a
+    /// `Decimal` cannot hold (more than 28 decimal places, at least 2^96 in
+    /// magnitude, or infinite).
+    #[cfg(feature = "rust_decimal")]
+    Decimal(rust_decimal::Decimal),
     /// Date/time value (DATE type).
     Date(NaiveDateTime),
+    /// Boolean value (BOOLEAN type, 23ai).
+    Boolean(bool),
+    /// Binary value (RAW, LONG RAW).
+    Raw(Vec<u8>),
+    /// INTERVAL YEAR TO MONTH value (both components carry the sign).
+    IntervalYM { years: i32, months: i32 },
+    /// INTERVAL DAY TO SECOND value.
+    IntervalDS(Duration),
+    /// Native JSON value (decoded from OSON).
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+    /// VECTOR value (23ai).
+    Vector(Vector),
+    /// LOB locator (CLOB, BLOB); content is read through the connection.
+    Lob(Lob),
 }
 
 impl OracleValue {
@@ -24,6 +56,9 @@ impl OracleValue {
     }
 
     /// Try to get the value as a string reference.
+    ///
+    /// `Integer` and `Decimal` numbers have no string form to borrow; use
+    /// `to_string`.
     pub fn as_str(&self) -> Option<&str> {
         match self {
             OracleValue::String(s) => Some(s),
@@ -36,6 +71,11 @@ impl OracleValue {
     pub fn to_i64(&self) -> Option<i64> {
         match self {
             OracleValue::Number(s) => s.parse().ok(),
+            OracleValue::Integer(i) => Some(*i),
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(d) if d.fract().is_zero() => {
+                rust_decimal::prelude::ToPrimitive::to_i64(d)
+            }
             _ => None,
         }
     }
@@ -44,6 +84,18 @@ impl OracleValue {
     pub fn to_f64(&self) -> Option<f64> {
         match self {
             OracleValue::Number(s) => s.parse().ok(),
+            OracleValue::Integer(i) => Some(*i as f64),
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(d) => rust_decimal::prelude::ToPrimitive::to_f64(d),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as an exact decimal.
+    #[cfg(feature = "rust_decimal")]
+    pub fn as_decimal(&self) -> Option<rust_decimal::Decimal> {
+        match self {
+            OracleValue::Decimal(d) => Some(*d),
             _ => None,
         }
     }
@@ -55,6 +107,104 @@ impl OracleValue {
             _ => None,
         }
     }
+
+    /// Try to get the value as a bool.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            OracleValue::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as a byte slice (RAW, LONG RAW).
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            OracleValue::Raw(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as a JSON value.
+    #[cfg(feature = "json")]
+    pub fn as_json(&self) -> Option<&serde_json::Value> {
+        match self {
+            OracleValue::Json(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as a vector.
+    pub fn as_vector(&self) -> Option<&Vector> {
+        match self {
+            OracleValue::Vector(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as a LOB locator.
+    pub fn as_lob(&self) -> Option<&Lob> {
+        match self {
+            OracleValue::Lob(lob) => Some(lob),
+            _ => None,
+        }
+    }
+
+    /// Try to convert a vector to dense f32 values (not available for BINARY vectors).
+    pub fn to_f32_vec(&self) -> Option<Vec<f32>> {
+        self.as_vector().and_then(Vector::to_f32_vec)
+    }
+
+    /// Try to convert a vector to dense f64 values (not available for BINARY vectors).
+    pub fn to_f64_vec(&self) -> Option<Vec<f64>> {
+        self.as_vector().and_then(Vector::to_f64_vec)
+    }
+
+    /// Try to get the value as (years, months) of an INTERVAL YEAR TO MONTH.
+    pub fn as_interval_ym(&self) -> Option<(i32, i32)> {
+        match self {
+            OracleValue::IntervalYM { years, months } => Some((*years, *months)),
+            _ => None,
+        }
+    }
+
+    /// Try to get the value as a Duration (INTERVAL DAY TO SECOND).
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            OracleValue::IntervalDS(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Estimate the memory used by the value, in bytes, including its heap
+    /// allocations.
+    pub fn memory_size(&self) -> usize {
+        let heap = match self {
+            OracleValue::String(s) | OracleValue::Number(s) => s.capacity(),
+            OracleValue::Raw(bytes) => bytes.capacity(),
+            #[cfg(feature = "json")]
+            OracleValue::Json(value) => value.to_string().len(),
+            OracleValue::Vector(vector) => {
+                let indices = match vector {
+                    Vector::Dense(_) => 0,
+                    Vector::Sparse { indices, .. } => indices.len() * 4,
+                };
+                indices + vector_data_size(vector.values())
+            }
+            OracleValue::Lob(lob) => lob.locator().len(),
+            _ => 0,
+        };
+        std::mem::size_of::<Self>() + heap
+    }
+}
+
+/// Bytes used by vector elements.
+fn vector_data_size(data: &VectorData) -> usize {
+    match data {
+        VectorData::Float32(v) => v.len() * 4,
+        VectorData::Float64(v) => v.len() * 8,
+        VectorData::Int8(v) => v.len(),
+        VectorData::Binary(v) => v.len(),
+    }
 }
 
 impl fmt::Display for OracleValue {
@@ -63,7 +213,44 @@ impl fmt::Display for OracleValue {
             OracleValue::Null => write!(f, "NULL"),
             OracleValue::String(s) => write!(f, "{}", s),
             OracleValue::Number(n) => write!(f, "{}", n),
+            OracleValue::Integer(i) => write!(f, "{}", i),
+            #[cfg(feature = "rust_decimal")]
+            OracleValue::Decimal(d) => write!(f, "{}", d),
             OracleValue::Date(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S")),
+            OracleValue::Boolean(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            OracleValue::Raw(bytes) => {
+                // Uppercase hex, matching RAWTOHEX()
+                for b in bytes {
+                    write!(f, "{:02X}", b)?;
+                }
+                Ok(())
+            }
+            OracleValue::IntervalYM { years, months } => {
+                let sign = if *years < 0 || *months < 0 { '-' } else { '+' };
+                write!(f, "{}{:02}-{:02}", sign, years.abs(), months.abs())
+            }
+            OracleValue::IntervalDS(d) => {
+                let sign = if *d < Duration::zero() { '-' } else { '+' };
+                let d = d.abs();
+                let secs = d.num_seconds();
+                let micros = (d - Duration::seconds(secs))
+                    .num_microseconds()
+                    .unwrap_or(0);
+                write!(
+                    f,
+                    "{}{:02} {:02}:{:02}:{:02}.{:06}",
+                    sign,
+                    secs / 86400,
+                    (secs % 86400) / 3600,
+                    (secs % 3600) / 60,
+                    secs % 60,
+                    micros
+                )
+            }
+            #[cfg(feature = "json")]
+            OracleValue::Json(v) => write!(f, "{}", v),
+            OracleValue::Vector(v) => write!(f, "{}", v),
+            OracleValue::Lob(lob) => write!(f, "{}", lob),
         }
     }
 }
@@ -99,5 +286,74 @@ mod tests {
         let int_val = OracleValue::Number("42".to_string());
         assert_eq!(int_val.to_i64(), Some(42));
         assert_eq!(int_val.to_f64(), Some(42.0));
+
+        let integer = OracleValue::Integer(-42);
+        assert_eq!(integer.to_i64(), Some(-42));
+        assert_eq!(integer.to_f64(), Some(-42.0));
+        assert_eq!(integer.as_str(), None);
+        assert_eq!(integer.to_string(), "-42");
+    }
+
+    #[test]
+    fn test_oracle_value_raw() {
+        let val = OracleValue::Raw(vec![0x00, 0xAB, 0xFF]);
+        assert_eq!(val.as_bytes(), Some(&[0x00, 0xAB, 0xFF][..]));
+        assert_eq!(val.as_str(), None);
+        assert_eq!(format!("{}", val), "00ABFF");
+    }
+
+    #[test]
+    fn test_oracle_value_boolean() {
+        let val = OracleValue::Boolean(true);
+        assert_eq!(val.as_bool(), Some(true));
+        assert_eq!(val.as_str(), None);
+        assert_eq!(format!("{}", val), "TRUE");
+        assert_eq!(format!("{}", OracleValue::Boolean(false)), "FALSE");
+        assert_eq!(OracleValue::Number("1".to_string()).as_bool(), None);
+    }
+
+    #[test]
+    fn test_oracle_value_vector() {
+        use crate::protocol::types::VectorData;
+
+        let val = OracleValue::Vector(Vector::Dense(VectorData::Float64(vec![1.0, 2.5])));
+        assert!(val.as_vector().is_some());
+        assert_eq!(val.to_f64_vec(), Some(vec![1.0, 2.5]));
+        assert_eq!(val.to_f32_vec(), Some(vec![1.0, 2.5]));
+        assert_eq!(format!("{}", val), "[1,2.5]");
+        assert_eq!(OracleValue::Null.to_f32_vec(), None);
+    }
+
+    #[test]
+    fn test_oracle_value_memory_size() {
+        let base = std::mem::size_of::<OracleValue>();
+        assert_eq!(OracleValue::Null.memory_size(), base);
+        assert_eq!(OracleValue::Boolean(true).memory_size(), base);
+        assert_eq!(
+            OracleValue::Raw(Vec::with_capacity(100)).memory_size(),
+            base + 100
+        );
+        let sparse = OracleValue::Vector(Vector::Sparse {
+            num_dimensions: 1000,
+            indices: vec![1, 500],
+            values: VectorData::Float32(vec![1.0, 2.0]),
+        });
+        assert_eq!(sparse.memory_size(), base + 16);
+    }
+
+    #[test]
+    fn test_oracle_value_intervals() {
+        let ym = OracleValue::IntervalYM {
+            years: -1,
+            months: -6,
+        };
+        assert_eq!(ym.as_interval_ym(), Some((-1, -6)));
+        assert_eq!(format!("{}", ym), "-01-06");
+
+        let ds = OracleValue::IntervalDS(
+            Duration::days(3) + Duration::seconds(3723) + Duration::microseconds(500),
+        );
+        assert_eq!(format!("{}", ds), "+03 01:02:03.000500");
+        assert!(ds.as_duration().is_some());
     }
 }
@@ -0,0 +1,197 @@
+//! VECTOR values (Oracle 23ai).
+
+use std::fmt;
+
+/// Element values of a vector, in their storage format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorData {
+    /// FLOAT32 elements.
+    Float32(Vec<f32>),
+    /// FLOAT64 elements.
+    Float64(Vec<f64>),
+    /// INT8 elements.
+    Int8(Vec<i8>),
+    /// BINARY elements, 8 dimensions packed per byte.
+    Binary(Vec<u8>),
+}
+
+impl VectorData {
+    /// Get the number of stored elements.
+    pub fn len(&self) -> usize {
+        match self {
+            VectorData::Float32(v) => v.len(),
+            VectorData::Float64(v) => v.len(),
+            VectorData::Int8(v) => v.len(),
+            VectorData::Binary(v) => v.len(),
+        }
+    }
+
+    /// Check if there are no stored elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the element at `index` as f64 (not available for BINARY).
+    fn get_f64(&self, index: usize) -> Option<f64> {
+        match self {
+            VectorData::Float32(v) => v.get(index).map(|&x| x as f64),
+            VectorData::Float64(v) => v.get(index).copied(),
+            VectorData::Int8(v) => v.get(index).map(|&x| x as f64),
+            VectorData::Binary(_) => None,
+        }
+    }
+}
+
+/// A VECTOR column value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vector {
+    /// Every dimension is stored.
+    Dense(VectorData),
+    /// Only non-zero dimensions are stored, with their indices.
+    Sparse {
+        /// Total number of dimensions.
+        num_dimensions: u32,
+        /// Zero-based indices of the stored values.
+        indices: Vec<u32>,
+        /// Values at `indices`.
+        values: VectorData,
+    },
+}
+
+impl Vector {
+    /// Check if this is a sparse vector.
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, Vector::Sparse { .. })
+    }
+
+    /// Get the number of dimensions.
+    pub fn num_dimensions(&self) -> usize {
+        match self {
+            Vector::Dense(VectorData::Binary(v)) => v.len() * 8,
+            Vector::Dense(data) => data.len(),
+            Vector::Sparse { num_dimensions, .. } => *num_dimensions as usize,
+        }
+    }
+
+    /// Get the stored element values.
+    pub fn values(&self) -> &VectorData {
+        match self {
+            Vector::Dense(data) => data,
+            Vector::Sparse { values, .. } => values,
+        }
+    }
+
+    /// Convert to dense f64 values.
+    ///
+    /// Sparse vectors are expanded with zeros. Returns `None` for BINARY vectors.
+    pub fn to_f64_vec(&self) -> Option<Vec<f64>> {
+        match self {
+            Vector::Dense(data) => (0..data.len()).map(|i| data.get_f64(i)).collect(),
+            Vector::Sparse {
+                num_dimensions,
+                indices,
+                values,
+            } => {
+                let mut dense = vec![0.0; *num_dimensions as usize];
+                for (i, &index) in indices.iter().enumerate() {
+                    let slot = dense.get_mut(index as usize)?;
+                    *slot = values.get_f64(i)?;
+                }
+                Some(dense)
+            }
+        }
+    }
+
+    /// Convert to dense f32 values.
+    ///
+    /// Sparse vectors are expanded with zeros. Returns `None` for BINARY vectors.
+    pub fn to_f32_vec(&self) -> Option<Vec<f32>> {
+        if let Vector::Dense(VectorData::Float32(v)) = self {
+            return Some(v.clone());
+        }
+        self.to_f64_vec()
+            .map(|v| v.into_iter().map(|x| x as f32).collect())
+    }
+}
+
+/// Write elements as a comma-separated list in brackets.
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+    write!(f, "[")?;
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    write!(f, "]")
+}
+
+impl fmt::Display for VectorData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VectorData::Float32(v) => write_list(f, v),
+            VectorData::Float64(v) => write_list(f, v),
+            VectorData::Int8(v) => write_list(f, v),
+            VectorData::Binary(v) => write_list(f, v),
+        }
+    }
+}
+
+impl fmt::Display for Vector {
+    /// Formats like `FROM_VECTOR()`: `[1,2,3]`, or `[dims,[indices],[values]]`
+    /// for sparse vectors.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Vector::Dense(data) => write!(f, "{}", data),
+            Vector::Sparse {
+                num_dimensions,
+                indices,
+                values,
+            } => {
+                write!(f, "[{},", num_dimensions)?;
+                write_list(f, indices)?;
+                write!(f, ",{}]", values)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_vector() {
+        let v = Vector::Dense(VectorData::Int8(vec![1, -2, 3]));
+        assert!(!v.is_sparse());
+        assert_eq!(v.num_dimensions(), 3);
+        assert_eq!(v.to_f64_vec(), Some(vec![1.0, -2.0, 3.0]));
+        assert_eq!(v.to_f32_vec(), Some(vec![1.0, -2.0, 3.0]));
+        assert_eq!(v.to_string(), "[1,-2,3]");
+
+        let v = Vector::Dense(VectorData::Binary(vec![0xF0, 0x0F]));
+        assert_eq!(v.num_dimensions(), 16);
+        assert_eq!(v.to_f64_vec(), None);
+    }
+
+    #[test]
+    fn test_sparse_vector() {
+        let v = Vector::Sparse {
+            num_dimensions: 5,
+            indices: vec![1, 4],
+            values: VectorData::Float32(vec![0.5, 2.0]),
+        };
+        assert!(v.is_sparse());
+        assert_eq!(v.num_dimensions(), 5);
+        assert_eq!(v.to_f32_vec(), Some(vec![0.0, 0.5, 0.0, 0.0, 2.0]));
+        assert_eq!(v.to_string(), "[5,[1,4],[0.5,2]]");
+
+        // Index beyond the dimension count
+        let v = Vector::Sparse {
+            num_dimensions: 2,
+            indices: vec![3],
+            values: VectorData::Float64(vec![1.0]),
+        };
+        assert_eq!(v.to_f64_vec(), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! Server version and banner from authentication session data.
+//!
+//! Servers report their version in the session parameters returned by
+//! authentication, but not always the same way: most send the encoded
+//! version number as `AUTH_VERSION_NO`, some send it as
+//! `AUTH_VERSION_STATUS` instead, and `AUTH_VERSION_STRING` holds either a
+//! status suffix ("- Production") or a full banner with a dotted version.
+
+use std::collections::HashMap;
+
+/// Server version as (major, minor, update, release, patch).
+pub type VersionTuple = (u8, u8, u8, u8, u8);
+
+/// Smallest encoded version number (major version 1).
+const MIN_VERSION_NUMBER: u32 = 1 << 24;
+
+/// Get the server version from the session parameters.
+///
+/// The encoded version number and any dotted version in the version string
+/// are both considered; the one with more known components wins, with the
+/// number preferred on ties.
+pub fn server_version(
+    params: &HashMap<String, String>,
+    ttc_field_version: u8,
+) -> Option<VersionTuple> {
+    let from_number = ["AUTH_VERSION_NO", "AUTH_VERSION_STATUS"]
+        .iter()
+        .filter_map(|key| params.get(*key))
+        .filter_map(|value| parse_version_number(value))
+        .find(|&number| number >= MIN_VERSION_NUMBER)
+        .map(|number| decode_version_number(number, ttc_field_version));
+    let from_string = params
+        .get("AUTH_VERSION_STRING")
+        .and_then(|s| parse_dotted_version(s));
+
+    match (from_number, from_string) {
+        (Some(number), Some(string)) if richness(string) > richness(number) => Some(string),
+        (Some(number), _) => Some(number),
+        (None, string) => string,
+    }
+}
+
+/// Get a human-readable server banner from the session parameters.
+///
+/// A version string that already contains a dotted version is returned as
+/// is; otherwise the banner is built from the version and the string.
+pub fn server_banner(params: &HashMap<String, String>, ttc_field_version: u8) -> Option<String> {
+    let string = params
+        .get("AUTH_VERSION_STRING")
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty());
+    if let Some(s) = string {
+        if parse_dotted_version(s).is_some() {
+            return Some(s.to_string());
+        }
+    }
+
+    let version = server_version(params, ttc_field_version)
+        .map(|v| format!("{}.{}.{}.{}.{}", v.0, v.1, v.2, v.3, v.4));
+    match (version, string) {
+        (Some(version), Some(s)) => Some(format!("{} {}", version, s)),
+        (Some(version), None) => Some(version),
+        (None, s) => s.map(str::to_string),
+    }
+}
+
+/// Parse an encoded version number, in decimal or `0x` hexadecimal.
+fn parse_version_number(value: &str) -> Option<u32> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Split an encoded version number into its components.
+fn decode_version_number(version: u32, ttc_field_version: u8) -> VersionTuple {
+    if ttc_field_version >= 11 {
+        // 18.1+ format
+        (
+            ((version >> 24) & 0xFF) as u8,
+            ((version >> 16) & 0xFF) as u8,
+            ((version >> 12) & 0x0F) as u8,
+            ((version >> 4) & 0xFF) as u8,
+            (version & 0x0F) as u8,
+        )
+    } else {
+        // Legacy format
+        (
+            ((version >> 24) & 0xFF) as u8,
+            ((version >> 20) & 0x0F) as u8,
+            ((version >> 12) & 0x0F) as u8,
+            ((version >> 8) & 0x0F) as u8,
+            (version & 0x0F) as u8,
+        )
+    }
+}
+
+/// Find the richest dotted version ("19.3.0.0.0") in a banner.
+///
+/// Full banners carry both the release ("Release 19.0.0.0.0") and the
+/// patched version ("Version 19.3.0.0.0"); the later one wins on ties.
+fn parse_dotted_version(banner: &str) -> Option<VersionTuple> {
+    banner
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter_map(|token| {
+            let parts: Vec<u8> = token
+                .trim_matches('.')
+                .split('.')
+                .map(|part| part.parse().ok())
+                .collect::<Option<_>>()?;
+            if !(2..=5).contains(&parts.len()) {
+                return None;
+            }
+            let part = |i: usize| parts.get(i).copied().unwrap_or(0);
+            Some((part(0), part(1), part(2), part(3), part(4)))
+        })
+        .fold(None, |best, version| match best {
+            Some(best) if richness(best) > richness(version) => Some(best),
+            _ => Some(version),
+        })
+}
+
+/// Number of components up to the last non-zero one.
+fn richness(version: VersionTuple) -> usize {
+    let parts = [version.0, version.1, version.2, version.3, version.4];
+    parts.iter().rposition(|&p| p != 0).map_or(0, |i| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_version_number() {
+        // 19.3.0.0.0 in the 18.1+ layout
+        let p = params(&[("AUTH_VERSION_NO", "318963712")]);
+        assert_eq!(server_version(&p, 13), Some((19, 3, 0, 0, 0)));
+        let p = params(&[("AUTH_VERSION_NO", "0x13030000")]);
+        assert_eq!(server_version(&p, 13), Some((19, 3, 0, 0, 0)));
+        // Legacy layout
+        let p = params(&[("AUTH_VERSION_NO", "186647552")]);
+        assert_eq!(server_version(&p, 8), Some((11, 2, 0, 4, 0)));
+    }
+
+    #[test]
+    fn test_version_fallbacks() {
+        let p = params(&[("AUTH_VERSION_STATUS", "0x17000000")]);
+        assert_eq!(server_version(&p, 13), Some((23, 0, 0, 0, 0)));
+
+        // A zero status is not a version number
+        let p = params(&[("AUTH_VERSION_STATUS", "0")]);
+        assert_eq!(server_version(&p, 13), None);
+
+        let p = params(&[
+            ("AUTH_VERSION_NO", "garbage"),
+            (
+                "AUTH_VERSION_STRING",
+                "Oracle Database 19c Enterprise Edition Release 19.0.0.0.0 - Production\nVersion 19.22.0.0.0",
+            ),
+        ]);
+        assert_eq!(server_version(&p, 13), Some((19, 22, 0, 0, 0)));
+        assert_eq!(server_version(&HashMap::new(), 13), None);
+    }
+
+    #[test]
+    fn test_version_prefers_richest() {
+        let p = params(&[
+            ("AUTH_VERSION_NO", "385875968"),
+            ("AUTH_VERSION_STRING", "Release 23.4.0.24.5"),
+        ]);
+        assert_eq!(server_version(&p, 24), Some((23, 4, 0, 24, 5)));
+
+        let p = params(&[
+            ("AUTH_VERSION_NO", "318963712"),
+            ("AUTH_VERSION_STRING", "Release 19.0.0.0.0"),
+        ]);
+        assert_eq!(server_version(&p, 13), Some((19, 3, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_banner() {
+        let p = params(&[
+            ("AUTH_VERSION_NO", "318963712"),
+            ("AUTH_VERSION_STRING", "- Production"),
+        ]);
+        assert_eq!(
+            server_banner(&p, 13).as_deref(),
+            Some("19.3.0.0.0 - Production")
+        );
+
+        let full =
+            "Oracle Database 23ai Free Release 23.0.0.0.0 - Develop, Learn, and Run for Free";
+        let p = params(&[
+            ("AUTH_VERSION_NO", "385875968"),
+            ("AUTH_VERSION_STRING", full),
+        ]);
+        assert_eq!(server_banner(&p, 24).as_deref(), Some(full));
+
+        let p = params(&[("AUTH_VERSION_STRING", "- Production")]);
+        assert_eq!(server_banner(&p, 13).as_deref(), Some("- Production"));
+        assert_eq!(server_banner(&HashMap::new(), 13), None);
+    }
+}
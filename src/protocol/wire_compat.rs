@@ -0,0 +1,582 @@
+//! Byte-level comparison of our requests against python-oracledb captures.
+//!
+//! python-oracledb prints every packet it sends and receives when run with
+//! `PYO_DEBUG_PACKETS=1`:
+//!
+//! ```text
+//! 2026-01-06 10:34:31.826 Sending packet [op 1] on socket 5
+//! 0000 : 00 00 01 0D 01 00 00 00 |........|
+//! ```
+//!
+//! A `Scenario` serializes the messages this client would send for the same
+//! steps, exactly as they go on the wire, and `Scenario::compare` lines them
+//! up with the recorded requests. Each run of differing bytes is reported
+//! with its offset and the packet or message field it falls in, which
+//! narrows down connect and execute incompatibilities on new server
+//! versions without a packet sniffer.
+//!
+//! Fields that differ by design (the random connection ID and client host
+//! name in the connect string, session keys, timestamps) show up too; judge
+//! the report with that in mind.
+
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+use crate::error::{Error, Result};
+use crate::protocol::constants::*;
+use crate::protocol::message::{DataMessage, Message};
+use crate::protocol::packet::{encode_data_message, encode_message, HEADER_SIZE};
+
+/// Direction of a captured packet, seen from the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent by the client.
+    Sent,
+    /// Received from the server.
+    Received,
+}
+
+/// A packet recorded by python-oracledb, header included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedPacket {
+    /// Whether the client sent or received the packet.
+    pub direction: Direction,
+    /// Packet bytes.
+    pub bytes: Vec<u8>,
+}
+
+/// Parse python-oracledb `PYO_DEBUG_PACKETS` output.
+///
+/// Lines other than packet headers and hex dump lines are ignored, so a
+/// whole program log can be passed in.
+pub fn parse_capture(text: &str) -> Result<Vec<CapturedPacket>> {
+    let mut packets = Vec::new();
+    let mut current: Option<CapturedPacket> = None;
+
+    for (line_num, line) in text.lines().enumerate() {
+        let direction = if line.contains("Sending packet") {
+            Some(Direction::Sent)
+        } else if line.contains("Receiving packet") {
+            Some(Direction::Received)
+        } else {
+            None
+        };
+        if let Some(direction) = direction {
+            packets.extend(current.take());
+            current = Some(CapturedPacket {
+                direction,
+                bytes: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some((offset, rest)) = line.split_once(" : ") else {
+            continue;
+        };
+        if offset.trim().is_empty() || !offset.trim().chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let Some(packet) = current.as_mut() else {
+            continue;
+        };
+        let hex = rest.split('|').next().unwrap_or_default();
+        for token in hex.split_whitespace() {
+            let byte = u8::from_str_radix(token, 16).map_err(|_| {
+                Error::protocol(format!(
+                    "Invalid hex byte {:?} on capture line {}",
+                    token,
+                    line_num + 1
+                ))
+            })?;
+            packet.bytes.push(byte);
+        }
+    }
+    packets.extend(current);
+    Ok(packets)
+}
+
+/// Format packets the way python-oracledb prints them, so that
+/// `parse_capture` reads them back.
+pub fn format_capture(packets: &[CapturedPacket]) -> String {
+    let mut out = String::new();
+    for (op, packet) in packets.iter().enumerate() {
+        let verb = match packet.direction {
+            Direction::Sent => "Sending",
+            Direction::Received => "Receiving",
+        };
+        let _ = writeln!(out, "{} packet [op {}] on socket 0", verb, op + 1);
+        for (n, chunk) in packet.bytes.chunks(8).enumerate() {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02X}", b)).collect();
+            let text: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+                .collect();
+            let _ = writeln!(out, "{:04} : {:<24}|{}|", n * 8, hex.join(" "), text);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The packets this client sends for a scripted sequence of messages.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::protocol::constants::TNS_SDU_DEFAULT;
+/// use oracle_thin_rs::protocol::wire_compat::{parse_capture, Scenario};
+/// use oracle_thin_rs::protocol::ProtocolMessage;
+///
+/// let mut scenario = Scenario::new(TNS_SDU_DEFAULT);
+/// scenario.set_large_sdu(true);
+/// scenario.data_message(&ProtocolMessage::default()).unwrap();
+///
+/// let capture = parse_capture(
+///     "Sending packet [op 1] on socket 5\n\
+///      0000 : 00 00 00 1C 06 00 00 00 |........|\n\
+///      0008 : 00 00 01 06 00 6F 72 61 |.....ora|\n\
+///      0016 : 63 6C 65 2D 74 68 69 6E |cle-thin|\n\
+///      0024 : 2D 72 73 00             |-rs.|\n",
+/// )
+/// .unwrap();
+/// assert!(scenario.compare(&capture).is_identical());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    sdu: u32,
+    use_large_sdu: bool,
+    packets: Vec<Vec<u8>>,
+}
+
+impl Scenario {
+    /// Start an empty scenario with the given SDU and 2-byte packet lengths,
+    /// as used until the server accepts the connection.
+    pub fn new(sdu: u32) -> Self {
+        Self {
+            sdu,
+            use_large_sdu: false,
+            packets: Vec::new(),
+        }
+    }
+
+    /// Switch to 4-byte packet lengths, as after an ACCEPT with protocol
+    /// version 315 or later.
+    pub fn set_large_sdu(&mut self, use_large_sdu: bool) {
+        self.use_large_sdu = use_large_sdu;
+    }
+
+    /// Add a message sent as a packet of its own type (CONNECT, MARKER).
+    pub fn message<M: Message>(&mut self, packet_type: u8, msg: &M) -> Result<&mut Self> {
+        let bytes = encode_message(packet_type, 0, msg, self.use_large_sdu)?;
+        self.packets.push(bytes);
+        Ok(self)
+    }
+
+    /// Add a DATA message, split into packets as it would be sent.
+    pub fn data_message<M: DataMessage>(&mut self, msg: &M) -> Result<&mut Self> {
+        let bytes = encode_data_message(msg, self.sdu as usize, self.use_large_sdu)?;
+        let mut rest = bytes.as_slice();
+        while !rest.is_empty() {
+            let len = packet_length(rest, self.use_large_sdu)
+                .unwrap_or(rest.len())
+                .clamp(HEADER_SIZE, rest.len());
+            let (packet, tail) = rest.split_at(len);
+            self.packets.push(packet.to_vec());
+            rest = tail;
+        }
+        Ok(self)
+    }
+
+    /// Get the serialized packets, headers included.
+    pub fn packets(&self) -> &[Vec<u8>] {
+        &self.packets
+    }
+
+    /// Compare the scenario's packets with the packets sent in a capture,
+    /// in order.
+    pub fn compare(&self, capture: &[CapturedPacket]) -> Report {
+        let theirs: Vec<&[u8]> = capture
+            .iter()
+            .filter(|p| p.direction == Direction::Sent)
+            .map(|p| p.bytes.as_slice())
+            .collect();
+
+        let mut divergences = Vec::new();
+        let mut large_sdu = false;
+        for (index, (ours, theirs)) in self.packets.iter().zip(&theirs).enumerate() {
+            // Only the CONNECT packet is framed before the SDU is negotiated
+            if ours.get(4) != Some(&TNS_PACKET_TYPE_CONNECT) {
+                large_sdu = self.use_large_sdu;
+            }
+            divergences.extend(diff_packet(index, ours, theirs, large_sdu));
+        }
+
+        Report {
+            divergences,
+            our_packets: self.packets.len(),
+            their_packets: theirs.len(),
+        }
+    }
+}
+
+/// A run of bytes that differs between our packet and the captured one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the packet among the sent packets.
+    pub packet: usize,
+    /// Offset of the first differing byte in the packet.
+    pub offset: usize,
+    /// Field the bytes belong to.
+    pub field: &'static str,
+    /// Our bytes (shorter, or empty, if our packet ends first).
+    pub ours: Vec<u8>,
+    /// The captured bytes.
+    pub theirs: Vec<u8>,
+}
+
+/// Result of comparing a scenario with a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Differing byte runs, in packet and offset order.
+    pub divergences: Vec<Divergence>,
+    /// Number of packets in the scenario.
+    pub our_packets: usize,
+    /// Number of sent packets in the capture.
+    pub their_packets: usize,
+}
+
+impl Report {
+    /// Check whether every packet matched byte for byte.
+    pub fn is_identical(&self) -> bool {
+        self.divergences.is_empty() && self.our_packets == self.their_packets
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "{} packets identical", self.our_packets);
+        }
+        if self.our_packets != self.their_packets {
+            writeln!(
+                f,
+                "packet count differs: ours {}, python {}",
+                self.our_packets, self.their_packets
+            )?;
+        }
+        let mut packet = None;
+        for d in &self.divergences {
+            if packet != Some(d.packet) {
+                writeln!(f, "packet {}:", d.packet + 1)?;
+                packet = Some(d.packet);
+            }
+            writeln!(
+                f,
+                "  offset {:5} (0x{:04x}) {}: ours [{}], python [{}]",
+                d.offset,
+                d.offset,
+                d.field,
+                hex(&d.ours),
+                hex(&d.theirs)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare one packet, grouping differing bytes into runs within a field.
+fn diff_packet(index: usize, ours: &[u8], theirs: &[u8], large_sdu: bool) -> Vec<Divergence> {
+    // Fields are named from our packet, or the captured one past our end
+    let fields = |offset: usize| {
+        let packet = if offset < ours.len() { ours } else { theirs };
+        field_at(packet, offset, large_sdu)
+    };
+
+    let mut divergences: Vec<Divergence> = Vec::new();
+    let mut last: Option<(usize, Range<usize>)> = None;
+    for offset in 0..ours.len().max(theirs.len()) {
+        if ours.get(offset) == theirs.get(offset) {
+            continue;
+        }
+        let (field, range) = fields(offset);
+        let extends = matches!(&last, Some((end, r)) if *end == offset && *r == range);
+        match divergences.last_mut() {
+            Some(d) if extends => {
+                d.ours.extend(ours.get(offset));
+                d.theirs.extend(theirs.get(offset));
+            }
+            _ => divergences.push(Divergence {
+                packet: index,
+                offset,
+                field,
+                ours: ours.get(offset).copied().into_iter().collect(),
+                theirs: theirs.get(offset).copied().into_iter().collect(),
+            }),
+        }
+        last = Some((offset + 1, range));
+    }
+    divergences
+}
+
+/// Name the field at `offset` in a packet, with the field's byte range.
+fn field_at(packet: &[u8], offset: usize, large_sdu: bool) -> (&'static str, Range<usize>) {
+    let header: &[(usize, &str)] = if large_sdu {
+        &[
+            (0, "packet length"),
+            (4, "packet type"),
+            (5, "packet flags"),
+        ]
+    } else {
+        &[
+            (0, "packet length"),
+            (2, "packet checksum"),
+            (4, "packet type"),
+            (5, "packet flags"),
+        ]
+    };
+    if offset < HEADER_SIZE {
+        return lookup(header, offset, 6, "header checksum");
+    }
+
+    let payload_offset = offset - HEADER_SIZE;
+    let (name, range) = match packet.get(4).copied() {
+        Some(TNS_PACKET_TYPE_CONNECT) => lookup(
+            &CONNECT_FIELDS,
+            payload_offset,
+            CONNECT_DATA_OFFSET,
+            "connect data",
+        ),
+        Some(TNS_PACKET_TYPE_DATA) => {
+            let function = packet.get(HEADER_SIZE + 2) == Some(&TNS_MSG_TYPE_FUNCTION);
+            let fields: &[(usize, &str)] = if function {
+                &[
+                    (0, "data flags"),
+                    (2, "message type"),
+                    (3, "function code"),
+                    (4, "sequence number"),
+                ]
+            } else {
+                &[(0, "data flags"), (2, "message type")]
+            };
+            let body_start = if function { 5 } else { 3 };
+            lookup(fields, payload_offset, body_start, "message body")
+        }
+        _ => ("payload", 0..usize::MAX),
+    };
+    (
+        name,
+        range.start + HEADER_SIZE..range.end.saturating_add(HEADER_SIZE),
+    )
+}
+
+/// Find the field containing `offset` in a list of (start, name) pairs,
+/// with everything from `rest_start` on named `rest`.
+fn lookup(
+    fields: &[(usize, &'static str)],
+    offset: usize,
+    rest_start: usize,
+    rest: &'static str,
+) -> (&'static str, Range<usize>) {
+    if offset >= rest_start {
+        return (rest, rest_start..usize::MAX);
+    }
+    let index = fields
+        .iter()
+        .rposition(|&(start, _)| start <= offset)
+        .unwrap_or(0);
+    let (start, name) = fields[index];
+    let end = fields.get(index + 1).map_or(rest_start, |&(next, _)| next);
+    (name, start..end)
+}
+
+/// CONNECT packet fields, by offset in the payload.
+const CONNECT_FIELDS: [(usize, &str); 19] = [
+    (0, "version desired"),
+    (2, "version minimum"),
+    (4, "service options"),
+    (6, "SDU"),
+    (8, "TDU"),
+    (10, "protocol characteristics"),
+    (12, "line turnaround"),
+    (14, "value of one"),
+    (16, "connect data length"),
+    (18, "connect data offset"),
+    (20, "max receivable data"),
+    (24, "NSI flags 1"),
+    (25, "NSI flags 2"),
+    (26, "obsolete fields"),
+    (50, "large SDU"),
+    (54, "large TDU"),
+    (58, "connect flags 1"),
+    (62, "connect flags 2"),
+    (66, "connect data"),
+];
+
+/// Offset of the connect string in the CONNECT payload.
+const CONNECT_DATA_OFFSET: usize = 66;
+
+/// Read the length from a packet header.
+pub(crate) fn packet_length(packet: &[u8], large_sdu: bool) -> Option<usize> {
+    if large_sdu {
+        let bytes: [u8; 4] = packet.get(..4)?.try_into().ok()?;
+        Some(u32::from_be_bytes(bytes) as usize)
+    } else {
+        let bytes: [u8; 2] = packet.get(..2)?.try_into().ok()?;
+        Some(u16::from_be_bytes(bytes) as usize)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::messages::{ConnectMessage, MarkerMessage, ProtocolMessage};
+
+    /// Format packets the way python-oracledb prints them.
+    fn python_dump(packets: &[Vec<u8>]) -> String {
+        let mut out = String::from("Connecting...\n");
+        for (i, packet) in packets.iter().enumerate() {
+            out.push_str(&format!(
+                "2026-01-06 10:34:31.826 Sending packet [op {}] on socket 5\n",
+                i + 1
+            ));
+            for (n, chunk) in packet.chunks(8).enumerate() {
+                let hex: Vec<String> = chunk.iter().map(|b| format!("{:02X}", b)).collect();
+                out.push_str(&format!("{:04} : {:<24}|........|\n", n * 8, hex.join(" ")));
+            }
+            out.push_str("2026-01-06 10:34:31.900 Receiving packet [op 1] on socket 5\n");
+            out.push_str("0000 : 00 00 00 0B 0C 00 00 00 |........|\n\n");
+        }
+        out
+    }
+
+    fn scenario(connect_string: &[u8]) -> Scenario {
+        let mut scenario = Scenario::new(TNS_SDU_DEFAULT);
+        scenario
+            .message(
+                TNS_PACKET_TYPE_CONNECT,
+                &ConnectMessage {
+                    connect_string,
+                    sdu: TNS_SDU_DEFAULT,
+                    oob: false,
+                },
+            )
+            .unwrap();
+        scenario.set_large_sdu(true);
+        scenario
+            .data_message(&ProtocolMessage::default())
+            .unwrap()
+            .message(TNS_PACKET_TYPE_MARKER, &MarkerMessage::reset())
+            .unwrap();
+        scenario
+    }
+
+    #[test]
+    fn test_parse_capture() {
+        let packets = parse_capture(&python_dump(&[vec![1, 2, 3], vec![0xAB; 10]])).unwrap();
+        assert_eq!(packets.len(), 4);
+        assert_eq!(packets[0].direction, Direction::Sent);
+        assert_eq!(packets[0].bytes, [1, 2, 3]);
+        assert_eq!(packets[1].direction, Direction::Received);
+        assert_eq!(packets[1].bytes, [0, 0, 0, 0x0B, 0x0C, 0, 0, 0]);
+        assert_eq!(packets[2].bytes, [0xAB; 10]);
+
+        assert!(parse_capture("Sending packet\n0000 : 0G |.|\n").is_err());
+        assert!(parse_capture("no packets here\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_capture() {
+        let packets = vec![
+            CapturedPacket {
+                direction: Direction::Sent,
+                bytes: b"|abc| 0123456789".to_vec(),
+            },
+            CapturedPacket {
+                direction: Direction::Received,
+                bytes: vec![0, 0, 0, 0x0B, 0x0C, 0, 0, 0, 1, 2, 3],
+            },
+        ];
+        let text = format_capture(&packets);
+        assert!(text.starts_with("Sending packet [op 1] on socket 0\n0000 : 7C 61 62 63"));
+        assert_eq!(parse_capture(&text).unwrap(), packets);
+    }
+
+    #[test]
+    fn test_compare_identical() {
+        let ours = scenario(b"(DESCRIPTION=(ADDRESS=(HOST=db)))");
+        assert_eq!(ours.packets().len(), 3);
+        let capture = parse_capture(&python_dump(ours.packets())).unwrap();
+        let report = ours.compare(&capture);
+        assert!(report.is_identical(), "{}", report);
+        assert_eq!(report.to_string(), "3 packets identical\n");
+    }
+
+    #[test]
+    fn test_compare_names_fields() {
+        let ours = scenario(b"(DESCRIPTION=(ADDRESS=(HOST=db)))");
+        let mut theirs = ours.packets().to_vec();
+        // CONNECT: older version desired, and a different connect string
+        theirs[0][HEADER_SIZE + 1] ^= 1;
+        theirs[0][HEADER_SIZE + CONNECT_DATA_OFFSET + 28] = b'X';
+        // Protocol message: flipped data flags and message type bytes
+        theirs[1][HEADER_SIZE + 1] = 0x40;
+        theirs[1][HEADER_SIZE + 2] = 0x07;
+        // Python sends an extra packet
+        theirs.push(vec![0; 8]);
+
+        let report = ours.compare(&parse_capture(&python_dump(&theirs)).unwrap());
+        assert!(!report.is_identical());
+        let fields: Vec<(usize, usize, &str)> = report
+            .divergences
+            .iter()
+            .map(|d| (d.packet, d.offset, d.field))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                (0, 9, "version desired"),
+                (0, 102, "connect data"),
+                (1, 9, "data flags"),
+                (1, 10, "message type"),
+            ]
+        );
+        let text = report.to_string();
+        assert!(text.starts_with("packet count differs: ours 3, python 4\n"));
+        assert!(text.contains("offset     9 (0x0009) version desired: ours [3f], python [3e]"));
+    }
+
+    #[test]
+    fn test_compare_length_mismatch() {
+        let ours = scenario(b"(DESCRIPTION=)");
+        let mut theirs = ours.packets().to_vec();
+        theirs[1].extend_from_slice(&[0xEE, 0xFF]);
+
+        let report = ours.compare(&parse_capture(&python_dump(&theirs)).unwrap());
+        let d = &report.divergences[0];
+        assert_eq!((d.packet, d.field), (1, "message body"));
+        assert!(d.ours.is_empty());
+        assert_eq!(d.theirs, [0xEE, 0xFF]);
+    }
+
+    #[test]
+    fn test_data_message_split() {
+        let driver_name = vec![b'x'; 100];
+        let mut scenario = Scenario::new(64);
+        scenario.set_large_sdu(true);
+        scenario
+            .data_message(&ProtocolMessage {
+                driver_name: &driver_name,
+            })
+            .unwrap();
+        assert_eq!(scenario.packets().len(), 2);
+        assert!(scenario.packets().iter().all(|p| p.len() <= 64));
+    }
+}
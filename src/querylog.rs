@@ -0,0 +1,243 @@
+//! Logging of the statements a connection runs.
+//!
+//! A `QueryLogger` set with `Connection::set_query_logger` gets a
+//! `QueryLog` entry for every statement the connection runs: the SQL, how
+//! long the call took, the rows it returned or affected and the ORA- code
+//! it failed with. The SQL has its literals replaced with `?` and the bind
+//! values are left out, unless the logger asks for them, so entries can be
+//! sent to log collectors that must not see application data.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::Error;
+use crate::protocol::statement::{redact_literals, Statement, StatementType};
+use crate::protocol::types::OracleValue;
+
+/// A statement run by a connection, as reported to a `QueryLogger`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryLog {
+    /// SQL text, with literals replaced by `?` unless the logger turned
+    /// redaction off.
+    pub sql: String,
+    /// Statement type.
+    pub statement_type: StatementType,
+    /// Time taken by the call, from sending the request to reading the
+    /// response. The operations of a pipeline sent in one round trip each
+    /// report the time of the whole round trip.
+    pub duration: Duration,
+    /// Rows fetched by a query with its execution (later fetches from a
+    /// cursor are not counted), or rows affected by DML.
+    pub rows: u64,
+    /// Number of rows of bind values (one per execution of the statement).
+    pub bind_rows: usize,
+    /// Bind values, one row per execution; empty unless redaction is off.
+    pub binds: Vec<Vec<OracleValue>>,
+    /// Whether the call failed.
+    pub failed: bool,
+    /// ORA- code of the error the call failed with. Calls failing for
+    /// other reasons, such as a lost connection, have no code.
+    pub error_code: Option<u32>,
+}
+
+/// Receiver of `QueryLog` entries.
+///
+/// Loggers are called on the task running the statement, after its
+/// response is read; slow work, such as network I/O, should be handed off
+/// to another task.
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::{QueryLog, QueryLogger};
+/// use std::time::Duration;
+///
+/// /// Report slow and failing statements.
+/// struct SlowQueries;
+///
+/// impl QueryLogger for SlowQueries {
+///     fn log(&self, entry: &QueryLog) {
+///         if let Some(code) = entry.error_code {
+///             eprintln!("ORA-{:05} from {}", code, entry.sql);
+///         } else if entry.duration > Duration::from_secs(1) {
+///             eprintln!("{:?} for {} ({} rows)", entry.duration, entry.sql, entry.rows);
+///         }
+///     }
+/// }
+/// ```
+pub trait QueryLogger: Send + Sync {
+    /// Record a statement.
+    fn log(&self, entry: &QueryLog);
+
+    /// Whether to replace the literals of the SQL with `?` and leave out
+    /// bind values (the default). Return `false` to get the SQL as sent,
+    /// and the bind values, for example when debugging against test data.
+    fn redact(&self) -> bool {
+        true
+    }
+}
+
+/// Logger reporting each statement as a `tracing` event.
+///
+/// Events have target `oracle_thin_rs::query`, level `INFO` (`WARN` for
+/// failed calls) and the fields of `QueryLog`, with the duration in
+/// microseconds. Bind values are never included.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingQueryLogger;
+
+#[cfg(feature = "tracing")]
+impl QueryLogger for TracingQueryLogger {
+    fn log(&self, entry: &QueryLog) {
+        let duration_us = entry.duration.as_micros() as u64;
+        if entry.failed {
+            tracing::warn!(
+                target: "oracle_thin_rs::query",
+                sql = %entry.sql,
+                statement_type = ?entry.statement_type,
+                duration_us,
+                rows = entry.rows,
+                bind_rows = entry.bind_rows,
+                error_code = entry.error_code,
+                "statement failed"
+            );
+        } else {
+            tracing::info!(
+                target: "oracle_thin_rs::query",
+                sql = %entry.sql,
+                statement_type = ?entry.statement_type,
+                duration_us,
+                rows = entry.rows,
+                bind_rows = entry.bind_rows,
+                "statement completed"
+            );
+        }
+    }
+}
+
+/// Report a statement to the logger if there is one.
+///
+/// `outcome` is the number of rows the call returned or affected, or the
+/// error it failed with.
+pub(crate) fn log_statement(
+    logger: Option<&Arc<dyn QueryLogger>>,
+    statement: &Statement,
+    binds: &[Vec<OracleValue>],
+    duration: Duration,
+    outcome: std::result::Result<u64, &Error>,
+) {
+    let Some(logger) = logger else {
+        return;
+    };
+    let redact = logger.redact();
+    let binds: Vec<Vec<OracleValue>> = binds
+        .iter()
+        .filter(|row| !row.is_empty())
+        .cloned()
+        .collect();
+    let entry = QueryLog {
+        sql: if redact {
+            redact_literals(statement.sql())
+        } else {
+            statement.sql().to_string()
+        },
+        statement_type: statement.statement_type(),
+        duration,
+        rows: *outcome.as_ref().unwrap_or(&0),
+        bind_rows: binds.len(),
+        binds: if redact { Vec::new() } else { binds },
+        failed: outcome.is_err(),
+        error_code: outcome.err().and_then(Error::code),
+    };
+    logger.log(&entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Keeps every entry, redacted or not.
+    struct Collector {
+        redact: bool,
+        entries: Mutex<Vec<QueryLog>>,
+    }
+
+    impl QueryLogger for Collector {
+        fn log(&self, entry: &QueryLog) {
+            self.entries.lock().unwrap().push(entry.clone());
+        }
+
+        fn redact(&self) -> bool {
+            self.redact
+        }
+    }
+
+    fn collector(redact: bool) -> Arc<Collector> {
+        Arc::new(Collector {
+            redact,
+            entries: Mutex::new(Vec::new()),
+        })
+    }
+
+    #[test]
+    fn test_log_statement_redacted() {
+        let collector = collector(true);
+        let logger: Arc<dyn QueryLogger> = collector.clone();
+        let stmt = Statement::parse("UPDATE t SET name = 'x' WHERE id = :id AND n > 10");
+        let binds = [vec![OracleValue::Integer(7)]];
+
+        log_statement(
+            Some(&logger),
+            &stmt,
+            &binds,
+            Duration::from_millis(3),
+            Ok(2),
+        );
+        let error = Error::oracle(1, "ORA-00001: unique constraint violated");
+        log_statement(Some(&logger), &stmt, &[], Duration::ZERO, Err(&error));
+        log_statement(
+            Some(&logger),
+            &stmt,
+            &[],
+            Duration::ZERO,
+            Err(&Error::ConnectionClosed),
+        );
+        log_statement(None, &stmt, &binds, Duration::ZERO, Ok(1));
+
+        let entries = collector.entries.lock().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries[0],
+            QueryLog {
+                sql: "UPDATE t SET name = ? WHERE id = :id AND n > ?".to_string(),
+                statement_type: StatementType::Dml,
+                duration: Duration::from_millis(3),
+                rows: 2,
+                bind_rows: 1,
+                binds: Vec::new(),
+                failed: false,
+                error_code: None,
+            }
+        );
+        assert!(entries[1].failed);
+        assert_eq!(entries[1].error_code, Some(1));
+        assert_eq!(entries[1].bind_rows, 0);
+        assert!(entries[2].failed);
+        assert_eq!(entries[2].error_code, None);
+    }
+
+    #[test]
+    fn test_log_statement_unredacted() {
+        let collector = collector(false);
+        let logger: Arc<dyn QueryLogger> = collector.clone();
+        let stmt = Statement::parse("SELECT * FROM t WHERE name = 'x'");
+        let binds = [vec![OracleValue::String("a".to_string())]];
+
+        log_statement(Some(&logger), &stmt, &binds, Duration::ZERO, Ok(5));
+        let entries = collector.entries.lock().unwrap();
+        assert_eq!(entries[0].sql, "SELECT * FROM t WHERE name = 'x'");
+        assert_eq!(entries[0].binds, binds);
+        assert_eq!(entries[0].rows, 5);
+    }
+}
@@ -0,0 +1,639 @@
+//! Connection wrapper that re-establishes lost sessions.
+//!
+//! `ReconnectingConnection` keeps the parameters and credentials used to
+//! connect, plus a list of session-init statements (current schema, NLS
+//! settings, module). When an operation fails because the session is lost,
+//! the next operation first reconnects according to a `ReconnectPolicy` and
+//! replays the init statements.
+//!
+//! By default the failed operation itself is not retried. Policies with
+//! `with_retries` re-run idempotent operations (queries and pings) on the new
+//! session instead of returning the error, and policies with `with_replay`
+//! replay a statement that was in flight when it is safe to (see
+//! `ReconnectingConnection::execute`). Anything scoped to the old session
+//! (an open transaction, cursors, package state) is gone either way, so every
+//! reconnect is recorded as a `ReconnectEvent` the caller can check.
+
+use std::time::Duration;
+
+use crate::connection::{Connection, ExecuteResult, QueryResult};
+use crate::error::{Error, Result};
+use crate::protocol::connect::ConnectParams;
+use crate::protocol::statement::Statement;
+use crate::protocol::types::{OracleValue, Row};
+
+/// Application error raised by `LTXID_OUTCOME_SQL` when the call committed.
+const LTXID_COMMITTED: u32 = 20001;
+
+/// Ask Transaction Guard whether the call with LTXID `:1` committed, raising
+/// `LTXID_COMMITTED` if it did.
+///
+/// Asking also blocks a call that has not committed from committing later,
+/// so it can be replayed.
+const LTXID_OUTCOME_SQL: &str = "DECLARE committed BOOLEAN; completed BOOLEAN; BEGIN \
+    DBMS_APP_CONT.GET_LTXID_OUTCOME(:1, committed, completed); \
+    IF committed THEN RAISE_APPLICATION_ERROR(-20001, 'call committed'); END IF; END;";
+
+/// When and how often to try re-establishing a lost session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Connect attempts per reconnect (0 disables reconnecting).
+    pub max_attempts: u32,
+    /// Delay before the second attempt, doubled for each further attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_backoff: Duration,
+    /// Idle time after which the session is pinged before the next
+    /// operation, so a dropped connection is re-established up front.
+    pub idle_check: Option<Duration>,
+    /// Times an idempotent operation is retried after losing the session.
+    pub max_retries: u32,
+    /// Times a statement in flight is replayed after losing the session.
+    pub max_replays: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            idle_check: None,
+            max_retries: 0,
+            max_replays: 0,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Create the default policy (3 attempts, 100ms backoff up to 5s).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a policy that never reconnects.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Set the number of connect attempts per reconnect.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the second attempt and its upper bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ReconnectPolicy;
+    /// use std::time::Duration;
+    ///
+    /// let policy = ReconnectPolicy::new()
+    ///     .with_max_attempts(5)
+    ///     .with_backoff(Duration::from_millis(50), Duration::from_secs(2));
+    /// assert_eq!(policy.backoff(3), Duration::from_millis(200));
+    /// ```
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Ping the session before an operation once it has been idle for
+    /// `max_idle`.
+    ///
+    /// A connection dropped while idle (for example by a firewall) is then
+    /// re-established before the operation instead of failing it.
+    pub fn with_idle_check(mut self, max_idle: Duration) -> Self {
+        self.idle_check = Some(max_idle);
+        self
+    }
+
+    /// Retry idempotent operations up to `max_retries` times when they fail
+    /// because the session was lost.
+    ///
+    /// Only queries and pings are retried, each on a newly established
+    /// session. Statements that may change data are never retried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ReconnectPolicy;
+    ///
+    /// let policy = ReconnectPolicy::new().with_retries(2);
+    /// assert_eq!(policy.max_retries, 2);
+    /// ```
+    pub fn with_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Replay a statement in flight up to `max_replays` times when the
+    /// session is lost, if that cannot run it twice.
+    ///
+    /// See `ReconnectingConnection::execute` for when a statement is
+    /// replayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use oracle_thin_rs::ReconnectPolicy;
+    ///
+    /// let policy = ReconnectPolicy::new().with_replay(1);
+    /// assert_eq!(policy.max_replays, 1);
+    /// ```
+    pub fn with_replay(mut self, max_replays: u32) -> Self {
+        self.max_replays = max_replays;
+        self
+    }
+
+    /// Delay to wait after the given failed attempt (1-based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Record of a session that was lost and re-established.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectEvent {
+    /// Error that lost the previous session.
+    pub cause: String,
+    /// Connect attempts made, including the successful one.
+    pub attempts: u32,
+    /// Number of reconnects made by the wrapper so far, including this one.
+    pub reconnect_count: u64,
+    /// Whether the statement in flight was replayed on the new session.
+    pub replayed: bool,
+    /// Whether the statement in flight committed, if Transaction Guard
+    /// was asked.
+    pub committed: Option<bool>,
+}
+
+/// A connection that reconnects and replays session state after the session
+/// is lost.
+///
+/// # Example
+///
+/// ```no_run
+/// use oracle_thin_rs::ReconnectingConnection;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut conn = ReconnectingConnection::connect(
+///         "localhost:1521/FREEPDB1",
+///         "read_user",
+///         "password"
+///     ).await?;
+///     conn.add_init_statement("ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY-MM-DD'").await?;
+///
+///     let rows = conn.query("SELECT TO_CHAR(SYSDATE) FROM DUAL").await?;
+///     if let Some(event) = conn.take_reconnect_event() {
+///         println!("session was re-established after: {}", event.cause);
+///     }
+///     println!("{:?}", rows.rows);
+///     Ok(())
+/// }
+/// ```
+pub struct ReconnectingConnection {
+    /// The current session.
+    conn: Connection,
+    /// Parameters used for every (re)connect.
+    params: ConnectParams,
+    /// Database username.
+    username: String,
+    /// Database password.
+    password: String,
+    /// Reconnect policy.
+    policy: ReconnectPolicy,
+    /// Statements replayed on every new session, in registration order.
+    init_statements: Vec<String>,
+    /// Cause of a lost session not yet re-established.
+    lost: Option<String>,
+    /// Number of successful reconnects.
+    reconnects: u64,
+    /// Most recent reconnect not yet taken by the caller.
+    event: Option<ReconnectEvent>,
+}
+
+impl ReconnectingConnection {
+    /// Connect with the default reconnect policy.
+    ///
+    /// `conn_str` has the same format as for `Connection::connect`.
+    pub async fn connect(conn_str: &str, username: &str, password: &str) -> Result<Self> {
+        let params = ConnectParams::parse(conn_str)?;
+        Self::connect_with_params(params, username, password, ReconnectPolicy::default()).await
+    }
+
+    /// Connect with explicit connection parameters and reconnect policy.
+    pub async fn connect_with_params(
+        params: ConnectParams,
+        username: &str,
+        password: &str,
+        policy: ReconnectPolicy,
+    ) -> Result<Self> {
+        let conn = Connection::connect_with_params(&params, username, password).await?;
+        Ok(Self {
+            conn,
+            params,
+            username: username.to_string(),
+            password: password.to_string(),
+            policy,
+            init_statements: Vec::new(),
+            lost: None,
+            reconnects: 0,
+            event: None,
+        })
+    }
+
+    /// Run a session-init statement now and replay it on every new session.
+    ///
+    /// Typical statements set the current schema, NLS parameters or the
+    /// module name. A statement that fails now is not registered.
+    pub async fn add_init_statement(&mut self, sql: &str) -> Result<()> {
+        self.ensure_connected().await?;
+        let result = self.conn.execute_query(sql, 0).await;
+        self.observe(result)?;
+        self.init_statements.push(sql.to_string());
+        Ok(())
+    }
+
+    /// Get the registered session-init statements.
+    pub fn init_statements(&self) -> &[String] {
+        &self.init_statements
+    }
+
+    /// Get the reconnect policy.
+    pub fn policy(&self) -> &ReconnectPolicy {
+        &self.policy
+    }
+
+    /// Set the reconnect policy.
+    pub fn set_policy(&mut self, policy: ReconnectPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get the number of times the session has been re-established.
+    pub fn reconnect_count(&self) -> u64 {
+        self.reconnects
+    }
+
+    /// Take the most recent reconnect event, if one happened since the last call.
+    ///
+    /// Callers should treat an event as the loss of any transaction or
+    /// session state they did not register as an init statement.
+    pub fn take_reconnect_event(&mut self) -> Option<ReconnectEvent> {
+        self.event.take()
+    }
+
+    /// Get the autocommit mode of the session.
+    pub fn autocommit(&self) -> bool {
+        self.conn.autocommit()
+    }
+
+    /// Set the autocommit mode, which is kept across reconnects.
+    pub fn set_autocommit(&mut self, autocommit: bool) {
+        self.conn.set_autocommit(autocommit);
+    }
+
+    /// Execute a SELECT query, reconnecting first if the session was lost.
+    ///
+    /// Retried on a new session if the policy allows it. See
+    /// `Connection::query`.
+    pub async fn query(&mut self, sql: &str) -> Result<QueryResult> {
+        let mut retries = 0;
+        loop {
+            self.ensure_connected().await?;
+            let result = self.conn.query(sql).await;
+            let result = self.observe(result);
+            if !self.should_retry(&result, &mut retries) {
+                return result;
+            }
+        }
+    }
+
+    /// Execute a query and return its first row, reconnecting first if the
+    /// session was lost.
+    ///
+    /// Retried on a new session if the policy allows it. See
+    /// `Connection::query_one`.
+    pub async fn query_one(&mut self, sql: &str) -> Result<Option<Row>> {
+        let mut retries = 0;
+        loop {
+            self.ensure_connected().await?;
+            let result = self.conn.query_one(sql).await;
+            let result = self.observe(result);
+            if !self.should_retry(&result, &mut retries) {
+                return result;
+            }
+        }
+    }
+
+    /// Execute a statement, reconnecting first if the session was lost.
+    ///
+    /// A statement in flight when the session is lost is replayed on a new
+    /// session if the policy allows it (`with_replay`) and replaying cannot
+    /// run it twice:
+    ///
+    /// - If work was left uncommitted before the statement, that work is
+    ///   lost, so the statement's error is returned.
+    /// - Queries, and DML without autocommit, are replayed: the lost session
+    ///   rolled back anything they did.
+    /// - Other statements (DML with autocommit, DDL, PL/SQL) may have
+    ///   committed. With Transaction Guard (services with `COMMIT_OUTCOME`
+    ///   enabled, and `EXECUTE` on `DBMS_APP_CONT`) the new session asks
+    ///   whether the statement committed, and replays it only if it did
+    ///   not. Otherwise the error is returned.
+    ///
+    /// The reconnect event records whether the statement was replayed and
+    /// any outcome Transaction Guard reported. See `Connection::execute`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use oracle_thin_rs::{ConnectParams, ReconnectPolicy, ReconnectingConnection, ToSql};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let params = ConnectParams::parse("localhost:1521/FREEPDB1")?;
+    ///     let policy = ReconnectPolicy::new().with_replay(1);
+    ///     let mut conn =
+    ///         ReconnectingConnection::connect_with_params(params, "user", "pass", policy).await?;
+    ///     conn.set_autocommit(true);
+    ///     conn.execute("INSERT INTO audit_log (id) VALUES (:1)", &[7i64.to_sql()])
+    ///         .await?;
+    ///     if let Some(event) = conn.take_reconnect_event() {
+    ///         println!("replayed: {}, committed: {:?}", event.replayed, event.committed);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn execute(&mut self, sql: &str, params: &[OracleValue]) -> Result<ExecuteResult> {
+        let mut replays = 0;
+        loop {
+            self.ensure_connected().await?;
+            let in_transaction = self.conn.transaction_in_progress();
+            let ltxid = self.conn.ltxid().map(<[u8]>::to_vec);
+            let result = self.conn.execute(sql, params).await;
+            let result = self.observe(result);
+            match &result {
+                Err(e)
+                    if is_retryable(e) && replays < self.policy.max_replays && !in_transaction => {}
+                _ => return result,
+            }
+
+            self.ensure_connected().await?;
+            let stmt = Statement::parse(sql);
+            let committed = if stmt.is_query() || (stmt.is_dml() && !self.conn.autocommit()) {
+                None
+            } else {
+                match ltxid {
+                    Some(ltxid) => Some(self.ltxid_committed(ltxid).await),
+                    None => return result,
+                }
+            };
+            if let Some(event) = &mut self.event {
+                event.committed = committed.flatten();
+            }
+            if committed.is_some_and(|committed| committed != Some(false)) {
+                return result;
+            }
+            if let Some(event) = &mut self.event {
+                event.replayed = true;
+            }
+            replays += 1;
+        }
+    }
+
+    /// Ask Transaction Guard on the current session whether the call with
+    /// the given LTXID committed (`None` if it could not tell).
+    async fn ltxid_committed(&mut self, ltxid: Vec<u8>) -> Option<bool> {
+        let result = self
+            .conn
+            .execute(LTXID_OUTCOME_SQL, &[OracleValue::Raw(ltxid)])
+            .await;
+        match self.observe(result) {
+            Ok(_) => Some(false),
+            Err(e) if e.code() == Some(LTXID_COMMITTED) => Some(true),
+            Err(_) => None,
+        }
+    }
+
+    /// Ping the server, reconnecting first if the session was lost.
+    ///
+    /// Retried on a new session if the policy allows it.
+    pub async fn ping(&mut self) -> Result<()> {
+        let mut retries = 0;
+        loop {
+            self.ensure_connected().await?;
+            let result = self.conn.ping().await;
+            let result = self.observe(result);
+            if !self.should_retry(&result, &mut retries) {
+                return result;
+            }
+        }
+    }
+
+    /// Get the underlying connection, reconnecting first if the session was lost.
+    ///
+    /// Use this for cursors and other APIs the wrapper does not forward.
+    /// Failures on the returned connection that lose the session are
+    /// detected on the next call.
+    pub async fn connection(&mut self) -> Result<&mut Connection> {
+        self.ensure_connected().await?;
+        Ok(&mut self.conn)
+    }
+
+    /// Re-establish the session now, even if it is not known to be lost.
+    pub async fn reconnect(&mut self) -> Result<()> {
+        let cause = self
+            .lost
+            .take()
+            .unwrap_or_else(|| "reconnect requested".to_string());
+        self.reconnect_with_cause(cause).await
+    }
+
+    /// Close the connection.
+    pub async fn close(self) -> Result<()> {
+        self.conn.close().await
+    }
+
+    /// Reconnect if an earlier operation lost the session.
+    async fn ensure_connected(&mut self) -> Result<()> {
+        let cause = match self.lost.take() {
+            Some(cause) => cause,
+            None if self.conn.is_broken() => "connection lost".to_string(),
+            None => match self.check_idle().await {
+                Some(cause) => cause,
+                None => return Ok(()),
+            },
+        };
+        self.reconnect_with_cause(cause).await
+    }
+
+    /// Ping an idle session if the policy asks for it, returning the cause
+    /// if the session turns out to be lost.
+    async fn check_idle(&mut self) -> Option<String> {
+        let max_idle = self.policy.idle_check?;
+        match self.conn.ping_if_idle(max_idle).await {
+            Err(e) if e.is_connection_lost() => Some(e.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Remember a session-losing error so the next operation reconnects.
+    fn observe<T>(&mut self, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            if e.is_connection_lost() {
+                self.lost = Some(e.to_string());
+            }
+        }
+        result
+    }
+
+    /// Check if an idempotent operation should be run again, counting the
+    /// retry.
+    fn should_retry<T>(&self, result: &Result<T>, retries: &mut u32) -> bool {
+        match result {
+            Err(e) if is_retryable(e) && *retries < self.policy.max_retries => {
+                *retries += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    async fn reconnect_with_cause(&mut self, cause: String) -> Result<()> {
+        let mut last_error = None;
+        for attempt in 1..=self.policy.max_attempts {
+            if attempt > 1 {
+                tokio::time::sleep(self.policy.backoff(attempt - 1)).await;
+            }
+            match self.establish().await {
+                Ok(conn) => {
+                    self.conn = conn;
+                    self.reconnects += 1;
+                    self.event = Some(ReconnectEvent {
+                        cause,
+                        attempts: attempt,
+                        reconnect_count: self.reconnects,
+                        replayed: false,
+                        committed: None,
+                    });
+                    return Ok(());
+                }
+                // Only network-level failures are worth another attempt
+                Err(e) if e.is_connection_lost() || is_connect_failure(&e) => {
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    self.lost = Some(cause);
+                    return Err(e);
+                }
+            }
+        }
+
+        self.lost = Some(cause);
+        Err(last_error.unwrap_or(Error::ConnectionClosed))
+    }
+
+    /// Open a new session and replay the session state onto it.
+    async fn establish(&self) -> Result<Connection> {
+        let mut conn =
+            Connection::connect_with_params(&self.params, &self.username, &self.password).await?;
+        conn.set_autocommit(self.conn.autocommit());
+        for sql in &self.init_statements {
+            conn.execute_query(sql, 0).await?;
+        }
+        Ok(conn)
+    }
+}
+
+/// Check if an error lost the session in a way a retry can recover from.
+///
+/// An oversized response loses the session too, but would fail again.
+fn is_retryable(error: &Error) -> bool {
+    error.is_connection_lost() && !matches!(error, Error::ResponseTooLarge { .. })
+}
+
+/// Check if an error is a transient failure to reach the server.
+fn is_connect_failure(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::ConnectionRefused { .. }
+            | Error::ConnectionTimeout { .. }
+            | Error::DnsResolutionFailed { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_backoff() {
+        let policy = ReconnectPolicy::new()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(350));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_policy_never() {
+        let policy = ReconnectPolicy::never();
+        assert_eq!(policy.max_attempts, 0);
+        assert_eq!(ReconnectPolicy::new().max_attempts, 3);
+    }
+
+    #[test]
+    fn test_policy_idle_check() {
+        assert_eq!(ReconnectPolicy::new().idle_check, None);
+        let policy = ReconnectPolicy::new().with_idle_check(Duration::from_secs(30));
+        assert_eq!(policy.idle_check, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_policy_retries() {
+        assert_eq!(ReconnectPolicy::new().max_retries, 0);
+        assert_eq!(ReconnectPolicy::new().with_retries(2).max_retries, 2);
+    }
+
+    #[test]
+    fn test_policy_replay() {
+        assert_eq!(ReconnectPolicy::new().max_replays, 0);
+        assert_eq!(ReconnectPolicy::new().with_replay(1).max_replays, 1);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&Error::ConnectionClosed));
+        assert!(is_retryable(&Error::oracle(3113, "end-of-file")));
+        assert!(is_retryable(&Error::oracle(12572, "packet reader failure")));
+        assert!(!is_retryable(&Error::oracle(
+            942,
+            "table or view does not exist"
+        )));
+        assert!(!is_retryable(&Error::ResponseTooLarge { size: 10, max: 5 }));
+    }
+
+    #[test]
+    fn test_is_connect_failure() {
+        assert!(is_connect_failure(&Error::ConnectionRefused {
+            message: "refused".to_string()
+        }));
+        assert!(!is_connect_failure(&Error::AuthenticationFailed {
+            message: "ORA-01017".to_string()
+        }));
+    }
+}
@@ -0,0 +1,182 @@
+//! Connection handle shared between tasks.
+//!
+//! `Connection` methods take `&mut self`: a session runs one call at a time.
+//! `SharedConnection` puts the connection behind an async mutex so it can be
+//! cloned into several tasks (or held in an `Arc` by a service) and used
+//! through `&self`, each call taking its turn.
+
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::connection::{Connection, ExecuteResult, QueryResult};
+use crate::error::Result;
+use crate::options::QueryOptions;
+use crate::protocol::connect::ConnectParams;
+use crate::protocol::types::{ColumnMetadata, FromRow, OracleValue, Row};
+
+/// A connection shared between tasks, with calls serialized.
+///
+/// Clones are cheap handles to the same session. Each method locks the
+/// connection for one call; calls from other handles wait their turn in
+/// FIFO order. Use `lock` for anything that spans several calls, such as a
+/// cursor or a transaction, so no other task's statements run in between.
+///
+/// A call whose future is dropped releases the lock, and the connection
+/// resynchronizes before the next call (see `Connection`).
+///
+/// # Example
+///
+/// ```no_run
+/// use oracle_thin_rs::{Cursor, SharedConnection};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let conn = SharedConnection::connect("localhost:1521/FREEPDB1", "user", "password").await?;
+///
+///     let tasks: Vec<_> = (0..4)
+///         .map(|i| {
+///             let conn = conn.clone();
+///             tokio::spawn(async move { conn.query_one(&format!("SELECT {} FROM DUAL", i)).await })
+///         })
+///         .collect();
+///     for task in tasks {
+///         println!("{:?}", task.await??);
+///     }
+///
+///     // A cursor keeps the connection to itself until it is dropped
+///     let mut guard = conn.lock().await;
+///     let mut cursor = guard.open_cursor("SELECT * FROM users").await?;
+///     while let Some(row) = cursor.next().await? {
+///         println!("{:?}", row);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedConnection {
+    inner: Arc<Mutex<Connection>>,
+}
+
+impl SharedConnection {
+    /// Share an established connection.
+    pub fn new(conn: Connection) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(conn)),
+        }
+    }
+
+    /// Connect to an Oracle database (see `Connection::connect`).
+    pub async fn connect(conn_str: &str, username: &str, password: &str) -> Result<Self> {
+        Ok(Self::new(
+            Connection::connect(conn_str, username, password).await?,
+        ))
+    }
+
+    /// Connect with explicit connection parameters (see
+    /// `Connection::connect_with_params`).
+    pub async fn connect_with_params(
+        params: &ConnectParams,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            Connection::connect_with_params(params, username, password).await?,
+        ))
+    }
+
+    /// Wait for exclusive access to the connection.
+    ///
+    /// Other handles' calls wait until the guard is dropped.
+    pub async fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.inner.lock().await
+    }
+
+    /// Take the connection back, if no other handle shares it.
+    ///
+    /// # Errors
+    /// Returns the handle unchanged while clones of it exist.
+    pub fn into_inner(self) -> std::result::Result<Connection, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(Mutex::into_inner)
+            .map_err(|inner| Self { inner })
+    }
+
+    /// Get the number of handles sharing the connection.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Execute a SELECT query (see `Connection::query`).
+    pub async fn query(&self, sql: &str) -> Result<QueryResult> {
+        self.lock().await.query(sql).await
+    }
+
+    /// Execute a SELECT query with explicit fetch options (see
+    /// `Connection::query_with_options`).
+    pub async fn query_with_options(
+        &self,
+        sql: &str,
+        options: &QueryOptions,
+    ) -> Result<QueryResult> {
+        self.lock().await.query_with_options(sql, options).await
+    }
+
+    /// Execute a query and return its first row (see `Connection::query_one`).
+    pub async fn query_one(&self, sql: &str) -> Result<Option<Row>> {
+        self.lock().await.query_one(sql).await
+    }
+
+    /// Execute a query and convert every row to `T` (see
+    /// `Connection::query_as`).
+    pub async fn query_as<T: FromRow>(&self, sql: &str) -> Result<Vec<T>> {
+        self.lock().await.query_as(sql).await
+    }
+
+    /// Execute a statement with positional binds (see `Connection::execute`).
+    pub async fn execute(&self, sql: &str, params: &[OracleValue]) -> Result<ExecuteResult> {
+        self.lock().await.execute(sql, params).await
+    }
+
+    /// Execute a statement once per row of binds (see
+    /// `Connection::execute_many`).
+    pub async fn execute_many(&self, sql: &str, rows: &[Vec<OracleValue>]) -> Result<u64> {
+        self.lock().await.execute_many(sql, rows).await
+    }
+
+    /// Describe a query's columns without executing it (see
+    /// `Connection::describe`).
+    pub async fn describe(&self, sql: &str) -> Result<Vec<ColumnMetadata>> {
+        self.lock().await.describe(sql).await
+    }
+
+    /// Ping the server (see `Connection::ping`).
+    pub async fn ping(&self) -> Result<()> {
+        self.lock().await.ping().await
+    }
+}
+
+impl From<Connection> for SharedConnection {
+    fn from(conn: Connection) -> Self {
+        Self::new(conn)
+    }
+}
+
+impl std::fmt::Debug for SharedConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedConnection")
+            .field("handles", &self.handle_count())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_connection_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+        assert_send_sync::<SharedConnection>();
+    }
+}
@@ -0,0 +1,143 @@
+//! Per-connection statistics.
+//!
+//! `Connection::stats` counts the round trips a connection makes, the bytes
+//! it exchanges and the parses, executions and fetches it asks the server
+//! for. Comparing snapshots taken around a piece of code shows how chatty
+//! it is: a loop of single-row queries shows up as one round trip (and one
+//! parse) per iteration.
+
+use std::time::Duration;
+
+use crate::protocol::constants::{
+    TNS_EXEC_OPTION_EXECUTE, TNS_EXEC_OPTION_FETCH, TNS_EXEC_OPTION_PARSE,
+};
+
+/// Counters for the work done by a connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use oracle_thin_rs::Connection;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut conn = Connection::connect("localhost:1521/FREEPDB1", "user", "password").await?;
+///
+///     let before = conn.stats();
+///     for id in 1..=10 {
+///         conn.query_one(&format!("SELECT name FROM users WHERE id = {}", id)).await?;
+///     }
+///     let used = conn.stats().since(&before);
+///     println!(
+///         "{} round trips, {} parses, {:?} waiting",
+///         used.round_trips, used.parses, used.wait_time
+///     );
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// Request/response round trips (connection establishment is not counted).
+    pub round_trips: u64,
+    /// Bytes written to the server, including connection establishment.
+    pub bytes_sent: u64,
+    /// Bytes read from the server, including connection establishment.
+    pub bytes_received: u64,
+    /// Statements sent to be parsed.
+    pub parses: u64,
+    /// Statement executions requested.
+    pub executes: u64,
+    /// Row fetches requested, including rows fetched with the execution.
+    pub fetches: u64,
+    /// Cursors dropped while still open on the server, without `close()`.
+    pub cursors_leaked: u64,
+    /// Time spent in round trips: sending requests and waiting for and
+    /// reading the responses.
+    pub wait_time: Duration,
+}
+
+impl ConnectionStats {
+    /// Get the work done since an earlier snapshot of the same connection.
+    pub fn since(&self, earlier: &ConnectionStats) -> ConnectionStats {
+        ConnectionStats {
+            round_trips: self.round_trips.saturating_sub(earlier.round_trips),
+            bytes_sent: self.bytes_sent.saturating_sub(earlier.bytes_sent),
+            bytes_received: self.bytes_received.saturating_sub(earlier.bytes_received),
+            parses: self.parses.saturating_sub(earlier.parses),
+            executes: self.executes.saturating_sub(earlier.executes),
+            fetches: self.fetches.saturating_sub(earlier.fetches),
+            cursors_leaked: self.cursors_leaked.saturating_sub(earlier.cursors_leaked),
+            wait_time: self.wait_time.saturating_sub(earlier.wait_time),
+        }
+    }
+
+    /// Count the work requested by an execute call with these options.
+    pub(crate) fn record_execute(&mut self, options: u32) {
+        if options & TNS_EXEC_OPTION_PARSE != 0 {
+            self.parses += 1;
+        }
+        if options & TNS_EXEC_OPTION_EXECUTE != 0 {
+            self.executes += 1;
+        }
+        if options & TNS_EXEC_OPTION_FETCH != 0 {
+            self.fetches += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_execute() {
+        let mut stats = ConnectionStats::default();
+        stats.record_execute(TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_EXECUTE);
+        stats.record_execute(
+            TNS_EXEC_OPTION_PARSE | TNS_EXEC_OPTION_EXECUTE | TNS_EXEC_OPTION_FETCH,
+        );
+        stats.record_execute(TNS_EXEC_OPTION_FETCH);
+        assert_eq!(stats.parses, 2);
+        assert_eq!(stats.executes, 2);
+        assert_eq!(stats.fetches, 2);
+    }
+
+    #[test]
+    fn test_since() {
+        let earlier = ConnectionStats {
+            round_trips: 2,
+            bytes_sent: 100,
+            bytes_received: 300,
+            parses: 1,
+            executes: 1,
+            fetches: 1,
+            cursors_leaked: 0,
+            wait_time: Duration::from_millis(5),
+        };
+        let later = ConnectionStats {
+            round_trips: 5,
+            bytes_sent: 250,
+            bytes_received: 900,
+            parses: 2,
+            executes: 3,
+            fetches: 4,
+            cursors_leaked: 2,
+            wait_time: Duration::from_millis(12),
+        };
+        assert_eq!(
+            later.since(&earlier),
+            ConnectionStats {
+                round_trips: 3,
+                bytes_sent: 150,
+                bytes_received: 600,
+                parses: 1,
+                executes: 2,
+                fetches: 3,
+                cursors_leaked: 2,
+                wait_time: Duration::from_millis(7),
+            }
+        );
+        // Snapshots from another connection do not underflow
+        assert_eq!(earlier.since(&later), ConnectionStats::default());
+    }
+}
@@ -0,0 +1,368 @@
+//! Plain-text table rendering of query results.
+//!
+//! `Display` for [`QueryResult`] and [`Row`] draws the values in a boxed
+//! table with a header of column names, numbers aligned right and long
+//! values cut short, for REPLs, command-line tools and test output.
+
+use std::fmt::{self, Write};
+
+use crate::connection::QueryResult;
+use crate::error::Result;
+use crate::protocol::types::{OracleValue, Row};
+
+/// Formatting options for [`QueryResult::to_table`] and [`Row::to_table`].
+///
+/// # Example
+///
+/// ```
+/// use oracle_thin_rs::TableOptions;
+///
+/// // Show at most 20 rows, values cut at 30 characters, NULLs left blank
+/// let options = TableOptions::new()
+///     .with_max_rows(20)
+///     .with_max_column_width(30)
+///     .with_null_text("");
+/// assert_eq!(options.max_rows, Some(20));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+    /// Longest value shown, in characters; longer values are cut and end
+    /// with `…`. `None` shows values in full.
+    pub max_column_width: Option<usize>,
+    /// Number of rows shown, with a line counting the rest. `None` shows
+    /// all rows.
+    pub max_rows: Option<usize>,
+    /// Text shown for NULL values.
+    pub null_text: String,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            max_column_width: Some(40),
+            max_rows: None,
+            null_text: "NULL".to_string(),
+        }
+    }
+}
+
+impl TableOptions {
+    /// Create the default options (values cut at 40 characters, all rows,
+    /// NULLs shown as `NULL`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the longest value shown, in characters.
+    pub fn with_max_column_width(mut self, width: usize) -> Self {
+        self.max_column_width = Some(width);
+        self
+    }
+
+    /// Show values in full, however long.
+    pub fn with_unlimited_column_width(mut self) -> Self {
+        self.max_column_width = None;
+        self
+    }
+
+    /// Set the number of rows shown.
+    pub fn with_max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    /// Set the text shown for NULL values.
+    pub fn with_null_text(mut self, null_text: impl Into<String>) -> Self {
+        self.null_text = null_text.into();
+        self
+    }
+}
+
+impl QueryResult {
+    /// Render the rows as a table, followed by the row count.
+    ///
+    /// ```text
+    /// +----+-------+
+    /// | ID | NAME  |
+    /// +----+-------+
+    /// |  1 | alice |
+    /// |  2 | NULL  |
+    /// +----+-------+
+    /// (2 rows)
+    /// ```
+    pub fn to_table(&self, options: &TableOptions) -> String {
+        let names: Vec<&str> = if self.columns.is_empty() {
+            self.rows.first().map(Row::column_names).unwrap_or_default()
+        } else {
+            self.columns.iter().map(|c| c.name.as_str()).collect()
+        };
+        let mut out = render(&names, &self.rows, options);
+        let rows = self.rows.len();
+        let _ = writeln!(out, "({} row{})", rows, if rows == 1 { "" } else { "s" });
+        out
+    }
+}
+
+impl Row {
+    /// Render the row as a one-row table under its column names.
+    pub fn to_table(&self, options: &TableOptions) -> String {
+        render(&self.column_names(), std::slice::from_ref(self), options)
+    }
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table(&TableOptions::default()))
+    }
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table(&TableOptions::default()))
+    }
+}
+
+/// Render rows under a header of column names.
+fn render(names: &[&str], rows: &[Row], options: &TableOptions) -> String {
+    let shown = options
+        .max_rows
+        .map_or(rows.len(), |max| max.min(rows.len()));
+    let cells: Vec<Vec<Cell>> = rows[..shown]
+        .iter()
+        .map(|row| {
+            (0..names.len())
+                .map(|i| Cell::new(row.try_get(i), options))
+                .collect()
+        })
+        .collect();
+    let header: Vec<String> = names
+        .iter()
+        .map(|name| cut(&printable(name), options.max_column_width))
+        .collect();
+
+    // Columns of numbers are aligned right; NULLs do not count
+    let numeric: Vec<bool> = (0..names.len())
+        .map(|i| {
+            let mut values = cells.iter().map(|row| &row[i]).filter(|c| !c.null);
+            values.clone().next().is_some() && values.all(|c| c.numeric)
+        })
+        .collect();
+    let widths: Vec<usize> = (0..names.len())
+        .map(|i| {
+            cells
+                .iter()
+                .map(|row| row[i].width)
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    border(&mut out, &widths);
+    line(
+        &mut out,
+        header.iter().map(|h| (h.as_str(), false)),
+        &widths,
+    );
+    border(&mut out, &widths);
+    for row in &cells {
+        line(
+            &mut out,
+            row.iter().zip(&numeric).map(|(c, &n)| (c.text.as_str(), n)),
+            &widths,
+        );
+    }
+    border(&mut out, &widths);
+    if shown < rows.len() {
+        let more = rows.len() - shown;
+        let _ = writeln!(
+            out,
+            "... {} more row{}",
+            more,
+            if more == 1 { "" } else { "s" }
+        );
+    }
+    out
+}
+
+/// A value as shown in the table.
+struct Cell {
+    text: String,
+    width: usize,
+    null: bool,
+    numeric: bool,
+}
+
+impl Cell {
+    fn new(value: Result<&OracleValue>, options: &TableOptions) -> Self {
+        let (text, null, numeric) = match value {
+            Ok(OracleValue::Null) => (options.null_text.clone(), true, false),
+            Ok(value) => (printable(&value.to_string()), false, is_numeric(value)),
+            Err(_) => ("<error>".to_string(), false, false),
+        };
+        let text = cut(&text, options.max_column_width);
+        let width = text.chars().count();
+        Self {
+            text,
+            width,
+            null,
+            numeric,
+        }
+    }
+}
+
+/// Whether a value is a number, aligned right.
+fn is_numeric(value: &OracleValue) -> bool {
+    match value {
+        OracleValue::Number(_) | OracleValue::Integer(_) => true,
+        #[cfg(feature = "rust_decimal")]
+        OracleValue::Decimal(_) => true,
+        _ => false,
+    }
+}
+
+/// Escape control characters, which would break the table's lines.
+fn printable(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_control() {
+            out.extend(c.escape_default());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Cut text longer than `max` characters, ending it with `…`.
+fn cut(text: &str, max: Option<usize>) -> String {
+    match max {
+        Some(max) if text.chars().count() > max => {
+            let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+            cut.push('…');
+            cut
+        }
+        _ => text.to_string(),
+    }
+}
+
+/// Write a `+----+` line.
+fn border(out: &mut String, widths: &[usize]) {
+    out.push('+');
+    for width in widths {
+        out.push_str(&"-".repeat(width + 2));
+        out.push('+');
+    }
+    out.push('\n');
+}
+
+/// Write a `| a | b |` line, padding each text to its column's width.
+fn line<'a>(out: &mut String, texts: impl Iterator<Item = (&'a str, bool)>, widths: &[usize]) {
+    out.push('|');
+    for ((text, right), width) in texts.zip(widths) {
+        let _ = if right {
+            write!(out, " {:>width$} |", text, width = width)
+        } else {
+            write!(out, " {:<width$} |", text, width = width)
+        };
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::constants::{ORA_TYPE_NUM_NUMBER, ORA_TYPE_NUM_VARCHAR};
+    use crate::protocol::statement::StatementType;
+    use crate::protocol::types::{ColumnInfo, ColumnMetadata};
+    use std::sync::Arc;
+
+    fn result(rows: Vec<Vec<OracleValue>>) -> QueryResult {
+        let columns = vec![
+            ColumnMetadata::new("ID".to_string(), ORA_TYPE_NUM_NUMBER as u8),
+            ColumnMetadata::new("NAME".to_string(), ORA_TYPE_NUM_VARCHAR as u8),
+        ];
+        let info = Arc::new(ColumnInfo::from_metadata(&columns).unwrap());
+        QueryResult {
+            columns,
+            rows: rows
+                .into_iter()
+                .map(|values| Row::new(values, info.clone()))
+                .collect(),
+            row_count: 0,
+            more_rows: false,
+            last_rowid: None,
+            statement_type: StatementType::Query,
+            implicit_results: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_query_result_table() {
+        let result = result(vec![
+            vec![
+                OracleValue::Integer(1),
+                OracleValue::String("alice".to_string()),
+            ],
+            vec![OracleValue::Number("-12.5".to_string()), OracleValue::Null],
+        ]);
+        assert_eq!(
+            result.to_string(),
+            "+-------+-------+\n\
+             | ID    | NAME  |\n\
+             +-------+-------+\n\
+             |     1 | alice |\n\
+             | -12.5 | NULL  |\n\
+             +-------+-------+\n\
+             (2 rows)\n"
+        );
+        assert_eq!(
+            result.rows[0].to_string(),
+            "+----+-------+\n\
+             | ID | NAME  |\n\
+             +----+-------+\n\
+             |  1 | alice |\n\
+             +----+-------+\n"
+        );
+    }
+
+    #[test]
+    fn test_table_options() {
+        let result = result(vec![
+            vec![
+                OracleValue::Integer(1),
+                OracleValue::String("a long\tname".to_string()),
+            ],
+            vec![OracleValue::Integer(2), OracleValue::Null],
+            vec![OracleValue::Integer(3), OracleValue::Null],
+        ]);
+        let options = TableOptions::new()
+            .with_max_column_width(8)
+            .with_max_rows(2)
+            .with_null_text("");
+        assert_eq!(
+            result.to_table(&options),
+            "+----+----------+\n\
+             | ID | NAME     |\n\
+             +----+----------+\n\
+             |  1 | a long\\… |\n\
+             |  2 |          |\n\
+             +----+----------+\n\
+             ... 1 more row\n\
+             (3 rows)\n"
+        );
+
+        let options = TableOptions::new().with_unlimited_column_width();
+        assert!(result.to_table(&options).contains("| a long\\tname |"));
+    }
+
+    #[test]
+    fn test_empty_table() {
+        assert_eq!(
+            result(Vec::new()).to_string(),
+            "+----+------+\n| ID | NAME |\n+----+------+\n+----+------+\n(0 rows)\n"
+        );
+    }
+}
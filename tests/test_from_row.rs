@@ -0,0 +1,95 @@
+//! `#[derive(FromRow)]` mapping rows to structs (no database needed).
+//!
+//! Run with: cargo test --features derive --test test_from_row
+
+#![cfg(feature = "derive")]
+
+use oracle_thin_rs::{Column, ColumnInfo, Error, FromRow, OracleType, OracleValue, Row};
+use std::sync::Arc;
+
+fn column(name: &str, data_type: OracleType, oracle_type_num: u8) -> Column {
+    Column {
+        name: name.to_string(),
+        nullable: true,
+        data_type,
+        oracle_type_num,
+        display_size: None,
+        is_json: false,
+        is_oson: false,
+    }
+}
+
+fn user_row(name: OracleValue) -> Row {
+    let info = ColumnInfo::new(vec![
+        column(
+            "USER_ID",
+            OracleType::Number {
+                precision: 10,
+                scale: 0,
+            },
+            2,
+        ),
+        column("NAME", OracleType::Varchar2 { max_size: 30 }, 1),
+        column("TYPE", OracleType::Varchar2 { max_size: 10 }, 1),
+    ]);
+    Row::new(
+        vec![
+            OracleValue::Number("7".to_string()),
+            name,
+            OracleValue::String("admin".to_string()),
+        ],
+        Arc::new(info),
+    )
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct User {
+    user_id: i64,
+    #[oracle(rename = "NAME")]
+    display_name: Option<String>,
+    r#type: String,
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct UserTuple(i64, String);
+
+#[derive(Debug, FromRow)]
+struct Missing {
+    #[allow(dead_code)]
+    email: String,
+}
+
+#[test]
+fn test_derive_named_fields() {
+    let user = User::from_row(&user_row(OracleValue::String("Ann".to_string()))).unwrap();
+    assert_eq!(
+        user,
+        User {
+            user_id: 7,
+            display_name: Some("Ann".to_string()),
+            r#type: "admin".to_string(),
+        }
+    );
+
+    let user = User::from_row(&user_row(OracleValue::Null)).unwrap();
+    assert_eq!(user.display_name, None);
+}
+
+#[test]
+fn test_derive_tuple_struct() {
+    let user = UserTuple::from_row(&user_row(OracleValue::String("Ann".to_string()))).unwrap();
+    assert_eq!(user, UserTuple(7, "Ann".to_string()));
+
+    assert!(matches!(
+        UserTuple::from_row(&user_row(OracleValue::Null)),
+        Err(Error::NullValue { column }) if column == "NAME"
+    ));
+}
+
+#[test]
+fn test_derive_missing_column() {
+    assert!(matches!(
+        Missing::from_row(&user_row(OracleValue::Null)),
+        Err(Error::ColumnNotFound { name }) if name == "email"
+    ));
+}
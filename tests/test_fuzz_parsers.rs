@@ -0,0 +1,339 @@
+//! Panic-freedom smoke test for the parsers that consume server data.
+//!
+//! Feeds seeded pseudo-random and mutated inputs to every parser reachable
+//! from a server packet. Parsers may return errors, but must never panic.
+//! The proptest properties at the end cover the same ground with inputs
+//! that are shrunk to a minimal case when a property fails.
+//!
+//! Run with: cargo test --test test_fuzz_parsers
+
+use bytes::{Bytes, BytesMut};
+use oracle_thin_rs::protocol::buffer::{LengthPrefixedBytes, ReadBuffer, WireValue, WriteBuffer};
+use oracle_thin_rs::protocol::constants::*;
+use oracle_thin_rs::protocol::decode;
+use oracle_thin_rs::protocol::message::WriteExt;
+use oracle_thin_rs::protocol::messages::LobOpMessage;
+use oracle_thin_rs::protocol::packet::decode_packet;
+use oracle_thin_rs::protocol::response::{
+    parse_execute_response, parse_fetch_response, parse_lob_op_response,
+    parse_returning_execute_response,
+};
+use oracle_thin_rs::protocol::tnsnames::{parse_descriptor, TnsNames};
+use oracle_thin_rs::protocol::types::{ColumnMetadata, RowId};
+use oracle_thin_rs::{ConnectParams, Statement};
+use proptest::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ITERATIONS: usize = 20_000;
+
+/// Message types that start a response, to get past the first dispatch.
+const MESSAGE_TYPES: [u8; 12] = [
+    TNS_MSG_TYPE_DESCRIBE_INFO,
+    TNS_MSG_TYPE_ROW_HEADER,
+    TNS_MSG_TYPE_ROW_DATA,
+    TNS_MSG_TYPE_ERROR,
+    TNS_MSG_TYPE_END_OF_RESPONSE,
+    TNS_MSG_TYPE_PARAMETER,
+    TNS_MSG_TYPE_BIT_VECTOR,
+    TNS_MSG_TYPE_STATUS,
+    TNS_MSG_TYPE_SERVER_SIDE_PIGGYBACK,
+    TNS_MSG_TYPE_IMPLICIT_RESULTSET,
+    TNS_MSG_TYPE_WARNING,
+    TNS_MSG_TYPE_LOB_DATA,
+];
+
+/// Column types with dedicated decoding paths.
+const COLUMN_TYPES: [u16; 19] = [
+    ORA_TYPE_NUM_VARCHAR,
+    ORA_TYPE_NUM_NUMBER,
+    ORA_TYPE_NUM_BINARY_INTEGER,
+    ORA_TYPE_NUM_LONG,
+    ORA_TYPE_NUM_ROWID,
+    ORA_TYPE_NUM_DATE,
+    ORA_TYPE_NUM_TIMESTAMP,
+    ORA_TYPE_NUM_RAW,
+    ORA_TYPE_NUM_LONG_RAW,
+    ORA_TYPE_NUM_UROWID,
+    ORA_TYPE_NUM_CHAR,
+    ORA_TYPE_NUM_BINARY_FLOAT,
+    ORA_TYPE_NUM_BINARY_DOUBLE,
+    ORA_TYPE_NUM_CLOB,
+    ORA_TYPE_NUM_JSON,
+    ORA_TYPE_NUM_VECTOR,
+    ORA_TYPE_NUM_BOOLEAN,
+    ORA_TYPE_NUM_INTERVAL_YM,
+    ORA_TYPE_NUM_INTERVAL_DS,
+];
+
+fn random_bytes(rng: &mut StdRng, max_len: usize) -> Vec<u8> {
+    let len = rng.gen_range(0..=max_len);
+    let mut data = vec![0u8; len];
+    rng.fill(&mut data[..]);
+    // Small values hit the length/indicator branches far more often
+    for b in data.iter_mut() {
+        if rng.gen_bool(0.3) {
+            *b %= 4;
+        }
+    }
+    data
+}
+
+/// Random payload prefixed with data flags and a plausible message type.
+fn random_response(rng: &mut StdRng) -> Bytes {
+    let mut data = vec![0, 0, MESSAGE_TYPES[rng.gen_range(0..MESSAGE_TYPES.len())]];
+    data.extend(random_bytes(rng, 256));
+    Bytes::from(data)
+}
+
+#[test]
+fn fuzz_response_parsers() {
+    let mut rng = StdRng::seed_from_u64(0x0AC1E);
+    for _ in 0..ITERATIONS {
+        let payload = random_response(&mut rng);
+        let version = rng.gen_range(0..=TNS_CCAP_FIELD_VERSION_MAX);
+
+        let mut buf = ReadBuffer::new(payload.clone());
+        let _ = buf.read_u16_be();
+        let _ = parse_execute_response(&mut buf, version, version);
+
+        let columns: Vec<ColumnMetadata> = (0..rng.gen_range(0..4))
+            .map(|i| {
+                let ty = COLUMN_TYPES[rng.gen_range(0..COLUMN_TYPES.len())];
+                ColumnMetadata::new(format!("C{}", i), ty as u8)
+            })
+            .collect();
+        let mut buf = ReadBuffer::new(payload.clone());
+        let _ = buf.read_u16_be();
+        let _ = parse_fetch_response(&mut buf, &columns, None, version);
+
+        let mut buf = ReadBuffer::new(payload.clone());
+        let _ = buf.read_u16_be();
+        let _ = parse_returning_execute_response(&mut buf, version, version, &columns);
+
+        let locator = [0u8; 40];
+        let msg = LobOpMessage::read(&locator, 1, 100);
+        let mut buf = ReadBuffer::new(payload);
+        let _ = buf.read_u16_be();
+        let _ = parse_lob_op_response(&mut buf, &msg, version);
+    }
+}
+
+#[test]
+fn fuzz_read_buffer() {
+    let mut rng = StdRng::seed_from_u64(0xB0FF);
+    for _ in 0..ITERATIONS {
+        let data = Bytes::from(random_bytes(&mut rng, 64));
+        let _ = ReadBuffer::new(data.clone()).read_ub2();
+        let _ = ReadBuffer::new(data.clone()).read_ub4();
+        let _ = ReadBuffer::new(data.clone()).read_ub8();
+        let _ = ReadBuffer::new(data.clone()).read_sb8();
+        let _ = ReadBuffer::new(data.clone()).read_bytes_with_length();
+        let _ = ReadBuffer::new(data.clone()).read_str_with_length();
+        let _ = ReadBuffer::new(data).skip_raw_bytes_chunked();
+    }
+}
+
+#[test]
+fn fuzz_wire_value_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0x0B4);
+    for _ in 0..ITERATIONS / 100 {
+        // Random widths, so every encoded length is covered
+        let a = rng.gen::<u16>() >> rng.gen_range(0..16);
+        let b = rng.gen::<u32>() >> rng.gen_range(0..32);
+        let c = rng.gen::<u64>() >> rng.gen_range(0..64);
+        let long_len = rng.gen_range(0..3) * 32_767 + rng.gen_range(0..300);
+        let bytes = random_bytes(&mut rng, long_len);
+
+        // Both writers produce the same bytes, which read back unchanged
+        let mut vec = Vec::new();
+        let mut buf = WriteBuffer::new();
+        vec.write_ub2(a);
+        buf.write_ub2(a);
+        vec.write_ub4(b);
+        buf.write_ub4(b);
+        vec.write_ub8(c);
+        buf.write_ub8(c);
+        vec.write_bytes_with_length(&bytes);
+        buf.write_bytes_with_length(&bytes);
+        assert_eq!(buf.as_bytes(), &vec[..]);
+
+        let mut read = ReadBuffer::new(buf.freeze());
+        assert_eq!(read.read_ub2().unwrap(), a);
+        assert_eq!(read.read_ub4().unwrap(), b);
+        assert_eq!(read.read_ub8().unwrap(), c);
+        let start = read.position();
+        let value = read.read_bytes_with_length().unwrap().unwrap_or_default();
+        assert_eq!(&value[..], &bytes[..]);
+        assert_eq!(
+            read.position() - start,
+            LengthPrefixedBytes(&bytes).wire_size()
+        );
+        assert_eq!(read.remaining(), 0);
+    }
+}
+
+#[test]
+fn fuzz_value_decoders() {
+    let mut rng = StdRng::seed_from_u64(0xDEC0DE);
+    for _ in 0..ITERATIONS {
+        let data = random_bytes(&mut rng, 48);
+        let _ = decode::decode_oracle_number(&data);
+        let _ = decode::decode_oracle_date(&data);
+        let _ = decode::decode_oracle_boolean(&data);
+        let _ = decode::decode_binary_float(&data);
+        let _ = decode::decode_binary_double(&data);
+        let _ = decode::decode_interval_ym(&data);
+        let _ = decode::decode_interval_ds(&data);
+        let _ = decode::decode_urowid(&data);
+        let _ = decode::decode_vector(&data);
+        #[cfg(feature = "json")]
+        let _ = decode::decode_oson(&data);
+
+        // Fixed-size decoders only get past the length check with exact sizes
+        for len in [4, 5, 7, 8, 11, 13] {
+            let exact: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let _ = decode::decode_oracle_date(&exact);
+            let _ = decode::decode_binary_float(&exact);
+            let _ = decode::decode_binary_double(&exact);
+            let _ = decode::decode_interval_ym(&exact);
+            let _ = decode::decode_interval_ds(&exact);
+            let _ = decode::decode_urowid(&exact);
+        }
+    }
+}
+
+#[test]
+fn fuzz_packet_framing() {
+    let mut rng = StdRng::seed_from_u64(0xFACE);
+    for _ in 0..ITERATIONS {
+        let mut buf = BytesMut::from(&random_bytes(&mut rng, 64)[..]);
+        let large_sdu = rng.gen_bool(0.5);
+        // Drain every complete packet; stop at the first error or partial packet
+        while let Ok(Some(_)) = decode_packet(&mut buf, large_sdu) {}
+    }
+}
+
+#[test]
+fn fuzz_text_inputs() {
+    let mut rng = StdRng::seed_from_u64(0x5E1EC7);
+    let alphabet: Vec<char> = "SELECT :b1 'q'[x]' \"id\" -- /* */ \n\té😀:=_$#"
+        .chars()
+        .collect();
+    for _ in 0..ITERATIONS {
+        let len = rng.gen_range(0..40);
+        let sql: String = (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let _ = Statement::parse(&sql);
+
+        if let Ok(rowid) = sql.parse::<RowId>() {
+            let _ = rowid.parts();
+        }
+        let _ = RowId::from_universal_bytes(sql.as_bytes()).parts();
+    }
+}
+
+#[test]
+fn fuzz_connect_descriptors() {
+    let mut rng = StdRng::seed_from_u64(0xD35C);
+    let alphabet: Vec<char> = "(()))== ,\"#\nHOST=db PORT é".chars().collect();
+    for _ in 0..ITERATIONS {
+        let len = rng.gen_range(0..60);
+        let text: String = (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+            .collect();
+        let _ = parse_descriptor(&text);
+        let _ = ConnectParams::from_descriptor(&text);
+        let _ = TnsNames::parse(&text);
+    }
+}
+
+/// A response payload: data flags, a message type that starts a response,
+/// then arbitrary bytes.
+fn response_payload() -> impl Strategy<Value = Bytes> {
+    (
+        prop::sample::select(&MESSAGE_TYPES[..]),
+        prop::collection::vec(any::<u8>(), 0..256),
+    )
+        .prop_map(|(msg_type, rest)| {
+            let mut data = vec![0, 0, msg_type];
+            data.extend(rest);
+            Bytes::from(data)
+        })
+}
+
+proptest! {
+    #[test]
+    fn prop_response_parsers_never_panic(
+        payload in response_payload(),
+        version in 0..=TNS_CCAP_FIELD_VERSION_MAX,
+        types in prop::collection::vec(prop::sample::select(&COLUMN_TYPES[..]), 0..4),
+    ) {
+        let columns: Vec<ColumnMetadata> = types
+            .iter()
+            .enumerate()
+            .map(|(i, &ty)| ColumnMetadata::new(format!("C{}", i), ty as u8))
+            .collect();
+
+        let mut buf = ReadBuffer::new(payload.clone());
+        buf.skip(2).unwrap();
+        let _ = parse_execute_response(&mut buf, version, version);
+
+        let mut buf = ReadBuffer::new(payload.clone());
+        buf.skip(2).unwrap();
+        let _ = parse_fetch_response(&mut buf, &columns, None, version);
+
+        let mut buf = ReadBuffer::new(payload);
+        buf.skip(2).unwrap();
+        let _ = parse_returning_execute_response(&mut buf, version, version, &columns);
+    }
+
+    #[test]
+    fn prop_read_buffer_never_panics(data in prop::collection::vec(any::<u8>(), 0..64)) {
+        let data = Bytes::from(data);
+        let _ = ReadBuffer::new(data.clone()).read_ub2();
+        let _ = ReadBuffer::new(data.clone()).read_ub4();
+        let _ = ReadBuffer::new(data.clone()).read_ub8();
+        let _ = ReadBuffer::new(data.clone()).read_sb8();
+        let _ = ReadBuffer::new(data.clone()).read_bytes_with_length();
+        let _ = ReadBuffer::new(data).skip_raw_bytes_chunked();
+    }
+
+    #[test]
+    fn prop_packet_framing_never_panics(
+        data in prop::collection::vec(any::<u8>(), 0..64),
+        large_sdu in any::<bool>(),
+    ) {
+        let mut buf = BytesMut::from(&data[..]);
+        while let Ok(Some(_)) = decode_packet(&mut buf, large_sdu) {}
+    }
+
+    #[test]
+    fn prop_wire_values_round_trip(
+        a in any::<u16>(),
+        b in any::<u32>(),
+        c in any::<u64>(),
+        bytes in prop::collection::vec(any::<u8>(), 0..70_000),
+    ) {
+        let mut vec = Vec::new();
+        let mut buf = WriteBuffer::new();
+        vec.write_ub2(a);
+        buf.write_ub2(a);
+        vec.write_ub4(b);
+        buf.write_ub4(b);
+        vec.write_ub8(c);
+        buf.write_ub8(c);
+        vec.write_bytes_with_length(&bytes);
+        buf.write_bytes_with_length(&bytes);
+        prop_assert_eq!(buf.as_bytes(), &vec[..]);
+
+        let mut read = ReadBuffer::new(buf.freeze());
+        prop_assert_eq!(read.read_ub2().unwrap(), a);
+        prop_assert_eq!(read.read_ub4().unwrap(), b);
+        prop_assert_eq!(read.read_ub8().unwrap(), c);
+        let value = read.read_bytes_with_length().unwrap().unwrap_or_default();
+        prop_assert_eq!(&value[..], &bytes[..]);
+        prop_assert_eq!(read.remaining(), 0);
+    }
+}
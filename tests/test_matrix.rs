@@ -0,0 +1,2225 @@
+//! Protocol compatibility matrix across server flavors.
+//!
+//! Runs the same canonical suite (types, LOBs, errors, cursors) against every
+//! configured server flavor and records the negotiated TTC field versions, so
+//! version-gated code paths (19.1 error info, 23.1/23.4 column metadata) stay
+//! covered.
+//!
+//! Each flavor is configured through `ORACLE_<FLAVOR>_*` variables in
+//! `tests/.env` (HOST, PORT, SERVICE, USERNAME, PASSWORD). A flavor without a
+//! password is skipped, so the matrix runs against whatever subset is available.
+//!
+//! Run with: cargo test --test test_matrix -- --nocapture
+
+use chrono::NaiveDate;
+use futures::stream::{StreamExt, TryStreamExt};
+use oracle_thin_rs::{
+    Connection, ConnectionHandle, Cursor, CursorExportExt, CursorStreamExt, Error, ExportOptions,
+    FetchAs, FromRow, Lob, OracleType, OracleValue, Phase, Pool, PoolOptions, PooledConnection,
+    QueryLog, QueryLogger, QueryOptions, ReconnectPolicy, ReconnectingConnection, Row, RowId,
+    SessionSettings, SharedConnection, StatementType, ToSql,
+};
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A server flavor in the compatibility matrix.
+struct Flavor {
+    /// Display name.
+    name: &'static str,
+    /// Environment variable prefix (e.g. `ORACLE_19C`).
+    env_prefix: &'static str,
+    /// Expected major version of the server.
+    major_version: u8,
+    /// Default service name.
+    default_service: &'static str,
+    /// Default username.
+    default_username: &'static str,
+}
+
+const FLAVOR_19C: Flavor = Flavor {
+    name: "19c",
+    env_prefix: "ORACLE_19C",
+    major_version: 19,
+    default_service: "pdb1",
+    default_username: "admin",
+};
+
+const FLAVOR_21C: Flavor = Flavor {
+    name: "21c",
+    env_prefix: "ORACLE_21C",
+    major_version: 21,
+    default_service: "xepdb1",
+    default_username: "test_user",
+};
+
+const FLAVOR_23AI: Flavor = Flavor {
+    name: "23ai",
+    env_prefix: "ORACLE_23AI",
+    major_version: 23,
+    default_service: "freepdb1",
+    default_username: "test_user",
+};
+
+/// Connection settings resolved from the environment.
+struct Endpoint {
+    conn_str: String,
+    username: String,
+    password: String,
+}
+
+impl Flavor {
+    fn var(&self, key: &str) -> Option<String> {
+        env::var(format!("{}_{}", self.env_prefix, key)).ok()
+    }
+
+    /// Resolve the endpoint, or `None` if the flavor is not configured.
+    fn endpoint(&self) -> Option<Endpoint> {
+        let _ = dotenvy::from_path("tests/.env");
+        let password = self.var("PASSWORD")?;
+        let host = self.var("HOST").unwrap_or_else(|| "localhost".to_string());
+        let port = self.var("PORT").unwrap_or_else(|| "1521".to_string());
+        let service = self
+            .var("SERVICE")
+            .unwrap_or_else(|| self.default_service.to_string());
+        let username = self
+            .var("USERNAME")
+            .unwrap_or_else(|| self.default_username.to_string());
+        Some(Endpoint {
+            conn_str: format!("{}:{}/{}", host, port, service),
+            username,
+            password,
+        })
+    }
+}
+
+/// Connect to a flavor, skipping (returning `None`) when it is not configured
+/// or not reachable.
+async fn connect_flavor(flavor: &Flavor) -> Option<Connection> {
+    let Some(endpoint) = flavor.endpoint() else {
+        eprintln!(
+            "Skipping {} matrix - {}_PASSWORD not set",
+            flavor.name, flavor.env_prefix
+        );
+        return None;
+    };
+
+    match Connection::connect(&endpoint.conn_str, &endpoint.username, &endpoint.password).await {
+        Ok(conn) => Some(conn),
+        Err(e) => {
+            let err_str = e.to_string();
+            if err_str.contains("Connection refused")
+                || err_str.contains("I/O error")
+                || matches!(
+                    e,
+                    Error::ConnectionTimeout { .. } | Error::DnsResolutionFailed { .. }
+                )
+            {
+                eprintln!("Skipping {} matrix - not reachable: {}", flavor.name, e);
+                return None;
+            }
+            panic!("[{}] Unexpected connection error: {}", flavor.name, e);
+        }
+    }
+}
+
+/// Record and sanity-check the negotiated protocol versions.
+fn check_versions(flavor: &Flavor, conn: &Connection) {
+    let version = conn.server_version();
+    let banner = conn.server_banner();
+    println!(
+        "[{}] server={:?} banner={:?} protocol={} sdu={} ttc_field_version={} server_ttc_field_version={}",
+        flavor.name,
+        version,
+        banner,
+        conn.protocol_version(),
+        conn.sdu(),
+        conn.ttc_field_version(),
+        conn.server_ttc_field_version(),
+    );
+
+    let version =
+        version.unwrap_or_else(|| panic!("[{}] Server version not reported", flavor.name));
+    assert_eq!(
+        version.0, flavor.major_version,
+        "[{}] Unexpected server major version",
+        flavor.name
+    );
+    assert!(
+        banner.is_some(),
+        "[{}] Server banner not reported",
+        flavor.name
+    );
+    assert!(
+        conn.server_ttc_field_version() > 0,
+        "[{}] Server field version was never negotiated",
+        flavor.name
+    );
+    assert!(
+        conn.ttc_field_version() <= conn.server_ttc_field_version(),
+        "[{}] Client field version {} exceeds server field version {}",
+        flavor.name,
+        conn.ttc_field_version(),
+        conn.server_ttc_field_version()
+    );
+
+    let features = conn.server_features();
+    assert_eq!(
+        features.server_version(),
+        Some(version),
+        "[{}] Features report a different server version",
+        flavor.name
+    );
+    assert_eq!(
+        features.supports_boolean(),
+        version.0 >= 23,
+        "[{}] Unexpected native BOOLEAN support",
+        flavor.name
+    );
+}
+
+/// Scalar types: strings, numbers, dates and NULLs.
+async fn check_types(flavor: &Flavor, conn: &mut Connection) {
+    let result = conn
+        .query(
+            "SELECT 'hello' AS STR_VAL, 42 AS INT_VAL, -123.456 AS DEC_VAL, \
+                    SYSDATE AS DATE_VAL, CAST(NULL AS VARCHAR2(10)) AS NULL_VAL \
+             FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] types query failed: {}", flavor.name, e));
+
+    assert_eq!(result.len(), 1, "[{}] Expected 1 row", flavor.name);
+    let row = &result.rows[0];
+    assert_eq!(row.get(0), Some(&OracleValue::String("hello".to_string())));
+    assert_eq!(row.get(1).and_then(|v| v.to_i64()), Some(42));
+    assert_eq!(
+        row.get(2),
+        Some(&OracleValue::Number("-123.456".to_string()))
+    );
+    assert!(
+        row.get(3).and_then(|v| v.as_date()).is_some(),
+        "[{}] Expected DATE value, got {:?}",
+        flavor.name,
+        row.get(3)
+    );
+    assert!(row.get(4).unwrap().is_null());
+}
+
+/// Integer NUMBER columns decode straight to `OracleValue::Integer`.
+async fn check_integer_columns(flavor: &Flavor, conn: &mut Connection) {
+    let row = conn
+        .query_one(
+            "SELECT CAST(42 AS NUMBER(10)), CAST(-999999999999999999 AS NUMBER(18)), \
+                    CAST(1.5 AS NUMBER(10, 1)), CAST(NULL AS NUMBER(5)) \
+             FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] integer query failed: {}", flavor.name, e))
+        .unwrap();
+    assert_eq!(
+        row.get(0),
+        Some(&OracleValue::Integer(42)),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get(1),
+        Some(&OracleValue::Integer(-999_999_999_999_999_999)),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(row.get(2).and_then(|v| v.to_f64()), Some(1.5));
+    assert!(row.get(3).unwrap().is_null(), "[{}]", flavor.name);
+}
+
+/// Integers, floats and dates bound from Rust types read back unchanged.
+async fn check_bind_encoders(flavor: &Flavor, conn: &mut Connection) {
+    let _ = conn
+        .execute("DROP TABLE oracle_thin_rs_binds PURGE", &[])
+        .await;
+    conn.execute(
+        "CREATE TABLE oracle_thin_rs_binds (id NUMBER, n NUMBER, d DATE)",
+        &[],
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+
+    let date = NaiveDate::from_ymd_opt(1999, 12, 31).unwrap();
+    let rows = vec![
+        vec![1i64.to_sql(), i64::MAX.to_sql(), date.to_sql()],
+        vec![2i64.to_sql(), i64::MIN.to_sql(), date.to_sql()],
+        vec![3i64.to_sql(), 0.1f64.to_sql(), OracleValue::Null],
+    ];
+    conn.execute_many(
+        "INSERT INTO oracle_thin_rs_binds (id, n, d) VALUES (:1, :2, :3)",
+        &rows,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] insert failed: {}", flavor.name, e));
+
+    let result = conn
+        .query("SELECT n, d FROM oracle_thin_rs_binds ORDER BY id")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query failed: {}", flavor.name, e));
+    let rows: Vec<(i64, NaiveDate)> = result.rows[..2]
+        .iter()
+        .map(|row| (row.get_as(0).unwrap(), row.get_as(1).unwrap()))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![(i64::MAX, date), (i64::MIN, date)],
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        result.rows[2].get(0).and_then(|v| v.to_f64()),
+        Some(0.1),
+        "[{}]",
+        flavor.name
+    );
+
+    conn.execute("DROP TABLE oracle_thin_rs_binds PURGE", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+}
+
+/// NUMBER values at the edges of precision and range decode exactly.
+async fn check_number_edges(flavor: &Flavor, conn: &mut Connection) {
+    // SQL literals and their decoded text
+    let cases = [
+        (
+            "1.2345678901234567890123456789012345678e37",
+            "12345678901234567890123456789012345678".to_string(),
+        ),
+        (
+            "-0.12345678901234567890123456789012345678",
+            "-0.12345678901234567890123456789012345678".to_string(),
+        ),
+        ("-1e125", format!("-1{}", "0".repeat(125))),
+        ("1e-130", format!("0.{}1", "0".repeat(129))),
+    ];
+    let literals: Vec<&str> = cases.iter().map(|(literal, _)| *literal).collect();
+    let sql = format!("SELECT {} FROM DUAL", literals.join(", "));
+    let row = conn
+        .query_one(&sql)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] NUMBER edge query failed: {}", flavor.name, e))
+        .unwrap();
+    for (i, (literal, expected)) in cases.iter().enumerate() {
+        assert_eq!(
+            row.get(i),
+            Some(&OracleValue::Number(expected.clone())),
+            "[{}] {}",
+            flavor.name,
+            literal
+        );
+    }
+}
+
+/// ROWID, ROWNUM and ORA_ROWSCN pseudo-columns describe and decode.
+async fn check_pseudo_columns(flavor: &Flavor, conn: &mut Connection) {
+    let result = conn
+        .query("SELECT ROWID, ROWNUM, ORA_ROWSCN FROM DUAL")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] pseudo-column query failed: {}", flavor.name, e));
+    let row = &result.rows[0];
+    let types: Vec<String> = row
+        .columns()
+        .iter()
+        .map(|c| c.data_type.to_string())
+        .collect();
+    assert_eq!(types, ["ROWID", "NUMBER", "NUMBER"], "[{}]", flavor.name);
+
+    let rowid: RowId = row
+        .get_as(0)
+        .unwrap_or_else(|e| panic!("[{}] ROWID conversion failed: {}", flavor.name, e));
+    assert!(rowid.parts().is_some(), "[{}] {}", flavor.name, rowid);
+    assert_eq!(row.get(1).and_then(|v| v.to_i64()), Some(1));
+    assert!(row
+        .get(2)
+        .and_then(|v| v.to_i64())
+        .is_some_and(|scn| scn > 0));
+
+    // The fetched ROWID addresses the same row
+    let sql = format!(
+        "SELECT DUMMY FROM DUAL WHERE ROWID = CHARTOROWID('{}')",
+        rowid
+    );
+    let row = conn.query_one(&sql).await.unwrap();
+    assert!(row.is_some(), "[{}] ROWID lookup found no row", flavor.name);
+}
+
+/// LOB columns must describe correctly under every field version.
+async fn check_lobs(flavor: &Flavor, conn: &mut Connection) {
+    let mut cursor = conn
+        .open_cursor(
+            "SELECT TO_CLOB('hello') AS CLOB_VAL, TO_BLOB(HEXTORAW('CAFE')) AS BLOB_VAL FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] LOB query failed: {}", flavor.name, e));
+
+    let types: Vec<u8> = cursor.columns().iter().map(|c| c.oracle_type).collect();
+    assert_eq!(
+        types,
+        vec![OracleType::Clob.type_num(), OracleType::Blob.type_num()],
+        "[{}] Unexpected LOB column types",
+        flavor.name
+    );
+
+    let rows = cursor
+        .fetch_all()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] LOB fetch failed: {}", flavor.name, e));
+    drop(cursor);
+
+    let row = &rows[0];
+    let mut clob = row.get_as::<Lob>(0).unwrap();
+    let mut blob = row.get_as::<Lob>(1).unwrap();
+    assert_eq!(clob.size(), 5, "[{}]", flavor.name);
+    assert_eq!(
+        conn.read_clob(&mut clob, 1, 100).await.unwrap(),
+        "hello",
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        conn.read_blob(&mut blob, 1, 100).await.unwrap(),
+        vec![0xCA, 0xFE],
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// National character set columns decode from UTF-16 and NCLOBs read as text.
+async fn check_nchar(flavor: &Flavor, conn: &mut Connection) {
+    let mut cursor = conn
+        .open_cursor(
+            "SELECT CAST(N'h\u{e9}llo \u{20ac}' AS NVARCHAR2(20)) AS NV, \
+             CAST(N'ab' AS NCHAR(3)) AS NC, TO_NCLOB(N'\u{1f600}x') AS NCL FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] NCHAR query failed: {}", flavor.name, e));
+    assert!(
+        cursor.columns().iter().all(|c| c.is_nchar()),
+        "[{}] NCHAR columns not described as national",
+        flavor.name
+    );
+
+    let rows = cursor
+        .fetch_all()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] NCHAR fetch failed: {}", flavor.name, e));
+    drop(cursor);
+
+    let row = &rows[0];
+    assert_eq!(
+        row.get_as::<String>(0).unwrap(),
+        "h\u{e9}llo \u{20ac}",
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(row.get_as::<String>(1).unwrap(), "ab ", "[{}]", flavor.name);
+    assert_eq!(
+        row.columns()[0].data_type,
+        OracleType::NVarchar2 { max_size: 20 },
+        "[{}]",
+        flavor.name
+    );
+    let mut nclob = row.get_as::<Lob>(2).unwrap();
+    assert_eq!(nclob.oracle_type(), &OracleType::Nclob, "[{}]", flavor.name);
+    assert_eq!(
+        conn.read_clob(&mut nclob, 1, 100).await.unwrap(),
+        "\u{1f600}x",
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// 1 MB LOBs are read back in a few large round trips.
+async fn check_large_lobs(flavor: &Flavor, conn: &mut Connection) {
+    let size: u64 = 1024 * 1024;
+    let row = conn
+        .query_one(&format!(
+            "SELECT RPAD(TO_CLOB('x'), {}, 'abcdefgh') FROM DUAL",
+            size
+        ))
+        .await
+        .unwrap_or_else(|e| panic!("[{}] large CLOB query failed: {}", flavor.name, e))
+        .unwrap();
+    let mut clob = row.get_as::<Lob>(0).unwrap();
+    assert_eq!(conn.lob_length(&mut clob).await.unwrap(), size);
+
+    let before = conn.stats();
+    let text = conn
+        .read_clob(&mut clob, 1, size)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] large CLOB read failed: {}", flavor.name, e));
+    let used = conn.stats().since(&before);
+    assert_eq!(text.len() as u64, size, "[{}]", flavor.name);
+    assert!(text.starts_with("xabcdefgh") && text.ends_with('h'));
+    assert!(used.round_trips <= 4, "[{}] {:?}", flavor.name, used);
+
+    let mut blob = conn.create_temp_blob().await.unwrap();
+    let data: Vec<u8> = (0..size as u32).map(|i| (i % 251) as u8).collect();
+    conn.write_blob(&mut blob, 1, &data).await.unwrap();
+    let before = conn.stats();
+    assert_eq!(
+        conn.read_blob(&mut blob, 1, size).await.unwrap(),
+        data,
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        conn.stats().since(&before).round_trips,
+        1,
+        "[{}]",
+        flavor.name
+    );
+    conn.free_temp_lob(blob);
+}
+
+/// Temporary LOBs can be created, written in chunks, read back and freed.
+async fn check_lob_write(flavor: &Flavor, conn: &mut Connection) {
+    let mut clob = conn
+        .create_temp_clob()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] create temp CLOB failed: {}", flavor.name, e));
+    assert!(clob.is_temporary(), "[{}]", flavor.name);
+
+    // Larger than one packet, with multi-byte characters on chunk boundaries
+    let text: String = "abcdé😀".repeat(5000);
+    let length = text.encode_utf16().count() as u64;
+    conn.write_clob(&mut clob, 1, &text).await.unwrap();
+    conn.append_clob(&mut clob, "!").await.unwrap();
+    assert_eq!(conn.lob_length(&mut clob).await.unwrap(), length + 1);
+    let read = conn.read_clob(&mut clob, 1, length + 1).await.unwrap();
+    assert_eq!(read.len(), text.len() + 1, "[{}]", flavor.name);
+    assert!(read.starts_with(&text) && read.ends_with('!'));
+
+    conn.trim_lob(&mut clob, 5).await.unwrap();
+    assert_eq!(conn.read_clob(&mut clob, 1, 100).await.unwrap(), "abcdé");
+
+    let mut blob = conn.create_temp_blob().await.unwrap();
+    let data: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+    conn.append_blob(&mut blob, &data).await.unwrap();
+    assert_eq!(blob.size(), data.len() as u64);
+    assert_eq!(
+        conn.read_blob(&mut blob, 1, data.len() as u64)
+            .await
+            .unwrap(),
+        data,
+        "[{}]",
+        flavor.name
+    );
+
+    conn.free_temp_lob(clob);
+    conn.free_temp_lob(blob);
+    assert_eq!(conn.pending_temp_lob_frees(), 2);
+    conn.ping().await.unwrap();
+    assert_eq!(conn.pending_temp_lob_frees(), 0);
+}
+
+/// BFILE locators are fetched and file operations reach the server.
+async fn check_bfile(flavor: &Flavor, conn: &mut Connection) {
+    let row = conn
+        .query_one("SELECT BFILENAME('DATA_PUMP_DIR', 'oracle_thin_rs_missing.bin') FROM DUAL")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] BFILE query failed: {}", flavor.name, e))
+        .unwrap();
+    let mut bfile = row.get_as::<Lob>(0).unwrap();
+    assert!(bfile.is_bfile(), "[{}]", flavor.name);
+
+    // Without READ on the directory the server reports ORA-22285
+    match conn.bfile_exists(&mut bfile).await {
+        Ok(exists) => assert!(!exists, "[{}]", flavor.name),
+        Err(Error::Oracle { code, .. }) => assert_eq!(code, 22285, "[{}]", flavor.name),
+        Err(e) => panic!("[{}] BFILE exists check failed: {}", flavor.name, e),
+    }
+    assert!(!conn.is_bfile_open(&mut bfile).await.unwrap());
+
+    let mut clob = conn.create_temp_clob().await.unwrap();
+    assert!(conn.bfile_exists(&mut clob).await.is_err());
+    conn.free_temp_lob(clob);
+}
+
+/// SQL text larger than one packet and one length chunk.
+async fn check_large_sql(flavor: &Flavor, conn: &mut Connection) {
+    // IN-lists are limited to 1000 expressions, so OR several of them
+    let lists: Vec<String> = (0..15)
+        .map(|i| {
+            let values: Vec<String> = (i * 999 + 1..=(i + 1) * 999)
+                .map(|n| n.to_string())
+                .collect();
+            format!("N IN ({})", values.join(", "))
+        })
+        .collect();
+    let sql = format!(
+        "SELECT COUNT(*) FROM (SELECT LEVEL AS N FROM DUAL CONNECT BY LEVEL <= 20000) WHERE {}",
+        lists.join(" OR ")
+    );
+    assert!(sql.len() > 65536);
+    let row = conn
+        .query_one(&sql)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] large SQL failed: {}", flavor.name, e))
+        .unwrap();
+    assert_eq!(row.get(0).and_then(|v| v.to_i64()), Some(14985));
+
+    // PL/SQL block well over 64k
+    let body = "x := x + 1;\n".repeat(8000);
+    let block = format!("DECLARE x NUMBER := 0; BEGIN {} END;", body);
+    conn.query(&block)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] large PL/SQL block failed: {}", flavor.name, e));
+    assert_eq!(conn.query("SELECT 1 FROM DUAL").await.unwrap().len(), 1);
+}
+
+/// Array DML in one round trip, with and without batch errors.
+async fn check_execute_many(flavor: &Flavor, conn: &mut Connection) {
+    const INSERT: &str = "INSERT INTO oracle_thin_rs_batch (id, name, created) VALUES (:1, :2, :3)";
+
+    let _ = conn.query("DROP TABLE oracle_thin_rs_batch PURGE").await;
+    conn.query(
+        "CREATE TABLE oracle_thin_rs_batch (id NUMBER PRIMARY KEY, name VARCHAR2(10), created DATE)",
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+
+    let created = NaiveDate::from_ymd_opt(2024, 2, 29)
+        .unwrap()
+        .and_hms_opt(13, 45, 30)
+        .unwrap();
+    let rows: Vec<Vec<OracleValue>> = (1..=100i64)
+        .map(|id| {
+            vec![
+                id.to_sql(),
+                format!("row {}", id).to_sql(),
+                created.to_sql(),
+            ]
+        })
+        .collect();
+    let before = conn.round_trips();
+    let count = conn
+        .execute_many(INSERT, &rows)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] execute_many failed: {}", flavor.name, e));
+    assert_eq!(count, 100, "[{}]", flavor.name);
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+
+    let row = conn
+        .query_one("SELECT COUNT(*), SUM(id), MAX(name), MAX(created) FROM oracle_thin_rs_batch")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.get(0).and_then(|v| v.to_i64()), Some(100));
+    assert_eq!(row.get(1).and_then(|v| v.to_i64()), Some(5050));
+    assert_eq!(row.get(2).and_then(|v| v.as_str()), Some("row 99"));
+    assert_eq!(row.get(3).and_then(|v| v.as_date()), Some(created));
+
+    // Duplicate key in row 1, value too long in row 3
+    let rows = vec![
+        vec![101i64.to_sql(), "ok".to_sql(), OracleValue::Null],
+        vec![1i64.to_sql(), "dup".to_sql(), OracleValue::Null],
+        vec![102i64.to_sql(), "ok".to_sql(), OracleValue::Null],
+        vec![103i64.to_sql(), "far too long".to_sql(), OracleValue::Null],
+    ];
+    let result = conn
+        .execute_many_with_batch_errors(INSERT, &rows)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] batch errors failed: {}", flavor.name, e));
+    assert_eq!(result.row_count, 2, "[{}]", flavor.name);
+    let errors: Vec<(u32, u32)> = result.errors.iter().map(|e| (e.offset, e.code)).collect();
+    assert_eq!(errors, vec![(1, 1), (3, 12899)], "[{}]", flavor.name);
+
+    // Without batch errors the first failing row is an error
+    match conn.execute_many(INSERT, &rows[1..2]).await {
+        Err(Error::Oracle { code, .. }) => assert_eq!(code, 1, "[{}]", flavor.name),
+        other => panic!("[{}] Expected ORA-00001, got {:?}", flavor.name, other),
+    }
+
+    // RETURNING ... INTO, for single and array executions
+    let number = OracleType::Number {
+        precision: 0,
+        scale: -127,
+    };
+    let name = OracleType::Varchar2 { max_size: 10 };
+    let result = conn
+        .execute_returning(
+            "UPDATE oracle_thin_rs_batch SET name = 'upd' WHERE id <= :1 \
+             RETURNING id, name INTO :id, :name",
+            &[3i64.to_sql()],
+            &[number.clone(), name],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] execute_returning failed: {}", flavor.name, e));
+    assert_eq!(result.row_count, 3, "[{}]", flavor.name);
+    let names: Vec<&str> = result.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["ID", "NAME"], "[{}]", flavor.name);
+    let mut ids: Vec<i64> = result.rows.iter().map(|r| r.get_as(0).unwrap()).collect();
+    ids.sort();
+    assert_eq!(ids, [1, 2, 3], "[{}]", flavor.name);
+    assert_eq!(result.rows[0].get_as::<String>(1).unwrap(), "upd");
+
+    let rows: Vec<Vec<OracleValue>> = (201..=203i64).map(|id| vec![id.to_sql()]).collect();
+    let returned = conn
+        .execute_many_returning(
+            "INSERT INTO oracle_thin_rs_batch (id) VALUES (:1) RETURNING id * 2 INTO :doubled",
+            &rows,
+            &[number],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] execute_many_returning failed: {}", flavor.name, e));
+    let doubled: Vec<i64> = returned
+        .iter()
+        .map(|rows| rows[0].get_as(0).unwrap())
+        .collect();
+    assert_eq!(doubled, [402, 404, 406], "[{}]", flavor.name);
+
+    let id = conn
+        .insert_returning_id(
+            "INSERT INTO oracle_thin_rs_batch (id, name) VALUES (:1 + 1, :2)",
+            &[300i64.to_sql(), "key".to_sql()],
+            "id",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] insert_returning_id failed: {}", flavor.name, e));
+    assert_eq!(id, 301, "[{}]", flavor.name);
+
+    conn.query("DROP TABLE oracle_thin_rs_batch PURGE")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+}
+
+/// Creating a PL/SQL unit with compilation errors succeeds with a warning.
+async fn check_warnings(flavor: &Flavor, conn: &mut Connection) {
+    let result = conn
+        .execute(
+            "CREATE OR REPLACE PROCEDURE oracle_thin_rs_invalid AS BEGIN missing_call; END;",
+            &[],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] CREATE PROCEDURE failed: {}", flavor.name, e));
+    let codes: Vec<u32> = result.warnings.iter().map(|w| w.code).collect();
+    assert_eq!(codes, [24344], "[{}]", flavor.name);
+
+    let result = conn
+        .execute("DROP PROCEDURE oracle_thin_rs_invalid", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP PROCEDURE failed: {}", flavor.name, e));
+    assert!(result.warnings.is_empty(), "[{}]", flavor.name);
+}
+
+/// Multi-megabyte LONG and LONG RAW values round-trip through binds and
+/// single-row fetches.
+async fn check_long_values(flavor: &Flavor, conn: &mut Connection) {
+    let text: String = "LONG value ".repeat(200_000);
+    let data: Vec<u8> = (0..3_000_000u32).map(|i| (i % 253) as u8).collect();
+    for (table, column, value) in [
+        (
+            "oracle_thin_rs_long",
+            "LONG",
+            OracleValue::String(text.clone()),
+        ),
+        (
+            "oracle_thin_rs_long_raw",
+            "LONG RAW",
+            OracleValue::Raw(data.clone()),
+        ),
+    ] {
+        let _ = conn
+            .execute(&format!("DROP TABLE {} PURGE", table), &[])
+            .await;
+        conn.execute(
+            &format!("CREATE TABLE {} (id NUMBER, val {})", table, column),
+            &[],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+        for id in 1..=2 {
+            conn.execute(
+                &format!("INSERT INTO {} (id, val) VALUES (:1, :2)", table),
+                &[OracleValue::Number(id.to_string()), value.clone()],
+            )
+            .await
+            .unwrap_or_else(|e| panic!("[{}] {} insert failed: {}", flavor.name, column, e));
+        }
+
+        // One row per round trip holds one value in memory at a time
+        let mut cursor = conn
+            .open_row_cursor(&format!("SELECT val FROM {} ORDER BY id", table), 1)
+            .await
+            .unwrap_or_else(|e| panic!("[{}] {} query failed: {}", flavor.name, column, e));
+        let mut count = 0;
+        while let Some(row) = cursor.next().await.unwrap() {
+            assert_eq!(row.get(0), Some(&value), "[{}] {}", flavor.name, column);
+            count += 1;
+        }
+        assert_eq!(count, 2, "[{}] {}", flavor.name, column);
+        drop(cursor);
+
+        conn.execute(&format!("DROP TABLE {} PURGE", table), &[])
+            .await
+            .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+    }
+}
+
+/// A pipeline returns one result per operation, in one round trip on 23ai.
+async fn check_pipeline(flavor: &Flavor, conn: &mut Connection) {
+    let _ = conn
+        .execute("DROP TABLE oracle_thin_rs_pipeline PURGE", &[])
+        .await;
+    conn.execute("CREATE TABLE oracle_thin_rs_pipeline (id NUMBER)", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+
+    let before = conn.round_trips();
+    let results = conn
+        .pipeline()
+        .add_execute(
+            "INSERT INTO oracle_thin_rs_pipeline (id) VALUES (:1)",
+            vec![OracleValue::Number("7".to_string())],
+        )
+        .add_query("SELECT missing_column FROM oracle_thin_rs_pipeline")
+        .add_query("SELECT id FROM oracle_thin_rs_pipeline")
+        .run()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] pipeline failed: {}", flavor.name, e));
+    if flavor.major_version >= 23 {
+        assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    }
+
+    let mut results = results.into_iter();
+    let inserted = results.next().unwrap().unwrap().into_execute().unwrap();
+    assert_eq!(inserted.row_count, 1, "[{}]", flavor.name);
+    let err = results.next().unwrap().unwrap_err();
+    assert_eq!(err.code(), Some(904), "[{}] {}", flavor.name, err);
+    let rows = results.next().unwrap().unwrap().into_query().unwrap();
+    assert_eq!(rows.len(), 1, "[{}]", flavor.name);
+    assert_eq!(rows.rows[0].get(0).and_then(|v| v.to_i64()), Some(7));
+    assert!(results.next().is_none(), "[{}]", flavor.name);
+
+    conn.execute("DROP TABLE oracle_thin_rs_pipeline PURGE", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+}
+
+/// DDL, DML and PL/SQL through `execute`, classified by statement type.
+async fn check_execute(flavor: &Flavor, conn: &mut Connection) {
+    let _ = conn
+        .execute("DROP TABLE oracle_thin_rs_exec PURGE", &[])
+        .await;
+    let result = conn
+        .execute(
+            "CREATE TABLE oracle_thin_rs_exec (id NUMBER, name VARCHAR2(10))",
+            &[],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+    assert_eq!(
+        result.statement_type,
+        StatementType::Ddl,
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(result.row_count, 0, "[{}]", flavor.name);
+
+    let result = conn
+        .execute(
+            "INSERT INTO oracle_thin_rs_exec (id, name) VALUES (:1, :2)",
+            &[1i64.to_sql(), "one".to_sql()],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] INSERT failed: {}", flavor.name, e));
+    assert_eq!(
+        result.statement_type,
+        StatementType::Dml,
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(result.row_count, 1, "[{}]", flavor.name);
+    assert!(result.last_rowid.is_some(), "[{}]", flavor.name);
+
+    let result = conn
+        .execute(
+            "BEGIN UPDATE oracle_thin_rs_exec SET id = id + 1; END;",
+            &[],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] PL/SQL failed: {}", flavor.name, e));
+    assert_eq!(
+        result.statement_type,
+        StatementType::PlSql,
+        "[{}]",
+        flavor.name
+    );
+
+    let rows = conn
+        .query("SELECT id FROM oracle_thin_rs_exec")
+        .await
+        .unwrap();
+    assert_eq!(
+        rows.statement_type,
+        StatementType::Query,
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(rows.rows[0].get(0).and_then(|v| v.to_i64()), Some(2));
+
+    // DDL is parsed on every execution, so it can be repeated
+    for _ in 0..2 {
+        conn.execute("TRUNCATE TABLE oracle_thin_rs_exec", &[])
+            .await
+            .unwrap_or_else(|e| panic!("[{}] TRUNCATE failed: {}", flavor.name, e));
+    }
+    conn.execute("DROP TABLE oracle_thin_rs_exec PURGE", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+}
+
+/// `describe` reports a query's columns without executing it.
+async fn check_describe(flavor: &Flavor, conn: &mut Connection) {
+    let before = conn.round_trips();
+    let columns = conn
+        .describe("SELECT 1 AS ID, 'x' AS NAME, 1 / 0 AS BOOM FROM DUAL")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] describe failed: {}", flavor.name, e));
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    // BOOM would raise ORA-01476 if the query were executed
+    assert_eq!(names, ["ID", "NAME", "BOOM"], "[{}]", flavor.name);
+    assert_eq!(
+        columns[1].data_type().unwrap(),
+        OracleType::Char { max_size: 1 },
+        "[{}]",
+        flavor.name
+    );
+
+    let columns = conn.describe("DELETE FROM oracle_thin_rs_missing").await;
+    match columns {
+        Err(Error::Oracle { code, .. }) => assert_eq!(code, 942, "[{}]", flavor.name),
+        other => panic!("[{}] Expected ORA-00942, got {:?}", flavor.name, other),
+    }
+    assert!(conn
+        .describe("CREATE TABLE oracle_thin_rs_never (id NUMBER)")
+        .await
+        .unwrap()
+        .is_empty());
+
+    // prepare parses on the server and lists the placeholders
+    let before = conn.round_trips();
+    let stmt = conn
+        .prepare("SELECT :a + :b FROM DUAL WHERE :a > 0")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] prepare failed: {}", flavor.name, e));
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    let names: Vec<&str> = stmt.bind_info().iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["A", "B"], "[{}]", flavor.name);
+    assert_eq!(stmt.bind_info()[0].occurrences, 2, "[{}]", flavor.name);
+    assert!(stmt.check_params(&[OracleValue::Integer(1)]).is_err());
+    match conn.prepare("SELECT :a FROM oracle_thin_rs_missing").await {
+        Err(Error::Oracle { code, .. }) => assert_eq!(code, 942, "[{}]", flavor.name),
+        other => panic!("[{}] Expected ORA-00942, got {:?}", flavor.name, other),
+    }
+}
+
+/// Row type for `check_query_as`.
+#[derive(Debug, PartialEq)]
+struct Level {
+    id: i64,
+    name: Option<String>,
+}
+
+impl FromRow for Level {
+    fn from_row(row: &Row) -> oracle_thin_rs::Result<Self> {
+        Ok(Self {
+            id: row.get_as_by_name("id")?,
+            name: row.get_as_by_name("name")?,
+        })
+    }
+}
+
+/// `query_as` fetches every row and converts it.
+async fn check_query_as(flavor: &Flavor, conn: &mut Connection) {
+    let sql = "SELECT LEVEL AS ID, DECODE(LEVEL, 1, 'one', NULL) AS NAME \
+               FROM DUAL CONNECT BY LEVEL <= 150";
+    let levels: Vec<Level> = conn
+        .query_as(sql)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query_as failed: {}", flavor.name, e));
+    // More rows than one prefetch batch
+    assert_eq!(levels.len(), 150, "[{}]", flavor.name);
+    assert_eq!(
+        levels[0],
+        Level {
+            id: 1,
+            name: Some("one".to_string())
+        },
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(levels[149].name, None, "[{}]", flavor.name);
+}
+
+/// Rows export as CSV and JSON lines.
+async fn check_export(flavor: &Flavor, conn: &mut Connection) {
+    let sql = "SELECT LEVEL AS ID, DECODE(LEVEL, 1, 'a,b', NULL) AS NAME, LEVEL / 2 AS HALF, \
+               DATE '2024-01-02' AS DAY FROM DUAL CONNECT BY LEVEL <= 2";
+
+    let mut cursor = conn.open_cursor(sql).await.unwrap();
+    let mut csv = Vec::new();
+    let rows = cursor
+        .write_csv_with_options(&mut csv, &ExportOptions::new().with_date_format("%Y-%m-%d"))
+        .await
+        .unwrap_or_else(|e| panic!("[{}] write_csv failed: {}", flavor.name, e));
+    drop(cursor);
+    assert_eq!(rows, 2, "[{}]", flavor.name);
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "ID,NAME,HALF,DAY\r\n1,\"a,b\",0.5,2024-01-02\r\n2,,1,2024-01-02\r\n",
+        "[{}]",
+        flavor.name
+    );
+
+    let mut cursor = conn.open_cursor(sql).await.unwrap();
+    let mut jsonl = Vec::new();
+    cursor
+        .write_jsonl(&mut jsonl)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] write_jsonl failed: {}", flavor.name, e));
+    assert_eq!(
+        String::from_utf8(jsonl).unwrap().lines().next(),
+        Some("{\"ID\":1,\"NAME\":\"a,b\",\"HALF\":0.5,\"DAY\":\"2024-01-02 00:00:00\"}"),
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// End-to-end tracing attributes reach the session with the next call.
+async fn check_end_to_end(flavor: &Flavor, conn: &mut Connection) {
+    conn.set_module("oracle-thin-rs");
+    conn.set_action("matrix");
+    conn.set_client_info("check_end_to_end");
+    conn.set_client_identifier("tester");
+
+    let before = conn.round_trips();
+    let row = conn
+        .query_one(
+            "SELECT SYS_CONTEXT('USERENV', 'MODULE'), SYS_CONTEXT('USERENV', 'ACTION'), \
+             SYS_CONTEXT('USERENV', 'CLIENT_INFO'), SYS_CONTEXT('USERENV', 'CLIENT_IDENTIFIER') \
+             FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] USERENV query failed: {}", flavor.name, e))
+        .unwrap();
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    let values: Vec<Option<String>> = (0..4).map(|i| row.get_as(i).unwrap()).collect();
+    assert_eq!(
+        values,
+        [
+            Some("oracle-thin-rs".to_string()),
+            Some("matrix".to_string()),
+            Some("check_end_to_end".to_string()),
+            Some("tester".to_string())
+        ],
+        "[{}]",
+        flavor.name
+    );
+
+    conn.set_action("");
+    let row = conn
+        .query_one("SELECT SYS_CONTEXT('USERENV', 'ACTION') FROM DUAL")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        row.get_as::<Option<String>>(0).unwrap(),
+        None,
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// Server errors must be parsed with the server's error info layout.
+async fn check_errors(flavor: &Flavor, conn: &mut Connection) {
+    match conn.query("SELEKT * FROM DUAL").await {
+        Err(Error::Oracle { code, .. }) => assert_eq!(code, 900, "[{}]", flavor.name),
+        other => panic!("[{}] Expected ORA-00900, got {:?}", flavor.name, other),
+    }
+
+    match conn.query("SELECT * FROM NON_EXISTENT_TABLE_12345").await {
+        Err(e @ Error::Oracle { code: 942, .. }) => {
+            // The error names the statement that failed
+            let context = e.context().unwrap();
+            assert_eq!(context.phase, Phase::Execute, "[{}]", flavor.name);
+            assert!(e.to_string().contains("NON_EXISTENT_TABLE_12345"));
+            // The offset points at the missing table
+            assert_eq!(e.offset(), Some(14), "[{}]", flavor.name);
+            assert_eq!(
+                e.sql_with_caret("SELECT * FROM NON_EXISTENT_TABLE_12345")
+                    .unwrap(),
+                "line 1: SELECT * FROM NON_EXISTENT_TABLE_12345\n                      ^",
+                "[{}]",
+                flavor.name
+            );
+        }
+        other => panic!("[{}] Expected ORA-00942, got {:?}", flavor.name, other),
+    }
+
+    // PL/SQL errors carry the call stack in the error chain
+    let e = conn
+        .execute(
+            "BEGIN RAISE_APPLICATION_ERROR(-20001, 'custom failure'); END;",
+            &[],
+        )
+        .await
+        .unwrap_err();
+    let chain = e.error_chain();
+    assert_eq!(chain[0], (20001, "custom failure"), "[{}]", flavor.name);
+    assert!(
+        chain.iter().any(|(code, _)| *code == 6512),
+        "[{}]",
+        flavor.name
+    );
+    assert!(!e.is_constraint_violation() && !e.is_recoverable());
+
+    // The connection must remain usable after errors
+    let result = conn.query("SELECT 1 FROM DUAL").await.unwrap();
+    assert_eq!(result.len(), 1, "[{}]", flavor.name);
+}
+
+/// Cursors spanning several fetch round trips.
+async fn check_cursors(flavor: &Flavor, conn: &mut Connection) {
+    let mut cursor = conn
+        .open_row_cursor(
+            "SELECT LEVEL AS ID, 'Row ' || LEVEL AS NAME FROM DUAL CONNECT BY LEVEL <= 250",
+            50,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] cursor open failed: {}", flavor.name, e));
+
+    let mut expected = 1;
+    while let Some(row) = cursor.next().await.unwrap() {
+        assert_eq!(row.get(0).and_then(|v| v.to_i64()), Some(expected));
+        expected += 1;
+    }
+    assert_eq!(expected - 1, 250, "[{}] Unexpected row count", flavor.name);
+    assert!(cursor.is_closed());
+}
+
+/// Columns repeating the previous row, which the server may omit, are
+/// filled in within and across fetches.
+async fn check_repeated_values(flavor: &Flavor, conn: &mut Connection) {
+    let mut cursor = conn
+        .open_row_cursor(
+            "SELECT 'same' AS S, TRUNC((LEVEL - 1) / 70) AS GRP, LEVEL AS ID \
+             FROM DUAL CONNECT BY LEVEL <= 300",
+            40,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] cursor open failed: {}", flavor.name, e));
+
+    let mut expected = 1;
+    while let Some(row) = cursor.next().await.unwrap() {
+        assert_eq!(row.get(0).and_then(|v| v.as_str()), Some("same"));
+        assert_eq!(
+            row.get(1).and_then(|v| v.to_i64()),
+            Some((expected - 1) / 70),
+            "[{}] row {}",
+            flavor.name,
+            expected
+        );
+        assert_eq!(row.get(2).and_then(|v| v.to_i64()), Some(expected));
+        expected += 1;
+    }
+    assert_eq!(expected - 1, 300, "[{}]", flavor.name);
+}
+
+/// A buffered-memory cap stops `fetch_all` and shrinks fetches for streaming.
+async fn check_buffer_cap(flavor: &Flavor, conn: &mut Connection) {
+    const SQL: &str = "SELECT LEVEL, RPAD('x', 100, 'x') FROM DUAL CONNECT BY LEVEL <= 2000";
+
+    let options = QueryOptions::new()
+        .with_prefetch_rows(10)
+        .with_fetch_size(500)
+        .with_max_buffered_bytes(32 * 1024);
+    let mut cursor = conn.open_cursor_with_options(SQL, &options).await.unwrap();
+    match cursor.fetch_all().await {
+        Err(Error::BufferLimitExceeded { max, .. }) => assert_eq!(max, 32 * 1024),
+        other => panic!(
+            "[{}] Expected BufferLimitExceeded, got {:?}",
+            flavor.name,
+            other.map(|r| r.len())
+        ),
+    }
+    assert!(cursor.is_streaming_only(), "[{}]", flavor.name);
+    assert!(cursor.fetch_size() < 500, "[{}]", flavor.name);
+
+    // Nothing is lost: the collected rows are streamed first
+    let mut expected = 1;
+    while let Some(row) = cursor.next().await.unwrap() {
+        assert_eq!(row.get(0).and_then(|v| v.to_i64()), Some(expected));
+        expected += 1;
+    }
+    assert_eq!(expected - 1, 2000, "[{}]", flavor.name);
+    assert!(
+        cursor.peak_buffered_bytes() > 32 * 1024,
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// Dropping a cursor early releases it on the next call without stray packets.
+async fn check_early_drop(flavor: &Flavor, conn: &mut Connection) {
+    let leaked = conn.stats().cursors_leaked;
+    let cursor = conn
+        .open_row_cursor("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 1000", 20)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] cursor open failed: {}", flavor.name, e));
+    let rows: Vec<_> = cursor
+        .into_stream()
+        .take(5)
+        .try_collect()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] stream failed: {}", flavor.name, e));
+    assert_eq!(rows.len(), 5, "[{}]", flavor.name);
+    assert_eq!(conn.pending_cursor_closes(), 1, "[{}]", flavor.name);
+    assert_eq!(conn.stats().cursors_leaked - leaked, 1, "[{}]", flavor.name);
+
+    // The close rides along with the next call and leaves the stream in sync
+    let before = conn.round_trips();
+    let result = conn.query("SELECT 'after' FROM DUAL").await.unwrap();
+    assert_eq!(
+        result.rows[0].get(0),
+        Some(&OracleValue::String("after".to_string())),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    assert_eq!(conn.pending_cursor_closes(), 0, "[{}]", flavor.name);
+
+    // An explicit close releases the cursor immediately
+    let mut cursor = conn
+        .open_row_cursor("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 1000", 20)
+        .await
+        .unwrap();
+    cursor.next().await.unwrap();
+    cursor.close().await.unwrap();
+    assert!(cursor.is_closed());
+    drop(cursor);
+    assert_eq!(conn.pending_cursor_closes(), 0, "[{}]", flavor.name);
+    assert_eq!(conn.stats().cursors_leaked - leaked, 1, "[{}]", flavor.name);
+
+    // Cursors dropped open can be closed in one round trip without a carrier
+    for _ in 0..3 {
+        let mut cursor = conn
+            .open_row_cursor("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 1000", 20)
+            .await
+            .unwrap();
+        cursor.next().await.unwrap();
+    }
+    assert_eq!(conn.pending_cursor_closes(), 3, "[{}]", flavor.name);
+    let before = conn.round_trips();
+    conn.close_pending_cursors().await.unwrap();
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+    assert_eq!(conn.pending_cursor_closes(), 0, "[{}]", flavor.name);
+    conn.close_pending_cursors().await.unwrap();
+    assert_eq!(conn.round_trips() - before, 1, "[{}]", flavor.name);
+}
+
+/// 23ai-only types: BOOLEAN and VECTOR.
+async fn check_23ai_types(flavor: &Flavor, conn: &mut Connection) {
+    if flavor.major_version < 23 {
+        return;
+    }
+
+    let row = conn
+        .query_one(
+            "SELECT TRUE, FALSE, CAST(NULL AS BOOLEAN), \
+             TO_VECTOR('[1.5, -2, 3]', 3, FLOAT32), TO_VECTOR('[1, 2]', 2, INT8) FROM DUAL",
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] 23ai types query failed: {}", flavor.name, e))
+        .expect("one row");
+
+    assert_eq!(row.get(0), Some(&OracleValue::Boolean(true)));
+    assert_eq!(row.get(1), Some(&OracleValue::Boolean(false)));
+    assert!(row.get(2).unwrap().is_null());
+    assert_eq!(
+        row.get(3).and_then(|v| v.to_f32_vec()),
+        Some(vec![1.5, -2.0, 3.0]),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get(4).and_then(|v| v.to_f64_vec()),
+        Some(vec![1.0, 2.0]),
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// Rows fetched with FETCH calls on the open cursor match the rows
+/// returned by the execute call itself.
+async fn check_fetch_path(flavor: &Flavor, conn: &mut Connection) {
+    let sql = "SELECT LEVEL AS N, 'row ' || LEVEL AS S, \
+               CASE WHEN MOD(LEVEL, 3) = 0 THEN NULL ELSE LEVEL / 4 END AS F, \
+               DATE '2024-01-01' + LEVEL AS D \
+               FROM DUAL CONNECT BY LEVEL <= 120";
+
+    // Every row with the execute
+    let options = QueryOptions::new().with_prefetch_rows(200);
+    let executed = conn.query_with_options(sql, &options).await.unwrap();
+    assert_eq!(executed.len(), 120, "[{}]", flavor.name);
+    assert!(!executed.more_rows, "[{}]", flavor.name);
+
+    // A few rows with the execute, then uneven FETCH batches
+    let options = QueryOptions::new()
+        .with_prefetch_rows(7)
+        .with_fetch_size(16);
+    let before = conn.stats();
+    let mut cursor = conn.open_cursor_with_options(sql, &options).await.unwrap();
+    let fetched = cursor.fetch_all().await.unwrap();
+    assert!(cursor.is_closed(), "[{}]", flavor.name);
+    drop(cursor);
+    let used = conn.stats().since(&before);
+    assert_eq!(
+        used.parses, 1,
+        "[{}] FETCH must not parse again",
+        flavor.name
+    );
+    assert_eq!(used.executes, 1, "[{}]", flavor.name);
+    assert_eq!(used.fetches, 1 + 113_u64.div_ceil(16), "[{}]", flavor.name);
+
+    assert_eq!(fetched.len(), executed.rows.len(), "[{}]", flavor.name);
+    for (i, (a, b)) in fetched.iter().zip(&executed.rows).enumerate() {
+        assert_eq!(
+            a.values().unwrap(),
+            b.values().unwrap(),
+            "[{}] row {}",
+            flavor.name,
+            i
+        );
+    }
+    let names =
+        |row: &Row| -> Vec<String> { row.columns().iter().map(|c| c.name.clone()).collect() };
+    assert_eq!(
+        names(&fetched[0]),
+        names(&executed.rows[0]),
+        "[{}]",
+        flavor.name
+    );
+}
+
+/// Statistics count the round trips, statements and bytes of each call.
+async fn check_stats(flavor: &Flavor, conn: &mut Connection) {
+    let before = conn.stats();
+    assert_eq!(before.round_trips, conn.round_trips(), "[{}]", flavor.name);
+    assert!(before.bytes_sent > 0 && before.bytes_received > 0);
+
+    // SQL not run before, so not in the statement cache
+    let sql = "SELECT 'check_stats' FROM DUAL";
+    conn.query(sql).await.unwrap();
+    let used = conn.stats().since(&before);
+    assert_eq!(used.round_trips, 1, "[{}]", flavor.name);
+    assert_eq!(
+        (used.parses, used.executes, used.fetches),
+        (1, 1, 1),
+        "[{}]",
+        flavor.name
+    );
+    assert!(
+        used.bytes_sent > 0 && used.bytes_received > 0,
+        "[{}]",
+        flavor.name
+    );
+    assert!(used.wait_time > Duration::ZERO, "[{}]", flavor.name);
+
+    // The cached cursor is re-executed without a parse
+    let before = conn.stats();
+    let rows = conn.query(sql).await.unwrap();
+    assert_eq!(rows.len(), 1, "[{}]", flavor.name);
+    let used = conn.stats().since(&before);
+    assert_eq!(
+        (used.parses, used.executes, used.fetches),
+        (0, 1, 1),
+        "[{}]",
+        flavor.name
+    );
+
+    // Execute with 10 rows, then two more fetches
+    let options = QueryOptions::new()
+        .with_prefetch_rows(10)
+        .with_fetch_size(10);
+    let before = conn.stats();
+    let mut cursor = conn
+        .open_cursor_with_options("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 25", &options)
+        .await
+        .unwrap();
+    assert_eq!(cursor.fetch_all().await.unwrap().len(), 25);
+    drop(cursor);
+    let used = conn.stats().since(&before);
+    assert_eq!(used.parses, 1, "[{}]", flavor.name);
+    assert_eq!(used.fetches, 3, "[{}]", flavor.name);
+    assert_eq!(used.round_trips, 3, "[{}]", flavor.name);
+
+    let before = conn.stats();
+    conn.describe("SELECT 1 FROM DUAL").await.unwrap();
+    let used = conn.stats().since(&before);
+    assert_eq!((used.parses, used.executes), (1, 0), "[{}]", flavor.name);
+}
+
+/// LOB-free queries must complete in a single round trip (no DEFINE/fetch).
+async fn check_round_trips(flavor: &Flavor, conn: &mut Connection) {
+    let before = conn.round_trips();
+    let result = conn
+        .query("SELECT 1 AS A, 'x' AS B, SYSDATE AS C FROM DUAL")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query failed: {}", flavor.name, e));
+    assert_eq!(result.len(), 1);
+    assert_eq!(
+        conn.round_trips() - before,
+        1,
+        "[{}] LOB-free query should take one round trip",
+        flavor.name
+    );
+
+    let before = conn.round_trips();
+    let row = conn
+        .query_one("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 10")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query_one failed: {}", flavor.name, e));
+    assert_eq!(row.and_then(|r| r.get(0).and_then(|v| v.to_i64())), Some(1));
+    assert_eq!(
+        conn.round_trips() - before,
+        1,
+        "[{}] single-row mode should take one round trip",
+        flavor.name
+    );
+
+    // A small first batch, then large fetches
+    let sql = "SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 250";
+    let options = QueryOptions::new()
+        .with_prefetch_rows(10)
+        .with_fetch_size(200);
+    let result = conn.query_with_options(sql, &options).await.unwrap();
+    assert_eq!(result.len(), 10, "[{}]", flavor.name);
+    assert!(result.more_rows, "[{}]", flavor.name);
+
+    let before = conn.round_trips();
+    let mut cursor = conn.open_cursor_with_options(sql, &options).await.unwrap();
+    assert_eq!(cursor.fetch_size(), 200);
+    let rows = cursor.fetch_all().await.unwrap();
+    drop(cursor);
+    assert_eq!(rows.len(), 250, "[{}]", flavor.name);
+    // Execute with 10 rows, then fetches of 200 and 40
+    assert!(
+        conn.round_trips() - before <= 3,
+        "[{}] fetch size not applied",
+        flavor.name
+    );
+}
+
+/// PL/SQL result sets from DBMS_SQL.RETURN_RESULT are fetched in full.
+async fn check_implicit_results(flavor: &Flavor, conn: &mut Connection) {
+    let sql = "DECLARE
+            c1 SYS_REFCURSOR;
+            c2 SYS_REFCURSOR;
+        BEGIN
+            OPEN c1 FOR SELECT LEVEL AS n FROM DUAL CONNECT BY LEVEL <= 5;
+            DBMS_SQL.RETURN_RESULT(c1);
+            OPEN c2 FOR SELECT 'a' AS s, DATE '2024-01-02' AS d FROM DUAL;
+            DBMS_SQL.RETURN_RESULT(c2);
+        END;";
+    // Fetches of 2 rows take the first result set past its first fetch
+    let options = QueryOptions::new().with_fetch_size(2);
+    let result = conn
+        .query_with_options(sql, &options)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] implicit results failed: {}", flavor.name, e));
+    assert!(result.is_empty(), "[{}]", flavor.name);
+    assert_eq!(result.implicit_results.len(), 2, "[{}]", flavor.name);
+
+    let first = &result.implicit_results[0];
+    assert_eq!(first.column_names(), vec!["N"], "[{}]", flavor.name);
+    let values: Vec<i64> = first
+        .iter()
+        .filter_map(|row| row.get(0).and_then(|v| v.to_i64()))
+        .collect();
+    assert_eq!(values, vec![1, 2, 3, 4, 5], "[{}]", flavor.name);
+
+    let second = &result.implicit_results[1];
+    assert_eq!(second.column_names(), vec!["S", "D"], "[{}]", flavor.name);
+    assert_eq!(second.len(), 1, "[{}]", flavor.name);
+    assert_eq!(
+        second.rows[0].get(0).and_then(|v| v.as_str()),
+        Some("a"),
+        "[{}]",
+        flavor.name
+    );
+
+    // The connection stays usable once the cursors are consumed
+    let row = conn.query_one("SELECT 1 FROM DUAL").await.unwrap();
+    assert!(row.is_some(), "[{}]", flavor.name);
+}
+
+/// ORA-01013 from the server is reported as `Error::Cancelled`, and the
+/// statement can be executed again on the same session.
+async fn check_cancelled(flavor: &Flavor, conn: &mut Connection) {
+    let sql = "DECLARE cancelled EXCEPTION; PRAGMA EXCEPTION_INIT(cancelled, -1013); \
+               BEGIN IF :1 = 1 THEN RAISE cancelled; END IF; END;";
+    let flag = |n: &str| [OracleValue::Number(n.to_string())];
+    match conn.execute(sql, &flag("1")).await {
+        Err(e @ Error::Cancelled { .. }) => {
+            assert_eq!(e.code(), Some(1013), "[{}]", flavor.name);
+            assert!(e.context().is_some(), "[{}]", flavor.name);
+        }
+        other => panic!("[{}] expected Cancelled, got {:?}", flavor.name, other),
+    }
+    conn.execute(sql, &flag("0"))
+        .await
+        .unwrap_or_else(|e| panic!("[{}] re-execute failed: {}", flavor.name, e));
+}
+
+/// Columns defined with conversions arrive converted by the server.
+async fn check_fetch_as(flavor: &Flavor, conn: &mut Connection) {
+    conn.execute(
+        "ALTER SESSION SET NLS_TIMESTAMP_FORMAT = 'YYYY-MM-DD HH24:MI:SS'",
+        &[],
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] alter session failed: {}", flavor.name, e));
+    let options = QueryOptions::new()
+        .with_fetch_as(0, FetchAs::String)
+        .with_fetch_as(1, FetchAs::String)
+        .with_fetch_as(2, FetchAs::Long)
+        .with_fetch_as(3, FetchAs::Number);
+    let result = conn
+        .query_with_options(
+            "SELECT 12.5, TIMESTAMP '2024-03-01 10:20:30', TO_CLOB('text'), '42', LEVEL \
+             FROM DUAL CONNECT BY LEVEL <= 3",
+            &options,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("[{}] defined query failed: {}", flavor.name, e));
+    assert_eq!(result.len(), 3, "[{}]", flavor.name);
+    let row = &result.rows[0];
+    assert_eq!(
+        row.get(0).and_then(|v| v.as_str()),
+        Some("12.5"),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get(1).and_then(|v| v.as_str()),
+        Some("2024-03-01 10:20:30"),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get(2).and_then(|v| v.as_str()),
+        Some("text"),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get(3).and_then(|v| v.to_f64()),
+        Some(42.0),
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        result.rows[2].get(4).and_then(|v| v.to_i64()),
+        Some(3),
+        "[{}]",
+        flavor.name
+    );
+    assert!(
+        matches!(
+            result.columns[0].data_type(),
+            Ok(OracleType::Varchar2 { .. })
+        ),
+        "[{}]",
+        flavor.name
+    );
+
+    // A CLOB left as a locator cannot be fetched with converted columns
+    let options = QueryOptions::new().with_fetch_as(0, FetchAs::String);
+    let result = conn
+        .query_with_options("SELECT 1, TO_CLOB('x') FROM DUAL", &options)
+        .await;
+    assert!(
+        matches!(result, Err(Error::TypeConversion { .. })),
+        "[{}]",
+        flavor.name
+    );
+    conn.query_one("SELECT 1 FROM DUAL").await.unwrap();
+}
+
+/// Query log entries carry redacted SQL, row counts and error codes.
+async fn check_query_logger(flavor: &Flavor, conn: &mut Connection) {
+    #[derive(Default)]
+    struct Collector(Mutex<Vec<QueryLog>>);
+
+    impl QueryLogger for Collector {
+        fn log(&self, entry: &QueryLog) {
+            self.0.lock().unwrap().push(entry.clone());
+        }
+    }
+
+    let collector = Arc::new(Collector::default());
+    conn.set_query_logger(Some(collector.clone()));
+    conn.query("SELECT 'secret', 42 FROM DUAL CONNECT BY LEVEL <= 3")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query failed: {}", flavor.name, e));
+    let result = conn
+        .execute("SELECT * FROM no_such_table WHERE id = 7", &[])
+        .await;
+    assert_eq!(result.unwrap_err().code(), Some(942), "[{}]", flavor.name);
+    conn.set_query_logger(None);
+    conn.query_one("SELECT 1 FROM DUAL").await.unwrap();
+
+    let entries = collector.0.lock().unwrap();
+    assert_eq!(entries.len(), 2, "[{}]", flavor.name);
+    assert_eq!(
+        entries[0].sql, "SELECT ?, ? FROM DUAL CONNECT BY LEVEL <= ?",
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(entries[0].rows, 3, "[{}]", flavor.name);
+    assert!(!entries[0].failed, "[{}]", flavor.name);
+    assert_eq!(
+        entries[1].sql, "SELECT * FROM no_such_table WHERE id = ?",
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(entries[1].error_code, Some(942), "[{}]", flavor.name);
+}
+
+/// A call past the call timeout is interrupted and the connection recovers.
+async fn check_call_timeout(flavor: &Flavor, conn: &mut Connection) {
+    // Runs far longer than the timeout
+    let sql = "SELECT COUNT(*) FROM all_objects a, all_objects b, all_objects c";
+    conn.set_call_timeout(Duration::from_secs(1));
+    // The same statement runs again on the same session after each interrupt
+    for _ in 0..2 {
+        match conn.query(sql).await {
+            Err(Error::CallTimeout { .. }) => {}
+            other => panic!("[{}] expected CallTimeout, got {:?}", flavor.name, other),
+        }
+    }
+    conn.set_call_timeout(Duration::ZERO);
+
+    let row = conn
+        .query_one("SELECT 1 FROM DUAL")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] query after timeout failed: {}", flavor.name, e));
+    assert!(row.is_some(), "[{}]", flavor.name);
+}
+
+/// Long values split across packets are reassembled in the cursor path.
+async fn check_values_across_packets(flavor: &Flavor, conn: &mut Connection) {
+    // One row per fetch, each a little larger than the last, so the packet
+    // boundary moves through the third value
+    let base = (conn.sdu() as i64 - 8000 - 48).clamp(1, 3900);
+    let sql = format!(
+        "SELECT LEVEL, RPAD('a', 4000, 'a'), RPAD('b', 4000, 'b'), RPAD('c', {} + LEVEL, 'c') \
+         FROM DUAL CONNECT BY LEVEL <= 64",
+        base
+    );
+    let mut cursor = conn
+        .open_row_cursor(&sql, 1)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] cursor open failed: {}", flavor.name, e));
+    let mut count = 0;
+    while let Some(row) = cursor.next().await.unwrap() {
+        let level = row.get(0).and_then(|v| v.to_i64()).unwrap();
+        let len = row.get(3).and_then(|v| v.as_str()).map(str::len);
+        assert_eq!(len, Some((base + level) as usize), "[{}]", flavor.name);
+        count += 1;
+    }
+    assert_eq!(count, 64, "[{}]", flavor.name);
+}
+
+/// Responses spanning many packets are reassembled, up to the size limit.
+async fn check_large_responses(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    // About 100 KB of inline data in the execute response
+    let sql = "SELECT LEVEL, RPAD('x', 1000, 'x') FROM DUAL CONNECT BY LEVEL <= 100";
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str).unwrap();
+
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    let result = conn
+        .query(sql)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] multi-packet query failed: {}", flavor.name, e));
+    assert_eq!(result.len(), 100, "[{}]", flavor.name);
+    assert_eq!(
+        result.rows[99]
+            .get(1)
+            .and_then(|v| v.as_str())
+            .map(str::len),
+        Some(1000),
+        "[{}]",
+        flavor.name
+    );
+    conn.close().await.unwrap();
+
+    let params = params.with_max_response_size(16 * 1024);
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    match conn.query(sql).await {
+        Err(e @ Error::ResponseTooLarge { .. }) => assert!(e.is_connection_lost()),
+        other => panic!(
+            "[{}] expected ResponseTooLarge, got {:?}",
+            flavor.name, other
+        ),
+    }
+}
+
+/// Session settings in `ConnectParams` apply without extra round trips.
+async fn check_session_settings(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let settings = SessionSettings::new()
+        .with_time_zone("-03:30")
+        .with_nls_date_format("YYYY/MM/DD")
+        .with_nls_numeric_characters(",.")
+        .with_current_schema("SYS");
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str)
+        .unwrap()
+        .with_session_settings(settings);
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    assert_eq!(conn.round_trips(), 0, "[{}]", flavor.name);
+
+    let row = conn
+        .query_one(
+            "SELECT SESSIONTIMEZONE, TO_CHAR(DATE '2024-01-02'), TO_CHAR(1.5), \
+             SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA') FROM DUAL",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let values: Vec<String> = (0..4).map(|i| row.get_as(i).unwrap()).collect();
+    assert_eq!(
+        values,
+        ["-03:30", "2024/01/02", "1,5", "SYS"],
+        "[{}]",
+        flavor.name
+    );
+    conn.close().await.unwrap();
+
+    // The edition shortcut keeps the other settings
+    let params = params.with_edition("ORA$BASE");
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    let row = conn
+        .query_one(
+            "SELECT SYS_CONTEXT('USERENV', 'CURRENT_EDITION_NAME'), SESSIONTIMEZONE FROM DUAL",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        row.get_as::<String>(0).unwrap(),
+        "ORA$BASE",
+        "[{}]",
+        flavor.name
+    );
+    assert_eq!(
+        row.get_as::<String>(1).unwrap(),
+        "-03:30",
+        "[{}]",
+        flavor.name
+    );
+    conn.close().await.unwrap();
+}
+
+/// Keepalive probes are enabled from `expire_time` and idle sessions are
+/// pinged only once they have been idle long enough.
+async fn check_keepalive(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str)
+        .unwrap()
+        .with_expire_time(Duration::from_secs(60));
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    conn.query_one("SELECT 1 FROM DUAL").await.unwrap();
+
+    let pinged = conn.ping_if_idle(Duration::from_secs(3600)).await.unwrap();
+    assert!(!pinged, "[{}] pinged a busy session", flavor.name);
+    let round_trips = conn.round_trips();
+    let pinged = conn.ping_if_idle(Duration::ZERO).await.unwrap();
+    assert!(pinged, "[{}]", flavor.name);
+    assert_eq!(conn.round_trips(), round_trips + 1, "[{}]", flavor.name);
+    conn.close().await.unwrap();
+}
+
+/// A call into a package recompiled by another session, discarding this
+/// session's package state (ORA-04068), is parsed and run again.
+async fn check_stale_package(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let connect =
+        || Connection::connect(&endpoint.conn_str, &endpoint.username, &endpoint.password);
+    let mut conn = connect().await.unwrap();
+    let mut other = connect().await.unwrap();
+    let create = |version: u32| {
+        format!(
+            "CREATE OR REPLACE PACKAGE oracle_thin_rs_stale AS \
+             calls NUMBER := 0; \
+             FUNCTION bump RETURN NUMBER; END; /* v{} */",
+            version
+        )
+    };
+    let body = "CREATE OR REPLACE PACKAGE BODY oracle_thin_rs_stale AS \
+                FUNCTION bump RETURN NUMBER IS BEGIN calls := calls + 1; RETURN calls; END; END;";
+    let bump = "SELECT oracle_thin_rs_stale.bump FROM DUAL";
+    other.execute(&create(1), &[]).await.unwrap();
+    other.execute(body, &[]).await.unwrap();
+
+    let row = conn.query_one(bump).await.unwrap().unwrap();
+    assert_eq!(row.get_as::<i64>(0).unwrap(), 1, "[{}]", flavor.name);
+
+    // Recompiling discards the package state of the first session
+    other.execute(&create(2), &[]).await.unwrap();
+    other.execute(body, &[]).await.unwrap();
+    let row = conn
+        .query_one(bump)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] stale package call failed: {}", flavor.name, e))
+        .unwrap();
+    assert_eq!(row.get_as::<i64>(0).unwrap(), 1, "[{}]", flavor.name);
+
+    other
+        .execute("DROP PACKAGE oracle_thin_rs_stale", &[])
+        .await
+        .unwrap();
+}
+
+/// Pooled sessions keep the state their tag describes; the session
+/// callback prepares the others.
+async fn check_pool(flavor: &Flavor) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let options = PoolOptions::new()
+        .with_min_size(1)
+        .with_max_size(2)
+        .with_acquire_timeout(Duration::from_millis(500))
+        .with_session_callback(move |conn, tag| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                let format = match tag {
+                    Some("iso") => "YYYY-MM-DD",
+                    _ => "DD-MON-RR",
+                };
+                let sql = format!("ALTER SESSION SET NLS_DATE_FORMAT = '{}'", format);
+                conn.execute(&sql, &[]).await?;
+                Ok(())
+            })
+        });
+    let pool = Pool::connect(
+        &endpoint.conn_str,
+        &endpoint.username,
+        &endpoint.password,
+        options,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] pool failed: {}", flavor.name, e));
+    assert_eq!((pool.open_count(), pool.idle_count()), (1, 1));
+
+    async fn date_format(conn: &mut PooledConnection) -> String {
+        let sql = "SELECT value FROM nls_session_parameters WHERE parameter = 'NLS_DATE_FORMAT'";
+        let row = conn.query_one(sql).await.unwrap().unwrap();
+        row.get_as::<String>(0).unwrap()
+    }
+
+    // The untagged session opened up front is prepared for the tag
+    let mut conn = pool.acquire_tagged("iso").await.unwrap();
+    assert_eq!(conn.tag(), Some("iso"), "[{}]", flavor.name);
+    assert_eq!(date_format(&mut conn).await, "YYYY-MM-DD");
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "[{}]", flavor.name);
+
+    // A second session is opened while the first is lent out
+    let other = pool.acquire().await.unwrap();
+    assert_eq!(other.tag(), None, "[{}]", flavor.name);
+    assert_eq!(calls.load(Ordering::SeqCst), 2, "[{}]", flavor.name);
+    assert_eq!(pool.open_count(), 2, "[{}]", flavor.name);
+
+    // The pool is exhausted
+    match pool.acquire().await {
+        Err(e @ Error::PoolTimeout { .. }) => assert!(e.is_timeout()),
+        other => panic!("[{}] expected PoolTimeout, got {:?}", flavor.name, other),
+    }
+    drop(other);
+    drop(conn);
+
+    // The tagged session comes back without running the callback again
+    let mut conn = pool.acquire_tagged("iso").await.unwrap();
+    assert_eq!(date_format(&mut conn).await, "YYYY-MM-DD");
+    assert_eq!(calls.load(Ordering::SeqCst), 2, "[{}]", flavor.name);
+    conn.discard().await.unwrap();
+    assert_eq!(pool.open_count(), 1, "[{}]", flavor.name);
+
+    pool.close();
+    assert!(matches!(pool.acquire().await, Err(Error::ConnectionClosed)));
+    assert_eq!(pool.open_count(), 0, "[{}]", flavor.name);
+}
+
+/// Clones of a shared connection run calls from several tasks in turn.
+async fn check_shared(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let conn =
+        SharedConnection::connect(&endpoint.conn_str, &endpoint.username, &endpoint.password)
+            .await
+            .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+
+    let tasks: Vec<_> = (0..8)
+        .map(|i| {
+            let conn = conn.clone();
+            tokio::spawn(async move {
+                let row = conn.query_one(&format!("SELECT {} FROM DUAL", i)).await?;
+                row.unwrap().get_as::<i64>(0)
+            })
+        })
+        .collect();
+    for (i, task) in tasks.into_iter().enumerate() {
+        let value = task
+            .await
+            .unwrap()
+            .unwrap_or_else(|e| panic!("[{}] shared query failed: {}", flavor.name, e));
+        assert_eq!(value, i as i64, "[{}]", flavor.name);
+    }
+
+    {
+        let mut guard = conn.lock().await;
+        let mut cursor = guard
+            .open_cursor("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 3")
+            .await
+            .unwrap();
+        assert_eq!(
+            cursor.fetch_all().await.unwrap().len(),
+            3,
+            "[{}]",
+            flavor.name
+        );
+    }
+
+    assert_eq!(conn.handle_count(), 1, "[{}]", flavor.name);
+    conn.into_inner().unwrap().close().await.unwrap();
+}
+
+/// A connection owned by a background task finishes calls its callers gave
+/// up on, and runs closures spanning several calls.
+async fn check_actor(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str).unwrap();
+    let handle =
+        ConnectionHandle::connect_with_params(&params, &endpoint.username, &endpoint.password)
+            .await
+            .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+
+    // The task completes the abandoned call; the next one is unaffected
+    let abandoned = tokio::time::timeout(
+        Duration::from_millis(10),
+        handle
+            .query("SELECT COUNT(*) FROM all_objects, (SELECT 1 FROM DUAL CONNECT BY LEVEL <= 5)"),
+    )
+    .await;
+    assert!(abandoned.is_err(), "[{}]", flavor.name);
+    let row = handle
+        .query_one("SELECT 42 FROM DUAL")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.get_as::<i64>(0).unwrap(), 42, "[{}]", flavor.name);
+
+    let rows = handle
+        .run(|conn| {
+            Box::pin(async move {
+                let mut cursor = conn
+                    .open_cursor("SELECT LEVEL FROM DUAL CONNECT BY LEVEL <= 250")
+                    .await?;
+                Ok(cursor.fetch_all().await?.len())
+            })
+        })
+        .await
+        .unwrap_or_else(|e| panic!("[{}] run failed: {}", flavor.name, e));
+    assert_eq!(rows, 250, "[{}]", flavor.name);
+
+    handle.close().await.unwrap();
+    assert!(matches!(handle.ping().await, Err(Error::ConnectionClosed)));
+}
+
+/// A sessionless transaction suspended on one connection is resumed and
+/// committed on another (23ai).
+async fn check_sessionless(flavor: &Flavor) {
+    if flavor.major_version < 23 {
+        return;
+    }
+    let (Some(mut first), Some(mut second)) =
+        (connect_flavor(flavor).await, connect_flavor(flavor).await)
+    else {
+        return;
+    };
+    let _ = first
+        .execute("DROP TABLE oracle_thin_rs_sessionless PURGE", &[])
+        .await;
+    first
+        .execute("CREATE TABLE oracle_thin_rs_sessionless (id NUMBER)", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] CREATE TABLE failed: {}", flavor.name, e));
+
+    let timeout = Duration::from_secs(30);
+    let id = first
+        .begin_sessionless_transaction(None, timeout)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] begin failed: {}", flavor.name, e));
+    assert_eq!(id.len(), 16, "[{}]", flavor.name);
+    first
+        .execute(
+            "INSERT INTO oracle_thin_rs_sessionless VALUES (:1)",
+            &[1i64.to_sql()],
+        )
+        .await
+        .unwrap();
+    first
+        .suspend_sessionless_transaction()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] suspend failed: {}", flavor.name, e));
+
+    second
+        .resume_sessionless_transaction(&id, timeout)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] resume failed: {}", flavor.name, e));
+    second
+        .execute(
+            "INSERT INTO oracle_thin_rs_sessionless VALUES (:1)",
+            &[2i64.to_sql()],
+        )
+        .await
+        .unwrap();
+    second.execute("COMMIT", &[]).await.unwrap();
+
+    let row = first
+        .query_one("SELECT COUNT(*) FROM oracle_thin_rs_sessionless")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.get_as::<i64>(0).unwrap(), 2, "[{}]", flavor.name);
+    assert!(first
+        .begin_sessionless_transaction(Some(&[0; 65]), timeout)
+        .await
+        .is_err());
+
+    first
+        .execute("DROP TABLE oracle_thin_rs_sessionless PURGE", &[])
+        .await
+        .unwrap_or_else(|e| panic!("[{}] DROP TABLE failed: {}", flavor.name, e));
+}
+
+/// A larger SDU and socket buffers are negotiated and used for fetches.
+async fn check_sdu(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str)
+        .unwrap()
+        .with_sdu(65535)
+        .with_socket_buffer_sizes(Some(1 << 20), Some(1 << 20));
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    assert!(conn.sdu() <= 65535, "[{}] SDU {}", flavor.name, conn.sdu());
+    check_values_across_packets(flavor, &mut conn).await;
+    conn.close().await.unwrap();
+}
+
+/// A call timeout interrupts the call with an out-of-band break when enabled.
+async fn check_oob_break(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str)
+        .unwrap()
+        .with_oob_break(true);
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    check_call_timeout(flavor, &mut conn).await;
+    conn.close().await.unwrap();
+}
+
+/// An unreachable first address fails over to the next one.
+async fn check_failover(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let target = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str).unwrap();
+    // Nothing listens on a port just released by the OS
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .unwrap()
+        .port();
+    let params = oracle_thin_rs::ConnectParams::new("127.0.0.1", closed_port, &target.service_name)
+        .with_failover_address(&target.host, target.port);
+    let mut conn = Connection::connect_with_params(&params, &endpoint.username, &endpoint.password)
+        .await
+        .unwrap_or_else(|e| panic!("[{}] failover connect failed: {}", flavor.name, e));
+    conn.ping().await.unwrap();
+    conn.close().await.unwrap();
+
+    let params = params.with_failover(false);
+    let result =
+        Connection::connect_with_params(&params, &endpoint.username, &endpoint.password).await;
+    assert!(
+        result.is_err(),
+        "[{}] connected without failover",
+        flavor.name
+    );
+}
+
+/// A forced reconnect replays the session-init statements and is reported.
+async fn check_reconnect(flavor: &Flavor) {
+    let Some(endpoint) = flavor.endpoint() else {
+        return;
+    };
+    let params = oracle_thin_rs::ConnectParams::parse(&endpoint.conn_str).unwrap();
+    let mut conn = ReconnectingConnection::connect_with_params(
+        params,
+        &endpoint.username,
+        &endpoint.password,
+        ReconnectPolicy::new().with_max_attempts(2).with_retries(1),
+    )
+    .await
+    .unwrap_or_else(|e| panic!("[{}] connect failed: {}", flavor.name, e));
+    conn.add_init_statement("ALTER SESSION SET NLS_DATE_FORMAT = 'YYYY/MM/DD'")
+        .await
+        .unwrap_or_else(|e| panic!("[{}] init statement failed: {}", flavor.name, e));
+
+    let sql = "SELECT TO_CHAR(DATE '2024-01-02') FROM DUAL";
+    let formatted = |row: Option<oracle_thin_rs::Row>| {
+        row.and_then(|r| r.get(0).and_then(|v| v.as_str().map(str::to_string)))
+    };
+    let row = conn.query_one(sql).await.unwrap();
+    assert_eq!(formatted(row).as_deref(), Some("2024/01/02"));
+    assert!(conn.take_reconnect_event().is_none());
+
+    conn.set_autocommit(true);
+    conn.reconnect()
+        .await
+        .unwrap_or_else(|e| panic!("[{}] reconnect failed: {}", flavor.name, e));
+    let event = conn.take_reconnect_event().expect("reconnect event");
+    assert_eq!(event.attempts, 1, "[{}]", flavor.name);
+    assert_eq!(conn.reconnect_count(), 1);
+    assert!(
+        conn.autocommit(),
+        "[{}] autocommit not replayed",
+        flavor.name
+    );
+
+    let row = conn.query_one(sql).await.unwrap();
+    assert_eq!(
+        formatted(row).as_deref(),
+        Some("2024/01/02"),
+        "[{}] init statement not replayed",
+        flavor.name
+    );
+    conn.close().await.unwrap();
+}
+
+/// Run the canonical suite against a single flavor.
+async fn run_suite(flavor: &Flavor) {
+    let Some(mut conn) = connect_flavor(flavor).await else {
+        return;
+    };
+
+    check_versions(flavor, &conn);
+    check_types(flavor, &mut conn).await;
+    check_number_edges(flavor, &mut conn).await;
+    check_integer_columns(flavor, &mut conn).await;
+    check_bind_encoders(flavor, &mut conn).await;
+    check_pseudo_columns(flavor, &mut conn).await;
+    check_lobs(flavor, &mut conn).await;
+    check_lob_write(flavor, &mut conn).await;
+    check_large_lobs(flavor, &mut conn).await;
+    check_long_values(flavor, &mut conn).await;
+    check_nchar(flavor, &mut conn).await;
+    check_bfile(flavor, &mut conn).await;
+    check_large_sql(flavor, &mut conn).await;
+    check_execute_many(flavor, &mut conn).await;
+    check_execute(flavor, &mut conn).await;
+    check_warnings(flavor, &mut conn).await;
+    check_pipeline(flavor, &mut conn).await;
+    check_describe(flavor, &mut conn).await;
+    check_export(flavor, &mut conn).await;
+    check_query_as(flavor, &mut conn).await;
+    check_end_to_end(flavor, &mut conn).await;
+    check_errors(flavor, &mut conn).await;
+    check_implicit_results(flavor, &mut conn).await;
+    check_cancelled(flavor, &mut conn).await;
+    check_query_logger(flavor, &mut conn).await;
+    check_fetch_as(flavor, &mut conn).await;
+    check_call_timeout(flavor, &mut conn).await;
+    check_values_across_packets(flavor, &mut conn).await;
+    check_cursors(flavor, &mut conn).await;
+    check_repeated_values(flavor, &mut conn).await;
+    check_buffer_cap(flavor, &mut conn).await;
+    check_early_drop(flavor, &mut conn).await;
+    check_23ai_types(flavor, &mut conn).await;
+    check_round_trips(flavor, &mut conn).await;
+    check_stats(flavor, &mut conn).await;
+    check_fetch_path(flavor, &mut conn).await;
+    check_large_responses(flavor).await;
+    check_session_settings(flavor).await;
+    check_keepalive(flavor).await;
+    check_oob_break(flavor).await;
+    check_sdu(flavor).await;
+    check_shared(flavor).await;
+    check_actor(flavor).await;
+    check_pool(flavor).await;
+    check_sessionless(flavor).await;
+    check_stale_package(flavor).await;
+    check_failover(flavor).await;
+    check_reconnect(flavor).await;
+
+    conn.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_matrix_19c() {
+    run_suite(&FLAVOR_19C).await;
+}
+
+#[tokio::test]
+async fn test_matrix_21c() {
+    run_suite(&FLAVOR_21C).await;
+}
+
+#[tokio::test]
+async fn test_matrix_23ai() {
+    run_suite(&FLAVOR_23AI).await;
+}